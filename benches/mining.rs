@@ -0,0 +1,39 @@
+// Single-threaded vs parallel nonce search. Run with `cargo bench --bench mining`.
+
+use std::time::Instant;
+
+use time_decay_consensus::blockchain::Block;
+use time_decay_consensus::progress::CancellationToken;
+
+const DIFFICULTY: usize = 4;
+const BLOCKS: u64 = 8;
+
+fn bench(label: &str, threads: usize) {
+    let start = Instant::now();
+    for id in 0..BLOCKS {
+        let mut block = Block::unmined(id, "bench".to_string(), format!("bench block {}", id));
+        if threads == 1 {
+            block.mine_with(DIFFICULTY, &CancellationToken::new()).unwrap();
+        } else {
+            block.mine_parallel(DIFFICULTY, threads, &CancellationToken::new()).unwrap();
+        }
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:<12} {:>8.1} ms total, {:>7.1} ms/block",
+        label,
+        elapsed.as_secs_f64() * 1000.0,
+        elapsed.as_secs_f64() * 1000.0 / BLOCKS as f64
+    );
+}
+
+fn main() {
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    bench("1 thread", 1);
+    for threads in [2, 4] {
+        bench(&format!("{} threads", threads), threads);
+    }
+    if cores > 4 {
+        bench(&format!("{} threads", cores), cores);
+    }
+}
@@ -0,0 +1,107 @@
+// examples/gossip_demo.rs
+//
+// Three nodes in one process. Each voter submits to one node, which verifies the vote
+// and gossips it to the other two; echoes of already-admitted votes are rejected as
+// replays. Every node ends with the same votes, the same outcome, and the same
+// report hash.
+//
+//     cargo run --example gossip_demo
+
+use chrono::{DateTime, Duration, Utc};
+
+use time_decay_consensus::history::HistoryAnalyzer;
+use time_decay_consensus::intake::{Channel, VoteIntake, VoteSource};
+use time_decay_consensus::report::{FinalizationReport, ReportInput};
+use time_decay_consensus::threshold::DecisionRule;
+use time_decay_consensus::trust::TrustEngine;
+use time_decay_consensus::units::Seconds;
+use time_decay_consensus::vote::{DecayType, ProposalType, SignedVote};
+
+const PROPOSAL: &str = "proposal_gossip";
+
+struct Node {
+    name: &'static str,
+    intake: VoteIntake,
+    history: HistoryAnalyzer,
+}
+
+impl Node {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            intake: VoteIntake::new(Seconds::new(3600)),
+            history: HistoryAnalyzer::default(),
+        }
+    }
+
+    fn report(&self, opened_at: DateTime<Utc>, finalized_at: DateTime<Utc>) -> FinalizationReport {
+        FinalizationReport::build(ReportInput {
+            proposal_id: PROPOSAL,
+            proposal_type: ProposalType::Normal,
+            opened_at,
+            finalized_at,
+            votes: &self.intake.votes(),
+            trust: &TrustEngine::new(),
+            max_age_secs: 3600,
+            admissions: Some(self.intake.admissions()),
+            eligible_weight: None,
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        })
+    }
+}
+
+fn main() {
+    let opened_at = Utc::now() - Duration::seconds(300);
+    let mut nodes = [Node::new("node-a"), Node::new("node-b"), Node::new("node-c")];
+
+    for (i, voter) in ["alice", "bob", "carol", "dave"].iter().enumerate() {
+        let key = SignedVote::generate_keypair();
+        let cast_at = opened_at + Duration::seconds(30 * (i as i64 + 1));
+        let vote = SignedVote::new(voter.parse().unwrap(), PROPOSAL.parse().unwrap(), 1.0, cast_at, DecayType::Exponential, &key)
+            .with_nonce(SignedVote::generate_nonce(), &key);
+
+        // The voter's own node takes it from the CLI...
+        let origin = i % nodes.len();
+        let received = cast_at + Duration::seconds(1);
+        let node = &mut nodes[origin];
+        if node.intake.submit(vote.clone(), VoteSource::new(Channel::Cli), received, None, &mut node.history).is_err() {
+            continue;
+        }
+        // ...and gossips it to every peer, which echo it back once
+        let from = nodes[origin].name;
+        for (j, peer) in nodes.iter_mut().enumerate() {
+            if j == origin {
+                continue;
+            }
+            let source = VoteSource::from_peer(Channel::Gossip, from);
+            let _ = peer.intake.submit(vote.clone(), source, received + Duration::seconds(2), None, &mut peer.history);
+        }
+        let echo = VoteSource::from_peer(Channel::Gossip, nodes[(origin + 1) % 3].name);
+        let node = &mut nodes[origin];
+        if let Err(e) = node.intake.submit(vote, echo, received + Duration::seconds(3), None, &mut node.history) {
+            println!("🔁 {} dropped an echo of {}'s vote: {}", node.name, voter, e);
+        }
+    }
+
+    let finalized_at = Utc::now();
+    let reports: Vec<FinalizationReport> = nodes.iter().map(|n| n.report(opened_at, finalized_at)).collect();
+    for (node, report) in nodes.iter().zip(&reports) {
+        let stats: Vec<String> = node
+            .intake
+            .stats()
+            .iter()
+            .map(|(channel, s)| format!("{} {}/{}", channel, s.accepted, s.received))
+            .collect();
+        println!(
+            "🖥️ {}: {} votes, passed={}, report {} ({})",
+            node.name,
+            node.intake.admitted().len(),
+            report.passed,
+            &report.body_hash()[..16],
+            stats.join(", ")
+        );
+    }
+    let agreed = reports.windows(2).all(|w| w[0].body_hash() == w[1].body_hash());
+    println!("{} nodes agree on the outcome", if agreed { "✅" } else { "❌" });
+}
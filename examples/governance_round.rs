@@ -0,0 +1,118 @@
+// examples/governance_round.rs
+//
+// One node runs a full governance round: a signed proposal is registered, voters cast
+// signed votes inside its window, the node tallies them into a certified finalization
+// report, and the outcome is recorded on the chain.
+//
+//     cargo run --example governance_round
+
+use chrono::{Duration, Utc};
+
+use time_decay_consensus::blockchain::{Blockchain, FinalizationEntry, Transaction, VoteEntry};
+use time_decay_consensus::explorer;
+use time_decay_consensus::policy::PolicyTable;
+use time_decay_consensus::proposal::{Proposal, ProposalRegistry};
+use time_decay_consensus::report::{FinalizationReport, ReportInput};
+use time_decay_consensus::threshold::DecisionRule;
+use time_decay_consensus::trust::TrustEngine;
+use time_decay_consensus::vote::{DecayType, ProposalType, SignedVote, VoteChoice};
+
+const PROPOSAL: &str = "proposal_fee_cut";
+
+fn main() {
+    let opened_at = Utc::now() - Duration::seconds(600);
+    let policies = PolicyTable::new();
+
+    // The creator opens a Normal proposal with the policy's window
+    let creator_key = SignedVote::generate_keypair();
+    let window = policies.voting_window(&ProposalType::Normal, opened_at, 30);
+    let proposal = Proposal::new(
+        PROPOSAL.parse().unwrap(),
+        "Cut the relay fee to 0.5%",
+        ProposalType::Normal,
+        &window,
+        "validator_001".parse().unwrap(),
+        &creator_key,
+    );
+    let mut proposals = ProposalRegistry::new();
+    proposals.register(proposal).expect("freshly signed proposals register");
+    println!("📜 Opened {} until {}", PROPOSAL, window.closes_at().to_rfc3339());
+
+    // Five voters sign votes over the first few minutes; one is cast on a proposal
+    // the node has never seen and is turned away
+    let ballots = [
+        ("validator_001", VoteChoice::Approve, 30),
+        ("validator_002", VoteChoice::Approve, 90),
+        ("carol", VoteChoice::Approve, 150),
+        ("dave", VoteChoice::Reject, 240),
+        ("erin", VoteChoice::Approve, 300),
+    ];
+    let verified_at = Utc::now();
+    let mut votes = Vec::new();
+    for (voter, choice, offset) in ballots {
+        let key = SignedVote::generate_keypair();
+        let vote = SignedVote::new_with_choice(
+            voter.parse().unwrap(),
+            PROPOSAL.parse().unwrap(),
+            1.0,
+            opened_at + Duration::seconds(offset),
+            DecayType::Linear,
+            choice,
+            &key,
+        );
+        match vote.verify_with_proposals(verified_at, 3600, &proposals) {
+            Ok(()) => votes.push(vote),
+            Err(e) => println!("❌ {} rejected: {}", voter, e),
+        }
+    }
+    let stray = SignedVote::new(
+        "mallory".parse().unwrap(),
+        "proposal_unknown".parse().unwrap(),
+        5.0,
+        opened_at + Duration::seconds(60),
+        DecayType::Linear,
+        &SignedVote::generate_keypair(),
+    );
+    if let Err(e) = stray.verify_with_proposals(verified_at, 3600, &proposals) {
+        println!("❌ mallory rejected: {}", e);
+    }
+
+    // Tally as of now and certify the result
+    let finalized_at = Utc::now();
+    let mut report = FinalizationReport::build(ReportInput {
+        proposal_id: PROPOSAL,
+        proposal_type: ProposalType::Normal,
+        opened_at,
+        finalized_at,
+        votes: &votes,
+        trust: &TrustEngine::new(),
+        max_age_secs: 3600,
+        admissions: None,
+        eligible_weight: Some(5.0),
+        decision: DecisionRule::default(),
+        tie_break_seed: None,
+    });
+    report.certify(&SignedVote::generate_keypair());
+    proposals.close(&PROPOSAL.parse().unwrap(), finalized_at).expect("registered above");
+    print!("{}", report.render_text());
+
+    // Record the votes and the outcome on a local chain
+    let mut chain = Blockchain::with_genesis("Genesis Block".to_string(), 1);
+    let mut transactions: Vec<Transaction> = votes.iter().map(|v| Transaction::Vote(VoteEntry::from(v))).collect();
+    transactions.push(Transaction::Finalization(FinalizationEntry {
+        proposal_id: PROPOSAL.to_string(),
+        passed: report.passed,
+        weight: report.decided_weight(),
+        threshold: report.final_threshold,
+        finalized_at,
+    }));
+    chain.add_transactions(&transactions);
+    let activity = explorer::find_proposal(&chain, PROPOSAL);
+    println!(
+        "⛓️ Chain valid: {}; {} votes and {} finalization on chain; certificate verifies: {}",
+        chain.is_valid(),
+        activity.votes.len(),
+        activity.finalizations.len(),
+        report.verify_certificate()
+    );
+}
@@ -0,0 +1,62 @@
+// examples/offline_bundle.rs
+//
+// Voters without a connection sign votes offline; a relayer gathers them into one
+// batch, writes it to disk as CBOR, and a node later imports the file. The node
+// verifies every vote, admits the good ones through the bundle channel, and tallies
+// them; a vote altered in transit is rejected and logged.
+//
+//     cargo run --example offline_bundle
+
+use chrono::{Duration, Utc};
+
+use time_decay_consensus::history::{HistoryAnalyzer, RejectionQuery};
+use time_decay_consensus::intake::{Channel, VoteIntake, VoteSource};
+use time_decay_consensus::units::Seconds;
+use time_decay_consensus::vote::{DecayType, SignedVote, SignedVoteBatch, VoteChoice};
+use time_decay_consensus::weight_engine::WeightEngine;
+
+const PROPOSAL: &str = "proposal_offline";
+
+fn main() {
+    let signed_at = Utc::now() - Duration::seconds(90);
+
+    // Each voter signs on their own device
+    let mut votes: Vec<SignedVote> = [("alice", VoteChoice::Approve), ("bob", VoteChoice::Approve), ("carol", VoteChoice::Reject)]
+        .iter()
+        .map(|(voter, choice)| {
+            let key = SignedVote::generate_keypair();
+            SignedVote::new_with_choice(voter.parse().unwrap(), PROPOSAL.parse().unwrap(), 1.0, signed_at, DecayType::Stepped, *choice, &key)
+        })
+        .collect();
+    // One vote is flipped on its way to the relayer
+    votes[2].choice = VoteChoice::Approve;
+
+    // The relayer bundles them and writes the bundle out
+    let batch = SignedVoteBatch::from_votes(PROPOSAL.parse().unwrap(), votes).expect("all votes are on one proposal");
+    let path = std::env::temp_dir().join(format!("tdc_bundle_{}.cbor", std::process::id()));
+    std::fs::write(&path, batch.to_cbor().expect("batches encode")).expect("temp dir is writable");
+    println!("📦 Wrote {} votes to {}", batch.len(), path.display());
+
+    // Later, the node imports the file
+    let bytes = std::fs::read(&path).expect("bundle was just written");
+    std::fs::remove_file(&path).expect("bundle was just written");
+    let imported = SignedVoteBatch::from_cbor(&bytes).expect("bundle decodes");
+    let now = Utc::now();
+    let mut intake = VoteIntake::new(Seconds::new(3600));
+    let mut history = HistoryAnalyzer::default();
+    let results = intake.submit_batch(&imported, VoteSource::from_peer(Channel::Bundle, "relayer-1"), now, None, &mut history);
+    for (vote, result) in imported.votes().iter().zip(&results) {
+        match result {
+            Ok(()) => println!("✅ {} admitted", vote.voter_id),
+            Err(e) => println!("❌ {} rejected: {}", vote.voter_id, e),
+        }
+    }
+    let logged = history.rejections(&RejectionQuery::default());
+    println!("🗒️ {} rejection(s) logged from {}", logged.len(), logged.first().map_or("-", |r| r.source.as_str()));
+
+    let tally = WeightEngine::new().batch_calculate(&imported, intake.policy.max_age, now, None).tally;
+    println!(
+        "⚖️ approve {:.4}, reject {:.4} over {} counted votes",
+        tally.approve_weight, tally.reject_weight, tally.votes
+    );
+}
@@ -0,0 +1,268 @@
+// src/amendment.rs
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use thiserror::Error;
+
+use crate::hashing;
+use crate::vote::SignedVote;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AmendmentError {
+    #[error("Amendments closed at {0}")]
+    PastCutoff(DateTime<Utc>),
+    #[error("Only the proposer may amend")]
+    NotProposer,
+    #[error("Amendment {0} has an invalid signature")]
+    InvalidSignature(u64),
+    #[error("Amendment {0} does not extend the chain")]
+    BrokenChain(u64),
+}
+
+/// What happens to votes cast before the latest amendment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AmendmentPolicy {
+    /// Earlier votes no longer count; voters must vote again
+    Invalidate,
+    /// Earlier votes count once the voter confirms with a vote after the amendment
+    #[default]
+    RequireConfirmation,
+    /// Earlier votes keep counting as cast
+    Keep,
+}
+
+/// Where a vote stands against the current text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteStanding {
+    Counted,
+    Invalidated,
+    AwaitingConfirmation,
+}
+
+/// A signed change to a proposal's text, linked to the previous version by hash
+#[derive(Debug, Clone, PartialEq)]
+pub struct Amendment {
+    pub sequence: u64,
+    pub previous: String,
+    pub content: Vec<u8>,
+    pub submitted_at: DateTime<Utc>,
+    pub hash: String,
+    pub signature: Signature,
+}
+
+/// A proposal whose text can be amended by its proposer until `cutoff`
+#[derive(Debug, Clone)]
+pub struct AmendableProposal {
+    pub proposal_id: String,
+    pub proposer: VerifyingKey,
+    pub cutoff: DateTime<Utc>,
+    pub policy: AmendmentPolicy,
+    /// Link 0, over the original text
+    pub genesis: String,
+    amendments: Vec<Amendment>,
+}
+
+impl AmendableProposal {
+    pub fn new(
+        proposal_id: &str,
+        content: &[u8],
+        opened_at: DateTime<Utc>,
+        proposer: VerifyingKey,
+        cutoff: DateTime<Utc>,
+        policy: AmendmentPolicy,
+    ) -> Self {
+        Self {
+            proposal_id: proposal_id.to_string(),
+            proposer,
+            cutoff,
+            policy,
+            genesis: hashing::amendment_link("", proposal_id, 0, content, opened_at.timestamp()),
+            amendments: Vec::new(),
+        }
+    }
+
+    /// Hash of the current text
+    pub fn head(&self) -> &str {
+        self.amendments.last().map_or(&self.genesis, |a| &a.hash)
+    }
+
+    pub fn amendments(&self) -> &[Amendment] {
+        &self.amendments
+    }
+
+    /// When the text last changed, if it ever did
+    pub fn amended_at(&self) -> Option<DateTime<Utc>> {
+        self.amendments.last().map(|a| a.submitted_at)
+    }
+
+    /// Sign and append a new version of the text
+    pub fn amend(&mut self, content: &[u8], at: DateTime<Utc>, key: &SigningKey) -> Result<&Amendment, AmendmentError> {
+        let sequence = self.amendments.len() as u64 + 1;
+        let hash = hashing::amendment_link(self.head(), &self.proposal_id, sequence, content, at.timestamp());
+        let amendment = Amendment {
+            sequence,
+            previous: self.head().to_string(),
+            content: content.to_vec(),
+            submitted_at: at,
+            signature: key.sign(hash.as_bytes()),
+            hash,
+        };
+        self.submit(amendment)
+    }
+
+    /// Append an amendment signed elsewhere, checking the cutoff, signer, and link
+    pub fn submit(&mut self, amendment: Amendment) -> Result<&Amendment, AmendmentError> {
+        if amendment.submitted_at > self.cutoff {
+            return Err(AmendmentError::PastCutoff(self.cutoff));
+        }
+        if self.proposer.verify(amendment.hash.as_bytes(), &amendment.signature).is_err() {
+            return Err(AmendmentError::NotProposer);
+        }
+        if amendment.sequence != self.amendments.len() as u64 + 1 || amendment.previous != self.head() {
+            return Err(AmendmentError::BrokenChain(amendment.sequence));
+        }
+        self.amendments.push(amendment);
+        Ok(self.amendments.last().expect("just pushed"))
+    }
+
+    /// Recompute every link from the genesis hash and check each signature
+    pub fn verify_chain(&self) -> Result<(), AmendmentError> {
+        let mut previous = self.genesis.as_str();
+        for (i, a) in self.amendments.iter().enumerate() {
+            let expected = hashing::amendment_link(
+                previous,
+                &self.proposal_id,
+                i as u64 + 1,
+                &a.content,
+                a.submitted_at.timestamp(),
+            );
+            if a.sequence != i as u64 + 1 || a.previous != previous || a.hash != expected {
+                return Err(AmendmentError::BrokenChain(a.sequence));
+            }
+            if self.proposer.verify(a.hash.as_bytes(), &a.signature).is_err() {
+                return Err(AmendmentError::InvalidSignature(a.sequence));
+            }
+            previous = &a.hash;
+        }
+        Ok(())
+    }
+
+    /// Standing of `vote` given every vote cast on the proposal
+    pub fn standing(&self, vote: &SignedVote, votes: &[SignedVote]) -> VoteStanding {
+        let Some(amended_at) = self.amended_at() else {
+            return VoteStanding::Counted;
+        };
+        if vote.timestamp >= amended_at {
+            return VoteStanding::Counted;
+        }
+        match self.policy {
+            AmendmentPolicy::Keep => VoteStanding::Counted,
+            AmendmentPolicy::Invalidate => VoteStanding::Invalidated,
+            AmendmentPolicy::RequireConfirmation => {
+                let confirmed = votes
+                    .iter()
+                    .any(|v| v.voter_id == vote.voter_id && v.proposal_id == vote.proposal_id && v.timestamp >= amended_at);
+                if confirmed { VoteStanding::Counted } else { VoteStanding::AwaitingConfirmation }
+            }
+        }
+    }
+
+    /// One counted vote per voter: the latest vote that stands against the current text
+    pub fn counted_votes<'a>(&self, votes: &'a [SignedVote]) -> Vec<&'a SignedVote> {
+        let mut latest: HashMap<&str, &SignedVote> = HashMap::new();
+        for vote in votes.iter().filter(|v| v.proposal_id == self.proposal_id) {
+            if self.standing(vote, votes) != VoteStanding::Counted {
+                continue;
+            }
+            let entry = latest.entry(&vote.voter_id).or_insert(vote);
+            if vote.timestamp > entry.timestamp {
+                *entry = vote;
+            }
+        }
+        let mut counted: Vec<&SignedVote> = latest.into_values().collect();
+        counted.sort_by(|a, b| a.voter_id.cmp(&b.voter_id));
+        counted
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::DecayType;
+    use chrono::Duration;
+
+    fn setup(policy: AmendmentPolicy) -> (AmendableProposal, SigningKey, DateTime<Utc>) {
+        let proposer = SignedVote::generate_keypair();
+        let opened = Utc::now();
+        let proposal = AmendableProposal::new(
+            "p1",
+            b"Raise the block size",
+            opened,
+            proposer.verifying_key(),
+            opened + Duration::seconds(60),
+            policy,
+        );
+        (proposal, proposer, opened)
+    }
+
+    fn vote(voter: &str, at: DateTime<Utc>) -> SignedVote {
+        let key = SignedVote::generate_keypair();
+        SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, at, DecayType::Linear, &key)
+    }
+
+    #[test]
+    fn test_amendment_chain_is_verifiable() {
+        let (mut proposal, proposer, opened) = setup(AmendmentPolicy::default());
+        let genesis = proposal.head().to_string();
+        proposal.amend(b"Raise the block size to 2MB", opened + Duration::seconds(10), &proposer).unwrap();
+        let first = proposal.head().to_string();
+        proposal.amend(b"Raise the block size to 4MB", opened + Duration::seconds(20), &proposer).unwrap();
+        assert_eq!(proposal.amendments()[0].previous, genesis);
+        assert_eq!(proposal.amendments()[1].previous, first);
+        assert_eq!(proposal.verify_chain(), Ok(()));
+
+        let mut tampered = proposal.clone();
+        tampered.amendments[0].content = b"Lower the block size".to_vec();
+        assert_eq!(tampered.verify_chain(), Err(AmendmentError::BrokenChain(1)));
+
+        let outsider = SignedVote::generate_keypair();
+        assert_eq!(
+            proposal.amend(b"Hijack", opened + Duration::seconds(30), &outsider).unwrap_err(),
+            AmendmentError::NotProposer
+        );
+        assert!(matches!(
+            proposal.amend(b"Too late", opened + Duration::seconds(61), &proposer),
+            Err(AmendmentError::PastCutoff(_))
+        ));
+    }
+
+    #[test]
+    fn test_policies_decide_earlier_votes() {
+        for (policy, counted) in [
+            (AmendmentPolicy::Keep, vec!["alice", "bob", "carol"]),
+            (AmendmentPolicy::Invalidate, vec!["bob", "carol"]),
+            (AmendmentPolicy::RequireConfirmation, vec!["bob", "carol"]),
+        ] {
+            let (mut proposal, proposer, opened) = setup(policy);
+            let votes = vec![
+                vote("alice", opened + Duration::seconds(5)),
+                vote("bob", opened + Duration::seconds(6)),
+                vote("bob", opened + Duration::seconds(30)),
+                vote("carol", opened + Duration::seconds(40)),
+            ];
+            proposal.amend(b"Amended", opened + Duration::seconds(20), &proposer).unwrap();
+
+            let ids: Vec<&str> = proposal.counted_votes(&votes).iter().map(|v| v.voter_id.as_str()).collect();
+            assert_eq!(ids, counted, "{:?}", policy);
+        }
+
+        let (mut proposal, proposer, opened) = setup(AmendmentPolicy::RequireConfirmation);
+        let votes = vec![vote("alice", opened + Duration::seconds(5))];
+        assert_eq!(proposal.standing(&votes[0], &votes), VoteStanding::Counted);
+        proposal.amend(b"Amended", opened + Duration::seconds(20), &proposer).unwrap();
+        assert_eq!(proposal.standing(&votes[0], &votes), VoteStanding::AwaitingConfirmation);
+    }
+}
@@ -0,0 +1,728 @@
+// src/api.rs
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::blockchain::Blockchain;
+use crate::clock::{Clock, SystemClock};
+use crate::discovery::{self, ProposalDirectory, ProposalFilter};
+use crate::explorer::{self, LocatedFinalization, LocatedVote};
+use crate::history::{HistoryAnalyzer, RejectedVote, RejectionQuery};
+use crate::ids::{ProposalId, VoterId};
+use crate::messages::{self, MessageKey};
+use crate::vote::VoteChoice;
+use crate::replica::{SharedReplicaStatus, SyncPage};
+use crate::state_hash::SharedStateHash;
+
+/// How long a connection may take to send its request or accept the response
+pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest request or header line accepted, in bytes
+pub const MAX_LINE_BYTES: u64 = 8 * 1024;
+
+/// Most headers accepted on one request
+pub const MAX_HEADERS: usize = 64;
+
+/// Connections served at once when `Api::with_max_connections` is not set; further
+/// clients are answered `503` and dropped
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+
+/// Minimal parsed HTTP request
+#[derive(Debug, Clone, Default)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+}
+
+impl Request {
+    pub fn get(target: &str) -> Self {
+        let (path, query) = split_target(target);
+        Request {
+            method: "GET".to_string(),
+            path,
+            query,
+            headers: HashMap::new(),
+        }
+    }
+
+    /// Read the request line and headers; bodies are not supported. Lines longer than
+    /// `MAX_LINE_BYTES` or more than `MAX_HEADERS` headers fail with `InvalidData`.
+    pub fn read_from(reader: &mut impl BufRead) -> std::io::Result<Self> {
+        let mut line = String::new();
+        read_bounded_line(reader, &mut line)?;
+        let mut parts = line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let (path, query) = split_target(parts.next().unwrap_or("/"));
+
+        let mut headers = HashMap::new();
+        for count in 0.. {
+            let mut header = String::new();
+            if read_bounded_line(reader, &mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if count == MAX_HEADERS {
+                return Err(std::io::Error::new(ErrorKind::InvalidData, "too many headers"));
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+
+        Ok(Request { method, path, query, headers })
+    }
+}
+
+fn read_bounded_line(reader: &mut impl BufRead, line: &mut String) -> std::io::Result<usize> {
+    let read = reader.take(MAX_LINE_BYTES + 1).read_line(line)?;
+    if read as u64 > MAX_LINE_BYTES {
+        return Err(std::io::Error::new(ErrorKind::InvalidData, "request line too long"));
+    }
+    Ok(read)
+}
+
+fn split_target(target: &str) -> (String, HashMap<String, String>) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+    (path.to_string(), query)
+}
+
+/// Decode a form-encoded query component: `+` is a space and `%XX` a byte. Malformed
+/// escapes are kept as written.
+fn percent_decode(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => match component.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        Response {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: serde_json::to_string(value).expect("API views serialize to JSON"),
+        }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message }))
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            304 => "Not Modified",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+
+    pub fn write_to(&self, stream: &mut impl Write) -> std::io::Result<()> {
+        write!(stream, "HTTP/1.1 {} {}\r\n", self.status, self.reason())?;
+        for (name, value) in &self.headers {
+            write!(stream, "{}: {}\r\n", name, value)?;
+        }
+        write!(stream, "Content-Length: {}\r\nConnection: close\r\n\r\n", self.body.len())?;
+        stream.write_all(self.body.as_bytes())
+    }
+}
+
+/// Read-only HTTP view over node state
+#[derive(Clone)]
+pub struct Api {
+    chain: Arc<RwLock<Blockchain>>,
+    state_hash: Option<SharedStateHash>,
+    history: Option<Arc<RwLock<HistoryAnalyzer>>>,
+    directory: Option<Arc<RwLock<ProposalDirectory>>>,
+    replica: Option<SharedReplicaStatus>,
+    clock: Arc<dyn Clock>,
+    max_connections: usize,
+}
+
+/// Page size when a request gives no `limit`
+pub const DEFAULT_PAGE_LIMIT: usize = 100;
+pub const MAX_PAGE_LIMIT: usize = 1000;
+
+/// `limit` and `cursor` query parameters. Cursors are opaque to clients; they index the
+/// filtered, append-only result list, so pages stay stable while new entries arrive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PageParams {
+    offset: usize,
+    limit: usize,
+}
+
+impl PageParams {
+    fn from_query(query: &HashMap<String, String>) -> Result<Self, String> {
+        let limit = match query.get("limit") {
+            Some(limit) => match limit.parse::<usize>() {
+                Ok(limit) if (1..=MAX_PAGE_LIMIT).contains(&limit) => limit,
+                _ => return Err(format!("limit must be between 1 and {}", MAX_PAGE_LIMIT)),
+            },
+            None => DEFAULT_PAGE_LIMIT,
+        };
+        let offset = match query.get("cursor") {
+            Some(cursor) => decode_cursor(cursor).ok_or_else(|| "invalid cursor".to_string())?,
+            None => 0,
+        };
+        Ok(Self { offset, limit })
+    }
+
+    /// This page of `items`, and the cursor of the next one when more remain
+    fn apply<T>(&self, items: Vec<T>) -> (Vec<T>, Option<String>) {
+        let total = items.len();
+        let page: Vec<T> = items.into_iter().skip(self.offset).take(self.limit).collect();
+        let end = self.offset + page.len();
+        (page, (end < total).then(|| encode_cursor(end)))
+    }
+}
+
+fn encode_cursor(offset: usize) -> String {
+    hex::encode(format!("o{}", offset))
+}
+
+fn decode_cursor(cursor: &str) -> Option<usize> {
+    String::from_utf8(hex::decode(cursor).ok()?).ok()?.strip_prefix('o')?.parse().ok()
+}
+
+/// Strong validator over a response body
+fn etag(body: &str) -> String {
+    format!("\"{}\"", hex::encode(&Sha256::digest(body.as_bytes())[..16]))
+}
+
+/// Body of `/history/rejected`
+#[derive(Debug, Serialize)]
+struct RejectedVotesView<'a> {
+    total: usize,
+    by_reason: std::collections::BTreeMap<String, usize>,
+    votes: Vec<&'a RejectedVote>,
+    next_cursor: Option<String>,
+}
+
+/// Body of `/chain/proposals/<id>`
+#[derive(Debug, Serialize)]
+struct ProposalActivityView {
+    proposal_id: String,
+    /// Votes matching the filters, across all pages
+    total_votes: usize,
+    votes: Vec<LocatedVote>,
+    finalizations: Vec<LocatedFinalization>,
+    next_cursor: Option<String>,
+}
+
+impl Api {
+    pub fn new(chain: Arc<RwLock<Blockchain>>) -> Self {
+        Self {
+            chain,
+            state_hash: None,
+            history: None,
+            directory: None,
+            replica: None,
+            clock: Arc::new(SystemClock),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+        }
+    }
+
+    /// Serve at most `max` connections at once
+    pub fn with_max_connections(mut self, max: usize) -> Self {
+        self.max_connections = max;
+        self
+    }
+
+    /// Answer time-dependent queries, such as which proposals are open, as of `clock`
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Serve sync progress at `/replica/status` on a read-only replica
+    pub fn with_replica(mut self, status: SharedReplicaStatus) -> Self {
+        self.replica = Some(status);
+        self
+    }
+
+    /// List open proposals at `/proposals` from `directory` instead of deriving them
+    /// from the chain
+    pub fn with_directory(mut self, directory: Arc<RwLock<ProposalDirectory>>) -> Self {
+        self.directory = Some(directory);
+        self
+    }
+
+    /// `/proposals?type=&namespace=&closing_within=`: open proposals, soonest-closing first
+    fn open_proposals(&self, request: &Request) -> Response {
+        let filter = match ProposalFilter::from_query(&request.query) {
+            Ok(filter) => filter,
+            Err(e) => return Response::error(400, &e.to_string()),
+        };
+        let now = self.clock.now();
+        match &self.directory {
+            Some(directory) => {
+                let directory = directory.read().expect("directory lock poisoned");
+                Response::json(200, &directory.list(&filter, now))
+            }
+            None => {
+                let chain = self.chain.read().expect("chain lock poisoned");
+                let directory = ProposalDirectory::from_chain(&chain, discovery::DEFAULT_DURATION);
+                Response::json(200, &directory.list(&filter, now))
+            }
+        }
+    }
+
+    /// Serve rejected votes at `/history/rejected`, filtered by the `proposal`, `voter`,
+    /// `source`, and `since` (RFC 3339) query parameters; an offset's `+` must be sent
+    /// as `%2B`, since a bare `+` decodes to a space
+    pub fn with_history(mut self, history: Arc<RwLock<HistoryAnalyzer>>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    fn rejected_votes(&self, request: &Request) -> Response {
+        let Some(history) = &self.history else {
+            return Response::error(404, "no vote history on this node");
+        };
+        let since = match request.query.get("since") {
+            Some(since) => match chrono::DateTime::parse_from_rfc3339(since) {
+                Ok(since) => Some(since.with_timezone(&chrono::Utc)),
+                Err(_) => return Response::error(400, "since must be an RFC 3339 timestamp"),
+            },
+            None => None,
+        };
+        let (proposal_id, voter_id) = match (
+            request.query.get("proposal").map(|p| p.parse::<ProposalId>()).transpose(),
+            request.query.get("voter").map(|v| v.parse::<VoterId>()).transpose(),
+        ) {
+            (Ok(proposal_id), Ok(voter_id)) => (proposal_id, voter_id),
+            (Err(e), _) | (_, Err(e)) => return Response::error(400, &e.to_string()),
+        };
+        let query = RejectionQuery {
+            proposal_id,
+            voter_id,
+            source: request.query.get("source").cloned(),
+            since,
+        };
+        let page = match PageParams::from_query(&request.query) {
+            Ok(page) => page,
+            Err(e) => return Response::error(400, &e),
+        };
+        let history = history.read().expect("history lock poisoned");
+        let matching = history.rejections(&query);
+        let total = matching.len();
+        let (votes, next_cursor) = page.apply(matching);
+        Response::json(
+            200,
+            &RejectedVotesView {
+                total,
+                by_reason: history.rejection_counts(&query),
+                votes,
+                next_cursor,
+            },
+        )
+    }
+
+    /// `/chain/proposals/<id>?voter=&choice=&limit=&cursor=`: on-chain votes in block
+    /// order, filtered and paginated, plus every finalization
+    fn proposal_activity(&self, chain: &Blockchain, proposal_id: &str, request: &Request) -> Response {
+        let page = match PageParams::from_query(&request.query) {
+            Ok(page) => page,
+            Err(e) => return Response::error(400, &e),
+        };
+        let choice = match request.query.get("choice").map(|c| serde_json::from_value::<VoteChoice>(c.as_str().into())) {
+            Some(Ok(choice)) => Some(choice),
+            Some(Err(_)) => return Response::error(400, "choice must be approve, reject, or abstain"),
+            None => None,
+        };
+        let voter = match request.query.get("voter").map(|v| v.parse::<VoterId>()).transpose() {
+            Ok(voter) => voter,
+            Err(e) => return Response::error(400, &e.to_string()),
+        };
+
+        let activity = explorer::find_proposal(chain, proposal_id);
+        let matching: Vec<LocatedVote> = activity
+            .votes
+            .into_iter()
+            .filter(|v| voter.as_ref().is_none_or(|voter| v.vote.voter_id == voter.as_str()))
+            .filter(|v| choice.is_none_or(|choice| v.vote.choice == choice))
+            .collect();
+        let total_votes = matching.len();
+        let (votes, next_cursor) = page.apply(matching);
+        Response::json(
+            200,
+            &ProposalActivityView {
+                proposal_id: activity.proposal_id,
+                total_votes,
+                votes,
+                finalizations: activity.finalizations,
+                next_cursor,
+            },
+        )
+    }
+
+    /// Serve the periodically recomputed state hash at `/state/hash`, so peers can
+    /// compare it against their own
+    pub fn with_state_hash(mut self, state_hash: SharedStateHash) -> Self {
+        self.state_hash = Some(state_hash);
+        self
+    }
+
+    /// Route `request`, tagging successful responses with an ETag and answering
+    /// `304 Not Modified` when the client's `If-None-Match` already has this body
+    pub fn handle(&self, request: &Request) -> Response {
+        let mut response = self.route(request);
+        if response.status != 200 {
+            return response;
+        }
+        let tag = etag(&response.body);
+        let cached = request
+            .headers
+            .get("if-none-match")
+            .is_some_and(|tags| tags.split(',').any(|t| t.trim() == tag || t.trim() == "*"));
+        if cached {
+            response.status = 304;
+            response.body.clear();
+        }
+        response.headers.push(("ETag".to_string(), tag));
+        response
+    }
+
+    fn route(&self, request: &Request) -> Response {
+        if request.method != "GET" {
+            return Response::error(405, "only GET is supported");
+        }
+
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        if segments.as_slice() == ["state", "hash"] {
+            let latest = self
+                .state_hash
+                .as_ref()
+                .and_then(|shared| shared.read().expect("state hash lock poisoned").clone());
+            return match latest {
+                Some(hash) => Response::json(200, &hash),
+                None => Response::error(404, "state hash not computed yet"),
+            };
+        }
+        if segments.as_slice() == ["history", "rejected"] {
+            return self.rejected_votes(request);
+        }
+        if segments.as_slice() == ["proposals"] {
+            return self.open_proposals(request);
+        }
+        if segments.as_slice() == ["replica", "status"] {
+            return match &self.replica {
+                Some(status) => Response::json(200, &*status.read().expect("replica status lock poisoned")),
+                None => Response::error(404, "this node is not a replica"),
+            };
+        }
+        let chain = self.chain.read().expect("chain lock poisoned");
+
+        match segments.as_slice() {
+            ["chain"] => Response::json(200, &explorer::chain_summary(&chain)),
+            ["chain", "blocks", id] => match id.parse::<u64>() {
+                Ok(id) => match explorer::block_detail(&chain, id) {
+                    Some(detail) => Response::json(200, &detail),
+                    None => Response::error(404, "no such block"),
+                },
+                Err(_) => Response::error(400, "block id must be an integer"),
+            },
+            ["chain", "sync"] => match request.query.get("from").map_or(Ok(0), |from| from.parse::<u64>()) {
+                Ok(from) => Response::json(200, &SyncPage::from_chain(&chain, from)),
+                Err(_) => Response::error(400, "from must be a block id"),
+            },
+            ["chain", "proposals", proposal_id] => self.proposal_activity(&chain, proposal_id, request),
+            _ => Response::error(404, "unknown endpoint"),
+        }
+    }
+
+    fn handle_connection(&self, stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+        stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+        let mut writer = stream.try_clone()?;
+        match Request::read_from(&mut BufReader::new(stream)) {
+            Ok(request) => self.handle(&request).write_to(&mut writer),
+            Err(e) if e.kind() == ErrorKind::InvalidData => Response::error(400, &e.to_string()).write_to(&mut writer),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Serve each connection on its own thread, up to the connection limit, until the
+    /// listener fails; a client that stalls is dropped after `CONNECTION_TIMEOUT`
+    pub fn serve(&self, listener: TcpListener) {
+        let active = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(mut stream) if active.load(Ordering::SeqCst) >= self.max_connections => {
+                        let _ = stream.set_write_timeout(Some(CONNECTION_TIMEOUT));
+                        let _ = Response::error(503, "too many connections").write_to(&mut stream);
+                    }
+                    Ok(stream) => {
+                        active.fetch_add(1, Ordering::SeqCst);
+                        let active = &active;
+                        scope.spawn(move || {
+                            if let Err(e) = self.handle_connection(stream) {
+                                println!("{}", messages::text(MessageKey::ApiConnectionFailed, &[("error", e.to_string())]));
+                            }
+                            active.fetch_sub(1, Ordering::SeqCst);
+                        });
+                    }
+                    Err(e) => println!("{}", messages::text(MessageKey::ApiAcceptFailed, &[("error", e.to_string())])),
+                }
+            }
+        });
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api() -> Api {
+        let mut chain = Blockchain::new();
+        chain.add_block("Transaction 1 Data".to_string());
+        Api::new(Arc::new(RwLock::new(chain)))
+    }
+
+    #[test]
+    fn test_chain_endpoints() {
+        let api = api();
+        let chain = api.handle(&Request::get("/chain"));
+        assert_eq!(chain.status, 200);
+        assert!(chain.body.contains("\"length\":2"));
+
+        assert_eq!(api.handle(&Request::get("/chain/blocks/1")).status, 200);
+        assert_eq!(api.handle(&Request::get("/chain/blocks/7")).status, 404);
+        assert_eq!(api.handle(&Request::get("/chain/blocks/x")).status, 400);
+        assert_eq!(api.handle(&Request::get("/chain/proposals/p1")).status, 200);
+        let sync: SyncPage = serde_json::from_str(&api.handle(&Request::get("/chain/sync?from=1")).body).unwrap();
+        assert_eq!((sync.length, sync.blocks.len()), (2, 1));
+        assert_eq!(api.handle(&Request::get("/chain/sync?from=x")).status, 400);
+        assert_eq!(api.handle(&Request::get("/replica/status")).status, 404);
+        assert_eq!(api.handle(&Request::get("/nope")).status, 404);
+    }
+
+    #[test]
+    fn test_rejected_votes_endpoint() {
+        use crate::vote::{DecayType, SignedVote};
+
+        assert_eq!(api().handle(&Request::get("/history/rejected")).status, 404);
+
+        let key = SignedVote::generate_keypair();
+        let now = chrono::Utc::now();
+        let mut history = HistoryAnalyzer::default();
+        for (voter, source) in [("alice", "peer-a"), ("bob", "peer-b")] {
+            let vote = SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key);
+            history.record_rejection(RejectedVote::new(&vote, "Invalid signature", source, now));
+        }
+        let api = api().with_history(Arc::new(RwLock::new(history)));
+
+        let all = api.handle(&Request::get("/history/rejected?proposal=p1"));
+        assert_eq!(all.status, 200);
+        assert!(all.body.contains("\"total\":2"));
+        let filtered = api.handle(&Request::get("/history/rejected?source=peer-b"));
+        assert!(filtered.body.contains("\"total\":1"));
+        assert!(filtered.body.contains("\"Invalid signature\":1"));
+        assert_eq!(api.handle(&Request::get("/history/rejected?since=yesterday")).status, 400);
+        let encoded = api.handle(&Request::get("/history/rejected?since=2026-01-01T00%3A00%3A00%2B00%3A00"));
+        assert_eq!(encoded.status, 200);
+        assert!(encoded.body.contains("\"total\":2"));
+        let later = api.handle(&Request::get("/history/rejected?since=9999-01-01T00%3A00%3A00%2B00%3A00"));
+        assert!(later.body.contains("\"total\":0"));
+        assert_eq!(api.handle(&Request::get("/history/rejected?since=2026-01-01T00:00:00+00:00")).status, 400);
+
+        let first = api.handle(&Request::get("/history/rejected?limit=1"));
+        let view: serde_json::Value = serde_json::from_str(&first.body).unwrap();
+        assert_eq!((view["total"].as_u64(), view["votes"].as_array().unwrap().len()), (Some(2), 1));
+        let cursor = view["next_cursor"].as_str().unwrap();
+        let second = api.handle(&Request::get(&format!("/history/rejected?limit=1&cursor={}", cursor)));
+        let view: serde_json::Value = serde_json::from_str(&second.body).unwrap();
+        assert_eq!(view["votes"][0]["voter_id"], "bob");
+        assert!(view["next_cursor"].is_null());
+        assert_eq!(api.handle(&Request::get("/history/rejected?limit=0")).status, 400);
+        assert_eq!(api.handle(&Request::get("/history/rejected?cursor=zz")).status, 400);
+    }
+
+    #[test]
+    fn test_etags() {
+        let api = api();
+        let response = api.handle(&Request::get("/chain"));
+        let (_, tag) = response.headers.iter().find(|(name, _)| name == "ETag").unwrap().clone();
+
+        let mut request = Request::get("/chain");
+        request.headers.insert("if-none-match".to_string(), format!("\"other\", {}", tag));
+        let cached = api.handle(&request);
+        assert_eq!((cached.status, cached.body.as_str()), (304, ""));
+
+        api.chain.write().unwrap().add_block("Transaction 2 Data".to_string());
+        let changed = api.handle(&request);
+        assert_eq!(changed.status, 200);
+        assert!(changed.headers.iter().any(|(name, value)| name == "ETag" && *value != tag));
+        assert!(api.handle(&Request::get("/nope")).headers.iter().all(|(name, _)| name != "ETag"));
+    }
+
+    #[test]
+    fn test_proposal_activity_filters() {
+        let api = api();
+        let response = api.handle(&Request::get("/chain/proposals/p1?voter=alice&choice=approve&limit=10"));
+        let view: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!((view["total_votes"].as_u64(), view["next_cursor"].is_null()), (Some(0), true));
+        assert_eq!(api.handle(&Request::get("/chain/proposals/p1?choice=maybe")).status, 400);
+        assert_eq!(api.handle(&Request::get("/chain/proposals/p1?limit=5000")).status, 400);
+        assert_eq!(api.handle(&Request::get("/chain/proposals/p1?voter=alice%3Ap2")).status, 400);
+    }
+
+    #[test]
+    fn test_proposals_endpoint() {
+        use crate::discovery::ProposalListing;
+        use crate::units::Seconds;
+        use crate::vote::ProposalType;
+
+        let from_chain = api().handle(&Request::get("/proposals"));
+        assert_eq!((from_chain.status, from_chain.body.as_str()), (200, "[]"));
+
+        let now = chrono::Utc::now();
+        let mut directory = ProposalDirectory::new();
+        for (id, proposal_type) in [("p1", ProposalType::Normal), ("p2", ProposalType::Critical)] {
+            directory.open(ProposalListing::new(id, proposal_type, Some("treasury"), now, Seconds::new(600)), now);
+        }
+        let api = api().with_directory(Arc::new(RwLock::new(directory)));
+        let critical = api.handle(&Request::get("/proposals?type=critical"));
+        assert!(critical.body.contains("\"p2\"") && !critical.body.contains("\"p1\""));
+        assert_eq!(api.handle(&Request::get("/proposals?closing_within=x")).status, 400);
+    }
+
+    #[test]
+    fn test_state_hash_endpoint() {
+        use crate::registry::ValidatorRegistry;
+        use crate::state_hash::{DEFAULT_QUANTUM, StateHash};
+        use crate::trust::TrustEngine;
+
+        assert_eq!(api().handle(&Request::get("/state/hash")).status, 404);
+
+        let shared: SharedStateHash = Arc::new(RwLock::new(None));
+        let api = api().with_state_hash(Arc::clone(&shared));
+        assert_eq!(api.handle(&Request::get("/state/hash")).status, 404);
+
+        let hash = StateHash::compute(
+            &api.chain.read().unwrap(),
+            &ValidatorRegistry::new(),
+            &TrustEngine::new(),
+            chrono::Utc::now(),
+            DEFAULT_QUANTUM,
+        );
+        *shared.write().unwrap() = Some(hash.clone());
+        let response = api.handle(&Request::get("/state/hash"));
+        assert_eq!(response.status, 200);
+        let served: StateHash = serde_json::from_str(&response.body).unwrap();
+        assert!(served.diverged(&hash).is_empty());
+    }
+
+    #[test]
+    fn test_request_parsing() {
+        let raw = "GET /chain/blocks/1?verbose=1 HTTP/1.1\r\nHost: localhost\r\nIf-None-Match: \"abc\"\r\n\r\n";
+        let request = Request::read_from(&mut raw.as_bytes()).unwrap();
+        assert_eq!(request.path, "/chain/blocks/1");
+        assert_eq!(request.query.get("verbose").map(String::as_str), Some("1"));
+        assert_eq!(request.headers.get("if-none-match").map(String::as_str), Some("\"abc\""));
+
+        let request = Request::get("/proposals?q=fee+cap%2Fv2&tag=%E2%9C%93&bad=100%&odd=%zz");
+        assert_eq!(request.query.get("q").map(String::as_str), Some("fee cap/v2"));
+        assert_eq!(request.query.get("tag").map(String::as_str), Some("✓"));
+        assert_eq!(request.query.get("bad").map(String::as_str), Some("100%"));
+        assert_eq!(request.query.get("odd").map(String::as_str), Some("%zz"));
+
+        let long_target = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE_BYTES as usize));
+        let err = Request::read_from(&mut long_target.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let many_headers = format!("GET / HTTP/1.1\r\n{}\r\n", "X-Pad: 1\r\n".repeat(MAX_HEADERS + 1));
+        let err = Request::read_from(&mut many_headers.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_serves_over_tcp() {
+        let api = api();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            api.handle_connection(stream).unwrap();
+        });
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"GET /chain HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("application/json"));
+    }
+
+    #[test]
+    fn test_stalled_client_does_not_block_others() {
+        let api = api();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || api.serve(listener));
+
+        let _stalled = TcpStream::connect(addr).unwrap();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        stream.write_all(b"GET /chain HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[test]
+    fn test_connections_beyond_the_limit_are_refused() {
+        let api = api().with_max_connections(1);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || api.serve(listener));
+
+        let _stalled = TcpStream::connect(addr).unwrap();
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable"));
+    }
+}
@@ -0,0 +1,449 @@
+// src/archive.rs
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::blockchain::Block;
+use crate::history::HistoryAnalyzer;
+use crate::report::FinalizationReport;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long an object store gets to accept a connection
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long an object store may stall mid-request or mid-response
+pub const IO_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ArchiveError {
+    #[error("Invalid object store endpoint: {0}")]
+    InvalidEndpoint(String),
+    #[error("Object store request failed: {0}")]
+    Store(String),
+    #[error("No archived object at {0}")]
+    NotFound(String),
+    #[error("Archived object {key} hashes to {actual}, expected {expected}")]
+    IntegrityMismatch { key: String, expected: String, actual: String },
+    #[error("Archived object {key} could not be decoded: {reason}")]
+    Decode { key: String, reason: String },
+}
+
+/// Where archived objects go; implementations must return exactly the bytes they were given
+pub trait ObjectStore: Send + Sync {
+    /// Upload `body`, whose hex SHA-256 is `sha256`
+    fn put(&self, key: &str, body: &[u8], sha256: &str) -> Result<(), ArchiveError>;
+    fn get(&self, key: &str) -> Result<Vec<u8>, ArchiveError>;
+}
+
+/// Objects held in memory, for tests and dry runs
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    objects: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overwrite an object behind the archiver's back, e.g. to simulate corruption
+    pub fn tamper(&self, key: &str, body: Vec<u8>) {
+        self.objects.lock().expect("store lock poisoned").insert(key.to_string(), body);
+    }
+}
+
+impl ObjectStore for MemoryStore {
+    fn put(&self, key: &str, body: &[u8], _sha256: &str) -> Result<(), ArchiveError> {
+        self.objects.lock().expect("store lock poisoned").insert(key.to_string(), body.to_vec());
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, ArchiveError> {
+        let objects = self.objects.lock().expect("store lock poisoned");
+        objects.get(key).cloned().ok_or_else(|| ArchiveError::NotFound(key.to_string()))
+    }
+}
+
+/// An S3-compatible bucket (AWS, MinIO, Ceph, ...) reached over HTTPS, or plain HTTP
+/// for a local endpoint, with path-style addressing and SigV4-signed requests
+#[derive(Debug, Clone, PartialEq)]
+pub struct S3Store {
+    /// `https` or `http`
+    pub scheme: String,
+    pub host: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    secret_key: String,
+}
+
+impl S3Store {
+    pub fn new(endpoint: &str, bucket: &str, region: &str, access_key: &str, secret_key: &str) -> Result<Self, ArchiveError> {
+        let (scheme, host) = endpoint
+            .split_once("://")
+            .filter(|(scheme, _)| matches!(*scheme, "https" | "http"))
+            .map(|(scheme, rest)| (scheme, rest.trim_end_matches('/')))
+            .filter(|(_, host)| !host.is_empty() && !host.contains('/'))
+            .ok_or_else(|| ArchiveError::InvalidEndpoint(endpoint.to_string()))?;
+        Ok(Self {
+            scheme: scheme.to_string(),
+            host: host.to_string(),
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+        })
+    }
+
+    /// Send a signed request and return the status code and body
+    fn request(&self, method: &str, key: &str, body: &[u8], sha256: &str) -> Result<(u16, Vec<u8>), ArchiveError> {
+        let io = |e: std::io::Error| ArchiveError::Store(e.to_string());
+        let path = format!("/{}/{}", uri_encode(&self.bucket), uri_encode(key));
+        let headers = [("host", self.host.clone()), ("x-amz-content-sha256", sha256.to_string())];
+        let authorization = sign_v4(method, &path, &headers, sha256, &self.region, &self.access_key, &self.secret_key, Utc::now());
+
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(CONNECT_TIMEOUT)
+            .timeout_read(IO_TIMEOUT)
+            .timeout_write(IO_TIMEOUT)
+            .redirects(0)
+            .build();
+        // The Host header is set explicitly so it is byte-for-byte the one signed
+        let mut request = agent
+            .request(method, &format!("{}://{}{}", self.scheme, self.host, path))
+            .set("Host", &self.host)
+            .set("x-amz-content-sha256", sha256);
+        for (name, value) in &authorization {
+            request = request.set(name, value);
+        }
+        let response = match request.send_bytes(body) {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(ArchiveError::Store(e.to_string())),
+        };
+        let code = response.status();
+        let mut body = Vec::new();
+        response.into_reader().read_to_end(&mut body).map_err(io)?;
+        Ok((code, body))
+    }
+}
+
+impl ObjectStore for S3Store {
+    fn put(&self, key: &str, body: &[u8], sha256: &str) -> Result<(), ArchiveError> {
+        // S3 rejects the upload if the body does not match x-amz-content-sha256
+        match self.request("PUT", key, body, sha256)? {
+            (200..=299, _) => Ok(()),
+            (code, response) => Err(ArchiveError::Store(format!("PUT {} answered {}: {}", key, code, String::from_utf8_lossy(&response)))),
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, ArchiveError> {
+        match self.request("GET", key, &[], &sha256_hex(&[]))? {
+            (200..=299, body) => Ok(body),
+            (404, _) => Err(ArchiveError::NotFound(key.to_string())),
+            (code, response) => Err(ArchiveError::Store(format!("GET {} answered {}: {}", key, code, String::from_utf8_lossy(&response)))),
+        }
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encode everything but unreserved characters and `/`
+fn uri_encode(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac(format!("AWS4{}", secret_key).as_bytes(), date);
+    let key = hmac(&key, region);
+    let key = hmac(&key, service);
+    hmac(&key, "aws4_request")
+}
+
+/// AWS Signature Version 4 headers for a request with no query string; `headers` must
+/// have lowercase names
+#[allow(clippy::too_many_arguments)]
+fn sign_v4(
+    method: &str,
+    path: &str,
+    headers: &[(&str, String)],
+    payload_sha256: &str,
+    region: &str,
+    access_key: &str,
+    secret_key: &str,
+    at: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    let amz_date = at.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = at.format("%Y%m%d").to_string();
+    let mut headers = headers.to_vec();
+    headers.push(("x-amz-date", amz_date.clone()));
+    headers.sort();
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value.trim())).collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!("{}\n{}\n\n{}\n{}\n{}", method, path, canonical_headers, signed_headers, payload_sha256);
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, sha256_hex(canonical_request.as_bytes()));
+    let signature = hex::encode(hmac(&signing_key(secret_key, &date, region, "s3"), &string_to_sign));
+    vec![
+        ("x-amz-date", amz_date),
+        (
+            "Authorization",
+            format!("AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}", access_key, scope, signed_headers, signature),
+        ),
+    ]
+}
+
+/// What an archived object holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveKind {
+    History,
+    Report,
+    ChainSegment,
+}
+
+/// Manifest entry for one upload; keep it to restore the object later
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedObject {
+    pub key: String,
+    pub kind: ArchiveKind,
+    /// Hex SHA-256 of the uploaded bytes, checked again on restore
+    pub sha256: String,
+    pub size: usize,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// Uploads pruned history, finalization reports, and chain segments under `prefix`,
+/// and restores them only if they still match the content hash recorded at upload
+pub struct Archiver {
+    store: Box<dyn ObjectStore>,
+    pub prefix: String,
+    manifest: Vec<ArchivedObject>,
+}
+
+impl Archiver {
+    pub fn new(store: Box<dyn ObjectStore>, prefix: &str) -> Self {
+        Self { store, prefix: prefix.trim_end_matches('/').to_string(), manifest: Vec::new() }
+    }
+
+    /// Resume from a manifest saved by an earlier run
+    pub fn with_manifest(mut self, manifest: Vec<ArchivedObject>) -> Self {
+        self.manifest = manifest;
+        self
+    }
+
+    pub fn manifest(&self) -> &[ArchivedObject] {
+        &self.manifest
+    }
+
+    fn upload(&mut self, kind: ArchiveKind, name: &str, body: &[u8], at: DateTime<Utc>) -> Result<ArchivedObject, ArchiveError> {
+        let sha256 = sha256_hex(body);
+        let folder = match kind {
+            ArchiveKind::History => "history",
+            ArchiveKind::Report => "reports",
+            ArchiveKind::ChainSegment => "chain",
+        };
+        // The hash in the key keeps different contents from overwriting each other
+        let key = format!("{}/{}/{}-{}.json", self.prefix, folder, name, &sha256[..16]);
+        self.store.put(&key, body, &sha256)?;
+        let object = ArchivedObject { key, kind, sha256, size: body.len(), archived_at: at };
+        self.manifest.push(object.clone());
+        Ok(object)
+    }
+
+    /// Archive vote history that is about to be pruned locally, labelled e.g. by epoch
+    pub fn archive_history(&mut self, label: &str, history: &HistoryAnalyzer, at: DateTime<Utc>) -> Result<ArchivedObject, ArchiveError> {
+        self.upload(ArchiveKind::History, label, history.export_json().as_bytes(), at)
+    }
+
+    pub fn archive_report(&mut self, report: &FinalizationReport, at: DateTime<Utc>) -> Result<ArchivedObject, ArchiveError> {
+        self.upload(ArchiveKind::Report, &report.proposal.proposal_id, report.to_json().as_bytes(), at)
+    }
+
+    /// Archive a contiguous run of blocks, named by their first and last ids
+    pub fn archive_segment(&mut self, blocks: &[Block], at: DateTime<Utc>) -> Result<ArchivedObject, ArchiveError> {
+        let name = match (blocks.first(), blocks.last()) {
+            (Some(first), Some(last)) => format!("{:08}-{:08}", first.id, last.id),
+            _ => "empty".to_string(),
+        };
+        let body = serde_json::to_vec(blocks).expect("blocks serialize to JSON");
+        self.upload(ArchiveKind::ChainSegment, &name, &body, at)
+    }
+
+    /// Download `object` and check it against its recorded hash and size
+    pub fn restore(&self, object: &ArchivedObject) -> Result<Vec<u8>, ArchiveError> {
+        let body = self.store.get(&object.key)?;
+        let actual = sha256_hex(&body);
+        if actual != object.sha256 || body.len() != object.size {
+            return Err(ArchiveError::IntegrityMismatch { key: object.key.clone(), expected: object.sha256.clone(), actual });
+        }
+        Ok(body)
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(&self, object: &ArchivedObject) -> Result<T, ArchiveError> {
+        let body = self.restore(object)?;
+        serde_json::from_slice(&body).map_err(|e| ArchiveError::Decode { key: object.key.clone(), reason: e.to_string() })
+    }
+
+    pub fn restore_history(&self, object: &ArchivedObject) -> Result<HistoryAnalyzer, ArchiveError> {
+        let body = self.restore(object)?;
+        let json = String::from_utf8_lossy(&body);
+        HistoryAnalyzer::import_json(&json).map_err(|e| ArchiveError::Decode { key: object.key.clone(), reason: e.to_string() })
+    }
+
+    pub fn restore_report(&self, object: &ArchivedObject) -> Result<FinalizationReport, ArchiveError> {
+        self.decode(object)
+    }
+
+    /// Restore a chain segment, also checking every block's hash and link to the one before
+    pub fn restore_segment(&self, object: &ArchivedObject) -> Result<Vec<Block>, ArchiveError> {
+        let blocks: Vec<Block> = self.decode(object)?;
+        // Like `Blockchain::check_block`, the genesis block is trusted by construction
+        let broken = blocks.iter().any(|b| b.id != 0 && b.hash != b.calculate_hash())
+            || blocks.windows(2).any(|pair| pair[1].previous_hash != pair[0].hash);
+        if broken {
+            return Err(ArchiveError::Decode { key: object.key.clone(), reason: "block hashes do not chain".to_string() });
+        }
+        Ok(blocks)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+
+    use chrono::Duration;
+
+    use crate::blockchain::Blockchain;
+    use crate::report::ReportInput;
+    use crate::threshold::DecisionRule;
+    use crate::trust::TrustEngine;
+    use crate::vote::{DecayType, ProposalType, SignedVote};
+
+    /// Shares one `MemoryStore` between the archiver and the test
+    struct Shared(Arc<MemoryStore>);
+
+    impl ObjectStore for Shared {
+        fn put(&self, key: &str, body: &[u8], sha256: &str) -> Result<(), ArchiveError> {
+            self.0.put(key, body, sha256)
+        }
+
+        fn get(&self, key: &str) -> Result<Vec<u8>, ArchiveError> {
+            self.0.get(key)
+        }
+    }
+
+    #[test]
+    fn test_archive_round_trip_detects_tampering() {
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let votes = vec![SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key)];
+        let report = FinalizationReport::build(ReportInput {
+            proposal_id: "p1",
+            proposal_type: ProposalType::Normal,
+            opened_at: now - Duration::seconds(60),
+            finalized_at: now,
+            votes: &votes,
+            trust: &TrustEngine::new(),
+            max_age_secs: 3600,
+            admissions: None,
+            eligible_weight: None,
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        });
+        let mut chain = Blockchain::new();
+        chain.difficulty = 1;
+        chain.add_block("votes".to_string());
+        chain.add_block("more votes".to_string());
+
+        let store = Arc::new(MemoryStore::new());
+        let mut archiver = Archiver::new(Box::new(Shared(store.clone())), "tdc/");
+        let archived_report = archiver.archive_report(&report, now).unwrap();
+        let segment = archiver.archive_segment(&chain.blocks, now).unwrap();
+        let history = archiver.archive_history("epoch-1", &HistoryAnalyzer::default(), now).unwrap();
+        assert!(archived_report.key.starts_with("tdc/reports/p1-"));
+        assert!(segment.key.starts_with("tdc/chain/00000000-00000002-"));
+
+        // A fresh archiver restores from the saved manifest alone
+        let manifest: Vec<ArchivedObject> = serde_json::from_str(&serde_json::to_string(archiver.manifest()).unwrap()).unwrap();
+        let restorer = Archiver::new(Box::new(Shared(store.clone())), "tdc").with_manifest(manifest);
+        assert_eq!(restorer.restore_report(&archived_report), Ok(report));
+        assert_eq!(restorer.restore_segment(&segment).unwrap().len(), chain.blocks.len());
+        assert!(restorer.restore_history(&history).is_ok());
+
+        store.tamper(&archived_report.key, b"{}".to_vec());
+        assert!(matches!(restorer.restore_report(&archived_report), Err(ArchiveError::IntegrityMismatch { .. })));
+    }
+
+    #[test]
+    fn test_s3_requests_are_signed() {
+        // Signing key example from the AWS SigV4 documentation
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex::encode(key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+        let aws = S3Store::new("https://s3.amazonaws.com/", "b", "us-east-1", "a", "s").unwrap();
+        assert_eq!((aws.scheme.as_str(), aws.host.as_str()), ("https", "s3.amazonaws.com"));
+        assert!(S3Store::new("ftp://s3.amazonaws.com", "b", "us-east-1", "a", "s").is_err());
+        assert!(S3Store::new("s3.amazonaws.com", "b", "us-east-1", "a", "s").is_err());
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            let responses = [
+                "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+                // Large objects come back chunked
+                "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nhel\r\n2\r\nlo\r\n0\r\n\r\n",
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+            ];
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut head = String::new();
+                while reader.read_line(&mut head).unwrap() > 0 && !head.ends_with("\r\n\r\n") {}
+                let length = head
+                    .lines()
+                    .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length: ").map(|n| n.parse().unwrap()))
+                    .unwrap_or(0);
+                reader.read_exact(&mut vec![0; length]).unwrap();
+                requests.push(head);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+
+        let store = S3Store::new(&endpoint, "archive", "us-east-1", "AKIDEXAMPLE", "secret").unwrap();
+        store.put("tdc/reports/p 1.json", b"hello", &sha256_hex(b"hello")).unwrap();
+        assert_eq!(store.get("tdc/reports/p 1.json").unwrap(), b"hello");
+        assert_eq!(store.get("missing.json"), Err(ArchiveError::NotFound("missing.json".to_string())));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("PUT /archive/tdc/reports/p%201.json HTTP/1.1"));
+        assert!(requests[0].contains("Authorization: AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"));
+        assert!(requests[0].contains("SignedHeaders=host;x-amz-content-sha256;x-amz-date"));
+        assert_eq!(requests[0].to_ascii_lowercase().matches("\r\nhost: ").count(), 1);
+        assert!(requests[1].starts_with("GET /archive/"));
+    }
+}
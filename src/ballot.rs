@@ -0,0 +1,423 @@
+// src/ballot.rs
+
+use std::collections::BTreeMap;
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use hkdf::Hkdf;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+use crate::hashing;
+use crate::ids::{ProposalId, VoterId};
+use crate::scheme::{DefaultScheme, SignatureScheme};
+use crate::tally::Tally;
+use crate::trust::TrustSource;
+use crate::vote::{
+    SignedVote, deserialize_public_key, deserialize_signature, serialize_public_key, serialize_signature,
+};
+use crate::weight_engine::WeightEngine;
+use crate::window::VotingWindow;
+
+/// HKDF label for the per-ballot encryption key
+const KDF_INFO: &[u8] = b"tdc/ballot-key/v1";
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BallotError {
+    #[error("Ballot is for {found}, not {expected}")]
+    WrongProposal { expected: ProposalId, found: ProposalId },
+    #[error("Ballot from {0} has an invalid signature")]
+    InvalidSignature(VoterId),
+    #[error("{0} already cast a ballot")]
+    DuplicateBallot(VoterId),
+    #[error("Voting closed at {0}")]
+    WindowClosed(DateTime<Utc>),
+    #[error("The election key cannot be released before voting closes at {0}")]
+    WindowOpen(DateTime<Utc>),
+    #[error("Released key does not match the election key")]
+    KeyMismatch,
+    #[error("The election key has already been released")]
+    AlreadyReleased,
+    #[error("Ballots stay sealed until the election key is released")]
+    Sealed,
+    #[error("Ballot from {0} could not be decrypted")]
+    Undecryptable(VoterId),
+    #[error("Ballot from {voter} holds an invalid vote: {reason}")]
+    InvalidVote { voter: VoterId, reason: String },
+    #[error("Malformed {0}")]
+    Malformed(&'static str),
+}
+
+fn decode_key(hex_key: &str, field: &'static str) -> Result<[u8; 32], BallotError> {
+    hex::decode(hex_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(BallotError::Malformed(field))
+}
+
+/// Published half of a proposal's election key; voters encrypt ballots to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElectionPublicKey {
+    pub proposal_id: ProposalId,
+    /// Hex x25519 public key
+    pub key: String,
+}
+
+impl ElectionPublicKey {
+    fn point(&self) -> Result<PublicKey, BallotError> {
+        decode_key(&self.key, "election key").map(PublicKey::from)
+    }
+}
+
+/// The election secret, published once voting has closed so anyone can decrypt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRelease {
+    pub proposal_id: ProposalId,
+    /// Hex x25519 secret key
+    pub secret: String,
+}
+
+/// Secret half of a proposal's election key, held by whoever releases it after voting
+pub struct ElectionKey {
+    pub proposal_id: ProposalId,
+    secret: StaticSecret,
+}
+
+impl ElectionKey {
+    pub fn generate(proposal_id: ProposalId) -> Self {
+        Self {
+            proposal_id,
+            secret: StaticSecret::random_from_rng(OsRng),
+        }
+    }
+
+    pub fn public_key(&self) -> ElectionPublicKey {
+        ElectionPublicKey {
+            proposal_id: self.proposal_id.clone(),
+            key: hex::encode(PublicKey::from(&self.secret).as_bytes()),
+        }
+    }
+
+    pub fn release(&self) -> KeyRelease {
+        KeyRelease {
+            proposal_id: self.proposal_id.clone(),
+            secret: hex::encode(self.secret.to_bytes()),
+        }
+    }
+}
+
+/// ChaCha20-Poly1305 keyed from an x25519 exchange between a ballot's ephemeral key
+/// and the election key
+fn ballot_cipher(shared: &[u8; 32], ephemeral: &PublicKey, election: &PublicKey) -> ChaCha20Poly1305 {
+    let info = [KDF_INFO, ephemeral.as_bytes(), election.as_bytes()].concat();
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, shared)
+        .expand(&info, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+/// Ties the ciphertext to its voter and proposal, so it cannot be re-attributed
+fn associated_data(voter_id: &str, proposal_id: &str) -> String {
+    format!("ballot:{}:{}", voter_id, proposal_id)
+}
+
+/// A signed vote sealed to the election key. Who voted and when is public; the choice
+/// and weight are not until the key is released.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedBallot {
+    pub voter_id: VoterId,
+    pub proposal_id: ProposalId,
+    pub submitted_at: DateTime<Utc>,
+    /// Hex x25519 public key of the one-off sender key
+    pub ephemeral_key: String,
+    /// Hex
+    pub nonce: String,
+    /// Hex ChaCha20-Poly1305 ciphertext of the JSON vote
+    pub ciphertext: String,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    pub signature: Signature,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    pub public_key: VerifyingKey,
+}
+
+impl EncryptedBallot {
+    /// Encrypt `vote` to `election` and sign the result with the voter's key
+    pub fn seal(
+        vote: &SignedVote,
+        election: &ElectionPublicKey,
+        submitted_at: DateTime<Utc>,
+        key: &SigningKey,
+    ) -> Result<Self, BallotError> {
+        let election_key = election.point()?;
+        let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_key = PublicKey::from(&ephemeral);
+        let shared = ephemeral.diffie_hellman(&election_key);
+        let cipher = ballot_cipher(shared.as_bytes(), &ephemeral_key, &election_key);
+
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce);
+        let plaintext = serde_json::to_vec(vote).expect("votes serialize");
+        let aad = associated_data(&vote.voter_id, &vote.proposal_id);
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: aad.as_bytes() })
+            .expect("ChaCha20-Poly1305 encryption does not fail");
+
+        let mut ballot = Self {
+            voter_id: vote.voter_id.clone(),
+            proposal_id: vote.proposal_id.clone(),
+            submitted_at,
+            ephemeral_key: hex::encode(ephemeral_key.as_bytes()),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+            signature: Signature::from_bytes(&[0u8; 64]),
+            public_key: DefaultScheme::public_key(key),
+        };
+        ballot.signature = DefaultScheme::sign(key, ballot.digest()?.as_bytes());
+        Ok(ballot)
+    }
+
+    fn digest(&self) -> Result<String, BallotError> {
+        Ok(hashing::ballot_digest(
+            &self.voter_id,
+            &self.proposal_id,
+            &self.submitted_at.to_rfc3339(),
+            &hex::decode(&self.ephemeral_key).map_err(|_| BallotError::Malformed("ephemeral key"))?,
+            &hex::decode(&self.nonce).map_err(|_| BallotError::Malformed("nonce"))?,
+            &hex::decode(&self.ciphertext).map_err(|_| BallotError::Malformed("ciphertext"))?,
+        ))
+    }
+
+    pub fn verify(&self) -> Result<(), BallotError> {
+        DefaultScheme::verify(&self.public_key, self.digest()?.as_bytes(), &self.signature)
+            .map_err(|_| BallotError::InvalidSignature(self.voter_id.clone()))
+    }
+
+    /// Decrypt with the released election secret and check the vote inside: same voter,
+    /// proposal, and key as the ballot, validly signed, and dated no later than submission
+    pub fn open(&self, secret: &StaticSecret) -> Result<SignedVote, BallotError> {
+        let voter = || self.voter_id.clone();
+        let ephemeral_key = PublicKey::from(decode_key(&self.ephemeral_key, "ephemeral key")?);
+        let nonce: [u8; 12] = hex::decode(&self.nonce)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(BallotError::Malformed("nonce"))?;
+        let ciphertext = hex::decode(&self.ciphertext).map_err(|_| BallotError::Malformed("ciphertext"))?;
+
+        let shared = secret.diffie_hellman(&ephemeral_key);
+        let cipher = ballot_cipher(shared.as_bytes(), &ephemeral_key, &PublicKey::from(secret));
+        let aad = associated_data(&self.voter_id, &self.proposal_id);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: aad.as_bytes() })
+            .map_err(|_| BallotError::Undecryptable(voter()))?;
+
+        let invalid = |reason: &str| BallotError::InvalidVote { voter: voter(), reason: reason.to_string() };
+        let vote: SignedVote = serde_json::from_slice(&plaintext).map_err(|e| invalid(&e.to_string()))?;
+        if vote.voter_id != self.voter_id || vote.proposal_id != self.proposal_id || vote.public_key != self.public_key {
+            return Err(invalid("does not match the ballot"));
+        }
+        let message = vote.message();
+        DefaultScheme::verify(&vote.public_key, message.as_bytes(), &vote.signature)
+            .map_err(|_| invalid("signature does not verify"))?;
+        if vote.timestamp > self.submitted_at {
+            return Err(invalid("dated after the ballot was submitted"));
+        }
+        Ok(vote)
+    }
+}
+
+/// Ballots once the election key is out
+#[derive(Debug, Clone, Default)]
+pub struct OpenedBallots {
+    /// In voter order
+    pub votes: Vec<SignedVote>,
+    pub rejected: Vec<BallotError>,
+}
+
+/// Stores a proposal's encrypted ballots while `window` is open. Nothing about their
+/// contents can be tallied until the election key is released after the window closes,
+/// so no live tally exists for late voters to follow.
+pub struct BallotBox {
+    pub proposal_id: ProposalId,
+    pub window: VotingWindow,
+    election_key: ElectionPublicKey,
+    ballots: BTreeMap<VoterId, EncryptedBallot>,
+    released: Option<StaticSecret>,
+}
+
+impl BallotBox {
+    pub fn new(election_key: ElectionPublicKey, window: VotingWindow) -> Self {
+        Self {
+            proposal_id: election_key.proposal_id.clone(),
+            window,
+            election_key,
+            ballots: BTreeMap::new(),
+            released: None,
+        }
+    }
+
+    pub fn election_key(&self) -> &ElectionPublicKey {
+        &self.election_key
+    }
+
+    pub fn submit(&mut self, ballot: EncryptedBallot, now: DateTime<Utc>) -> Result<(), BallotError> {
+        if !self.window.is_open(now) {
+            return Err(BallotError::WindowClosed(self.window.closes_at()));
+        }
+        if ballot.proposal_id != self.proposal_id {
+            return Err(BallotError::WrongProposal {
+                expected: self.proposal_id.clone(),
+                found: ballot.proposal_id,
+            });
+        }
+        ballot.verify()?;
+        if self.ballots.contains_key(&ballot.voter_id) {
+            return Err(BallotError::DuplicateBallot(ballot.voter_id));
+        }
+        self.ballots.insert(ballot.voter_id.clone(), ballot);
+        Ok(())
+    }
+
+    /// Stored ciphertexts, in voter order
+    pub fn ballots(&self) -> impl Iterator<Item = &EncryptedBallot> {
+        self.ballots.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ballots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ballots.is_empty()
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.released.is_none()
+    }
+
+    /// Accept the election secret once voting has closed
+    pub fn release_key(&mut self, release: &KeyRelease, now: DateTime<Utc>) -> Result<(), BallotError> {
+        if self.window.is_open(now) {
+            return Err(BallotError::WindowOpen(self.window.closes_at()));
+        }
+        if self.released.is_some() {
+            return Err(BallotError::AlreadyReleased);
+        }
+        if release.proposal_id != self.proposal_id {
+            return Err(BallotError::WrongProposal {
+                expected: self.proposal_id.clone(),
+                found: release.proposal_id.clone(),
+            });
+        }
+        let secret = StaticSecret::from(decode_key(&release.secret, "election secret")?);
+        if PublicKey::from(&secret) != self.election_key.point()? {
+            return Err(BallotError::KeyMismatch);
+        }
+        self.released = Some(secret);
+        Ok(())
+    }
+
+    /// Decrypt every ballot; ones that fail to open are listed and never counted
+    pub fn open(&self) -> Result<OpenedBallots, BallotError> {
+        let secret = self.released.as_ref().ok_or(BallotError::Sealed)?;
+        let mut opened = OpenedBallots::default();
+        for ballot in self.ballots.values() {
+            match ballot.open(secret) {
+                Ok(vote) => opened.votes.push(vote),
+                Err(e) => opened.rejected.push(e),
+            }
+        }
+        Ok(opened)
+    }
+
+    /// Tally the opened votes as of `now`; each decays from its own timestamp
+    pub fn tally(
+        &self,
+        engine: &WeightEngine,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+    ) -> Result<Tally, BallotError> {
+        let mut tally = Tally::default();
+        for vote in self.open()?.votes {
+            tally.record(vote.choice, engine.explain(&vote, now, trust, None).final_weight.get());
+        }
+        Ok(tally)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::{DecayType, VoteChoice};
+    use crate::window::WindowType;
+    use chrono::Duration;
+
+    fn sealed(voter: &str, choice: VoteChoice, election: &ElectionPublicKey, at: DateTime<Utc>) -> EncryptedBallot {
+        let key = SignedVote::generate_keypair();
+        let vote =
+            SignedVote::new_with_choice(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, at, DecayType::Linear, choice, &key);
+        EncryptedBallot::seal(&vote, election, at, &key).unwrap()
+    }
+
+    #[test]
+    fn test_ballots_stay_sealed_until_release() {
+        let start = Utc::now();
+        let election = ElectionKey::generate("p1".parse().unwrap());
+        let mut ballot_box = BallotBox::new(election.public_key(), VotingWindow::new(start, WindowType::Custom(60), 0));
+
+        let at = start + Duration::seconds(5);
+        let alice = sealed("alice", VoteChoice::Approve, ballot_box.election_key(), at);
+        assert!(!alice.ciphertext.contains("approve"));
+        ballot_box.submit(alice.clone(), at).unwrap();
+        ballot_box.submit(sealed("bob", VoteChoice::Reject, ballot_box.election_key(), at), at).unwrap();
+        ballot_box.submit(sealed("carol", VoteChoice::Approve, ballot_box.election_key(), at), at).unwrap();
+        assert_eq!(ballot_box.submit(alice, at), Err(BallotError::DuplicateBallot("alice".parse().unwrap())));
+
+        let engine = WeightEngine::new();
+        assert_eq!(ballot_box.tally(&engine, at, None).unwrap_err(), BallotError::Sealed);
+        assert!(matches!(ballot_box.release_key(&election.release(), at), Err(BallotError::WindowOpen(_))));
+
+        let closed = start + Duration::seconds(61);
+        let late = sealed("dave", VoteChoice::Reject, ballot_box.election_key(), closed);
+        assert!(matches!(ballot_box.submit(late, closed), Err(BallotError::WindowClosed(_))));
+        let impostor = ElectionKey::generate("p1".parse().unwrap());
+        assert_eq!(ballot_box.release_key(&impostor.release(), closed), Err(BallotError::KeyMismatch));
+
+        ballot_box.release_key(&election.release(), closed).unwrap();
+        assert_eq!(ballot_box.release_key(&election.release(), closed), Err(BallotError::AlreadyReleased));
+        let tally = ballot_box.tally(&engine, closed, None).unwrap();
+        assert_eq!(tally.votes, 3);
+        assert!(tally.approve_weight > tally.reject_weight && tally.reject_weight > 0.0);
+    }
+
+    #[test]
+    fn test_tampered_ballots_are_rejected() {
+        let now = Utc::now();
+        let election = ElectionKey::generate("p1".parse().unwrap());
+        let mut ballot_box = BallotBox::new(election.public_key(), VotingWindow::new(now, WindowType::Custom(60), 0));
+
+        let mut forged = sealed("alice", VoteChoice::Approve, ballot_box.election_key(), now);
+        forged.voter_id = "mallory".parse().unwrap();
+        assert!(matches!(ballot_box.submit(forged, now), Err(BallotError::InvalidSignature(_))));
+
+        let ballot = sealed("alice", VoteChoice::Approve, ballot_box.election_key(), now);
+        let secret = StaticSecret::from(decode_key(&election.release().secret, "secret").unwrap());
+        let mut flipped = ballot.clone();
+        let mut bytes = hex::decode(&flipped.ciphertext).unwrap();
+        bytes[0] ^= 1;
+        flipped.ciphertext = hex::encode(bytes);
+        assert_eq!(flipped.open(&secret).unwrap_err(), BallotError::Undecryptable("alice".parse().unwrap()));
+        assert_eq!(ballot.open(&secret).unwrap().choice, VoteChoice::Approve);
+
+        let other = ElectionKey::generate("p1".parse().unwrap());
+        let wrong_secret = StaticSecret::from(decode_key(&other.release().secret, "secret").unwrap());
+        assert!(matches!(ballot.open(&wrong_secret), Err(BallotError::Undecryptable(_))));
+    }
+}
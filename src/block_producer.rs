@@ -0,0 +1,233 @@
+// src/block_producer.rs
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::blockchain::{BlockPayload, Blockchain, Transaction};
+
+/// When pending transactions are sealed into a block, and how large a block may be
+#[derive(Debug, Clone)]
+pub struct ProductionPolicy {
+    /// Produce once this many transactions are pending
+    pub every_n_transactions: Option<usize>,
+    /// Produce once this many seconds have passed since the last block
+    pub every_secs: Option<i64>,
+    /// Produce as soon as a finalization is pending
+    pub on_finalization: bool,
+    pub max_block_transactions: usize,
+    /// Upper bound on the encoded payload; a single oversized transaction still gets its own block
+    pub max_block_bytes: usize,
+}
+
+impl Default for ProductionPolicy {
+    fn default() -> Self {
+        Self {
+            every_n_transactions: Some(10),
+            every_secs: None,
+            on_finalization: true,
+            max_block_transactions: 100,
+            max_block_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Batches transactions into blocks instead of mining one block per write
+pub struct BlockProducer {
+    pub policy: ProductionPolicy,
+    pending: VecDeque<Transaction>,
+    last_block_at: DateTime<Utc>,
+}
+
+impl BlockProducer {
+    pub fn new(policy: ProductionPolicy, now: DateTime<Utc>) -> Self {
+        Self {
+            policy,
+            pending: VecDeque::new(),
+            last_block_at: now,
+        }
+    }
+
+    /// Queue a transaction and produce blocks if a policy now triggers.
+    /// Returns the ids of any blocks produced.
+    pub fn submit(&mut self, tx: Transaction, chain: &mut Blockchain, now: DateTime<Utc>) -> Vec<u64> {
+        self.pending.push_back(tx);
+        self.tick(chain, now)
+    }
+
+    /// Produce blocks for whichever policies have triggered by `now`
+    pub fn tick(&mut self, chain: &mut Blockchain, now: DateTime<Utc>) -> Vec<u64> {
+        let mut produced = Vec::new();
+        while self.should_produce(now) {
+            produced.push(self.produce_block(chain, now));
+        }
+        produced
+    }
+
+    /// Seal everything pending regardless of policy
+    pub fn flush(&mut self, chain: &mut Blockchain, now: DateTime<Utc>) -> Vec<u64> {
+        let mut produced = Vec::new();
+        while !self.pending.is_empty() {
+            produced.push(self.produce_block(chain, now));
+        }
+        produced
+    }
+
+    pub fn should_produce(&self, now: DateTime<Utc>) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+        let count_due = self
+            .policy
+            .every_n_transactions
+            .is_some_and(|n| self.pending.len() >= n);
+        let time_due = self
+            .policy
+            .every_secs
+            .is_some_and(|secs| now - self.last_block_at >= Duration::seconds(secs));
+        let finalization_due = self.policy.on_finalization
+            && self
+                .pending
+                .iter()
+                .any(|tx| matches!(tx, Transaction::Finalization(_)));
+        count_due || time_due || finalization_due
+    }
+
+    fn produce_block(&mut self, chain: &mut Blockchain, now: DateTime<Utc>) -> u64 {
+        let mut batch: Vec<Transaction> = Vec::new();
+        while let Some(tx) = self.pending.front() {
+            if batch.len() >= self.policy.max_block_transactions {
+                break;
+            }
+            batch.push(tx.clone());
+            if batch.len() > 1 && BlockPayload::encode(&batch).len() > self.policy.max_block_bytes {
+                batch.pop();
+                break;
+            }
+            self.pending.pop_front();
+        }
+
+        chain.add_transactions(&batch);
+        self.last_block_at = now;
+        chain.blocks.last().expect("block was just added").id
+    }
+
+    /// Transactions waiting for the next block, oldest first
+    pub fn pending(&self) -> impl Iterator<Item = &Transaction> {
+        self.pending.iter()
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn pending_for<'a>(&'a self, proposal_id: &'a str) -> impl Iterator<Item = &'a Transaction> {
+        self.pending.iter().filter(move |tx| tx.proposal_id() == proposal_id)
+    }
+
+    pub fn pending_bytes(&self) -> usize {
+        BlockPayload::encode(&self.pending.iter().cloned().collect::<Vec<_>>()).len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::FinalizationEntry;
+    use crate::blockchain::VoteEntry;
+    use crate::vote::{DecayType, SignedVote};
+
+    fn vote(voter: &str, proposal: &str) -> Transaction {
+        let key = SignedVote::generate_keypair();
+        let vote = SignedVote::new(voter.parse().unwrap(), proposal.parse().unwrap(), 1.0, Utc::now(), DecayType::Linear, &key);
+        Transaction::Vote(VoteEntry::from(&vote))
+    }
+
+    fn finalization(proposal: &str) -> Transaction {
+        Transaction::Finalization(FinalizationEntry {
+            proposal_id: proposal.to_string(),
+            passed: true,
+            weight: 1.0,
+            threshold: 0.5,
+            finalized_at: Utc::now(),
+        })
+    }
+
+    fn policy() -> ProductionPolicy {
+        ProductionPolicy {
+            every_n_transactions: Some(3),
+            every_secs: None,
+            on_finalization: false,
+            max_block_transactions: 100,
+            max_block_bytes: 64 * 1024,
+        }
+    }
+
+    #[test]
+    fn test_every_n_transactions() {
+        let now = Utc::now();
+        let mut chain = Blockchain::new();
+        let mut producer = BlockProducer::new(policy(), now);
+
+        assert!(producer.submit(vote("a", "p1"), &mut chain, now).is_empty());
+        assert!(producer.submit(vote("b", "p1"), &mut chain, now).is_empty());
+        assert_eq!(producer.pending_for("p1").count(), 2);
+
+        assert_eq!(producer.submit(vote("c", "p1"), &mut chain, now), vec![1]);
+        assert_eq!(producer.pending_len(), 0);
+        assert_eq!(BlockPayload::decode(&chain.blocks[1].data).transactions().len(), 3);
+    }
+
+    #[test]
+    fn test_interval_and_finalization_triggers() {
+        let now = Utc::now();
+        let mut chain = Blockchain::new();
+        let mut producer = BlockProducer::new(
+            ProductionPolicy {
+                every_n_transactions: None,
+                every_secs: Some(30),
+                on_finalization: true,
+                ..policy()
+            },
+            now,
+        );
+
+        assert!(producer.submit(vote("a", "p1"), &mut chain, now).is_empty());
+        assert_eq!(producer.tick(&mut chain, now + Duration::seconds(31)), vec![1]);
+
+        let later = now + Duration::seconds(35);
+        assert_eq!(producer.submit(finalization("p1"), &mut chain, later), vec![2]);
+        assert!(chain.is_valid());
+    }
+
+    #[test]
+    fn test_block_size_bounds() {
+        let now = Utc::now();
+        let mut chain = Blockchain::new();
+        let mut producer = BlockProducer::new(
+            ProductionPolicy {
+                every_n_transactions: None,
+                max_block_transactions: 2,
+                ..policy()
+            },
+            now,
+        );
+        for voter in ["a", "b", "c", "d", "e"] {
+            producer.submit(vote(voter, "p1"), &mut chain, now);
+        }
+        assert!(producer.pending_bytes() > 0);
+
+        assert_eq!(producer.flush(&mut chain, now), vec![1, 2, 3]);
+        let sizes: Vec<usize> = chain.blocks[1..]
+            .iter()
+            .map(|b| BlockPayload::decode(&b.data).transactions().len())
+            .collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+
+        producer.policy.max_block_bytes = 1;
+        producer.submit(vote("f", "p1"), &mut chain, now);
+        producer.submit(vote("g", "p1"), &mut chain, now);
+        assert_eq!(producer.flush(&mut chain, now).len(), 2);
+    }
+}
@@ -1,10 +1,194 @@
 use chrono::prelude::*;
-use sha2::{Sha256, Digest};
 use hex::encode;
-use rand::Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
 
-const DIFFICULTY: usize = 2; // For Proof of Work
+use crate::decay::DecayParams;
+use crate::hashing;
+use crate::progress::{Cancelled, CancellationToken, Progress};
+use crate::stake::StakeAttestation;
+use crate::vote::{DecayType, SignedVote, VoteChoice};
 
+pub const DIFFICULTY: usize = 2; // Default leading zeros for Proof of Work
+
+/// Chain-friendly copy of a `SignedVote`, with keys and signature hex-encoded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoteEntry {
+    pub voter_id: String,
+    pub proposal_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub original_weight: f64,
+    pub decay_model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decay_params: Option<Box<DecayParams>>,
+    /// Omitted for approvals, keeping earlier entries byte-identical
+    #[serde(default, skip_serializing_if = "VoteChoice::is_approve")]
+    pub choice: VoteChoice,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub version: u64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub nonce: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stake: Option<Box<StakeAttestation>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Omitted for version 1 payloads, keeping earlier entries byte-identical
+    #[serde(default = "crate::payload::legacy_payload_version", skip_serializing_if = "crate::payload::is_legacy_payload")]
+    pub payload_version: u32,
+    /// When the admitting node received the vote; not covered by the voter's signature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<DateTime<Utc>>,
+    pub signature: String,
+    pub public_key: String,
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+impl From<&SignedVote> for VoteEntry {
+    fn from(vote: &SignedVote) -> Self {
+        VoteEntry {
+            voter_id: vote.voter_id.to_string(),
+            proposal_id: vote.proposal_id.to_string(),
+            timestamp: vote.timestamp,
+            original_weight: vote.original_weight,
+            decay_model: format!("{:?}", vote.decay_model),
+            decay_params: vote.decay_params.clone().map(Box::new),
+            choice: vote.choice,
+            version: vote.version,
+            nonce: vote.nonce,
+            metadata: vote.metadata.clone(),
+            stake: vote.stake.clone().map(Box::new),
+            expires_at: vote.expires_at,
+            payload_version: vote.payload_version,
+            received_at: None,
+            signature: encode(vote.signature.to_bytes()),
+            public_key: encode(vote.public_key.as_bytes()),
+        }
+    }
+}
+
+impl VoteEntry {
+    /// Rebuild the signed vote, or `None` if the entry is malformed
+    pub fn to_signed_vote(&self) -> Option<SignedVote> {
+        let decay_model = match self.decay_model.as_str() {
+            "Linear" => DecayType::Linear,
+            "Exponential" => DecayType::Exponential,
+            "Stepped" => DecayType::Stepped,
+            _ => return None,
+        };
+        let signature: [u8; 64] = hex::decode(&self.signature).ok()?.try_into().ok()?;
+        let public_key: [u8; 32] = hex::decode(&self.public_key).ok()?.try_into().ok()?;
+        Some(SignedVote {
+            voter_id: self.voter_id.parse().ok()?,
+            proposal_id: self.proposal_id.parse().ok()?,
+            timestamp: self.timestamp,
+            original_weight: self.original_weight,
+            decay_model,
+            decay_params: self.decay_params.as_deref().cloned(),
+            choice: self.choice,
+            version: self.version,
+            nonce: self.nonce,
+            metadata: self.metadata.clone(),
+            stake: self.stake.as_deref().cloned(),
+            expires_at: self.expires_at,
+            payload_version: self.payload_version,
+            signature: ed25519_dalek::Signature::from_bytes(&signature),
+            public_key: ed25519_dalek::VerifyingKey::from_bytes(&public_key).ok()?,
+        })
+    }
+}
+
+/// Recorded outcome of a proposal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinalizationEntry {
+    pub proposal_id: String,
+    pub passed: bool,
+    pub weight: f64,
+    pub threshold: f64,
+    pub finalized_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Transaction {
+    Vote(VoteEntry),
+    Finalization(FinalizationEntry),
+}
+
+impl Transaction {
+    pub fn proposal_id(&self) -> &str {
+        match self {
+            Transaction::Vote(v) => &v.proposal_id,
+            Transaction::Finalization(f) => &f.proposal_id,
+        }
+    }
+}
+
+/// Decoded contents of `Block::data`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "format", content = "content", rename_all = "snake_case")]
+pub enum BlockPayload {
+    Transactions(Vec<Transaction>),
+    /// Free-form data written with `add_block`
+    Raw(String),
+}
+
+impl BlockPayload {
+    pub fn encode(transactions: &[Transaction]) -> String {
+        serde_json::to_string(transactions).expect("transactions serialize to JSON")
+    }
+
+    pub fn decode(data: &str) -> Self {
+        match serde_json::from_str(data) {
+            Ok(transactions) => BlockPayload::Transactions(transactions),
+            Err(_) => BlockPayload::Raw(data.to_string()),
+        }
+    }
+
+    pub fn transactions(&self) -> &[Transaction] {
+        match self {
+            BlockPayload::Transactions(txs) => txs,
+            BlockPayload::Raw(_) => &[],
+        }
+    }
+
+    /// Merkle root over the payload's leaves (each transaction, or the raw data)
+    pub fn merkle_root(&self) -> String {
+        let leaves: Vec<Vec<u8>> = match self {
+            BlockPayload::Transactions(txs) => txs
+                .iter()
+                .map(|tx| hashing::transaction_leaf(&serde_json::to_vec(tx).expect("transaction serializes")).to_vec())
+                .collect(),
+            BlockPayload::Raw(data) => vec![hashing::raw_payload_leaf(data).to_vec()],
+        };
+        merkle_root(leaves)
+    }
+}
+
+/// Pairwise Merkle root; an odd node is paired with itself
+pub fn merkle_root(mut level: Vec<Vec<u8>>) -> String {
+    if level.is_empty() {
+        return encode(hashing::merkle_empty());
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| hashing::merkle_node(&pair[0], pair.get(1).unwrap_or(&pair[0])).to_vec())
+            .collect();
+    }
+    encode(&level[0])
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Block {
     pub id: u64,
     pub hash: String,
@@ -16,52 +200,166 @@ pub struct Block {
 
 impl Block {
     pub fn new(id: u64, previous_hash: String, data: String) -> Self {
-        let timestamp = Utc::now().timestamp();
-        let mut block = Block {
+        let mut block = Self::unmined(id, previous_hash, data);
+        block.mine_block();
+        block
+    }
+
+    /// Block with no proof of work yet; `mine_block` or `mine_with` must run before it is valid
+    pub fn unmined(id: u64, previous_hash: String, data: String) -> Self {
+        Block {
             id,
             hash: String::new(),
             previous_hash,
-            timestamp,
+            timestamp: Utc::now().timestamp(),
             data,
             nonce: 0,
-        };
-        block.mine_block();
-        block
+        }
     }
 
     pub fn calculate_hash(&self) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(self.id.to_string());
-        hasher.update(&self.previous_hash);
-        hasher.update(self.timestamp.to_string());
-        hasher.update(&self.data);
-        hasher.update(self.nonce.to_string());
-        encode(hasher.finalize())
+        hashing::block_hash(self.id, &self.previous_hash, self.timestamp, &self.data, self.nonce)
     }
 
     pub fn mine_block(&mut self) {
-        let target = "0".repeat(DIFFICULTY);
+        self.mine_block_at(DIFFICULTY);
+    }
+
+    /// Mine to a specific number of leading zeros
+    pub fn mine_block_at(&mut self, difficulty: usize) {
+        // A fresh token is never cancelled, so mining always completes
+        let _ = self.mine_with(difficulty, &CancellationToken::new());
+    }
+
+    /// Search for a valid nonce, stopping early if `cancel` fires
+    pub fn mine_with(&mut self, difficulty: usize, cancel: &CancellationToken) -> Result<(), Cancelled> {
+        let target = "0".repeat(difficulty);
+        let progress = Progress::spinner(format!("Mining block {}", self.id));
         while !self.hash.starts_with(&target) {
+            if cancel.is_cancelled() {
+                progress.abandon(format!("Mining block {} cancelled", self.id));
+                return Err(Cancelled);
+            }
             self.nonce += 1;
             self.hash = self.calculate_hash();
+            if self.nonce.is_multiple_of(1024) {
+                progress.set_position(self.nonce);
+            }
         }
+        progress.finish();
         println!("Block {} mined: {}", self.id, self.hash);
+        Ok(())
+    }
+
+    /// Nonce search split across `threads` workers; worker `i` tries nonces
+    /// `i + 1, i + 1 + threads, ...`. The first worker to hit the target raises a
+    /// shared flag that stops the others. Cancelling `cancel` (e.g. because a
+    /// competing block for this height arrived) stops all workers and leaves the
+    /// block unchanged.
+    pub fn mine_parallel(
+        &mut self,
+        difficulty: usize,
+        threads: usize,
+        cancel: &CancellationToken,
+    ) -> Result<(), Cancelled> {
+        let threads = threads.max(1) as u64;
+        let target = "0".repeat(difficulty);
+        let found = AtomicBool::new(false);
+        let winner = Mutex::new(None::<(u64, String)>);
+        let tried = AtomicU64::new(0);
+        let progress = Progress::spinner(format!("Mining block {} on {} threads", self.id, threads));
+
+        thread::scope(|scope| {
+            for worker in 0..threads {
+                let mut candidate = self.clone();
+                let (found, winner, tried, target, progress) = (&found, &winner, &tried, &target, &progress);
+                scope.spawn(move || {
+                    candidate.nonce = worker + 1;
+                    loop {
+                        if found.load(Ordering::Relaxed) || cancel.is_cancelled() {
+                            return;
+                        }
+                        candidate.hash = candidate.calculate_hash();
+                        if candidate.hash.starts_with(target.as_str()) {
+                            if !found.swap(true, Ordering::SeqCst) {
+                                *winner.lock().expect("winner lock poisoned") =
+                                    Some((candidate.nonce, candidate.hash.clone()));
+                            }
+                            return;
+                        }
+                        candidate.nonce += threads;
+                        let total = tried.fetch_add(1, Ordering::Relaxed) + 1;
+                        if worker == 0 && total.is_multiple_of(1024) {
+                            progress.set_position(total);
+                        }
+                    }
+                });
+            }
+        });
+
+        match winner.into_inner().expect("winner lock poisoned") {
+            Some((nonce, hash)) => {
+                self.nonce = nonce;
+                self.hash = hash;
+                progress.finish();
+                println!("Block {} mined: {}", self.id, self.hash);
+                Ok(())
+            }
+            None => {
+                progress.abandon(format!("Mining block {} cancelled", self.id));
+                Err(Cancelled)
+            }
+        }
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Blockchain {
     pub blocks: Vec<Block>,
+    #[serde(default = "default_difficulty")]
+    pub difficulty: usize,
+    /// Worker threads used for nonce search; node-local, so never persisted
+    #[serde(skip, default = "default_mining_threads")]
+    pub mining_threads: usize,
+}
+
+fn default_difficulty() -> usize {
+    DIFFICULTY
+}
+
+fn default_mining_threads() -> usize {
+    1
+}
+
+impl Default for Blockchain {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Blockchain {
     pub fn new() -> Self {
         let mut blockchain = Blockchain {
             blocks: vec![],
+            difficulty: DIFFICULTY,
+            mining_threads: default_mining_threads(),
         };
         blockchain.create_genesis_block();
         blockchain
     }
 
+    /// Chain with its own genesis data and difficulty. The genesis hash commits to
+    /// `genesis_data`, so blocks from chains with different genesis data never link up.
+    pub fn with_genesis(genesis_data: String, difficulty: usize) -> Self {
+        let mut genesis_block = Block::unmined(0, String::new(), genesis_data);
+        genesis_block.hash = genesis_block.calculate_hash();
+        Blockchain {
+            blocks: vec![genesis_block],
+            difficulty,
+            mining_threads: default_mining_threads(),
+        }
+    }
+
     fn create_genesis_block(&mut self) {
         let genesis_block = Block {
             id: 0,
@@ -76,31 +374,166 @@ impl Blockchain {
 
     pub fn add_block(&mut self, data: String) {
         let previous_block = self.blocks.last().expect("Blockchain should have at least one block");
-        let new_block = Block::new(previous_block.id + 1, previous_block.hash.clone(), data);
+        let mut new_block = Block::unmined(previous_block.id + 1, previous_block.hash.clone(), data);
+        if self.mining_threads > 1 {
+            // A fresh token is never cancelled, so mining always completes
+            let _ = new_block.mine_parallel(self.difficulty, self.mining_threads, &CancellationToken::new());
+        } else {
+            new_block.mine_block_at(self.difficulty);
+        }
         self.blocks.push(new_block);
     }
 
-    pub fn is_valid(&self) -> bool {
-        for i in 1..self.blocks.len() {
-            let current_block = &self.blocks[i];
-            let previous_block = &self.blocks[i - 1];
+    /// Mine a block whose payload is the given transactions
+    pub fn add_transactions(&mut self, transactions: &[Transaction]) {
+        self.add_block(BlockPayload::encode(transactions));
+    }
 
-            if current_block.hash != current_block.calculate_hash() {
-                println!("Invalid hash for block {}", current_block.id);
-                return false;
-            }
+    /// Mine and append a block; on cancellation the chain is left unchanged
+    pub fn try_add_block(&mut self, data: String, cancel: &CancellationToken) -> Result<(), Cancelled> {
+        let previous_block = self.blocks.last().expect("Blockchain should have at least one block");
+        let mut block = Block::unmined(previous_block.id + 1, previous_block.hash.clone(), data);
+        if self.mining_threads > 1 {
+            block.mine_parallel(self.difficulty, self.mining_threads, cancel)?;
+        } else {
+            block.mine_with(self.difficulty, cancel)?;
+        }
+        self.blocks.push(block);
+        Ok(())
+    }
 
-            if current_block.previous_hash != previous_block.hash {
-                println!("Invalid previous hash for block {}", current_block.id);
-                return false;
-            }
+    /// Append a block mined elsewhere, e.g. by a competing producer, if it extends the tip.
+    /// Callers mining the same height should cancel their own search when this succeeds.
+    pub fn accept_block(&mut self, block: Block) -> Result<(), String> {
+        self.blocks.push(block);
+        let index = self.blocks.len() - 1;
+        if let Err(reason) = self.check_block(index) {
+            self.blocks.pop();
+            return Err(reason);
+        }
+        Ok(())
+    }
 
-            let target = "0".repeat(DIFFICULTY);
-            if !current_block.hash.starts_with(&target) {
-                println!("Block {} does not meet difficulty target", current_block.id);
+    pub fn is_valid(&self) -> bool {
+        for i in 1..self.blocks.len() {
+            if let Err(reason) = self.check_block(i) {
+                println!("{}", reason);
                 return false;
             }
         }
         true
     }
-}
\ No newline at end of file
+
+    /// Validate the block at `index` against its own hash, its predecessor, and the difficulty
+    pub fn check_block(&self, index: usize) -> Result<(), String> {
+        let current_block = &self.blocks[index];
+        if index == 0 {
+            return Ok(()); // Genesis block is trusted by construction
+        }
+        let previous_block = &self.blocks[index - 1];
+
+        if current_block.hash != current_block.calculate_hash() {
+            return Err(format!("Invalid hash for block {}", current_block.id));
+        }
+
+        if current_block.previous_hash != previous_block.hash {
+            return Err(format!("Invalid previous hash for block {}", current_block.id));
+        }
+
+        let target = "0".repeat(self.difficulty);
+        if !current_block.hash.starts_with(&target) {
+            return Err(format!("Block {} does not meet difficulty target", current_block.id));
+        }
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let raw = std::fs::read_to_string(path)?;
+        serde_json::from_str(&raw).map_err(std::io::Error::other)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_block_keeps_chain_valid() {
+        let mut chain = Blockchain::new();
+        chain.try_add_block("payload".to_string(), &CancellationToken::new()).unwrap();
+        assert_eq!(chain.blocks.len(), 2);
+        assert!(chain.is_valid());
+    }
+
+    #[test]
+    fn test_transaction_payload_round_trip() {
+        let key = SignedVote::generate_keypair();
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, Utc::now(), DecayType::Stepped, &key);
+        let entry = VoteEntry::from(&vote);
+        let data = BlockPayload::encode(&[Transaction::Vote(entry.clone())]);
+
+        let payload = BlockPayload::decode(&data);
+        assert_eq!(payload.transactions(), &[Transaction::Vote(entry.clone())]);
+        assert!(entry.to_signed_vote().unwrap().verify(300).is_ok());
+        assert_eq!(BlockPayload::decode("Genesis Block"), BlockPayload::Raw("Genesis Block".to_string()));
+    }
+
+    #[test]
+    fn test_merkle_root() {
+        let a = hashing::raw_payload_leaf("a").to_vec();
+        let b = hashing::raw_payload_leaf("b").to_vec();
+        assert_eq!(merkle_root(vec![a.clone()]), encode(&a));
+        assert_ne!(merkle_root(vec![a.clone(), b.clone()]), merkle_root(vec![b, a]));
+    }
+
+    #[test]
+    fn test_parallel_mining_produces_valid_blocks() {
+        let mut chain = Blockchain::new();
+        chain.mining_threads = 4;
+        chain.add_block("parallel".to_string());
+        chain.try_add_block("parallel again".to_string(), &CancellationToken::new()).unwrap();
+        assert_eq!(chain.blocks.len(), 3);
+        assert!(chain.is_valid());
+    }
+
+    #[test]
+    fn test_competing_block_cancels_parallel_search() {
+        let mut chain = Blockchain::new();
+        let tip = chain.blocks.last().unwrap().hash.clone();
+
+        let mut competitor = Block::unmined(1, tip.clone(), "competitor".to_string());
+        competitor.mine_block();
+        chain.accept_block(competitor).unwrap();
+
+        // A search that cannot realistically finish is stopped by the cancellation
+        let cancel = CancellationToken::new();
+        let mut ours = Block::unmined(1, tip, "ours".to_string());
+        let handle = {
+            let cancel = cancel.clone();
+            thread::spawn(move || ours.mine_parallel(64, 2, &cancel))
+        };
+        cancel.cancel();
+        assert_eq!(handle.join().unwrap(), Err(Cancelled));
+
+        let stale = Block::unmined(1, "not-the-tip".to_string(), "stale".to_string());
+        assert!(chain.accept_block(stale).is_err());
+        assert_eq!(chain.blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_cancelled_mining_leaves_chain_unchanged() {
+        let mut chain = Blockchain::new();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        assert_eq!(chain.try_add_block("payload".to_string(), &cancel), Err(Cancelled));
+        assert_eq!(chain.blocks.len(), 1);
+        assert!(chain.is_valid());
+    }
+}
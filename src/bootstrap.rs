@@ -0,0 +1,217 @@
+// src/bootstrap.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::VerifyingKey;
+use thiserror::Error;
+
+use crate::ids::VoterId;
+use crate::reputation::{ConflictRule, ReputationBook, ReputationError, ReputationExport};
+use crate::trust::TrustSource;
+use crate::units::Seconds;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BootstrapError {
+    #[error("Invalid stake {stake} for {validator_id}")]
+    InvalidStake { validator_id: String, stake: f64 },
+    #[error("Invalid bonus range {min}..={max}")]
+    InvalidRange { min: f64, max: f64 },
+    #[error("Transition duration must be positive")]
+    InvalidSchedule,
+    #[error("Attestation rejected: {0}")]
+    Attestation(#[from] ReputationError),
+}
+
+/// Where a new deployment's first trust scores come from
+#[derive(Debug, Clone)]
+pub enum BootstrapStrategy {
+    /// Every validator starts with the same bonus
+    Uniform(f64),
+    /// Bonus rises linearly with stake, from `min_bonus` at zero to `max_bonus` at the
+    /// largest stake; validators without a stake get `min_bonus`
+    StakeProportional {
+        stakes: BTreeMap<String, f64>,
+        min_bonus: f64,
+        max_bonus: f64,
+    },
+    /// Scores attested by another deployment in a signed reputation export
+    Attested {
+        export: ReputationExport,
+        trusted: Vec<VerifyingKey>,
+    },
+}
+
+impl BootstrapStrategy {
+    /// Starting bonus for each of `validators`
+    pub fn scores(&self, validators: &[&str]) -> Result<BTreeMap<String, f64>, BootstrapError> {
+        let scores = match self {
+            BootstrapStrategy::Uniform(bonus) => validators.iter().map(|v| (v.to_string(), *bonus)).collect(),
+            BootstrapStrategy::StakeProportional { stakes, min_bonus, max_bonus } => {
+                if !(min_bonus.is_finite() && max_bonus.is_finite() && min_bonus <= max_bonus) {
+                    return Err(BootstrapError::InvalidRange { min: *min_bonus, max: *max_bonus });
+                }
+                if let Some((id, stake)) = stakes.iter().find(|(_, s)| !(s.is_finite() && **s >= 0.0)) {
+                    return Err(BootstrapError::InvalidStake { validator_id: id.clone(), stake: *stake });
+                }
+                let largest = stakes.values().cloned().fold(0.0, f64::max);
+                validators
+                    .iter()
+                    .map(|v| {
+                        let stake = stakes.get(*v).copied().unwrap_or(0.0);
+                        let share = if largest > 0.0 { stake / largest } else { 0.0 };
+                        (v.to_string(), min_bonus + (max_bonus - min_bonus) * share)
+                    })
+                    .collect()
+            }
+            BootstrapStrategy::Attested { export, trusted } => {
+                let mut book = ReputationBook::new("bootstrap");
+                book.import(export, trusted, ConflictRule::PreferImported, export.exported_at)?;
+                validators.iter().map(|v| (v.to_string(), book.score(v).map_or(1.0, |s| s.bonus))).collect()
+            }
+        };
+        Ok(scores)
+    }
+}
+
+/// Linear hand-over from bootstrap scores to behavior-derived ones over `duration` from `start`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransitionSchedule {
+    pub start: DateTime<Utc>,
+    pub duration: Seconds,
+}
+
+impl TransitionSchedule {
+    pub fn new(start: DateTime<Utc>, duration: Seconds) -> Result<Self, BootstrapError> {
+        if duration.as_i64() <= 0 {
+            return Err(BootstrapError::InvalidSchedule);
+        }
+        Ok(Self { start, duration })
+    }
+
+    /// Share of the bonus taken from behavior at `now`, in [0, 1]
+    pub fn behavior_share(&self, now: DateTime<Utc>) -> f64 {
+        (Seconds::between(self.start, now).as_f64() / self.duration.as_f64()).clamp(0.0, 1.0)
+    }
+
+    pub fn is_complete(&self, now: DateTime<Utc>) -> bool {
+        self.behavior_share(now) >= 1.0
+    }
+}
+
+/// Trust for a new deployment: bootstrap scores blended into `behavior` on a schedule.
+/// As a `TrustSource` it answers as of the time last passed to `advance_to`.
+pub struct BootstrappedTrust {
+    bootstrap: BTreeMap<String, f64>,
+    behavior: Box<dyn TrustSource>,
+    pub schedule: TransitionSchedule,
+    as_of: DateTime<Utc>,
+}
+
+impl BootstrappedTrust {
+    pub fn new(
+        strategy: &BootstrapStrategy,
+        validators: &[&str],
+        behavior: Box<dyn TrustSource>,
+        schedule: TransitionSchedule,
+    ) -> Result<Self, BootstrapError> {
+        Ok(Self {
+            bootstrap: strategy.scores(validators)?,
+            behavior,
+            as_of: schedule.start,
+            schedule,
+        })
+    }
+
+    pub fn bootstrap_bonus(&self, validator_id: &str) -> f64 {
+        self.bootstrap.get(validator_id).copied().unwrap_or(1.0)
+    }
+
+    /// Blended bonus at `now`; unknown validators bootstrap at 1.0
+    pub fn bonus_at(&self, validator_id: &VoterId, now: DateTime<Utc>) -> f64 {
+        let share = self.schedule.behavior_share(now);
+        (1.0 - share) * self.bootstrap_bonus(validator_id) + share * self.behavior.get_bonus(validator_id)
+    }
+
+    /// Move the blend to `now`; returns true when this call completes the transition,
+    /// after which scores come from behavior alone
+    pub fn advance_to(&mut self, now: DateTime<Utc>) -> bool {
+        let completed = self.schedule.is_complete(now) && !self.schedule.is_complete(self.as_of);
+        self.as_of = now;
+        completed
+    }
+
+    pub fn as_of(&self) -> DateTime<Utc> {
+        self.as_of
+    }
+}
+
+impl TrustSource for BootstrappedTrust {
+    fn get_bonus(&self, validator_id: &VoterId) -> f64 {
+        self.bonus_at(validator_id, self.as_of)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trust::TrustEngine;
+    use crate::vote::SignedVote;
+    use chrono::Duration;
+
+    const VALIDATORS: [&str; 3] = ["validator_001", "validator_002", "validator_003"];
+
+    #[test]
+    fn test_bootstrap_strategies() {
+        let uniform = BootstrapStrategy::Uniform(1.05).scores(&VALIDATORS).unwrap();
+        assert!(uniform.values().all(|b| *b == 1.05));
+
+        let stakes: BTreeMap<String, f64> =
+            [("validator_001".to_string(), 100.0), ("validator_002".to_string(), 50.0)].into_iter().collect();
+        let staked = BootstrapStrategy::StakeProportional { stakes, min_bonus: 1.0, max_bonus: 1.4 };
+        let scores = staked.scores(&VALIDATORS).unwrap();
+        assert_eq!(scores.values().copied().collect::<Vec<_>>(), vec![1.4, 1.2, 1.0]);
+        let negative: BTreeMap<String, f64> = [("validator_001".to_string(), -1.0)].into_iter().collect();
+        assert!(matches!(
+            BootstrapStrategy::StakeProportional { stakes: negative, min_bonus: 1.0, max_bonus: 1.4 }.scores(&VALIDATORS),
+            Err(BootstrapError::InvalidStake { .. })
+        ));
+
+        let now = Utc::now();
+        let mut origin = ReputationBook::new("mainnet");
+        origin.set("validator_002", 1.3, now).unwrap();
+        let key = SignedVote::generate_keypair();
+        let export = origin.export(now, &key);
+        let attested = BootstrapStrategy::Attested { export: export.clone(), trusted: vec![key.verifying_key()] };
+        assert_eq!(attested.scores(&VALIDATORS).unwrap()["validator_002"], 1.3);
+        let untrusted = BootstrapStrategy::Attested { export, trusted: Vec::new() };
+        assert!(matches!(untrusted.scores(&VALIDATORS), Err(BootstrapError::Attestation(_))));
+    }
+
+    #[test]
+    fn test_transition_hands_over_to_behavior() {
+        let start = Utc::now();
+        let schedule = TransitionSchedule::new(start, Seconds::new(100)).unwrap();
+        assert!(TransitionSchedule::new(start, Seconds::ZERO).is_err());
+
+        let mut trust = BootstrappedTrust::new(
+            &BootstrapStrategy::Uniform(1.4),
+            &VALIDATORS,
+            Box::new(TrustEngine::new()),
+            schedule,
+        )
+        .unwrap();
+        assert_eq!(trust.get_bonus(&"validator_003".parse().unwrap()), 1.4);
+
+        assert!(!trust.advance_to(start + Duration::seconds(50)));
+        assert!((trust.get_bonus(&"validator_001".parse().unwrap()) - 1.3).abs() < 1e-9);
+        assert!((trust.get_bonus(&"validator_003".parse().unwrap()) - 1.2).abs() < 1e-9);
+
+        assert!(trust.advance_to(start + Duration::seconds(150)));
+        assert!(trust.schedule.is_complete(trust.as_of()));
+        assert!(!trust.advance_to(start + Duration::seconds(200)));
+        assert_eq!(trust.get_bonus(&"validator_001".parse().unwrap()), 1.2);
+        assert_eq!(trust.get_bonus(&"validator_003".parse().unwrap()), 1.0);
+    }
+}
@@ -0,0 +1,213 @@
+// src/builder.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, SigningKey};
+use thiserror::Error;
+
+use crate::decay::DecayParams;
+use crate::ids::{IdError, ProposalId, VoterId};
+use crate::payload::PAYLOAD_VERSION;
+use crate::scheme::{DefaultScheme, SignatureScheme};
+use crate::stake::StakeAttestation;
+use crate::vote::{DecayType, MAX_METADATA_BYTES, MAX_METADATA_ENTRIES, SignedVote, VoteChoice};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BuildError {
+    #[error("Vote has no voter")]
+    MissingVoter,
+    #[error("Vote has no proposal")]
+    MissingProposal,
+    #[error(transparent)]
+    InvalidId(#[from] IdError),
+    #[error("Vote weight {0} is not a positive number")]
+    InvalidWeight(f64),
+    #[error("Metadata has {entries} entries / {bytes} bytes, over the limit of {} / {}", MAX_METADATA_ENTRIES, MAX_METADATA_BYTES)]
+    MetadataTooLarge { entries: usize, bytes: usize },
+    #[error("Vote expires at {expires_at}, not after it was cast at {timestamp}")]
+    ExpiresBeforeCast { timestamp: DateTime<Utc>, expires_at: DateTime<Utc> },
+}
+
+/// Fluent construction of a `SignedVote`. Unset fields default to weight 1.0,
+/// exponential decay, an approval, and the current time; `sign` checks everything
+/// before signing.
+#[derive(Debug, Clone, Default)]
+pub struct SignedVoteBuilder {
+    voter: Option<String>,
+    proposal: Option<String>,
+    weight: Option<f64>,
+    decay: Option<DecayType>,
+    decay_params: Option<DecayParams>,
+    choice: VoteChoice,
+    timestamp: Option<DateTime<Utc>>,
+    nonce: u64,
+    metadata: BTreeMap<String, String>,
+    stake: Option<StakeAttestation>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl SignedVote {
+    pub fn builder() -> SignedVoteBuilder {
+        SignedVoteBuilder::default()
+    }
+}
+
+impl SignedVoteBuilder {
+    pub fn voter(mut self, voter: impl Into<String>) -> Self {
+        self.voter = Some(voter.into());
+        self
+    }
+
+    pub fn proposal(mut self, proposal: impl Into<String>) -> Self {
+        self.proposal = Some(proposal.into());
+        self
+    }
+
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn decay(mut self, decay: DecayType) -> Self {
+        self.decay = Some(decay);
+        self
+    }
+
+    /// Decay under `params` rather than the model's standard ones
+    pub fn decay_params(mut self, params: DecayParams) -> Self {
+        self.decay_params = Some(params);
+        self
+    }
+
+    pub fn choice(mut self, choice: VoteChoice) -> Self {
+        self.choice = choice;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Sign with a fresh random nonce, so a replay can be told from a new vote
+    pub fn random_nonce(self) -> Self {
+        self.nonce(SignedVote::generate_nonce())
+    }
+
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn stake(mut self, stake: StakeAttestation) -> Self {
+        self.stake = Some(stake);
+        self
+    }
+
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    pub fn sign(self, signing_key: &SigningKey) -> Result<SignedVote, BuildError> {
+        let voter_id = VoterId::new(self.voter.ok_or(BuildError::MissingVoter)?)?;
+        let proposal_id = ProposalId::new(self.proposal.ok_or(BuildError::MissingProposal)?)?;
+        let original_weight = self.weight.unwrap_or(1.0);
+        if !(original_weight.is_finite() && original_weight > 0.0) {
+            return Err(BuildError::InvalidWeight(original_weight));
+        }
+        let entries = self.metadata.len();
+        let bytes: usize = self.metadata.iter().map(|(k, v)| k.len() + v.len()).sum();
+        if entries > MAX_METADATA_ENTRIES || bytes > MAX_METADATA_BYTES {
+            return Err(BuildError::MetadataTooLarge { entries, bytes });
+        }
+        let timestamp = self.timestamp.unwrap_or_else(Utc::now);
+        if let Some(expires_at) = self.expires_at
+            && expires_at <= timestamp
+        {
+            return Err(BuildError::ExpiresBeforeCast { timestamp, expires_at });
+        }
+
+        let mut vote = SignedVote {
+            voter_id,
+            proposal_id,
+            timestamp,
+            original_weight,
+            decay_model: self.decay.unwrap_or(DecayType::Exponential),
+            decay_params: self.decay_params,
+            choice: self.choice,
+            version: 0,
+            nonce: self.nonce,
+            metadata: self.metadata,
+            stake: self.stake,
+            expires_at: self.expires_at,
+            payload_version: PAYLOAD_VERSION,
+            signature: Signature::from_bytes(&[0; 64]),
+            public_key: DefaultScheme::public_key(signing_key),
+        };
+        vote.signature = DefaultScheme::sign(signing_key, vote.message().as_bytes());
+        Ok(vote)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_builder_signs_what_constructors_sign() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let built = SignedVote::builder()
+            .voter("alice")
+            .proposal("p1")
+            .weight(2.0)
+            .decay(DecayType::Linear)
+            .choice(VoteChoice::Reject)
+            .timestamp(now)
+            .sign(&key)
+            .unwrap();
+        let direct = SignedVote::new_with_choice("alice".parse().unwrap(), "p1".parse().unwrap(), 2.0, now, DecayType::Linear, VoteChoice::Reject, &key);
+        assert_eq!(built.message(), direct.message());
+        assert_eq!(built.signature, direct.signature);
+
+        let full = SignedVote::builder()
+            .voter("alice")
+            .proposal("p1")
+            .random_nonce()
+            .metadata("client", "cli/0.1")
+            .expires_at(now + Duration::seconds(600))
+            .sign(&key)
+            .unwrap();
+        assert_eq!(full.verify(10), Ok(()));
+        assert_eq!((full.original_weight, full.metadata.len()), (1.0, 1));
+        assert!(matches!(full.decay_model, DecayType::Exponential));
+    }
+
+    #[test]
+    fn test_builder_validates_before_signing() {
+        let key = SignedVote::generate_keypair();
+        let base = || SignedVote::builder().voter("alice").proposal("p1");
+        assert_eq!(SignedVote::builder().proposal("p1").sign(&key).unwrap_err(), BuildError::MissingVoter);
+        assert_eq!(SignedVote::builder().voter("alice").sign(&key).unwrap_err(), BuildError::MissingProposal);
+        assert!(matches!(base().voter("al ice").sign(&key), Err(BuildError::InvalidId(IdError::InvalidCharacter { .. }))));
+        assert_eq!(base().weight(-1.0).sign(&key).unwrap_err(), BuildError::InvalidWeight(-1.0));
+        assert!(matches!(base().weight(f64::NAN).sign(&key), Err(BuildError::InvalidWeight(_))));
+
+        let now = Utc::now();
+        assert!(matches!(
+            base().timestamp(now).expires_at(now).sign(&key),
+            Err(BuildError::ExpiresBeforeCast { .. })
+        ));
+        let bulky = (0..=MAX_METADATA_ENTRIES).fold(base(), |b, i| b.metadata(format!("k{}", i), ""));
+        assert!(matches!(bulky.sign(&key), Err(BuildError::MetadataTooLarge { entries: 17, .. })));
+    }
+}
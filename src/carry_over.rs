@@ -0,0 +1,204 @@
+// src/carry_over.rs
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ids::{ProposalId, VoterId};
+use crate::report::FinalizationReport;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CarryOverError {
+    #[error("Carry-over bounds must satisfy 0 <= min <= 1 <= max, got [{min}, {max}]")]
+    InvalidBounds { min: f64, max: f64 },
+    #[error("Carry-over rates must be finite")]
+    InvalidRate,
+    #[error("Proposal {0} is already a phase of this series")]
+    DuplicatePhase(ProposalId),
+    #[error("Proposal {0} is not a phase of this series")]
+    UnknownPhase(String),
+}
+
+/// How a voter's decayed weight at the close of one phase scales their weight in the
+/// next. `rate` may be negative to damp voters who dominated the previous phase.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CarryOverPolicy {
+    /// Multiplier gained per unit of prior decayed weight
+    pub rate: f64,
+    /// Multiplier for voters who did not vote in the prior phase
+    pub absent_multiplier: f64,
+    pub min_multiplier: f64,
+    pub max_multiplier: f64,
+}
+
+impl CarryOverPolicy {
+    pub fn new(rate: f64, absent_multiplier: f64, min_multiplier: f64, max_multiplier: f64) -> Result<Self, CarryOverError> {
+        let bounded = (0.0..=1.0).contains(&min_multiplier) && max_multiplier >= 1.0 && max_multiplier.is_finite();
+        if !bounded {
+            return Err(CarryOverError::InvalidBounds { min: min_multiplier, max: max_multiplier });
+        }
+        if !rate.is_finite() || !absent_multiplier.is_finite() {
+            return Err(CarryOverError::InvalidRate);
+        }
+        Ok(Self { rate, absent_multiplier, min_multiplier, max_multiplier })
+    }
+
+    /// Multiplier before and after clamping to the policy bounds
+    pub fn multiplier(&self, prior_weight: Option<f64>) -> (f64, f64) {
+        let raw = prior_weight.map_or(self.absent_multiplier, |w| 1.0 + self.rate * w);
+        (raw, raw.clamp(self.min_multiplier, self.max_multiplier))
+    }
+}
+
+/// Trace of one carry-over adjustment, kept on the weight explanation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CarryOver {
+    pub from_proposal: ProposalId,
+    /// Decayed weight at the prior phase's close; `None` when the voter sat it out
+    pub prior_weight: Option<f64>,
+    pub raw_multiplier: f64,
+    pub multiplier: f64,
+}
+
+impl CarryOver {
+    /// The policy bounds changed the multiplier
+    pub fn clamped(&self) -> bool {
+        self.raw_multiplier != self.multiplier
+    }
+}
+
+/// Ordered phases of related proposals, e.g. the stages of a budget, with each closed
+/// phase's decayed weights. Carry-over is opt-in: no policy means no adjustment.
+#[derive(Debug, Clone, Default)]
+pub struct ProposalSeries {
+    pub name: String,
+    pub policy: Option<CarryOverPolicy>,
+    phases: Vec<ProposalId>,
+    closing_weights: HashMap<ProposalId, HashMap<VoterId, f64>>,
+}
+
+impl ProposalSeries {
+    pub fn new(name: &str, policy: Option<CarryOverPolicy>) -> Self {
+        Self {
+            name: name.to_string(),
+            policy,
+            ..Self::default()
+        }
+    }
+
+    pub fn add_phase(&mut self, proposal_id: ProposalId) -> Result<(), CarryOverError> {
+        if self.phases.contains(&proposal_id) {
+            return Err(CarryOverError::DuplicatePhase(proposal_id));
+        }
+        self.phases.push(proposal_id);
+        Ok(())
+    }
+
+    pub fn phases(&self) -> &[ProposalId] {
+        &self.phases
+    }
+
+    pub fn previous(&self, proposal_id: &ProposalId) -> Option<&ProposalId> {
+        let index = self.phases.iter().position(|p| p == proposal_id)?;
+        index.checked_sub(1).map(|i| &self.phases[i])
+    }
+
+    /// Record each voter's decayed weight as `proposal_id` closed
+    pub fn close_phase(
+        &mut self,
+        proposal_id: &ProposalId,
+        weights: impl IntoIterator<Item = (VoterId, f64)>,
+    ) -> Result<(), CarryOverError> {
+        if !self.phases.contains(proposal_id) {
+            return Err(CarryOverError::UnknownPhase(proposal_id.to_string()));
+        }
+        self.closing_weights.insert(proposal_id.clone(), weights.into_iter().collect());
+        Ok(())
+    }
+
+    /// Close a phase from its finalization report's counted contributions
+    pub fn close_phase_from_report(&mut self, report: &FinalizationReport) -> Result<(), CarryOverError> {
+        let proposal_id = self
+            .phases
+            .iter()
+            .find(|p| **p == report.proposal.proposal_id)
+            .cloned()
+            .ok_or_else(|| CarryOverError::UnknownPhase(report.proposal.proposal_id.clone()))?;
+        let weights: Vec<(VoterId, f64)> = report
+            .tally
+            .contributions
+            .iter()
+            .filter_map(|c| Some((c.voter_id.parse().ok()?, c.effective_weight)))
+            .collect();
+        self.close_phase(&proposal_id, weights)
+    }
+
+    /// The adjustment for `voter` in `proposal_id`, or `None` when there is no policy,
+    /// the proposal is the first phase, or the prior phase has not closed yet
+    pub fn carry_over(&self, voter: &VoterId, proposal_id: &ProposalId) -> Option<CarryOver> {
+        let policy = self.policy?;
+        let previous = self.previous(proposal_id)?;
+        let closing = self.closing_weights.get(previous)?;
+        let prior_weight = closing.get(voter).copied();
+        let (raw_multiplier, multiplier) = policy.multiplier(prior_weight);
+        Some(CarryOver {
+            from_proposal: previous.clone(),
+            prior_weight,
+            raw_multiplier,
+            multiplier,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(policy: Option<CarryOverPolicy>) -> ProposalSeries {
+        let mut series = ProposalSeries::new("budget-2026", policy);
+        for phase in ["budget-q1", "budget-q2"] {
+            series.add_phase(phase.parse().unwrap()).unwrap();
+        }
+        series
+    }
+
+    #[test]
+    fn test_policy_bounds() {
+        assert!(CarryOverPolicy::new(0.2, 1.0, 1.2, 1.5).is_err());
+        assert!(CarryOverPolicy::new(0.2, 1.0, 0.8, 0.9).is_err());
+        assert!(CarryOverPolicy::new(f64::NAN, 1.0, 0.8, 1.5).is_err());
+
+        let policy = CarryOverPolicy::new(0.5, 0.9, 0.8, 1.25).unwrap();
+        assert_eq!(policy.multiplier(Some(0.2)), (1.1, 1.1));
+        assert_eq!(policy.multiplier(Some(2.0)), (2.0, 1.25));
+        assert_eq!(policy.multiplier(None), (0.9, 0.9));
+    }
+
+    #[test]
+    fn test_carry_over_follows_phases() {
+        let q1: ProposalId = "budget-q1".parse().unwrap();
+        let q2: ProposalId = "budget-q2".parse().unwrap();
+        let alice: VoterId = "alice".parse().unwrap();
+        let policy = CarryOverPolicy::new(-0.5, 1.0, 0.5, 1.0).unwrap();
+        let mut series = series(Some(policy));
+
+        assert_eq!(series.carry_over(&alice, &q2), None);
+        series.close_phase(&q1, [(alice.clone(), 0.4)]).unwrap();
+        assert!(series.close_phase(&"other".parse().unwrap(), []).is_err());
+        assert!(series.add_phase(q1.clone()).is_err());
+
+        let carried = series.carry_over(&alice, &q2).unwrap();
+        assert_eq!(carried.from_proposal, q1);
+        assert_eq!(carried.prior_weight, Some(0.4));
+        assert!((carried.multiplier - 0.8).abs() < 1e-12);
+        assert!(!carried.clamped());
+        assert_eq!(series.carry_over(&"bob".parse().unwrap(), &q2).unwrap().multiplier, 1.0);
+        assert_eq!(series.carry_over(&alice, &q1), None);
+
+        let mut opted_out = self::series(None);
+        opted_out.close_phase(&q1, [(alice.clone(), 0.4)]).unwrap();
+        assert_eq!(opted_out.carry_over(&alice, &q2), None);
+    }
+}
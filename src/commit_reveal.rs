@@ -0,0 +1,321 @@
+// src/commit_reveal.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::hashing;
+use crate::ids::{ProposalId, VoterId};
+use crate::scheme::{DefaultScheme, SignatureScheme};
+use crate::units::Seconds;
+use crate::vote::{
+    SignedVote, deserialize_public_key, deserialize_signature, serialize_public_key, serialize_signature,
+};
+use crate::window::VotingWindow;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CommitRevealError {
+    #[error("Commitment from {0} has an invalid signature")]
+    InvalidCommitmentSignature(VoterId),
+    #[error("Revealed vote from {0} has an invalid signature")]
+    InvalidVoteSignature(VoterId),
+    #[error("Commitment is for {found}, not {expected}")]
+    WrongProposal { expected: ProposalId, found: ProposalId },
+    #[error("{0} already committed")]
+    DuplicateCommitment(VoterId),
+    #[error("The commit phase closed at {0}")]
+    CommitClosed(DateTime<Utc>),
+    #[error("Reveals open at {0}")]
+    RevealNotOpen(DateTime<Utc>),
+    #[error("The reveal phase closed at {0}")]
+    RevealClosed(DateTime<Utc>),
+    #[error("{0} has no commitment")]
+    NoCommitment(VoterId),
+    #[error("{0} already revealed")]
+    AlreadyRevealed(VoterId),
+    #[error("Revealed vote from {0} does not match the commitment")]
+    Mismatch(VoterId),
+    #[error("Revealed vote from {0} is dated after its commitment")]
+    VoteAfterCommitment(VoterId),
+}
+
+/// Bytes a voter signs when committing
+pub fn commitment_message(voter_id: &str, proposal_id: &str, commitment: &str, committed_at: DateTime<Utc>) -> String {
+    format!("commit:{}:{}:{}:{}", voter_id, proposal_id, commitment, committed_at.to_rfc3339())
+}
+
+/// A signed, salted hash of a vote, published while voting is open. It names the voter
+/// and proposal but hides the choice and weight until the vote is revealed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteCommitment {
+    pub voter_id: VoterId,
+    pub proposal_id: ProposalId,
+    /// `hashing::vote_commitment` of the vote and salt, hex
+    pub commitment: String,
+    pub committed_at: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    pub signature: Signature,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    pub public_key: VerifyingKey,
+}
+
+impl VoteCommitment {
+    /// Seal `vote` under `salt`, signed with the key that signed the vote
+    pub fn new(vote: &SignedVote, salt: &[u8], committed_at: DateTime<Utc>, key: &SigningKey) -> Self {
+        let commitment = hashing::vote_commitment(&hashing::vote_hash(vote), salt);
+        let message = commitment_message(&vote.voter_id, &vote.proposal_id, &commitment, committed_at);
+        Self {
+            voter_id: vote.voter_id.clone(),
+            proposal_id: vote.proposal_id.clone(),
+            commitment,
+            committed_at,
+            signature: DefaultScheme::sign(key, message.as_bytes()),
+            public_key: DefaultScheme::public_key(key),
+        }
+    }
+
+    pub fn verify(&self) -> Result<(), CommitRevealError> {
+        let message = commitment_message(&self.voter_id, &self.proposal_id, &self.commitment, self.committed_at);
+        DefaultScheme::verify(&self.public_key, message.as_bytes(), &self.signature)
+            .map_err(|_| CommitRevealError::InvalidCommitmentSignature(self.voter_id.clone()))
+    }
+}
+
+/// The vote behind a commitment and the salt that sealed it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteReveal {
+    pub vote: SignedVote,
+    /// Hex
+    pub salt: String,
+}
+
+impl VoteReveal {
+    pub fn new(vote: SignedVote, salt: &[u8]) -> Self {
+        Self { vote, salt: hex::encode(salt) }
+    }
+
+    /// Check the reveal opens `commitment`: same voter and key, a validly signed vote
+    /// dated no later than the commitment, and a matching hash
+    pub fn verify(&self, commitment: &VoteCommitment) -> Result<(), CommitRevealError> {
+        let vote = &self.vote;
+        let voter = || vote.voter_id.clone();
+        if vote.voter_id != commitment.voter_id || vote.public_key != commitment.public_key {
+            return Err(CommitRevealError::Mismatch(voter()));
+        }
+        if vote.proposal_id != commitment.proposal_id {
+            return Err(CommitRevealError::WrongProposal {
+                expected: commitment.proposal_id.clone(),
+                found: vote.proposal_id.clone(),
+            });
+        }
+        let message = vote.message();
+        DefaultScheme::verify(&vote.public_key, message.as_bytes(), &vote.signature)
+            .map_err(|_| CommitRevealError::InvalidVoteSignature(voter()))?;
+        if vote.timestamp > commitment.committed_at {
+            return Err(CommitRevealError::VoteAfterCommitment(voter()));
+        }
+        let salt = hex::decode(&self.salt).map_err(|_| CommitRevealError::Mismatch(voter()))?;
+        if hashing::vote_commitment(&hashing::vote_hash(vote), &salt) != commitment.commitment {
+            return Err(CommitRevealError::Mismatch(voter()));
+        }
+        Ok(())
+    }
+}
+
+/// What a closed commit-reveal round counts
+#[derive(Debug, Clone, Default)]
+pub struct RevealOutcome {
+    /// Revealed votes in voter order; they decay from their own timestamps
+    pub votes: Vec<SignedVote>,
+    /// Voters whose commitments were never revealed and are discarded
+    pub unrevealed: Vec<VoterId>,
+}
+
+/// Two-phase voting on one proposal: commitments are accepted while `window` is open
+/// (grace included), reveals for `reveal_secs` after it closes. Only revealed votes count.
+pub struct CommitRevealRound {
+    pub proposal_id: ProposalId,
+    pub window: VotingWindow,
+    pub reveal_secs: u64,
+    commitments: BTreeMap<VoterId, VoteCommitment>,
+    revealed: BTreeMap<VoterId, SignedVote>,
+}
+
+impl CommitRevealRound {
+    pub fn new(proposal_id: ProposalId, window: VotingWindow, reveal_secs: u64) -> Self {
+        Self {
+            proposal_id,
+            window,
+            reveal_secs,
+            commitments: BTreeMap::new(),
+            revealed: BTreeMap::new(),
+        }
+    }
+
+    /// End of the reveal phase
+    pub fn reveal_deadline(&self) -> DateTime<Utc> {
+        let reveal = Seconds::try_from(self.reveal_secs).unwrap_or(Seconds::new(i64::MAX));
+        self.window.closes_at().checked_add_signed(reveal.to_duration()).unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    pub fn commit(&mut self, commitment: VoteCommitment, now: DateTime<Utc>) -> Result<(), CommitRevealError> {
+        if !self.window.is_open(now) {
+            return Err(CommitRevealError::CommitClosed(self.window.closes_at()));
+        }
+        if commitment.proposal_id != self.proposal_id {
+            return Err(CommitRevealError::WrongProposal {
+                expected: self.proposal_id.clone(),
+                found: commitment.proposal_id,
+            });
+        }
+        commitment.verify()?;
+        if self.commitments.contains_key(&commitment.voter_id) {
+            return Err(CommitRevealError::DuplicateCommitment(commitment.voter_id));
+        }
+        self.commitments.insert(commitment.voter_id.clone(), commitment);
+        Ok(())
+    }
+
+    pub fn reveal(&mut self, reveal: VoteReveal, now: DateTime<Utc>) -> Result<(), CommitRevealError> {
+        if self.window.is_open(now) {
+            return Err(CommitRevealError::RevealNotOpen(self.window.closes_at()));
+        }
+        if now > self.reveal_deadline() {
+            return Err(CommitRevealError::RevealClosed(self.reveal_deadline()));
+        }
+        let voter = reveal.vote.voter_id.clone();
+        let commitment = self
+            .commitments
+            .get(&voter)
+            .ok_or_else(|| CommitRevealError::NoCommitment(voter.clone()))?;
+        if self.revealed.contains_key(&voter) {
+            return Err(CommitRevealError::AlreadyRevealed(voter));
+        }
+        reveal.verify(commitment)?;
+        self.revealed.insert(voter, reveal.vote);
+        Ok(())
+    }
+
+    pub fn commitments(&self) -> impl Iterator<Item = &VoteCommitment> {
+        self.commitments.values()
+    }
+
+    pub fn revealed(&self) -> impl Iterator<Item = &SignedVote> {
+        self.revealed.values()
+    }
+
+    /// Revealed votes and discarded commitments. Before the reveal deadline this is
+    /// provisional; `None` while commitments are still being accepted.
+    pub fn outcome(&self, now: DateTime<Utc>) -> Option<RevealOutcome> {
+        if self.window.is_open(now) {
+            return None;
+        }
+        Some(RevealOutcome {
+            votes: self.revealed.values().cloned().collect(),
+            unrevealed: self
+                .commitments
+                .keys()
+                .filter(|voter| !self.revealed.contains_key(*voter))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Close the round once the reveal phase is over, discarding unrevealed commitments
+    pub fn close(&mut self, now: DateTime<Utc>) -> Option<RevealOutcome> {
+        if now <= self.reveal_deadline() {
+            return None;
+        }
+        let outcome = self.outcome(now)?;
+        for voter in &outcome.unrevealed {
+            self.commitments.remove(voter);
+        }
+        Some(outcome)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::{DecayType, VoteChoice};
+    use chrono::Duration;
+    use crate::window::WindowType;
+
+    fn vote(voter: &str, choice: VoteChoice, at: DateTime<Utc>, key: &SigningKey) -> SignedVote {
+        SignedVote::new_with_choice(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, at, DecayType::Linear, choice, key)
+    }
+
+    #[test]
+    fn test_commit_then_reveal() {
+        let start = Utc::now();
+        let mut round = CommitRevealRound::new("p1".parse().unwrap(), VotingWindow::new(start, WindowType::Custom(60), 5), 30);
+        let (alice_key, bob_key) = (SignedVote::generate_keypair(), SignedVote::generate_keypair());
+        let alice = vote("alice", VoteChoice::Reject, start, &alice_key);
+        let bob = vote("bob", VoteChoice::Approve, start, &bob_key);
+
+        let at = start + Duration::seconds(10);
+        round.commit(VoteCommitment::new(&alice, b"alice salt", at, &alice_key), at).unwrap();
+        round.commit(VoteCommitment::new(&bob, b"bob salt", at, &bob_key), at).unwrap();
+        assert_eq!(
+            round.commit(VoteCommitment::new(&alice, b"again", at, &alice_key), at),
+            Err(CommitRevealError::DuplicateCommitment("alice".parse().unwrap()))
+        );
+        assert!(matches!(
+            round.reveal(VoteReveal::new(alice.clone(), b"alice salt"), at),
+            Err(CommitRevealError::RevealNotOpen(_))
+        ));
+        assert!(round.outcome(at).is_none());
+
+        let reveal_at = start + Duration::seconds(70);
+        assert!(matches!(
+            round.commit(VoteCommitment::new(&alice, b"late", reveal_at, &alice_key), reveal_at),
+            Err(CommitRevealError::CommitClosed(_))
+        ));
+        assert_eq!(
+            round.reveal(VoteReveal::new(alice.clone(), b"wrong salt"), reveal_at),
+            Err(CommitRevealError::Mismatch("alice".parse().unwrap()))
+        );
+        let switched = vote("alice", VoteChoice::Approve, start, &alice_key);
+        assert!(round.reveal(VoteReveal::new(switched, b"alice salt"), reveal_at).is_err());
+        round.reveal(VoteReveal::new(alice.clone(), b"alice salt"), reveal_at).unwrap();
+        assert!(matches!(
+            round.reveal(VoteReveal::new(alice, b"alice salt"), reveal_at),
+            Err(CommitRevealError::AlreadyRevealed(_))
+        ));
+
+        assert!(round.close(reveal_at).is_none());
+        let after = round.reveal_deadline() + Duration::seconds(1);
+        assert!(matches!(
+            round.reveal(VoteReveal::new(bob, b"bob salt"), after),
+            Err(CommitRevealError::RevealClosed(_))
+        ));
+        let outcome = round.close(after).unwrap();
+        assert_eq!(outcome.votes.len(), 1);
+        assert_eq!(outcome.votes[0].choice, VoteChoice::Reject);
+        assert_eq!(outcome.unrevealed, vec!["bob".parse::<VoterId>().unwrap()]);
+        assert_eq!(round.commitments().count(), 1);
+    }
+
+    #[test]
+    fn test_commitment_signature_and_timing() {
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let vote = vote("carol", VoteChoice::Approve, now, &key);
+
+        let mut forged = VoteCommitment::new(&vote, b"salt", now, &key);
+        forged.committed_at = now + Duration::seconds(1);
+        assert!(matches!(forged.verify(), Err(CommitRevealError::InvalidCommitmentSignature(_))));
+
+        let backdated = VoteCommitment::new(&vote, b"salt", now - Duration::seconds(1), &key);
+        assert!(matches!(
+            VoteReveal::new(vote.clone(), b"salt").verify(&backdated),
+            Err(CommitRevealError::VoteAfterCommitment(_))
+        ));
+        let other_key = VoteCommitment::new(&vote, b"salt", now, &SignedVote::generate_keypair());
+        assert!(VoteReveal::new(vote, b"salt").verify(&other_key).is_err());
+    }
+}
@@ -0,0 +1,444 @@
+// src/committee.rs
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use thiserror::Error;
+
+use crate::ids::{IdError, VoterId};
+use crate::registry::ValidatorRegistry;
+use crate::scheme::{DefaultScheme, SignatureScheme};
+use crate::trust::TrustSource;
+use crate::units::{Seconds, Weight};
+use crate::vote::{DecayType, SignedVote, VoteChoice};
+use crate::weight_engine::{self, WeightEngine};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CommitteeError {
+    #[error("Committee has no members")]
+    Empty,
+    #[error("{0} is not a registered validator")]
+    UnknownMember(String),
+    #[error("{0} already sits on another committee")]
+    OverlappingMember(String),
+    #[error("Committee {0} already exists")]
+    DuplicateCommittee(String),
+    #[error("Quorum must be in (0, 1], got {0}")]
+    InvalidQuorum(f64),
+    #[error("Invalid committee id: {0}")]
+    InvalidId(#[from] IdError),
+    #[error("Vote is for committee {found}, not {expected}")]
+    WrongCommittee { expected: String, found: String },
+    #[error("{0} does not sit on this committee")]
+    NotAMember(String),
+    #[error("{0} signed more than once")]
+    DuplicateSigner(String),
+    #[error("Invalid signature from {0}")]
+    InvalidSignature(String),
+    #[error("Only {signed} of the {required} required members signed")]
+    BelowThreshold { signed: usize, required: usize },
+}
+
+/// Payload every signing member of a committee vote signs
+pub fn committee_message(committee_id: &str, proposal_id: &str, timestamp: DateTime<Utc>, choice: VoteChoice) -> String {
+    let choice = match choice {
+        VoteChoice::Approve => "approve",
+        VoteChoice::Reject => "reject",
+        VoteChoice::Abstain => "abstain",
+    };
+    format!("committee:{}:{}:{}:{}", committee_id, proposal_id, timestamp, choice)
+}
+
+/// A subset of the registry that votes internally and casts one aggregated vote
+#[derive(Debug, Clone)]
+pub struct Committee {
+    pub id: String,
+    /// Members' keys as registered when the committee was formed
+    members: BTreeMap<String, VerifyingKey>,
+    /// Weight of the committee's single vote in the general assembly
+    pub weight: Weight,
+    pub decay: DecayType,
+    /// Share of members that must vote before the committee votes
+    pub quorum: f64,
+}
+
+impl Committee {
+    pub fn new(
+        id: &str,
+        members: &[&str],
+        weight: Weight,
+        decay: DecayType,
+        quorum: f64,
+        registry: &ValidatorRegistry,
+    ) -> Result<Self, CommitteeError> {
+        VoterId::new(id)?;
+        if members.is_empty() {
+            return Err(CommitteeError::Empty);
+        }
+        if !(quorum > 0.0 && quorum <= 1.0) {
+            return Err(CommitteeError::InvalidQuorum(quorum));
+        }
+        let mut keys = BTreeMap::new();
+        for member in members {
+            let key = registry
+                .public_key(member)
+                .ok_or_else(|| CommitteeError::UnknownMember(member.to_string()))?;
+            keys.insert(member.to_string(), *key);
+        }
+        Ok(Self {
+            id: id.to_string(),
+            members: keys,
+            weight,
+            decay,
+            quorum,
+        })
+    }
+
+    pub fn members(&self) -> impl Iterator<Item = &str> {
+        self.members.keys().map(String::as_str)
+    }
+
+    pub fn is_member(&self, voter_id: &str) -> bool {
+        self.members.contains_key(voter_id)
+    }
+
+    /// Signatures a committee vote needs: the quorum share of members, rounded up
+    pub fn threshold(&self) -> usize {
+        ((self.quorum * self.members.len() as f64).ceil() as usize).max(1)
+    }
+
+    /// Check an m-of-n committee vote: every signer sits on this committee, signs once,
+    /// and signed the vote's payload with their registered key, and at least
+    /// `threshold()` of them did
+    pub fn verify(&self, vote: &CommitteeVote) -> Result<(), CommitteeError> {
+        if vote.committee_id != self.id {
+            return Err(CommitteeError::WrongCommittee {
+                expected: self.id.clone(),
+                found: vote.committee_id.clone(),
+            });
+        }
+        let message = vote.message();
+        let mut signers = HashSet::new();
+        for signed in &vote.signatures {
+            let key = self
+                .members
+                .get(&signed.member)
+                .ok_or_else(|| CommitteeError::NotAMember(signed.member.clone()))?;
+            if !signers.insert(signed.member.as_str()) {
+                return Err(CommitteeError::DuplicateSigner(signed.member.clone()));
+            }
+            DefaultScheme::verify(key, message.as_bytes(), &signed.signature)
+                .map_err(|_| CommitteeError::InvalidSignature(signed.member.clone()))?;
+        }
+        if signers.len() < self.threshold() {
+            return Err(CommitteeError::BelowThreshold { signed: signers.len(), required: self.threshold() });
+        }
+        Ok(())
+    }
+
+    /// Run the internal vote on `proposal_id`. The committee votes once quorum is
+    /// reached, timestamped when the deciding member voted; `None` if it never is.
+    pub fn aggregate(&self, proposal_id: &str, votes: &[SignedVote]) -> Option<CommitteeVote> {
+        let mut member_votes: Vec<&SignedVote> = votes
+            .iter()
+            .filter(|v| v.proposal_id == proposal_id && self.is_member(&v.voter_id))
+            .collect();
+        member_votes.sort_by_key(|v| v.timestamp);
+
+        let mut participants = Vec::new();
+        let mut choices = Vec::new();
+        let mut decided_at = None;
+        for vote in member_votes {
+            if participants.contains(&vote.voter_id.to_string()) {
+                continue;
+            }
+            participants.push(vote.voter_id.to_string());
+            choices.push(vote.choice);
+            if decided_at.is_none() && participants.len() as f64 / self.members.len() as f64 >= self.quorum {
+                decided_at = Some(vote.timestamp);
+            }
+        }
+        Some(CommitteeVote {
+            committee_id: self.id.clone(),
+            proposal_id: proposal_id.to_string(),
+            participation: participants.len() as f64 / self.members.len() as f64,
+            participants,
+            choice: plurality(&choices),
+            weight: self.weight,
+            decay: self.decay,
+            timestamp: decided_at?,
+            signatures: Vec::new(),
+        })
+    }
+}
+
+/// Most common choice among members; a tie abstains
+fn plurality(choices: &[VoteChoice]) -> VoteChoice {
+    let count = |choice| choices.iter().filter(|c| **c == choice).count();
+    let (approve, reject) = (count(VoteChoice::Approve), count(VoteChoice::Reject));
+    match approve.cmp(&reject) {
+        std::cmp::Ordering::Greater if approve >= count(VoteChoice::Abstain) => VoteChoice::Approve,
+        std::cmp::Ordering::Less if reject >= count(VoteChoice::Abstain) => VoteChoice::Reject,
+        _ => VoteChoice::Abstain,
+    }
+}
+
+/// One member's signature over a committee vote's payload
+#[derive(Debug, Clone)]
+pub struct MemberSignature {
+    pub member: String,
+    pub signature: Signature,
+}
+
+/// A committee's aggregated vote, decaying from the moment quorum was reached. Votes
+/// built by `Committee::aggregate` are backed by the members' own signed votes; votes
+/// built by `CommitteeVote::sign` carry the members' signatures over one payload.
+#[derive(Debug, Clone)]
+pub struct CommitteeVote {
+    pub committee_id: String,
+    pub proposal_id: String,
+    pub participants: Vec<String>,
+    pub participation: f64,
+    pub choice: VoteChoice,
+    pub weight: Weight,
+    pub decay: DecayType,
+    pub timestamp: DateTime<Utc>,
+    pub signatures: Vec<MemberSignature>,
+}
+
+impl CommitteeVote {
+    /// Have each of `signers` sign the same vote on behalf of `committee`
+    pub fn sign(
+        committee: &Committee,
+        proposal_id: &str,
+        choice: VoteChoice,
+        timestamp: DateTime<Utc>,
+        signers: &[(&str, &SigningKey)],
+    ) -> Self {
+        let message = committee_message(&committee.id, proposal_id, timestamp, choice);
+        let participants: Vec<String> = signers.iter().map(|(member, _)| member.to_string()).collect();
+        Self {
+            committee_id: committee.id.clone(),
+            proposal_id: proposal_id.to_string(),
+            participation: participants.len() as f64 / committee.members.len() as f64,
+            participants,
+            choice,
+            weight: committee.weight,
+            decay: committee.decay,
+            timestamp,
+            signatures: signers
+                .iter()
+                .map(|(member, key)| MemberSignature {
+                    member: member.to_string(),
+                    signature: DefaultScheme::sign(key, message.as_bytes()),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        committee_message(&self.committee_id, &self.proposal_id, self.timestamp, self.choice)
+    }
+
+    pub fn weight_at(&self, now: DateTime<Utc>) -> Weight {
+        weight_engine::decay_weight(self.decay, self.weight, Seconds::between(self.timestamp, now))
+    }
+}
+
+/// Committee and general-assembly weight for one proposal
+#[derive(Debug, Clone)]
+pub struct TwoLevelTally {
+    pub committee_votes: Vec<CommitteeVote>,
+    pub committee_weight: f64,
+    pub assembly_weight: f64,
+    /// Member votes counted through their committee rather than individually
+    pub absorbed: usize,
+}
+
+impl TwoLevelTally {
+    pub fn total_weight(&self) -> f64 {
+        self.committee_weight + self.assembly_weight
+    }
+}
+
+/// Non-overlapping committees; a member's vote only ever counts through its committee
+#[derive(Debug, Clone, Default)]
+pub struct CommitteeSet {
+    committees: Vec<Committee>,
+}
+
+impl CommitteeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, committee: Committee) -> Result<(), CommitteeError> {
+        if self.committees.iter().any(|c| c.id == committee.id) {
+            return Err(CommitteeError::DuplicateCommittee(committee.id));
+        }
+        if let Some(member) = committee.members().find(|m| self.committee_of(m).is_some()) {
+            return Err(CommitteeError::OverlappingMember(member.to_string()));
+        }
+        self.committees.push(committee);
+        Ok(())
+    }
+
+    pub fn committees(&self) -> &[Committee] {
+        &self.committees
+    }
+
+    pub fn get(&self, committee_id: &str) -> Option<&Committee> {
+        self.committees.iter().find(|c| c.id == committee_id)
+    }
+
+    pub fn committee_of(&self, voter_id: &str) -> Option<&Committee> {
+        self.committees.iter().find(|c| c.is_member(voter_id))
+    }
+
+    /// Tally `proposal_id` as of `now`: one decayed vote per committee that reached
+    /// quorum, plus every non-member's first vote at its usual weight
+    pub fn tally(
+        &self,
+        proposal_id: &str,
+        votes: &[SignedVote],
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+    ) -> TwoLevelTally {
+        let committee_votes: Vec<CommitteeVote> =
+            self.committees.iter().filter_map(|c| c.aggregate(proposal_id, votes)).collect();
+
+        let engine = WeightEngine::new();
+        let mut seen = HashSet::new();
+        let mut absorbed = 0;
+        let mut assembly_weight = 0.0;
+        for vote in votes.iter().filter(|v| v.proposal_id == proposal_id) {
+            if self.committee_of(&vote.voter_id).is_some() {
+                absorbed += 1;
+            } else if seen.insert(vote.voter_id.as_str()) {
+                assembly_weight += engine.explain(vote, now, trust, None).final_weight.get();
+            }
+        }
+
+        TwoLevelTally {
+            committee_weight: committee_votes.iter().map(|c| c.weight_at(now).get()).sum(),
+            committee_votes,
+            assembly_weight,
+            absorbed,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn registry(ids: &[&str]) -> ValidatorRegistry {
+        let mut registry = ValidatorRegistry::new();
+        for id in ids {
+            registry.register(id.to_string(), SignedVote::generate_keypair().verifying_key());
+        }
+        registry
+    }
+
+    fn vote(voter: &str, at: DateTime<Utc>) -> SignedVote {
+        let key = SignedVote::generate_keypair();
+        SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, at, DecayType::Linear, &key)
+    }
+
+    #[test]
+    fn test_committee_membership_is_validated() {
+        let registry = registry(&["alice", "bob", "carol"]);
+        let w = Weight::saturating(2.0);
+        assert_eq!(
+            Committee::new("council", &["alice", "mallory"], w, DecayType::Linear, 0.5, &registry).unwrap_err(),
+            CommitteeError::UnknownMember("mallory".to_string())
+        );
+        assert!(Committee::new("council", &[], w, DecayType::Linear, 0.5, &registry).is_err());
+        assert!(Committee::new("council", &["alice"], w, DecayType::Linear, 0.0, &registry).is_err());
+
+        let mut set = CommitteeSet::new();
+        set.add(Committee::new("council", &["alice", "bob"], w, DecayType::Linear, 0.5, &registry).unwrap())
+            .unwrap();
+        let overlapping = Committee::new("board", &["bob", "carol"], w, DecayType::Linear, 0.5, &registry).unwrap();
+        assert_eq!(set.add(overlapping), Err(CommitteeError::OverlappingMember("bob".to_string())));
+    }
+
+    #[test]
+    fn test_two_level_tally() {
+        let registry = registry(&["alice", "bob", "carol", "dave"]);
+        let now = Utc::now();
+        let mut set = CommitteeSet::new();
+        set.add(
+            Committee::new("council", &["alice", "bob", "carol"], Weight::saturating(3.0), DecayType::Stepped, 0.6, &registry)
+                .unwrap(),
+        )
+        .unwrap();
+
+        let votes = vec![
+            vote("alice", now - Duration::seconds(100)),
+            vote("bob", now - Duration::seconds(70)),
+            vote("dave", now - Duration::seconds(10)),
+            vote("dave", now - Duration::seconds(5)),
+        ];
+        let tally = set.tally("p1", &votes, now, None);
+
+        assert_eq!(tally.committee_votes.len(), 1);
+        let council = &tally.committee_votes[0];
+        assert_eq!(council.timestamp, now - Duration::seconds(70));
+        assert_eq!(council.participants, vec!["alice", "bob"]);
+        // Quorum reached 70s ago: first stepped decay step
+        assert!((tally.committee_weight - 2.4).abs() < 1e-9);
+        assert_eq!(tally.absorbed, 2);
+        assert!((tally.assembly_weight - 0.99).abs() < 1e-9);
+        assert!((tally.total_weight() - 3.39).abs() < 1e-9);
+
+        let short = set.tally("p1", &votes[..1], now, None);
+        assert!(short.committee_votes.is_empty());
+        assert_eq!(short.total_weight(), 0.0);
+    }
+
+    #[test]
+    fn test_multisig_committee_vote() {
+        let keys: Vec<SigningKey> = (0..3).map(|_| SignedVote::generate_keypair()).collect();
+        let mut registry = ValidatorRegistry::new();
+        for (id, key) in ["alice", "bob", "carol"].iter().zip(&keys) {
+            registry.register(id.to_string(), key.verifying_key());
+        }
+        let w = Weight::saturating(3.0);
+        let council = Committee::new("council", &["alice", "bob", "carol"], w, DecayType::Linear, 0.6, &registry).unwrap();
+        assert_eq!(council.threshold(), 2);
+        assert!(Committee::new("bad:id", &["alice"], w, DecayType::Linear, 0.5, &registry).is_err());
+
+        let now = Utc::now();
+        let sign = |signers: &[(&str, &SigningKey)]| CommitteeVote::sign(&council, "p1", VoteChoice::Reject, now, signers);
+        let vote = sign(&[("alice", &keys[0]), ("carol", &keys[2])]);
+        assert_eq!(council.verify(&vote), Ok(()));
+        assert_eq!(vote.participants, vec!["alice", "carol"]);
+
+        assert_eq!(
+            council.verify(&sign(&[("alice", &keys[0])])),
+            Err(CommitteeError::BelowThreshold { signed: 1, required: 2 })
+        );
+        assert_eq!(
+            council.verify(&sign(&[("alice", &keys[0]), ("alice", &keys[0])])),
+            Err(CommitteeError::DuplicateSigner("alice".to_string()))
+        );
+        assert_eq!(
+            council.verify(&sign(&[("alice", &keys[0]), ("bob", &keys[2])])),
+            Err(CommitteeError::InvalidSignature("bob".to_string()))
+        );
+        assert_eq!(
+            council.verify(&sign(&[("alice", &keys[0]), ("dave", &keys[1])])),
+            Err(CommitteeError::NotAMember("dave".to_string()))
+        );
+
+        let mut tampered = vote.clone();
+        tampered.choice = VoteChoice::Approve;
+        assert!(matches!(council.verify(&tampered), Err(CommitteeError::InvalidSignature(_))));
+        let board = Committee::new("board", &["alice", "bob"], w, DecayType::Linear, 0.5, &registry).unwrap();
+        assert!(matches!(board.verify(&vote), Err(CommitteeError::WrongCommittee { .. })));
+    }
+}
@@ -0,0 +1,227 @@
+// src/conformance.rs
+
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signer, SigningKey};
+
+use crate::messages::{self, MessageKey};
+use crate::payload::VotePayload;
+use crate::threshold::ThresholdEscalator;
+use crate::vectors::{TestVectors, decay_from_name, proposal_type_from_name};
+use crate::vote::{SignedVote, VoteChoice, vote_message};
+use crate::weight_engine::WeightEngine;
+
+/// Allowed absolute difference between expected and computed floats
+pub const FLOAT_TOLERANCE: f64 = 1e-9;
+
+/// A single fixture entry this crate disagrees with
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceFailure {
+    pub category: &'static str,
+    pub index: usize,
+    pub detail: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ConformanceReport {
+    pub checked: usize,
+    pub failures: Vec<ConformanceFailure>,
+}
+
+impl ConformanceReport {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    fn fail(&mut self, category: &'static str, index: usize, detail: String) {
+        self.failures.push(ConformanceFailure { category, index, detail });
+    }
+
+    pub fn print_summary(&self) {
+        println!(
+            "{}",
+            messages::text(
+                MessageKey::ConformanceSummary,
+                &[("checked", self.checked.to_string()), ("failed", self.failures.len().to_string())],
+            )
+        );
+        for f in &self.failures {
+            println!(
+                "{}",
+                messages::text(
+                    MessageKey::ConformanceFailure,
+                    &[
+                        ("category", f.category.to_string()),
+                        ("index", f.index.to_string()),
+                        ("detail", f.detail.clone()),
+                    ],
+                )
+            );
+        }
+    }
+}
+
+/// Load a JSON vector fixture and check every entry against this crate
+pub fn run_file(path: impl AsRef<Path>) -> Result<ConformanceReport, String> {
+    let raw = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let vectors: TestVectors = crate::schema::parse(&raw).map_err(|e| e.to_string())?;
+    Ok(run(&vectors))
+}
+
+/// Check every vector, collecting mismatches instead of stopping at the first
+pub fn run(vectors: &TestVectors) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+    check_votes(vectors, &mut report);
+    check_payloads(vectors, &mut report);
+    check_decay(vectors, &mut report);
+    check_thresholds(vectors, &mut report);
+    report
+}
+
+fn check_votes(vectors: &TestVectors, report: &mut ConformanceReport) {
+    for (i, v) in vectors.votes.iter().enumerate() {
+        report.checked += 1;
+
+        let secret: Option<[u8; 32]> = hex::decode(&v.secret_key).ok().and_then(|b| b.try_into().ok());
+        let Some(secret) = secret else {
+            report.fail("votes", i, "secret key is not 32 hex bytes".to_string());
+            continue;
+        };
+        let Ok(timestamp) = DateTime::parse_from_rfc3339(&v.timestamp) else {
+            report.fail("votes", i, format!("unparseable timestamp {}", v.timestamp));
+            continue;
+        };
+
+        let key = SigningKey::from_bytes(&secret);
+        let public_key = hex::encode(key.verifying_key().as_bytes());
+        if public_key != v.public_key {
+            report.fail("votes", i, format!("public key {} != {}", public_key, v.public_key));
+        }
+
+        let payload = vote_message(&v.voter_id, &v.proposal_id, timestamp.with_timezone(&Utc), VoteChoice::Approve, 0, 0);
+        let payload_hex = hex::encode(payload.as_bytes());
+        if payload_hex != v.payload {
+            report.fail("votes", i, format!("payload {} != {}", payload_hex, v.payload));
+        }
+
+        let signature = hex::encode(key.sign(payload.as_bytes()).to_bytes());
+        if signature != v.signature {
+            report.fail("votes", i, format!("signature {} != {}", signature, v.signature));
+        }
+    }
+}
+
+fn check_payloads(vectors: &TestVectors, report: &mut ConformanceReport) {
+    for (i, v) in vectors.payloads.iter().enumerate() {
+        report.checked += 1;
+
+        let secret: Option<[u8; 32]> = hex::decode(&v.secret_key).ok().and_then(|b| b.try_into().ok());
+        let (Some(secret), Some(payload)) = (secret, v.to_payload()) else {
+            report.fail("payloads", i, "secret key, stake signature, or weight is malformed".to_string());
+            continue;
+        };
+        let bytes = payload.to_bytes();
+        let payload_hex = hex::encode(&bytes);
+        if payload_hex != v.payload {
+            report.fail("payloads", i, format!("payload {} != {}", payload_hex, v.payload));
+        }
+        match hex::decode(&v.payload).map(|b| VotePayload::from_bytes(&b)) {
+            Ok(Ok(decoded)) if decoded == payload => {}
+            Ok(Ok(_)) => report.fail("payloads", i, "payload decodes to different fields".to_string()),
+            Ok(Err(e)) => report.fail("payloads", i, format!("payload does not decode: {}", e)),
+            Err(_) => report.fail("payloads", i, "payload is not hex".to_string()),
+        }
+
+        let signature = hex::encode(SigningKey::from_bytes(&secret).sign(&bytes).to_bytes());
+        if signature != v.signature {
+            report.fail("payloads", i, format!("signature {} != {}", signature, v.signature));
+        }
+    }
+}
+
+fn check_decay(vectors: &TestVectors, report: &mut ConformanceReport) {
+    let key = SignedVote::generate_keypair();
+    let cast_at = Utc::now();
+
+    for (i, v) in vectors.decay.iter().enumerate() {
+        report.checked += 1;
+        let Some(decay) = decay_from_name(&v.decay_model) else {
+            report.fail("decay", i, format!("unknown decay model {}", v.decay_model));
+            continue;
+        };
+
+        let vote = SignedVote::new(
+            "conformance_voter".parse().expect("valid id"),
+            "conformance_proposal".parse().expect("valid id"),
+            v.original_weight,
+            cast_at,
+            decay,
+            &key,
+        );
+        let weight = WeightEngine::new().calculate_weight(&vote, cast_at + Duration::seconds(v.elapsed_secs), None);
+        if (weight - v.expected_weight).abs() > FLOAT_TOLERANCE {
+            report.fail("decay", i, format!("weight {} != {}", weight, v.expected_weight));
+        }
+    }
+}
+
+fn check_thresholds(vectors: &TestVectors, report: &mut ConformanceReport) {
+    let start = Utc::now();
+
+    for (i, v) in vectors.thresholds.iter().enumerate() {
+        report.checked += 1;
+        let Some(proposal_type) = proposal_type_from_name(&v.proposal_type) else {
+            report.fail("thresholds", i, format!("unknown proposal type {}", v.proposal_type));
+            continue;
+        };
+
+        let escalator = ThresholdEscalator::for_proposal_type(proposal_type);
+        let threshold = escalator.threshold_with_profile(start + Duration::seconds(v.elapsed_secs), start);
+        if (threshold - v.expected_threshold).abs() > FLOAT_TOLERANCE {
+            report.fail("thresholds", i, format!("threshold {} != {}", threshold, v.expected_threshold));
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vectors::generate;
+
+    const FIXTURE: &str = include_str!("../conformance/vectors.json");
+
+    #[test]
+    fn test_published_fixture_passes() {
+        let vectors: TestVectors = serde_json::from_str(FIXTURE).unwrap();
+        let report = run(&vectors);
+        assert!(report.passed(), "{:?}", report.failures);
+        assert_eq!(
+            report.checked,
+            vectors.votes.len() + vectors.payloads.len() + vectors.decay.len() + vectors.thresholds.len()
+        );
+    }
+
+    #[test]
+    fn test_fixture_matches_generator() {
+        let vectors: TestVectors = serde_json::from_str(FIXTURE).unwrap();
+        assert_eq!(vectors, generate());
+    }
+
+    #[test]
+    fn test_mismatches_are_reported() {
+        let mut vectors = generate();
+        vectors.votes[0].voter_id = "mallory".to_string();
+        vectors.payloads[1].version = 3;
+        vectors.decay[1].expected_weight += 0.5;
+        vectors.thresholds[0].proposal_type = "unknown".to_string();
+
+        let report = run(&vectors);
+        let categories: Vec<_> = report.failures.iter().map(|f| f.category).collect();
+        assert!(categories.contains(&"votes"));
+        assert!(categories.contains(&"payloads"));
+        assert!(categories.contains(&"decay"));
+        assert!(categories.contains(&"thresholds"));
+    }
+}
@@ -1,6 +1,18 @@
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::units::{Seconds, Weight};
+use crate::vote::DecayType;
+
 pub trait DecayModel {
     fn compute_weight(&self, original_weight: f64, elapsed_time: f64) -> f64;
+
+    /// Typed form of `compute_weight`; time before the vote counts as no time at all
+    fn decay(&self, original_weight: Weight, elapsed: Seconds) -> Weight {
+        Weight::saturating(self.compute_weight(original_weight.get(), elapsed.as_u64_saturating() as f64))
+    }
 }
 
 pub struct LinearDecay {
@@ -9,7 +21,7 @@ pub struct LinearDecay {
 
 impl DecayModel for LinearDecay {
     fn compute_weight(&self, original_weight: f64, elapsed_time: f64) -> f64 {
-        let decayed = original_weight - self.rate * elapsed_time as f64;
+        let decayed = original_weight - self.rate * elapsed_time;
         decayed.max(0.1 * original_weight)
     }
 }
@@ -20,7 +32,7 @@ pub struct ExponentialDecay {
 
 impl DecayModel for ExponentialDecay {
     fn compute_weight(&self, original_weight: f64, elapsed_time: f64) -> f64 {
-        let decayed = original_weight * (-self.rate * elapsed_time as f64).exp();
+        let decayed = original_weight * (-self.rate * elapsed_time).exp();
         decayed.max(0.1 * original_weight)
     }
 }
@@ -41,6 +53,206 @@ impl DecayModel for SteppedDecay {
     }
 }
 
+/// `(seconds since the vote, multiplier)` steps of `DecayType::Stepped`
+pub const STANDARD_STEPS: [(f64, f64); 3] = [(60.0, 0.8), (180.0, 0.5), (300.0, 0.2)];
+pub const STANDARD_EXPONENTIAL_RATE: f64 = 0.005;
+pub const STANDARD_LINEAR_RATE: f64 = 0.001;
+/// Share of its weight a vote keeps however long it decays
+pub const STANDARD_FLOOR: f64 = 0.1;
+
+impl DecayType {
+    /// Time from `age` until a stepped vote drops to its next step; `None` for the
+    /// continuous models and past the last step
+    pub fn next_step(&self, age: Seconds) -> Option<Seconds> {
+        VoteDecay::from(*self).next_step(age)
+    }
+}
+
+/// The built-in models at the engine's standard rates
+impl DecayModel for DecayType {
+    fn compute_weight(&self, original_weight: f64, elapsed_time: f64) -> f64 {
+        match self {
+            DecayType::Exponential => {
+                ExponentialDecay { rate: STANDARD_EXPONENTIAL_RATE }.compute_weight(original_weight, elapsed_time)
+            }
+            DecayType::Linear => LinearDecay { rate: STANDARD_LINEAR_RATE }.compute_weight(original_weight, elapsed_time),
+            DecayType::Stepped => SteppedDecay {
+                decay_steps: STANDARD_STEPS.to_vec(),
+            }
+            .compute_weight(original_weight, elapsed_time),
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DecayParamsError {
+    #[error("Decay {param} does not apply to {model:?} decay")]
+    NotApplicable { param: &'static str, model: DecayType },
+    #[error("Decay rate {rate} is outside {min}..={max}")]
+    RateOutOfBounds { rate: f64, min: f64, max: f64 },
+    #[error("Decay floor {floor} is outside {min}..={max}")]
+    FloorOutOfBounds { floor: f64, min: f64, max: f64 },
+    #[error("{steps} decay steps, over the limit of {max}")]
+    TooManySteps { steps: usize, max: usize },
+    #[error("Decay step ({at}s, x{multiplier}) must fall within {max_secs}s, after the step before and no heavier")]
+    InvalidStep { at: f64, multiplier: f64, max_secs: f64 },
+}
+
+/// Decay a voter chose for their own vote, signed along with it. Unset fields take
+/// the model's standard values; `rate` applies to linear and exponential decay,
+/// `steps` to stepped decay, and `floor` to all three.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DecayParams {
+    /// Share of the weight the vote keeps however long it decays
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub floor: Option<f64>,
+    /// Per second: weight lost for linear decay, exponent for exponential decay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f64>,
+    /// `(seconds since the vote, multiplier)` in place of `STANDARD_STEPS`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps: Option<Vec<(f64, f64)>>,
+}
+
+/// The decay parameters a server accepts from voters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DecayBounds {
+    pub min_rate: f64,
+    pub max_rate: f64,
+    pub min_floor: f64,
+    pub max_floor: f64,
+    pub max_steps: usize,
+    /// Latest a step may fall after the vote
+    pub max_step_secs: f64,
+}
+
+impl Default for DecayBounds {
+    fn default() -> Self {
+        Self {
+            min_rate: 0.0001,
+            max_rate: 0.05,
+            min_floor: 0.05,
+            max_floor: 0.5,
+            max_steps: 8,
+            max_step_secs: 30.0 * 86_400.0,
+        }
+    }
+}
+
+impl DecayBounds {
+    /// Whether `params` suit `model` and fall within these bounds
+    pub fn check(&self, model: DecayType, params: &DecayParams) -> Result<(), DecayParamsError> {
+        if let Some(rate) = params.rate {
+            if matches!(model, DecayType::Stepped) {
+                return Err(DecayParamsError::NotApplicable { param: "rate", model });
+            }
+            if !(self.min_rate..=self.max_rate).contains(&rate) {
+                return Err(DecayParamsError::RateOutOfBounds { rate, min: self.min_rate, max: self.max_rate });
+            }
+        }
+        if let Some(floor) = params.floor
+            && !(self.min_floor..=self.max_floor).contains(&floor)
+        {
+            return Err(DecayParamsError::FloorOutOfBounds { floor, min: self.min_floor, max: self.max_floor });
+        }
+        if let Some(steps) = &params.steps {
+            if !matches!(model, DecayType::Stepped) {
+                return Err(DecayParamsError::NotApplicable { param: "steps", model });
+            }
+            if steps.len() > self.max_steps {
+                return Err(DecayParamsError::TooManySteps { steps: steps.len(), max: self.max_steps });
+            }
+            let mut previous = (0.0, 1.0);
+            for &(at, multiplier) in steps {
+                if !(at > previous.0 && at <= self.max_step_secs && (0.0..=previous.1).contains(&multiplier)) {
+                    return Err(DecayParamsError::InvalidStep { at, multiplier, max_secs: self.max_step_secs });
+                }
+                previous = (at, multiplier);
+            }
+        }
+        Ok(())
+    }
+
+    /// `model` under `params` pulled inside these bounds: rates and floors clamped,
+    /// steps clamped and cut to `max_steps`, and parameters `model` has no use for dropped
+    pub fn resolve(&self, model: DecayType, params: Option<&DecayParams>) -> VoteDecay {
+        let Some(params) = params else {
+            return VoteDecay::from(model);
+        };
+        let clamp = |value: f64, min: f64, max: f64| if value.is_nan() { min } else { value.clamp(min, max) };
+        let stepped = matches!(model, DecayType::Stepped);
+        VoteDecay {
+            model,
+            params: DecayParams {
+                floor: params.floor.map(|f| clamp(f, self.min_floor, self.max_floor)),
+                rate: params.rate.filter(|_| !stepped).map(|r| clamp(r, self.min_rate, self.max_rate)),
+                steps: params.steps.as_ref().filter(|_| stepped).map(|steps| {
+                    steps
+                        .iter()
+                        .take(self.max_steps)
+                        .map(|&(at, multiplier)| (clamp(at, 0.0, self.max_step_secs), clamp(multiplier, 0.0, 1.0)))
+                        .collect()
+                }),
+            },
+        }
+    }
+}
+
+/// A vote's decay model with the parameters it is weighed under
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoteDecay {
+    pub model: DecayType,
+    pub params: DecayParams,
+}
+
+impl From<DecayType> for VoteDecay {
+    fn from(model: DecayType) -> Self {
+        Self { model, params: DecayParams::default() }
+    }
+}
+
+impl VoteDecay {
+    fn steps(&self) -> &[(f64, f64)] {
+        self.params.steps.as_deref().unwrap_or(&STANDARD_STEPS)
+    }
+
+    /// Time from `age` until a stepped vote drops to its next step; `None` for the
+    /// continuous models and past the last step
+    pub fn next_step(&self, age: Seconds) -> Option<Seconds> {
+        match self.model {
+            DecayType::Stepped => self
+                .steps()
+                .iter()
+                .map(|&(at, _)| Seconds::new(at as i64))
+                .find(|at| *at > age)
+                .map(|at| at - age.max(Seconds::ZERO)),
+            _ => None,
+        }
+    }
+}
+
+impl DecayModel for VoteDecay {
+    fn compute_weight(&self, original_weight: f64, elapsed_time: f64) -> f64 {
+        let floor = self.params.floor.unwrap_or(STANDARD_FLOOR) * original_weight;
+        let decayed = match self.model {
+            DecayType::Exponential => {
+                original_weight * (-self.params.rate.unwrap_or(STANDARD_EXPONENTIAL_RATE) * elapsed_time).exp()
+            }
+            DecayType::Linear => original_weight - self.params.rate.unwrap_or(STANDARD_LINEAR_RATE) * elapsed_time,
+            DecayType::Stepped => {
+                let mut multiplier = 1.0;
+                for &(threshold, factor) in self.steps() {
+                    if elapsed_time >= threshold {
+                        multiplier = factor;
+                    }
+                }
+                original_weight * multiplier
+            }
+        };
+        decayed.max(floor)
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -113,4 +325,65 @@ mod tests {
         let w = model.compute_weight(w0, 100.0);
         assert!(w >= 0.1 * w0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_typed_decay_ignores_negative_elapsed() {
+        let model = LinearDecay { rate: 1.0 };
+        let w0 = Weight::new(10.0).unwrap();
+        assert_eq!(model.decay(w0, Seconds::new(-5)), w0);
+        assert_eq!(model.decay(w0, Seconds::new(4)).get(), 6.0);
+    }
+
+    #[test]
+    fn test_vote_decay_applies_signed_params() {
+        for (model, elapsed) in [(DecayType::Linear, 400.0), (DecayType::Exponential, 120.0), (DecayType::Stepped, 200.0)] {
+            assert_eq!(VoteDecay::from(model).compute_weight(2.0, elapsed), model.compute_weight(2.0, elapsed));
+        }
+
+        let fast = VoteDecay { model: DecayType::Linear, params: DecayParams { rate: Some(0.01), floor: Some(0.3), steps: None } };
+        assert!((fast.compute_weight(1.0, 50.0) - 0.5).abs() < 1e-9);
+        assert_eq!(fast.compute_weight(1.0, 500.0), 0.3);
+
+        let stepped = DecayParams { steps: Some(vec![(30.0, 0.9), (90.0, 0.6)]), ..DecayParams::default() };
+        let stepped = VoteDecay { model: DecayType::Stepped, params: stepped };
+        assert_eq!(stepped.compute_weight(1.0, 45.0), 0.9);
+        assert_eq!(stepped.next_step(Seconds::new(45)), Some(Seconds::new(45)));
+        assert_eq!(stepped.next_step(Seconds::new(90)), None);
+    }
+
+    #[test]
+    fn test_bounds_reject_and_clamp_absurd_params() {
+        let bounds = DecayBounds::default();
+        let params = |rate, floor| DecayParams { rate, floor, steps: None };
+        assert_eq!(bounds.check(DecayType::Exponential, &params(Some(0.01), Some(0.2))), Ok(()));
+        assert!(matches!(
+            bounds.check(DecayType::Exponential, &params(Some(0.0), None)),
+            Err(DecayParamsError::RateOutOfBounds { .. })
+        ));
+        assert!(matches!(
+            bounds.check(DecayType::Linear, &params(None, Some(f64::NAN))),
+            Err(DecayParamsError::FloorOutOfBounds { .. })
+        ));
+        assert!(matches!(
+            bounds.check(DecayType::Stepped, &params(Some(0.01), None)),
+            Err(DecayParamsError::NotApplicable { param: "rate", .. })
+        ));
+        let steps = |steps: Vec<(f64, f64)>| DecayParams { steps: Some(steps), ..DecayParams::default() };
+        assert!(matches!(
+            bounds.check(DecayType::Stepped, &steps(vec![(60.0, 0.5), (30.0, 0.4)])),
+            Err(DecayParamsError::InvalidStep { at, .. }) if at == 30.0
+        ));
+        assert!(matches!(
+            bounds.check(DecayType::Stepped, &steps(vec![(60.0, 1.5)])),
+            Err(DecayParamsError::InvalidStep { .. })
+        ));
+        assert!(matches!(
+            bounds.check(DecayType::Stepped, &steps((1..=9).map(|i| (i as f64, 0.5)).collect())),
+            Err(DecayParamsError::TooManySteps { steps: 9, max: 8 })
+        ));
+
+        let resolved = bounds.resolve(DecayType::Linear, Some(&DecayParams { rate: Some(1e9), floor: Some(0.0), steps: Some(vec![]) }));
+        assert_eq!(resolved.params, params(Some(bounds.max_rate), Some(bounds.min_floor)));
+        assert_eq!(bounds.resolve(DecayType::Stepped, None), VoteDecay::from(DecayType::Stepped));
+    }
+}
@@ -0,0 +1,194 @@
+// src/dedup.rs
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::hashing;
+use crate::ids::{ProposalId, VoterId};
+use crate::vote::SignedVote;
+
+/// What to do when a voter already has a vote on the proposal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// Turn the second vote away and report it
+    #[default]
+    Reject,
+    /// Keep whichever vote is newer by version, then timestamp
+    ReplaceWithLatest,
+    /// Keep the first vote and quietly drop later ones
+    KeepFirst,
+}
+
+/// What `VoteDeduplicator::submit` did with a vote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    /// First vote from this voter on the proposal
+    Added,
+    /// The incoming vote displaced the one held before
+    Replaced,
+    /// The vote held before stays; the incoming one was dropped
+    Kept,
+    /// The incoming vote was refused as a duplicate
+    Rejected,
+}
+
+impl Resolution {
+    /// Whether the incoming vote is now the one counted
+    pub fn is_counted(self) -> bool {
+        matches!(self, Resolution::Added | Resolution::Replaced)
+    }
+}
+
+/// Audit record of two votes from one voter on one proposal, and how they were settled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateConflict {
+    pub voter_id: VoterId,
+    pub proposal_id: ProposalId,
+    /// `hashing::vote_hash` of the vote that is counted afterwards
+    pub kept: String,
+    pub discarded: String,
+    pub policy: DuplicatePolicy,
+    pub resolution: Resolution,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl fmt::Display for DuplicateConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on {}: {:?} under {:?}, kept {}, discarded {}",
+            self.voter_id, self.proposal_id, self.resolution, self.policy, self.kept, self.discarded
+        )
+    }
+}
+
+/// Holds one vote per voter and proposal, so duplicates are settled by `policy` before
+/// the votes reach `WeightEngine` or a report
+#[derive(Debug, Clone, Default)]
+pub struct VoteDeduplicator {
+    pub policy: DuplicatePolicy,
+    votes: BTreeMap<(ProposalId, VoterId), SignedVote>,
+    conflicts: Vec<DuplicateConflict>,
+}
+
+impl VoteDeduplicator {
+    pub fn new(policy: DuplicatePolicy) -> Self {
+        Self { policy, ..Self::default() }
+    }
+
+    /// Hold `vote` unless it duplicates one already held; conflicts are recorded as of `at`
+    pub fn submit(&mut self, vote: SignedVote, at: DateTime<Utc>) -> Resolution {
+        let key = (vote.proposal_id.clone(), vote.voter_id.clone());
+        let Some(held) = self.votes.get(&key) else {
+            self.votes.insert(key, vote);
+            return Resolution::Added;
+        };
+        let newer = (vote.version, vote.timestamp) > (held.version, held.timestamp);
+        let resolution = match self.policy {
+            DuplicatePolicy::Reject => Resolution::Rejected,
+            DuplicatePolicy::ReplaceWithLatest if newer => Resolution::Replaced,
+            DuplicatePolicy::ReplaceWithLatest | DuplicatePolicy::KeepFirst => Resolution::Kept,
+        };
+        let (held_hash, incoming_hash) = (hashing::vote_hash(held), hashing::vote_hash(&vote));
+        let (kept, discarded) = if resolution == Resolution::Replaced {
+            (incoming_hash, held_hash)
+        } else {
+            (held_hash, incoming_hash)
+        };
+        self.conflicts.push(DuplicateConflict {
+            voter_id: vote.voter_id.clone(),
+            proposal_id: vote.proposal_id.clone(),
+            kept,
+            discarded,
+            policy: self.policy,
+            resolution,
+            detected_at: at,
+        });
+        if resolution == Resolution::Replaced {
+            self.votes.insert(key, vote);
+        }
+        resolution
+    }
+
+    pub fn get(&self, voter_id: &VoterId, proposal_id: &ProposalId) -> Option<&SignedVote> {
+        self.votes.get(&(proposal_id.clone(), voter_id.clone()))
+    }
+
+    /// One vote per voter, in proposal then voter order
+    pub fn votes(&self) -> Vec<SignedVote> {
+        self.votes.values().cloned().collect()
+    }
+
+    pub fn votes_on(&self, proposal_id: &ProposalId) -> Vec<SignedVote> {
+        self.votes.values().filter(|v| v.proposal_id == *proposal_id).cloned().collect()
+    }
+
+    pub fn conflicts(&self) -> &[DuplicateConflict] {
+        &self.conflicts
+    }
+
+    pub fn len(&self) -> usize {
+        self.votes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.votes.is_empty()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::vote::{DecayType, VoteChoice};
+    use crate::weight_engine::WeightEngine;
+
+    #[test]
+    fn test_policies_settle_duplicates() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let first = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now - Duration::seconds(30), DecayType::Linear, &key);
+        let amended = first.amend(2.0, now - Duration::seconds(10), VoteChoice::Approve, &key);
+        let other = SignedVote::new("alice".parse().unwrap(), "p2".parse().unwrap(), 1.0, now, DecayType::Linear, &key);
+
+        let settle = |policy, order: [&SignedVote; 2]| {
+            let mut dedup = VoteDeduplicator::new(policy);
+            assert_eq!(dedup.submit(other.clone(), now), Resolution::Added);
+            assert_eq!(dedup.submit(order[0].clone(), now), Resolution::Added);
+            let resolution = dedup.submit(order[1].clone(), now);
+            (dedup, resolution)
+        };
+
+        let (rejecting, resolution) = settle(DuplicatePolicy::Reject, [&first, &amended]);
+        assert_eq!(resolution, Resolution::Rejected);
+        assert_eq!(rejecting.get(&first.voter_id, &first.proposal_id).map(|v| v.version), Some(0));
+
+        let (latest, resolution) = settle(DuplicatePolicy::ReplaceWithLatest, [&first, &amended]);
+        assert_eq!(resolution, Resolution::Replaced);
+        assert_eq!(latest.get(&first.voter_id, &first.proposal_id).map(|v| v.version), Some(1));
+        let conflict = &latest.conflicts()[0];
+        assert_eq!((conflict.kept.clone(), conflict.discarded.clone()), (hashing::vote_hash(&amended), hashing::vote_hash(&first)));
+        // An older version arriving late does not displace the newer one
+        let (late, resolution) = settle(DuplicatePolicy::ReplaceWithLatest, [&amended, &first]);
+        assert_eq!(resolution, Resolution::Kept);
+        assert_eq!(late.get(&first.voter_id, &first.proposal_id).map(|v| v.version), Some(1));
+
+        let (keep_first, resolution) = settle(DuplicatePolicy::KeepFirst, [&first, &amended]);
+        assert_eq!(resolution, Resolution::Kept);
+        assert!(!resolution.is_counted());
+        assert_eq!(keep_first.conflicts().len(), 1);
+
+        // Only one vote per voter and proposal reaches the weight engine
+        let p1 = latest.votes_on(&first.proposal_id);
+        assert_eq!(p1.len(), 1);
+        let score = WeightEngine::new().score(&latest.votes(), None);
+        assert_eq!(score.len(), 2);
+    }
+}
@@ -0,0 +1,618 @@
+// src/delegation.rs
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ids::{ProposalId, VoterId};
+use crate::units::{Seconds, Weight};
+use crate::vote::{
+    DecayType, SignedVote, VoteChoice, deserialize_public_key, deserialize_signature, serialize_public_key,
+    serialize_signature,
+};
+use crate::weight_engine::decay_weight;
+
+/// Longest chain of hops followed before a delegation is refused
+pub const MAX_DELEGATION_DEPTH: usize = 8;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DelegationError {
+    #[error("{0} cannot delegate to themselves")]
+    SelfDelegation(VoterId),
+    #[error("Delegation from {0} has an invalid signature")]
+    InvalidSignature(VoterId),
+    #[error("Delegation from {0} has an empty time range")]
+    EmptyWindow(VoterId),
+    #[error("Delegated weight from {0} must be positive and finite")]
+    InvalidWeight(VoterId),
+    #[error("Delegation cycle: {}", format_path(.0))]
+    Cycle(Vec<VoterId>),
+    #[error("Delegation chain exceeds {max} hops: {}", format_path(.path))]
+    TooDeep { path: Vec<VoterId>, max: usize },
+}
+
+fn format_path(path: &[VoterId]) -> String {
+    path.iter().map(VoterId::as_str).collect::<Vec<_>>().join(" -> ")
+}
+
+/// What a delegation applies to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegationScope {
+    Proposal(ProposalId),
+    /// Every proposal voted on in `[from, until)`
+    Window { from: DateTime<Utc>, until: DateTime<Utc> },
+}
+
+impl DelegationScope {
+    pub fn covers(&self, proposal_id: &ProposalId, at: DateTime<Utc>) -> bool {
+        match self {
+            DelegationScope::Proposal(id) => id == proposal_id,
+            DelegationScope::Window { from, until } => *from <= at && at < *until,
+        }
+    }
+}
+
+impl fmt::Display for DelegationScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DelegationScope::Proposal(id) => write!(f, "proposal/{}", id),
+            DelegationScope::Window { from, until } => write!(f, "window/{}/{}", from.to_rfc3339(), until.to_rfc3339()),
+        }
+    }
+}
+
+/// A voter's signed hand-over of `weight` voting power to `delegate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delegation {
+    pub delegator: VoterId,
+    pub delegate: VoterId,
+    pub scope: DelegationScope,
+    pub weight: f64,
+    pub issued_at: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    pub signature: Signature,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    pub public_key: VerifyingKey,
+}
+
+/// Bytes a delegator signs
+pub fn delegation_message(
+    delegator: &str,
+    delegate: &str,
+    scope: &DelegationScope,
+    weight: f64,
+    issued_at: DateTime<Utc>,
+) -> String {
+    format!("delegate:{}:{}:{}:{}:{}", delegator, delegate, scope, weight, issued_at.to_rfc3339())
+}
+
+impl Delegation {
+    pub fn new(
+        delegator: VoterId,
+        delegate: VoterId,
+        scope: DelegationScope,
+        weight: f64,
+        issued_at: DateTime<Utc>,
+        key: &SigningKey,
+    ) -> Self {
+        let message = delegation_message(&delegator, &delegate, &scope, weight, issued_at);
+        Self {
+            signature: key.sign(message.as_bytes()),
+            public_key: key.verifying_key(),
+            delegator,
+            delegate,
+            scope,
+            weight,
+            issued_at,
+        }
+    }
+
+    pub fn verify(&self) -> Result<(), DelegationError> {
+        if self.delegator == self.delegate {
+            return Err(DelegationError::SelfDelegation(self.delegator.clone()));
+        }
+        if !self.weight.is_finite() || self.weight <= 0.0 {
+            return Err(DelegationError::InvalidWeight(self.delegator.clone()));
+        }
+        if let DelegationScope::Window { from, until } = self.scope
+            && until <= from
+        {
+            return Err(DelegationError::EmptyWindow(self.delegator.clone()));
+        }
+        let message = delegation_message(&self.delegator, &self.delegate, &self.scope, self.weight, self.issued_at);
+        self.public_key
+            .verify(message.as_bytes(), &self.signature)
+            .map_err(|_| DelegationError::InvalidSignature(self.delegator.clone()))
+    }
+}
+
+/// How weight travelled from its original voter to the vote it counted with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineageRoute {
+    Delegation,
+    Fallback,
+}
+
+/// The full route of one voter's routed weight, kept so contested delegations can be
+/// audited after the tally
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoteLineage {
+    /// The original voter first, then every delegate passed through; the last entry
+    /// is the voter whose vote the weight counted with
+    pub path: Vec<VoterId>,
+    pub route: LineageRoute,
+    /// Weight handed over by the original voter, before decay
+    pub weight: f64,
+}
+
+impl VoteLineage {
+    pub fn origin(&self) -> &VoterId {
+        &self.path[0]
+    }
+
+    pub fn recipient(&self) -> &VoterId {
+        &self.path[self.path.len() - 1]
+    }
+}
+
+impl fmt::Display for VoteLineage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let route = match self.route {
+            LineageRoute::Delegation => "delegated",
+            LineageRoute::Fallback => "fallback",
+        };
+        write!(f, "{} ({} {:.4})", format_path(&self.path), route, self.weight)
+    }
+}
+
+/// Power that reached one delegate, and the chains that could not be followed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DelegatedPower {
+    pub weight: f64,
+    /// Original delegators whose power arrived, in id order
+    pub delegators: Vec<VoterId>,
+    /// The chain each delegator's power followed, in the same order
+    pub lineage: Vec<VoteLineage>,
+    /// Chains through the delegate that were cut by a cycle or the depth limit
+    pub rejected: Vec<DelegationError>,
+}
+
+/// Verified delegations, resolved per proposal. A voter who votes directly keeps their
+/// own power, so a chain stops at the first voter on it who cast a vote.
+#[derive(Debug, Clone)]
+pub struct DelegationGraph {
+    delegations: Vec<Delegation>,
+    pub max_depth: usize,
+}
+
+impl Default for DelegationGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DelegationGraph {
+    pub fn new() -> Self {
+        Self {
+            delegations: Vec::new(),
+            max_depth: MAX_DELEGATION_DEPTH,
+        }
+    }
+
+    pub fn add(&mut self, delegation: Delegation) -> Result<(), DelegationError> {
+        delegation.verify()?;
+        self.delegations.push(delegation);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.delegations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.delegations.is_empty()
+    }
+
+    /// The delegation `voter` has in force for `proposal_id` at `at`. A proposal-scoped
+    /// delegation beats a time window; otherwise the most recently issued one wins.
+    pub fn active(&self, voter: &VoterId, proposal_id: &ProposalId, at: DateTime<Utc>) -> Option<&Delegation> {
+        self.delegations
+            .iter()
+            .filter(|d| d.delegator == *voter && d.scope.covers(proposal_id, at))
+            .max_by_key(|d| (matches!(d.scope, DelegationScope::Proposal(_)), d.issued_at))
+    }
+
+    /// Follow `voter`'s delegations to the first voter in `voted`. `Ok(None)` when the
+    /// chain ends at someone who did not vote, so the power goes unused.
+    pub fn resolve(
+        &self,
+        voter: &VoterId,
+        proposal_id: &ProposalId,
+        at: DateTime<Utc>,
+        voted: &HashSet<VoterId>,
+    ) -> Result<Option<VoterId>, DelegationError> {
+        Ok(self.resolve_path(voter, proposal_id, at, voted)?.and_then(|path| path.last().cloned()))
+    }
+
+    /// Like `resolve`, returning the whole chain from `voter` to the voter it reached
+    pub fn resolve_path(
+        &self,
+        voter: &VoterId,
+        proposal_id: &ProposalId,
+        at: DateTime<Utc>,
+        voted: &HashSet<VoterId>,
+    ) -> Result<Option<Vec<VoterId>>, DelegationError> {
+        let mut path = vec![voter.clone()];
+        let mut current = voter;
+        while let Some(delegation) = self.active(current, proposal_id, at) {
+            let next = &delegation.delegate;
+            let looped = path.contains(next);
+            path.push(next.clone());
+            if looped {
+                return Err(DelegationError::Cycle(path));
+            }
+            if path.len() - 1 > self.max_depth {
+                return Err(DelegationError::TooDeep { path, max: self.max_depth });
+            }
+            if voted.contains(next) {
+                return Ok(Some(path));
+            }
+            current = next;
+        }
+        Ok(None)
+    }
+
+    /// Power delegated to `delegate` on `proposal_id` by voters who did not vote themselves
+    pub fn delegated_to(
+        &self,
+        delegate: &VoterId,
+        proposal_id: &ProposalId,
+        at: DateTime<Utc>,
+        voted: &HashSet<VoterId>,
+    ) -> DelegatedPower {
+        let delegators: BTreeSet<&VoterId> = self
+            .delegations
+            .iter()
+            .map(|d| &d.delegator)
+            .filter(|v| !voted.contains(*v))
+            .collect();
+
+        let mut power = DelegatedPower::default();
+        for delegator in delegators {
+            let Some(delegation) = self.active(delegator, proposal_id, at) else {
+                continue;
+            };
+            match self.resolve_path(delegator, proposal_id, at, voted) {
+                Ok(Some(path)) if path.last() == Some(delegate) => {
+                    power.weight += delegation.weight;
+                    power.delegators.push(delegator.clone());
+                    power.lineage.push(VoteLineage { path, route: LineageRoute::Delegation, weight: delegation.weight });
+                }
+                Err(
+                    ref e @ (DelegationError::Cycle(ref path) | DelegationError::TooDeep { ref path, .. }),
+                ) if path.contains(delegate) => power.rejected.push(e.clone()),
+                _ => {}
+            }
+        }
+        power
+    }
+}
+
+/// A voter's signed standing instruction: if they abstain or miss the window on a
+/// proposal in `scope`, `weight` goes to `delegate` when the proposal closes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FallbackInstruction {
+    pub voter: VoterId,
+    pub delegate: VoterId,
+    pub scope: DelegationScope,
+    pub weight: f64,
+    pub decay_model: DecayType,
+    pub issued_at: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    pub signature: Signature,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    pub public_key: VerifyingKey,
+}
+
+/// Bytes a voter signs to register a fallback delegate
+pub fn fallback_message(
+    voter: &str,
+    delegate: &str,
+    scope: &DelegationScope,
+    weight: f64,
+    decay_model: DecayType,
+    issued_at: DateTime<Utc>,
+) -> String {
+    format!("fallback:{}:{}:{}:{}:{:?}:{}", voter, delegate, scope, weight, decay_model, issued_at.to_rfc3339())
+}
+
+impl FallbackInstruction {
+    pub fn new(
+        voter: VoterId,
+        delegate: VoterId,
+        scope: DelegationScope,
+        weight: f64,
+        decay_model: DecayType,
+        issued_at: DateTime<Utc>,
+        key: &SigningKey,
+    ) -> Self {
+        let message = fallback_message(&voter, &delegate, &scope, weight, decay_model, issued_at);
+        Self {
+            signature: key.sign(message.as_bytes()),
+            public_key: key.verifying_key(),
+            voter,
+            delegate,
+            scope,
+            weight,
+            decay_model,
+            issued_at,
+        }
+    }
+
+    pub fn verify(&self) -> Result<(), DelegationError> {
+        if self.voter == self.delegate {
+            return Err(DelegationError::SelfDelegation(self.voter.clone()));
+        }
+        if !self.weight.is_finite() || self.weight <= 0.0 {
+            return Err(DelegationError::InvalidWeight(self.voter.clone()));
+        }
+        if let DelegationScope::Window { from, until } = self.scope
+            && until <= from
+        {
+            return Err(DelegationError::EmptyWindow(self.voter.clone()));
+        }
+        let message =
+            fallback_message(&self.voter, &self.delegate, &self.scope, self.weight, self.decay_model, self.issued_at);
+        self.public_key
+            .verify(message.as_bytes(), &self.signature)
+            .map_err(|_| DelegationError::InvalidSignature(self.voter.clone()))
+    }
+}
+
+/// Why a fallback fired
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackTrigger {
+    Abstained,
+    Absent,
+}
+
+/// Weight routed to a fallback delegate at close, decayed from the proposal opening
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackUse {
+    pub voter_id: VoterId,
+    pub delegate: VoterId,
+    pub trigger: FallbackTrigger,
+    /// The delegate's choice, which the routed weight counts toward
+    pub choice: VoteChoice,
+    pub original_weight: f64,
+    pub decayed_weight: f64,
+}
+
+impl FallbackUse {
+    pub fn lineage(&self) -> VoteLineage {
+        VoteLineage {
+            path: vec![self.voter_id.clone(), self.delegate.clone()],
+            route: LineageRoute::Fallback,
+            weight: self.original_weight,
+        }
+    }
+}
+
+/// Verified fallback instructions; for each voter the latest issued one covering a
+/// proposal applies. Fallbacks do not chain: a delegate who abstained or did not vote
+/// leaves the weight unused.
+#[derive(Debug, Clone, Default)]
+pub struct FallbackRegistry {
+    instructions: Vec<FallbackInstruction>,
+}
+
+impl FallbackRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, instruction: FallbackInstruction) -> Result<(), DelegationError> {
+        instruction.verify()?;
+        self.instructions.push(instruction);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    /// The instruction `voter` has in force for a proposal opened at `opened_at`
+    pub fn active(
+        &self,
+        voter: &VoterId,
+        proposal_id: &ProposalId,
+        opened_at: DateTime<Utc>,
+    ) -> Option<&FallbackInstruction> {
+        self.instructions
+            .iter()
+            .filter(|i| i.voter == *voter && i.scope.covers(proposal_id, opened_at))
+            .max_by_key(|i| (matches!(i.scope, DelegationScope::Proposal(_)), i.issued_at))
+    }
+
+    /// Fallbacks that fire when `proposal_id` closes at `closed_at`, given the votes
+    /// counted on it, in voter id order
+    pub fn resolve(
+        &self,
+        proposal_id: &ProposalId,
+        opened_at: DateTime<Utc>,
+        closed_at: DateTime<Utc>,
+        counted: &[&SignedVote],
+    ) -> Vec<FallbackUse> {
+        let choices: BTreeMap<&VoterId, VoteChoice> = counted.iter().map(|v| (&v.voter_id, v.choice)).collect();
+        let voters: BTreeSet<&VoterId> = self.instructions.iter().map(|i| &i.voter).collect();
+        let age = Seconds::between(opened_at, closed_at);
+
+        let mut uses = Vec::new();
+        for voter in voters {
+            let trigger = match choices.get(voter) {
+                None => FallbackTrigger::Absent,
+                Some(VoteChoice::Abstain) => FallbackTrigger::Abstained,
+                Some(_) => continue,
+            };
+            let Some(instruction) = self.active(voter, proposal_id, opened_at) else {
+                continue;
+            };
+            let choice = match choices.get(&instruction.delegate) {
+                Some(VoteChoice::Abstain) | None => continue,
+                Some(choice) => *choice,
+            };
+            uses.push(FallbackUse {
+                voter_id: voter.clone(),
+                delegate: instruction.delegate.clone(),
+                trigger,
+                choice,
+                original_weight: instruction.weight,
+                decayed_weight: decay_weight(instruction.decay_model, Weight::saturating(instruction.weight), age).get(),
+            });
+        }
+        uses
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn id(s: &str) -> VoterId {
+        s.parse().unwrap()
+    }
+
+    fn delegate(graph: &mut DelegationGraph, from: &str, to: &str, scope: DelegationScope, at: DateTime<Utc>) {
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        graph.add(Delegation::new(id(from), id(to), scope, 1.0, at, &key)).unwrap();
+    }
+
+    #[test]
+    fn test_signature_and_shape_checks() {
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let now = Utc::now();
+        let p1 = DelegationScope::Proposal("p1".parse().unwrap());
+
+        let mut tampered = Delegation::new(id("alice"), id("bob"), p1.clone(), 1.0, now, &key);
+        tampered.weight = 5.0;
+        assert_eq!(tampered.verify(), Err(DelegationError::InvalidSignature(id("alice"))));
+
+        let own = Delegation::new(id("alice"), id("alice"), p1, 1.0, now, &key);
+        assert_eq!(own.verify(), Err(DelegationError::SelfDelegation(id("alice"))));
+
+        let empty = DelegationScope::Window { from: now, until: now };
+        assert!(DelegationGraph::new().add(Delegation::new(id("alice"), id("bob"), empty, 1.0, now, &key)).is_err());
+    }
+
+    #[test]
+    fn test_chains_resolve_to_first_voter() {
+        let now = Utc::now();
+        let p1: ProposalId = "p1".parse().unwrap();
+        let window = DelegationScope::Window { from: now - Duration::days(1), until: now + Duration::days(1) };
+        let mut graph = DelegationGraph::new();
+        delegate(&mut graph, "alice", "bob", window.clone(), now);
+        delegate(&mut graph, "bob", "carol", window.clone(), now);
+        delegate(&mut graph, "dave", "erin", window, now);
+        delegate(&mut graph, "dave", "carol", DelegationScope::Proposal(p1.clone()), now - Duration::hours(1));
+
+        let voted: HashSet<VoterId> = [id("carol")].into();
+        assert_eq!(graph.resolve(&id("alice"), &p1, now, &voted), Ok(Some(id("carol"))));
+        let power = graph.delegated_to(&id("carol"), &p1, now, &voted);
+        assert_eq!(power.delegators, vec![id("alice"), id("bob"), id("dave")]);
+        assert_eq!(power.weight, 3.0);
+        assert_eq!(power.lineage[0].path, vec![id("alice"), id("bob"), id("carol")]);
+        assert_eq!(power.lineage[0].to_string(), "alice -> bob -> carol (delegated 1.0000)");
+        assert_eq!(power.lineage[2].origin(), &id("dave"));
+
+        // Bob voting keeps his own power and catches Alice's
+        let voted: HashSet<VoterId> = [id("bob"), id("carol")].into();
+        assert_eq!(graph.delegated_to(&id("bob"), &p1, now, &voted).delegators, vec![id("alice")]);
+        assert_eq!(graph.delegated_to(&id("carol"), &p1, now, &voted).delegators, vec![id("dave")]);
+
+        // Outside the window only Dave's proposal-scoped delegation applies
+        let later = now + Duration::days(2);
+        assert_eq!(graph.delegated_to(&id("carol"), &p1, later, &voted).delegators, vec![id("dave")]);
+    }
+
+    #[test]
+    fn test_cycles_and_depth_limit() {
+        let now = Utc::now();
+        let p1: ProposalId = "p1".parse().unwrap();
+        let scope = DelegationScope::Proposal(p1.clone());
+        let mut graph = DelegationGraph::new();
+        delegate(&mut graph, "alice", "bob", scope.clone(), now);
+        delegate(&mut graph, "bob", "alice", scope.clone(), now);
+        delegate(&mut graph, "carol", "alice", scope.clone(), now);
+
+        let voted = HashSet::new();
+        assert_eq!(
+            graph.resolve(&id("carol"), &p1, now, &voted),
+            Err(DelegationError::Cycle(vec![id("carol"), id("alice"), id("bob"), id("alice")]))
+        );
+
+        let mut chain = DelegationGraph::new();
+        chain.max_depth = 2;
+        delegate(&mut chain, "v0", "v1", scope.clone(), now);
+        delegate(&mut chain, "v1", "v2", scope.clone(), now);
+        delegate(&mut chain, "v2", "v3", scope, now);
+        let voted: HashSet<VoterId> = [id("v3")].into();
+        assert!(matches!(chain.resolve(&id("v0"), &p1, now, &voted), Err(DelegationError::TooDeep { max: 2, .. })));
+        let power = chain.delegated_to(&id("v3"), &p1, now, &voted);
+        assert_eq!(power.delegators, vec![id("v1"), id("v2")]);
+        assert_eq!(power.rejected.len(), 1);
+    }
+
+    #[test]
+    fn test_fallbacks_fire_on_abstention_or_absence() {
+        let now = Utc::now();
+        let opened = now - Duration::seconds(120);
+        let p1: ProposalId = "p1".parse().unwrap();
+        let scope = DelegationScope::Window { from: now - Duration::days(1), until: now + Duration::days(1) };
+        let key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let instruct = |voter: &str, delegate: &str| {
+            FallbackInstruction::new(id(voter), id(delegate), scope.clone(), 1.0, DecayType::Linear, now, &key)
+        };
+        let mut registry = FallbackRegistry::new();
+        for (voter, delegate) in [("alice", "carol"), ("bob", "carol"), ("dave", "erin"), ("frank", "carol")] {
+            registry.register(instruct(voter, delegate)).unwrap();
+        }
+        let mut forged = instruct("gina", "carol");
+        forged.weight = 9.0;
+        assert_eq!(registry.register(forged), Err(DelegationError::InvalidSignature(id("gina"))));
+
+        let cast = |voter: &str, choice: VoteChoice| {
+            SignedVote::new_with_choice(id(voter), p1.clone(), 1.0, now - Duration::seconds(60), DecayType::Linear, choice, &key)
+        };
+        let votes = [
+            cast("bob", VoteChoice::Abstain),
+            cast("carol", VoteChoice::Reject),
+            cast("erin", VoteChoice::Abstain),
+            cast("frank", VoteChoice::Approve),
+        ];
+        let counted: Vec<&SignedVote> = votes.iter().collect();
+        let uses = registry.resolve(&p1, opened, now, &counted);
+
+        // Alice was absent, Bob abstained; Dave's delegate abstained and Frank voted himself
+        let summary: Vec<(&str, FallbackTrigger, VoteChoice)> =
+            uses.iter().map(|u| (u.voter_id.as_str(), u.trigger, u.choice)).collect();
+        assert_eq!(
+            summary,
+            vec![("alice", FallbackTrigger::Absent, VoteChoice::Reject), ("bob", FallbackTrigger::Abstained, VoteChoice::Reject)]
+        );
+        // Decayed from the proposal opening, not from when the delegate voted
+        assert!((uses[0].decayed_weight - (1.0 - 0.001 * 120.0)).abs() < 1e-9);
+    }
+}
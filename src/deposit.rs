@@ -0,0 +1,229 @@
+// src/deposit.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::tally::Tally;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DepositError {
+    #[error("Deposit of {offered} is below the minimum of {required}")]
+    BelowMinimum { required: f64, offered: f64 },
+    #[error("Proposal {0} already has a deposit")]
+    AlreadyDeposited(String),
+    #[error("No deposit for proposal {0}")]
+    UnknownProposal(String),
+    #[error("Deposit for proposal {0} is already settled")]
+    AlreadySettled(String),
+}
+
+/// How much a proposer must lock, and how much participation earns it back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepositConfig {
+    pub min_deposit: f64,
+    /// Turnout fraction (cast weight over eligible weight) needed for a refund
+    pub min_participation: f64,
+}
+
+impl DepositConfig {
+    pub fn new() -> Self {
+        Self {
+            min_deposit: 10.0,
+            min_participation: 0.2,
+        }
+    }
+}
+
+impl Default for DepositConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositStatus {
+    Locked,
+    Returned,
+    Forfeited,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Deposit {
+    pub proposal_id: String,
+    pub proposer: String,
+    pub amount: f64,
+    pub locked_at: DateTime<Utc>,
+    pub status: DepositStatus,
+}
+
+/// One movement of a deposit; `participation` is set on settlement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepositEvent {
+    pub proposal_id: String,
+    pub proposer: String,
+    pub amount: f64,
+    pub status: DepositStatus,
+    pub participation: Option<f64>,
+    pub at: DateTime<Utc>,
+}
+
+/// Filter for `DepositLedger::events`; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct DepositQuery {
+    pub proposal_id: Option<String>,
+    pub proposer: Option<String>,
+    pub status: Option<DepositStatus>,
+}
+
+impl DepositQuery {
+    pub fn matches(&self, event: &DepositEvent) -> bool {
+        self.proposal_id.as_ref().is_none_or(|p| *p == event.proposal_id)
+            && self.proposer.as_ref().is_none_or(|p| *p == event.proposer)
+            && self.status.is_none_or(|s| s == event.status)
+    }
+}
+
+/// Proposal deposits: locked on submission, returned if the proposal draws enough
+/// participation, forfeited otherwise. Every flow is kept as an event.
+#[derive(Debug, Default)]
+pub struct DepositLedger {
+    pub config: DepositConfig,
+    deposits: BTreeMap<String, Deposit>,
+    events: Vec<DepositEvent>,
+}
+
+impl DepositLedger {
+    pub fn new(config: DepositConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Lock `amount` from `proposer` against `proposal_id`
+    pub fn lock(
+        &mut self,
+        proposal_id: &str,
+        proposer: &str,
+        amount: f64,
+        at: DateTime<Utc>,
+    ) -> Result<&Deposit, DepositError> {
+        if amount.is_nan() || amount < self.config.min_deposit {
+            return Err(DepositError::BelowMinimum {
+                required: self.config.min_deposit,
+                offered: amount,
+            });
+        }
+        if self.deposits.contains_key(proposal_id) {
+            return Err(DepositError::AlreadyDeposited(proposal_id.to_string()));
+        }
+        let deposit = Deposit {
+            proposal_id: proposal_id.to_string(),
+            proposer: proposer.to_string(),
+            amount,
+            locked_at: at,
+            status: DepositStatus::Locked,
+        };
+        self.log(&deposit, None, at);
+        Ok(self.deposits.entry(proposal_id.to_string()).or_insert(deposit))
+    }
+
+    /// Return or forfeit the deposit once the proposal closes with `participation`
+    pub fn settle(&mut self, proposal_id: &str, participation: f64, at: DateTime<Utc>) -> Result<DepositStatus, DepositError> {
+        let deposit = self
+            .deposits
+            .get_mut(proposal_id)
+            .ok_or_else(|| DepositError::UnknownProposal(proposal_id.to_string()))?;
+        if deposit.status != DepositStatus::Locked {
+            return Err(DepositError::AlreadySettled(proposal_id.to_string()));
+        }
+        deposit.status = if participation >= self.config.min_participation {
+            DepositStatus::Returned
+        } else {
+            DepositStatus::Forfeited
+        };
+        let deposit = deposit.clone();
+        self.log(&deposit, Some(participation), at);
+        Ok(deposit.status)
+    }
+
+    /// `settle` using the tally's turnout fraction
+    pub fn settle_tally(&mut self, proposal_id: &str, tally: &Tally, at: DateTime<Utc>) -> Result<DepositStatus, DepositError> {
+        self.settle(proposal_id, tally.turnout_fraction(), at)
+    }
+
+    fn log(&mut self, deposit: &Deposit, participation: Option<f64>, at: DateTime<Utc>) {
+        self.events.push(DepositEvent {
+            proposal_id: deposit.proposal_id.clone(),
+            proposer: deposit.proposer.clone(),
+            amount: deposit.amount,
+            status: deposit.status,
+            participation,
+            at,
+        });
+    }
+
+    pub fn deposit(&self, proposal_id: &str) -> Option<&Deposit> {
+        self.deposits.get(proposal_id)
+    }
+
+    /// Deposit events matching `query`, oldest first
+    pub fn events(&self, query: &DepositQuery) -> Vec<&DepositEvent> {
+        self.events.iter().filter(|e| query.matches(e)).collect()
+    }
+
+    /// Sum of deposits currently in `status`
+    pub fn total(&self, status: DepositStatus) -> f64 {
+        self.deposits.values().filter(|d| d.status == status).map(|d| d.amount).sum()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deposits_return_or_forfeit_on_participation() {
+        let now = Utc::now();
+        let mut ledger = DepositLedger::new(DepositConfig::default());
+        assert_eq!(
+            ledger.lock("p1", "alice", 5.0, now).unwrap_err(),
+            DepositError::BelowMinimum { required: 10.0, offered: 5.0 }
+        );
+        ledger.lock("p1", "alice", 10.0, now).unwrap();
+        ledger.lock("p2", "alice", 12.0, now).unwrap();
+        ledger.lock("p3", "bob", 10.0, now).unwrap();
+        assert_eq!(ledger.lock("p1", "bob", 10.0, now).unwrap_err(), DepositError::AlreadyDeposited("p1".to_string()));
+        assert_eq!(ledger.total(DepositStatus::Locked), 32.0);
+
+        let mut busy = Tally::new(10.0);
+        busy.approve(3.0);
+        assert_eq!(ledger.settle_tally("p1", &busy, now), Ok(DepositStatus::Returned));
+        assert_eq!(ledger.settle("p2", 0.05, now), Ok(DepositStatus::Forfeited));
+        assert_eq!(ledger.settle("p2", 0.5, now), Err(DepositError::AlreadySettled("p2".to_string())));
+        assert_eq!(ledger.settle("p9", 0.5, now), Err(DepositError::UnknownProposal("p9".to_string())));
+        assert_eq!(ledger.total(DepositStatus::Forfeited), 12.0);
+        assert_eq!(ledger.deposit("p3").unwrap().status, DepositStatus::Locked);
+    }
+
+    #[test]
+    fn test_deposit_flows_are_queryable() {
+        let now = Utc::now();
+        let mut ledger = DepositLedger::new(DepositConfig::default());
+        ledger.lock("p1", "alice", 10.0, now).unwrap();
+        ledger.lock("p2", "bob", 10.0, now).unwrap();
+        ledger.settle("p1", 0.1, now).unwrap();
+
+        let alice = DepositQuery { proposer: Some("alice".to_string()), ..DepositQuery::default() };
+        let flows: Vec<DepositStatus> = ledger.events(&alice).iter().map(|e| e.status).collect();
+        assert_eq!(flows, vec![DepositStatus::Locked, DepositStatus::Forfeited]);
+        let forfeited = DepositQuery { status: Some(DepositStatus::Forfeited), ..DepositQuery::default() };
+        assert_eq!(ledger.events(&forfeited)[0].participation, Some(0.1));
+        assert_eq!(ledger.events(&DepositQuery::default()).len(), 3);
+    }
+}
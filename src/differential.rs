@@ -0,0 +1,215 @@
+// src/differential.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::fixed::{self, Fixed};
+use crate::threshold::ThresholdEscalator;
+use crate::trust::TrustSource;
+use crate::units::Seconds;
+use crate::vote::{DecayType, ProposalType, SignedVote};
+use crate::weight_engine::WeightEngine;
+
+/// Largest float/fixed tally difference treated as agreement
+pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+/// Knobs for generating vote streams
+#[derive(Debug, Clone)]
+pub struct DifferentialConfig {
+    pub seed: u64,
+    pub cases: usize,
+    pub max_votes: usize,
+    pub max_age_secs: i64,
+    pub max_weight: f64,
+    pub tolerance: f64,
+    pub now: DateTime<Utc>,
+}
+
+impl Default for DifferentialConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            cases: 200,
+            max_votes: 12,
+            max_age_secs: 600,
+            max_weight: 2.0,
+            tolerance: DEFAULT_TOLERANCE,
+            now: crate::vectors::vector_epoch(),
+        }
+    }
+}
+
+/// One vote stream evaluated through both numeric paths
+#[derive(Debug, Clone, Serialize)]
+pub struct DifferentialCase {
+    pub index: usize,
+    pub votes: usize,
+    pub dominant_decay: String,
+    pub mean_age_secs: i64,
+    pub threshold: f64,
+    pub float_weight: f64,
+    pub fixed_weight: f64,
+    pub float_passed: bool,
+    pub fixed_passed: bool,
+}
+
+impl DifferentialCase {
+    pub fn difference(&self) -> f64 {
+        (self.float_weight - self.fixed_weight).abs()
+    }
+
+    pub fn flipped(&self) -> bool {
+        self.float_passed != self.fixed_passed
+    }
+
+    /// Within `tolerance` of the threshold, where rounding alone could flip the outcome
+    pub fn near_threshold(&self, tolerance: f64) -> bool {
+        (self.float_weight - self.threshold).abs() <= tolerance
+    }
+}
+
+/// Outcome of a differential run; `regions` counts sensitive cases by
+/// dominant decay model and 60-second mean-age bucket
+#[derive(Debug, Clone, Serialize)]
+pub struct DifferentialReport {
+    pub seed: u64,
+    pub cases: usize,
+    pub tolerance: f64,
+    pub max_difference: f64,
+    pub disagreements: Vec<DifferentialCase>,
+    pub sensitive: Vec<DifferentialCase>,
+    pub regions: BTreeMap<String, usize>,
+}
+
+impl DifferentialReport {
+    /// Both paths agree within tolerance and no outcome flipped
+    pub fn agrees(&self) -> bool {
+        self.disagreements.is_empty()
+    }
+}
+
+/// Tally the same generated vote streams through `WeightEngine` (f64) and
+/// `fixed::decayed_weight`, comparing totals and pass/fail against the escalated threshold
+pub fn run(config: &DifferentialConfig, trust: &dyn TrustSource) -> DifferentialReport {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let key = SignedVote::generate_keypair();
+    let decays = [DecayType::Linear, DecayType::Exponential, DecayType::Stepped];
+    let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+
+    let mut report = DifferentialReport {
+        seed: config.seed,
+        cases: config.cases,
+        tolerance: config.tolerance,
+        max_difference: 0.0,
+        disagreements: Vec::new(),
+        sensitive: Vec::new(),
+        regions: BTreeMap::new(),
+    };
+
+    for index in 0..config.cases {
+        let count = rng.gen_range(1..=config.max_votes.max(1));
+        let votes: Vec<SignedVote> = (0..count)
+            .map(|i| {
+                let age = rng.gen_range(0..=config.max_age_secs.max(0));
+                SignedVote::new(
+                    format!("validator_{:03}", rng.gen_range(0..4) + i * 4).parse().expect("valid id"),
+                    "proposal_diff".parse().expect("valid id"),
+                    rng.gen_range(0.0..config.max_weight),
+                    config.now - Duration::seconds(age),
+                    decays[rng.gen_range(0..decays.len())],
+                    &key,
+                )
+            })
+            .collect();
+
+        let mut engine = WeightEngine::new();
+        let float_weight: f64 = votes.iter().map(|v| engine.calculate_weight(v, config.now, Some(trust))).sum();
+        let fixed_weight: Fixed = votes
+            .iter()
+            .map(|v| {
+                let original = Fixed::from_f64(v.original_weight);
+                let decayed = fixed::decayed_weight(v.decay_model, original, Seconds::between(v.timestamp, config.now));
+                decayed * Fixed::from_f64(trust.get_bonus(&v.voter_id))
+            })
+            .sum();
+
+        let elapsed = Seconds::new(rng.gen_range(0..=config.max_age_secs.max(0)));
+        let threshold = escalator.threshold_at(elapsed);
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for v in &votes {
+            *counts.entry(format!("{:?}", v.decay_model)).or_insert(0) += 1;
+        }
+        let dominant_decay = counts
+            .iter()
+            .max_by_key(|(_, n)| **n)
+            .map(|(d, _)| d.clone())
+            .unwrap_or_default();
+        let mean_age_secs =
+            votes.iter().map(|v| (config.now - v.timestamp).num_seconds()).sum::<i64>() / count as i64;
+
+        let case = DifferentialCase {
+            index,
+            votes: count,
+            dominant_decay,
+            mean_age_secs,
+            threshold,
+            float_weight,
+            fixed_weight: fixed_weight.to_f64(),
+            float_passed: float_weight >= threshold,
+            fixed_passed: fixed_weight >= Fixed::from_f64(threshold),
+        };
+
+        report.max_difference = report.max_difference.max(case.difference());
+        if case.flipped() || case.near_threshold(config.tolerance) {
+            let region = format!("{}/{}s", case.dominant_decay, case.mean_age_secs / 60 * 60);
+            *report.regions.entry(region).or_insert(0) += 1;
+            report.sensitive.push(case.clone());
+        }
+        if case.difference() > config.tolerance || case.flipped() {
+            report.disagreements.push(case);
+        }
+    }
+    report
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trust::TrustEngine;
+
+    #[test]
+    fn test_paths_agree_on_seeded_streams() {
+        let report = run(
+            &DifferentialConfig {
+                seed: 3,
+                cases: 100,
+                ..DifferentialConfig::default()
+            },
+            &TrustEngine::new(),
+        );
+        assert!(report.agrees(), "{:?}", report.disagreements);
+        assert!(report.max_difference < DEFAULT_TOLERANCE);
+    }
+
+    #[test]
+    fn test_near_threshold_cases_are_flagged() {
+        let case = DifferentialCase {
+            index: 0,
+            votes: 1,
+            dominant_decay: "Linear".to_string(),
+            mean_age_secs: 90,
+            threshold: 0.6,
+            float_weight: 0.6 - 1e-12,
+            fixed_weight: 0.6,
+            float_passed: false,
+            fixed_passed: true,
+        };
+        assert!(case.flipped());
+        assert!(case.near_threshold(DEFAULT_TOLERANCE));
+    }
+}
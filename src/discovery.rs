@@ -0,0 +1,396 @@
+// src/discovery.rs
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::Sender;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::blockchain::Blockchain;
+use crate::explorer;
+use crate::units::Seconds;
+use crate::vote::ProposalType;
+
+/// Window assumed for proposals discovered on-chain, matching `WindowType::Medium`
+pub const DEFAULT_DURATION: Seconds = Seconds::new(1800);
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DiscoveryError {
+    #[error("Unsupported webhook URL {0}; expected http://host[:port]/path")]
+    InvalidUrl(String),
+    #[error("Notification delivery failed: {0}")]
+    Delivery(String),
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
+}
+
+/// An open proposal as advertised to voters
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProposalListing {
+    pub proposal_id: String,
+    pub proposal_type: String,
+    pub namespace: Option<String>,
+    pub opened_at: DateTime<Utc>,
+    pub closes_at: DateTime<Utc>,
+}
+
+impl ProposalListing {
+    pub fn new(
+        proposal_id: &str,
+        proposal_type: ProposalType,
+        namespace: Option<&str>,
+        opened_at: DateTime<Utc>,
+        duration: Seconds,
+    ) -> Self {
+        Self {
+            proposal_id: proposal_id.to_string(),
+            proposal_type: format!("{:?}", proposal_type),
+            namespace: namespace.map(str::to_string),
+            opened_at,
+            closes_at: opened_at + duration.to_duration(),
+        }
+    }
+
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.opened_at <= now && now < self.closes_at
+    }
+}
+
+/// Which proposals a listing or subscription covers; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProposalFilter {
+    /// Matched case-insensitively, e.g. `critical`
+    pub proposal_type: Option<String>,
+    pub namespace: Option<String>,
+    /// Only proposals closing within this long of `now`
+    pub closing_within: Option<Seconds>,
+}
+
+impl ProposalFilter {
+    /// Build from the `type`, `namespace`, and `closing_within` (seconds) query parameters
+    pub fn from_query(query: &HashMap<String, String>) -> Result<Self, DiscoveryError> {
+        let closing_within = match query.get("closing_within") {
+            Some(secs) => Some(Seconds::new(secs.parse().map_err(|_| {
+                DiscoveryError::InvalidFilter("closing_within must be a number of seconds".to_string())
+            })?)),
+            None => None,
+        };
+        Ok(Self {
+            proposal_type: query.get("type").cloned(),
+            namespace: query.get("namespace").cloned(),
+            closing_within,
+        })
+    }
+
+    pub fn matches(&self, listing: &ProposalListing, now: DateTime<Utc>) -> bool {
+        self.proposal_type
+            .as_ref()
+            .is_none_or(|t| t.eq_ignore_ascii_case(&listing.proposal_type))
+            && self.namespace.as_ref().is_none_or(|n| listing.namespace.as_ref() == Some(n))
+            && self
+                .closing_within
+                .is_none_or(|within| listing.closes_at <= now + within.to_duration())
+    }
+}
+
+/// Sent to a subscriber when a matching proposal opens
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub subscription_id: u64,
+    pub voter_id: String,
+    pub proposal: ProposalListing,
+}
+
+/// Outcome of notifying subscribers about a newly opened proposal
+#[derive(Debug, Default)]
+pub struct Notified {
+    pub delivered: Vec<Notification>,
+    /// Notifications the subscriber's sink refused, with the reason
+    pub failed: Vec<(Notification, DiscoveryError)>,
+}
+
+/// Where notifications are delivered
+pub trait NotificationSink: Send + Sync {
+    fn notify(&self, notification: &Notification) -> Result<(), DiscoveryError>;
+}
+
+/// Delivers into an in-process channel
+pub struct ChannelSink(pub Sender<Notification>);
+
+impl NotificationSink for ChannelSink {
+    fn notify(&self, notification: &Notification) -> Result<(), DiscoveryError> {
+        self.0
+            .send(notification.clone())
+            .map_err(|e| DiscoveryError::Delivery(e.to_string()))
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn notify(&self, notification: &Notification) -> Result<(), DiscoveryError> {
+        self.post(&serde_json::to_string(notification).expect("notifications serialize to JSON"))
+    }
+}
+
+/// POSTs each notification as JSON to a plain-HTTP endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookSink {
+    pub host: String,
+    pub path: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> Result<Self, DiscoveryError> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| DiscoveryError::InvalidUrl(url.to_string()))?;
+        let (host, path) = rest.split_once('/').map_or((rest, "/".to_string()), |(h, p)| (h, format!("/{}", p)));
+        if host.is_empty() {
+            return Err(DiscoveryError::InvalidUrl(url.to_string()));
+        }
+        let host = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+        Ok(Self { host, path })
+    }
+
+    /// POST a JSON body to the endpoint
+    pub fn post(&self, body: &str) -> Result<(), DiscoveryError> {
+        let mut stream = TcpStream::connect(&self.host).map_err(|e| DiscoveryError::Delivery(e.to_string()))?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            self.host,
+            body.len(),
+            body
+        )
+        .map_err(|e| DiscoveryError::Delivery(e.to_string()))
+    }
+
+    /// POST a JSON body with extra `headers` and wait for the response; anything but a
+    /// 2xx status is a failed delivery
+    pub fn post_acknowledged(&self, body: &str, headers: &[(&str, String)]) -> Result<(), DiscoveryError> {
+        let mut stream = TcpStream::connect(&self.host).map_err(|e| DiscoveryError::Delivery(e.to_string()))?;
+        let extra: String = headers.iter().map(|(name, value)| format!("{}: {}\r\n", name, value)).collect();
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            self.host,
+            extra,
+            body.len(),
+            body
+        )
+        .map_err(|e| DiscoveryError::Delivery(e.to_string()))?;
+        let mut status = String::new();
+        BufReader::new(&stream)
+            .read_line(&mut status)
+            .map_err(|e| DiscoveryError::Delivery(e.to_string()))?;
+        match status.split_whitespace().nth(1).and_then(|code| code.parse::<u16>().ok()) {
+            Some(code) if (200..300).contains(&code) => Ok(()),
+            _ => Err(DiscoveryError::Delivery(format!("endpoint answered {:?}", status.trim()))),
+        }
+    }
+}
+
+struct Subscription {
+    voter_id: String,
+    filter: ProposalFilter,
+    sink: Box<dyn NotificationSink>,
+}
+
+/// Open proposals plus voters' subscriptions to new ones
+#[derive(Default)]
+pub struct ProposalDirectory {
+    listings: BTreeMap<String, ProposalListing>,
+    subscriptions: BTreeMap<u64, Subscription>,
+    next_subscription: u64,
+}
+
+impl ProposalDirectory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listings for every proposal on `chain` without a finalization. The chain does
+    /// not record types or windows, so each is listed as a normal proposal open for
+    /// `duration` from its first vote.
+    pub fn from_chain(chain: &Blockchain, duration: Seconds) -> Self {
+        let mut directory = Self::new();
+        for (proposal_id, votes) in explorer::open_proposals(chain) {
+            if let Some(opened_at) = votes.iter().map(|v| v.timestamp).min() {
+                let listing = ProposalListing::new(&proposal_id, ProposalType::Normal, None, opened_at, duration);
+                directory.listings.insert(proposal_id, listing);
+            }
+        }
+        directory
+    }
+
+    pub fn subscribe(&mut self, voter_id: &str, filter: ProposalFilter, sink: Box<dyn NotificationSink>) -> u64 {
+        self.next_subscription += 1;
+        self.subscriptions.insert(
+            self.next_subscription,
+            Subscription {
+                voter_id: voter_id.to_string(),
+                filter,
+                sink,
+            },
+        );
+        self.next_subscription
+    }
+
+    pub fn unsubscribe(&mut self, subscription_id: u64) -> bool {
+        self.subscriptions.remove(&subscription_id).is_some()
+    }
+
+    /// List `listing` and notify every subscriber whose filter matches it at `now`.
+    /// Failed deliveries are returned alongside the delivered ones rather than retried.
+    pub fn open(&mut self, listing: ProposalListing, now: DateTime<Utc>) -> Notified {
+        let mut notified = Notified::default();
+        for (id, subscription) in &self.subscriptions {
+            if !subscription.filter.matches(&listing, now) {
+                continue;
+            }
+            let notification = Notification {
+                subscription_id: *id,
+                voter_id: subscription.voter_id.clone(),
+                proposal: listing.clone(),
+            };
+            match subscription.sink.notify(&notification) {
+                Ok(()) => notified.delivered.push(notification),
+                Err(e) => notified.failed.push((notification, e)),
+            }
+        }
+        self.listings.insert(listing.proposal_id.clone(), listing);
+        notified
+    }
+
+    pub fn close(&mut self, proposal_id: &str) -> Option<ProposalListing> {
+        self.listings.remove(proposal_id)
+    }
+
+    /// Proposals open at `now` that match `filter`, soonest-closing first
+    pub fn list(&self, filter: &ProposalFilter, now: DateTime<Utc>) -> Vec<&ProposalListing> {
+        let mut open: Vec<&ProposalListing> = self
+            .listings
+            .values()
+            .filter(|l| l.is_open(now) && filter.matches(l, now))
+            .collect();
+        open.sort_by_key(|l| l.closes_at);
+        open
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn listing(id: &str, proposal_type: ProposalType, namespace: &str, now: DateTime<Utc>, secs: i64) -> ProposalListing {
+        ProposalListing::new(id, proposal_type, Some(namespace), now, Seconds::new(secs))
+    }
+
+    #[test]
+    fn test_list_filters_open_proposals() {
+        let now = Utc::now();
+        let mut directory = ProposalDirectory::new();
+        directory.open(listing("p1", ProposalType::Normal, "treasury", now, 1800), now);
+        directory.open(listing("p2", ProposalType::Critical, "treasury", now, 300), now);
+        directory.open(listing("p3", ProposalType::Critical, "protocol", now, 7200), now);
+        directory.open(listing("p4", ProposalType::Normal, "protocol", now - chrono::Duration::hours(3), 60), now);
+
+        let ids = |filter: &ProposalFilter| -> Vec<String> {
+            directory.list(filter, now).iter().map(|l| l.proposal_id.clone()).collect()
+        };
+        assert_eq!(ids(&ProposalFilter::default()), vec!["p2", "p1", "p3"]);
+
+        let query: HashMap<String, String> =
+            [("type".to_string(), "critical".to_string())].into_iter().collect();
+        assert_eq!(ids(&ProposalFilter::from_query(&query).unwrap()), vec!["p2", "p3"]);
+        let soon = ProposalFilter { closing_within: Some(Seconds::new(600)), ..ProposalFilter::default() };
+        assert_eq!(ids(&soon), vec!["p2"]);
+        let protocol = ProposalFilter { namespace: Some("protocol".to_string()), ..ProposalFilter::default() };
+        assert_eq!(ids(&protocol), vec!["p3"]);
+
+        let bad: HashMap<String, String> = [("closing_within".to_string(), "soon".to_string())].into_iter().collect();
+        assert!(ProposalFilter::from_query(&bad).is_err());
+    }
+
+    #[test]
+    fn test_subscribers_are_notified_of_matching_proposals() {
+        let now = Utc::now();
+        let (tx, rx) = mpsc::channel();
+        let mut directory = ProposalDirectory::new();
+        let critical = ProposalFilter { proposal_type: Some("Critical".to_string()), ..ProposalFilter::default() };
+        let id = directory.subscribe("alice", critical, Box::new(ChannelSink(tx)));
+
+        assert!(directory.open(listing("p1", ProposalType::Normal, "treasury", now, 300), now).delivered.is_empty());
+        let notified = directory.open(listing("p2", ProposalType::Critical, "treasury", now, 300), now);
+        assert_eq!((notified.delivered.len(), notified.failed.len()), (1, 0));
+        let received = rx.try_recv().unwrap();
+        assert_eq!((received.subscription_id, received.proposal.proposal_id.as_str()), (id, "p2"));
+
+        drop(rx);
+        let notified = directory.open(listing("p3", ProposalType::Critical, "treasury", now, 300), now);
+        assert_eq!(notified.failed.len(), 1);
+        assert!(matches!(notified.failed[0].1, DiscoveryError::Delivery(_)));
+
+        assert!(directory.unsubscribe(id));
+        assert!(directory.open(listing("p4", ProposalType::Critical, "treasury", now, 300), now).failed.is_empty());
+    }
+
+    #[test]
+    fn test_webhook_posts_json() {
+        use std::io::Read;
+        use std::net::TcpListener;
+
+        assert!(WebhookSink::new("https://example.com/hook").is_err());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let sink = WebhookSink::new(&format!("http://{}/hooks/proposals", listener.local_addr().unwrap())).unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            stream.read_to_string(&mut request).unwrap();
+            request
+        });
+
+        let now = Utc::now();
+        let notification = Notification {
+            subscription_id: 1,
+            voter_id: "alice".to_string(),
+            proposal: listing("p1", ProposalType::Normal, "treasury", now, 300),
+        };
+        sink.notify(&notification).unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /hooks/proposals HTTP/1.1"));
+        assert!(request.contains("\"proposal_id\":\"p1\""));
+    }
+
+    #[test]
+    fn test_acknowledged_post_requires_a_2xx() {
+        use std::io::{BufRead, BufReader};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let sink = WebhookSink::new(&format!("http://{}/hooks/outcomes", listener.local_addr().unwrap())).unwrap();
+        let server = std::thread::spawn(move || {
+            let mut headers = Vec::new();
+            for status in ["204 No Content", "500 Internal Server Error"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut line = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                while reader.read_line(&mut line).unwrap() > 2 {}
+                headers.push(line);
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status).unwrap();
+            }
+            headers
+        });
+
+        let signature = [("X-Test-Signature", "sha256=00".to_string())];
+        assert_eq!(sink.post_acknowledged("{}", &signature), Ok(()));
+        assert!(matches!(sink.post_acknowledged("{}", &signature), Err(DiscoveryError::Delivery(e)) if e.contains("500")));
+        assert!(server.join().unwrap()[0].contains("X-Test-Signature: sha256=00\r\n"));
+    }
+}
@@ -0,0 +1,398 @@
+// src/epoch.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::blockchain::{Block, BlockPayload, Blockchain, FinalizationEntry, Transaction, merkle_root};
+use crate::hashing;
+use crate::ids::ProposalId;
+use crate::progress::CancellationToken;
+use crate::report::{FinalizationReport, ReportInput};
+use crate::threshold::DecisionRule;
+use crate::trust::TrustSource;
+use crate::vote::{ProposalType, SignedVote};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum EpochError {
+    #[error("Proposal {0} is already scheduled")]
+    DuplicateProposal(ProposalId),
+    #[error("Unknown proposal: {0}")]
+    UnknownProposal(ProposalId),
+    #[error("Epoch {epoch} is not after the last closed epoch {closed}")]
+    AlreadyClosed { epoch: u64, closed: u64 },
+    #[error("No proposals are due by the end of epoch {0}")]
+    NothingDue(u64),
+    #[error("Proposal {0} already has a finalization on chain")]
+    AlreadyFinalized(ProposalId),
+    #[error("Epoch close was cancelled")]
+    Cancelled,
+}
+
+/// Fixed-length epochs counted from `genesis`; epoch `n` covers `[start(n), end(n))`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpochSchedule {
+    pub genesis: DateTime<Utc>,
+    pub length: Duration,
+}
+
+impl EpochSchedule {
+    pub fn new(genesis: DateTime<Utc>, length: Duration) -> Self {
+        assert!(length > Duration::zero(), "epoch length must be positive");
+        Self { genesis, length }
+    }
+
+    /// Epoch containing `at`; instants before genesis fall in epoch 0
+    pub fn epoch_at(&self, at: DateTime<Utc>) -> u64 {
+        let elapsed = (at - self.genesis).num_milliseconds().max(0);
+        (elapsed / self.length.num_milliseconds()) as u64
+    }
+
+    pub fn start(&self, epoch: u64) -> DateTime<Utc> {
+        self.genesis + self.length * epoch as i32
+    }
+
+    /// Boundary at which epoch `epoch` closes
+    pub fn end(&self, epoch: u64) -> DateTime<Utc> {
+        self.start(epoch + 1)
+    }
+}
+
+/// A proposal waiting for the epoch its voting window closes in
+#[derive(Debug, Clone)]
+pub struct ScheduledProposal {
+    pub proposal_id: ProposalId,
+    pub proposal_type: ProposalType,
+    pub opened_at: DateTime<Utc>,
+    pub closes_at: DateTime<Utc>,
+    pub eligible_weight: Option<f64>,
+    pub decision: DecisionRule,
+    pub votes: Vec<SignedVote>,
+}
+
+impl ScheduledProposal {
+    pub fn new(proposal_id: ProposalId, proposal_type: ProposalType, opened_at: DateTime<Utc>, closes_at: DateTime<Utc>) -> Self {
+        Self {
+            proposal_id,
+            proposal_type,
+            opened_at,
+            closes_at,
+            eligible_weight: None,
+            decision: DecisionRule::default(),
+            votes: Vec::new(),
+        }
+    }
+}
+
+/// Issuer signature over every report finalized at one epoch boundary and the block
+/// that recorded them, standing in for one certificate per report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochCertificate {
+    pub epoch: u64,
+    pub closed_at: DateTime<Utc>,
+    /// Body hash of each report, in the order its finalization appears in the block
+    pub report_hashes: Vec<String>,
+    pub reports_root: String,
+    pub block_root: String,
+    pub epoch_hash: String,
+    pub issuer: String,
+    pub signature: String,
+}
+
+impl EpochCertificate {
+    fn sign(epoch: u64, closed_at: DateTime<Utc>, reports: &[FinalizationReport], block_root: String, issuer: &SigningKey) -> Self {
+        let report_hashes: Vec<String> = reports.iter().map(FinalizationReport::body_hash).collect();
+        let reports_root = reports_root(&report_hashes);
+        let epoch_hash = hashing::epoch_hash(epoch, closed_at.timestamp(), &reports_root, &block_root);
+        Self {
+            epoch,
+            closed_at,
+            signature: hex::encode(issuer.sign(epoch_hash.as_bytes()).to_bytes()),
+            issuer: hex::encode(issuer.verifying_key().as_bytes()),
+            report_hashes,
+            reports_root,
+            block_root,
+            epoch_hash,
+        }
+    }
+
+    /// True when the signature is valid and covers exactly these reports and this block
+    pub fn verify(&self, reports: &[FinalizationReport], block: &Block) -> bool {
+        let decoded = (|| {
+            let key: [u8; 32] = hex::decode(&self.issuer).ok()?.try_into().ok()?;
+            let sig: [u8; 64] = hex::decode(&self.signature).ok()?.try_into().ok()?;
+            Some((VerifyingKey::from_bytes(&key).ok()?, Signature::from_bytes(&sig)))
+        })();
+        let Some((key, signature)) = decoded else {
+            return false;
+        };
+        let report_hashes: Vec<String> = reports.iter().map(FinalizationReport::body_hash).collect();
+        let expected = hashing::epoch_hash(self.epoch, self.closed_at.timestamp(), &self.reports_root, &self.block_root);
+
+        report_hashes == self.report_hashes
+            && reports_root(&report_hashes) == self.reports_root
+            && BlockPayload::decode(&block.data).merkle_root() == self.block_root
+            && expected == self.epoch_hash
+            && key.verify(self.epoch_hash.as_bytes(), &signature).is_ok()
+    }
+}
+
+fn reports_root(report_hashes: &[String]) -> String {
+    merkle_root(report_hashes.iter().map(|h| hex::decode(h).expect("report hashes are hex")).collect())
+}
+
+/// Result of a successful epoch close
+#[derive(Debug, Clone)]
+pub struct EpochClose {
+    pub epoch: u64,
+    pub block_id: u64,
+    pub reports: Vec<FinalizationReport>,
+    pub certificate: EpochCertificate,
+}
+
+/// Holds proposals until the epoch boundary after their window closes, then finalizes
+/// every due proposal together in one block under one certificate
+pub struct EpochFinalizer {
+    pub schedule: EpochSchedule,
+    pub max_age_secs: i64,
+    pending: BTreeMap<ProposalId, ScheduledProposal>,
+    last_closed: Option<u64>,
+}
+
+impl EpochFinalizer {
+    pub fn new(schedule: EpochSchedule) -> Self {
+        Self {
+            schedule,
+            max_age_secs: i64::MAX,
+            pending: BTreeMap::new(),
+            last_closed: None,
+        }
+    }
+
+    pub fn schedule_proposal(&mut self, proposal: ScheduledProposal) -> Result<(), EpochError> {
+        if self.pending.contains_key(&proposal.proposal_id) {
+            return Err(EpochError::DuplicateProposal(proposal.proposal_id));
+        }
+        self.pending.insert(proposal.proposal_id.clone(), proposal);
+        Ok(())
+    }
+
+    pub fn add_vote(&mut self, vote: SignedVote) -> Result<(), EpochError> {
+        match self.pending.get_mut(&vote.proposal_id) {
+            Some(proposal) => {
+                proposal.votes.push(vote);
+                Ok(())
+            }
+            None => Err(EpochError::UnknownProposal(vote.proposal_id)),
+        }
+    }
+
+    pub fn pending(&self) -> impl Iterator<Item = &ScheduledProposal> {
+        self.pending.values()
+    }
+
+    pub fn last_closed(&self) -> Option<u64> {
+        self.last_closed
+    }
+
+    /// Proposals whose window has closed by the end of `epoch`, in id order
+    pub fn due(&self, epoch: u64) -> Vec<&ScheduledProposal> {
+        let boundary = self.schedule.end(epoch);
+        self.pending.values().filter(|p| p.closes_at <= boundary).collect()
+    }
+
+    /// Finalize every proposal due by the end of `epoch` as one block and one certificate.
+    /// All reports are built and checked before the block is mined; on any error, including
+    /// cancellation while mining, neither the chain nor the pending set changes.
+    pub fn close_epoch(
+        &mut self,
+        epoch: u64,
+        chain: &mut Blockchain,
+        trust: &dyn TrustSource,
+        issuer: &SigningKey,
+        cancel: &CancellationToken,
+    ) -> Result<EpochClose, EpochError> {
+        if let Some(closed) = self.last_closed.filter(|closed| epoch <= *closed) {
+            return Err(EpochError::AlreadyClosed { epoch, closed });
+        }
+        let due = self.due(epoch);
+        if due.is_empty() {
+            return Err(EpochError::NothingDue(epoch));
+        }
+        if let Some(finalized) = due.iter().find(|p| has_finalization(chain, &p.proposal_id)) {
+            return Err(EpochError::AlreadyFinalized(finalized.proposal_id.clone()));
+        }
+
+        let closed_at = self.schedule.end(epoch);
+        let seed = chain.blocks.last().expect("Blockchain should have at least one block").hash.clone();
+        let reports: Vec<FinalizationReport> = due
+            .iter()
+            .map(|p| {
+                FinalizationReport::build(ReportInput {
+                    proposal_id: p.proposal_id.as_str(),
+                    proposal_type: p.proposal_type.clone(),
+                    opened_at: p.opened_at,
+                    finalized_at: closed_at,
+                    votes: &p.votes,
+                    trust,
+                    max_age_secs: self.max_age_secs,
+                    admissions: None,
+                    eligible_weight: p.eligible_weight,
+                    decision: p.decision,
+                    tie_break_seed: Some(&seed),
+                })
+            })
+            .collect();
+
+        let transactions: Vec<Transaction> = reports
+            .iter()
+            .map(|r| {
+                Transaction::Finalization(FinalizationEntry {
+                    proposal_id: r.proposal.proposal_id.clone(),
+                    passed: r.passed,
+                    weight: r.decided_weight(),
+                    threshold: r.final_threshold,
+                    finalized_at: closed_at,
+                })
+            })
+            .collect();
+        let data = BlockPayload::encode(&transactions);
+        let block_root = BlockPayload::decode(&data).merkle_root();
+        let certificate = EpochCertificate::sign(epoch, closed_at, &reports, block_root, issuer);
+
+        chain.try_add_block(data, cancel).map_err(|_| EpochError::Cancelled)?;
+
+        // The block is on chain, so only now does the pending set change
+        for report in &reports {
+            self.pending.remove(report.proposal.proposal_id.as_str());
+        }
+        self.last_closed = Some(epoch);
+
+        Ok(EpochClose {
+            epoch,
+            block_id: chain.blocks.last().expect("block was just added").id,
+            reports,
+            certificate,
+        })
+    }
+}
+
+fn has_finalization(chain: &Blockchain, proposal_id: &ProposalId) -> bool {
+    chain.blocks.iter().any(|block| {
+        BlockPayload::decode(&block.data)
+            .transactions()
+            .iter()
+            .any(|tx| matches!(tx, Transaction::Finalization(f) if f.proposal_id == proposal_id.as_str()))
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trust::TrustEngine;
+    use crate::vote::DecayType;
+
+    fn schedule() -> EpochSchedule {
+        EpochSchedule::new(DateTime::from_timestamp(1_700_000_000, 0).unwrap(), Duration::hours(1))
+    }
+
+    fn finalizer_with(closes_after_mins: &[(&str, i64)]) -> EpochFinalizer {
+        let schedule = schedule();
+        let mut finalizer = EpochFinalizer::new(schedule);
+        for (id, mins) in closes_after_mins {
+            let proposal_id: ProposalId = id.parse().unwrap();
+            let mut proposal = ScheduledProposal::new(
+                proposal_id.clone(),
+                ProposalType::Normal,
+                schedule.genesis,
+                schedule.genesis + Duration::minutes(*mins),
+            );
+            proposal.eligible_weight = Some(1.0);
+            finalizer.schedule_proposal(proposal).unwrap();
+            let key = SignedVote::generate_keypair();
+            let vote = SignedVote::new(
+                "alice".parse().unwrap(),
+                proposal_id,
+                1.0,
+                schedule.genesis + Duration::minutes(1),
+                DecayType::Linear,
+                &key,
+            );
+            finalizer.add_vote(vote).unwrap();
+        }
+        finalizer
+    }
+
+    fn chain() -> Blockchain {
+        let mut chain = Blockchain::new();
+        chain.difficulty = 1;
+        chain
+    }
+
+    #[test]
+    fn test_schedule_boundaries() {
+        let schedule = schedule();
+        assert_eq!(schedule.epoch_at(schedule.genesis), 0);
+        assert_eq!(schedule.epoch_at(schedule.genesis + Duration::minutes(150)), 2);
+        assert_eq!(schedule.end(2), schedule.genesis + Duration::hours(3));
+    }
+
+    #[test]
+    fn test_close_finalizes_due_proposals_in_one_block() {
+        let mut finalizer = finalizer_with(&[("p1", 30), ("p2", 60), ("p3", 90)]);
+        let mut chain = chain();
+        let issuer = SignedVote::generate_keypair();
+
+        let closed = finalizer
+            .close_epoch(0, &mut chain, &TrustEngine::new(), &issuer, &CancellationToken::new())
+            .unwrap();
+        assert_eq!(closed.block_id, 1);
+        assert_eq!(chain.blocks.len(), 2);
+        let ids: Vec<&str> = closed.reports.iter().map(|r| r.proposal.proposal_id.as_str()).collect();
+        assert_eq!(ids, vec!["p1", "p2"]);
+        assert_eq!(BlockPayload::decode(&chain.blocks[1].data).transactions().len(), 2);
+        assert!(closed.certificate.verify(&closed.reports, &chain.blocks[1]));
+        assert!(!closed.certificate.verify(&closed.reports[..1], &chain.blocks[1]));
+        assert!(!closed.certificate.verify(&closed.reports, &chain.blocks[0]));
+
+        assert_eq!(finalizer.pending().count(), 1);
+        assert_eq!(
+            finalizer.close_epoch(0, &mut chain, &TrustEngine::new(), &issuer, &CancellationToken::new()).unwrap_err(),
+            EpochError::AlreadyClosed { epoch: 0, closed: 0 }
+        );
+        assert!(chain.is_valid());
+    }
+
+    #[test]
+    fn test_failed_close_changes_nothing() {
+        let mut finalizer = finalizer_with(&[("p1", 30), ("p2", 45)]);
+        let mut chain = chain();
+        let issuer = SignedVote::generate_keypair();
+        chain.add_transactions(&[Transaction::Finalization(FinalizationEntry {
+            proposal_id: "p2".to_string(),
+            passed: true,
+            weight: 1.0,
+            threshold: 0.5,
+            finalized_at: Utc::now(),
+        })]);
+
+        let result = finalizer.close_epoch(0, &mut chain, &TrustEngine::new(), &issuer, &CancellationToken::new());
+        assert_eq!(result.unwrap_err(), EpochError::AlreadyFinalized("p2".parse().unwrap()));
+        assert_eq!(chain.blocks.len(), 2);
+        assert_eq!(finalizer.pending().count(), 2);
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        finalizer.pending.remove("p2");
+        chain.difficulty = 64;
+        let result = finalizer.close_epoch(0, &mut chain, &TrustEngine::new(), &issuer, &cancel);
+        assert_eq!(result.unwrap_err(), EpochError::Cancelled);
+        assert_eq!(chain.blocks.len(), 2);
+        assert_eq!(finalizer.pending().count(), 1);
+        assert_eq!(finalizer.last_closed(), None);
+    }
+}
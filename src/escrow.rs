@@ -0,0 +1,245 @@
+// src/escrow.rs
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::units::Weight;
+use crate::vote::{ProposalType, SignedVote};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum EscrowError {
+    #[error("Escrow fraction must be within [0, 1], got {0}")]
+    InvalidFraction(f64),
+    #[error("No recorded vote from {voter_id} on {proposal_id}")]
+    UnknownVote { voter_id: String, proposal_id: String },
+}
+
+/// Share of each vote's weight held back on Critical proposals until close
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EscrowConfig {
+    fraction: f64,
+}
+
+impl Default for EscrowConfig {
+    fn default() -> Self {
+        Self { fraction: 0.5 }
+    }
+}
+
+impl EscrowConfig {
+    pub fn new(fraction: f64) -> Result<Self, EscrowError> {
+        if (0.0..=1.0).contains(&fraction) {
+            Ok(Self { fraction })
+        } else {
+            Err(EscrowError::InvalidFraction(fraction))
+        }
+    }
+
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    pub fn applies_to(&self, proposal_type: &ProposalType) -> bool {
+        matches!(proposal_type, ProposalType::Critical)
+    }
+}
+
+/// Why escrowed weight was not released
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForfeitReason {
+    /// The voter signed more than one vote on the proposal
+    Equivocation,
+    /// The voter withdrew before the proposal closed
+    Withdrawal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EscrowStatus {
+    /// The proposal has not closed yet
+    Held,
+    Released,
+    Forfeited(ForfeitReason),
+}
+
+/// How a vote's weight splits between the part counted right away and the escrowed part
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EscrowSettlement {
+    pub immediate: Weight,
+    pub escrowed: Weight,
+    pub status: EscrowStatus,
+}
+
+impl EscrowSettlement {
+    /// Weight that counts toward the tally; escrow only adds once released
+    pub fn counted(&self) -> Weight {
+        match self.status {
+            EscrowStatus::Released => self.immediate + self.escrowed,
+            _ => self.immediate,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EscrowEntry {
+    signature: [u8; 64],
+    equivocated: bool,
+    withdrawn_at: Option<DateTime<Utc>>,
+}
+
+/// Tracks per-proposal voter behaviour that decides whether escrow is released
+#[derive(Debug, Default)]
+pub struct EscrowLedger {
+    config: EscrowConfig,
+    entries: HashMap<(String, String), EscrowEntry>,
+}
+
+impl EscrowLedger {
+    pub fn new(config: EscrowConfig) -> Self {
+        Self {
+            config,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Record a vote; returns true when it conflicts with an earlier vote by the same voter
+    pub fn record(&mut self, vote: &SignedVote) -> bool {
+        let signature = vote.signature.to_bytes();
+        let key = (vote.proposal_id.to_string(), vote.voter_id.to_string());
+        match self.entries.get_mut(&key) {
+            Some(entry) if entry.signature != signature => {
+                entry.equivocated = true;
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.entries.insert(
+                    key,
+                    EscrowEntry {
+                        signature,
+                        equivocated: false,
+                        withdrawn_at: None,
+                    },
+                );
+                false
+            }
+        }
+    }
+
+    /// Mark the voter's vote on `proposal_id` as withdrawn at `at`
+    pub fn withdraw(&mut self, voter_id: &str, proposal_id: &str, at: DateTime<Utc>) -> Result<(), EscrowError> {
+        let entry = self
+            .entries
+            .get_mut(&(proposal_id.to_string(), voter_id.to_string()))
+            .ok_or_else(|| EscrowError::UnknownVote {
+                voter_id: voter_id.to_string(),
+                proposal_id: proposal_id.to_string(),
+            })?;
+        entry.withdrawn_at.get_or_insert(at);
+        Ok(())
+    }
+
+    /// Escrow state of a voter's weight as of `now` for a proposal closing at `closes_at`
+    pub fn status(&self, voter_id: &str, proposal_id: &str, closes_at: DateTime<Utc>, now: DateTime<Utc>) -> EscrowStatus {
+        let entry = self.entries.get(&(proposal_id.to_string(), voter_id.to_string()));
+        if entry.is_some_and(|e| e.equivocated) {
+            return EscrowStatus::Forfeited(ForfeitReason::Equivocation);
+        }
+        if entry.and_then(|e| e.withdrawn_at).is_some_and(|at| at < closes_at) {
+            return EscrowStatus::Forfeited(ForfeitReason::Withdrawal);
+        }
+        if now < closes_at {
+            EscrowStatus::Held
+        } else {
+            EscrowStatus::Released
+        }
+    }
+
+    /// Split `weight` for `vote`; proposals other than Critical are counted in full
+    pub fn settle(
+        &self,
+        vote: &SignedVote,
+        proposal_type: &ProposalType,
+        weight: Weight,
+        closes_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> EscrowSettlement {
+        if !self.config.applies_to(proposal_type) {
+            return EscrowSettlement {
+                immediate: weight,
+                escrowed: Weight::ZERO,
+                status: EscrowStatus::Released,
+            };
+        }
+        let escrowed = weight.scale(self.config.fraction);
+        EscrowSettlement {
+            immediate: Weight::saturating(weight.get() - escrowed.get()),
+            escrowed,
+            status: self.status(&vote.voter_id, &vote.proposal_id, closes_at, now),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::DecayType;
+    use chrono::Duration;
+
+    fn vote(key: &ed25519_dalek::SigningKey, at: DateTime<Utc>) -> SignedVote {
+        SignedVote::new("alice".parse().unwrap(), "proposal_1".parse().unwrap(), 1.0, at, DecayType::Linear, key)
+    }
+
+    #[test]
+    fn test_escrow_released_at_close() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let closes_at = now + Duration::seconds(60);
+        let mut ledger = EscrowLedger::new(EscrowConfig::new(0.4).unwrap());
+        let v = vote(&key, now);
+        assert!(!ledger.record(&v));
+        assert!(!ledger.record(&v));
+
+        let weight = Weight::new(1.0).unwrap();
+        let open = ledger.settle(&v, &ProposalType::Critical, weight, closes_at, now);
+        assert_eq!(open.status, EscrowStatus::Held);
+        assert!((open.counted().get() - 0.6).abs() < 1e-9);
+
+        let closed = ledger.settle(&v, &ProposalType::Critical, weight, closes_at, closes_at);
+        assert_eq!(closed.status, EscrowStatus::Released);
+        assert!((closed.counted().get() - 1.0).abs() < 1e-9);
+
+        let normal = ledger.settle(&v, &ProposalType::Normal, weight, closes_at, now);
+        assert_eq!(normal.escrowed, Weight::ZERO);
+        assert_eq!(normal.counted(), weight);
+    }
+
+    #[test]
+    fn test_equivocation_and_withdrawal_forfeit_escrow() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let closes_at = now + Duration::seconds(60);
+        let weight = Weight::new(1.0).unwrap();
+
+        let mut ledger = EscrowLedger::default();
+        let first = vote(&key, now);
+        ledger.record(&first);
+        assert!(ledger.record(&vote(&key, now + Duration::seconds(50))));
+        let settlement = ledger.settle(&first, &ProposalType::Critical, weight, closes_at, closes_at);
+        assert_eq!(settlement.status, EscrowStatus::Forfeited(ForfeitReason::Equivocation));
+        assert!((settlement.counted().get() - 0.5).abs() < 1e-9);
+
+        let mut ledger = EscrowLedger::default();
+        ledger.record(&first);
+        ledger.withdraw("alice", "proposal_1", now + Duration::seconds(55)).unwrap();
+        assert_eq!(
+            ledger.status("alice", "proposal_1", closes_at, closes_at),
+            EscrowStatus::Forfeited(ForfeitReason::Withdrawal)
+        );
+        assert!(ledger.withdraw("bob", "proposal_1", now).is_err());
+        assert_eq!(EscrowConfig::new(1.5), Err(EscrowError::InvalidFraction(1.5)));
+    }
+}
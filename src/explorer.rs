@@ -0,0 +1,319 @@
+// src/explorer.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::blockchain::{Block, BlockPayload, Blockchain, FinalizationEntry, Transaction, VoteEntry};
+use crate::messages::{self, MessageKey};
+use crate::vote::SignedVote;
+
+/// One-line view of a block for chain listings
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockSummary {
+    pub id: u64,
+    pub hash: String,
+    pub nonce: u64,
+    pub merkle_root: String,
+    pub transactions: usize,
+    pub valid: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainSummary {
+    pub length: usize,
+    pub valid: bool,
+    pub blocks: Vec<BlockSummary>,
+}
+
+/// Full view of a block with its payload decoded
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockDetail {
+    pub id: u64,
+    pub hash: String,
+    pub previous_hash: String,
+    pub timestamp: i64,
+    pub nonce: u64,
+    pub merkle_root: String,
+    pub valid: bool,
+    pub invalid_reason: Option<String>,
+    pub payload: BlockPayload,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocatedVote {
+    pub block_id: u64,
+    pub vote: VoteEntry,
+    pub signature_valid: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocatedFinalization {
+    pub block_id: u64,
+    pub finalization: FinalizationEntry,
+}
+
+/// Every on-chain record mentioning a proposal
+#[derive(Debug, Clone, Serialize)]
+pub struct ProposalActivity {
+    pub proposal_id: String,
+    pub votes: Vec<LocatedVote>,
+    pub finalizations: Vec<LocatedFinalization>,
+}
+
+fn summarize(chain: &Blockchain, index: usize, block: &Block) -> BlockSummary {
+    let payload = BlockPayload::decode(&block.data);
+    BlockSummary {
+        id: block.id,
+        hash: block.hash.clone(),
+        nonce: block.nonce,
+        merkle_root: payload.merkle_root(),
+        transactions: payload.transactions().len(),
+        valid: chain.check_block(index).is_ok(),
+    }
+}
+
+pub fn chain_summary(chain: &Blockchain) -> ChainSummary {
+    let blocks: Vec<BlockSummary> = chain
+        .blocks
+        .iter()
+        .enumerate()
+        .map(|(i, block)| summarize(chain, i, block))
+        .collect();
+    ChainSummary {
+        length: blocks.len(),
+        valid: blocks.iter().all(|b| b.valid),
+        blocks,
+    }
+}
+
+pub fn block_detail(chain: &Blockchain, id: u64) -> Option<BlockDetail> {
+    let index = chain.blocks.iter().position(|b| b.id == id)?;
+    let block = &chain.blocks[index];
+    let payload = BlockPayload::decode(&block.data);
+    let check = chain.check_block(index);
+    Some(BlockDetail {
+        id: block.id,
+        hash: block.hash.clone(),
+        previous_hash: block.previous_hash.clone(),
+        timestamp: block.timestamp,
+        nonce: block.nonce,
+        merkle_root: payload.merkle_root(),
+        valid: check.is_ok(),
+        invalid_reason: check.err(),
+        payload,
+    })
+}
+
+/// Votes on every proposal that has no finalization recorded, keyed by proposal id
+pub fn open_proposals(chain: &Blockchain) -> BTreeMap<String, Vec<SignedVote>> {
+    let mut votes: BTreeMap<String, Vec<SignedVote>> = BTreeMap::new();
+    let mut finalized = BTreeSet::new();
+    for block in &chain.blocks {
+        for tx in BlockPayload::decode(&block.data).transactions() {
+            match tx {
+                Transaction::Vote(entry) => {
+                    if let Some(vote) = entry.to_signed_vote() {
+                        votes.entry(entry.proposal_id.clone()).or_default().push(vote);
+                    }
+                }
+                Transaction::Finalization(f) => {
+                    finalized.insert(f.proposal_id.clone());
+                }
+            }
+        }
+    }
+    votes.retain(|id, _| !finalized.contains(id));
+    votes
+}
+
+pub fn find_proposal(chain: &Blockchain, proposal_id: &str) -> ProposalActivity {
+    let mut activity = ProposalActivity {
+        proposal_id: proposal_id.to_string(),
+        votes: Vec::new(),
+        finalizations: Vec::new(),
+    };
+
+    for block in &chain.blocks {
+        let payload = BlockPayload::decode(&block.data);
+        for tx in payload.transactions().iter().filter(|tx| tx.proposal_id() == proposal_id) {
+            match tx {
+                Transaction::Vote(vote) => activity.votes.push(LocatedVote {
+                    block_id: block.id,
+                    vote: vote.clone(),
+                    signature_valid: vote
+                        .to_signed_vote()
+                        .is_some_and(|v| v.verify(i64::MAX).is_ok()),
+                }),
+                Transaction::Finalization(finalization) => activity.finalizations.push(LocatedFinalization {
+                    block_id: block.id,
+                    finalization: finalization.clone(),
+                }),
+            }
+        }
+    }
+    activity
+}
+
+pub fn print_chain(summary: &ChainSummary) {
+    let header = [("length", summary.length.to_string()), ("valid", summary.valid.to_string())];
+    println!("{}", messages::text(MessageKey::ChainSummary, &header));
+    for b in &summary.blocks {
+        println!(
+            "{}",
+            messages::text(
+                MessageKey::ChainSummaryBlock,
+                &[
+                    ("id", b.id.to_string()),
+                    ("hash", b.hash.clone()),
+                    ("nonce", b.nonce.to_string()),
+                    ("merkle_root", b.merkle_root.clone()),
+                    ("transactions", b.transactions.to_string()),
+                    ("valid", b.valid.to_string()),
+                ],
+            )
+        );
+    }
+}
+
+fn print_block_field(field: &str, value: String) {
+    let field = format!("{:<14}", format!("{}:", field));
+    println!("{}", messages::text(MessageKey::BlockField, &[("field", field), ("value", value)]));
+}
+
+pub fn print_block(detail: &BlockDetail) {
+    println!("{}", messages::text(MessageKey::BlockHeader, &[("id", detail.id.to_string())]));
+    print_block_field("hash", detail.hash.clone());
+    print_block_field("previous_hash", detail.previous_hash.clone());
+    print_block_field("timestamp", detail.timestamp.to_string());
+    print_block_field("nonce", detail.nonce.to_string());
+    print_block_field("merkle_root", detail.merkle_root.clone());
+    match &detail.invalid_reason {
+        None => print_block_field("valid", "true".to_string()),
+        Some(reason) => print_block_field("valid", format!("false ({})", reason)),
+    }
+    match &detail.payload {
+        BlockPayload::Raw(data) => print_block_field("data", data.clone()),
+        BlockPayload::Transactions(txs) => {
+            for tx in txs {
+                let line = match tx {
+                    Transaction::Vote(v) => messages::text(
+                        MessageKey::BlockVote,
+                        &[
+                            ("voter_id", v.voter_id.clone()),
+                            ("proposal_id", v.proposal_id.clone()),
+                            ("weight", v.original_weight.to_string()),
+                            ("decay", v.decay_model.to_string()),
+                            ("timestamp", v.timestamp.to_string()),
+                        ],
+                    ),
+                    Transaction::Finalization(f) => messages::text(
+                        MessageKey::BlockFinalization,
+                        &[
+                            ("proposal_id", f.proposal_id.clone()),
+                            ("passed", f.passed.to_string()),
+                            ("weight", format!("{:.4}", f.weight)),
+                            ("threshold", format!("{:.4}", f.threshold)),
+                            ("finalized_at", f.finalized_at.to_string()),
+                        ],
+                    ),
+                };
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+pub fn print_proposal(activity: &ProposalActivity) {
+    let header = [
+        ("proposal_id", activity.proposal_id.clone()),
+        ("votes", activity.votes.len().to_string()),
+        ("finalizations", activity.finalizations.len().to_string()),
+    ];
+    println!("{}", messages::text(MessageKey::ProposalActivity, &header));
+    for v in &activity.votes {
+        println!(
+            "{}",
+            messages::text(
+                MessageKey::ProposalActivityVote,
+                &[
+                    ("block_id", v.block_id.to_string()),
+                    ("voter_id", v.vote.voter_id.clone()),
+                    ("weight", v.vote.original_weight.to_string()),
+                    ("signature_valid", v.signature_valid.to_string()),
+                ],
+            )
+        );
+    }
+    for f in &activity.finalizations {
+        println!(
+            "{}",
+            messages::text(
+                MessageKey::ProposalActivityFinalization,
+                &[
+                    ("block_id", f.block_id.to_string()),
+                    ("passed", f.finalization.passed.to_string()),
+                    ("weight", format!("{:.4}", f.finalization.weight)),
+                    ("threshold", format!("{:.4}", f.finalization.threshold)),
+                ],
+            )
+        );
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::{DecayType, SignedVote};
+    use chrono::Utc;
+
+    fn sample_chain() -> Blockchain {
+        let key = SignedVote::generate_keypair();
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, Utc::now(), DecayType::Linear, &key);
+        let mut chain = Blockchain::new();
+        chain.add_block("Transaction 1 Data".to_string());
+        chain.add_transactions(&[
+            Transaction::Vote(VoteEntry::from(&vote)),
+            Transaction::Finalization(FinalizationEntry {
+                proposal_id: "p1".to_string(),
+                passed: true,
+                weight: 0.8,
+                threshold: 0.6,
+                finalized_at: Utc::now(),
+            }),
+        ]);
+        chain
+    }
+
+    #[test]
+    fn test_chain_summary() {
+        let chain = sample_chain();
+        let summary = chain_summary(&chain);
+        assert_eq!(summary.length, 3);
+        assert!(summary.valid);
+        assert_eq!(summary.blocks[2].transactions, 2);
+    }
+
+    #[test]
+    fn test_block_detail_flags_tampering() {
+        let mut chain = sample_chain();
+        assert!(block_detail(&chain, 2).unwrap().valid);
+        assert!(block_detail(&chain, 9).is_none());
+
+        chain.blocks[2].data = "tampered".to_string();
+        let detail = block_detail(&chain, 2).unwrap();
+        assert!(!detail.valid);
+        assert!(detail.invalid_reason.is_some());
+    }
+
+    #[test]
+    fn test_find_proposal() {
+        let chain = sample_chain();
+        let activity = find_proposal(&chain, "p1");
+        assert_eq!(activity.votes.len(), 1);
+        assert!(activity.votes[0].signature_valid);
+        assert_eq!(activity.finalizations.len(), 1);
+        assert!(find_proposal(&chain, "p2").votes.is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+// src/fixed.rs
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::units::Seconds;
+use crate::vote::DecayType;
+
+/// Fractional digits carried by `Fixed`
+pub const SCALE: i64 = 1_000_000_000;
+
+/// Decimal fixed-point number with nine fractional digits. Every operation is plain
+/// integer arithmetic, so results are identical on every platform.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(i64);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(SCALE);
+
+    pub const fn from_raw(raw: i64) -> Self {
+        Fixed(raw)
+    }
+
+    pub const fn raw(self) -> i64 {
+        self.0
+    }
+
+    pub const fn from_int(value: i64) -> Self {
+        Fixed(value.saturating_mul(SCALE))
+    }
+
+    /// Nearest representable value; NaN becomes zero and out-of-range values saturate
+    pub fn from_f64(value: f64) -> Self {
+        Fixed((value * SCALE as f64).round() as i64)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+
+    /// e^-x for x >= 0, by range reduction and a Taylor series
+    pub fn exp_neg(self) -> Fixed {
+        if self.0 <= 0 {
+            return Fixed::ONE;
+        }
+        // e^-40 is below the smallest representable step
+        if self > Fixed::from_int(40) {
+            return Fixed::ZERO;
+        }
+        let mut halvings = 0;
+        let mut x = self;
+        while x > Fixed(SCALE / 4) {
+            x = Fixed(x.0 / 2);
+            halvings += 1;
+        }
+
+        let mut term = Fixed::ONE;
+        let mut sum = Fixed::ONE;
+        for n in 1..=12 {
+            term = Fixed(-(term * x).0 / n);
+            sum = sum + term;
+        }
+        for _ in 0..halvings {
+            sum = sum * sum;
+        }
+        sum
+    }
+}
+
+fn round_div(numerator: i128, denominator: i128) -> i64 {
+    let half = denominator.abs() / 2;
+    let rounded = if (numerator < 0) == (denominator < 0) {
+        (numerator.abs() + half) / denominator.abs()
+    } else {
+        -((numerator.abs() + half) / denominator.abs())
+    };
+    rounded.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, other: Fixed) -> Fixed {
+        Fixed(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, other: Fixed) -> Fixed {
+        Fixed(self.0.saturating_sub(other.0))
+    }
+}
+
+/// Product rounded half away from zero
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, other: Fixed) -> Fixed {
+        Fixed(round_div(self.0 as i128 * other.0 as i128, SCALE as i128))
+    }
+}
+
+/// Quotient rounded half away from zero; division by zero saturates
+impl Div for Fixed {
+    type Output = Fixed;
+
+    fn div(self, other: Fixed) -> Fixed {
+        if other.0 == 0 {
+            return if self.0 < 0 { Fixed(i64::MIN) } else { Fixed(i64::MAX) };
+        }
+        Fixed(round_div(self.0 as i128 * SCALE as i128, other.0 as i128))
+    }
+}
+
+impl Sum for Fixed {
+    fn sum<I: Iterator<Item = Fixed>>(iter: I) -> Fixed {
+        iter.fold(Fixed::ZERO, Add::add)
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(f, "{}{}.{:09}", sign, abs / SCALE as u64, abs % SCALE as u64)
+    }
+}
+
+/// Fixed-point counterpart of the decay models `WeightEngine` applies, with the same
+/// rates and the same 10% floor
+pub fn decayed_weight(decay: DecayType, original: Fixed, elapsed: Seconds) -> Fixed {
+    let secs = Fixed::from_int(elapsed.as_u64_saturating().min(i64::MAX as u64 / SCALE as u64) as i64);
+    let decayed = match decay {
+        DecayType::Linear => original - Fixed::from_raw(1_000_000) * secs,
+        DecayType::Exponential => original * (Fixed::from_raw(5_000_000) * secs).exp_neg(),
+        DecayType::Stepped => {
+            let factor = match elapsed.as_i64() {
+                s if s >= 300 => Fixed::from_raw(200_000_000),
+                s if s >= 180 => Fixed::from_raw(500_000_000),
+                s if s >= 60 => Fixed::from_raw(800_000_000),
+                _ => Fixed::ONE,
+            };
+            original * factor
+        }
+    };
+    decayed.max(original * Fixed::from_raw(100_000_000))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic_rounds_deterministically() {
+        let third = Fixed::ONE / Fixed::from_int(3);
+        assert_eq!(third.raw(), 333_333_333);
+        assert_eq!((Fixed::from_raw(-3) / Fixed::from_int(2)).raw(), -2);
+        assert_eq!(Fixed::from_f64(1.5) * Fixed::from_f64(2.0), Fixed::from_int(3));
+        assert_eq!(Fixed::from_f64(0.25).to_string(), "0.250000000");
+        assert_eq!(Fixed::from_f64(-1.5).to_string(), "-1.500000000");
+        assert_eq!((Fixed::ONE / Fixed::ZERO).raw(), i64::MAX);
+    }
+
+    #[test]
+    fn test_exp_neg_tracks_float() {
+        for x in [0.0, 0.1, 0.5, 1.0, 2.3, 3.0, 7.5] {
+            let fixed = Fixed::from_f64(x).exp_neg().to_f64();
+            assert!((fixed - (-x).exp()).abs() < 1e-8, "e^-{}: {}", x, fixed);
+        }
+        assert_eq!(Fixed::from_int(50).exp_neg(), Fixed::ZERO);
+    }
+
+    #[test]
+    fn test_decay_matches_float_models() {
+        use crate::decay::{DecayModel, ExponentialDecay, LinearDecay};
+        let w = Fixed::from_f64(2.5);
+        for secs in [0, 30, 60, 200, 400, 5000] {
+            let age = Seconds::new(secs);
+            let linear = LinearDecay { rate: 0.001 }.compute_weight(2.5, secs as f64);
+            let exponential = ExponentialDecay { rate: 0.005 }.compute_weight(2.5, secs as f64);
+            assert!((decayed_weight(DecayType::Linear, w, age).to_f64() - linear).abs() < 1e-8);
+            assert!((decayed_weight(DecayType::Exponential, w, age).to_f64() - exponential).abs() < 1e-8);
+        }
+    }
+}
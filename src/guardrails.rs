@@ -0,0 +1,301 @@
+// src/guardrails.rs
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::ids::ProposalId;
+use crate::plugins::{PluginConfig, PluginRef};
+use crate::report::FinalizationReport;
+
+/// One way a configuration would trivialize or freeze consensus
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum GuardrailViolation {
+    #[error("decay rate {rate} of {model} is outside [{min}, {max}]")]
+    DecayRate { model: String, rate: f64, min: f64, max: f64 },
+    #[error("trust bonus {bonus} for {validator} is outside (0, {max}]")]
+    TrustBonus { validator: String, bonus: f64, max: f64 },
+    #[error("window of {secs}s is outside [{min}, {max}]s")]
+    WindowLength { secs: u64, min: u64, max: u64 },
+    #[error("{name} {value} is outside [{min}, {max}]")]
+    Threshold { name: &'static str, value: f64, min: f64, max: f64 },
+    #[error("ceiling {ceiling} is below the base threshold {base}")]
+    CeilingBelowBase { base: f64, ceiling: f64 },
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum GuardrailError {
+    #[error("Configuration breaks governance guardrails: {}", format_violations(.0))]
+    Violations(Vec<GuardrailViolation>),
+    #[error("Parameter change {proposal_id} cannot apply the outcome of {report}")]
+    WrongProposal { proposal_id: ProposalId, report: String },
+    #[error("Parameter change {0} was not passed by a certified decision")]
+    NotPassed(ProposalId),
+}
+
+fn format_violations(violations: &[GuardrailViolation]) -> String {
+    violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+/// Bounds every configuration must stay within, whether loaded from a file or changed
+/// by a passed `ParameterChange`. Rates are per second, as the decay models take them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Guardrails {
+    pub min_decay_rate: f64,
+    pub max_decay_rate: f64,
+    pub max_trust_bonus: f64,
+    pub min_window_secs: u64,
+    pub max_window_secs: u64,
+    pub min_threshold: f64,
+    pub max_threshold: f64,
+}
+
+impl Default for Guardrails {
+    fn default() -> Self {
+        Self {
+            min_decay_rate: 1e-6,
+            max_decay_rate: 1.0,
+            max_trust_bonus: 2.0,
+            min_window_secs: 60,
+            max_window_secs: 30 * 86_400,
+            min_threshold: 0.5,
+            max_threshold: 0.95,
+        }
+    }
+}
+
+impl Guardrails {
+    /// Every violation in `config`, in field order
+    pub fn violations(&self, config: &PluginConfig) -> Vec<GuardrailViolation> {
+        let mut violations = Vec::new();
+        if let Some(rate) = config.decay.params.get("rate").and_then(Value::as_f64)
+            && !(self.min_decay_rate..=self.max_decay_rate).contains(&rate)
+        {
+            violations.push(GuardrailViolation::DecayRate {
+                model: config.decay.name.clone(),
+                rate,
+                min: self.min_decay_rate,
+                max: self.max_decay_rate,
+            });
+        }
+        if config.trust.name == "table"
+            && let Some(table) = config.trust.params.as_object()
+        {
+            for (validator, bonus) in table {
+                let bonus = bonus.as_f64().unwrap_or(f64::NAN);
+                if !(bonus > 0.0 && bonus <= self.max_trust_bonus) {
+                    violations.push(GuardrailViolation::TrustBonus {
+                        validator: validator.clone(),
+                        bonus,
+                        max: self.max_trust_bonus,
+                    });
+                }
+            }
+        }
+        if let Some(secs) = config.window_secs
+            && !(self.min_window_secs..=self.max_window_secs).contains(&secs)
+        {
+            violations.push(GuardrailViolation::WindowLength {
+                secs,
+                min: self.min_window_secs,
+                max: self.max_window_secs,
+            });
+        }
+        for (name, value) in [("base threshold", config.base_threshold), ("ceiling", config.ceiling)] {
+            if let Some(value) = value
+                && !(self.min_threshold..=self.max_threshold).contains(&value)
+            {
+                violations.push(GuardrailViolation::Threshold {
+                    name,
+                    value,
+                    min: self.min_threshold,
+                    max: self.max_threshold,
+                });
+            }
+        }
+        if let (Some(base), Some(ceiling)) = (config.base_threshold, config.ceiling)
+            && ceiling < base
+        {
+            violations.push(GuardrailViolation::CeilingBelowBase { base, ceiling });
+        }
+        violations
+    }
+
+    pub fn check(&self, config: &PluginConfig) -> Result<(), GuardrailError> {
+        let violations = self.violations(config);
+        if violations.is_empty() { Ok(()) } else { Err(GuardrailError::Violations(violations)) }
+    }
+}
+
+/// One governed parameter and its new value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "parameter", content = "value", rename_all = "snake_case")]
+pub enum ParameterUpdate {
+    Decay(PluginRef),
+    Escalation(PluginRef),
+    Trust(PluginRef),
+    WindowSecs(u64),
+    BaseThreshold(f64),
+    Ceiling(f64),
+    MinVoteCount(usize),
+}
+
+/// A proposal to change the running configuration, applied once it passes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ParameterChange {
+    pub proposal_id: ProposalId,
+    pub updates: Vec<ParameterUpdate>,
+}
+
+impl ParameterChange {
+    /// The configuration after this change, given the certified report that passed it.
+    /// The updates apply together or not at all: a result outside `guardrails` is
+    /// rejected and `current` stays in force.
+    pub fn apply(
+        &self,
+        current: &PluginConfig,
+        outcome: &FinalizationReport,
+        guardrails: &Guardrails,
+    ) -> Result<PluginConfig, GuardrailError> {
+        if outcome.proposal.proposal_id != self.proposal_id.as_str() {
+            return Err(GuardrailError::WrongProposal {
+                proposal_id: self.proposal_id.clone(),
+                report: outcome.proposal.proposal_id.clone(),
+            });
+        }
+        if !outcome.passed || !outcome.verify_certificate() {
+            return Err(GuardrailError::NotPassed(self.proposal_id.clone()));
+        }
+        let mut next = current.clone();
+        for update in &self.updates {
+            match update {
+                ParameterUpdate::Decay(plugin) => next.decay = plugin.clone(),
+                ParameterUpdate::Escalation(plugin) => next.escalation = plugin.clone(),
+                ParameterUpdate::Trust(plugin) => next.trust = plugin.clone(),
+                ParameterUpdate::WindowSecs(secs) => next.window_secs = Some(*secs),
+                ParameterUpdate::BaseThreshold(value) => next.base_threshold = Some(*value),
+                ParameterUpdate::Ceiling(value) => next.ceiling = Some(*value),
+                ParameterUpdate::MinVoteCount(count) => next.min_vote_count = Some(*count),
+            }
+        }
+        guardrails.check(&next)?;
+        Ok(next)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    use crate::report::ReportInput;
+    use crate::threshold::DecisionRule;
+    use crate::trust::TrustEngine;
+    use crate::vote::{DecayType, ProposalType, SignedVote};
+
+    fn config() -> PluginConfig {
+        serde_json::from_value(json!({
+            "decay": {"name": "linear", "params": {"rate": 0.001}},
+            "escalation": {"name": "linear", "params": {"rate": 0.01}},
+            "trust": {"name": "table", "params": {"alice": 1.5}},
+            "window_secs": 1800,
+            "base_threshold": 0.51,
+            "ceiling": 0.9
+        }))
+        .unwrap()
+    }
+
+    fn passed(proposal_id: &str) -> FinalizationReport {
+        let now = Utc::now();
+        let votes: Vec<SignedVote> = (0..3)
+            .map(|i| {
+                let key = SignedVote::generate_keypair();
+                let voter = format!("voter_{}", i).parse().unwrap();
+                SignedVote::new(voter, proposal_id.parse().unwrap(), 2.0, now - Duration::seconds(5), DecayType::Linear, &key)
+            })
+            .collect();
+        let mut report = FinalizationReport::build(ReportInput {
+            proposal_id,
+            proposal_type: ProposalType::Normal,
+            opened_at: now - Duration::seconds(10),
+            finalized_at: now,
+            votes: &votes,
+            trust: &TrustEngine::new(),
+            max_age_secs: 3600,
+            admissions: None,
+            eligible_weight: Some(6.0),
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        });
+        report.certify(&SignedVote::generate_keypair());
+        assert!(report.passed);
+        report
+    }
+
+    #[test]
+    fn test_config_bounds() {
+        let guardrails = Guardrails::default();
+        assert_eq!(guardrails.check(&config()), Ok(()));
+
+        let mut reckless = config();
+        reckless.decay.params = json!({"rate": 5.0});
+        reckless.trust.params = json!({"alice": 10.0, "bob": -1.0});
+        reckless.window_secs = Some(5);
+        reckless.base_threshold = Some(0.2);
+        reckless.ceiling = Some(0.99);
+        let violations = guardrails.violations(&reckless);
+        assert_eq!(violations.len(), 6);
+        assert!(matches!(violations[0], GuardrailViolation::DecayRate { rate: 5.0, .. }));
+        assert!(matches!(&violations[1], GuardrailViolation::TrustBonus { validator, .. } if validator == "alice"));
+        assert!(matches!(violations[3], GuardrailViolation::WindowLength { secs: 5, .. }));
+
+        let mut inverted = config();
+        inverted.ceiling = Some(0.5);
+        assert_eq!(
+            guardrails.violations(&inverted),
+            vec![GuardrailViolation::CeilingBelowBase { base: 0.51, ceiling: 0.5 }]
+        );
+
+        let path = std::env::temp_dir().join(format!("tdc_guardrails_{}.json", std::process::id()));
+        std::fs::write(&path, serde_json::to_string(&reckless).unwrap()).unwrap();
+        let loaded = PluginConfig::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(loaded, Err(crate::plugins::PluginError::Guardrail(GuardrailError::Violations(v))) if v.len() == 6));
+    }
+
+    #[test]
+    fn test_parameter_change_application() {
+        let guardrails = Guardrails::default();
+        let change = ParameterChange {
+            proposal_id: "params_1".parse().unwrap(),
+            updates: vec![ParameterUpdate::WindowSecs(3600), ParameterUpdate::BaseThreshold(0.6)],
+        };
+        let next = change.apply(&config(), &passed("params_1"), &guardrails).unwrap();
+        assert_eq!((next.window_secs, next.base_threshold), (Some(3600), Some(0.6)));
+
+        assert!(matches!(
+            change.apply(&config(), &passed("other"), &guardrails),
+            Err(GuardrailError::WrongProposal { .. })
+        ));
+        let mut failed = passed("params_1");
+        failed.passed = false;
+        assert_eq!(
+            change.apply(&config(), &failed, &guardrails),
+            Err(GuardrailError::NotPassed("params_1".parse().unwrap()))
+        );
+
+        let freeze = ParameterChange {
+            proposal_id: "params_1".parse().unwrap(),
+            updates: vec![
+                ParameterUpdate::Ceiling(1.0),
+                ParameterUpdate::Decay(PluginRef { name: "exponential".into(), params: json!({"rate": 0.0}) }),
+            ],
+        };
+        let err = freeze.apply(&config(), &passed("params_1"), &guardrails).unwrap_err();
+        assert!(matches!(err, GuardrailError::Violations(ref v) if v.len() == 2));
+        assert!(err.to_string().contains("ceiling 1 is outside"));
+    }
+}
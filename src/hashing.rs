@@ -0,0 +1,358 @@
+// src/hashing.rs
+
+use hex::encode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::vote::SignedVote;
+
+/// Personalization strings; every hash starts with one so digests from different
+/// subsystems can never collide. Bump the version suffix when a layout changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Domain {
+    Block,
+    Transaction,
+    RawPayload,
+    MerkleNode,
+    MerkleEmpty,
+    Vote,
+    SlotSeed,
+    Report,
+    Certificate,
+    State,
+    TieBreak,
+    Amendment,
+    Reputation,
+    Telemetry,
+    Epoch,
+    Commitment,
+    Ballot,
+    Parameters,
+    Config,
+    Attestation,
+    Receipt,
+}
+
+impl Domain {
+    pub fn personalization(&self) -> &'static str {
+        match self {
+            Domain::Block => "tdc/block/v1",
+            Domain::Transaction => "tdc/transaction/v1",
+            Domain::RawPayload => "tdc/raw-payload/v1",
+            Domain::MerkleNode => "tdc/merkle-node/v1",
+            Domain::MerkleEmpty => "tdc/merkle-empty/v1",
+            Domain::Vote => "tdc/vote/v1",
+            Domain::SlotSeed => "tdc/slot-seed/v1",
+            Domain::Report => "tdc/report/v1",
+            Domain::Certificate => "tdc/certificate/v1",
+            Domain::State => "tdc/state/v1",
+            Domain::TieBreak => "tdc/tie-break/v1",
+            Domain::Amendment => "tdc/amendment/v1",
+            Domain::Reputation => "tdc/reputation/v1",
+            Domain::Telemetry => "tdc/telemetry/v1",
+            Domain::Epoch => "tdc/epoch/v1",
+            Domain::Commitment => "tdc/commitment/v1",
+            Domain::Ballot => "tdc/ballot/v1",
+            Domain::Parameters => "tdc/parameters/v1",
+            Domain::Config => "tdc/config/v1",
+            Domain::Attestation => "tdc/attestation/v1",
+            Domain::Receipt => "tdc/receipt/v1",
+        }
+    }
+}
+
+/// SHA-256 over a personalization string followed by length-prefixed fields,
+/// so `("ab", "c")` and `("a", "bc")` hash differently
+pub struct CanonicalHasher {
+    inner: Sha256,
+}
+
+impl CanonicalHasher {
+    pub fn new(domain: Domain) -> Self {
+        let mut hasher = Self { inner: Sha256::new() };
+        hasher.bytes(domain.personalization().as_bytes());
+        hasher
+    }
+
+    pub fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.inner.update((value.len() as u64).to_be_bytes());
+        self.inner.update(value);
+        self
+    }
+
+    pub fn str(&mut self, value: &str) -> &mut Self {
+        self.bytes(value.as_bytes())
+    }
+
+    pub fn u64(&mut self, value: u64) -> &mut Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    pub fn i64(&mut self, value: i64) -> &mut Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    pub fn f64(&mut self, value: f64) -> &mut Self {
+        self.bytes(&value.to_bits().to_be_bytes())
+    }
+
+    pub fn finish(self) -> [u8; 32] {
+        self.inner.finalize().into()
+    }
+
+    pub fn finish_hex(self) -> String {
+        encode(self.finish())
+    }
+}
+
+pub fn block_hash(id: u64, previous_hash: &str, timestamp: i64, data: &str, nonce: u64) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Block);
+    hasher.u64(id).str(previous_hash).i64(timestamp).str(data).u64(nonce);
+    hasher.finish_hex()
+}
+
+/// Merkle leaf for a transaction, over its JSON encoding
+pub fn transaction_leaf(canonical_json: &[u8]) -> [u8; 32] {
+    let mut hasher = CanonicalHasher::new(Domain::Transaction);
+    hasher.bytes(canonical_json);
+    hasher.finish()
+}
+
+/// Merkle leaf for a block whose data is not a transaction list
+pub fn raw_payload_leaf(data: &str) -> [u8; 32] {
+    let mut hasher = CanonicalHasher::new(Domain::RawPayload);
+    hasher.str(data);
+    hasher.finish()
+}
+
+pub fn merkle_node(left: &[u8], right: &[u8]) -> [u8; 32] {
+    let mut hasher = CanonicalHasher::new(Domain::MerkleNode);
+    hasher.bytes(left).bytes(right);
+    hasher.finish()
+}
+
+pub fn merkle_empty() -> [u8; 32] {
+    CanonicalHasher::new(Domain::MerkleEmpty).finish()
+}
+
+/// Identity of a signed vote: the canonical message plus everything the signature
+/// does not cover
+pub fn vote_hash(vote: &SignedVote) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Vote);
+    hasher
+        .str(&vote.message())
+        .f64(vote.original_weight)
+        .str(&format!("{:?}", vote.decay_model))
+        .bytes(vote.public_key.as_bytes())
+        .bytes(&vote.signature.to_bytes());
+    hasher.finish_hex()
+}
+
+/// Seed for proof-of-stake leader selection at `slot`
+pub fn slot_seed(previous_hash: &str, slot: u64) -> [u8; 32] {
+    let mut hasher = CanonicalHasher::new(Domain::SlotSeed);
+    hasher.str(previous_hash).u64(slot);
+    hasher.finish()
+}
+
+/// Identity of a finalization report, over its JSON body without the certificate
+pub fn report_hash(canonical_json: &[u8]) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Report);
+    hasher.bytes(canonical_json);
+    hasher.finish_hex()
+}
+
+/// Identity of a finalization certificate, which later proposals cite as their predecessor
+pub fn certificate_hash(report_hash: &str, issuer: &str, signature: &str) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Certificate);
+    hasher.str(report_hash).str(issuer).str(signature);
+    hasher.finish_hex()
+}
+
+/// Root of a node state hash over its component digests at quantized time `at`
+pub fn state_root(at: i64, proposals: &str, registry: &str, trust: &str) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::State);
+    hasher.str("root").i64(at).str(proposals).str(registry).str(trust);
+    hasher.finish_hex()
+}
+
+/// Coin for breaking an exact tie on `proposal_id`, drawn from a block hash every node agrees on
+pub fn tie_break_seed(block_hash: &str, proposal_id: &str) -> [u8; 32] {
+    let mut hasher = CanonicalHasher::new(Domain::TieBreak);
+    hasher.str(block_hash).str(proposal_id);
+    hasher.finish()
+}
+
+/// One link of a proposal's content chain; the original text is link 0 with an empty `previous`
+pub fn amendment_link(previous: &str, proposal_id: &str, sequence: u64, content: &[u8], submitted_at: i64) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Amendment);
+    hasher.str(previous).str(proposal_id).u64(sequence).bytes(content).i64(submitted_at);
+    hasher.finish_hex()
+}
+
+/// Identity of a reputation export, over its JSON body without the signature
+pub fn reputation_hash(canonical_json: &[u8]) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Reputation);
+    hasher.bytes(canonical_json);
+    hasher.finish_hex()
+}
+
+/// Seed for a proposal's trace and span ids: index 0 gives the trace id, later indexes
+/// give span ids, so re-exporting the same timeline yields the same ids
+pub fn telemetry_id(proposal_id: &str, opened_at: i64, index: u64) -> [u8; 32] {
+    let mut hasher = CanonicalHasher::new(Domain::Telemetry);
+    hasher.str(proposal_id).i64(opened_at).u64(index);
+    hasher.finish()
+}
+
+/// What an epoch certificate signs: the epoch, its boundary, the Merkle root of the
+/// finalized reports' body hashes, and the Merkle root of the block that recorded them
+pub fn epoch_hash(epoch: u64, closed_at: i64, reports_root: &str, block_root: &str) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Epoch);
+    hasher.u64(epoch).i64(closed_at).str(reports_root).str(block_root);
+    hasher.finish_hex()
+}
+
+/// Sealed form of a vote for commit-reveal: binds the full vote, by its `vote_hash`,
+/// to a secret salt so the choice stays hidden until revealed
+pub fn vote_commitment(vote_hash: &str, salt: &[u8]) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Commitment);
+    hasher.str(vote_hash).bytes(salt);
+    hasher.finish_hex()
+}
+
+/// What a voter signs when casting an encrypted ballot: who, on what, when, and the
+/// exact sealed bytes, so a ballot cannot be swapped or replayed under another name
+pub fn ballot_digest(
+    voter_id: &str,
+    proposal_id: &str,
+    submitted_at: &str,
+    ephemeral_key: &[u8],
+    nonce: &[u8],
+    ciphertext: &[u8],
+) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Ballot);
+    hasher.str(voter_id).str(proposal_id).str(submitted_at).bytes(ephemeral_key).bytes(nonce).bytes(ciphertext);
+    hasher.finish_hex()
+}
+
+/// Identity of the decision parameters a report was finalized under, over their JSON
+pub fn parameters_hash(canonical_json: &[u8]) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Parameters);
+    hasher.bytes(canonical_json);
+    hasher.finish_hex()
+}
+
+/// Digest of the node configuration in force when a report was certified
+pub fn config_digest(canonical_json: &[u8]) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Config);
+    hasher.bytes(canonical_json);
+    hasher.finish_hex()
+}
+
+/// What an attested certificate signs: the report body plus the software version,
+/// parameter set, and configuration that produced it; an absent config digest is empty
+pub fn attestation_hash(report_hash: &str, crate_version: &str, parameters_hash: &str, config_digest: &str) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Attestation);
+    hasher.str(report_hash).str(crate_version).str(parameters_hash).str(config_digest);
+    hasher.finish_hex()
+}
+
+/// What a node's vote receipt signs: the vote, the weight it was counted with, the
+/// threshold it counted toward, and whether it was accepted and why not
+pub fn receipt_hash(
+    vote_hash: &str,
+    voter_id: &str,
+    proposal_id: &str,
+    weight: f64,
+    threshold: f64,
+    rejection: Option<&str>,
+    issued_at: &str,
+) -> String {
+    let mut hasher = CanonicalHasher::new(Domain::Receipt);
+    hasher
+        .str(vote_hash)
+        .str(voter_id)
+        .str(proposal_id)
+        .f64(weight)
+        .f64(threshold)
+        .u64(u64::from(rejection.is_none()))
+        .str(rejection.unwrap_or(""))
+        .str(issued_at);
+    hasher.finish_hex()
+}
+
+/// A fixed input and its expected digest, locking a hash format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HashFixture {
+    pub name: String,
+    pub hex: String,
+}
+
+/// Fixtures for every domain, built from fixed inputs
+pub fn fixtures() -> Vec<HashFixture> {
+    let fixture = |name: &str, hex: String| HashFixture { name: name.to_string(), hex };
+    let leaf = transaction_leaf(br#"{"kind":"finalization"}"#);
+    vec![
+        fixture("block", block_hash(1, "00ab", 1_735_689_600, "Transaction 1 Data", 42)),
+        fixture("transaction_leaf", encode(leaf)),
+        fixture("raw_payload_leaf", encode(raw_payload_leaf("Genesis Block"))),
+        fixture("merkle_node", encode(merkle_node(&leaf, &leaf))),
+        fixture("merkle_empty", encode(merkle_empty())),
+        fixture("slot_seed", encode(slot_seed("00ab", 7))),
+        fixture("report", report_hash(br#"{"passed":true}"#)),
+        fixture("certificate", certificate_hash("00ab", "cd", "ef")),
+        fixture("state", state_root(1_735_689_600, "00", "01", "02")),
+        fixture("tie_break", encode(tie_break_seed("00ab", "proposal_1"))),
+        fixture("amendment", amendment_link("00ab", "proposal_1", 1, b"Amended text", 1_735_689_600)),
+        fixture("reputation", reputation_hash(br#"{"version":1}"#)),
+        fixture("telemetry", encode(telemetry_id("proposal_1", 1_735_689_600, 1))),
+        fixture("epoch", epoch_hash(3, 1_735_689_600, "00ab", "cd")),
+        fixture("commitment", vote_commitment("00ab", b"salt")),
+        fixture("ballot", ballot_digest("alice", "proposal_1", "2025-01-01T00:00:00+00:00", b"key", b"nonce", b"sealed")),
+        fixture("parameters", parameters_hash(br#"{"base_threshold":0.51}"#)),
+        fixture("config", config_digest(br#"{"decay_models":[]}"#)),
+        fixture("attestation", attestation_hash("00ab", "0.1.0", "cd", "ef")),
+        fixture("receipt", receipt_hash("00ab", "alice", "proposal_1", 0.5, 0.51, None, "2025-01-01T00:00:00+00:00")),
+    ]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::DecayType;
+    use crate::vectors::{vector_epoch, vector_key};
+
+    #[test]
+    fn test_fixtures_match_committed_formats() {
+        let committed: Vec<HashFixture> =
+            serde_json::from_str(include_str!("../conformance/hashes.json")).unwrap();
+        assert_eq!(fixtures(), committed);
+    }
+
+    #[test]
+    fn test_domains_and_fields_are_separated() {
+        let mut a = CanonicalHasher::new(Domain::Block);
+        a.str("ab").str("c");
+        let mut b = CanonicalHasher::new(Domain::Block);
+        b.str("a").str("bc");
+        assert_ne!(a.finish(), b.finish());
+
+        let mut block = CanonicalHasher::new(Domain::Block);
+        block.str("x");
+        let mut vote = CanonicalHasher::new(Domain::Vote);
+        vote.str("x");
+        assert_ne!(block.finish(), vote.finish());
+    }
+
+    #[test]
+    fn test_vote_hash_covers_weight() {
+        let key = vector_key(0);
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, vector_epoch(), DecayType::Linear, &key);
+        let mut heavier = vote.clone();
+        heavier.original_weight = 2.0;
+        assert_eq!(vote_hash(&vote), vote_hash(&vote.clone()));
+        assert_ne!(vote_hash(&vote), vote_hash(&heavier));
+    }
+}
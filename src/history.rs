@@ -1,7 +1,15 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::hashing;
+use crate::ids::{ProposalId, VoterId};
+use crate::messages::{self, MessageKey};
+use crate::vote::SignedVote;
 
 /// Stores the result of an individual vote
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VoteRecord {
     pub vote_id: String,
     pub weight: f64,
@@ -10,10 +18,58 @@ pub struct VoteRecord {
     pub timestamp: DateTime<Utc>,
 }
 
+/// A vote that was turned away, kept so operators can see why participation is low
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RejectedVote {
+    pub vote_hash: String,
+    pub voter_id: VoterId,
+    pub proposal_id: ProposalId,
+    pub reason: String,
+    /// Peer or channel the vote arrived from
+    pub source: String,
+    pub rejected_at: DateTime<Utc>,
+    /// Cast on a practice proposal, so it does not count against the voter
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub practice: bool,
+}
+
+impl RejectedVote {
+    pub fn new(vote: &SignedVote, reason: impl ToString, source: &str, rejected_at: DateTime<Utc>) -> Self {
+        Self {
+            vote_hash: hashing::vote_hash(vote),
+            voter_id: vote.voter_id.clone(),
+            proposal_id: vote.proposal_id.clone(),
+            reason: reason.to_string(),
+            source: source.to_string(),
+            rejected_at,
+            practice: false,
+        }
+    }
+}
+
+/// Filter for `HistoryAnalyzer::rejections`; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct RejectionQuery {
+    pub proposal_id: Option<ProposalId>,
+    pub voter_id: Option<VoterId>,
+    pub source: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl RejectionQuery {
+    pub fn matches(&self, rejected: &RejectedVote) -> bool {
+        self.proposal_id.as_ref().is_none_or(|p| *p == rejected.proposal_id)
+            && self.voter_id.as_ref().is_none_or(|v| *v == rejected.voter_id)
+            && self.source.as_ref().is_none_or(|s| *s == rejected.source)
+            && self.since.is_none_or(|since| rejected.rejected_at >= since)
+    }
+}
+
 /// Collects vote history and provides analysis
 #[derive(Default)]
 pub struct HistoryAnalyzer {
     pub records: Vec<VoteRecord>,
+    pub rejected: Vec<RejectedVote>,
 }
 
 impl HistoryAnalyzer {
@@ -22,6 +78,25 @@ impl HistoryAnalyzer {
         self.records.push(record);
     }
 
+    /// Log a vote that was not counted, and why
+    pub fn record_rejection(&mut self, rejected: RejectedVote) {
+        self.rejected.push(rejected);
+    }
+
+    /// Rejected votes matching `query`, oldest first
+    pub fn rejections(&self, query: &RejectionQuery) -> Vec<&RejectedVote> {
+        self.rejected.iter().filter(|r| query.matches(r)).collect()
+    }
+
+    /// How many matching votes were rejected for each reason
+    pub fn rejection_counts(&self, query: &RejectionQuery) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for r in self.rejections(query) {
+            *counts.entry(r.reason.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
     /// Average margin of success or failure
     pub fn average_margin(&self) -> f64 {
         let total_margin: f64 = self
@@ -44,17 +119,35 @@ impl HistoryAnalyzer {
         }
     }
 
+    /// Export the records as JSON, oldest first
+    pub fn export_json(&self) -> String {
+        serde_json::to_string_pretty(&self.records).expect("vote records serialize")
+    }
+
+    /// Load records previously written by `export_json`
+    pub fn import_json(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            records: serde_json::from_str(json)?,
+            ..Self::default()
+        })
+    }
+
     /// Display vote history
     pub fn print_history(&self) {
-        println!("\n📊 Historical Vote Log:");
+        println!("{}", messages::text(MessageKey::HistoryHeader, &[]));
         for r in &self.records {
             println!(
-                "- {}: weight={:.4}, threshold={:.4}, passed={}, at {}",
-                r.vote_id,
-                r.weight,
-                r.threshold,
-                r.passed,
-                r.timestamp
+                "{}",
+                messages::text(
+                    MessageKey::HistoryLine,
+                    &[
+                        ("vote_id", r.vote_id.clone()),
+                        ("weight", format!("{:.4}", r.weight)),
+                        ("threshold", format!("{:.4}", r.threshold)),
+                        ("passed", r.passed.to_string()),
+                        ("timestamp", r.timestamp.to_string()),
+                    ],
+                )
             );
         }
     }
@@ -85,7 +178,7 @@ mod tests {
 
         assert_eq!(analyzer.records.len(), 1);
         assert_eq!(analyzer.records[0].vote_id, "vote1");
-        assert_eq!(analyzer.records[0].passed, true);
+        assert!(analyzer.records[0].passed);
 
         analyzer.print_history(); // Should not panic
     }
@@ -126,4 +219,38 @@ mod tests {
         assert_eq!(analyzer.suggested_base_threshold(), 0.50);
         analyzer.print_history(); // Should not panic
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_export_round_trip() {
+        let mut analyzer = HistoryAnalyzer::default();
+        analyzer.record_vote(sample_vote("v1", 0.7, 0.5, true));
+        analyzer.record_vote(sample_vote("v2", 0.3, 0.5, false));
+
+        let imported = HistoryAnalyzer::import_json(&analyzer.export_json()).unwrap();
+        assert_eq!(imported.records, analyzer.records);
+    }
+
+    #[test]
+    fn test_rejections_are_queryable() {
+        use crate::vote::DecayType;
+
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let vote = |voter: &str, proposal: &str| {
+            SignedVote::new(voter.parse().unwrap(), proposal.parse().unwrap(), 1.0, now, DecayType::Linear, &key)
+        };
+
+        let mut analyzer = HistoryAnalyzer::default();
+        analyzer.record_rejection(RejectedVote::new(&vote("alice", "p1"), "Invalid signature", "peer-a", now));
+        analyzer.record_rejection(RejectedVote::new(&vote("bob", "p1"), "Timestamp is too old", "peer-b", now));
+        analyzer.record_rejection(RejectedVote::new(&vote("carol", "p2"), "Invalid signature", "peer-a", now));
+
+        let p1 = RejectionQuery { proposal_id: Some("p1".parse().unwrap()), ..RejectionQuery::default() };
+        assert_eq!(analyzer.rejections(&p1).len(), 2);
+        let from_a = RejectionQuery { source: Some("peer-a".to_string()), ..RejectionQuery::default() };
+        assert_eq!(analyzer.rejection_counts(&from_a).get("Invalid signature"), Some(&2));
+        let later = RejectionQuery { since: Some(now + chrono::Duration::seconds(1)), ..RejectionQuery::default() };
+        assert!(analyzer.rejections(&later).is_empty());
+        assert_eq!(analyzer.rejected[0].vote_hash, hashing::vote_hash(&vote("alice", "p1")));
+    }
+}
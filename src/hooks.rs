@@ -0,0 +1,59 @@
+// src/hooks.rs
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::vote::SignedVote;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Policy hook failed: {0}")]
+pub struct HookError(pub String);
+
+/// Adjusts a vote's computed weight, e.g. to cap or boost particular voters
+pub trait WeightModifier: Send + Sync {
+    fn modify_weight(&self, vote: &SignedVote, weight: f64) -> Result<f64, HookError>;
+}
+
+/// Decides whether a verified vote may enter the tally; `Ok(Some(reason))` rejects it
+pub trait AdmissionPolicy: Send + Sync {
+    fn admit(&self, vote: &SignedVote, now: DateTime<Utc>) -> Result<Option<String>, HookError>;
+}
+
+/// Decides how many seconds to add to a closing voting window
+pub trait ExtensionPolicy: Send + Sync {
+    fn extension_secs(&self, time_left: i64, weight: f64, threshold: f64) -> Result<u64, HookError>;
+}
+
+/// Leaves weights unchanged
+pub struct NoModifier;
+
+impl WeightModifier for NoModifier {
+    fn modify_weight(&self, _vote: &SignedVote, weight: f64) -> Result<f64, HookError> {
+        Ok(weight)
+    }
+}
+
+/// Admits every verified vote
+pub struct AdmitAll;
+
+impl AdmissionPolicy for AdmitAll {
+    fn admit(&self, _vote: &SignedVote, _now: DateTime<Utc>) -> Result<Option<String>, HookError> {
+        Ok(None)
+    }
+}
+
+/// Built-in rule from `VotingWindow::should_extend`: extend when under 20 seconds
+/// remain and the weight is within 90% of the threshold
+pub struct CloseCallExtension {
+    pub extra_secs: u64,
+}
+
+impl ExtensionPolicy for CloseCallExtension {
+    fn extension_secs(&self, time_left: i64, weight: f64, threshold: f64) -> Result<u64, HookError> {
+        if time_left <= 20 && weight >= 0.9 * threshold {
+            Ok(self.extra_secs)
+        } else {
+            Ok(0)
+        }
+    }
+}
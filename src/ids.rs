@@ -0,0 +1,178 @@
+// src/ids.rs
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Longest id accepted, in bytes
+pub const MAX_ID_LEN: usize = 128;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum IdError {
+    #[error("{kind} must not be empty")]
+    Empty { kind: &'static str },
+    #[error("{kind} is longer than {max} bytes", max = MAX_ID_LEN)]
+    TooLong { kind: &'static str },
+    #[error("{kind} {id:?} contains {found:?}; only letters, digits, and _ - . / @ are allowed")]
+    InvalidCharacter { kind: &'static str, id: String, found: char },
+}
+
+/// `validate`'s character rule as a JSON Schema pattern
+const ID_PATTERN: &str = "^[A-Za-z0-9_./@-]+$";
+
+/// Ids are joined with `:` in signed vote messages, so the separator can never appear
+/// in one and two different id pairs can never sign the same message
+fn validate(kind: &'static str, id: &str) -> Result<(), IdError> {
+    if id.is_empty() {
+        return Err(IdError::Empty { kind });
+    }
+    if id.len() > MAX_ID_LEN {
+        return Err(IdError::TooLong { kind });
+    }
+    match id.chars().find(|c| !(c.is_ascii_alphanumeric() || "_-./@".contains(*c))) {
+        Some(found) => Err(IdError::InvalidCharacter { kind, id: id.to_string(), found }),
+        None => Ok(()),
+    }
+}
+
+macro_rules! id_type {
+    ($(#[$doc:meta])* $name:ident, $kind:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        #[serde(try_from = "String", into = "String")]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(id: impl Into<String>) -> Result<Self, IdError> {
+                let id = id.into();
+                validate($kind, &id)?;
+                Ok(Self(id))
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::new(s)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = IdError;
+
+            fn try_from(id: String) -> Result<Self, Self::Error> {
+                Self::new(id)
+            }
+        }
+
+        impl JsonSchema for $name {
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                stringify!($name).into()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                json_schema!({
+                    "type": "string",
+                    "minLength": 1,
+                    "maxLength": MAX_ID_LEN,
+                    "pattern": ID_PATTERN,
+                })
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> String {
+                id.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+id_type!(
+    /// Identifies a voter (validator); never interchangeable with a `ProposalId`
+    VoterId,
+    "Voter id"
+);
+
+id_type!(
+    /// Identifies a proposal; never interchangeable with a `VoterId`
+    ProposalId,
+    "Proposal id"
+);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_validate_and_round_trip() {
+        let voter: VoterId = "validator_001".parse().unwrap();
+        assert_eq!(voter, "validator_001");
+        assert_eq!(voter.to_string(), "validator_001");
+        assert!(ProposalId::new("governance/upgrade-2.1@main").is_ok());
+
+        assert_eq!(VoterId::new(""), Err(IdError::Empty { kind: "Voter id" }));
+        assert!(matches!(ProposalId::new("p1:reject"), Err(IdError::InvalidCharacter { found: ':', .. })));
+        assert!(matches!(VoterId::new("a".repeat(MAX_ID_LEN + 1)), Err(IdError::TooLong { .. })));
+
+        let json = serde_json::to_string(&voter).unwrap();
+        assert_eq!(json, "\"validator_001\"");
+        assert_eq!(serde_json::from_str::<VoterId>(&json).unwrap(), voter);
+        assert!(serde_json::from_str::<ProposalId>("\"bad id\"").is_err());
+    }
+}
@@ -0,0 +1,378 @@
+// src/import.rs
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::bootstrap::BootstrapStrategy;
+use crate::history::{HistoryAnalyzer, VoteRecord};
+use crate::ids::{ProposalId, VoterId};
+use crate::vote::VoteChoice;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ImportError {
+    #[error("Malformed {format:?} input: {message}")]
+    Malformed { format: ImportFormat, message: String },
+    #[error("Record {record}: missing field {field:?}")]
+    MissingField { record: usize, field: String },
+    #[error("Record {record}: invalid {field:?} value {value:?}")]
+    InvalidValue { record: usize, field: String, value: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Comma-separated with a header row
+    Csv,
+    /// An array of flat objects
+    Json,
+}
+
+impl ImportFormat {
+    /// Guess the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(ImportFormat::Csv),
+            "json" => Some(ImportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Which columns (or JSON fields) of an external export hold each part of a
+/// historical vote, and how to read their values. Each record is one voter's vote on
+/// one proposal, alongside that proposal's outcome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ImportMapping {
+    pub proposal: String,
+    pub voter: String,
+    /// Column of the voter's choice; without one every vote counts as an approval
+    pub choice: Option<String>,
+    /// Column of the vote's weight; without one every vote weighs 1.0
+    pub weight: Option<String>,
+    /// Column of the threshold the proposal needed; without one `default_threshold` applies
+    pub threshold: Option<String>,
+    pub outcome: String,
+    pub timestamp: String,
+    /// `strftime` pattern of naive UTC timestamps; RFC 3339 or Unix seconds when unset
+    pub timestamp_format: Option<String>,
+    pub default_threshold: f64,
+    /// Outcome values meaning the proposal passed, compared case-insensitively
+    pub passed_values: Vec<String>,
+    pub approve_values: Vec<String>,
+    pub reject_values: Vec<String>,
+    /// Choice values for an abstention; anything else in the choice column is an error
+    pub abstain_values: Vec<String>,
+}
+
+impl Default for ImportMapping {
+    fn default() -> Self {
+        let values = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+        Self {
+            proposal: "proposal".into(),
+            voter: "voter".into(),
+            choice: Some("choice".into()),
+            weight: Some("weight".into()),
+            threshold: Some("threshold".into()),
+            outcome: "outcome".into(),
+            timestamp: "timestamp".into(),
+            timestamp_format: None,
+            default_threshold: 0.5,
+            passed_values: values(&["passed", "pass", "accepted", "executed", "true", "yes", "1"]),
+            approve_values: values(&["approve", "for", "yes", "yea", "1"]),
+            reject_values: values(&["reject", "against", "no", "nay", "0"]),
+            abstain_values: values(&["abstain"]),
+        }
+    }
+}
+
+impl ImportMapping {
+    fn parse_timestamp(&self, value: &str) -> Option<DateTime<Utc>> {
+        match &self.timestamp_format {
+            Some(format) => NaiveDateTime::parse_from_str(value, format).ok().map(|t| t.and_utc()),
+            None => DateTime::parse_from_rfc3339(value)
+                .map(|t| t.with_timezone(&Utc))
+                .ok()
+                .or_else(|| DateTime::from_timestamp(value.parse().ok()?, 0)),
+        }
+    }
+
+    fn parse_choice(&self, value: &str) -> Option<VoteChoice> {
+        let is = |values: &[String]| values.iter().any(|v| v.eq_ignore_ascii_case(value));
+        if is(&self.approve_values) {
+            Some(VoteChoice::Approve)
+        } else if is(&self.reject_values) {
+            Some(VoteChoice::Reject)
+        } else if is(&self.abstain_values) {
+            Some(VoteChoice::Abstain)
+        } else {
+            None
+        }
+    }
+}
+
+/// One vote from an external governance record
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoricalVote {
+    pub proposal_id: ProposalId,
+    pub voter_id: VoterId,
+    pub choice: VoteChoice,
+    pub weight: f64,
+    pub threshold: f64,
+    /// Whether the proposal passed
+    pub passed: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl HistoricalVote {
+    /// Whether the voter sided with the eventual outcome; abstentions never do
+    pub fn agreed(&self) -> bool {
+        match self.choice {
+            VoteChoice::Approve => self.passed,
+            VoteChoice::Reject => !self.passed,
+            VoteChoice::Abstain => false,
+        }
+    }
+}
+
+/// How a voter's past votes lined up with the outcomes of the proposals they voted on
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrackRecord {
+    pub votes: usize,
+    pub agreed: usize,
+}
+
+/// Governance history from before this deployment, ready to seed threshold tuning
+/// and trust bootstrapping
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoricalImport {
+    pub votes: Vec<HistoricalVote>,
+}
+
+impl HistoricalImport {
+    /// Parse `input` record by record through `mapping`; records are numbered from 1
+    pub fn parse(input: &str, format: ImportFormat, mapping: &ImportMapping) -> Result<Self, ImportError> {
+        let malformed = |message: String| ImportError::Malformed { format, message };
+        let records: Vec<BTreeMap<String, String>> = match format {
+            ImportFormat::Csv => {
+                let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(input.as_bytes());
+                let headers = reader.headers().map_err(|e| malformed(e.to_string()))?.clone();
+                reader
+                    .records()
+                    .map(|row| {
+                        let row = row.map_err(|e| malformed(e.to_string()))?;
+                        Ok(headers.iter().zip(row.iter()).map(|(h, v)| (h.to_string(), v.to_string())).collect())
+                    })
+                    .collect::<Result<_, ImportError>>()?
+            }
+            ImportFormat::Json => {
+                let rows: Vec<BTreeMap<String, Value>> = serde_json::from_str(input).map_err(|e| malformed(e.to_string()))?;
+                rows.into_iter()
+                    .map(|row| {
+                        row.into_iter()
+                            .filter(|(_, v)| !v.is_null())
+                            .map(|(k, v)| match v {
+                                Value::String(s) => (k, s),
+                                other => (k, other.to_string()),
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+        };
+
+        let votes = records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| Self::vote(i + 1, record, mapping))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { votes })
+    }
+
+    /// Read and parse the file at `path`, its format taken from its extension
+    pub fn from_file(path: &Path, mapping: &ImportMapping) -> Result<Self, ImportError> {
+        let format = ImportFormat::from_path(path).ok_or_else(|| ImportError::Malformed {
+            format: ImportFormat::Csv,
+            message: format!("{} is neither .csv nor .json", path.display()),
+        })?;
+        let input = std::fs::read_to_string(path).map_err(|e| ImportError::Malformed { format, message: e.to_string() })?;
+        Self::parse(&input, format, mapping)
+    }
+
+    fn vote(record_no: usize, record: &BTreeMap<String, String>, mapping: &ImportMapping) -> Result<HistoricalVote, ImportError> {
+        let get = |column: &str| {
+            record
+                .get(column)
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| ImportError::MissingField { record: record_no, field: column.to_string() })
+        };
+        let invalid = |field: &str, value: &str| ImportError::InvalidValue {
+            record: record_no,
+            field: field.to_string(),
+            value: value.to_string(),
+        };
+        let number = |column: &Option<String>, default: f64| match column {
+            Some(column) if record.get(column).is_some_and(|v| !v.is_empty()) => {
+                let value = get(column)?;
+                value.parse::<f64>().ok().filter(|n| n.is_finite() && *n >= 0.0).ok_or_else(|| invalid(column, value))
+            }
+            _ => Ok(default),
+        };
+
+        let proposal = get(&mapping.proposal)?;
+        let voter = get(&mapping.voter)?;
+        let outcome = get(&mapping.outcome)?;
+        let timestamp = get(&mapping.timestamp)?;
+        let choice = match &mapping.choice {
+            Some(column) => {
+                let value = get(column)?;
+                mapping.parse_choice(value).ok_or_else(|| invalid(column, value))?
+            }
+            None => VoteChoice::Approve,
+        };
+        Ok(HistoricalVote {
+            proposal_id: proposal.parse().map_err(|_| invalid(&mapping.proposal, proposal))?,
+            voter_id: voter.parse().map_err(|_| invalid(&mapping.voter, voter))?,
+            choice,
+            weight: number(&mapping.weight, 1.0)?,
+            threshold: number(&mapping.threshold, mapping.default_threshold)?,
+            passed: mapping.passed_values.iter().any(|v| v.eq_ignore_ascii_case(outcome)),
+            timestamp: mapping.parse_timestamp(timestamp).ok_or_else(|| invalid(&mapping.timestamp, timestamp))?,
+        })
+    }
+
+    /// Record one result per proposal into `history`: the approving share of its
+    /// decided weight against the threshold it needed, stamped with its last vote
+    pub fn record_into(&self, history: &mut HistoryAnalyzer) {
+        let mut proposals: BTreeMap<&ProposalId, Vec<&HistoricalVote>> = BTreeMap::new();
+        for vote in &self.votes {
+            proposals.entry(&vote.proposal_id).or_default().push(vote);
+        }
+        let mut records: Vec<VoteRecord> = proposals
+            .into_iter()
+            .map(|(proposal_id, votes)| {
+                let decided: f64 = votes.iter().filter(|v| v.choice != VoteChoice::Abstain).map(|v| v.weight).sum();
+                let approving: f64 = votes.iter().filter(|v| v.choice.is_approve()).map(|v| v.weight).sum();
+                let last = votes.iter().max_by_key(|v| v.timestamp).expect("grouped proposals have votes");
+                VoteRecord {
+                    vote_id: proposal_id.to_string(),
+                    weight: if decided > 0.0 { approving / decided } else { 0.0 },
+                    threshold: last.threshold,
+                    passed: last.passed,
+                    timestamp: last.timestamp,
+                }
+            })
+            .collect();
+        records.sort_by_key(|r| r.timestamp);
+        for record in records {
+            history.record_vote(record);
+        }
+    }
+
+    pub fn track_records(&self) -> BTreeMap<VoterId, TrackRecord> {
+        let mut records: BTreeMap<VoterId, TrackRecord> = BTreeMap::new();
+        for vote in &self.votes {
+            let record = records.entry(vote.voter_id.clone()).or_default();
+            record.votes += 1;
+            record.agreed += vote.agreed() as usize;
+        }
+        records
+    }
+
+    /// Starting trust for each voter, rising with how often they sided with the outcome
+    pub fn bootstrap_strategy(&self, min_bonus: f64, max_bonus: f64) -> BootstrapStrategy {
+        let stakes = self.track_records().into_iter().map(|(id, r)| (id.to_string(), r.agreed as f64)).collect();
+        BootstrapStrategy::StakeProportional { stakes, min_bonus, max_bonus }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+proposal,voter,choice,weight,threshold,outcome,timestamp
+p1,alice,for,2,0.6,Passed,2024-03-01T10:00:00Z
+p1,bob,against,1,0.6,Passed,2024-03-01T11:00:00Z
+p2,alice,against,1,0.5,failed,1709380800
+p2,carol,abstain,,0.5,failed,1709384400
+";
+
+    #[test]
+    fn test_csv_import_feeds_history_and_bootstrap() {
+        let import = HistoricalImport::parse(CSV, ImportFormat::Csv, &ImportMapping::default()).unwrap();
+        assert_eq!(import.votes.len(), 4);
+        assert_eq!(import.votes[3].weight, 1.0);
+        assert!(import.votes[0].passed && !import.votes[2].passed);
+
+        let mut history = HistoryAnalyzer::default();
+        import.record_into(&mut history);
+        let ids: Vec<&str> = history.records.iter().map(|r| r.vote_id.as_str()).collect();
+        assert_eq!(ids, vec!["p1", "p2"]);
+        assert!((history.records[0].weight - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!((history.records[1].weight, history.records[1].threshold), (0.0, 0.5));
+
+        let alice = &import.track_records()[&"alice".parse::<VoterId>().unwrap()];
+        assert_eq!((alice.votes, alice.agreed), (2, 2));
+        let scores = import.bootstrap_strategy(1.0, 1.5).scores(&["alice", "bob", "carol", "dave"]).unwrap();
+        assert_eq!((scores["alice"], scores["bob"], scores["carol"], scores["dave"]), (1.5, 1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_json_import_with_custom_mapping() {
+        let json = r#"[
+            {"id": "grant-7", "member": "alice", "yes": 3.5, "result": "executed", "at": "01/03/2024 10:00"},
+            {"id": "grant-7", "member": "bob", "yes": null, "result": "executed", "at": "01/03/2024 11:30"}
+        ]"#;
+        let mapping = ImportMapping {
+            proposal: "id".into(),
+            voter: "member".into(),
+            choice: None,
+            weight: Some("yes".into()),
+            threshold: None,
+            outcome: "result".into(),
+            timestamp: "at".into(),
+            timestamp_format: Some("%d/%m/%Y %H:%M".into()),
+            default_threshold: 0.67,
+            ..ImportMapping::default()
+        };
+        let import = HistoricalImport::parse(json, ImportFormat::Json, &mapping).unwrap();
+        assert_eq!(import.votes[0].weight, 3.5);
+        assert_eq!(import.votes[1].weight, 1.0);
+        assert_eq!(import.votes[1].threshold, 0.67);
+        assert_eq!(import.votes[1].timestamp.to_rfc3339(), "2024-03-01T11:30:00+00:00");
+        assert!(import.votes.iter().all(|v| v.passed && v.agreed()));
+
+        let decoded: ImportMapping = serde_json::from_str(r#"{"proposal": "id"}"#).unwrap();
+        assert_eq!(decoded, ImportMapping { proposal: "id".into(), ..ImportMapping::default() });
+    }
+
+    #[test]
+    fn test_import_reports_the_offending_record() {
+        let mapping = ImportMapping::default();
+        let parse = |csv: &str| HistoricalImport::parse(csv, ImportFormat::Csv, &mapping);
+        let header = "proposal,voter,choice,weight,threshold,outcome,timestamp\n";
+        assert_eq!(
+            parse(&format!("{}p1,alice,for,1,0.5,passed,2024-03-01T10:00:00Z\np1,,for,1,0.5,passed,2024-03-01T10:00:00Z\n", header)),
+            Err(ImportError::MissingField { record: 2, field: "voter".into() })
+        );
+        assert_eq!(
+            parse(&format!("{}p1,alice,maybe,1,0.5,passed,2024-03-01T10:00:00Z\n", header)),
+            Err(ImportError::InvalidValue { record: 1, field: "choice".into(), value: "maybe".into() })
+        );
+        assert!(matches!(
+            parse(&format!("{}p1,alice,for,-2,0.5,passed,2024-03-01T10:00:00Z\n", header)),
+            Err(ImportError::InvalidValue { field, .. }) if field == "weight"
+        ));
+        assert!(matches!(
+            HistoricalImport::parse("{}", ImportFormat::Json, &mapping),
+            Err(ImportError::Malformed { format: ImportFormat::Json, .. })
+        ));
+    }
+}
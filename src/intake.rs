@@ -0,0 +1,528 @@
+// src/intake.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::blockchain::{BlockPayload, Blockchain, Transaction, VoteEntry};
+use crate::decay::DecayBounds;
+use crate::hashing;
+use crate::history::{HistoryAnalyzer, RejectedVote};
+use crate::ids::ProposalId;
+use crate::receipt::{ReceiptIssuer, VoteReceipt};
+use crate::registry::ValidatorRegistry;
+use crate::snapshot::{SeriesError, SnapshotRecorder};
+use crate::units::Seconds;
+use crate::verify::{ReplayGuard, VerificationError, VerificationPolicy, VersionTracker};
+use crate::vote::{SignedVote, SignedVoteBatch};
+
+/// How a vote reached this node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Cli,
+    Http,
+    Gossip,
+    Bundle,
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Channel::Cli => "cli",
+            Channel::Http => "http",
+            Channel::Gossip => "gossip",
+            Channel::Bundle => "bundle",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Ingestion channel plus the peer that handed the vote over, when known
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoteSource {
+    pub channel: Channel,
+    pub peer: Option<String>,
+}
+
+impl VoteSource {
+    pub fn new(channel: Channel) -> Self {
+        Self { channel, peer: None }
+    }
+
+    pub fn from_peer(channel: Channel, peer: &str) -> Self {
+        Self {
+            channel,
+            peer: Some(peer.to_string()),
+        }
+    }
+}
+
+/// Rendered as `channel` or `channel/peer`
+impl fmt::Display for VoteSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.peer {
+            Some(peer) => write!(f, "{}/{}", self.channel, peer),
+            None => write!(f, "{}", self.channel),
+        }
+    }
+}
+
+/// A verified vote together with where and when it arrived
+#[derive(Debug, Clone)]
+pub struct AdmittedVote {
+    pub vote: SignedVote,
+    pub source: VoteSource,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Recorded on chain so syncing nodes learn when the vote was admitted
+impl From<&AdmittedVote> for VoteEntry {
+    fn from(admitted: &AdmittedVote) -> Self {
+        VoteEntry {
+            received_at: Some(admitted.received_at),
+            ..VoteEntry::from(&admitted.vote)
+        }
+    }
+}
+
+/// When each admitted vote was first received, keyed by vote hash. Recounts and syncs
+/// verify against the receipt time, so a vote that was timely when it arrived is not
+/// later rejected because its signature has since grown older than `max_age`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdmissionLog {
+    received: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl AdmissionLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receipt times of every vote on `chain` that was recorded with one
+    pub fn from_chain(chain: &Blockchain) -> Self {
+        let mut log = Self::new();
+        for block in &chain.blocks {
+            for tx in BlockPayload::decode(&block.data).transactions() {
+                if let Transaction::Vote(entry) = tx
+                    && let (Some(received_at), Some(vote)) = (entry.received_at, entry.to_signed_vote())
+                {
+                    log.record(&vote, received_at);
+                }
+            }
+        }
+        log
+    }
+
+    /// Note `vote` as received at `received_at`; an earlier receipt is kept
+    pub fn record(&mut self, vote: &SignedVote, received_at: DateTime<Utc>) {
+        let at = self.received.entry(hashing::vote_hash(vote)).or_insert(received_at);
+        *at = (*at).min(received_at);
+    }
+
+    pub fn received_at(&self, vote: &SignedVote) -> Option<DateTime<Utc>> {
+        self.received.get(&hashing::vote_hash(vote)).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.received.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.received.is_empty()
+    }
+
+    /// Verify `vote` as of its receipt, or as of `now` if it was never admitted here
+    pub fn verify(&self, vote: &SignedVote, max_age: Seconds, now: DateTime<Utc>) -> Result<(), VerificationError> {
+        vote.verify_at(self.received_at(vote).unwrap_or(now), max_age)
+    }
+}
+
+/// Counters for one channel. Latency runs from the vote timestamp to its arrival.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChannelStats {
+    pub received: usize,
+    pub accepted: usize,
+    pub rejected: usize,
+    /// Admitted votes later withdrawn by re-verification
+    #[serde(default)]
+    pub retracted: usize,
+    pub total_latency_secs: i64,
+    pub max_latency_secs: i64,
+}
+
+impl ChannelStats {
+    pub fn acceptance_rate(&self) -> f64 {
+        if self.received == 0 { 0.0 } else { self.accepted as f64 / self.received as f64 }
+    }
+
+    pub fn mean_latency_secs(&self) -> f64 {
+        if self.received == 0 { 0.0 } else { self.total_latency_secs as f64 / self.received as f64 }
+    }
+
+    fn observe(&mut self, latency: Seconds, accepted: bool) {
+        let latency = latency.as_i64().max(0);
+        self.received += 1;
+        self.total_latency_secs = self.total_latency_secs.saturating_add(latency);
+        self.max_latency_secs = self.max_latency_secs.max(latency);
+        if accepted {
+            self.accepted += 1;
+        } else {
+            self.rejected += 1;
+        }
+    }
+}
+
+/// Verifies incoming votes, tags the admitted ones with their source, and keeps
+/// per-channel statistics; rejections go to the history's rejected-votes log
+#[derive(Default)]
+pub struct VoteIntake {
+    pub policy: VerificationPolicy,
+    /// Decay parameters voters may sign; votes outside them are rejected
+    pub decay_bounds: DecayBounds,
+    /// Practice proposals (see `ProposalRegistry::practice_ids`); votes on them are
+    /// verified as usual, but their rejections are logged as practice
+    pub practice: BTreeSet<ProposalId>,
+    admitted: Vec<AdmittedVote>,
+    admissions: AdmissionLog,
+    stats: BTreeMap<Channel, ChannelStats>,
+    versions: VersionTracker,
+    replays: ReplayGuard,
+    snapshots: Option<SnapshotRecorder>,
+}
+
+impl VoteIntake {
+    pub fn new(max_age: Seconds) -> Self {
+        Self::with_policy(VerificationPolicy::new(max_age))
+    }
+
+    pub fn with_policy(policy: VerificationPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Persist tally snapshots through `recorder` as votes are admitted. A proposal's
+    /// series starts at its first admitted vote unless opened on the recorder beforehand.
+    pub fn with_snapshots(mut self, recorder: SnapshotRecorder) -> Self {
+        self.snapshots = Some(recorder);
+        self
+    }
+
+    pub fn snapshots(&self) -> Option<&SnapshotRecorder> {
+        self.snapshots.as_ref()
+    }
+
+    /// Persist any snapshot slots due by `now`, returning how many points were written;
+    /// call periodically so series keep advancing while no votes arrive
+    pub fn record_snapshots(&mut self, now: DateTime<Utc>) -> Result<usize, SeriesError> {
+        let Some(recorder) = &mut self.snapshots else {
+            return Ok(0);
+        };
+        if !recorder.is_due(now) {
+            return Ok(0);
+        }
+        let votes: Vec<SignedVote> = self.admitted.iter().map(|a| a.vote.clone()).collect();
+        recorder.record(now, &votes)
+    }
+
+    /// Verify `vote` as of `received_at` and admit it or log why not
+    pub fn submit(
+        &mut self,
+        vote: SignedVote,
+        source: VoteSource,
+        received_at: DateTime<Utc>,
+        registry: Option<&ValidatorRegistry>,
+        history: &mut HistoryAnalyzer,
+    ) -> Result<(), VerificationError> {
+        let result = vote
+            .verify_with_policy(&self.policy, received_at)
+            .and_then(|_| vote.check_decay(&self.decay_bounds))
+            .and_then(|_| registry.map_or(Ok(()), |r| r.check_vote(&vote)))
+            .and_then(|_| self.replays.check(&vote))
+            .and_then(|_| self.versions.accept(&vote))
+            .and_then(|_| self.replays.accept(&vote));
+
+        self.stats
+            .entry(source.channel)
+            .or_default()
+            .observe(Seconds::between(vote.timestamp, received_at), result.is_ok());
+        match &result {
+            Ok(()) => {
+                self.admissions.record(&vote, received_at);
+                if let Some(recorder) = &mut self.snapshots {
+                    recorder.open(&vote.proposal_id, vote.timestamp);
+                }
+                self.admitted.push(AdmittedVote { vote, source, received_at });
+                // Slots that fail to persist stay due, so the next periodic
+                // `record_snapshots` call retries them and reports the error
+                let _ = self.record_snapshots(received_at);
+            }
+            Err(e) => self.log_rejection(&vote, e, &source, received_at, history),
+        }
+        result
+    }
+
+    /// `submit`, then have `issuer` weigh the vote and sign a receipt of the outcome
+    pub fn submit_with_receipt(
+        &mut self,
+        vote: SignedVote,
+        source: VoteSource,
+        received_at: DateTime<Utc>,
+        registry: Option<&ValidatorRegistry>,
+        history: &mut HistoryAnalyzer,
+        issuer: &ReceiptIssuer,
+    ) -> VoteReceipt {
+        let receipted = vote.clone();
+        let outcome = self.submit(vote, source, received_at, registry, history);
+        issuer.issue(&receipted, &outcome, received_at, registry)
+    }
+
+    /// Submit every vote in a relayed batch from `source`; results line up with the batch
+    pub fn submit_batch(
+        &mut self,
+        batch: &SignedVoteBatch,
+        source: VoteSource,
+        received_at: DateTime<Utc>,
+        registry: Option<&ValidatorRegistry>,
+        history: &mut HistoryAnalyzer,
+    ) -> Vec<Result<(), VerificationError>> {
+        batch
+            .votes()
+            .iter()
+            .map(|vote| self.submit(vote.clone(), source.clone(), received_at, registry, history))
+            .collect()
+    }
+
+    pub fn admitted(&self) -> &[AdmittedVote] {
+        &self.admitted
+    }
+
+    /// Receipt times of every vote admitted so far, retracted ones included
+    pub fn admissions(&self) -> &AdmissionLog {
+        &self.admissions
+    }
+
+    pub fn find(&self, vote_hash: &str) -> Option<&AdmittedVote> {
+        self.admitted.iter().find(|a| hashing::vote_hash(&a.vote) == vote_hash)
+    }
+
+    /// Withdraw a previously admitted vote, logging `reason` as its rejection
+    pub fn retract(
+        &mut self,
+        vote_hash: &str,
+        reason: &str,
+        at: DateTime<Utc>,
+        history: &mut HistoryAnalyzer,
+    ) -> Option<AdmittedVote> {
+        let index = self.admitted.iter().position(|a| hashing::vote_hash(&a.vote) == vote_hash)?;
+        let retracted = self.admitted.remove(index);
+        self.stats.entry(retracted.source.channel).or_default().retracted += 1;
+        self.log_rejection(&retracted.vote, reason, &retracted.source, at, history);
+        Some(retracted)
+    }
+
+    fn log_rejection(
+        &self,
+        vote: &SignedVote,
+        reason: impl ToString,
+        source: &VoteSource,
+        at: DateTime<Utc>,
+        history: &mut HistoryAnalyzer,
+    ) {
+        let mut rejected = RejectedVote::new(vote, reason, &source.to_string(), at);
+        rejected.practice = self.practice.contains(&vote.proposal_id);
+        history.record_rejection(rejected);
+    }
+
+    /// Admitted votes without their attribution, e.g. for `ReportInput::votes`
+    pub fn votes(&self) -> Vec<SignedVote> {
+        self.admitted.iter().map(|a| a.vote.clone()).collect()
+    }
+
+    pub fn stats(&self) -> &BTreeMap<Channel, ChannelStats> {
+        &self.stats
+    }
+
+    /// Admitted vote counts per peer, for spotting a single source dominating
+    pub fn by_peer(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for a in &self.admitted {
+            *counts.entry(a.source.to_string()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::RejectionQuery;
+    use crate::vote::DecayType;
+    use chrono::Duration;
+
+    #[test]
+    fn test_submit_tags_votes_and_tracks_channels() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let vote = |voter: &str, age: i64| {
+            SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, now - Duration::seconds(age), DecayType::Linear, &key)
+        };
+        let mut intake = VoteIntake::new(Seconds::new(60));
+        let mut history = HistoryAnalyzer::default();
+
+        intake
+            .submit(vote("alice", 2), VoteSource::from_peer(Channel::Gossip, "peer-a"), now, None, &mut history)
+            .unwrap();
+        intake
+            .submit(vote("bob", 4), VoteSource::new(Channel::Http), now, None, &mut history)
+            .unwrap();
+        assert_eq!(
+            intake.submit(vote("carol", 600), VoteSource::from_peer(Channel::Gossip, "peer-b"), now, None, &mut history),
+            Err(VerificationError::TimestampExpired)
+        );
+
+        assert_eq!(intake.votes().len(), 2);
+        assert_eq!(intake.admitted()[0].source.to_string(), "gossip/peer-a");
+        let gossip = &intake.stats()[&Channel::Gossip];
+        assert_eq!((gossip.received, gossip.accepted, gossip.rejected), (2, 1, 1));
+        assert_eq!(gossip.acceptance_rate(), 0.5);
+        assert_eq!(gossip.mean_latency_secs(), 301.0);
+        assert_eq!(intake.stats()[&Channel::Http].acceptance_rate(), 1.0);
+
+        let from_b = RejectionQuery { source: Some("gossip/peer-b".to_string()), ..RejectionQuery::default() };
+        assert_eq!(history.rejections(&from_b)[0].reason, "Timestamp is too old");
+    }
+
+    #[test]
+    fn test_decay_params_outside_bounds_are_rejected() {
+        use crate::decay::{DecayParams, DecayParamsError};
+
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let vote = |voter: &str, rate: f64| {
+            SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Exponential, &key)
+                .with_decay_params(DecayParams { rate: Some(rate), ..DecayParams::default() }, &key)
+        };
+        let mut intake = VoteIntake::new(Seconds::new(300));
+        let mut history = HistoryAnalyzer::default();
+        let source = VoteSource::new(Channel::Http);
+
+        assert_eq!(intake.submit(vote("alice", 0.01), source.clone(), now, None, &mut history), Ok(()));
+        assert!(matches!(
+            intake.submit(vote("bob", 0.0), source.clone(), now, None, &mut history),
+            Err(VerificationError::DecayOutOfBounds(DecayParamsError::RateOutOfBounds { .. }))
+        ));
+        intake.decay_bounds.min_rate = 0.0;
+        assert_eq!(intake.submit(vote("bob", 0.0), source, now, None, &mut history), Ok(()));
+    }
+
+    #[test]
+    fn test_out_of_order_versions_are_rejected() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let first = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key);
+        let amended = first.amend(1.0, now, crate::vote::VoteChoice::Reject, &key);
+        let mut intake = VoteIntake::new(Seconds::new(60));
+        let mut history = HistoryAnalyzer::default();
+
+        intake.submit(amended, VoteSource::new(Channel::Gossip), now, None, &mut history).unwrap();
+        assert_eq!(
+            intake.submit(first, VoteSource::new(Channel::Gossip), now, None, &mut history),
+            Err(VerificationError::OutOfOrderVersion { latest: 1, got: 0 })
+        );
+        assert_eq!(intake.votes().len(), 1);
+    }
+
+    #[test]
+    fn test_submit_batch_admits_each_vote() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let vote = |voter: &str, age: i64| {
+            SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, now - Duration::seconds(age), DecayType::Linear, &key)
+        };
+        let batch = SignedVoteBatch::from_votes("p1".parse().unwrap(), vec![vote("alice", 1), vote("bob", 600)]).unwrap();
+        let mut intake = VoteIntake::new(Seconds::new(60));
+        let mut history = HistoryAnalyzer::default();
+
+        let results = intake.submit_batch(&batch, VoteSource::from_peer(Channel::Gossip, "relayer"), now, None, &mut history);
+        assert_eq!(results, vec![Ok(()), Err(VerificationError::TimestampExpired)]);
+        assert_eq!(intake.by_peer()["gossip/relayer"], 1);
+    }
+
+    #[test]
+    fn test_admission_time_freezes_validity() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let signed_at = now - Duration::seconds(3600);
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, signed_at, DecayType::Linear, &key);
+        let mut intake = VoteIntake::new(Seconds::new(60));
+        let mut history = HistoryAnalyzer::default();
+        let received_at = signed_at + Duration::seconds(50);
+        intake.submit(vote.clone(), VoteSource::new(Channel::Http), received_at, None, &mut history).unwrap();
+
+        // A recount an hour later would reject it against the clock, but not against its receipt
+        assert_eq!(vote.verify_at(now, intake.policy.max_age), Err(VerificationError::TimestampExpired));
+        assert_eq!(intake.admissions().verify(&vote, intake.policy.max_age, now), Ok(()));
+        let stranger = SignedVote::new("bob".parse().unwrap(), "p1".parse().unwrap(), 1.0, signed_at, DecayType::Linear, &key);
+        assert_eq!(intake.admissions().verify(&stranger, intake.policy.max_age, now), Err(VerificationError::TimestampExpired));
+
+        // Syncing nodes recover the receipt from the chain
+        let mut chain = Blockchain::with_genesis("Genesis Block".to_string(), 1);
+        chain.add_transactions(&[Transaction::Vote(VoteEntry::from(&intake.admitted()[0]))]);
+        let synced = AdmissionLog::from_chain(&chain);
+        assert_eq!(synced.received_at(&vote), Some(received_at));
+        assert_eq!(&synced, intake.admissions());
+    }
+
+    #[test]
+    fn test_replayed_vote_rejected() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key)
+            .with_nonce(42, &key);
+        let mut intake = VoteIntake::new(Seconds::new(60));
+        let mut history = HistoryAnalyzer::default();
+
+        intake.submit(vote.clone(), VoteSource::new(Channel::Http), now, None, &mut history).unwrap();
+        assert_eq!(
+            intake.submit(vote, VoteSource::new(Channel::Gossip), now, None, &mut history),
+            Err(VerificationError::Replayed)
+        );
+        assert_eq!(history.rejections(&RejectionQuery::default())[0].reason, "Vote was already submitted");
+    }
+
+    #[test]
+    fn test_admissions_persist_snapshots_as_slots_come_due() {
+        use crate::snapshot::{DEFAULT_SNAPSHOT_CADENCE, SeriesStore};
+        use crate::threshold::ThresholdEscalator;
+        use crate::trust::TrustEngine;
+        use crate::vote::ProposalType;
+
+        let key = SignedVote::generate_keypair();
+        let opened = Utc::now() - Duration::seconds(100);
+        let at = |secs: i64| opened + Duration::seconds(secs);
+        let vote = |voter: &str, secs: i64| {
+            SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, at(secs), DecayType::Linear, &key)
+        };
+        let path = std::env::temp_dir().join(format!("tdc-intake-series-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = SeriesStore::new(&path);
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+        let recorder = SnapshotRecorder::new(store.clone(), DEFAULT_SNAPSHOT_CADENCE, escalator, Box::new(TrustEngine::new()));
+        let mut intake = VoteIntake::new(Seconds::new(600)).with_snapshots(recorder);
+        let mut history = HistoryAnalyzer::default();
+
+        intake.submit(vote("alice", 0), VoteSource::new(Channel::Http), at(1), None, &mut history).unwrap();
+        intake.submit(vote("bob", 40), VoteSource::new(Channel::Http), at(41), None, &mut history).unwrap();
+        assert_eq!(intake.record_snapshots(at(95)).unwrap(), 2);
+
+        let series = store.load().unwrap();
+        assert_eq!(series.iter().map(|p| (p.elapsed_secs, p.tally.votes)).collect::<Vec<_>>(), vec![(0, 1), (30, 1), (60, 2), (90, 2)]);
+        let _ = std::fs::remove_file(&path);
+    }
+}
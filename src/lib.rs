@@ -0,0 +1,79 @@
+pub mod amendment;
+pub mod api;
+#[cfg(feature = "archive")]
+pub mod archive;
+pub mod ballot;
+pub mod block_producer;
+pub mod blockchain;
+pub mod bootstrap;
+pub mod builder;
+pub mod carry_over;
+pub mod commit_reveal;
+pub mod clock;
+pub mod committee;
+pub mod conformance;
+pub mod decay;
+pub mod dedup;
+pub mod delegation;
+pub mod deposit;
+pub mod differential;
+pub mod discovery;
+pub mod epoch;
+pub mod escrow;
+pub mod explorer;
+pub mod fixed;
+pub mod guardrails;
+pub mod hashing;
+pub mod history;
+pub mod hooks;
+pub mod ids;
+pub mod import;
+pub mod intake;
+pub mod liveness;
+pub mod messages;
+pub mod metrics;
+pub mod momentum;
+pub mod registry;
+pub mod namespace;
+pub mod narrative;
+pub mod opportunity;
+pub mod payload;
+pub mod plugins;
+pub mod policy;
+pub mod pos;
+pub mod progress;
+pub mod proposal;
+pub mod receipt;
+pub mod remote_signer;
+pub mod replica;
+pub mod report;
+pub mod reputation;
+pub mod revocation;
+pub mod reverify;
+pub mod rounds;
+pub mod schema;
+pub mod score;
+pub mod scheme;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod signer;
+pub mod simulation;
+pub mod snapshot;
+pub mod stake;
+pub mod state_hash;
+pub mod storage;
+pub mod tally;
+pub mod telemetry;
+pub mod threshold;
+pub mod tiers;
+pub mod trust;
+pub mod trust_audit;
+pub mod tune;
+pub mod units;
+pub mod vectors;
+pub mod verify;
+pub mod vote;
+pub mod voter_view;
+pub mod webhook;
+pub mod weight_engine;
+pub mod window;
@@ -0,0 +1,138 @@
+// src/liveness.rs
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::units::Seconds;
+
+/// When vote arrivals count as stalled: fewer than `min_arrivals` network-wide over
+/// the trailing `window`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LivenessPolicy {
+    pub window: Seconds,
+    pub min_arrivals: usize,
+}
+
+impl Default for LivenessPolicy {
+    fn default() -> Self {
+        Self { window: Seconds::new(600), min_arrivals: 1 }
+    }
+}
+
+/// A stretch during which threshold escalation was paused; open while `until` is unset
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PausedInterval {
+    pub from: DateTime<Utc>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl PausedInterval {
+    /// How much of `start..end` this interval covers; an open interval runs to `end`
+    pub fn overlap(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Seconds {
+        let from = self.from.max(start);
+        let until = self.until.map_or(end, |until| until.min(end));
+        Seconds::between(from, until).max(Seconds::ZERO)
+    }
+}
+
+/// Total paused time within `start..end`
+pub fn paused_between(intervals: &[PausedInterval], start: DateTime<Utc>, end: DateTime<Utc>) -> Seconds {
+    intervals.iter().map(|i| i.overlap(start, end)).fold(Seconds::ZERO, |total, s| total + s)
+}
+
+/// Watches the network-wide vote arrival rate, pausing threshold escalation while
+/// arrivals stall (e.g. during an outage) and resuming once they pick up again
+#[derive(Debug, Clone)]
+pub struct LivenessMonitor {
+    pub policy: LivenessPolicy,
+    started_at: DateTime<Utc>,
+    arrivals: VecDeque<DateTime<Utc>>,
+    intervals: Vec<PausedInterval>,
+}
+
+impl LivenessMonitor {
+    /// Start watching at `now`; no stall is detected until a full window has passed
+    pub fn new(policy: LivenessPolicy, now: DateTime<Utc>) -> Self {
+        Self { policy, started_at: now, arrivals: VecDeque::new(), intervals: Vec::new() }
+    }
+
+    /// Note a vote arriving at `at`; resumes escalation if arrivals have recovered
+    pub fn record_arrival(&mut self, at: DateTime<Utc>) {
+        self.arrivals.push_back(at);
+        self.observe(at);
+    }
+
+    /// Re-check the arrival rate as of `now`, pausing or resuming escalation.
+    /// Returns whether escalation is paused; each pause is kept in `intervals`.
+    pub fn observe(&mut self, now: DateTime<Utc>) -> bool {
+        let cutoff = now - self.policy.window.to_duration();
+        while self.arrivals.front().is_some_and(|at| *at < cutoff) {
+            self.arrivals.pop_front();
+        }
+        let stalled = self.arrivals.len() < self.policy.min_arrivals && self.started_at <= cutoff;
+        match (stalled, self.intervals.last_mut()) {
+            (true, Some(PausedInterval { until: None, .. })) => {}
+            (true, _) => self.intervals.push(PausedInterval { from: now, until: None }),
+            (false, Some(open @ PausedInterval { until: None, .. })) => open.until = Some(now),
+            (false, _) => {}
+        }
+        self.is_paused()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.intervals.last().is_some_and(|i| i.until.is_none())
+    }
+
+    /// Every pause so far, oldest first
+    pub fn intervals(&self) -> &[PausedInterval] {
+        &self.intervals
+    }
+
+    pub fn paused_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Seconds {
+        paused_between(&self.intervals, start, end)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::threshold::ThresholdEscalator;
+    use crate::vote::ProposalType;
+
+    #[test]
+    fn test_stalls_pause_escalation_until_arrivals_resume() {
+        let start = Utc::now();
+        let at = |secs: i64| start + Duration::seconds(secs);
+        let policy = LivenessPolicy { window: Seconds::new(60), min_arrivals: 2 };
+        let mut monitor = LivenessMonitor::new(policy, start);
+
+        // Too early to judge, then steady arrivals
+        assert!(!monitor.observe(at(30)));
+        for secs in [40, 70, 100] {
+            monitor.record_arrival(at(secs));
+        }
+        assert!(!monitor.observe(at(120)));
+
+        // The network goes quiet, then one vote is not enough to resume
+        assert!(monitor.observe(at(200)));
+        assert!(monitor.observe(at(300)));
+        monitor.record_arrival(at(400));
+        assert!(monitor.is_paused());
+        monitor.record_arrival(at(410));
+        assert!(!monitor.is_paused());
+        assert_eq!(monitor.intervals(), &[PausedInterval { from: at(200), until: Some(at(410)) }]);
+
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Constitutional);
+        assert_eq!(monitor.paused_between(at(0), at(500)), Seconds::new(210));
+        assert_eq!(monitor.paused_between(at(300), at(350)), Seconds::new(50));
+        // Fifty paused seconds are not escalated through
+        let paused = escalator.threshold_with_pauses(at(250), at(0), monitor.intervals());
+        assert_eq!(paused, escalator.threshold_with_profile(at(200), at(0)));
+        assert!(paused < escalator.threshold_with_profile(at(250), at(0)));
+    }
+}
@@ -1,50 +1,82 @@
-mod decay;
-mod threshold;
-mod verify;
-mod vote;
-mod window;
-mod weight_engine;
-mod trust;
-mod history;
-mod simulation; 
-mod blockchain;
-
-use threshold::ThresholdEscalator;
-use vote::{SignedVote, DecayType, ProposalType};
-use weight_engine::WeightEngine;
-use trust::TrustEngine;
-use history::{VoteRecord, HistoryAnalyzer};
-use simulation::run_simulation;
-use blockchain::Blockchain;
+use time_decay_consensus::threshold::{DecisionRule, TallyMetric, ThresholdEscalator};
+use time_decay_consensus::vote::{SignedVote, DecayType, ProposalType};
+use time_decay_consensus::weight_engine::WeightEngine;
+use time_decay_consensus::trust::TrustEngine;
+use time_decay_consensus::history::{VoteRecord, HistoryAnalyzer};
+use time_decay_consensus::hooks::{CloseCallExtension, ExtensionPolicy};
+use time_decay_consensus::simulation::{
+    run_simulation_with, simulate_extensions, simulate_trust_attack, ChaosSimConfig, ExtensionSimConfig, TrustAccrual,
+};
+use time_decay_consensus::progress;
+use time_decay_consensus::block_producer::{BlockProducer, ProductionPolicy};
+use time_decay_consensus::blockchain::{Blockchain, FinalizationEntry, Transaction, VoteEntry};
+use time_decay_consensus::messages::{self, Catalog, MessageKey};
+use time_decay_consensus::ids::{ProposalId, VoterId};
+use time_decay_consensus::narrative::Narrative;
+use time_decay_consensus::opportunity::{AttackModel, OpportunityReport};
+use time_decay_consensus::report::{FinalizationReport, ReportInput};
+use time_decay_consensus::registry::{RegistryError, Revocation, ValidatorRegistry};
+use time_decay_consensus::reputation::ReputationExport;
+use time_decay_consensus::revocation::{RevocationError, RevocationList};
+use time_decay_consensus::snapshot::{self, SnapshotDiff, TallySnapshot};
+use time_decay_consensus::trust_audit::{ProposalAudit, TrustAudit};
+use time_decay_consensus::units::Seconds;
+use time_decay_consensus::{api, conformance, discovery, explorer, import, metrics, replica, schema, state_hash, telemetry, tune, vectors};
 use chrono::Utc;
 
-#[allow(unused_imports)]
-use decay::DecayModel;
-#[allow(unused_imports)]
-use verify::VerificationError;
-#[allow(unused_imports)]
-use blockchain::Block;
 
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = install_catalog(&mut args) {
+        eprintln!("Failed to load message catalog: {}", e);
+        std::process::exit(2);
+    }
 
+    if let Some(command) = args.first() {
+        match command.as_str() {
+            "vectors" => write_vectors(args.get(1)),
+            "conform" => run_conformance(args.get(1)),
+            "chain" => run_chain_command(&args[1..]),
+            "extensions" => run_extension_simulation(args.get(1)),
+            "chaos" => run_chaos_simulation(args.get(1)),
+            "metrics" => run_metrics_command(&args[1..]),
+            "replica" => run_replica(&args[1..]),
+            "schemas" => write_schemas(args.get(1)),
+            "tune" => run_tune(&args[1..]),
+            "import" => run_import(&args[1..]),
+            "revocations" => run_revocations(&args[1..]),
+            other => println!("{}", messages::text(MessageKey::UnknownCommand, &[("command", other.to_string())])),
+        }
+        return;
+    }
+
+    if let Err(e) = progress::install_interrupt_handler() {
+        eprintln!("Failed to install Ctrl-C handler: {}", e);
+    }
+    let cancel = progress::interrupt_token();
 
-fn main() {
     // Run simulation directly
-    run_simulation();
+    run_simulation_with(&cancel);
 
     // Blockchain demonstration
-    println!("
---- Blockchain Demonstration ---");
+    println!("{}", messages::text(MessageKey::BlockchainHeader, &[]));
     let mut blockchain = Blockchain::new();
-    blockchain.add_block("Transaction 1 Data".to_string());
-    blockchain.add_block("Transaction 2 Data".to_string());
-    blockchain.add_block("Transaction 3 Data".to_string());
+    for data in ["Transaction 1 Data", "Transaction 2 Data", "Transaction 3 Data"] {
+        if blockchain.try_add_block(data.to_string(), &cancel).is_err() {
+            println!("{}", messages::text(MessageKey::Interrupted, &[]));
+            return;
+        }
+    }
 
-    println!("Blockchain is valid: {}", blockchain.is_valid());
+    println!("{}", messages::text(MessageKey::BlockchainValid, &[("valid", blockchain.is_valid().to_string())]));
 
     for block in &blockchain.blocks {
-        println!("Block {}: {}", block.id, block.hash);
+        println!(
+            "{}",
+            messages::text(MessageKey::BlockLine, &[("id", block.id.to_string()), ("hash", block.hash.clone())])
+        );
     }
-    println!("--------------------------------");
+    println!("{}", messages::text(MessageKey::Separator, &[]));
 
     #[warn(unused_variables)]
     // Step 1: Generate a keypair (validator)
@@ -52,8 +84,8 @@ fn main() {
     let _verify_key = signing_key.verifying_key();
 
     // Step 2: Hardcoded input
-    let voter_id = "hardcoded_voter".to_string();
-    let proposal_id = "hardcoded_proposal".to_string();
+    let voter_id: VoterId = "hardcoded_voter".parse().expect("valid id");
+    let proposal_id: ProposalId = "hardcoded_proposal".parse().expect("valid id");
     let original_weight: f64 = 0.75;
     let decay_model = DecayType::Linear;
     let proposal_type = ProposalType::Normal;
@@ -65,15 +97,15 @@ fn main() {
         proposal_id,
         original_weight,
         now,
-        decay_model.clone(),
+        decay_model,
         &signing_key,
     );
 
     // Step 4: Verify vote
     match vote.verify(300) {
-        Ok(_) => println!("✅ Signature verification successful."),
+        Ok(_) => println!("{}", messages::text(MessageKey::SignatureVerified, &[])),
         Err(e) => {
-            println!("❌ Verification failed: {:?}", e);
+            println!("{}", messages::text(MessageKey::VerificationFailed, &[("error", format!("{:?}", e))]));
             return;
         }
     }
@@ -82,25 +114,28 @@ fn main() {
     let mut weight_engine = WeightEngine::new();
     let trust_engine = TrustEngine::new();
     let weight = weight_engine.calculate_weight(&vote, now, Some(&trust_engine));
-    println!("🧮 Final vote weight after decay & trust bonus: {:.4}", weight);
+    println!("{}", messages::text(MessageKey::FinalWeight, &[("weight", format!("{:.4}", weight))]));
 
     // Step 6: Threshold logic
     let mut threshold_engine = ThresholdEscalator::for_proposal_type(proposal_type.clone());
     threshold_engine.total_votes = 3; // Simulated count
     let current_threshold = threshold_engine.threshold_with_profile(now, vote.timestamp);
-    println!("🔢 Required threshold at this time: {:.2}%", current_threshold * 100.0);
+    println!(
+        "{}",
+        messages::text(MessageKey::RequiredThreshold, &[("threshold", format!("{:.2}", current_threshold * 100.0))])
+    );
 
     let passed = threshold_engine.is_threshold_met(weight, current_threshold);
     if passed {
-        println!("✅ Vote passes threshold and minimum vote count");
+        println!("{}", messages::text(MessageKey::VotePassed, &[]));
     } else {
-        println!("❌ Vote rejected: weight or participation too low");
+        println!("{}", messages::text(MessageKey::VoteRejected, &[]));
     }
 
     // Step 7: Historical record
     let mut history = HistoryAnalyzer::default();
     let record = VoteRecord {
-        vote_id: vote.voter_id.clone(),
+        vote_id: vote.voter_id.to_string(),
         weight,
         threshold: current_threshold,
         passed,
@@ -108,34 +143,860 @@ fn main() {
     };
     history.record_vote(record);
 
+    // Step 8: Anchor the vote and its outcome on chain; the finalization seals the block
+    let mut producer = BlockProducer::new(ProductionPolicy::default(), now);
+    producer.submit(Transaction::Vote(VoteEntry::from(&vote)), &mut blockchain, now);
+    producer.submit(
+        Transaction::Finalization(FinalizationEntry {
+            proposal_id: vote.proposal_id.to_string(),
+            passed,
+            weight,
+            threshold: current_threshold,
+            finalized_at: now,
+        }),
+        &mut blockchain,
+        now,
+    );
+    if let Err(e) = blockchain.save(chain_file()) {
+        println!("{}", messages::text(MessageKey::ChainSaveFailed, &[("error", e.to_string())]));
+    }
+
     // Logs
-    println!("
-📊 Historical Vote Log:");
+    println!("{}", messages::text(MessageKey::HistoryHeader, &[]));
     history.print_history();
 
-    println!("
-📜 Weight History Log:");
+    println!("{}", messages::text(MessageKey::WeightHistoryHeader, &[]));
     for record in weight_engine.get_history() {
         println!(
-            "- {} -> {:.4} at {:?}",
-            record.vote_id, record.weight, record.timestamp
+            "{}",
+            messages::text(
+                MessageKey::WeightHistoryLine,
+                &[
+                    ("vote_id", record.vote_id.to_string()),
+                    ("weight", format!("{:.4}", record.weight)),
+                    ("timestamp", format!("{:?}", record.timestamp)),
+                ],
+            )
         );
     }
 
 }
 
+/// Pick the output catalog from `--plain`, `--messages <name|file>` or `TDC_MESSAGES`,
+/// removing the flags from `args`
+fn install_catalog(args: &mut Vec<String>) -> Result<(), String> {
+    let mut selected = std::env::var("TDC_MESSAGES").ok();
+
+    if let Some(pos) = args.iter().position(|a| a == "--plain") {
+        args.remove(pos);
+        selected = Some("plain".to_string());
+    }
+    if let Some(pos) = args.iter().position(|a| a == "--messages") {
+        args.remove(pos);
+        if pos >= args.len() {
+            return Err("--messages needs a catalog name or file".to_string());
+        }
+        selected = Some(args.remove(pos));
+    }
+
+    if let Some(name) = selected {
+        messages::install(Catalog::select(&name)?);
+    }
+    Ok(())
+}
+
+/// Chain file used by the demo and the `chain` commands
+fn chain_file() -> String {
+    std::env::var("TDC_CHAIN_FILE").unwrap_or_else(|_| "chain.json".to_string())
+}
+
+/// `chain show | block <id> | find-proposal <id> | report <id> [--json] [--time-weighted] [--narrative] | diff <id> <from> <to> [--json] | trust-audit <scores.json> [--at <time>] [--json] | trace <id> [--otlp <endpoint>] | proposals [filters] | serve [addr]`
+fn run_chain_command(args: &[String]) {
+    let path = chain_file();
+    let chain = match Blockchain::load(&path) {
+        Ok(chain) => chain,
+        Err(e) => {
+            println!("{}", messages::text(MessageKey::ChainLoadFailed, &[("path", path.clone()), ("error", e.to_string())]));
+            std::process::exit(1);
+        }
+    };
+
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("show"), _) => explorer::print_chain(&explorer::chain_summary(&chain)),
+        (Some("block"), Some(id)) => match id.parse().ok().and_then(|id| explorer::block_detail(&chain, id)) {
+            Some(detail) => explorer::print_block(&detail),
+            None => println!("{}", messages::text(MessageKey::BlockNotFound, &[("id", id.clone())])),
+        },
+        (Some("find-proposal"), Some(id)) => explorer::print_proposal(&explorer::find_proposal(&chain, id)),
+        (Some("report"), Some(id)) => {
+            let metric = if args.iter().any(|a| a == "--time-weighted") {
+                TallyMetric::TimeWeighted
+            } else {
+                TallyMetric::Instantaneous
+            };
+            let narrative = args.iter().any(|a| a == "--narrative");
+            print_report(&chain, id, args.iter().any(|a| a == "--json"), narrative, metric)
+        }
+        (Some("diff"), Some(id)) => match (args.get(2), args.get(3)) {
+            (Some(from), Some(to)) => print_snapshot_diff(&chain, id, from, to, args.iter().any(|a| a == "--json")),
+            _ => println!("{}", messages::text(MessageKey::Usage, &[("usage", "chain diff <id> <from> <to> [--json] (RFC 3339 times)".to_string())])),
+        },
+        (Some("series"), Some(id)) => {
+            let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+            let every = match flag("--every").map(|s| s.parse::<i64>()) {
+                Some(Ok(secs)) if secs > 0 => Seconds::new(secs),
+                Some(_) => return println!("{}", messages::text(MessageKey::Usage, &[("usage", "chain series <id> [--every <secs>] [--json]".to_string())])),
+                None => snapshot::DEFAULT_SNAPSHOT_CADENCE,
+            };
+            print_series(&chain, id, every, args.iter().any(|a| a == "--json"))
+        }
+        (Some("trust-audit"), Some(file)) => {
+            let at = args.iter().position(|a| a == "--at").and_then(|i| args.get(i + 1));
+            print_trust_audit(&chain, file, at.map(String::as_str), args.iter().any(|a| a == "--json"))
+        }
+        (Some("opportunity"), Some(id)) => print_opportunities(&chain, id, args.iter().any(|a| a == "--json")),
+        (Some("trace"), Some(id)) => match telemetry::ProposalTimeline::from_chain(&chain, id) {
+            Some(timeline) => {
+                let endpoint = args.iter().position(|a| a == "--otlp").and_then(|i| args.get(i + 1));
+                match endpoint {
+                    Some(endpoint) => match telemetry::OtlpExporter::new(endpoint).and_then(|e| e.export(&timeline)) {
+                        Ok(()) => println!(
+                            "{}",
+                            messages::text(
+                                MessageKey::TraceExported,
+                                &[("trace_id", timeline.trace_id().to_string()), ("endpoint", endpoint.clone())],
+                            )
+                        ),
+                        Err(e) => println!("{}", messages::text(MessageKey::Failed, &[("error", e.to_string())])),
+                    },
+                    None => println!("{}", timeline.to_otlp_json()),
+                }
+            }
+            None => println!("{}", messages::text(MessageKey::NoVotesForProposal, &[("proposal_id", id.clone())])),
+        },
+        (Some("proposals"), _) => {
+            let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+            let mut query = std::collections::HashMap::new();
+            for (param, name) in [("type", "--type"), ("namespace", "--namespace"), ("closing_within", "--closing-within")] {
+                if let Some(value) = flag(name) {
+                    query.insert(param.to_string(), value);
+                }
+            }
+            match discovery::ProposalFilter::from_query(&query) {
+                Ok(filter) => {
+                    let now = Utc::now();
+                    let directory = discovery::ProposalDirectory::from_chain(&chain, discovery::DEFAULT_DURATION);
+                    let open = directory.list(&filter, now);
+                    println!("{}", messages::text(MessageKey::OpenProposals, &[("count", open.len().to_string())]));
+                    for listing in open {
+                        println!(
+                            "{}",
+                            messages::text(
+                                MessageKey::ProposalListing,
+                                &[
+                                    ("proposal_id", listing.proposal_id.to_string()),
+                                    ("proposal_type", listing.proposal_type.to_string()),
+                                    ("closes_at", listing.closes_at.to_string()),
+                                    ("remaining", (listing.closes_at - now).num_seconds().to_string()),
+                                ],
+                            )
+                        );
+                    }
+                }
+                Err(e) => println!("{}", messages::text(MessageKey::Failed, &[("error", e.to_string())])),
+            }
+        }
+        (Some("serve"), addr) => {
+            let addr = addr.map(String::as_str).unwrap_or("127.0.0.1:8080");
+            match std::net::TcpListener::bind(addr) {
+                Ok(listener) => {
+                    println!("{}", messages::text(MessageKey::ApiServing, &[("addr", addr.to_string())]));
+                    let chain = std::sync::Arc::new(std::sync::RwLock::new(chain));
+                    let shared = std::sync::Arc::clone(&chain);
+                    let (state_hash, _) = state_hash::spawn_recompute(
+                        std::time::Duration::from_secs(30),
+                        progress::CancellationToken::new(),
+                        move || {
+                            let chain = shared.read().expect("chain lock poisoned");
+                            state_hash::StateHash::compute(
+                                &chain,
+                                &ValidatorRegistry::new(),
+                                &TrustEngine::new(),
+                                Utc::now(),
+                                state_hash::DEFAULT_QUANTUM,
+                            )
+                        },
+                    );
+                    api::Api::new(chain).with_state_hash(state_hash).serve(listener);
+                }
+                Err(e) => println!("{}", messages::text(MessageKey::BindFailed, &[("addr", addr.to_string()), ("error", e.to_string())])),
+            }
+        }
+        _ => println!("{}", messages::text(MessageKey::Usage, &[("usage", "chain show | chain block <id> | chain find-proposal <id> | chain report <id> [--json] [--time-weighted] [--narrative] | chain diff <id> <from> <to> [--json] | chain series <id> [--every <secs>] [--json] | chain trust-audit <scores.json> [--at <time>] [--json] | chain opportunity <id> [--json] | chain proposals [--type t] [--namespace n] [--closing-within secs] | chain serve [addr]".to_string())])),
+    }
+}
+
+/// Compare extension policies over seeded deadline-rush trials: `extensions [seed]`
+fn run_extension_simulation(seed: Option<&String>) {
+    let config = ExtensionSimConfig {
+        seed: seed.and_then(|s| s.parse().ok()).unwrap_or(0),
+        ..ExtensionSimConfig::default()
+    };
+    let policies = [
+        CloseCallExtension { extra_secs: 30 },
+        CloseCallExtension { extra_secs: 60 },
+        CloseCallExtension { extra_secs: 300 },
+    ];
+    let names: Vec<String> = policies.iter().map(|p| format!("close_call_{}s", p.extra_secs)).collect();
+    let named: Vec<(&str, &dyn ExtensionPolicy)> = names
+        .iter()
+        .zip(&policies)
+        .map(|(name, policy)| (name.as_str(), policy as &dyn ExtensionPolicy))
+        .collect();
+    let report = simulate_extensions(&config, &named);
+
+    println!(
+        "{}",
+        messages::text(
+            MessageKey::ExtensionHeader,
+            &[("trials", config.trials.to_string()), ("arrival", report.arrival.to_string()), ("seed", report.seed.to_string())],
+        )
+    );
+    for stats in &report.policies {
+        println!(
+            "{}",
+            messages::text(
+                MessageKey::ExtensionLine,
+                &[
+                    ("policy", format!("{:<18}", stats.policy)),
+                    ("triggered", format!("{:>5.1}", stats.trigger_rate() * 100.0)),
+                    ("changes", format!("{:>4}", stats.outcome_changes)),
+                    ("pass", format!("{:>5.1}", stats.pass_rate * 100.0)),
+                    ("baseline", format!("{:>5.1}", stats.baseline_pass_rate * 100.0)),
+                    ("latency", format!("{:>6.1}", stats.mean_added_latency_secs)),
+                    ("extended_latency", format!("{:>6.1}", stats.mean_added_latency_when_extended_secs)),
+                ],
+            )
+        );
+    }
+}
+
+/// Attackers farm trust and then defect on a critical proposal, under each trust preset
+fn run_chaos_simulation(seed: Option<&String>) {
+    let config = ChaosSimConfig {
+        seed: seed.and_then(|s| s.parse().ok()).unwrap_or(0),
+        ..ChaosSimConfig::default()
+    };
+    let report = simulate_trust_attack(&config, &TrustAccrual::presets());
+
+    println!(
+        "{}",
+        messages::text(
+            MessageKey::ChaosHeader,
+            &[
+                ("attackers", config.attackers.to_string()),
+                ("honest", config.honest.to_string()),
+                ("days", config.farming_days.to_string()),
+                ("trials", config.trials.to_string()),
+                ("seed", report.seed.to_string()),
+            ],
+        )
+    );
+    for outcome in &report.outcomes {
+        println!(
+            "{}",
+            messages::text(
+                MessageKey::ChaosLine,
+                &[
+                    ("accrual", format!("{:<14}", outcome.accrual)),
+                    ("attacker_bonus", format!("{:>5.2}", outcome.attacker_mean_bonus)),
+                    ("honest_bonus", format!("{:.2}", outcome.honest_mean_bonus)),
+                    ("weight_share", format!("{:>5.1}", outcome.attacker_weight_share * 100.0)),
+                    ("vote_share", format!("{:>5.1}", outcome.attacker_vote_share * 100.0)),
+                    ("amplification", format!("{:.2}", outcome.amplification())),
+                    ("blocked", format!("{:>5.1}", outcome.block_rate * 100.0)),
+                ],
+            )
+        );
+    }
+}
+
+/// Rebuild a proposal's finalization report from its on-chain votes. The chain does not
+/// record proposal types, so the report assumes a normal proposal. `narrative` prints
+/// the plain-language account instead of the full report.
+fn print_report(chain: &Blockchain, proposal_id: &str, json: bool, narrative: bool, metric: TallyMetric) {
+    let activity = explorer::find_proposal(chain, proposal_id);
+    let votes: Vec<SignedVote> = activity.votes.iter().filter_map(|v| v.vote.to_signed_vote()).collect();
+    let finalized_at = activity
+        .finalizations
+        .iter()
+        .map(|f| f.finalization.finalized_at)
+        .max()
+        .unwrap_or_else(Utc::now);
+    let opened_at = votes.iter().map(|v| v.timestamp).min().unwrap_or(finalized_at);
+    // Ties are broken by the block holding the last vote, which every node agrees on
+    let seed = activity
+        .votes
+        .iter()
+        .map(|v| v.block_id)
+        .max()
+        .and_then(|id| chain.blocks.get(id as usize))
+        .map(|b| b.hash.as_str());
+
+    let report = FinalizationReport::build(ReportInput {
+        proposal_id,
+        proposal_type: ProposalType::Normal,
+        opened_at,
+        finalized_at,
+        votes: &votes,
+        trust: &TrustEngine::new(),
+        max_age_secs: i64::MAX,
+        admissions: None,
+        eligible_weight: None,
+        decision: DecisionRule { metric, ..DecisionRule::default() },
+        tie_break_seed: seed,
+    });
+    match (narrative, json) {
+        (true, true) => println!("{}", Narrative::from_report(&report).to_json()),
+        (true, false) => print!("{}", Narrative::from_report(&report).render_text()),
+        (false, true) => println!("{}", report.to_json()),
+        (false, false) => print!("{}", report.render_text()),
+    }
+}
+
+/// Snapshot a proposal's on-chain tally at two RFC 3339 times and print what changed
+/// between them. Like `print_report`, the proposal is assumed to be a normal one.
+fn print_snapshot_diff(chain: &Blockchain, proposal_id: &str, from: &str, to: &str, json: bool) {
+    let parse = |at: &str| chrono::DateTime::parse_from_rfc3339(at).map(|t| t.with_timezone(&Utc));
+    let (from, to, id) = match (parse(from), parse(to), proposal_id.parse::<ProposalId>()) {
+        (Ok(from), Ok(to), Ok(id)) => (from, to, id),
+        (Err(e), _, _) | (_, Err(e), _) => return println!("{}", messages::text(MessageKey::InvalidTime, &[("error", e.to_string())])),
+        (_, _, Err(e)) => return println!("{}", messages::text(MessageKey::Failed, &[("error", e.to_string())])),
+    };
+    let activity = explorer::find_proposal(chain, proposal_id);
+    let votes: Vec<SignedVote> = activity.votes.iter().filter_map(|v| v.vote.to_signed_vote()).collect();
+    let opened_at = votes.iter().map(|v| v.timestamp).min().unwrap_or(from);
+    let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+    let trust = TrustEngine::new();
+    let snapshot = |at| TallySnapshot::capture(&id, &votes, &escalator, opened_at, at, &trust);
+    let diff = SnapshotDiff::between(&snapshot(from), &snapshot(to));
+    if json {
+        println!("{}", diff.to_json());
+    } else {
+        print!("{}", diff.render_text());
+    }
+}
+
+/// Print a proposal's snapshot series from the store at `TDC_SERIES_FILE` (default
+/// `series.jsonl`). A proposal with nothing stored is backfilled once from its on-chain
+/// votes, sampled every `every` from the first vote to the last, and the points stored.
+/// Like `print_report`, the proposal is assumed to be a normal one.
+fn print_series(chain: &Blockchain, proposal_id: &str, every: Seconds, json: bool) {
+    let id = match proposal_id.parse::<ProposalId>() {
+        Ok(id) => id,
+        Err(e) => return println!("{}", messages::text(MessageKey::Failed, &[("error", e.to_string())])),
+    };
+    let path = std::env::var("TDC_SERIES_FILE").unwrap_or_else(|_| "series.jsonl".to_string());
+    let store = snapshot::SeriesStore::new(&path);
+    let mut points = match store.load_proposal(&id) {
+        Ok(points) => points,
+        Err(snapshot::SeriesError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return println!("{}", messages::text(MessageKey::ReadFailed, &[("path", path), ("error", e.to_string())])),
+    };
+    if points.is_empty() {
+        let activity = explorer::find_proposal(chain, proposal_id);
+        let votes: Vec<SignedVote> = activity.votes.iter().filter_map(|v| v.vote.to_signed_vote()).collect();
+        let (Some(opened_at), Some(until)) = (votes.iter().map(|v| v.timestamp).min(), votes.iter().map(|v| v.timestamp).max()) else {
+            return println!("{}", messages::text(MessageKey::NoVotesForProposal, &[("proposal_id", proposal_id.to_string())]));
+        };
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+        points = snapshot::sample_series(&id, &votes, &escalator, opened_at, until, every, &TrustEngine::new());
+        match store.append(&points) {
+            Ok(written) => println!(
+                "{}",
+                messages::text(
+                    MessageKey::SeriesBackfilled,
+                    &[("count", written.to_string()), ("proposal_id", proposal_id.to_string()), ("path", path.clone())],
+                )
+            ),
+            Err(e) => println!("{}", messages::text(MessageKey::WriteFailed, &[("path", path.clone()), ("error", e.to_string())])),
+        }
+    }
+    if json {
+        return println!("{}", serde_json::to_string_pretty(&points).expect("series points serialize"));
+    }
+    println!(
+        "{}",
+        messages::text(MessageKey::SeriesHeader, &[("proposal_id", proposal_id.to_string()), ("count", points.len().to_string())])
+    );
+    for point in &points {
+        println!(
+            "{}",
+            messages::text(
+                MessageKey::SeriesPoint,
+                &[
+                    ("elapsed", point.elapsed_secs.to_string()),
+                    ("approve", format!("{:.4}", point.tally.approve_weight)),
+                    ("reject", format!("{:.4}", point.tally.reject_weight)),
+                    ("threshold", format!("{:.4}", point.threshold)),
+                    ("status", if point.passing { "passing" } else { "failing" }.to_string()),
+                ],
+            )
+        );
+    }
+}
+
+/// Tally every open proposal on chain under the built-in trust scores and under the
+/// reputation export in `file`, and print which outcomes the new scores would flip.
+/// Like `print_report`, proposals are assumed to be normal ones.
+fn print_trust_audit(chain: &Blockchain, file: &str, at: Option<&str>, json: bool) {
+    let proposed = match std::fs::read_to_string(file).map_err(|e| e.to_string()).and_then(|json| {
+        let export = ReputationExport::from_json(&json).map_err(|e| e.to_string())?;
+        export.validate().map_err(|e| e.to_string())?;
+        Ok(export)
+    }) {
+        Ok(export) => export,
+        Err(e) => return println!("{}", messages::text(MessageKey::LoadFailed, &[("path", file.to_string()), ("error", e)])),
+    };
+    let at = match at.map(chrono::DateTime::parse_from_rfc3339) {
+        None => Utc::now(),
+        Some(Ok(at)) => at.with_timezone(&Utc),
+        Some(Err(e)) => return println!("{}", messages::text(MessageKey::InvalidTime, &[("error", e.to_string())])),
+    };
+    let current = TrustEngine::new();
+    let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+    let audits = explorer::open_proposals(chain)
+        .into_iter()
+        .filter_map(|(id, votes)| {
+            let id = id.parse::<ProposalId>().ok()?;
+            let opened_at = votes.iter().map(|v| v.timestamp).min()?;
+            Some(ProposalAudit::compare(&id, &votes, &escalator, opened_at, at, &current, &proposed))
+        })
+        .collect();
+    let audit = TrustAudit::new(at, audits);
+    if json {
+        println!("{}", audit.to_json());
+    } else {
+        print!("{}", audit.render_text());
+    }
+}
+
+/// Report the coalition an attacker needed at each moment of a proposal's window to
+/// flip its outcome. Like `print_report`, the proposal is assumed to be a normal one.
+fn print_opportunities(chain: &Blockchain, proposal_id: &str, json: bool) {
+    let id = match proposal_id.parse::<ProposalId>() {
+        Ok(id) => id,
+        Err(e) => return println!("{}", messages::text(MessageKey::Failed, &[("error", e.to_string())])),
+    };
+    let activity = explorer::find_proposal(chain, proposal_id);
+    let votes: Vec<SignedVote> = activity.votes.iter().filter_map(|v| v.vote.to_signed_vote()).collect();
+    let finalized_at = activity
+        .finalizations
+        .iter()
+        .map(|f| f.finalization.finalized_at)
+        .max()
+        .unwrap_or_else(Utc::now);
+    let opened_at = votes.iter().map(|v| v.timestamp).min().unwrap_or(finalized_at);
+    let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+    let report = OpportunityReport::analyze(&id, &votes, &escalator, opened_at, finalized_at, &TrustEngine::new(), &AttackModel::default());
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report.render_text());
+    }
+}
+
+/// Emit canonical test vectors to stdout or to the given file
+fn write_vectors(path: Option<&String>) {
+    let json = vectors::to_json(&vectors::generate());
+    match path {
+        Some(path) => match std::fs::write(path, json) {
+            Ok(_) => println!("{}", messages::text(MessageKey::VectorsWritten, &[("path", path.clone())])),
+            Err(e) => println!("{}", messages::text(MessageKey::VectorsWriteFailed, &[("error", e.to_string())])),
+        },
+        None => println!("{}", json),
+    }
+}
+
+/// Publish the JSON Schemas every external input is validated against
+fn write_schemas(dir: Option<&String>) {
+    let dir = dir.map(String::as_str).unwrap_or(schema::SCHEMA_DIR);
+    match schema::write_all(dir) {
+        Ok(written) => {
+            for path in written {
+                println!("{}", messages::text(MessageKey::SchemaWritten, &[("path", path)]));
+            }
+        }
+        Err(e) => {
+            println!("{}", messages::text(MessageKey::SchemasWriteFailed, &[("error", e.to_string())]));
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Check this build against a JSON vector fixture
+fn run_conformance(path: Option<&String>) {
+    let path = path.map(String::as_str).unwrap_or("conformance/vectors.json");
+    match conformance::run_file(path) {
+        Ok(report) => {
+            report.print_summary();
+            if !report.passed() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            println!(
+                "{}",
+                messages::text(MessageKey::ConformanceLoadFailed, &[("path", path.to_string()), ("error", e)])
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `metrics report [file]`: capacity summary over persisted metrics snapshots
+fn run_metrics_command(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("report") => {
+            let path = args
+                .get(1)
+                .cloned()
+                .or_else(|| std::env::var("TDC_METRICS_FILE").ok())
+                .unwrap_or_else(|| "metrics.jsonl".to_string());
+            match metrics::MetricsStore::new(&path).load() {
+                Ok(snapshots) => print!("{}", metrics::CapacityReport::from_snapshots(&snapshots).render_text()),
+                Err(e) => {
+                    println!("{}", messages::text(MessageKey::MetricsReadFailed, &[("path", path.clone()), ("error", e.to_string())]));
+                    std::process::exit(1);
+                }
+            }
+        }
+        _ => println!("{}", messages::text(MessageKey::Usage, &[("usage", "metrics report [file]".to_string())])),
+    }
+}
+/// Follow peers read-only and serve the API: `replica <peer>... [--listen addr] [--interval secs]`
+fn run_replica(args: &[String]) {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+    let listen = flag("--listen").unwrap_or_else(|| "127.0.0.1:8081".to_string());
+    let interval = flag("--interval").and_then(|s| s.parse().ok()).unwrap_or(10);
+    let mut peers: Vec<Box<dyn replica::PeerSource>> = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        if arg.starts_with("--") {
+            rest.next();
+        } else {
+            peers.push(Box::new(replica::HttpPeer::new(arg)));
+        }
+    }
+    if peers.is_empty() {
+        println!("{}", messages::text(MessageKey::Usage, &[("usage", "replica <peer>... [--listen addr] [--interval secs]".to_string())]));
+        return;
+    }
+
+    let replica = match replica::Replica::bootstrap(peers) {
+        Ok(replica) => std::sync::Arc::new(replica),
+        Err(e) => {
+            println!("{}", messages::text(MessageKey::Failed, &[("error", e.to_string())]));
+            std::process::exit(1);
+        }
+    };
+    let listener = match std::net::TcpListener::bind(&listen) {
+        Ok(listener) => listener,
+        Err(e) => {
+            println!("{}", messages::text(MessageKey::BindFailed, &[("addr", listen.clone()), ("error", e.to_string())]));
+            std::process::exit(1);
+        }
+    };
+    let api = api::Api::new(replica.chain()).with_replica(replica.status());
+    let on_sync = |result: Result<usize, replica::ReplicaError>| match result {
+        Ok(0) => {}
+        Ok(count) => println!("{}", messages::text(MessageKey::ReplicaSynced, &[("count", count.to_string())])),
+        Err(e) => println!("{}", messages::text(MessageKey::ReplicaSyncFailed, &[("error", e.to_string())])),
+    };
+    std::sync::Arc::clone(&replica).spawn_sync(std::time::Duration::from_secs(interval), progress::CancellationToken::new(), on_sync);
+    println!(
+        "{}",
+        messages::text(MessageKey::ReplicaServing, &[("addr", listen.clone()), ("interval", interval.to_string())])
+    );
+    api.serve(listener);
+}
+
+/// Recommend a plugin config for the given goals and write it where `--out` says
+fn run_tune(args: &[String]) {
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+    let defaults = tune::TuneGoals::default();
+    let goals = tune::TuneGoals {
+        voters: flag("--voters").and_then(|s| s.parse().ok()).unwrap_or(defaults.voters),
+        turnout: flag("--turnout").and_then(|s| s.parse().ok()).unwrap_or(defaults.turnout),
+        late_vote_tolerance: flag("--late-tolerance").and_then(|s| s.parse().ok()).unwrap_or(defaults.late_vote_tolerance),
+        target_latency_secs: flag("--latency").and_then(|s| s.parse().ok()).unwrap_or(defaults.target_latency_secs),
+        attack_budget: flag("--attack-budget").and_then(|s| s.parse().ok()).unwrap_or(defaults.attack_budget),
+        trials: flag("--trials").and_then(|s| s.parse().ok()).unwrap_or(defaults.trials),
+        seed: flag("--seed").and_then(|s| s.parse().ok()).unwrap_or(defaults.seed),
+    };
+    let report = tune::tune(&goals);
+    let config = serde_json::to_string_pretty(&report.config).expect("plugin configs serialize");
+
+    println!(
+        "{}",
+        messages::text(
+            MessageKey::TuneGoals,
+            &[
+                ("voters", goals.voters.to_string()),
+                ("turnout", format!("{:.0}", goals.turnout * 100.0)),
+                ("late_factor", format!("{:.1}", 1.0 / (1.0 - goals.late_vote_tolerance.clamp(0.0, 0.9)))),
+                ("latency", goals.target_latency_secs.to_string()),
+                ("attack_budget", format!("{:.0}", goals.attack_budget * 100.0)),
+            ],
+        )
+    );
+    println!(
+        "{}",
+        messages::text(
+            MessageKey::TuneResult,
+            &[
+                ("candidates", report.candidates.to_string()),
+                ("trials", goals.trials.to_string()),
+                ("pass", format!("{:>5.1}", report.pass_rate * 100.0)),
+                ("attack", format!("{:>5.1}", report.attack_rate * 100.0)),
+                ("decision", format!("{:.0}", report.mean_decision_secs)),
+            ],
+        )
+    );
+    match flag("--out") {
+        Some(path) => match std::fs::write(&path, config + "\n") {
+            Ok(()) => println!("{}", messages::text(MessageKey::ConfigWritten, &[("path", path)])),
+            Err(e) => {
+                println!("{}", messages::text(MessageKey::WriteFailed, &[("path", path), ("error", e.to_string())]));
+                std::process::exit(1);
+            }
+        },
+        None => println!("{}", config),
+    }
+}
+
+/// `revocations list | add <public-key> --issuer-key <file> [--at <time>] [reason] | check <vote.json>`
+/// on the list at `TDC_REVOCATIONS_FILE` (default `revocations.json`). Entries must be
+/// signed by one of the authority keys in `TDC_REVOCATION_AUTHORITIES` (comma-separated
+/// hex); the list is append-only.
+fn run_revocations(args: &[String]) {
+    let parse_key = |hex: &str| {
+        hex::decode(hex.trim())
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .and_then(|bytes| ed25519_dalek::VerifyingKey::from_bytes(&bytes).ok())
+            .ok_or_else(|| format!("{} is not a hex ed25519 public key", hex))
+    };
+    let authorities = match std::env::var("TDC_REVOCATION_AUTHORITIES")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|k| !k.trim().is_empty())
+        .map(parse_key)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(authorities) => authorities,
+        Err(e) => {
+            println!("{}", messages::text(MessageKey::RevocationAuthoritiesInvalid, &[("error", e)]));
+            std::process::exit(1);
+        }
+    };
+    let path = std::env::var("TDC_REVOCATIONS_FILE").unwrap_or_else(|_| "revocations.json".to_string());
+    let mut list = match RevocationList::load(&path, authorities.iter().copied()) {
+        Ok(list) => list,
+        Err(RevocationError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => RevocationList::new(authorities),
+        Err(e) => {
+            println!("{}", messages::text(MessageKey::RevocationsLoadFailed, &[("path", path.clone()), ("error", e.to_string())]));
+            std::process::exit(1);
+        }
+    };
+
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("list") | None, _) => {
+            println!("{}", messages::text(MessageKey::RevocationsHeader, &[("count", list.len().to_string()), ("path", path.clone())]));
+            for r in list.log() {
+                println!(
+                    "{}",
+                    messages::text(
+                        MessageKey::RevocationLine,
+                        &[
+                            ("public_key", hex::encode(r.public_key.as_bytes())),
+                            ("revoked_at", r.revoked_at.to_rfc3339()),
+                            ("issuer", hex::encode(r.issuer.as_bytes())),
+                            ("reason", r.reason.clone()),
+                        ],
+                    )
+                );
+            }
+        }
+        (Some("add"), Some(hex)) => {
+            let key = match parse_key(hex) {
+                Ok(key) => key,
+                Err(e) => return println!("{}", messages::text(MessageKey::Failed, &[("error", e)])),
+            };
+            let mut rest = args[2..].to_vec();
+            let mut take = |name: &str| match rest.iter().position(|a| a == name) {
+                Some(i) if i + 1 < rest.len() => {
+                    let value = rest.remove(i + 1);
+                    rest.remove(i);
+                    Ok(Some(value))
+                }
+                Some(_) => Err(format!("{} needs a value", name)),
+                None => Ok(None),
+            };
+            let (issuer, at) = match (take("--issuer-key"), take("--at")) {
+                (Ok(Some(issuer)), Ok(at)) => (issuer, at),
+                (Ok(None), _) => return println!("{}", messages::text(MessageKey::IssuerKeyRequired, &[])),
+                (Err(e), _) | (_, Err(e)) => return println!("{}", messages::text(MessageKey::Failed, &[("error", e)])),
+            };
+            let at = match at.map(|at| chrono::DateTime::parse_from_rfc3339(&at)) {
+                Some(Ok(at)) => at.with_timezone(&Utc),
+                Some(Err(e)) => return println!("{}", messages::text(MessageKey::InvalidTime, &[("error", e.to_string())])),
+                None => Utc::now(),
+            };
+            let issuer_key = match std::fs::read_to_string(&issuer).map_err(|e| e.to_string()).and_then(|secret| {
+                hex::decode(secret.trim())
+                    .ok()
+                    .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                    .map(|bytes| ed25519_dalek::SigningKey::from_bytes(&bytes))
+                    .ok_or_else(|| "expected a hex ed25519 secret key".to_string())
+            }) {
+                Ok(key) => key,
+                Err(e) => return println!("{}", messages::text(MessageKey::LoadFailed, &[("path", issuer), ("error", e)])),
+            };
+            match list.revoke(Revocation::new(key, at, rest.join(" "), &issuer_key)) {
+                Ok(()) => match list.save(&path) {
+                    Ok(()) => println!(
+                        "{}",
+                        messages::text(MessageKey::RevocationAdded, &[("public_key", hex.clone()), ("revoked_at", at.to_rfc3339())])
+                    ),
+                    Err(e) => {
+                        println!("{}", messages::text(MessageKey::RevocationsSaveFailed, &[("path", path), ("error", e.to_string())]));
+                        std::process::exit(1);
+                    }
+                },
+                Err(RegistryError::AlreadyRevoked) => {
+                    let revoked_at = list.revoked_at(&key).unwrap_or(at).to_rfc3339();
+                    println!("{}", messages::text(MessageKey::KeyAlreadyRevoked, &[("public_key", hex.clone()), ("revoked_at", revoked_at)]))
+                }
+                Err(e) => println!("{}", messages::text(MessageKey::Failed, &[("error", e.to_string())])),
+            }
+        }
+        (Some("check"), Some(file)) => {
+            let vote = match std::fs::read_to_string(file).map_err(|e| e.to_string()).and_then(|json| {
+                SignedVote::from_json(&json).map_err(|e| e.to_string())
+            }) {
+                Ok(vote) => vote,
+                Err(e) => return println!("{}", messages::text(MessageKey::LoadFailed, &[("path", file.clone()), ("error", e)])),
+            };
+            match list.check(&vote) {
+                Ok(()) => println!(
+                    "{}",
+                    messages::text(
+                        MessageKey::KeyNotRevoked,
+                        &[("voter", vote.voter_id.to_string()), ("timestamp", vote.timestamp.to_rfc3339())],
+                    )
+                ),
+                Err(e) => {
+                    println!("{}", messages::text(MessageKey::RevocationCheckFailed, &[("voter", vote.voter_id.to_string()), ("error", e.to_string())]));
+                    std::process::exit(1);
+                }
+            }
+        }
+        _ => println!(
+            "{}",
+            messages::text(
+                MessageKey::Usage,
+                &[("usage", "revocations list | revocations add <public-key> --issuer-key <file> [--at <time>] [reason] | revocations check <vote.json>".to_string())],
+            )
+        ),
+    }
+}
+
+fn run_import(args: &[String]) {
+    let Some(path) = args.first().filter(|a| !a.starts_with("--")) else {
+        println!("{}", messages::text(MessageKey::Usage, &[("usage", "import <file.csv|file.json> [--mapping file] [--out file]".to_string())]));
+        return;
+    };
+    let flag = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+    let mapping = match flag("--mapping") {
+        Some(mapping_path) => match std::fs::read_to_string(&mapping_path).map_err(|e| e.to_string()).and_then(|json| {
+            schema::parse::<import::ImportMapping>(&json).map_err(|e| e.to_string())
+        }) {
+            Ok(mapping) => mapping,
+            Err(e) => {
+                println!("{}", messages::text(MessageKey::MappingLoadFailed, &[("path", mapping_path), ("error", e)]));
+                std::process::exit(1);
+            }
+        },
+        None => import::ImportMapping::default(),
+    };
+    let imported = match import::HistoricalImport::from_file(std::path::Path::new(path), &mapping) {
+        Ok(imported) => imported,
+        Err(e) => {
+            println!("{}", messages::text(MessageKey::ImportFailed, &[("path", path.clone()), ("error", e.to_string())]));
+            std::process::exit(1);
+        }
+    };
+
+    let mut history = HistoryAnalyzer::default();
+    imported.record_into(&mut history);
+    let track_records = imported.track_records();
+    println!(
+        "{}",
+        messages::text(
+            MessageKey::Imported,
+            &[
+                ("votes", imported.votes.len().to_string()),
+                ("proposals", history.records.len().to_string()),
+                ("voters", track_records.len().to_string()),
+            ],
+        )
+    );
+    println!(
+        "{}",
+        messages::text(
+            MessageKey::ImportCalibration,
+            &[
+                ("margin", format!("{:+.3}", history.average_margin())),
+                ("threshold", format!("{:.2}", history.suggested_base_threshold())),
+            ],
+        )
+    );
+    for (voter, record) in &track_records {
+        println!(
+            "{}",
+            messages::text(
+                MessageKey::ImportTrackRecord,
+                &[
+                    ("voter", format!("{:<16}", voter.as_str())),
+                    ("votes", format!("{:>4}", record.votes)),
+                    ("agreed", format!("{:>4}", record.agreed)),
+                ],
+            )
+        );
+    }
+    if let Some(out) = flag("--out") {
+        match std::fs::write(&out, history.export_json() + "\n") {
+            Ok(()) => println!("{}", messages::text(MessageKey::HistoryWritten, &[("path", out)])),
+            Err(e) => {
+                println!("{}", messages::text(MessageKey::WriteFailed, &[("path", out), ("error", e.to_string())]));
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
     use chrono::{Utc, Duration};
-    use ed25519_dalek::SigningKey;
 
-    use crate::trust::TrustEngine;
-    use crate::vote::{DecayType, SignedVote};
-    use crate::weight_engine::WeightEngine;
-    use crate::threshold::{ThresholdEscalator, ProgressionProfile};
-    use crate::history::{HistoryAnalyzer, VoteRecord};
-    use crate::vote::ProposalType;
+    use time_decay_consensus::trust::TrustEngine;
+    use time_decay_consensus::vote::{DecayType, SignedVote};
+    use time_decay_consensus::weight_engine::WeightEngine;
+    use time_decay_consensus::threshold::ThresholdEscalator;
+    use time_decay_consensus::history::{HistoryAnalyzer, VoteRecord};
+    use time_decay_consensus::vote::ProposalType;
 
     #[test]
     fn test_signed_vote_verification() {
@@ -143,8 +1004,8 @@ mod tests {
         let now = Utc::now();
 
         let vote = SignedVote::new(
-            "voter_123".to_string(),
-            "proposal_abc".to_string(),
+            "voter_123".parse().unwrap(),
+            "proposal_abc".parse().unwrap(),
             1.0,
             now,
             DecayType::Linear,
@@ -155,8 +1016,8 @@ mod tests {
 
         // simulate a future timestamp — should fail
         let bad_vote = SignedVote::new(
-            "voter_123".to_string(),
-            "proposal_abc".to_string(),
+            "voter_123".parse().unwrap(),
+            "proposal_abc".parse().unwrap(),
             1.0,
             now + Duration::seconds(10),
             DecayType::Linear,
@@ -171,8 +1032,8 @@ mod tests {
         let now = Utc::now();
 
         let vote = SignedVote::new(
-            "validator_001".to_string(),
-            "proposal_abc".to_string(),
+            "validator_001".parse().unwrap(),
+            "proposal_abc".parse().unwrap(),
             2.0,
             now - Duration::seconds(60),
             DecayType::Exponential,
@@ -197,7 +1058,7 @@ mod tests {
         let threshold = escalator.threshold_with_profile(now, now);
         let passed = escalator.is_threshold_met(0.6, threshold);
 
-        assert!(threshold >= 0.0 && threshold <= 1.0, "Threshold should be between 0 and 1");
+        assert!((0.0..=1.0).contains(&threshold), "Threshold should be between 0 and 1");
         assert!(
             passed == (0.6 >= threshold),
             "Pass condition should match weight vs. threshold"
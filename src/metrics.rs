@@ -0,0 +1,360 @@
+// src/metrics.rs
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::progress::CancellationToken;
+
+/// Upper bounds of the ingestion latency buckets, in milliseconds; a final bucket takes the rest
+pub const LATENCY_BUCKETS_MS: [f64; 12] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0];
+
+/// Bucketed latency counts; histograms from different windows merge exactly
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// One count per entry of `LATENCY_BUCKETS_MS`, plus overflow
+    pub counts: Vec<u64>,
+    pub max_ms: f64,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency_ms: f64) {
+        if self.counts.is_empty() {
+            self.counts = vec![0; LATENCY_BUCKETS_MS.len() + 1];
+        }
+        let bucket = LATENCY_BUCKETS_MS.iter().position(|b| latency_ms <= *b).unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.counts[bucket] += 1;
+        self.max_ms = self.max_ms.max(latency_ms);
+    }
+
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        if self.counts.len() < other.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (count, more) in self.counts.iter_mut().zip(&other.counts) {
+            *count += more;
+        }
+        self.max_ms = self.max_ms.max(other.max_ms);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Upper bound of the bucket holding the `q` quantile; overflow reports the observed max
+    pub fn quantile(&self, q: f64) -> f64 {
+        let total = self.count();
+        if total == 0 {
+            return 0.0;
+        }
+        let rank = ((q.clamp(0.0, 1.0) * total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return LATENCY_BUCKETS_MS.get(i).map_or(self.max_ms, |b| b.min(self.max_ms));
+            }
+        }
+        self.max_ms
+    }
+}
+
+/// Metrics for one window, as persisted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub started_at: DateTime<Utc>,
+    pub taken_at: DateTime<Utc>,
+    pub votes_received: u64,
+    pub votes_admitted: u64,
+    /// Most votes received within a single second of the window
+    pub peak_votes_per_sec: u64,
+    pub latency: LatencyHistogram,
+    pub max_queue_depth: usize,
+    pub tally_ticks: u64,
+    pub mean_tally_tick_ms: f64,
+    pub max_tally_tick_ms: f64,
+}
+
+impl MetricsSnapshot {
+    pub fn window_secs(&self) -> f64 {
+        (self.taken_at - self.started_at).num_milliseconds() as f64 / 1000.0
+    }
+
+    pub fn votes_per_sec(&self) -> f64 {
+        let secs = self.window_secs();
+        if secs > 0.0 { self.votes_received as f64 / secs } else { 0.0 }
+    }
+}
+
+/// Accumulates throughput, latency, queue, and tally-tick metrics for the current window
+#[derive(Debug, Clone)]
+pub struct MetricsRecorder {
+    started_at: DateTime<Utc>,
+    votes_received: u64,
+    votes_admitted: u64,
+    per_second: BTreeMap<i64, u64>,
+    latency: LatencyHistogram,
+    max_queue_depth: usize,
+    tally_tick_ms: Vec<f64>,
+}
+
+impl MetricsRecorder {
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            started_at,
+            votes_received: 0,
+            votes_admitted: 0,
+            per_second: BTreeMap::new(),
+            latency: LatencyHistogram::default(),
+            max_queue_depth: 0,
+            tally_tick_ms: Vec::new(),
+        }
+    }
+
+    /// A vote received at `received_at` that took `latency_ms` to ingest
+    pub fn record_vote(&mut self, received_at: DateTime<Utc>, latency_ms: f64, admitted: bool) {
+        self.votes_received += 1;
+        if admitted {
+            self.votes_admitted += 1;
+        }
+        *self.per_second.entry(received_at.timestamp()).or_insert(0) += 1;
+        self.latency.record(latency_ms.max(0.0));
+    }
+
+    pub fn record_queue_depth(&mut self, depth: usize) {
+        self.max_queue_depth = self.max_queue_depth.max(depth);
+    }
+
+    pub fn record_tally_tick(&mut self, duration: std::time::Duration) {
+        self.tally_tick_ms.push(duration.as_secs_f64() * 1000.0);
+    }
+
+    /// Run `tally` and record how long it took
+    pub fn time_tally<T>(&mut self, tally: impl FnOnce() -> T) -> T {
+        let start = std::time::Instant::now();
+        let result = tally();
+        self.record_tally_tick(start.elapsed());
+        result
+    }
+
+    /// Close the window at `now` and start a new one
+    pub fn snapshot(&mut self, now: DateTime<Utc>) -> MetricsSnapshot {
+        let ticks = std::mem::take(&mut self.tally_tick_ms);
+        let snapshot = MetricsSnapshot {
+            started_at: self.started_at,
+            taken_at: now,
+            votes_received: self.votes_received,
+            votes_admitted: self.votes_admitted,
+            peak_votes_per_sec: self.per_second.values().copied().max().unwrap_or(0),
+            latency: std::mem::take(&mut self.latency),
+            max_queue_depth: self.max_queue_depth,
+            tally_ticks: ticks.len() as u64,
+            mean_tally_tick_ms: if ticks.is_empty() { 0.0 } else { ticks.iter().sum::<f64>() / ticks.len() as f64 },
+            max_tally_tick_ms: ticks.iter().copied().fold(0.0, f64::max),
+        };
+        *self = Self::new(now);
+        snapshot
+    }
+}
+
+/// Snapshots appended one JSON object per line
+#[derive(Debug, Clone)]
+pub struct MetricsStore {
+    pub path: PathBuf,
+}
+
+impl MetricsStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn append(&self, snapshot: &MetricsSnapshot) -> std::io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(snapshot).expect("snapshots serialize"))
+    }
+
+    /// Every stored snapshot, oldest first; unreadable lines are skipped
+    pub fn load(&self) -> std::io::Result<Vec<MetricsSnapshot>> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut snapshots = Vec::new();
+        for line in reader.lines() {
+            if let Ok(snapshot) = serde_json::from_str(&line?) {
+                snapshots.push(snapshot);
+            }
+        }
+        Ok(snapshots)
+    }
+}
+
+/// Snapshot `recorder` into `store` every `interval` on a background thread until `cancel`
+/// fires; a final snapshot is written on the way out. Failed writes go to `on_error`.
+pub fn spawn_snapshots(
+    recorder: Arc<Mutex<MetricsRecorder>>,
+    store: MetricsStore,
+    interval: std::time::Duration,
+    cancel: CancellationToken,
+    on_error: impl Fn(std::io::Error) + Send + 'static,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let tick = interval.min(std::time::Duration::from_millis(50));
+        loop {
+            let mut waited = std::time::Duration::ZERO;
+            while waited < interval && !cancel.is_cancelled() {
+                std::thread::sleep(tick);
+                waited += tick;
+            }
+            let snapshot = recorder.lock().expect("metrics lock poisoned").snapshot(Utc::now());
+            if let Err(e) = store.append(&snapshot) {
+                on_error(e);
+            }
+            if cancel.is_cancelled() {
+                break;
+            }
+        }
+    })
+}
+
+/// Sizing summary over stored snapshots
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CapacityReport {
+    pub snapshots: usize,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub votes_received: u64,
+    pub latency_p50_ms: f64,
+    pub latency_p95_ms: f64,
+    pub peak_votes_per_sec: u64,
+    pub peak_at: Option<DateTime<Utc>>,
+    pub max_queue_depth: usize,
+    /// Mean tally tick per snapshot that ran any, oldest first
+    pub tally_tick_trend: Vec<(DateTime<Utc>, f64)>,
+    /// Change in mean tick duration from the first half of the trend to the second, in percent
+    pub tally_tick_change_pct: Option<f64>,
+}
+
+impl CapacityReport {
+    pub fn from_snapshots(snapshots: &[MetricsSnapshot]) -> Self {
+        let mut latency = LatencyHistogram::default();
+        for s in snapshots {
+            latency.merge(&s.latency);
+        }
+        let peak = snapshots.iter().max_by_key(|s| s.peak_votes_per_sec);
+        let trend: Vec<(DateTime<Utc>, f64)> = snapshots
+            .iter()
+            .filter(|s| s.tally_ticks > 0)
+            .map(|s| (s.taken_at, s.mean_tally_tick_ms))
+            .collect();
+        let mean = |points: &[(DateTime<Utc>, f64)]| points.iter().map(|(_, ms)| ms).sum::<f64>() / points.len() as f64;
+        let (early, late) = trend.split_at(trend.len() / 2);
+        let change = (!early.is_empty() && mean(early) > 0.0).then(|| (mean(late) / mean(early) - 1.0) * 100.0);
+
+        Self {
+            snapshots: snapshots.len(),
+            from: snapshots.first().map(|s| s.started_at),
+            to: snapshots.last().map(|s| s.taken_at),
+            votes_received: snapshots.iter().map(|s| s.votes_received).sum(),
+            latency_p50_ms: latency.quantile(0.5),
+            latency_p95_ms: latency.quantile(0.95),
+            peak_votes_per_sec: peak.map_or(0, |s| s.peak_votes_per_sec),
+            peak_at: peak.map(|s| s.taken_at),
+            max_queue_depth: snapshots.iter().map(|s| s.max_queue_depth).max().unwrap_or(0),
+            tally_tick_trend: trend,
+            tally_tick_change_pct: change,
+        }
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "📈 Capacity report over {} snapshot(s)", self.snapshots);
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            let _ = writeln!(out, "  period:    {} to {}", from, to);
+        }
+        let _ = writeln!(out, "  votes:     {} received", self.votes_received);
+        let _ = writeln!(
+            out,
+            "  latency:   p50 ≤ {:.0}ms, p95 ≤ {:.0}ms",
+            self.latency_p50_ms, self.latency_p95_ms
+        );
+        let _ = writeln!(
+            out,
+            "  peak:      {} votes/sec{}",
+            self.peak_votes_per_sec,
+            self.peak_at.map_or(String::new(), |at| format!(" (window ending {})", at))
+        );
+        let _ = writeln!(out, "  queue:     max depth {}", self.max_queue_depth);
+        let _ = writeln!(out, "  tally ticks:");
+        for (at, ms) in &self.tally_tick_trend {
+            let _ = writeln!(out, "  - {}: {:.2}ms", at, ms);
+        }
+        if let Some(change) = self.tally_tick_change_pct {
+            let _ = writeln!(out, "  tick trend: {:+.1}% from first half to second", change);
+        }
+        out
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_histogram_quantiles_and_merge() {
+        let mut a = LatencyHistogram::default();
+        for ms in [0.5, 3.0, 4.0, 8.0, 40.0] {
+            a.record(ms);
+        }
+        assert_eq!(a.quantile(0.5), 5.0);
+        assert_eq!(a.quantile(0.95), 40.0);
+
+        let mut b = LatencyHistogram::default();
+        b.record(9000.0);
+        a.merge(&b);
+        assert_eq!(a.count(), 6);
+        assert_eq!(a.quantile(1.0), 9000.0);
+        assert_eq!(LatencyHistogram::default().quantile(0.5), 0.0);
+    }
+
+    #[test]
+    fn test_snapshots_persist_and_report() {
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut recorder = MetricsRecorder::new(start);
+        for i in 0..6 {
+            recorder.record_vote(start + Duration::milliseconds(i * 100), 3.0, i != 5);
+        }
+        recorder.record_vote(start + Duration::seconds(5), 150.0, true);
+        recorder.record_queue_depth(12);
+        recorder.record_tally_tick(std::time::Duration::from_millis(4));
+        let first = recorder.snapshot(start + Duration::seconds(10));
+        assert_eq!((first.votes_received, first.votes_admitted, first.peak_votes_per_sec), (7, 6, 6));
+        assert!((first.votes_per_sec() - 0.7).abs() < 1e-9);
+
+        recorder.record_tally_tick(std::time::Duration::from_millis(6));
+        let second = recorder.snapshot(start + Duration::seconds(20));
+        assert_eq!(second.votes_received, 0);
+
+        let path = std::env::temp_dir().join(format!("tdc-metrics-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = MetricsStore::new(&path);
+        store.append(&first).unwrap();
+        store.append(&second).unwrap();
+        let loaded = store.load().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, vec![first, second]);
+
+        let report = CapacityReport::from_snapshots(&loaded);
+        assert_eq!((report.latency_p50_ms, report.latency_p95_ms), (5.0, 150.0));
+        assert_eq!(report.peak_votes_per_sec, 6);
+        assert_eq!(report.max_queue_depth, 12);
+        assert!((report.tally_tick_change_pct.unwrap() - 50.0).abs() < 1e-6);
+        assert!(report.render_text().contains("p95 ≤ 150ms"));
+    }
+}
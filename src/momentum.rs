@@ -0,0 +1,186 @@
+// src/momentum.rs
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::threshold::ThresholdEscalator;
+use crate::units::Seconds;
+
+/// Knobs for `MomentumModifier`. Rates are changes in approval ratio per second.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MomentumConfig {
+    /// How far back the approval trend is measured
+    pub lookback: Seconds,
+    /// Rise in approval ratio per second above which support counts as a surge
+    pub surge_rate: f64,
+    /// Added to the threshold while a surge lasts (still capped at the ceiling)
+    pub surge_penalty: f64,
+    /// Approval ratio moving less than this per second counts as steady
+    pub steady_rate: f64,
+    /// Escalation clock speed while support is steady, in (0, 1]
+    pub steady_slowdown: f64,
+}
+
+impl MomentumConfig {
+    pub fn new() -> Self {
+        Self {
+            lookback: Seconds::new(60),
+            surge_rate: 0.005,
+            surge_penalty: 0.1,
+            steady_rate: 0.0005,
+            steady_slowdown: 0.5,
+        }
+    }
+}
+
+impl Default for MomentumConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How approval has been moving over the lookback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Momentum {
+    /// Too few samples to judge, or neither surging nor steady
+    Neutral,
+    Surge,
+    Steady,
+}
+
+/// One threshold evaluation, with everything that went into it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MomentumAdjustment {
+    pub at: DateTime<Utc>,
+    pub approval_ratio: f64,
+    /// Change in approval ratio per second over the lookback
+    pub rate: f64,
+    pub momentum: Momentum,
+    pub base_threshold: f64,
+    pub adjusted_threshold: f64,
+}
+
+/// Escalation modifier reacting to how fast approval moves: surges raise the
+/// threshold to resist flash-mob swings, steady support slows escalation down
+#[derive(Debug, Clone, Default)]
+pub struct MomentumModifier {
+    pub config: MomentumConfig,
+    samples: VecDeque<(DateTime<Utc>, f64)>,
+    log: Vec<MomentumAdjustment>,
+}
+
+impl MomentumModifier {
+    pub fn new(config: MomentumConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Record the approval ratio at `at`; samples older than the lookback are dropped
+    pub fn observe(&mut self, at: DateTime<Utc>, approval_ratio: f64) {
+        self.samples.push_back((at, approval_ratio));
+        let cutoff = at - self.config.lookback.to_duration();
+        while self.samples.len() > 2 && self.samples[1].0 <= cutoff {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Approval ratio change per second between the oldest and newest sample
+    pub fn rate(&self) -> Option<f64> {
+        let (first, last) = (self.samples.front()?, self.samples.back()?);
+        let span = Seconds::between(first.0, last.0);
+        (span.as_i64() > 0).then(|| (last.1 - first.1) / span.as_f64())
+    }
+
+    pub fn momentum(&self) -> Momentum {
+        match self.rate() {
+            Some(rate) if rate >= self.config.surge_rate => Momentum::Surge,
+            Some(rate) if rate.abs() <= self.config.steady_rate => Momentum::Steady,
+            _ => Momentum::Neutral,
+        }
+    }
+
+    /// `escalator`'s threshold at `now` for a window opened at `start`, adjusted for
+    /// momentum. Every call is appended to the log.
+    pub fn threshold(&mut self, escalator: &ThresholdEscalator, now: DateTime<Utc>, start: DateTime<Utc>) -> f64 {
+        let base_threshold = escalator.threshold_with_profile(now, start);
+        let momentum = self.momentum();
+        let adjusted_threshold = match momentum {
+            Momentum::Surge => (base_threshold + self.config.surge_penalty).min(escalator.ceiling.max(base_threshold)),
+            Momentum::Steady => {
+                let elapsed = (now - start).num_milliseconds().max(0) as f64;
+                let slowed = (elapsed * self.config.steady_slowdown.clamp(0.0, 1.0)) as i64;
+                escalator.threshold_with_profile(now, now - Duration::milliseconds(slowed))
+            }
+            Momentum::Neutral => base_threshold,
+        };
+        self.log.push(MomentumAdjustment {
+            at: now,
+            approval_ratio: self.samples.back().map_or(0.0, |s| s.1),
+            rate: self.rate().unwrap_or(0.0),
+            momentum,
+            base_threshold,
+            adjusted_threshold,
+        });
+        adjusted_threshold
+    }
+
+    pub fn log(&self) -> &[MomentumAdjustment] {
+        &self.log
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::ProposalType;
+
+    #[test]
+    fn test_surge_raises_threshold() {
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+        let start = Utc::now();
+        let mut modifier = MomentumModifier::new(MomentumConfig::default());
+        modifier.observe(start + Duration::seconds(10), 0.1);
+        modifier.observe(start + Duration::seconds(20), 0.7);
+        assert_eq!(modifier.momentum(), Momentum::Surge);
+
+        let now = start + Duration::seconds(20);
+        let threshold = modifier.threshold(&escalator, now, start);
+        assert!((threshold - (escalator.threshold_with_profile(now, start) + 0.1)).abs() < 1e-9);
+        let entry = &modifier.log()[0];
+        assert_eq!(entry.momentum, Momentum::Surge);
+        assert!((entry.rate - 0.06).abs() < 1e-9);
+        assert_eq!(entry.approval_ratio, 0.7);
+    }
+
+    #[test]
+    fn test_steady_support_slows_escalation() {
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+        let start = Utc::now();
+        let mut modifier = MomentumModifier::new(MomentumConfig::default());
+        for i in 0..=6 {
+            modifier.observe(start + Duration::seconds(i * 10), 0.6);
+        }
+        assert_eq!(modifier.momentum(), Momentum::Steady);
+
+        let now = start + Duration::seconds(20);
+        let threshold = modifier.threshold(&escalator, now, start);
+        assert!((threshold - escalator.threshold_with_profile(start + Duration::seconds(10), start)).abs() < 1e-9);
+        assert!(threshold < modifier.log()[0].base_threshold);
+    }
+
+    #[test]
+    fn test_lookback_drops_old_samples() {
+        let start = Utc::now();
+        let mut modifier = MomentumModifier::new(MomentumConfig::default());
+        assert_eq!(modifier.momentum(), Momentum::Neutral);
+        modifier.observe(start, 0.0);
+        modifier.observe(start + Duration::seconds(100), 0.5);
+        modifier.observe(start + Duration::seconds(200), 0.5);
+        assert_eq!(modifier.rate(), Some(0.0));
+    }
+}
@@ -0,0 +1,254 @@
+// src/namespace.rs
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::blockchain::{BlockPayload, Blockchain, Transaction};
+
+#[derive(Error, Debug)]
+pub enum NamespaceError {
+    #[error("Unknown namespace: {0}")]
+    UnknownNamespace(String),
+    #[error("Namespace already exists: {0}")]
+    DuplicateNamespace(String),
+    #[error("Unknown proposal: {0}")]
+    UnknownProposal(String),
+    #[error("Proposal {proposal_id} belongs to namespace {owner}")]
+    ForeignProposal { proposal_id: String, owner: String },
+    #[error("Storage error: {0}")]
+    Storage(#[from] std::io::Error),
+}
+
+/// Per-namespace chain settings
+#[derive(Debug, Clone)]
+pub struct NamespaceConfig {
+    pub namespace: String,
+    pub difficulty: usize,
+    pub storage_path: PathBuf,
+}
+
+impl NamespaceConfig {
+    pub fn new(namespace: &str, difficulty: usize, storage_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            difficulty,
+            storage_path: storage_dir.into().join(format!("{}.chain.json", namespace)),
+        }
+    }
+
+    fn genesis_data(&self) -> String {
+        format!("Genesis Block: {}", self.namespace)
+    }
+}
+
+struct NamespaceChain {
+    config: NamespaceConfig,
+    chain: Blockchain,
+}
+
+/// One independent chain per governance namespace. Every proposal is owned by exactly
+/// one namespace, and its transactions may only land on that namespace's chain.
+#[derive(Default)]
+pub struct NamespaceManager {
+    chains: HashMap<String, NamespaceChain>,
+    proposal_owners: HashMap<String, String>,
+}
+
+impl NamespaceManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a namespace, resuming its chain from storage when a file exists
+    pub fn add_namespace(&mut self, config: NamespaceConfig) -> Result<(), NamespaceError> {
+        if self.chains.contains_key(&config.namespace) {
+            return Err(NamespaceError::DuplicateNamespace(config.namespace));
+        }
+
+        let chain = if config.storage_path.exists() {
+            Blockchain::load(&config.storage_path)?
+        } else {
+            Blockchain::with_genesis(config.genesis_data(), config.difficulty)
+        };
+
+        for block in &chain.blocks {
+            for tx in BlockPayload::decode(&block.data).transactions() {
+                self.proposal_owners
+                    .entry(tx.proposal_id().to_string())
+                    .or_insert_with(|| config.namespace.clone());
+            }
+        }
+
+        self.chains
+            .insert(config.namespace.clone(), NamespaceChain { config, chain });
+        Ok(())
+    }
+
+    pub fn namespaces(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.chains.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+
+    pub fn chain(&self, namespace: &str) -> Option<&Blockchain> {
+        self.chains.get(namespace).map(|c| &c.chain)
+    }
+
+    /// Register a proposal under a namespace; ids are unique across all namespaces
+    pub fn open_proposal(&mut self, namespace: &str, proposal_id: &str) -> Result<(), NamespaceError> {
+        if !self.chains.contains_key(namespace) {
+            return Err(NamespaceError::UnknownNamespace(namespace.to_string()));
+        }
+        if let Some(owner) = self.proposal_owners.get(proposal_id) {
+            if owner != namespace {
+                return Err(NamespaceError::ForeignProposal {
+                    proposal_id: proposal_id.to_string(),
+                    owner: owner.clone(),
+                });
+            }
+            return Ok(());
+        }
+        self.proposal_owners
+            .insert(proposal_id.to_string(), namespace.to_string());
+        Ok(())
+    }
+
+    pub fn namespace_of(&self, proposal_id: &str) -> Option<&str> {
+        self.proposal_owners.get(proposal_id).map(String::as_str)
+    }
+
+    /// Check that a proposal may be touched from `namespace`
+    pub fn check_proposal(&self, namespace: &str, proposal_id: &str) -> Result<(), NamespaceError> {
+        match self.proposal_owners.get(proposal_id) {
+            None => Err(NamespaceError::UnknownProposal(proposal_id.to_string())),
+            Some(owner) if owner != namespace => Err(NamespaceError::ForeignProposal {
+                proposal_id: proposal_id.to_string(),
+                owner: owner.clone(),
+            }),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Mine transactions into a namespace's chain; rejected as a whole if any of them
+    /// refers to a proposal owned elsewhere
+    pub fn record(&mut self, namespace: &str, transactions: &[Transaction]) -> Result<(), NamespaceError> {
+        if !self.chains.contains_key(namespace) {
+            return Err(NamespaceError::UnknownNamespace(namespace.to_string()));
+        }
+        for tx in transactions {
+            self.check_proposal(namespace, tx.proposal_id())?;
+        }
+
+        let entry = self.chains.get_mut(namespace).expect("namespace checked above");
+        entry.chain.add_transactions(transactions);
+        Ok(())
+    }
+
+    /// Persist one namespace's chain to its own storage path
+    pub fn save(&self, namespace: &str) -> Result<(), NamespaceError> {
+        let entry = self
+            .chains
+            .get(namespace)
+            .ok_or_else(|| NamespaceError::UnknownNamespace(namespace.to_string()))?;
+        if let Some(dir) = entry.config.storage_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        entry.chain.save(&entry.config.storage_path)?;
+        Ok(())
+    }
+
+    pub fn save_all(&self) -> Result<(), NamespaceError> {
+        for namespace in self.chains.keys() {
+            self.save(namespace)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::FinalizationEntry;
+    use chrono::Utc;
+
+    fn finalization(proposal_id: &str) -> Transaction {
+        Transaction::Finalization(FinalizationEntry {
+            proposal_id: proposal_id.to_string(),
+            passed: true,
+            weight: 1.0,
+            threshold: 0.5,
+            finalized_at: Utc::now(),
+        })
+    }
+
+    fn two_namespaces(dir: &std::path::Path) -> NamespaceManager {
+        let mut manager = NamespaceManager::new();
+        manager.add_namespace(NamespaceConfig::new("dao-a", 1, dir)).unwrap();
+        manager.add_namespace(NamespaceConfig::new("dao-b", 2, dir)).unwrap();
+        manager
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tdc-namespace-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_chains_are_independent() {
+        let dir = temp_dir("independent");
+        let manager = two_namespaces(&dir);
+        let a = manager.chain("dao-a").unwrap();
+        let b = manager.chain("dao-b").unwrap();
+
+        assert_ne!(a.blocks[0].hash, b.blocks[0].hash);
+        assert_eq!(a.difficulty, 1);
+        assert_eq!(b.difficulty, 2);
+        assert_eq!(manager.namespaces(), vec!["dao-a", "dao-b"]);
+    }
+
+    #[test]
+    fn test_cross_namespace_isolation() {
+        let dir = temp_dir("isolation");
+        let mut manager = two_namespaces(&dir);
+        manager.open_proposal("dao-a", "p1").unwrap();
+
+        assert!(matches!(
+            manager.open_proposal("dao-b", "p1"),
+            Err(NamespaceError::ForeignProposal { .. })
+        ));
+        assert!(matches!(
+            manager.record("dao-b", &[finalization("p1")]),
+            Err(NamespaceError::ForeignProposal { .. })
+        ));
+        assert!(matches!(
+            manager.record("dao-a", &[finalization("unknown")]),
+            Err(NamespaceError::UnknownProposal(_))
+        ));
+
+        manager.record("dao-a", &[finalization("p1")]).unwrap();
+        assert_eq!(manager.chain("dao-a").unwrap().blocks.len(), 2);
+        assert_eq!(manager.chain("dao-b").unwrap().blocks.len(), 1);
+        assert!(manager.chain("dao-a").unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_separate_storage_round_trip() {
+        let dir = temp_dir("storage");
+        let mut manager = two_namespaces(&dir);
+        manager.open_proposal("dao-b", "p2").unwrap();
+        manager.record("dao-b", &[finalization("p2")]).unwrap();
+        manager.save_all().unwrap();
+
+        assert!(dir.join("dao-a.chain.json").exists());
+        assert!(dir.join("dao-b.chain.json").exists());
+
+        let reloaded = two_namespaces(&dir);
+        assert_eq!(reloaded.chain("dao-b").unwrap().blocks.len(), 2);
+        assert_eq!(reloaded.namespace_of("p2"), Some("dao-b"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
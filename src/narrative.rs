@@ -0,0 +1,168 @@
+// src/narrative.rs
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::report::{Anomaly, FinalizationReport};
+
+/// Votes cast in this final share of the voting window count as late
+pub const LATE_WINDOW_SHARE: f64 = 0.1;
+
+/// Plain-language account of a finalization, for governance announcements
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Narrative {
+    pub proposal_id: String,
+    pub passed: bool,
+    pub decided_at: DateTime<Utc>,
+    /// Weight the decision was made on, on the same scale as the threshold
+    pub decided_weight: f64,
+    pub threshold: f64,
+    /// Whether the threshold had risen above its base by the time of the decision
+    pub escalated: bool,
+    /// Share of the eligible weight that voted
+    pub quorum: f64,
+    pub approval: f64,
+    pub counted_votes: usize,
+    pub late_votes: usize,
+    /// Share of the counted weight that came from late votes
+    pub late_share: f64,
+    pub excluded_votes: usize,
+    pub tied: bool,
+    /// The narrative, one sentence per entry, headline first
+    pub sentences: Vec<String>,
+}
+
+impl Narrative {
+    pub fn from_report(report: &FinalizationReport) -> Self {
+        let p = &report.proposal;
+        let span = (p.finalized_at - p.opened_at).num_seconds().max(0);
+        let late_from = p.finalized_at - Duration::seconds((span as f64 * LATE_WINDOW_SHARE).round() as i64);
+        let late: Vec<f64> = report
+            .tally
+            .contributions
+            .iter()
+            .filter(|c| c.cast_at.is_some_and(|at| at >= late_from))
+            .map(|c| c.share)
+            .collect();
+
+        let mut narrative = Self {
+            proposal_id: p.proposal_id.clone(),
+            passed: report.passed,
+            decided_at: p.finalized_at,
+            decided_weight: report.decided_weight(),
+            threshold: report.final_threshold,
+            escalated: report.final_threshold > report.parameters.base_threshold,
+            quorum: report.tally.normalized.turnout_fraction,
+            approval: report.tally.normalized.approval_fraction,
+            counted_votes: report.tally.counted_votes,
+            late_votes: late.len(),
+            late_share: late.iter().sum(),
+            excluded_votes: report.tally.excluded_votes,
+            tied: report.tied,
+            sentences: Vec::new(),
+        };
+        narrative.sentences = narrative.compose(&report.anomalies);
+        narrative
+    }
+
+    fn compose(&self, anomalies: &[Anomaly]) -> Vec<String> {
+        let mut sentences = vec![format!(
+            "{} {} at {} with {:.1}% of decayed weight vs {:.1}% {} threshold; quorum {:.1}%; {} late vote{} contributed {:.1}%.",
+            self.proposal_id,
+            if self.passed { "passed" } else { "was rejected" },
+            self.decided_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.decided_weight * 100.0,
+            self.threshold * 100.0,
+            if self.escalated { "escalated" } else { "base" },
+            self.quorum * 100.0,
+            self.late_votes,
+            if self.late_votes == 1 { "" } else { "s" },
+            self.late_share * 100.0
+        )];
+        sentences.push(format!(
+            "{} vote{} counted, {:.1}% of cast weight in favor.",
+            self.counted_votes,
+            if self.counted_votes == 1 { " was" } else { "s were" },
+            self.approval * 100.0
+        ));
+        if self.tied {
+            sentences.push("The weight landed exactly on the threshold, so the tie-break rule decided.".to_string());
+        }
+        if self.excluded_votes > 0 {
+            sentences.push(format!(
+                "{} vote{} excluded ({} anomal{} noted).",
+                self.excluded_votes,
+                if self.excluded_votes == 1 { " was" } else { "s were" },
+                anomalies.len(),
+                if anomalies.len() == 1 { "y" } else { "ies" }
+            ));
+        }
+        sentences
+    }
+
+    pub fn headline(&self) -> &str {
+        &self.sentences[0]
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        for sentence in &self.sentences {
+            let _ = writeln!(out, "{}", sentence);
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("narrative serializes")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ReportInput;
+    use crate::threshold::DecisionRule;
+    use crate::trust::TrustEngine;
+    use crate::vote::{DecayType, ProposalType, SignedVote};
+
+    #[test]
+    fn test_narrative_from_report() {
+        let now = Utc::now();
+        let opened = now - Duration::seconds(100);
+        let key = SignedVote::generate_keypair();
+        let vote = |voter: &str, ago: i64| {
+            SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, now - Duration::seconds(ago), DecayType::Linear, &key)
+        };
+        let votes = vec![vote("alice", 80), vote("bob", 60), vote("carol", 5), vote("dave", 500)];
+        let report = FinalizationReport::build(ReportInput {
+            proposal_id: "p1",
+            proposal_type: ProposalType::Normal,
+            opened_at: opened,
+            finalized_at: now,
+            votes: &votes,
+            trust: &TrustEngine::new(),
+            max_age_secs: 3600,
+            admissions: None,
+            eligible_weight: Some(4.0),
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        });
+
+        let narrative = Narrative::from_report(&report);
+        assert!(narrative.passed);
+        assert!(narrative.escalated);
+        assert_eq!((narrative.counted_votes, narrative.late_votes, narrative.excluded_votes), (3, 1, 1));
+        let carol = report.tally.contributions.iter().find(|c| c.voter_id == "carol").unwrap();
+        assert!((narrative.late_share - carol.share).abs() < 1e-12);
+        assert!(narrative.headline().starts_with("p1 passed at "));
+        assert!(narrative.headline().contains(&format!("vs {:.1}% escalated threshold", report.final_threshold * 100.0)));
+        assert!(narrative.headline().contains("1 late vote contributed"));
+        assert!(narrative.render_text().contains("1 vote was excluded (1 anomaly noted)."));
+
+        let decoded: Narrative = serde_json::from_str(&narrative.to_json()).unwrap();
+        assert_eq!(decoded, narrative);
+    }
+}
@@ -0,0 +1,211 @@
+// src/opportunity.rs
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::ids::ProposalId;
+use crate::snapshot::TallySnapshot;
+use crate::threshold::ThresholdEscalator;
+use crate::trust::TrustSource;
+use crate::units::{Seconds, Weight};
+use crate::vote::{DecayType, SignedVote};
+use crate::weight_engine::decay_weight;
+
+/// The attacker assumed by an opportunity analysis: identical fresh members with no
+/// trust bonus, all voting against the outcome at the same moment
+#[derive(Debug, Clone)]
+pub struct AttackModel {
+    pub member_weight: f64,
+    pub decay_model: DecayType,
+    /// Coalitions larger than this are reported as unable to flip the outcome
+    pub max_coalition: usize,
+    /// Moments checked between opening and finalization, both ends included
+    pub samples: usize,
+}
+
+impl Default for AttackModel {
+    fn default() -> Self {
+        Self {
+            member_weight: 1.0,
+            decay_model: DecayType::Linear,
+            max_coalition: 1000,
+            samples: 24,
+        }
+    }
+}
+
+/// What a coalition voting at `at` needed to flip the final outcome
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Opportunity {
+    pub at: DateTime<Utc>,
+    /// One member's weight at finalization after decaying since `at`
+    pub member_weight_at_close: f64,
+    /// Smallest flipping coalition; `None` when it would exceed `max_coalition`
+    pub coalition: Option<usize>,
+    pub coalition_weight: Option<f64>,
+}
+
+/// For a finished proposal, the coalition an attacker needed at each moment of its
+/// window to flip the outcome, given how their votes would decay and where the
+/// threshold had escalated to by finalization
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpportunityReport {
+    pub proposal_id: ProposalId,
+    pub opened_at: DateTime<Utc>,
+    pub finalized_at: DateTime<Utc>,
+    pub passed: bool,
+    pub decided_weight: f64,
+    pub threshold: f64,
+    pub honest_voters: usize,
+    pub moments: Vec<Opportunity>,
+}
+
+impl OpportunityReport {
+    pub fn analyze(
+        proposal_id: &ProposalId,
+        votes: &[SignedVote],
+        escalator: &ThresholdEscalator,
+        opened_at: DateTime<Utc>,
+        finalized_at: DateTime<Utc>,
+        trust: &dyn TrustSource,
+        model: &AttackModel,
+    ) -> Self {
+        let outcome = TallySnapshot::capture(proposal_id, votes, escalator, opened_at, finalized_at, trust);
+        let span = (finalized_at - opened_at).num_milliseconds().max(0);
+        let samples = model.samples.max(1) as i64;
+        let moments = (0..=samples)
+            .map(|i| {
+                let at = opened_at + Duration::milliseconds(span * i / samples);
+                let member = decay_weight(
+                    model.decay_model,
+                    Weight::saturating(model.member_weight),
+                    Seconds::between(at, finalized_at),
+                )
+                .get();
+                let coalition = (1..=model.max_coalition).find(|&k| flips(&outcome, escalator, k, member));
+                Opportunity {
+                    at,
+                    member_weight_at_close: member,
+                    coalition,
+                    coalition_weight: coalition.map(|k| k as f64 * member),
+                }
+            })
+            .collect();
+        Self {
+            proposal_id: proposal_id.clone(),
+            opened_at,
+            finalized_at,
+            passed: outcome.passing,
+            decided_weight: outcome.decided_weight,
+            threshold: outcome.threshold,
+            honest_voters: outcome.voters.len(),
+            moments,
+        }
+    }
+
+    /// The smallest coalition that could have flipped the outcome at any moment
+    pub fn minimal_coalition(&self) -> Option<usize> {
+        self.moments.iter().filter_map(|m| m.coalition).min()
+    }
+
+    /// First and last moment at which the minimal coalition sufficed
+    pub fn window(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let minimal = self.minimal_coalition()?;
+        let mut moments = self.moments.iter().filter(|m| m.coalition == Some(minimal));
+        let first = moments.next()?.at;
+        Some((first, moments.next_back().map_or(first, |m| m.at)))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("opportunity reports serialize")
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let outcome = if self.passed { "passed" } else { "failed" };
+        let _ = writeln!(out, "🛡️ {} {} with {:.4} against threshold {:.4}", self.proposal_id, outcome, self.decided_weight, self.threshold);
+        match (self.minimal_coalition(), self.window()) {
+            (Some(minimal), Some((from, to))) => {
+                let _ = writeln!(out, "  minimal flipping coalition: {} (honest voters: {})", minimal, self.honest_voters);
+                let _ = writeln!(out, "  window: {} to {}", from.to_rfc3339(), to.to_rfc3339());
+            }
+            _ => {
+                let _ = writeln!(out, "  no coalition within the model's limit could flip the outcome");
+            }
+        }
+        for moment in &self.moments {
+            let needed = moment.coalition.map_or_else(|| "-".to_string(), |k| k.to_string());
+            let _ = writeln!(out, "  {} member {:.4} coalition {}", moment.at.to_rfc3339(), moment.member_weight_at_close, needed);
+        }
+        out
+    }
+}
+
+/// Whether `size` members each adding `member` weight against the outcome reverse it
+fn flips(outcome: &TallySnapshot, escalator: &ThresholdEscalator, size: usize, member: f64) -> bool {
+    let tally = &outcome.tally;
+    let attack = size as f64 * member;
+    let decide = |weight: f64| escalator.decision.decide(weight, outcome.threshold, "", &outcome.proposal_id).passed;
+    if outcome.passing {
+        !decide((tally.approve_weight - tally.reject_weight - attack).max(0.0))
+    } else {
+        let opposed = outcome.voters.values().any(|v| !v.choice.is_approve());
+        let approve = tally.approve_weight + attack;
+        let decided = if opposed { (approve - tally.reject_weight).max(0.0) } else { approve };
+        decide(decided) && outcome.voters.len() + size >= escalator.min_vote_count
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::trust::TrustEngine;
+    use crate::vote::ProposalType;
+
+    fn analyze(voters: &[&str]) -> OpportunityReport {
+        let opened = Utc::now() - Duration::seconds(600);
+        let p1: ProposalId = "p1".parse().unwrap();
+        let key = SignedVote::generate_keypair();
+        let votes: Vec<SignedVote> = voters
+            .iter()
+            .map(|v| SignedVote::new(v.parse().unwrap(), p1.clone(), 1.0, opened, DecayType::Linear, &key))
+            .collect();
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+        let model = AttackModel { samples: 6, ..AttackModel::default() };
+        OpportunityReport::analyze(&p1, &votes, &escalator, opened, opened + Duration::seconds(600), &TrustEngine::new(), &model)
+    }
+
+    #[test]
+    fn test_late_coalitions_are_cheapest() {
+        let report = analyze(&["alice", "bob", "carol", "dave"]);
+        assert!(report.passed);
+        assert_eq!(report.moments.len(), 7);
+        let sizes: Vec<usize> = report.moments.iter().map(|m| m.coalition.unwrap()).collect();
+        // Members voting later decay less by finalization, so fewer are needed
+        assert!(sizes.windows(2).all(|w| w[1] <= w[0]));
+        assert!(sizes[0] > sizes[6]);
+        assert_eq!(report.minimal_coalition(), Some(sizes[6]));
+        assert_eq!(report.window().unwrap().1, report.finalized_at);
+        assert!(report.render_text().contains("minimal flipping coalition"));
+    }
+
+    #[test]
+    fn test_failed_proposals_need_the_missing_votes() {
+        let report = analyze(&["alice"]);
+        assert!(!report.passed);
+        // Normal proposals need three votes, so one attacker is never enough
+        assert!(report.moments.iter().all(|m| m.coalition.unwrap() >= 2));
+
+        let unreachable = AttackModel { max_coalition: 1, ..AttackModel::default() };
+        let p1: ProposalId = "p1".parse().unwrap();
+        let now = Utc::now();
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+        let report = OpportunityReport::analyze(&p1, &[], &escalator, now, now, &TrustEngine::new(), &unreachable);
+        assert_eq!(report.minimal_coalition(), None);
+        assert!(report.to_json().contains("\"coalition\": null"));
+    }
+}
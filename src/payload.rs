@@ -0,0 +1,474 @@
+// src/payload.rs
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::decay::DecayParams;
+use crate::vectors::{decay_from_name, decay_name};
+use crate::vote::{DecayType, SignedVote, VoteChoice};
+
+/// Payload layout signed before weight and decay model were covered
+pub const LEGACY_PAYLOAD_VERSION: u32 = 1;
+/// Payload layout new votes are signed with; also covers weight and decay model
+pub const PAYLOAD_VERSION: u32 = 2;
+
+pub(crate) fn legacy_payload_version() -> u32 {
+    LEGACY_PAYLOAD_VERSION
+}
+
+pub(crate) fn is_legacy_payload(version: &u32) -> bool {
+    *version == LEGACY_PAYLOAD_VERSION
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum PayloadError {
+    #[error("Payload is not UTF-8")]
+    NotUtf8,
+    #[error("Payload is missing its {0}")]
+    Missing(&'static str),
+    #[error("Malformed {0} in payload")]
+    Malformed(&'static str),
+    #[error("Payload has trailing data: {0}")]
+    Trailing(String),
+    #[error("Payload is not in canonical form")]
+    NonCanonical,
+}
+
+/// A point in time as whole seconds since the Unix epoch plus nanoseconds (`< 10^9`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+pub struct PayloadTime {
+    pub secs: i64,
+    pub nanos: u32,
+}
+
+impl From<DateTime<Utc>> for PayloadTime {
+    fn from(at: DateTime<Utc>) -> Self {
+        Self { secs: at.timestamp(), nanos: at.timestamp_subsec_nanos() }
+    }
+}
+
+impl PayloadTime {
+    pub fn to_datetime(self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.secs, self.nanos)
+    }
+
+    /// `YYYY-MM-DD hh:mm:ss[.f] UTC`, the vote timestamp form
+    fn write_utc(&self, out: &mut String) {
+        self.write(out, ' ');
+        out.push_str(" UTC");
+    }
+
+    /// `YYYY-MM-DDThh:mm:ss[.f]+00:00`, the expiry form
+    fn write_rfc3339(&self, out: &mut String) {
+        self.write(out, 'T');
+        out.push_str("+00:00");
+    }
+
+    fn write(&self, out: &mut String, separator: char) {
+        let days = self.secs.div_euclid(86_400);
+        let secs_of_day = self.secs.rem_euclid(86_400);
+        let (year, month, day) = civil_from_days(days);
+        if (0..=9999).contains(&year) {
+            out.push_str(&format!("{:04}", year));
+        } else {
+            out.push_str(&format!("{:+05}", year));
+        }
+        out.push_str(&format!(
+            "-{:02}-{:02}{}{:02}:{:02}:{:02}",
+            month,
+            day,
+            separator,
+            secs_of_day / 3600,
+            secs_of_day / 60 % 60,
+            secs_of_day % 60
+        ));
+        out.push_str(&fraction(self.nanos));
+    }
+
+    /// Parse the output of `write` up to and including `suffix`, returning the rest
+    fn parse<'a>(input: &'a str, separator: char, suffix: &str) -> Result<(Self, &'a str), PayloadError> {
+        let malformed = PayloadError::Malformed("timestamp");
+        let end = input.find(suffix).ok_or(malformed.clone())?;
+        let (text, rest) = (&input[..end], &input[end + suffix.len()..]);
+        let (date, time) = text.split_once(separator).ok_or(malformed.clone())?;
+        let (year, month_day) = date.get(1..).and_then(|d| d.find('-')).map(|i| date.split_at(i + 1)).ok_or(malformed.clone())?;
+        let year: i64 = year.parse().map_err(|_| malformed.clone())?;
+        let mut md = month_day[1..].splitn(2, '-');
+        let month: u32 = digits(md.next(), 2).ok_or(malformed.clone())?;
+        let day: u32 = digits(md.next(), 2).ok_or(malformed.clone())?;
+        let (hms, frac) = time.split_once('.').map_or((time, None), |(h, f)| (h, Some(f)));
+        let mut parts = hms.splitn(3, ':');
+        let hour: i64 = digits(parts.next(), 2).ok_or(malformed.clone())?;
+        let minute: i64 = digits(parts.next(), 2).ok_or(malformed.clone())?;
+        let second: i64 = digits(parts.next(), 2).ok_or(malformed.clone())?;
+        let nanos = match frac {
+            None => 0,
+            Some(f) if matches!(f.len(), 3 | 6 | 9) => {
+                let value: u32 = digits(Some(f), f.len()).ok_or(malformed.clone())?;
+                value * 10u32.pow(9 - f.len() as u32)
+            }
+            Some(_) => return Err(malformed),
+        };
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+            return Err(malformed);
+        }
+        let secs = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second;
+        Ok((Self { secs, nanos }, rest))
+    }
+}
+
+/// Fractional seconds as chrono prints them: none, or 3, 6 or 9 digits, whichever is shortest exact
+fn fraction(nanos: u32) -> String {
+    if nanos == 0 {
+        String::new()
+    } else if nanos.is_multiple_of(1_000_000) {
+        format!(".{:03}", nanos / 1_000_000)
+    } else if nanos.is_multiple_of(1_000) {
+        format!(".{:06}", nanos / 1_000)
+    } else {
+        format!(".{:09}", nanos)
+    }
+}
+
+fn digits<T: std::str::FromStr>(text: Option<&str>, len: usize) -> Option<T> {
+    text.filter(|t| t.len() == len && t.bytes().all(|b| b.is_ascii_digit()))?.parse().ok()
+}
+
+/// Proleptic Gregorian date of `days` since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// The exact bytes a vote's signature covers, built and parsed without chrono so other
+/// languages can reproduce them from this description:
+///
+/// ```text
+/// payload   = voter ":" proposal ":" timestamp [choice] [":v" version] [":n" nonce]
+///             [":m" metadata] [":s" stake] [":e" expiry] [":d" decay] [coverage]
+/// timestamp = YYYY "-" MM "-" DD " " hh ":" mm ":" ss [fraction] " UTC"
+/// expiry    = YYYY "-" MM "-" DD "T" hh ":" mm ":" ss [fraction] "+00:00"
+/// fraction  = "." 3, 6 or 9 digits, the fewest that hold the nanoseconds exactly
+/// choice    = ":reject" | ":abstain"      ; omitted for approve
+/// version   = decimal, omitted when 0      nonce = decimal, omitted when 0
+/// metadata  = compact JSON object with keys sorted, omitted when empty
+/// stake     = lowercase hex of the 64-byte stake attestation signature
+/// decay     = compact JSON object of "floor", "rate" and "steps", in that order,
+///             each omitted when unset
+/// coverage  = ":p" payload-version ":w" weight ":" model   ; omitted for version 1
+/// model     = "linear" | "exponential" | "stepped"
+/// ```
+///
+/// Years outside 0000-9999 are written with a sign and at least four digits. Metadata
+/// JSON escapes only `"`, `\` and control characters; other characters are raw UTF-8.
+/// Decay numbers are written in their shortest round-trip form, always with a fraction
+/// or exponent (`60.0`, `0.002`, `1e-5`), and so is the weight.
+///
+/// Version 1 payloads leave `original_weight` and `decay_model` unsigned; they are
+/// still accepted so votes signed before version 2 keep verifying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VotePayload {
+    pub voter_id: String,
+    pub proposal_id: String,
+    pub timestamp: PayloadTime,
+    pub choice: VoteChoice,
+    pub version: u64,
+    pub nonce: u64,
+    pub metadata: BTreeMap<String, String>,
+    pub stake_signature: Option<[u8; 64]>,
+    pub expires_at: Option<PayloadTime>,
+    pub decay_params: Option<DecayParams>,
+    pub payload_version: u32,
+    /// Covered from payload version 2 on
+    pub original_weight: f64,
+    /// Covered from payload version 2 on
+    pub decay_model: DecayType,
+}
+
+impl From<&SignedVote> for VotePayload {
+    fn from(vote: &SignedVote) -> Self {
+        let payload = Self {
+            choice: vote.choice,
+            version: vote.version,
+            nonce: vote.nonce,
+            metadata: vote.metadata.clone(),
+            stake_signature: vote.stake.as_ref().map(|s| s.signature.to_bytes()),
+            expires_at: vote.expires_at.map(PayloadTime::from),
+            decay_params: vote.decay_params.clone(),
+            ..Self::new(vote.voter_id.as_str(), vote.proposal_id.as_str(), vote.timestamp.into())
+        };
+        if is_legacy_payload(&vote.payload_version) {
+            payload
+        } else {
+            payload.covering(vote.original_weight, vote.decay_model)
+        }
+    }
+}
+
+impl VotePayload {
+    /// A plain version 1 approval with no optional fields
+    pub fn new(voter_id: &str, proposal_id: &str, timestamp: PayloadTime) -> Self {
+        Self {
+            voter_id: voter_id.to_string(),
+            proposal_id: proposal_id.to_string(),
+            timestamp,
+            choice: VoteChoice::Approve,
+            version: 0,
+            nonce: 0,
+            metadata: BTreeMap::new(),
+            stake_signature: None,
+            expires_at: None,
+            decay_params: None,
+            payload_version: LEGACY_PAYLOAD_VERSION,
+            original_weight: 1.0,
+            decay_model: DecayType::Linear,
+        }
+    }
+
+    /// This payload at the current version, covering `original_weight` and `decay_model`
+    pub fn covering(self, original_weight: f64, decay_model: DecayType) -> Self {
+        Self { payload_version: PAYLOAD_VERSION, original_weight, decay_model, ..self }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_string().into_bytes()
+    }
+
+    /// Parse canonical payload bytes; anything `to_bytes` would not have produced is rejected
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PayloadError> {
+        let text = std::str::from_utf8(bytes).map_err(|_| PayloadError::NotUtf8)?;
+        let (voter_id, rest) = text.split_once(':').ok_or(PayloadError::Missing("proposal id"))?;
+        let (proposal_id, rest) = rest.split_once(':').ok_or(PayloadError::Missing("timestamp"))?;
+        let (timestamp, mut rest) = PayloadTime::parse(rest, ' ', " UTC")?;
+        let mut payload = Self::new(voter_id, proposal_id, timestamp);
+
+        if let Some(r) = rest.strip_prefix(":reject") {
+            (payload.choice, rest) = (VoteChoice::Reject, r);
+        } else if let Some(r) = rest.strip_prefix(":abstain") {
+            (payload.choice, rest) = (VoteChoice::Abstain, r);
+        }
+        if let Some(r) = rest.strip_prefix(":v") {
+            (payload.version, rest) = number(r, "version")?;
+        }
+        if let Some(r) = rest.strip_prefix(":n") {
+            (payload.nonce, rest) = number(r, "nonce")?;
+        }
+        if let Some(r) = rest.strip_prefix(":m") {
+            let mut stream = serde_json::Deserializer::from_str(r).into_iter::<BTreeMap<String, String>>();
+            payload.metadata = stream
+                .next()
+                .and_then(Result::ok)
+                .ok_or(PayloadError::Malformed("metadata"))?;
+            rest = &r[stream.byte_offset()..];
+        }
+        if let Some(r) = rest.strip_prefix(":s") {
+            let hex = r.get(..128).ok_or(PayloadError::Malformed("stake signature"))?;
+            let bytes = hex::decode(hex).map_err(|_| PayloadError::Malformed("stake signature"))?;
+            payload.stake_signature = Some(bytes.try_into().expect("128 hex digits are 64 bytes"));
+            rest = &r[128..];
+        }
+        if let Some(r) = rest.strip_prefix(":e") {
+            let (expires_at, r) = PayloadTime::parse(r, 'T', "+00:00")?;
+            (payload.expires_at, rest) = (Some(expires_at), r);
+        }
+        if let Some(r) = rest.strip_prefix(":d") {
+            let mut stream = serde_json::Deserializer::from_str(r).into_iter::<DecayParams>();
+            payload.decay_params = Some(stream.next().and_then(Result::ok).ok_or(PayloadError::Malformed("decay"))?);
+            rest = &r[stream.byte_offset()..];
+        }
+        if let Some(r) = rest.strip_prefix(":p") {
+            let (version, r) = number(r, "payload version")?;
+            if version != u64::from(PAYLOAD_VERSION) {
+                return Err(PayloadError::Malformed("payload version"));
+            }
+            let r = r.strip_prefix(":w").ok_or(PayloadError::Missing("weight"))?;
+            let mut stream = serde_json::Deserializer::from_str(r).into_iter::<f64>();
+            payload.original_weight = stream.next().and_then(Result::ok).ok_or(PayloadError::Malformed("weight"))?;
+            let r = r[stream.byte_offset()..].strip_prefix(':').ok_or(PayloadError::Missing("decay model"))?;
+            let end = r.find(|c: char| !c.is_ascii_lowercase()).unwrap_or(r.len());
+            let decay_model = decay_from_name(&r[..end]).ok_or(PayloadError::Malformed("decay model"))?;
+            let r = &r[end..];
+            (payload.payload_version, payload.decay_model, rest) = (PAYLOAD_VERSION, decay_model, r);
+        }
+        if !rest.is_empty() {
+            return Err(PayloadError::Trailing(rest.to_string()));
+        }
+        if payload.to_bytes() != bytes {
+            return Err(PayloadError::NonCanonical);
+        }
+        Ok(payload)
+    }
+}
+
+fn number<'a>(text: &'a str, field: &'static str) -> Result<(u64, &'a str), PayloadError> {
+    let end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let value = text[..end].parse().map_err(|_| PayloadError::Malformed(field))?;
+    Ok((value, &text[end..]))
+}
+
+/// The canonical payload text; `to_bytes` is its UTF-8 encoding
+impl fmt::Display for VotePayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!("{}:{}:", self.voter_id, self.proposal_id);
+        self.timestamp.write_utc(&mut out);
+        match self.choice {
+            VoteChoice::Approve => {}
+            VoteChoice::Reject => out.push_str(":reject"),
+            VoteChoice::Abstain => out.push_str(":abstain"),
+        }
+        if self.version > 0 {
+            out.push_str(&format!(":v{}", self.version));
+        }
+        if self.nonce > 0 {
+            out.push_str(&format!(":n{}", self.nonce));
+        }
+        if !self.metadata.is_empty() {
+            out.push_str(&format!(":m{}", serde_json::to_string(&self.metadata).expect("metadata serializes")));
+        }
+        if let Some(stake) = &self.stake_signature {
+            out.push_str(&format!(":s{}", hex::encode(stake)));
+        }
+        if let Some(expires_at) = &self.expires_at {
+            out.push_str(":e");
+            expires_at.write_rfc3339(&mut out);
+        }
+        if let Some(decay) = &self.decay_params {
+            out.push_str(&format!(":d{}", serde_json::to_string(decay).expect("decay parameters serialize")));
+        }
+        if self.payload_version > LEGACY_PAYLOAD_VERSION {
+            let weight = serde_json::to_string(&self.original_weight).expect("weights serialize");
+            out.push_str(&format!(":p{}:w{}:{}", self.payload_version, weight, decay_name(self.decay_model)));
+        }
+        f.write_str(&out)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stake::StakeAttestation;
+    use crate::verify::{VerificationError, VerificationPolicy};
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_payload_matches_signed_message() {
+        let key = SignedVote::generate_keypair();
+        let epoch = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let times = [
+            epoch,
+            epoch + Duration::milliseconds(250),
+            epoch + Duration::microseconds(1_500),
+            epoch + Duration::nanoseconds(7),
+            Utc.with_ymd_and_hms(1969, 12, 31, 23, 59, 59).unwrap() + Duration::milliseconds(500),
+            Utc.with_ymd_and_hms(2000, 2, 29, 12, 0, 0).unwrap(),
+        ];
+        for (i, at) in times.into_iter().enumerate() {
+            let plain = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, at, DecayType::Linear, &key);
+            let full = plain
+                .clone()
+                .amend(1.0, at, VoteChoice::Abstain, &key)
+                .with_nonce(i as u64 + 1, &key)
+                .with_metadata(BTreeMap::from([("note".into(), "a \"quoted\": ü\n".into())]), &key)
+                .with_stake(StakeAttestation::new("alice".parse().unwrap(), 5.0, at, &key), &key)
+                .with_expiry(at + Duration::microseconds(90_000_001), &key);
+
+            for vote in [plain, full] {
+                let payload = VotePayload::from(&vote);
+                assert_eq!(payload.to_bytes(), vote.message().into_bytes());
+                assert_eq!(VotePayload::from_bytes(&payload.to_bytes()), Ok(payload.clone()));
+                assert_eq!(payload.timestamp.to_datetime(), Some(vote.timestamp));
+            }
+        }
+    }
+
+    #[test]
+    fn test_non_canonical_payloads_rejected() {
+        let parse = |text: &str| VotePayload::from_bytes(text.as_bytes());
+        assert!(parse("alice:p1:2025-01-01 00:00:00 UTC").is_ok());
+        assert_eq!(parse("alice:p1:2025-01-01 00:00:00.500000 UTC"), Err(PayloadError::NonCanonical));
+        assert_eq!(parse("alice:p1:2025-01-01 00:00:00 UTC:v0"), Err(PayloadError::NonCanonical));
+        assert_eq!(parse("alice:p1:2025-01-01 00:00:00 UTC:m{}"), Err(PayloadError::NonCanonical));
+        assert_eq!(parse("alice:p1:2025-13-01 00:00:00 UTC"), Err(PayloadError::Malformed("timestamp")));
+        assert_eq!(parse("alice:p1:2025-01-01 00:00:00 UTC:x"), Err(PayloadError::Trailing(":x".into())));
+        assert_eq!(parse("alice"), Err(PayloadError::Missing("proposal id")));
+        assert!(parse("alice:p1:2025-01-01 00:00:00 UTC:p2:w1.5:stepped").is_ok());
+        assert_eq!(parse("alice:p1:2025-01-01 00:00:00 UTC:p1:w1.5:stepped"), Err(PayloadError::Malformed("payload version")));
+        assert_eq!(parse("alice:p1:2025-01-01 00:00:00 UTC:p2:w1.50:stepped"), Err(PayloadError::NonCanonical));
+        assert_eq!(parse("alice:p1:2025-01-01 00:00:00 UTC:p2:w1.5:cubic"), Err(PayloadError::Malformed("decay model")));
+    }
+
+    #[test]
+    fn test_weight_and_decay_model_are_signed() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key);
+        assert_eq!(vote.payload_version, PAYLOAD_VERSION);
+        assert!(vote.message().ends_with(":p2:w1.0:linear"));
+
+        let heavier = SignedVote { original_weight: 100.0, ..vote.clone() };
+        let slower = SignedVote { decay_model: DecayType::Stepped, ..vote.clone() };
+        assert_eq!(heavier.verify(60), Err(VerificationError::InvalidSignature));
+        assert_eq!(slower.verify(60), Err(VerificationError::InvalidSignature));
+
+        // A vote signed under version 1 still verifies, and its JSON carries no version
+        let message = crate::vote::vote_message("alice", "p1", now, VoteChoice::Approve, 0, 0);
+        let legacy = SignedVote {
+            payload_version: LEGACY_PAYLOAD_VERSION,
+            signature: ed25519_dalek::Signer::sign(&key, message.as_bytes()),
+            ..vote
+        };
+        assert_eq!(legacy.verify(60), Ok(()));
+        assert!(!legacy.to_json().unwrap().contains("payload_version"));
+        assert_eq!(SignedVote::from_json(&legacy.to_json().unwrap()).unwrap().payload_version, LEGACY_PAYLOAD_VERSION);
+        // Re-signing upgrades it
+        assert_eq!(legacy.with_nonce(3, &key).payload_version, PAYLOAD_VERSION);
+    }
+
+    #[test]
+    fn test_policy_can_refuse_legacy_payloads() {
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key);
+        let message = crate::vote::vote_message("alice", "p1", now, VoteChoice::Approve, 0, 0);
+        let legacy = SignedVote {
+            payload_version: LEGACY_PAYLOAD_VERSION,
+            signature: ed25519_dalek::Signer::sign(&key, message.as_bytes()),
+            ..vote.clone()
+        };
+        // Version 1 does not sign the weight, so a relayed vote can be inflated unnoticed
+        let inflated = SignedVote { original_weight: 100.0, ..legacy };
+        let lenient = VerificationPolicy::default();
+        assert_eq!(inflated.verify_with_policy(&lenient, now), Ok(()));
+
+        let strict = VerificationPolicy::default().with_min_payload_version(PAYLOAD_VERSION);
+        assert_eq!(
+            inflated.verify_with_policy(&strict, now),
+            Err(VerificationError::PayloadVersionTooOld { version: LEGACY_PAYLOAD_VERSION, min: PAYLOAD_VERSION })
+        );
+        assert_eq!(vote.verify_with_policy(&strict, now), Ok(()));
+    }
+}
@@ -0,0 +1,323 @@
+// src/plugins.rs
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::decay::{DecayModel, ExponentialDecay, LinearDecay, SteppedDecay};
+use crate::guardrails::{GuardrailError, Guardrails};
+use crate::ids::VoterId;
+use crate::schema::{self, SchemaError};
+use crate::threshold::EscalationPattern;
+use crate::trust::{TrustEngine, TrustSource};
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("A {kind} plugin named {name} is already registered")]
+    DuplicateName { kind: &'static str, name: String },
+    #[error("No {kind} plugin named {name}")]
+    UnknownName { kind: &'static str, name: String },
+    #[error("Invalid parameters for {name}: {reason}")]
+    InvalidParams { name: String, reason: String },
+    #[error("Failed to read plugin config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid plugin config: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("Invalid plugin config: {0}")]
+    Schema(#[from] SchemaError),
+    #[error(transparent)]
+    Guardrail(#[from] GuardrailError),
+}
+
+pub type DecayFactory = Box<dyn Fn(&Value) -> Result<Box<dyn DecayModel + Send + Sync>, PluginError> + Send + Sync>;
+pub type EscalationFactory = Box<dyn Fn(&Value) -> Result<EscalationPattern, PluginError> + Send + Sync>;
+pub type TrustFactory = Box<dyn Fn(&Value) -> Result<Box<dyn TrustSource>, PluginError> + Send + Sync>;
+
+/// A plugin referenced by name from a configuration file, with its parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PluginRef {
+    pub name: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PluginConfig {
+    pub decay: PluginRef,
+    pub escalation: PluginRef,
+    pub trust: PluginRef,
+    /// Voting window length; the window type decides when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_secs: Option<u64>,
+    /// Starting threshold; the proposal type decides when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ceiling: Option<f64>,
+    /// Votes a proposal needs before it can pass; the proposal type decides when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_vote_count: Option<usize>,
+}
+
+impl PluginConfig {
+    /// Load a config, rejecting it if it breaks the default `Guardrails`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PluginError> {
+        Self::from_file_within(path, &Guardrails::default())
+    }
+
+    pub fn from_file_within(path: impl AsRef<Path>, guardrails: &Guardrails) -> Result<Self, PluginError> {
+        let config: Self = schema::parse(&std::fs::read_to_string(path)?)?;
+        guardrails.check(&config)?;
+        Ok(config)
+    }
+}
+
+/// Implementations built from a `PluginConfig`
+pub struct ResolvedPlugins {
+    pub decay: Box<dyn DecayModel + Send + Sync>,
+    pub escalation: EscalationPattern,
+    pub trust: Box<dyn TrustSource>,
+}
+
+/// Named factories for decay models, escalation curves, and trust sources. External
+/// crates register their implementations at startup; configs refer to them by name.
+#[derive(Default)]
+pub struct PluginRegistry {
+    decay: HashMap<String, DecayFactory>,
+    escalation: HashMap<String, EscalationFactory>,
+    trust: HashMap<String, TrustFactory>,
+}
+
+fn param(name: &str, params: &Value, key: &str) -> Result<f64, PluginError> {
+    params.get(key).and_then(Value::as_f64).ok_or_else(|| PluginError::InvalidParams {
+        name: name.to_string(),
+        reason: format!("missing number `{}`", key),
+    })
+}
+
+fn typed<T: for<'de> Deserialize<'de>>(name: &str, params: &Value) -> Result<T, PluginError> {
+    serde_json::from_value(params.clone()).map_err(|e| PluginError::InvalidParams {
+        name: name.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Fixed per-validator multipliers, for the built-in `table` trust source
+struct TrustTable(HashMap<String, f64>);
+
+impl TrustSource for TrustTable {
+    fn get_bonus(&self, validator_id: &VoterId) -> f64 {
+        self.0.get(validator_id.as_str()).copied().unwrap_or(1.0)
+    }
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry preloaded with this crate's own implementations
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        let builtin = "built-in plugin names are unique";
+
+        registry
+            .register_decay("linear", |p| Ok(Box::new(LinearDecay { rate: param("linear", p, "rate")? })))
+            .expect(builtin);
+        registry
+            .register_decay("exponential", |p| {
+                Ok(Box::new(ExponentialDecay { rate: param("exponential", p, "rate")? }))
+            })
+            .expect(builtin);
+        registry
+            .register_decay("stepped", |p| {
+                Ok(Box::new(SteppedDecay { decay_steps: typed("stepped", &p["steps"])? }))
+            })
+            .expect(builtin);
+
+        registry
+            .register_escalation("linear", |p| Ok(EscalationPattern::Linear(param("linear", p, "rate")?)))
+            .expect(builtin);
+        registry
+            .register_escalation("exponential", |p| {
+                Ok(EscalationPattern::Exponential(param("exponential", p, "factor")?))
+            })
+            .expect(builtin);
+        registry
+            .register_escalation("sigmoid", |p| {
+                Ok(EscalationPattern::Sigmoid(param("sigmoid", p, "k")?, param("sigmoid", p, "midpoint")?))
+            })
+            .expect(builtin);
+
+        registry
+            .register_trust("default", |_| Ok(Box::new(TrustEngine::new())))
+            .expect(builtin);
+        registry
+            .register_trust("table", |p| Ok(Box::new(TrustTable(typed("table", p)?))))
+            .expect(builtin);
+        registry
+    }
+
+    pub fn register_decay(
+        &mut self,
+        name: &str,
+        factory: impl Fn(&Value) -> Result<Box<dyn DecayModel + Send + Sync>, PluginError> + Send + Sync + 'static,
+    ) -> Result<(), PluginError> {
+        insert(&mut self.decay, "decay", name, Box::new(factory))
+    }
+
+    pub fn register_escalation(
+        &mut self,
+        name: &str,
+        factory: impl Fn(&Value) -> Result<EscalationPattern, PluginError> + Send + Sync + 'static,
+    ) -> Result<(), PluginError> {
+        insert(&mut self.escalation, "escalation", name, Box::new(factory))
+    }
+
+    pub fn register_trust(
+        &mut self,
+        name: &str,
+        factory: impl Fn(&Value) -> Result<Box<dyn TrustSource>, PluginError> + Send + Sync + 'static,
+    ) -> Result<(), PluginError> {
+        insert(&mut self.trust, "trust", name, Box::new(factory))
+    }
+
+    pub fn decay(&self, plugin: &PluginRef) -> Result<Box<dyn DecayModel + Send + Sync>, PluginError> {
+        lookup(&self.decay, "decay", &plugin.name)?(&plugin.params)
+    }
+
+    pub fn escalation(&self, plugin: &PluginRef) -> Result<EscalationPattern, PluginError> {
+        lookup(&self.escalation, "escalation", &plugin.name)?(&plugin.params)
+    }
+
+    pub fn trust(&self, plugin: &PluginRef) -> Result<Box<dyn TrustSource>, PluginError> {
+        lookup(&self.trust, "trust", &plugin.name)?(&plugin.params)
+    }
+
+    pub fn resolve(&self, config: &PluginConfig) -> Result<ResolvedPlugins, PluginError> {
+        Ok(ResolvedPlugins {
+            decay: self.decay(&config.decay)?,
+            escalation: self.escalation(&config.escalation)?,
+            trust: self.trust(&config.trust)?,
+        })
+    }
+
+    /// Registered names of each kind, sorted: (decay, escalation, trust)
+    pub fn names(&self) -> (Vec<&str>, Vec<&str>, Vec<&str>) {
+        (sorted_keys(&self.decay), sorted_keys(&self.escalation), sorted_keys(&self.trust))
+    }
+}
+
+fn insert<F>(map: &mut HashMap<String, F>, kind: &'static str, name: &str, factory: F) -> Result<(), PluginError> {
+    if map.contains_key(name) {
+        return Err(PluginError::DuplicateName { kind, name: name.to_string() });
+    }
+    map.insert(name.to_string(), factory);
+    Ok(())
+}
+
+fn lookup<'a, F>(map: &'a HashMap<String, F>, kind: &'static str, name: &str) -> Result<&'a F, PluginError> {
+    map.get(name).ok_or_else(|| PluginError::UnknownName { kind, name: name.to_string() })
+}
+
+fn sorted_keys<F>(map: &HashMap<String, F>) -> Vec<&str> {
+    let mut names: Vec<&str> = map.keys().map(String::as_str).collect();
+    names.sort();
+    names
+}
+
+/// Wrap a curve so it can be registered as an escalation plugin
+pub fn custom_escalation(curve: impl crate::threshold::EscalationCurve + 'static) -> EscalationPattern {
+    EscalationPattern::Custom(Arc::new(curve))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::threshold::EscalationCurve;
+
+    #[derive(Debug)]
+    struct Step {
+        at: u64,
+    }
+
+    impl EscalationCurve for Step {
+        fn threshold(&self, base_threshold: f64, ceiling: f64, elapsed_secs: u64) -> f64 {
+            if elapsed_secs >= self.at { ceiling } else { base_threshold }
+        }
+    }
+
+    struct HalfDecay;
+
+    impl DecayModel for HalfDecay {
+        fn compute_weight(&self, original_weight: f64, _elapsed_time: f64) -> f64 {
+            original_weight / 2.0
+        }
+    }
+
+    fn config(json: &str) -> PluginConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_builtins_resolve_from_config() {
+        let registry = PluginRegistry::with_builtins();
+        let plugins = registry
+            .resolve(&config(
+                r#"{
+                    "decay": {"name": "stepped", "params": {"steps": [[60.0, 0.5]]}},
+                    "escalation": {"name": "linear", "params": {"rate": 0.01}},
+                    "trust": {"name": "table", "params": {"alice": 1.5}}
+                }"#,
+            ))
+            .unwrap();
+
+        assert_eq!(plugins.decay.compute_weight(1.0, 90.0), 0.5);
+        assert!((plugins.escalation.threshold(0.5, 0.9, 10) - 0.6).abs() < 1e-9);
+        assert_eq!(plugins.trust.get_bonus(&"alice".parse().unwrap()), 1.5);
+        assert_eq!(plugins.trust.get_bonus(&"bob".parse().unwrap()), 1.0);
+    }
+
+    #[test]
+    fn test_external_registration() {
+        let mut registry = PluginRegistry::with_builtins();
+        registry.register_decay("half", |_| Ok(Box::new(HalfDecay))).unwrap();
+        registry
+            .register_escalation("step", |p| Ok(custom_escalation(Step { at: p["at"].as_u64().unwrap_or(60) })))
+            .unwrap();
+        assert!(matches!(
+            registry.register_decay("linear", |_| Ok(Box::new(HalfDecay))),
+            Err(PluginError::DuplicateName { .. })
+        ));
+
+        let plugins = registry
+            .resolve(&config(
+                r#"{"decay": {"name": "half"}, "escalation": {"name": "step", "params": {"at": 30}}, "trust": {"name": "default"}}"#,
+            ))
+            .unwrap();
+        assert_eq!(plugins.decay.compute_weight(4.0, 0.0), 2.0);
+        assert_eq!(plugins.escalation.threshold(0.5, 0.9, 29), 0.5);
+        assert_eq!(plugins.escalation.threshold(0.5, 0.9, 30), 0.9);
+        assert_eq!(plugins.trust.get_bonus(&"validator_001".parse().unwrap()), 1.2);
+        assert!(registry.names().0.contains(&"half"));
+    }
+
+    #[test]
+    fn test_unknown_names_and_bad_params() {
+        let registry = PluginRegistry::with_builtins();
+        assert!(matches!(
+            registry.decay(&PluginRef { name: "cubic".to_string(), params: Value::Null }),
+            Err(PluginError::UnknownName { kind: "decay", .. })
+        ));
+        assert!(matches!(
+            registry.escalation(&PluginRef { name: "sigmoid".to_string(), params: serde_json::json!({"k": 0.1}) }),
+            Err(PluginError::InvalidParams { .. })
+        ));
+    }
+}
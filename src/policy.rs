@@ -0,0 +1,183 @@
+// src/policy.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::threshold::{DecisionRule, EscalationPattern, ProgressionProfile, ThresholdEscalator};
+use crate::vote::{DecayType, ProposalType};
+use crate::window::{VotingWindow, WindowType};
+
+/// Defaults a proposal type is decided under: its threshold curve, how long it stays
+/// open, and how many votes it needs
+#[derive(Debug, Clone)]
+pub struct ProposalPolicy {
+    pub base_threshold: f64,
+    pub ceiling: f64,
+    pub pattern: EscalationPattern,
+    pub profile: ProgressionProfile,
+    pub min_vote_count: usize,
+    pub window: WindowType,
+    /// Decay models votes may use; empty allows any
+    pub decay_models: Vec<DecayType>,
+}
+
+impl ProposalPolicy {
+    /// Built-in policy for `proposal_type`
+    pub fn for_type(proposal_type: &ProposalType) -> Self {
+        match proposal_type {
+            ProposalType::Normal => Self {
+                base_threshold: 0.51,
+                ceiling: 0.9,
+                pattern: EscalationPattern::Linear(0.01),
+                profile: ProgressionProfile::Conservative,
+                min_vote_count: 3,
+                window: WindowType::Medium,
+                decay_models: Vec::new(),
+            },
+            ProposalType::Critical => Self {
+                base_threshold: 0.75,
+                ceiling: 0.95,
+                pattern: EscalationPattern::Linear(0.02),
+                profile: ProgressionProfile::Aggressive,
+                min_vote_count: 5,
+                window: WindowType::Long,
+                decay_models: Vec::new(),
+            },
+            // Changes to the rules themselves: a supermajority, slow escalation, a week to vote
+            ProposalType::Constitutional => Self {
+                base_threshold: 0.67,
+                ceiling: 0.95,
+                pattern: EscalationPattern::Linear(0.001),
+                profile: ProgressionProfile::Conservative,
+                min_vote_count: 10,
+                window: WindowType::Custom(7 * 86_400),
+                // Exponential decay would wipe out early votes long before a week-long vote closes
+                decay_models: vec![DecayType::Linear, DecayType::Stepped],
+            },
+            ProposalType::Budget => Self {
+                base_threshold: 0.6,
+                ceiling: 0.9,
+                pattern: EscalationPattern::Linear(0.005),
+                profile: ProgressionProfile::Conservative,
+                min_vote_count: 5,
+                window: WindowType::Custom(86_400),
+                decay_models: Vec::new(),
+            },
+            // Must decide within minutes, so the bar rises quickly but stays reachable
+            ProposalType::Emergency => Self {
+                base_threshold: 0.67,
+                ceiling: 0.8,
+                pattern: EscalationPattern::Linear(0.05),
+                profile: ProgressionProfile::Aggressive,
+                min_vote_count: 3,
+                window: WindowType::Short,
+                decay_models: Vec::new(),
+            },
+        }
+    }
+
+    pub fn escalator(&self) -> ThresholdEscalator {
+        ThresholdEscalator {
+            base_threshold: self.base_threshold,
+            ceiling: self.ceiling,
+            pattern: self.pattern.clone(),
+            emergency_override: false,
+            profile: self.profile.clone(),
+            total_votes: 0,
+            min_vote_count: self.min_vote_count,
+            decision: DecisionRule::default(),
+        }
+    }
+
+    pub fn voting_window(&self, start_time: DateTime<Utc>, grace_secs: u64) -> VotingWindow {
+        VotingWindow::new(start_time, self.window, grace_secs)
+    }
+
+    pub fn allows_decay(&self, decay_model: DecayType) -> bool {
+        self.decay_models.is_empty() || self.decay_models.contains(&decay_model)
+    }
+}
+
+/// Policy per proposal type; types without an override use `ProposalPolicy::for_type`
+#[derive(Debug, Clone, Default)]
+pub struct PolicyTable {
+    overrides: BTreeMap<ProposalType, ProposalPolicy>,
+}
+
+impl PolicyTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the policy for `proposal_type`, returning the one it displaced
+    pub fn set(&mut self, proposal_type: ProposalType, policy: ProposalPolicy) -> ProposalPolicy {
+        let previous = self.get(&proposal_type);
+        self.overrides.insert(proposal_type, policy);
+        previous
+    }
+
+    pub fn get(&self, proposal_type: &ProposalType) -> ProposalPolicy {
+        self.overrides
+            .get(proposal_type)
+            .cloned()
+            .unwrap_or_else(|| ProposalPolicy::for_type(proposal_type))
+    }
+
+    pub fn escalator(&self, proposal_type: &ProposalType) -> ThresholdEscalator {
+        self.get(proposal_type).escalator()
+    }
+
+    pub fn voting_window(&self, proposal_type: &ProposalType, start_time: DateTime<Utc>, grace_secs: u64) -> VotingWindow {
+        self.get(proposal_type).voting_window(start_time, grace_secs)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_type_has_its_own_policy() {
+        let table = PolicyTable::new();
+        let now = Utc::now();
+        let rows: Vec<(ProposalType, f64, usize, u64)> = ProposalType::ALL
+            .iter()
+            .map(|t| {
+                let escalator = table.escalator(t);
+                let window = table.voting_window(t, now, 0);
+                (t.clone(), escalator.base_threshold, escalator.min_vote_count, window.duration_secs)
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (ProposalType::Normal, 0.51, 3, 1800),
+                (ProposalType::Critical, 0.75, 5, 7200),
+                (ProposalType::Constitutional, 0.67, 10, 604_800),
+                (ProposalType::Budget, 0.6, 5, 86_400),
+                (ProposalType::Emergency, 0.67, 3, 300),
+            ]
+        );
+        for t in ProposalType::ALL {
+            let escalator = table.escalator(&t);
+            assert!(escalator.base_threshold < escalator.ceiling);
+            assert_eq!(escalator.current_threshold(u64::MAX), escalator.ceiling);
+        }
+    }
+
+    #[test]
+    fn test_overrides_replace_defaults() {
+        let mut table = PolicyTable::new();
+        let stricter = ProposalPolicy { min_vote_count: 20, ..ProposalPolicy::for_type(&ProposalType::Budget) };
+        let previous = table.set(ProposalType::Budget, stricter);
+        assert_eq!(previous.min_vote_count, 5);
+        assert_eq!(table.escalator(&ProposalType::Budget).min_vote_count, 20);
+        assert_eq!(table.escalator(&ProposalType::Normal).min_vote_count, 3);
+        assert_eq!(
+            ThresholdEscalator::for_proposal_type(ProposalType::Emergency).ceiling,
+            ProposalPolicy::for_type(&ProposalType::Emergency).ceiling
+        );
+    }
+}
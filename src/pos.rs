@@ -0,0 +1,304 @@
+// src/pos.rs
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use thiserror::Error;
+
+use crate::blockchain::Block;
+use crate::hashing;
+use crate::trust::TrustSource;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum StakeError {
+    #[error("Producer set is empty or has no positive weight")]
+    NoProducers,
+    #[error("Unknown producer: {0}")]
+    UnknownProducer(String),
+    #[error("Producer {producer} is not eligible for slot {slot} (expected {expected})")]
+    NotEligible { slot: u64, producer: String, expected: String },
+    #[error("Signing key does not belong to producer {0}")]
+    KeyMismatch(String),
+    #[error("Slot {slot} does not follow slot {previous}")]
+    SlotNotIncreasing { slot: u64, previous: u64 },
+    #[error("Invalid hash for block {0}")]
+    InvalidHash(u64),
+    #[error("Invalid previous hash for block {0}")]
+    BrokenLink(u64),
+    #[error("Invalid producer signature on block {0}")]
+    InvalidSignature(u64),
+}
+
+#[derive(Debug, Clone)]
+pub struct Producer {
+    pub id: String,
+    pub public_key: VerifyingKey,
+    /// Trust-adjusted weight used for slot selection
+    pub weight: f64,
+}
+
+/// Validators eligible to produce blocks, ordered by id so selection is deterministic
+#[derive(Debug, Clone, Default)]
+pub struct ProducerSet {
+    producers: Vec<Producer>,
+}
+
+impl ProducerSet {
+    /// Build the set from base weights, applying each validator's trust bonus
+    pub fn from_weights(entries: Vec<(String, VerifyingKey, f64)>, trust: &dyn TrustSource) -> Self {
+        let mut producers: Vec<Producer> = entries
+            .into_iter()
+            .map(|(id, public_key, weight)| Producer {
+                // Ids that cannot name a voter earn no bonus
+                weight: weight.max(0.0) * id.parse().map_or(1.0, |voter| trust.get_bonus(&voter)),
+                id,
+                public_key,
+            })
+            .collect();
+        producers.sort_by(|a, b| a.id.cmp(&b.id));
+        Self { producers }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Producer> {
+        self.producers.iter().find(|p| p.id == id)
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.producers.iter().map(|p| p.weight).sum()
+    }
+
+    /// Pick the producer for `slot`, with probability proportional to weight.
+    /// The draw is seeded by the previous block hash, so every node agrees on it.
+    pub fn eligible_producer(&self, previous_hash: &str, slot: u64) -> Result<&Producer, StakeError> {
+        let total = self.total_weight();
+        if total <= 0.0 {
+            return Err(StakeError::NoProducers);
+        }
+
+        let seed = hashing::slot_seed(previous_hash, slot);
+        let draw = u64::from_be_bytes(seed[..8].try_into().expect("digest has 8 bytes"));
+        let mut point = (draw as f64 / u64::MAX as f64) * total;
+
+        for producer in self.producers.iter().filter(|p| p.weight > 0.0) {
+            if point < producer.weight {
+                return Ok(producer);
+            }
+            point -= producer.weight;
+        }
+        // Float rounding can leave the point just past the last bucket
+        self.producers
+            .iter()
+            .rev()
+            .find(|p| p.weight > 0.0)
+            .ok_or(StakeError::NoProducers)
+    }
+}
+
+/// Block sealed by its slot producer's signature instead of proof of work
+#[derive(Debug, Clone)]
+pub struct StakeBlock {
+    pub block: Block,
+    pub slot: u64,
+    pub producer_id: String,
+    pub signature: Signature,
+}
+
+impl StakeBlock {
+    fn message(hash: &str, slot: u64) -> String {
+        format!("stake-block:{}:{}", hash, slot)
+    }
+}
+
+/// Chain whose blocks are produced by weighted slot leaders
+pub struct StakeChain {
+    pub genesis: Block,
+    pub blocks: Vec<StakeBlock>,
+    pub producers: ProducerSet,
+}
+
+impl StakeChain {
+    pub fn new(genesis_data: String, producers: ProducerSet) -> Self {
+        let mut genesis = Block::unmined(0, String::new(), genesis_data);
+        genesis.hash = genesis.calculate_hash();
+        Self {
+            genesis,
+            blocks: Vec::new(),
+            producers,
+        }
+    }
+
+    fn tip(&self) -> (&Block, u64) {
+        match self.blocks.last() {
+            Some(b) => (&b.block, b.slot),
+            None => (&self.genesis, 0),
+        }
+    }
+
+    /// Who may produce the next block at `slot`
+    pub fn eligible_producer(&self, slot: u64) -> Result<&Producer, StakeError> {
+        self.producers.eligible_producer(&self.tip().0.hash, slot)
+    }
+
+    /// Sign and append a block for `slot` if `producer_id` is its leader
+    pub fn produce(
+        &mut self,
+        slot: u64,
+        data: String,
+        producer_id: &str,
+        signing_key: &SigningKey,
+    ) -> Result<(), StakeError> {
+        let (tip, tip_slot) = self.tip();
+        if slot <= tip_slot {
+            return Err(StakeError::SlotNotIncreasing { slot, previous: tip_slot });
+        }
+
+        let producer = self
+            .producers
+            .get(producer_id)
+            .ok_or_else(|| StakeError::UnknownProducer(producer_id.to_string()))?;
+        if producer.public_key != signing_key.verifying_key() {
+            return Err(StakeError::KeyMismatch(producer_id.to_string()));
+        }
+        let expected = self.producers.eligible_producer(&tip.hash, slot)?;
+        if expected.id != producer_id {
+            return Err(StakeError::NotEligible {
+                slot,
+                producer: producer_id.to_string(),
+                expected: expected.id.clone(),
+            });
+        }
+
+        let mut block = Block::unmined(tip.id + 1, tip.hash.clone(), data);
+        block.hash = block.calculate_hash();
+        let signature = signing_key.sign(StakeBlock::message(&block.hash, slot).as_bytes());
+        self.blocks.push(StakeBlock {
+            block,
+            slot,
+            producer_id: producer_id.to_string(),
+            signature,
+        });
+        Ok(())
+    }
+
+    /// Check hashes, links, slot order, leader eligibility, and producer signatures
+    pub fn validate(&self) -> Result<(), StakeError> {
+        let mut previous = &self.genesis;
+        let mut previous_slot = 0;
+
+        for stake_block in &self.blocks {
+            let block = &stake_block.block;
+            if block.hash != block.calculate_hash() {
+                return Err(StakeError::InvalidHash(block.id));
+            }
+            if block.previous_hash != previous.hash {
+                return Err(StakeError::BrokenLink(block.id));
+            }
+            if stake_block.slot <= previous_slot {
+                return Err(StakeError::SlotNotIncreasing {
+                    slot: stake_block.slot,
+                    previous: previous_slot,
+                });
+            }
+
+            let expected = self.producers.eligible_producer(&previous.hash, stake_block.slot)?;
+            if expected.id != stake_block.producer_id {
+                return Err(StakeError::NotEligible {
+                    slot: stake_block.slot,
+                    producer: stake_block.producer_id.clone(),
+                    expected: expected.id.clone(),
+                });
+            }
+            expected
+                .public_key
+                .verify(
+                    StakeBlock::message(&block.hash, stake_block.slot).as_bytes(),
+                    &stake_block.signature,
+                )
+                .map_err(|_| StakeError::InvalidSignature(block.id))?;
+
+            previous = block;
+            previous_slot = stake_block.slot;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trust::TrustEngine;
+    use crate::vote::SignedVote;
+    use std::collections::HashMap;
+
+    fn setup() -> (StakeChain, HashMap<String, SigningKey>) {
+        let mut keys = HashMap::new();
+        let mut entries = Vec::new();
+        for (id, weight) in [("validator_001", 1.0), ("validator_002", 1.0), ("validator_003", 2.0)] {
+            let key = SignedVote::generate_keypair();
+            entries.push((id.to_string(), key.verifying_key(), weight));
+            keys.insert(id.to_string(), key);
+        }
+        let producers = ProducerSet::from_weights(entries, &TrustEngine::new());
+        (StakeChain::new("Genesis Block".to_string(), producers), keys)
+    }
+
+    fn produce_next(chain: &mut StakeChain, keys: &HashMap<String, SigningKey>, slot: u64) {
+        let leader = chain.eligible_producer(slot).unwrap().id.clone();
+        chain
+            .produce(slot, format!("slot {}", slot), &leader, &keys[&leader])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_trust_adjusts_producer_weight() {
+        let (chain, _) = setup();
+        assert_eq!(chain.producers.get("validator_001").unwrap().weight, 1.2);
+        assert_eq!(chain.producers.get("validator_003").unwrap().weight, 2.0);
+    }
+
+    #[test]
+    fn test_leader_produces_valid_chain() {
+        let (mut chain, keys) = setup();
+        for slot in 1..=5 {
+            produce_next(&mut chain, &keys, slot);
+        }
+        assert_eq!(chain.blocks.len(), 5);
+        assert_eq!(chain.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_ineligible_producer_rejected() {
+        let (mut chain, keys) = setup();
+        let leader = chain.eligible_producer(1).unwrap().id.clone();
+        let other = keys.keys().find(|id| **id != leader).unwrap().clone();
+
+        let result = chain.produce(1, "data".to_string(), &other, &keys[&other]);
+        assert!(matches!(result, Err(StakeError::NotEligible { .. })));
+        let result = chain.produce(1, "data".to_string(), &leader, &keys[&other]);
+        assert_eq!(result, Err(StakeError::KeyMismatch(leader)));
+    }
+
+    #[test]
+    fn test_validation_detects_forged_blocks() {
+        let (mut chain, keys) = setup();
+        produce_next(&mut chain, &keys, 1);
+        produce_next(&mut chain, &keys, 2);
+
+        let mut forged = chain.blocks.clone();
+        chain.blocks[1].block.data = "rewritten".to_string();
+        assert_eq!(chain.validate(), Err(StakeError::InvalidHash(2)));
+
+        forged[1].producer_id = "validator_999".to_string();
+        chain.blocks = forged;
+        assert!(matches!(chain.validate(), Err(StakeError::NotEligible { .. })));
+    }
+
+    #[test]
+    fn test_selection_tracks_weight() {
+        let (chain, _) = setup();
+        let heavy = (1..=2000)
+            .filter(|slot| chain.eligible_producer(*slot).unwrap().id == "validator_003")
+            .count();
+        // validator_003 holds 2.0 of 4.3 total weight
+        assert!((800..1060).contains(&heavy), "heavy producer won {} slots", heavy);
+    }
+}
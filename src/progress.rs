@@ -0,0 +1,124 @@
+// src/progress.rs
+
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use indicatif::{ProgressBar, ProgressStyle};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+#[error("Operation cancelled")]
+pub struct Cancelled;
+
+/// Shared flag long-running loops poll between units of work
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() { Err(Cancelled) } else { Ok(()) }
+    }
+}
+
+static INTERRUPT: OnceLock<CancellationToken> = OnceLock::new();
+
+/// Token cancelled by Ctrl-C once `install_interrupt_handler` has run
+pub fn interrupt_token() -> CancellationToken {
+    INTERRUPT.get_or_init(CancellationToken::new).clone()
+}
+
+/// Route Ctrl-C to `interrupt_token()` so loops stop at their next safe point
+pub fn install_interrupt_handler() -> Result<(), ctrlc::Error> {
+    let token = interrupt_token();
+    ctrlc::set_handler(move || token.cancel())
+}
+
+/// Progress bar on stderr; drawing is skipped when stderr is not a terminal
+pub struct Progress {
+    bar: ProgressBar,
+}
+
+impl Progress {
+    /// Bar with a known amount of work, showing position and ETA
+    pub fn new(len: u64, message: impl Into<String>) -> Self {
+        let bar = ProgressBar::new(len);
+        bar.set_style(
+            ProgressStyle::with_template("{msg} [{bar:40}] {pos}/{len} ({eta} left)")
+                .expect("valid progress template")
+                .progress_chars("=> "),
+        );
+        bar.set_message(message.into());
+        Self { bar }
+    }
+
+    /// Spinner for work with no known end, such as nonce search
+    pub fn spinner(message: impl Into<String>) -> Self {
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(
+            ProgressStyle::with_template("{spinner} {msg} {pos} tried ({per_sec})")
+                .expect("valid spinner template")
+                .tick_chars("|/-\\ "),
+        );
+        bar.set_message(message.into());
+        bar.enable_steady_tick(Duration::from_millis(120));
+        Self { bar }
+    }
+
+    pub fn inc(&self, delta: u64) {
+        self.bar.inc(delta);
+    }
+
+    pub fn set_position(&self, pos: u64) {
+        self.bar.set_position(pos);
+    }
+
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+
+    /// Stop drawing but leave the last state visible
+    pub fn abandon(&self, message: impl Into<String>) {
+        self.bar.abandon_with_message(message.into());
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_shared_between_clones() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert_eq!(clone.check(), Ok(()));
+
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert_eq!(clone.check(), Err(Cancelled));
+    }
+
+    #[test]
+    fn test_progress_counts() {
+        let progress = Progress::new(3, "work");
+        progress.inc(2);
+        assert_eq!(progress.bar.position(), 2);
+        progress.finish();
+    }
+}
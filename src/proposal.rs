@@ -0,0 +1,420 @@
+// src/proposal.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::blockchain::Transaction;
+use crate::ids::{ProposalId, VoterId};
+use crate::policy::PolicyTable;
+use crate::scheme::{DefaultScheme, SignatureScheme};
+use crate::threshold::ThresholdEscalator;
+use crate::verify::VerificationError;
+use crate::vote::{
+    ProposalType, SignedVote, deserialize_public_key, deserialize_signature, serialize_public_key, serialize_signature,
+};
+use crate::window::{VotingWindow, WindowType};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ProposalError {
+    #[error("Proposal {0} is already registered")]
+    Duplicate(ProposalId),
+    #[error("Proposal {0} has an invalid creator signature")]
+    InvalidSignature(ProposalId),
+    #[error("Unknown proposal: {0}")]
+    Unknown(ProposalId),
+    #[error("Proposal {0} is already closed")]
+    AlreadyClosed(ProposalId),
+}
+
+/// Bytes a creator signs; the description comes last so it may contain any text
+pub fn proposal_message(
+    id: &str,
+    proposal_type: &ProposalType,
+    opens_at: DateTime<Utc>,
+    duration_secs: u64,
+    grace_secs: u64,
+    creator: &str,
+    description: &str,
+) -> String {
+    format!(
+        "proposal:{}:{:?}:{}:{}:{}:{}:{}",
+        id,
+        proposal_type,
+        opens_at.to_rfc3339(),
+        duration_secs,
+        grace_secs,
+        creator,
+        description
+    )
+}
+
+/// A proposal as opened by its creator: what it decides and when votes count
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Proposal {
+    pub id: ProposalId,
+    pub description: String,
+    pub proposal_type: ProposalType,
+    pub opens_at: DateTime<Utc>,
+    pub duration_secs: u64,
+    pub grace_secs: u64,
+    pub creator: VoterId,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    pub signature: Signature,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    pub public_key: VerifyingKey,
+    /// Runs like any other proposal but binds no one: its report is marked non-binding,
+    /// and it stays off the chain and out of performance scoring
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub practice: bool,
+}
+
+impl Proposal {
+    pub fn new(
+        id: ProposalId,
+        description: impl Into<String>,
+        proposal_type: ProposalType,
+        window: &VotingWindow,
+        creator: VoterId,
+        key: &SigningKey,
+    ) -> Self {
+        let mut proposal = Self {
+            id,
+            description: description.into(),
+            proposal_type,
+            opens_at: window.start_time,
+            duration_secs: window.duration_secs,
+            grace_secs: window.grace_secs,
+            creator,
+            signature: Signature::from_bytes(&[0; 64]),
+            public_key: DefaultScheme::public_key(key),
+            practice: false,
+        };
+        proposal.signature = DefaultScheme::sign(key, proposal.message().as_bytes());
+        proposal
+    }
+
+    /// A sandboxed proposal for learning the system; the practice flag is signed
+    pub fn practice(
+        id: ProposalId,
+        description: impl Into<String>,
+        proposal_type: ProposalType,
+        window: &VotingWindow,
+        creator: VoterId,
+        key: &SigningKey,
+    ) -> Self {
+        let mut proposal = Self::new(id, description, proposal_type, window, creator, key);
+        proposal.practice = true;
+        proposal.signature = DefaultScheme::sign(key, proposal.message().as_bytes());
+        proposal
+    }
+
+    /// Signed bytes; practice proposals are prefixed so the flag cannot be stripped
+    pub fn message(&self) -> String {
+        let message = proposal_message(
+            &self.id,
+            &self.proposal_type,
+            self.opens_at,
+            self.duration_secs,
+            self.grace_secs,
+            &self.creator,
+            &self.description,
+        );
+        if self.practice { format!("practice:{}", message) } else { message }
+    }
+
+    pub fn verify(&self) -> Result<(), ProposalError> {
+        DefaultScheme::verify(&self.public_key, self.message().as_bytes(), &self.signature)
+            .map_err(|_| ProposalError::InvalidSignature(self.id.clone()))
+    }
+
+    pub fn window(&self) -> VotingWindow {
+        VotingWindow::new(self.opens_at, WindowType::Custom(self.duration_secs), self.grace_secs)
+    }
+
+    /// Threshold defaults for this proposal's type
+    pub fn escalator(&self) -> ThresholdEscalator {
+        ThresholdEscalator::for_proposal_type(self.proposal_type.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    proposal: Proposal,
+    /// Set when the proposal was finalized before its window ran out
+    closed_at: Option<DateTime<Utc>>,
+}
+
+/// Every proposal votes may be cast on. Votes on proposals missing from the registry,
+/// cast before one opens, or after it closed are rejected.
+#[derive(Debug, Clone, Default)]
+pub struct ProposalRegistry {
+    proposals: BTreeMap<ProposalId, Entry>,
+    policies: PolicyTable,
+}
+
+impl ProposalRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Judge votes by `policies` rather than the built-in policy of each type
+    pub fn set_policies(&mut self, policies: PolicyTable) {
+        self.policies = policies;
+    }
+
+    pub fn register(&mut self, proposal: Proposal) -> Result<(), ProposalError> {
+        proposal.verify()?;
+        if self.proposals.contains_key(&proposal.id) {
+            return Err(ProposalError::Duplicate(proposal.id));
+        }
+        self.proposals.insert(proposal.id.clone(), Entry { proposal, closed_at: None });
+        Ok(())
+    }
+
+    pub fn get(&self, id: &ProposalId) -> Option<&Proposal> {
+        self.proposals.get(id).map(|e| &e.proposal)
+    }
+
+    pub fn len(&self) -> usize {
+        self.proposals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proposals.is_empty()
+    }
+
+    /// Close `id` at `at`, e.g. once it is finalized; later votes are rejected
+    pub fn close(&mut self, id: &ProposalId, at: DateTime<Utc>) -> Result<(), ProposalError> {
+        let entry = self.proposals.get_mut(id).ok_or_else(|| ProposalError::Unknown(id.clone()))?;
+        if entry.closed_at.is_some() {
+            return Err(ProposalError::AlreadyClosed(id.clone()));
+        }
+        entry.closed_at = Some(at);
+        Ok(())
+    }
+
+    /// When `id` stops taking votes: its close, or the end of its window and grace
+    pub fn closes_at(&self, id: &ProposalId) -> Option<DateTime<Utc>> {
+        let entry = self.proposals.get(id)?;
+        let window_end = entry.proposal.window().closes_at();
+        Some(entry.closed_at.map_or(window_end, |closed| closed.min(window_end)))
+    }
+
+    pub fn is_open(&self, id: &ProposalId, at: DateTime<Utc>) -> bool {
+        match (self.get(id), self.closes_at(id)) {
+            (Some(proposal), Some(closes_at)) => proposal.opens_at <= at && at <= closes_at,
+            _ => false,
+        }
+    }
+
+    /// Proposals taking votes at `at`, in id order
+    pub fn open_at(&self, at: DateTime<Utc>) -> Vec<&Proposal> {
+        self.proposals.keys().filter(|id| self.is_open(id, at)).filter_map(|id| self.get(id)).collect()
+    }
+
+    /// Reject `vote` unless its proposal is registered, open at the vote's timestamp, and
+    /// its type's policy allows the vote's decay model
+    pub fn check_vote(&self, vote: &SignedVote) -> Result<(), VerificationError> {
+        let proposal = self
+            .get(&vote.proposal_id)
+            .ok_or_else(|| VerificationError::UnknownProposal(vote.proposal_id.clone()))?;
+        if vote.timestamp < proposal.opens_at {
+            return Err(VerificationError::ProposalNotOpen(vote.proposal_id.clone()));
+        }
+        if !self.is_open(&vote.proposal_id, vote.timestamp) {
+            return Err(VerificationError::ProposalClosed(vote.proposal_id.clone()));
+        }
+        if !self.policies.get(&proposal.proposal_type).allows_decay(vote.decay_model) {
+            return Err(VerificationError::DecayModelForbidden {
+                proposal_id: vote.proposal_id.clone(),
+                decay_model: vote.decay_model,
+            });
+        }
+        Ok(())
+    }
+
+    /// Threshold defaults for a registered proposal, by its type
+    pub fn escalator(&self, id: &ProposalId) -> Option<ThresholdEscalator> {
+        self.get(id).map(Proposal::escalator)
+    }
+
+    pub fn is_practice(&self, id: &ProposalId) -> bool {
+        self.get(id).is_some_and(|p| p.practice)
+    }
+
+    /// Ids of every practice proposal, e.g. for `VoteIntake::practice`
+    pub fn practice_ids(&self) -> BTreeSet<ProposalId> {
+        self.proposals.values().filter(|e| e.proposal.practice).map(|e| e.proposal.id.clone()).collect()
+    }
+
+    /// `transactions` minus those on practice proposals, which never reach the chain
+    pub fn binding_transactions(&self, transactions: &[Transaction]) -> Vec<Transaction> {
+        transactions
+            .iter()
+            .filter(|tx| self.proposals.get(tx.proposal_id()).is_none_or(|e| !e.proposal.practice))
+            .cloned()
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::blockchain::VoteEntry;
+    use crate::history::HistoryAnalyzer;
+    use crate::intake::{Channel, VoteIntake, VoteSource};
+    use crate::policy::ProposalPolicy;
+    use crate::report::{FinalizationReport, ReportInput};
+    use crate::threshold::DecisionRule;
+    use crate::tiers::Performance;
+    use crate::trust::TrustEngine;
+    use crate::units::Seconds;
+    use crate::verify::VerificationPolicy;
+    use crate::vote::DecayType;
+
+    fn open(registry: &mut ProposalRegistry, id: &str, proposal_type: ProposalType, opens_at: DateTime<Utc>) {
+        let key = SignedVote::generate_keypair();
+        let window = ProposalPolicy::for_type(&proposal_type).voting_window(opens_at, 10);
+        let proposal = Proposal::new(id.parse().unwrap(), "Raise the fee: 2%", proposal_type, &window, "carol".parse().unwrap(), &key);
+        registry.register(proposal).unwrap();
+    }
+
+    fn vote(proposal: &str, at: DateTime<Utc>) -> SignedVote {
+        let key = SignedVote::generate_keypair();
+        SignedVote::new("alice".parse().unwrap(), proposal.parse().unwrap(), 1.0, at, DecayType::Linear, &key)
+    }
+
+    #[test]
+    fn test_registration_and_signatures() {
+        let now = Utc::now();
+        let mut registry = ProposalRegistry::new();
+        open(&mut registry, "p1", ProposalType::Budget, now);
+        let p1 = registry.get(&"p1".parse().unwrap()).unwrap().clone();
+        assert_eq!(p1.window().duration_secs, 86_400);
+        assert_eq!(registry.escalator(&p1.id).unwrap().base_threshold, 0.6);
+
+        assert_eq!(registry.register(p1.clone()), Err(ProposalError::Duplicate(p1.id.clone())));
+        let mut forged = p1.clone();
+        forged.id = "p2".parse().unwrap();
+        assert_eq!(registry.register(forged), Err(ProposalError::InvalidSignature("p2".parse().unwrap())));
+        let decoded: Proposal = serde_json::from_str(&serde_json::to_string(&p1).unwrap()).unwrap();
+        assert_eq!(decoded.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_votes_on_unknown_or_closed_proposals_are_rejected() {
+        let now = Utc::now();
+        let mut registry = ProposalRegistry::new();
+        open(&mut registry, "p1", ProposalType::Emergency, now - Duration::seconds(60));
+        open(&mut registry, "p2", ProposalType::Normal, now + Duration::seconds(60));
+        let p1: ProposalId = "p1".parse().unwrap();
+
+        assert_eq!(vote("p1", now).verify_with_proposals(now, 60, &registry), Ok(()));
+        assert_eq!(
+            vote("p3", now).verify_with_proposals(now, 60, &registry),
+            Err(VerificationError::UnknownProposal("p3".parse().unwrap()))
+        );
+        assert_eq!(
+            registry.check_vote(&vote("p2", now)),
+            Err(VerificationError::ProposalNotOpen("p2".parse().unwrap()))
+        );
+        // Emergency windows last five minutes plus grace
+        assert_eq!(
+            registry.check_vote(&vote("p1", now + Duration::seconds(260))),
+            Err(VerificationError::ProposalClosed(p1.clone()))
+        );
+        assert_eq!(registry.open_at(now).iter().map(|p| p.id.as_str()).collect::<Vec<_>>(), vec!["p1"]);
+
+        registry.close(&p1, now).unwrap();
+        assert_eq!(registry.close(&p1, now), Err(ProposalError::AlreadyClosed(p1.clone())));
+        assert_eq!(registry.check_vote(&vote("p1", now)), Ok(()));
+        assert_eq!(
+            registry.check_vote(&vote("p1", now + Duration::seconds(1))),
+            Err(VerificationError::ProposalClosed(p1))
+        );
+    }
+
+    #[test]
+    fn test_policies_restrict_decay_models() {
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let mut registry = ProposalRegistry::new();
+        open(&mut registry, "charter", ProposalType::Constitutional, now);
+        open(&mut registry, "fees", ProposalType::Normal, now);
+        let cast = |proposal: &str, decay| SignedVote::new("alice".parse().unwrap(), proposal.parse().unwrap(), 1.0, now, decay, &key);
+        let policy = VerificationPolicy::new(Seconds::new(60));
+
+        assert_eq!(cast("charter", DecayType::Stepped).verify_against(&policy, now, Some(&registry)), Ok(()));
+        assert_eq!(
+            cast("charter", DecayType::Exponential).verify_against(&policy, now, Some(&registry)),
+            Err(VerificationError::DecayModelForbidden { proposal_id: "charter".parse().unwrap(), decay_model: DecayType::Exponential })
+        );
+        assert_eq!(cast("charter", DecayType::Exponential).verify_against(&policy, now, None), Ok(()));
+        assert_eq!(cast("fees", DecayType::Exponential).verify_with_proposals(now, 60, &registry), Ok(()));
+
+        let mut policies = PolicyTable::new();
+        policies.set(ProposalType::Normal, ProposalPolicy { decay_models: vec![DecayType::Linear], ..ProposalPolicy::for_type(&ProposalType::Normal) });
+        registry.set_policies(policies);
+        assert!(matches!(
+            cast("fees", DecayType::Exponential).verify_with_proposals(now, 60, &registry),
+            Err(VerificationError::DecayModelForbidden { .. })
+        ));
+    }
+
+    #[test]
+    fn test_practice_proposals_bind_no_one() {
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let mut registry = ProposalRegistry::new();
+        open(&mut registry, "p1", ProposalType::Normal, now);
+        let window = ProposalPolicy::for_type(&ProposalType::Normal).voting_window(now, 10);
+        let drill = Proposal::practice("drill".parse().unwrap(), "Try it out", ProposalType::Normal, &window, "carol".parse().unwrap(), &key);
+        let mut stripped = drill.clone();
+        stripped.practice = false;
+        assert_eq!(stripped.verify(), Err(ProposalError::InvalidSignature(drill.id.clone())));
+        registry.register(drill).unwrap();
+        assert_eq!(registry.practice_ids(), BTreeSet::from(["drill".parse().unwrap()]));
+
+        // Votes on the drill go through the usual checks but never reach the chain
+        let drill_vote = vote("drill", now + Duration::seconds(1));
+        assert_eq!(registry.check_vote(&drill_vote), Ok(()));
+        let transactions = [Transaction::Vote(VoteEntry::from(&drill_vote)), Transaction::Vote(VoteEntry::from(&vote("p1", now)))];
+        let binding = registry.binding_transactions(&transactions);
+        assert_eq!(binding.iter().map(Transaction::proposal_id).collect::<Vec<_>>(), vec!["p1"]);
+
+        // Rejections on the drill do not count against the voter
+        let mut intake = VoteIntake::new(Seconds::new(60));
+        intake.practice = registry.practice_ids();
+        let mut history = HistoryAnalyzer::default();
+        let stale = vote("drill", now - Duration::seconds(600));
+        assert!(intake.submit(stale, VoteSource::new(Channel::Http), now, None, &mut history).is_err());
+        let alice = "alice".parse().unwrap();
+        assert_eq!(history.rejected.len(), 1);
+        assert_eq!(Performance::observe(&alice, 1, 1, &history, now - Duration::days(1)).rejected_votes, 0);
+
+        let mut report = FinalizationReport::build(ReportInput {
+            proposal_id: "drill",
+            proposal_type: ProposalType::Normal,
+            opened_at: now,
+            finalized_at: now + Duration::seconds(5),
+            votes: &[drill_vote],
+            trust: &TrustEngine::new(),
+            max_age_secs: 3600,
+            admissions: None,
+            eligible_weight: None,
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        });
+        report.mark_practice();
+        report.certify(&key);
+        assert!(report.verify_certificate() && report.proposal.practice);
+        assert!(report.render_text().contains("non-binding"));
+    }
+}
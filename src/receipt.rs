@@ -0,0 +1,198 @@
+// src/receipt.rs
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::hashing;
+use crate::ids::{ProposalId, VoterId};
+use crate::registry::ValidatorRegistry;
+use crate::scheme::{DefaultScheme, SignatureScheme};
+use crate::threshold::ThresholdEscalator;
+use crate::trust::TrustSource;
+use crate::verify::VerificationError;
+use crate::vote::{SignedVote, deserialize_public_key, deserialize_signature, serialize_public_key, serialize_signature};
+use crate::weight_engine::WeightEngine;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ReceiptError {
+    #[error("Receipt was issued for a different vote")]
+    WrongVote,
+    #[error("Receipt was not issued by the expected node")]
+    WrongNode,
+    #[error("Invalid receipt signature")]
+    InvalidSignature,
+}
+
+/// A node's signed statement of what it did with a vote: the weight it counted, the
+/// threshold in force when it did, or why it turned the vote away. Voters keep it,
+/// alongside their vote, as proof their ballot was counted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VoteReceipt {
+    pub vote_hash: String,
+    pub voter_id: VoterId,
+    pub proposal_id: ProposalId,
+    /// Decayed, trust-adjusted weight at `issued_at`; zero for a rejected vote
+    pub weight: f64,
+    pub threshold: f64,
+    pub accepted: bool,
+    /// Why the vote was rejected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub issued_at: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<32>")]
+    pub node: VerifyingKey,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<64>")]
+    pub signature: Signature,
+}
+
+impl VoteReceipt {
+    /// Sign a receipt for `vote`; an `Err` outcome records the rejection and a weight of zero
+    pub fn new(
+        vote: &SignedVote,
+        outcome: Result<f64, &VerificationError>,
+        threshold: f64,
+        issued_at: DateTime<Utc>,
+        node: &SigningKey,
+    ) -> Self {
+        let (weight, reason) = match outcome {
+            Ok(weight) => (weight, None),
+            Err(e) => (0.0, Some(e.to_string())),
+        };
+        let mut receipt = Self {
+            vote_hash: hashing::vote_hash(vote),
+            voter_id: vote.voter_id.clone(),
+            proposal_id: vote.proposal_id.clone(),
+            weight,
+            threshold,
+            accepted: reason.is_none(),
+            reason,
+            issued_at,
+            node: DefaultScheme::public_key(node),
+            signature: Signature::from_bytes(&[0; 64]),
+        };
+        receipt.signature = DefaultScheme::sign(node, receipt.signed_hash().as_bytes());
+        receipt
+    }
+
+    fn signed_hash(&self) -> String {
+        let rejection = if self.accepted { None } else { Some(self.reason.as_deref().unwrap_or("")) };
+        hashing::receipt_hash(
+            &self.vote_hash,
+            &self.voter_id,
+            &self.proposal_id,
+            self.weight,
+            self.threshold,
+            rejection,
+            &self.issued_at.to_rfc3339(),
+        )
+    }
+
+    /// Whether the receipt is intact under the key of the node it names
+    pub fn verify_signature(&self) -> Result<(), ReceiptError> {
+        DefaultScheme::verify(&self.node, self.signed_hash().as_bytes(), &self.signature)
+            .map_err(|_| ReceiptError::InvalidSignature)
+    }
+
+    /// Check the receipt was issued for exactly `vote` by `node`, and is intact
+    pub fn verify(&self, vote: &SignedVote, node: &VerifyingKey) -> Result<(), ReceiptError> {
+        if self.vote_hash != hashing::vote_hash(vote) || self.voter_id != vote.voter_id || self.proposal_id != vote.proposal_id {
+            return Err(ReceiptError::WrongVote);
+        }
+        if self.node != *node {
+            return Err(ReceiptError::WrongNode);
+        }
+        self.verify_signature()
+    }
+}
+
+/// What a node weighs admitted votes with when it signs their receipts
+pub struct ReceiptIssuer<'a> {
+    pub engine: &'a WeightEngine,
+    pub trust: Option<&'a dyn TrustSource>,
+    pub escalator: &'a ThresholdEscalator,
+    pub opened_at: DateTime<Utc>,
+    pub node: &'a SigningKey,
+}
+
+impl ReceiptIssuer<'_> {
+    /// Receipt for `vote` as verified at `at`, weighing it if it was accepted
+    pub fn issue(
+        &self,
+        vote: &SignedVote,
+        outcome: &Result<(), VerificationError>,
+        at: DateTime<Utc>,
+        registry: Option<&ValidatorRegistry>,
+    ) -> VoteReceipt {
+        let weight = outcome
+            .as_ref()
+            .map(|_| self.engine.explain(vote, at, self.trust, registry).final_weight.get());
+        let threshold = self.escalator.threshold_with_profile(at, self.opened_at);
+        VoteReceipt::new(vote, weight, threshold, at, self.node)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::history::HistoryAnalyzer;
+    use crate::intake::{Channel, VoteIntake, VoteSource};
+    use crate::units::Seconds;
+    use crate::vote::{DecayType, ProposalType};
+
+    #[test]
+    fn test_intake_issues_verifiable_receipts() {
+        let voter = SignedVote::generate_keypair();
+        let node = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let engine = WeightEngine::new();
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+        let issuer = ReceiptIssuer { engine: &engine, trust: None, escalator: &escalator, opened_at: now, node: &node };
+        let mut intake = VoteIntake::new(Seconds::new(300));
+        let mut history = HistoryAnalyzer::default();
+
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &voter);
+        let at = now + Duration::seconds(10);
+        let receipt = intake.submit_with_receipt(vote.clone(), VoteSource::new(Channel::Http), at, None, &mut history, &issuer);
+        assert!(receipt.accepted);
+        assert!((receipt.weight - 0.99).abs() < 1e-9);
+        assert_eq!(receipt.threshold, escalator.threshold_with_profile(at, now));
+        assert_eq!(receipt.verify(&vote, &node.verifying_key()), Ok(()));
+
+        // A replay is turned away, and its receipt says so
+        let replayed = intake.submit_with_receipt(vote.clone(), VoteSource::new(Channel::Gossip), at, None, &mut history, &issuer);
+        assert!(!replayed.accepted && replayed.weight == 0.0);
+        assert_eq!(replayed.reason.as_deref(), Some(VerificationError::Replayed.to_string().as_str()));
+        assert_eq!(replayed.verify(&vote, &node.verifying_key()), Ok(()));
+
+        let decoded: VoteReceipt = serde_json::from_str(&serde_json::to_string(&receipt).unwrap()).unwrap();
+        assert_eq!(decoded, receipt);
+    }
+
+    #[test]
+    fn test_receipt_rejects_tampering() {
+        let key = SignedVote::generate_keypair();
+        let node = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key);
+        let other = SignedVote::new("bob".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key);
+        let receipt = VoteReceipt::new(&vote, Ok(0.8), 0.51, now, &node);
+
+        let mut inflated = receipt.clone();
+        inflated.weight = 5.0;
+        assert_eq!(inflated.verify(&vote, &node.verifying_key()), Err(ReceiptError::InvalidSignature));
+        let mut flipped = VoteReceipt::new(&vote, Err(&VerificationError::InvalidSignature), 0.51, now, &node);
+        flipped.accepted = true;
+        assert_eq!(flipped.verify_signature(), Err(ReceiptError::InvalidSignature));
+
+        assert_eq!(receipt.verify(&other, &node.verifying_key()), Err(ReceiptError::WrongVote));
+        assert_eq!(receipt.verify(&vote, &key.verifying_key()), Err(ReceiptError::WrongNode));
+    }
+}
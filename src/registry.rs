@@ -0,0 +1,568 @@
+// src/registry.rs
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::revocation::RevocationList;
+use crate::units::Seconds;
+use crate::verify::VerificationError;
+use crate::vote::{SignedVote, deserialize_public_key, deserialize_signature, serialize_public_key, serialize_signature};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum RegistryError {
+    #[error("Revocation issuer is not a registry authority")]
+    UnauthorizedIssuer,
+    #[error("Revocation signature is invalid")]
+    InvalidRevocationSignature,
+    #[error("Key is already revoked")]
+    AlreadyRevoked,
+    #[error("Unknown validator: {0}")]
+    UnknownValidator(String),
+    #[error("Validator {0} is already exiting")]
+    AlreadyExiting(String),
+    #[error("Validator {0} has not requested an exit")]
+    NotExiting(String),
+    #[error("Stake is still in cooldown for another {0}")]
+    CooldownActive(Seconds),
+    #[error("Stake for {0} was already released")]
+    AlreadyReleased(String),
+    #[error("Category weight must be finite and non-negative, got {0}")]
+    InvalidCategoryWeight(f64),
+}
+
+/// Per-category weight multipliers for one validator, e.g. `parameter_change -> 2.0`.
+/// Categories without an entry weigh 1.0.
+pub type RoleWeights = BTreeMap<String, f64>;
+
+/// Signed statement that a public key is compromised as of `revoked_at`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Revocation {
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<32>")]
+    pub public_key: VerifyingKey,
+    /// Votes stamped at or after this time are rejected; earlier ones still count
+    pub revoked_at: DateTime<Utc>,
+    pub reason: String,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<32>")]
+    pub issuer: VerifyingKey,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<64>")]
+    pub signature: Signature,
+}
+
+impl Revocation {
+    /// Create a revocation signed by a registry authority
+    pub fn new(
+        public_key: VerifyingKey,
+        revoked_at: DateTime<Utc>,
+        reason: String,
+        issuer_key: &SigningKey,
+    ) -> Self {
+        let signature = issuer_key.sign(Self::message(&public_key, revoked_at, &reason).as_bytes());
+        Self {
+            public_key,
+            revoked_at,
+            reason,
+            issuer: issuer_key.verifying_key(),
+            signature,
+        }
+    }
+
+    fn message(public_key: &VerifyingKey, revoked_at: DateTime<Utc>, reason: &str) -> String {
+        format!(
+            "revoke:{}:{}:{}",
+            hex::encode(public_key.as_bytes()),
+            revoked_at.to_rfc3339(),
+            reason
+        )
+    }
+
+    /// Check the issuer signature over the revocation
+    pub fn verify(&self) -> Result<(), RegistryError> {
+        let message = Self::message(&self.public_key, self.revoked_at, &self.reason);
+        self.issuer
+            .verify(message.as_bytes(), &self.signature)
+            .map_err(|_| RegistryError::InvalidRevocationSignature)
+    }
+}
+
+/// Shape of the probation ramp from the initial multiplier up to full weight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RampCurve {
+    Linear,
+    /// Slow start, quadratic in elapsed time
+    EaseIn,
+    /// Jumps in this many equal steps
+    Steps(u32),
+}
+
+/// How a newly onboarded validator's weight ramps up
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbationPolicy {
+    pub period: Seconds,
+    pub initial_multiplier: f64,
+    pub curve: RampCurve,
+}
+
+impl Default for ProbationPolicy {
+    fn default() -> Self {
+        Self {
+            period: Seconds::new(7 * 24 * 3600),
+            initial_multiplier: 0.25,
+            curve: RampCurve::Linear,
+        }
+    }
+}
+
+impl ProbationPolicy {
+    /// Weight multiplier `elapsed` after onboarding; 1.0 once the period is over
+    pub fn multiplier(&self, elapsed: Seconds) -> f64 {
+        if self.period <= Seconds::ZERO || elapsed >= self.period {
+            return 1.0;
+        }
+        let progress = elapsed.max(Seconds::ZERO).as_f64() / self.period.as_f64();
+        let shaped = match self.curve {
+            RampCurve::Linear => progress,
+            RampCurve::EaseIn => progress * progress,
+            RampCurve::Steps(n) => {
+                let n = n.max(1) as f64;
+                (progress * n).floor() / n
+            }
+        };
+        let initial = self.initial_multiplier.clamp(0.0, 1.0);
+        initial + (1.0 - initial) * shaped
+    }
+}
+
+/// Probation state for a validator added through `ValidatorRegistry::onboard`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Onboarding {
+    pub onboarded_at: DateTime<Utc>,
+    pub policy: ProbationPolicy,
+}
+
+impl Onboarding {
+    pub fn ends_at(&self) -> DateTime<Utc> {
+        self.onboarded_at + self.policy.period.to_duration()
+    }
+
+    pub fn multiplier_at(&self, at: DateTime<Utc>) -> f64 {
+        self.policy.multiplier(Seconds::between(self.onboarded_at, at))
+    }
+}
+
+/// What happens to votes a validator cast before exiting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitPolicy {
+    /// Votes keep counting and keep decaying as usual
+    KeepDecaying,
+    /// Votes count with the weight they had when the exit was requested
+    Freeze,
+    /// Votes stop counting
+    Remove,
+}
+
+/// A validator's withdrawal; stake is released once `cooldown` has passed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExitRequest {
+    pub requested_at: DateTime<Utc>,
+    pub policy: ExitPolicy,
+    pub cooldown: Seconds,
+    pub released_at: Option<DateTime<Utc>>,
+}
+
+impl ExitRequest {
+    /// Earliest time the stake can be released
+    pub fn releasable_at(&self) -> DateTime<Utc> {
+        self.requested_at + self.cooldown.to_duration()
+    }
+
+    /// Whether `vote` was cast while the validator was still active
+    pub fn covers(&self, vote: &SignedVote) -> bool {
+        vote.timestamp < self.requested_at
+    }
+
+    /// Instant to measure decay against: frozen votes stop aging at the exit
+    pub fn decay_until(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self.policy {
+            ExitPolicy::Freeze => now.min(self.requested_at),
+            _ => now,
+        }
+    }
+
+    pub fn weight_multiplier(&self) -> f64 {
+        match self.policy {
+            ExitPolicy::Remove => 0.0,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Known validator keys, the authorities allowed to revoke them, and revocation state
+#[derive(Default)]
+pub struct ValidatorRegistry {
+    validators: HashMap<String, VerifyingKey>,
+    revocations: RevocationList,
+    onboarding: HashMap<String, Onboarding>,
+    exits: HashMap<String, ExitRequest>,
+    roles: HashMap<String, RoleWeights>,
+}
+
+impl ValidatorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, voter_id: String, public_key: VerifyingKey) {
+        self.validators.insert(voter_id, public_key);
+    }
+
+    pub fn public_key(&self, voter_id: &str) -> Option<&VerifyingKey> {
+        self.validators.get(voter_id)
+    }
+
+    /// Registered validator ids, sorted
+    pub fn validator_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.validators.keys().map(String::as_str).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Authority keys, sorted by their bytes
+    pub fn authorities(&self) -> Vec<[u8; 32]> {
+        self.revocations.authorities()
+    }
+
+    /// Register a new validator whose weight ramps up under `policy`.
+    /// Validators added with `register` count at full weight immediately.
+    pub fn onboard(
+        &mut self,
+        voter_id: String,
+        public_key: VerifyingKey,
+        onboarded_at: DateTime<Utc>,
+        policy: ProbationPolicy,
+    ) {
+        self.onboarding
+            .insert(voter_id.clone(), Onboarding { onboarded_at, policy });
+        self.register(voter_id, public_key);
+    }
+
+    pub fn onboarding(&self, voter_id: &str) -> Option<&Onboarding> {
+        self.onboarding.get(voter_id)
+    }
+
+    /// Probation multiplier for a vote cast by `voter_id` at `at`
+    pub fn probation_multiplier(&self, voter_id: &str, at: DateTime<Utc>) -> f64 {
+        self.onboarding
+            .get(voter_id)
+            .map_or(1.0, |o| o.multiplier_at(at))
+    }
+
+    pub fn on_probation(&self, voter_id: &str, at: DateTime<Utc>) -> bool {
+        self.probation_multiplier(voter_id, at) < 1.0
+    }
+
+    /// Weigh `voter_id`'s votes on `category` proposals by `multiplier`
+    pub fn set_category_weight(&mut self, voter_id: &str, category: &str, multiplier: f64) -> Result<(), RegistryError> {
+        if !self.validators.contains_key(voter_id) {
+            return Err(RegistryError::UnknownValidator(voter_id.to_string()));
+        }
+        if !multiplier.is_finite() || multiplier < 0.0 {
+            return Err(RegistryError::InvalidCategoryWeight(multiplier));
+        }
+        self.roles
+            .entry(voter_id.to_string())
+            .or_default()
+            .insert(category.to_string(), multiplier);
+        Ok(())
+    }
+
+    pub fn role_weights(&self, voter_id: &str) -> Option<&RoleWeights> {
+        self.roles.get(voter_id)
+    }
+
+    /// Multiplier for `voter_id` on a `category` proposal; 1.0 unless configured
+    pub fn category_weight(&self, voter_id: &str, category: &str) -> f64 {
+        self.roles
+            .get(voter_id)
+            .and_then(|r| r.get(category))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Start `voter_id`'s withdrawal. Votes cast from `at` on are rejected; earlier
+    /// votes on still-open proposals are handled according to `policy`.
+    pub fn request_exit(
+        &mut self,
+        voter_id: &str,
+        at: DateTime<Utc>,
+        policy: ExitPolicy,
+        cooldown: Seconds,
+    ) -> Result<&ExitRequest, RegistryError> {
+        if !self.validators.contains_key(voter_id) {
+            return Err(RegistryError::UnknownValidator(voter_id.to_string()));
+        }
+        if self.exits.contains_key(voter_id) {
+            return Err(RegistryError::AlreadyExiting(voter_id.to_string()));
+        }
+
+        Ok(self.exits.entry(voter_id.to_string()).or_insert(ExitRequest {
+            requested_at: at,
+            policy,
+            cooldown,
+            released_at: None,
+        }))
+    }
+
+    pub fn exit(&self, voter_id: &str) -> Option<&ExitRequest> {
+        self.exits.get(voter_id)
+    }
+
+    /// Release the stake of an exited validator once the cooldown is over
+    pub fn release_stake(&mut self, voter_id: &str, now: DateTime<Utc>) -> Result<(), RegistryError> {
+        let exit = self
+            .exits
+            .get_mut(voter_id)
+            .ok_or_else(|| RegistryError::NotExiting(voter_id.to_string()))?;
+        if exit.released_at.is_some() {
+            return Err(RegistryError::AlreadyReleased(voter_id.to_string()));
+        }
+        let remaining = Seconds::between(now, exit.releasable_at());
+        if remaining > Seconds::ZERO {
+            return Err(RegistryError::CooldownActive(remaining));
+        }
+
+        exit.released_at = Some(now);
+        Ok(())
+    }
+
+    /// Exited validators whose cooldown is over but whose stake is still held
+    pub fn releasable(&self, now: DateTime<Utc>) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .exits
+            .iter()
+            .filter(|(_, e)| e.released_at.is_none() && e.releasable_at() <= now)
+            .map(|(id, _)| id.as_str())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Allow `authority` to sign revocations
+    pub fn add_authority(&mut self, authority: VerifyingKey) {
+        self.revocations.add_authority(authority);
+    }
+
+    /// Apply a signed revocation after checking its issuer
+    pub fn revoke(&mut self, revocation: Revocation) -> Result<(), RegistryError> {
+        self.revocations.revoke(revocation)
+    }
+
+    /// Time from which votes signed by `public_key` are no longer accepted
+    pub fn revoked_at(&self, public_key: &VerifyingKey) -> Option<DateTime<Utc>> {
+        self.revocations.revoked_at(public_key)
+    }
+
+    /// Every accepted revocation, in the order it was applied
+    pub fn revocation_log(&self) -> &[Revocation] {
+        self.revocations.log()
+    }
+
+    /// The authorities and revocations, e.g. to persist them or to verify votes under
+    /// `VerificationPolicy::with_revocations`
+    pub fn revocations(&self) -> &RevocationList {
+        &self.revocations
+    }
+
+    /// Apply every revocation in `list` not already applied, checking each against this
+    /// registry's authorities; returns how many were new
+    pub fn merge_revocations(&mut self, list: &RevocationList) -> Result<usize, RegistryError> {
+        let mut added = 0;
+        for revocation in list.log() {
+            if self.revocations.log().contains(revocation) {
+                continue;
+            }
+            self.revoke(revocation.clone())?;
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    /// Reject votes from unregistered voters or signed with a key other than the one
+    /// registered for the voter, votes signed with a revoked key at or after its
+    /// revocation time, and votes cast after the validator asked to exit
+    pub fn check_vote(&self, vote: &SignedVote) -> Result<(), VerificationError> {
+        match self.validators.get(vote.voter_id.as_str()) {
+            None => return Err(VerificationError::UnregisteredVoter(vote.voter_id.clone())),
+            Some(key) if *key != vote.public_key => return Err(VerificationError::UnregisteredKey(vote.voter_id.clone())),
+            Some(_) => {}
+        }
+        self.revocations.check(vote)?;
+        match self.exits.get(vote.voter_id.as_str()) {
+            Some(exit) if !exit.covers(vote) => Err(VerificationError::ValidatorExited),
+            _ => Ok(()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::DecayType;
+    use chrono::Duration;
+
+    fn setup() -> (ValidatorRegistry, SigningKey, SigningKey) {
+        let authority = SignedVote::generate_keypair();
+        let validator = SignedVote::generate_keypair();
+        let mut registry = ValidatorRegistry::new();
+        registry.add_authority(authority.verifying_key());
+        registry.register("validator_001".to_string(), validator.verifying_key());
+        (registry, authority, validator)
+    }
+
+    fn vote_at(key: &SigningKey, timestamp: DateTime<Utc>) -> SignedVote {
+        SignedVote::new(
+            "validator_001".parse().unwrap(),
+            "proposal_1".parse().unwrap(),
+            1.0,
+            timestamp,
+            DecayType::Linear,
+            key,
+        )
+    }
+
+    #[test]
+    fn test_votes_after_revocation_rejected() {
+        let (mut registry, authority, validator) = setup();
+        let now = Utc::now();
+        let revocation = Revocation::new(validator.verifying_key(), now, "leaked".to_string(), &authority);
+        registry.revoke(revocation).unwrap();
+
+        let before = vote_at(&validator, now - Duration::seconds(10));
+        let after = vote_at(&validator, now + Duration::seconds(1));
+
+        assert_eq!(registry.check_vote(&before), Ok(()));
+        assert_eq!(registry.check_vote(&after), Err(VerificationError::KeyRevoked));
+        assert_eq!(registry.revocation_log().len(), 1);
+    }
+
+    #[test]
+    fn test_votes_must_use_the_registered_key() {
+        let (mut registry, authority, validator) = setup();
+        let now = Utc::now();
+        registry
+            .revoke(Revocation::new(validator.verifying_key(), now, "leaked".to_string(), &authority))
+            .unwrap();
+
+        // A fresh key does not get around the revocation of the registered one
+        let fresh = SignedVote::generate_keypair();
+        let voter: crate::ids::VoterId = "validator_001".parse().unwrap();
+        assert_eq!(
+            registry.check_vote(&vote_at(&fresh, now + Duration::seconds(1))),
+            Err(VerificationError::UnregisteredKey(voter))
+        );
+        let stranger = SignedVote::new("stranger".parse().unwrap(), "proposal_1".parse().unwrap(), 1.0, now, DecayType::Linear, &fresh);
+        assert_eq!(registry.check_vote(&stranger), Err(VerificationError::UnregisteredVoter("stranger".parse().unwrap())));
+    }
+
+    #[test]
+    fn test_revocation_requires_authority() {
+        let (mut registry, _authority, validator) = setup();
+        let rogue = SignedVote::generate_keypair();
+        let revocation = Revocation::new(validator.verifying_key(), Utc::now(), "spite".to_string(), &rogue);
+
+        assert_eq!(registry.revoke(revocation), Err(RegistryError::UnauthorizedIssuer));
+        assert!(registry.revoked_at(&validator.verifying_key()).is_none());
+    }
+
+    #[test]
+    fn test_tampered_revocation_rejected() {
+        let (mut registry, authority, validator) = setup();
+        let mut revocation = Revocation::new(validator.verifying_key(), Utc::now(), "leaked".to_string(), &authority);
+        revocation.revoked_at -= Duration::days(30);
+
+        assert_eq!(registry.revoke(revocation), Err(RegistryError::InvalidRevocationSignature));
+    }
+
+    #[test]
+    fn test_double_revocation() {
+        let (mut registry, authority, validator) = setup();
+        let now = Utc::now();
+        registry
+            .revoke(Revocation::new(validator.verifying_key(), now, "leaked".to_string(), &authority))
+            .unwrap();
+
+        let again = Revocation::new(validator.verifying_key(), now, "leaked".to_string(), &authority);
+        assert_eq!(registry.revoke(again), Err(RegistryError::AlreadyRevoked));
+    }
+
+    #[test]
+    fn test_probation_ramp() {
+        let (mut registry, _authority, _validator) = setup();
+        let newcomer = SignedVote::generate_keypair();
+        let start = Utc::now();
+        let policy = ProbationPolicy {
+            period: Seconds::new(100),
+            initial_multiplier: 0.2,
+            curve: RampCurve::Linear,
+        };
+        registry.onboard("newcomer".to_string(), newcomer.verifying_key(), start, policy.clone());
+
+        assert_eq!(registry.probation_multiplier("newcomer", start), 0.2);
+        let halfway = registry.probation_multiplier("newcomer", start + Duration::seconds(50));
+        assert!((halfway - 0.6).abs() < 1e-9);
+        assert_eq!(registry.probation_multiplier("newcomer", start + Duration::seconds(100)), 1.0);
+        assert!(!registry.on_probation("validator_001", start));
+        assert_eq!(registry.onboarding("newcomer").unwrap().ends_at(), start + Duration::seconds(100));
+
+        let steps = ProbationPolicy { curve: RampCurve::Steps(4), ..policy.clone() };
+        assert_eq!(steps.multiplier(Seconds::new(24)), 0.2);
+        assert!((steps.multiplier(Seconds::new(25)) - 0.4).abs() < 1e-9);
+        let ease = ProbationPolicy { curve: RampCurve::EaseIn, ..policy };
+        assert!(ease.multiplier(Seconds::new(50)) < halfway);
+    }
+
+    #[test]
+    fn test_exit_rejects_later_votes_and_releases_after_cooldown() {
+        let (mut registry, _authority, validator) = setup();
+        let now = Utc::now();
+        let cooldown = Seconds::new(3600);
+
+        assert_eq!(
+            registry.request_exit("nobody", now, ExitPolicy::Freeze, cooldown).unwrap_err(),
+            RegistryError::UnknownValidator("nobody".to_string())
+        );
+        registry.request_exit("validator_001", now, ExitPolicy::Freeze, cooldown).unwrap();
+        assert!(matches!(
+            registry.request_exit("validator_001", now, ExitPolicy::Remove, cooldown),
+            Err(RegistryError::AlreadyExiting(_))
+        ));
+
+        assert_eq!(registry.check_vote(&vote_at(&validator, now - Duration::seconds(5))), Ok(()));
+        assert_eq!(
+            registry.check_vote(&vote_at(&validator, now + Duration::seconds(5))),
+            Err(VerificationError::ValidatorExited)
+        );
+
+        assert_eq!(
+            registry.release_stake("validator_001", now + Duration::seconds(600)),
+            Err(RegistryError::CooldownActive(Seconds::new(3000)))
+        );
+        assert!(registry.releasable(now).is_empty());
+        let later = now + Duration::seconds(3600);
+        assert_eq!(registry.releasable(later), vec!["validator_001"]);
+        registry.release_stake("validator_001", later).unwrap();
+        assert!(matches!(
+            registry.release_stake("validator_001", later),
+            Err(RegistryError::AlreadyReleased(_))
+        ));
+        assert!(registry.releasable(later).is_empty());
+    }
+}
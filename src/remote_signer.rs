@@ -0,0 +1,722 @@
+// src/remote_signer.rs
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use x25519_dalek::{EphemeralSecret, PublicKey};
+
+use crate::ids::{ProposalId, VoterId};
+use crate::messages::{self, MessageKey};
+use crate::payload::{PAYLOAD_VERSION, VotePayload};
+use crate::vote::{DecayType, SignedVote, VoteChoice};
+
+/// Domain string mixed into every handshake transcript
+pub const HANDSHAKE_DOMAIN: &str = "tdc-remote-signer/v2";
+
+/// How long either side waits on a read or write before dropping the connection
+pub const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Error, Debug)]
+pub enum RemoteSignerError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Malformed message: {0}")]
+    Malformed(String),
+    #[error("Connection closed by peer")]
+    ConnectionClosed,
+    #[error("Peer authentication failed")]
+    AuthenticationFailed,
+    #[error("Frame failed its session MAC or arrived out of order")]
+    BadFrame,
+    #[error("Client key is not authorized")]
+    UnauthorizedClient,
+    #[error("Signing refused by policy: {0}")]
+    PolicyViolation(String),
+    #[error("Signer returned an invalid signature")]
+    InvalidSignature,
+    #[error("Remote signer error: {0}")]
+    Remote(String),
+}
+
+/// Rules controlling what the signer daemon is willing to sign
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SignerPolicy {
+    /// Hex-encoded identity keys of nodes allowed to request signatures
+    pub allowed_clients: Vec<String>,
+    /// Voter ids the signer will sign for (empty = every key it holds)
+    #[serde(default)]
+    pub allowed_voters: Vec<VoterId>,
+    /// Proposal id prefixes the signer will sign for (empty = any proposal)
+    #[serde(default)]
+    pub allowed_proposal_prefixes: Vec<String>,
+    /// Maximum distance between a requested timestamp and the signer's clock
+    #[serde(default = "default_max_skew")]
+    pub max_timestamp_skew_secs: i64,
+    /// Largest original weight the signer will sign for (unset = any weight)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_weight: Option<f64>,
+    /// Decay models the signer will sign for (empty = any model)
+    #[serde(default)]
+    pub allowed_decay_models: Vec<DecayType>,
+}
+
+fn default_max_skew() -> i64 {
+    30
+}
+
+impl SignerPolicy {
+    /// Load a policy from a JSON file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, RemoteSignerError> {
+        let raw = std::fs::read_to_string(path)?;
+        crate::schema::parse(&raw).map_err(|e| RemoteSignerError::Malformed(e.to_string()))
+    }
+
+    pub fn is_client_allowed(&self, client: &VerifyingKey) -> bool {
+        let client_hex = hex::encode(client.as_bytes());
+        self.allowed_clients
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&client_hex))
+    }
+
+    /// Check a signing request against the policy, returning the refusal reason. Once a
+    /// weight or decay limit is set, version 1 requests, which commit to neither, are refused.
+    pub fn check(&self, request: &SignRequest, now: DateTime<Utc>) -> Result<(), String> {
+        if !self.allowed_voters.is_empty() && !self.allowed_voters.contains(&request.voter_id) {
+            return Err(format!("voter {} is not allowed", request.voter_id));
+        }
+
+        if !self.allowed_proposal_prefixes.is_empty()
+            && !self
+                .allowed_proposal_prefixes
+                .iter()
+                .any(|prefix| request.proposal_id.starts_with(prefix.as_str()))
+        {
+            return Err(format!("proposal {} is not allowed", request.proposal_id));
+        }
+
+        let skew = (now - request.timestamp).num_seconds().abs();
+        if skew > self.max_timestamp_skew_secs {
+            return Err(format!("timestamp is {}s away from signer clock", skew));
+        }
+
+        if let Some(max_weight) = self.max_weight {
+            match request.original_weight {
+                Some(weight) if weight <= max_weight => {}
+                Some(weight) => return Err(format!("weight {} is above the limit of {}", weight, max_weight)),
+                None => return Err("request does not commit to a weight".to_string()),
+            }
+        }
+
+        if !self.allowed_decay_models.is_empty() {
+            match request.decay_model {
+                Some(model) if self.allowed_decay_models.contains(&model) => {}
+                Some(model) => return Err(format!("decay model {:?} is not allowed", model)),
+                None => return Err("request does not commit to a decay model".to_string()),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Fields of a vote the node asks the signer to sign
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignRequest {
+    pub voter_id: VoterId,
+    pub proposal_id: ProposalId,
+    pub timestamp: DateTime<Utc>,
+    /// Requests from nodes that predate choices are approvals
+    #[serde(default, skip_serializing_if = "VoteChoice::is_approve")]
+    pub choice: VoteChoice,
+    /// Covered by the signature along with `decay_model`; requests from older nodes
+    /// carry neither and get a version 1 signature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_weight: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decay_model: Option<DecayType>,
+}
+
+impl SignRequest {
+    /// Canonical payload signed for this request
+    pub fn message(&self) -> String {
+        let payload = VotePayload { choice: self.choice, ..VotePayload::new(&self.voter_id, &self.proposal_id, self.timestamp.into()) };
+        match (self.original_weight, self.decay_model) {
+            (Some(weight), Some(decay_model)) => payload.covering(weight, decay_model).to_string(),
+            _ => payload.to_string(),
+        }
+    }
+}
+
+/// Wire messages, exchanged as one JSON object per line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerMessage {
+    Hello {
+        public_key: String,
+        nonce: String,
+        /// Hex x25519 key for this connection only
+        ephemeral: String,
+    },
+    HelloAck {
+        public_key: String,
+        nonce: String,
+        ephemeral: String,
+        signature: String,
+    },
+    Auth {
+        signature: String,
+    },
+    Sign(SignRequest),
+    Signature {
+        signature: String,
+        public_key: String,
+    },
+    Error {
+        reason: String,
+    },
+    /// Every message after the handshake, wrapped with its sequence number and a MAC
+    /// under the sending direction's session key
+    Frame {
+        seq: u64,
+        payload: String,
+        mac: String,
+    },
+}
+
+/// Nonces and ephemeral keys of both sides, signed by each identity key so the session
+/// key derived from the ephemerals belongs to the authenticated peers
+struct Handshake {
+    client_nonce: String,
+    server_nonce: String,
+    client_ephemeral: String,
+    server_ephemeral: String,
+}
+
+impl Handshake {
+    fn transcript(&self, role: &str) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}",
+            HANDSHAKE_DOMAIN, role, self.client_nonce, self.server_nonce, self.client_ephemeral, self.server_ephemeral
+        )
+    }
+
+    /// Split the x25519 shared secret into one MAC key per direction, salted with the
+    /// server transcript so each connection gets fresh keys
+    fn session(&self, secret: EphemeralSecret, peer: &str, role: &str) -> Result<Session, RemoteSignerError> {
+        let peer: [u8; 32] = hex::decode(peer)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| RemoteSignerError::Malformed("ephemeral key".to_string()))?;
+        let shared = secret.diffie_hellman(&PublicKey::from(peer));
+        let kdf = Hkdf::<Sha256>::new(Some(self.transcript("session").as_bytes()), shared.as_bytes());
+        let key = |direction: &str| {
+            let mut key = [0u8; 32];
+            kdf.expand(direction.as_bytes(), &mut key).expect("32 bytes is a valid HKDF-SHA256 output length");
+            key
+        };
+        let (client, server) = (key("client->server"), key("server->client"));
+        Ok(match role {
+            "client" => Session { send_key: client, receive_key: server, send_seq: 0, receive_seq: 0 },
+            _ => Session { send_key: server, receive_key: client, send_seq: 0, receive_seq: 0 },
+        })
+    }
+}
+
+fn ephemeral_keypair() -> (EphemeralSecret, String) {
+    let secret = EphemeralSecret::random_from_rng(OsRng);
+    let public = hex::encode(PublicKey::from(&secret).as_bytes());
+    (secret, public)
+}
+
+/// Per-connection MAC keys and sequence numbers; a frame that fails its MAC, replays,
+/// or skips a sequence number ends the session
+struct Session {
+    send_key: [u8; 32],
+    receive_key: [u8; 32],
+    send_seq: u64,
+    receive_seq: u64,
+}
+
+fn frame_mac(key: &[u8; 32], seq: u64, payload: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(&seq.to_be_bytes());
+    mac.update(payload.as_bytes());
+    mac
+}
+
+impl Session {
+    fn send(&mut self, stream: &mut TcpStream, message: &SignerMessage) -> Result<(), RemoteSignerError> {
+        let payload = serde_json::to_string(message).map_err(|e| RemoteSignerError::Malformed(e.to_string()))?;
+        let seq = self.send_seq;
+        self.send_seq += 1;
+        let mac = hex::encode(frame_mac(&self.send_key, seq, &payload).finalize().into_bytes());
+        send(stream, &SignerMessage::Frame { seq, payload, mac })
+    }
+
+    fn receive(&mut self, reader: &mut BufReader<TcpStream>) -> Result<SignerMessage, RemoteSignerError> {
+        let (seq, payload, mac) = match receive(reader)? {
+            SignerMessage::Frame { seq, payload, mac } => (seq, payload, mac),
+            _ => return Err(RemoteSignerError::BadFrame),
+        };
+        let tag = hex::decode(mac).map_err(|_| RemoteSignerError::BadFrame)?;
+        if seq != self.receive_seq || frame_mac(&self.receive_key, seq, &payload).verify_slice(&tag).is_err() {
+            return Err(RemoteSignerError::BadFrame);
+        }
+        self.receive_seq += 1;
+        match serde_json::from_str(&payload).map_err(|e| RemoteSignerError::Malformed(e.to_string()))? {
+            SignerMessage::Frame { .. } => Err(RemoteSignerError::BadFrame),
+            message => Ok(message),
+        }
+    }
+}
+
+fn set_timeouts(stream: &TcpStream) -> Result<(), RemoteSignerError> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    Ok(())
+}
+
+fn fresh_nonce() -> String {
+    let mut nonce = [0u8; 32];
+    OsRng.fill_bytes(&mut nonce);
+    hex::encode(nonce)
+}
+
+fn decode_key(encoded: &str) -> Result<VerifyingKey, RemoteSignerError> {
+    let bytes: [u8; 32] = hex::decode(encoded)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| RemoteSignerError::Malformed("public key".to_string()))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| RemoteSignerError::Malformed("public key".to_string()))
+}
+
+fn decode_signature(encoded: &str) -> Result<Signature, RemoteSignerError> {
+    let bytes: [u8; 64] = hex::decode(encoded)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| RemoteSignerError::Malformed("signature".to_string()))?;
+    Ok(Signature::from_bytes(&bytes))
+}
+
+fn send(stream: &mut TcpStream, message: &SignerMessage) -> Result<(), RemoteSignerError> {
+    let mut line =
+        serde_json::to_string(message).map_err(|e| RemoteSignerError::Malformed(e.to_string()))?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+fn receive(reader: &mut BufReader<TcpStream>) -> Result<SignerMessage, RemoteSignerError> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(RemoteSignerError::ConnectionClosed);
+    }
+    serde_json::from_str(&line).map_err(|e| RemoteSignerError::Malformed(e.to_string()))
+}
+
+/// Signer daemon holding voter keys away from the consensus node
+pub struct RemoteSignerServer {
+    identity: SigningKey,
+    voter_keys: HashMap<VoterId, SigningKey>,
+    policy: SignerPolicy,
+}
+
+impl RemoteSignerServer {
+    pub fn new(identity: SigningKey, policy: SignerPolicy) -> Self {
+        Self {
+            identity,
+            voter_keys: HashMap::new(),
+            policy,
+        }
+    }
+
+    /// Register a voter key the daemon may sign with
+    pub fn add_voter_key(&mut self, voter_id: VoterId, key: SigningKey) {
+        self.voter_keys.insert(voter_id, key);
+    }
+
+    /// Identity key clients pin to authenticate this signer
+    pub fn identity_key(&self) -> VerifyingKey {
+        self.identity.verifying_key()
+    }
+
+    /// Accept connections forever, each on its own thread, so a stalled client only
+    /// holds up itself until `IO_TIMEOUT` drops it
+    pub fn serve(&self, listener: TcpListener) {
+        std::thread::scope(|scope| {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        scope.spawn(move || {
+                            if let Err(e) = self.handle_connection(stream) {
+                                println!("{}", messages::text(MessageKey::SignerConnectionFailed, &[("error", e.to_string())]));
+                            }
+                        });
+                    }
+                    Err(e) => println!("{}", messages::text(MessageKey::SignerAcceptFailed, &[("error", e.to_string())])),
+                }
+            }
+        });
+    }
+
+    /// Run the mutual handshake, then answer MAC'd signing requests until the client hangs up
+    pub fn handle_connection(&self, stream: TcpStream) -> Result<(), RemoteSignerError> {
+        set_timeouts(&stream)?;
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+
+        let (client_key, client_nonce, client_ephemeral) = match receive(&mut reader)? {
+            SignerMessage::Hello { public_key, nonce, ephemeral } => (decode_key(&public_key)?, nonce, ephemeral),
+            other => return Err(RemoteSignerError::Malformed(format!("expected hello, got {:?}", other))),
+        };
+
+        if !self.policy.is_client_allowed(&client_key) {
+            send(&mut writer, &SignerMessage::Error {
+                reason: "client not authorized".to_string(),
+            })?;
+            return Err(RemoteSignerError::UnauthorizedClient);
+        }
+
+        let (secret, server_ephemeral) = ephemeral_keypair();
+        let handshake = Handshake { client_nonce, server_nonce: fresh_nonce(), client_ephemeral, server_ephemeral };
+        let proof = self.identity.sign(handshake.transcript("server").as_bytes());
+        send(&mut writer, &SignerMessage::HelloAck {
+            public_key: hex::encode(self.identity_key().as_bytes()),
+            nonce: handshake.server_nonce.clone(),
+            ephemeral: handshake.server_ephemeral.clone(),
+            signature: hex::encode(proof.to_bytes()),
+        })?;
+
+        let client_proof = match receive(&mut reader)? {
+            SignerMessage::Auth { signature } => decode_signature(&signature)?,
+            other => return Err(RemoteSignerError::Malformed(format!("expected auth, got {:?}", other))),
+        };
+        client_key
+            .verify(handshake.transcript("client").as_bytes(), &client_proof)
+            .map_err(|_| RemoteSignerError::AuthenticationFailed)?;
+        let mut session = handshake.session(secret, &handshake.client_ephemeral, "server")?;
+
+        loop {
+            let request = match session.receive(&mut reader) {
+                Ok(SignerMessage::Sign(request)) => request,
+                Ok(other) => {
+                    return Err(RemoteSignerError::Malformed(format!("expected sign, got {:?}", other)));
+                }
+                Err(RemoteSignerError::ConnectionClosed) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+
+            let reply = match self.sign(&request, Utc::now()) {
+                Ok((signature, public_key)) => SignerMessage::Signature {
+                    signature: hex::encode(signature.to_bytes()),
+                    public_key: hex::encode(public_key.as_bytes()),
+                },
+                Err(reason) => SignerMessage::Error { reason },
+            };
+            session.send(&mut writer, &reply)?;
+        }
+    }
+
+    fn sign(&self, request: &SignRequest, now: DateTime<Utc>) -> Result<(Signature, VerifyingKey), String> {
+        self.policy.check(request, now)?;
+        let key = self
+            .voter_keys
+            .get(&request.voter_id)
+            .ok_or_else(|| format!("no key held for voter {}", request.voter_id))?;
+        Ok((key.sign(request.message().as_bytes()), key.verifying_key()))
+    }
+}
+
+/// Node-side connection to an authenticated signer daemon
+pub struct RemoteSignerClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    session: Session,
+}
+
+impl RemoteSignerClient {
+    /// Connect and mutually authenticate against a pinned signer identity key
+    pub fn connect(
+        addr: impl ToSocketAddrs,
+        identity: &SigningKey,
+        server_key: &VerifyingKey,
+    ) -> Result<Self, RemoteSignerError> {
+        let mut writer = TcpStream::connect(addr)?;
+        set_timeouts(&writer)?;
+        let mut reader = BufReader::new(writer.try_clone()?);
+
+        let client_nonce = fresh_nonce();
+        let (secret, client_ephemeral) = ephemeral_keypair();
+        send(&mut writer, &SignerMessage::Hello {
+            public_key: hex::encode(identity.verifying_key().as_bytes()),
+            nonce: client_nonce.clone(),
+            ephemeral: client_ephemeral.clone(),
+        })?;
+
+        let (public_key, server_nonce, server_ephemeral, proof) = match receive(&mut reader)? {
+            SignerMessage::HelloAck { public_key, nonce, ephemeral, signature } => (public_key, nonce, ephemeral, signature),
+            SignerMessage::Error { reason } => return Err(RemoteSignerError::Remote(reason)),
+            other => return Err(RemoteSignerError::Malformed(format!("expected hello_ack, got {:?}", other))),
+        };
+
+        if decode_key(&public_key)? != *server_key {
+            return Err(RemoteSignerError::AuthenticationFailed);
+        }
+        let handshake = Handshake { client_nonce, server_nonce, client_ephemeral, server_ephemeral };
+        server_key
+            .verify(handshake.transcript("server").as_bytes(), &decode_signature(&proof)?)
+            .map_err(|_| RemoteSignerError::AuthenticationFailed)?;
+
+        let client_proof = identity.sign(handshake.transcript("client").as_bytes());
+        send(&mut writer, &SignerMessage::Auth {
+            signature: hex::encode(client_proof.to_bytes()),
+        })?;
+        let session = handshake.session(secret, &handshake.server_ephemeral, "client")?;
+
+        Ok(Self { reader, writer, session })
+    }
+
+    /// Ask the signer for a version 1 signature over voter, proposal, and timestamp, checking it before returning
+    pub fn request_signature(
+        &mut self,
+        voter_id: &VoterId,
+        proposal_id: &ProposalId,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(Signature, VerifyingKey), RemoteSignerError> {
+        self.sign_request(&SignRequest {
+            voter_id: voter_id.clone(),
+            proposal_id: proposal_id.clone(),
+            timestamp,
+            choice: VoteChoice::Approve,
+            original_weight: None,
+            decay_model: None,
+        })
+    }
+
+    /// Send `request` to the signer and check the signature it returns
+    pub fn sign_request(&mut self, request: &SignRequest) -> Result<(Signature, VerifyingKey), RemoteSignerError> {
+        self.session.send(&mut self.writer, &SignerMessage::Sign(request.clone()))?;
+
+        let (signature, public_key) = match self.session.receive(&mut self.reader)? {
+            SignerMessage::Signature { signature, public_key } => {
+                (decode_signature(&signature)?, decode_key(&public_key)?)
+            }
+            SignerMessage::Error { reason } => return Err(RemoteSignerError::PolicyViolation(reason)),
+            other => return Err(RemoteSignerError::Malformed(format!("expected signature, got {:?}", other))),
+        };
+
+        public_key
+            .verify(request.message().as_bytes(), &signature)
+            .map_err(|_| RemoteSignerError::InvalidSignature)?;
+
+        Ok((signature, public_key))
+    }
+
+    /// Build a complete `SignedVote` using a remotely held key
+    pub fn sign_vote(
+        &mut self,
+        voter_id: VoterId,
+        proposal_id: ProposalId,
+        original_weight: f64,
+        timestamp: DateTime<Utc>,
+        decay_model: DecayType,
+        choice: VoteChoice,
+    ) -> Result<SignedVote, RemoteSignerError> {
+        let (signature, public_key) = self.sign_request(&SignRequest {
+            voter_id: voter_id.clone(),
+            proposal_id: proposal_id.clone(),
+            timestamp,
+            choice,
+            original_weight: Some(original_weight),
+            decay_model: Some(decay_model),
+        })?;
+        Ok(SignedVote {
+            voter_id,
+            proposal_id,
+            timestamp,
+            original_weight,
+            decay_model,
+            decay_params: None,
+            choice,
+            version: 0,
+            nonce: 0,
+            metadata: Default::default(),
+            stake: None,
+            expires_at: None,
+            payload_version: PAYLOAD_VERSION,
+            signature,
+            public_key,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn policy_for(client: &SigningKey) -> SignerPolicy {
+        SignerPolicy {
+            allowed_clients: vec![hex::encode(client.verifying_key().as_bytes())],
+            allowed_voters: vec!["alice".parse().unwrap()],
+            allowed_proposal_prefixes: vec!["gov-".to_string()],
+            max_timestamp_skew_secs: 30,
+            max_weight: None,
+            allowed_decay_models: Vec::new(),
+        }
+    }
+
+    fn spawn_signer(
+        policy: SignerPolicy,
+    ) -> (std::net::SocketAddr, VerifyingKey, thread::JoinHandle<Result<(), RemoteSignerError>>) {
+        let mut server = RemoteSignerServer::new(SignedVote::generate_keypair(), policy);
+        server.add_voter_key("alice".parse().unwrap(), SignedVote::generate_keypair());
+        let server_key = server.identity_key();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            server.handle_connection(stream)
+        });
+        (addr, server_key, handle)
+    }
+
+    #[test]
+    fn test_remote_signed_vote_verifies() {
+        let client_identity = SignedVote::generate_keypair();
+        let (addr, server_key, handle) = spawn_signer(policy_for(&client_identity));
+
+        let mut client = RemoteSignerClient::connect(addr, &client_identity, &server_key).unwrap();
+        let vote = client
+            .sign_vote("alice".parse().unwrap(), "gov-1".parse().unwrap(), 1.0, Utc::now(), DecayType::Linear, VoteChoice::Approve)
+            .unwrap();
+        assert_eq!(vote.verify(300), Ok(()));
+        let reject = client
+            .sign_vote("alice".parse().unwrap(), "gov-1".parse().unwrap(), 1.0, Utc::now(), DecayType::Linear, VoteChoice::Reject)
+            .unwrap();
+        assert_eq!((reject.choice, reject.verify(300)), (VoteChoice::Reject, Ok(())));
+        // The signature covers the choice, so flipping it is caught
+        assert!(SignedVote { choice: VoteChoice::Approve, ..reject }.verify(300).is_err());
+
+        drop(client);
+        assert!(handle.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_frames_without_the_session_mac_are_rejected() {
+        let client_identity = SignedVote::generate_keypair();
+        let (addr, server_key, handle) = spawn_signer(policy_for(&client_identity));
+
+        let mut client = RemoteSignerClient::connect(addr, &client_identity, &server_key).unwrap();
+        let request = SignRequest {
+            voter_id: "alice".parse().unwrap(),
+            proposal_id: "gov-1".parse().unwrap(),
+            timestamp: Utc::now(),
+            choice: VoteChoice::Reject,
+            original_weight: None,
+            decay_model: None,
+        };
+        // An injected bare request, then a frame whose MAC was made with the wrong key
+        send(&mut client.writer, &SignerMessage::Sign(request.clone())).unwrap();
+        assert!(matches!(handle.join().unwrap(), Err(RemoteSignerError::BadFrame)));
+
+        let (addr, server_key, handle) = spawn_signer(policy_for(&client_identity));
+        let mut client = RemoteSignerClient::connect(addr, &client_identity, &server_key).unwrap();
+        client.session.send_key = [7u8; 32];
+        assert!(client.sign_request(&request).is_err());
+        assert!(matches!(handle.join().unwrap(), Err(RemoteSignerError::BadFrame)));
+    }
+
+    #[test]
+    fn test_policy_refuses_request() {
+        let client_identity = SignedVote::generate_keypair();
+        let (addr, server_key, _handle) = spawn_signer(policy_for(&client_identity));
+
+        let mut client = RemoteSignerClient::connect(addr, &client_identity, &server_key).unwrap();
+        let alice = "alice".parse().unwrap();
+        let wrong_proposal = client.request_signature(&alice, &"other-1".parse().unwrap(), Utc::now());
+        assert!(matches!(wrong_proposal, Err(RemoteSignerError::PolicyViolation(_))));
+
+        let backdated = client.request_signature(&alice, &"gov-1".parse().unwrap(), Utc::now() - chrono::Duration::seconds(600));
+        assert!(matches!(backdated, Err(RemoteSignerError::PolicyViolation(_))));
+    }
+
+    #[test]
+    fn test_policy_limits_weight_and_decay_model() {
+        let client_identity = SignedVote::generate_keypair();
+        let policy = SignerPolicy {
+            max_weight: Some(2.0),
+            allowed_decay_models: vec![DecayType::Linear],
+            ..policy_for(&client_identity)
+        };
+        let (addr, server_key, _handle) = spawn_signer(policy);
+
+        let mut client = RemoteSignerClient::connect(addr, &client_identity, &server_key).unwrap();
+        let mut sign = |weight, decay| {
+            client.sign_vote("alice".parse().unwrap(), "gov-1".parse().unwrap(), weight, Utc::now(), decay, VoteChoice::Approve)
+        };
+        assert!(sign(2.0, DecayType::Linear).is_ok());
+        assert!(matches!(sign(50.0, DecayType::Linear), Err(RemoteSignerError::PolicyViolation(_))));
+        assert!(matches!(sign(f64::NAN, DecayType::Linear), Err(RemoteSignerError::PolicyViolation(_))));
+        assert!(matches!(sign(1.0, DecayType::Exponential), Err(RemoteSignerError::PolicyViolation(_))));
+        // A version 1 request commits to no weight, so it cannot be held to the limit
+        let legacy = client.request_signature(&"alice".parse().unwrap(), &"gov-1".parse().unwrap(), Utc::now());
+        assert!(matches!(legacy, Err(RemoteSignerError::PolicyViolation(_))));
+    }
+
+    #[test]
+    fn test_requests_with_invalid_ids_do_not_parse() {
+        let raw = r#"{"type": "sign", "voter_id": "alice", "proposal_id": "gov-1", "timestamp": "2026-01-01T00:00:00Z"}"#;
+        assert!(serde_json::from_str::<SignerMessage>(raw).is_ok());
+        // The `:` separator would let the signed message be read as another id pair
+        let spliced = raw.replace("\"alice\"", "\"alice:gov-2\"");
+        assert!(serde_json::from_str::<SignerMessage>(&spliced).is_err());
+    }
+
+    #[test]
+    fn test_unauthorized_client_rejected() {
+        let allowed = SignedVote::generate_keypair();
+        let intruder = SignedVote::generate_keypair();
+        let (addr, server_key, handle) = spawn_signer(policy_for(&allowed));
+
+        let result = RemoteSignerClient::connect(addr, &intruder, &server_key);
+        assert!(matches!(result, Err(RemoteSignerError::Remote(_))));
+        assert!(matches!(handle.join().unwrap(), Err(RemoteSignerError::UnauthorizedClient)));
+    }
+
+    #[test]
+    fn test_client_rejects_unpinned_signer() {
+        let client_identity = SignedVote::generate_keypair();
+        let (addr, _server_key, _handle) = spawn_signer(policy_for(&client_identity));
+
+        let impostor_key = SignedVote::generate_keypair().verifying_key();
+        let result = RemoteSignerClient::connect(addr, &client_identity, &impostor_key);
+        assert!(matches!(result, Err(RemoteSignerError::AuthenticationFailed)));
+    }
+
+    #[test]
+    fn test_policy_file_defaults() {
+        let policy: SignerPolicy = serde_json::from_str(r#"{"allowed_clients": []}"#).unwrap();
+        assert!(policy.allowed_voters.is_empty());
+        assert_eq!(policy.max_timestamp_skew_secs, 30);
+        assert_eq!(policy.max_weight, None);
+        assert!(policy.allowed_decay_models.is_empty());
+    }
+}
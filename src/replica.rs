@@ -0,0 +1,331 @@
+// src/replica.rs
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::blockchain::{Block, Blockchain};
+use crate::progress::CancellationToken;
+
+/// Most blocks returned by one `/chain/sync` request
+pub const SYNC_PAGE_SIZE: usize = 100;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ReplicaError {
+    #[error("Peer {peer} is unreachable: {reason}")]
+    Unreachable { peer: String, reason: String },
+    #[error("Peer {peer} sent an invalid block: {reason}")]
+    InvalidBlock { peer: String, reason: String },
+    #[error("No peers configured")]
+    NoPeers,
+}
+
+/// Blocks from `from` onward, as served at `/chain/sync?from=`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SyncPage {
+    pub difficulty: usize,
+    /// Length of the serving chain, so a replica knows how far behind it is
+    pub length: u64,
+    pub blocks: Vec<Block>,
+}
+
+impl SyncPage {
+    pub fn from_chain(chain: &Blockchain, from: u64) -> Self {
+        let from = (from as usize).min(chain.blocks.len());
+        let to = (from + SYNC_PAGE_SIZE).min(chain.blocks.len());
+        Self {
+            difficulty: chain.difficulty,
+            length: chain.blocks.len() as u64,
+            blocks: chain.blocks[from..to].to_vec(),
+        }
+    }
+}
+
+/// Somewhere a replica can read blocks from
+pub trait PeerSource: Send + Sync {
+    fn name(&self) -> String;
+    fn fetch(&self, from: u64) -> Result<SyncPage, ReplicaError>;
+}
+
+/// A full node serving the HTTP API
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpPeer {
+    pub host: String,
+}
+
+impl HttpPeer {
+    pub fn new(host: &str) -> Self {
+        Self {
+            host: host.trim_start_matches("http://").trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn unreachable(&self, reason: impl ToString) -> ReplicaError {
+        ReplicaError::Unreachable {
+            peer: self.host.clone(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl PeerSource for HttpPeer {
+    fn name(&self) -> String {
+        self.host.clone()
+    }
+
+    fn fetch(&self, from: u64) -> Result<SyncPage, ReplicaError> {
+        let mut stream = TcpStream::connect(&self.host).map_err(|e| self.unreachable(e))?;
+        write!(
+            stream,
+            "GET /chain/sync?from={} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            from, self.host
+        )
+        .map_err(|e| self.unreachable(e))?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).map_err(|e| self.unreachable(e))?;
+
+        let (head, body) = response.split_once("\r\n\r\n").ok_or_else(|| self.unreachable("malformed response"))?;
+        if !head.starts_with("HTTP/1.1 200") {
+            return Err(self.unreachable(head.lines().next().unwrap_or_default()));
+        }
+        crate::schema::parse(body).map_err(|e| self.unreachable(e))
+    }
+}
+
+/// A chain in the same process, e.g. a co-located full node
+pub struct LocalPeer {
+    pub name: String,
+    pub chain: Arc<RwLock<Blockchain>>,
+}
+
+impl PeerSource for LocalPeer {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn fetch(&self, from: u64) -> Result<SyncPage, ReplicaError> {
+        Ok(SyncPage::from_chain(&self.chain.read().expect("chain lock poisoned"), from))
+    }
+}
+
+/// What a replica reports at `/replica/status`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplicaStatus {
+    pub peers: Vec<String>,
+    pub length: usize,
+    /// Longest chain any peer reported on the last sync
+    pub peer_length: u64,
+    pub blocks_synced: u64,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+pub type SharedReplicaStatus = Arc<RwLock<ReplicaStatus>>;
+
+/// A node that follows peers' chains and serves reads, but never admits votes or
+/// produces blocks: the only way its chain grows is by accepting peers' blocks, each of
+/// which must link to the local tip and meet the difficulty target.
+pub struct Replica {
+    chain: Arc<RwLock<Blockchain>>,
+    peers: Vec<Box<dyn PeerSource>>,
+    status: SharedReplicaStatus,
+}
+
+impl Replica {
+    /// Start from the first reachable peer's genesis block and difficulty
+    pub fn bootstrap(peers: Vec<Box<dyn PeerSource>>) -> Result<Self, ReplicaError> {
+        let mut last_error = ReplicaError::NoPeers;
+        for peer in &peers {
+            let page = match peer.fetch(0) {
+                Ok(page) => page,
+                Err(e) => {
+                    last_error = e;
+                    continue;
+                }
+            };
+            let Some(genesis) = page.blocks.first() else {
+                last_error = ReplicaError::InvalidBlock {
+                    peer: peer.name(),
+                    reason: "no genesis block".to_string(),
+                };
+                continue;
+            };
+            let chain = Blockchain {
+                blocks: vec![genesis.clone()],
+                difficulty: page.difficulty,
+                mining_threads: 1,
+            };
+            return Ok(Self::new(chain, peers));
+        }
+        Err(last_error)
+    }
+
+    /// Follow `peers` from an existing chain, e.g. one loaded from disk
+    pub fn new(chain: Blockchain, peers: Vec<Box<dyn PeerSource>>) -> Self {
+        let status = ReplicaStatus {
+            peers: peers.iter().map(|p| p.name()).collect(),
+            length: chain.blocks.len(),
+            ..ReplicaStatus::default()
+        };
+        Self {
+            chain: Arc::new(RwLock::new(chain)),
+            peers,
+            status: Arc::new(RwLock::new(status)),
+        }
+    }
+
+    /// Read handle for the API and exports
+    pub fn chain(&self) -> Arc<RwLock<Blockchain>> {
+        Arc::clone(&self.chain)
+    }
+
+    pub fn status(&self) -> SharedReplicaStatus {
+        Arc::clone(&self.status)
+    }
+
+    /// Pull new blocks from each peer in turn; returns how many were appended. A peer
+    /// that fails or sends a block that does not extend the tip is skipped.
+    pub fn sync_once(&self, now: DateTime<Utc>) -> Result<usize, ReplicaError> {
+        if self.peers.is_empty() {
+            return Err(ReplicaError::NoPeers);
+        }
+        let mut appended = 0;
+        let mut peer_length = 0;
+        let mut last_error = None;
+        for peer in &self.peers {
+            match self.sync_from(peer.as_ref()) {
+                Ok((count, length)) => {
+                    appended += count;
+                    peer_length = peer_length.max(length);
+                }
+                Err((count, e)) => {
+                    appended += count;
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        let mut status = self.status.write().expect("replica status lock poisoned");
+        status.length = self.chain.read().expect("chain lock poisoned").blocks.len();
+        status.peer_length = peer_length;
+        status.blocks_synced += appended as u64;
+        status.last_synced_at = Some(now);
+        status.last_error = last_error.as_ref().map(ToString::to_string);
+        match last_error {
+            Some(e) if appended == 0 && peer_length == 0 => Err(e),
+            _ => Ok(appended),
+        }
+    }
+
+    fn sync_from(&self, peer: &dyn PeerSource) -> Result<(usize, u64), (usize, ReplicaError)> {
+        let mut appended = 0;
+        loop {
+            let from = self.chain.read().expect("chain lock poisoned").blocks.len() as u64;
+            let page = peer.fetch(from).map_err(|e| (appended, e))?;
+            if page.blocks.is_empty() {
+                return Ok((appended, page.length));
+            }
+            let mut chain = self.chain.write().expect("chain lock poisoned");
+            for block in page.blocks {
+                chain.accept_block(block).map_err(|reason| {
+                    (appended, ReplicaError::InvalidBlock { peer: peer.name(), reason })
+                })?;
+                appended += 1;
+            }
+        }
+    }
+
+    /// Sync every `interval` on a background thread until `cancel` fires, handing each
+    /// round's outcome to `on_sync`
+    pub fn spawn_sync(
+        self: Arc<Self>,
+        interval: std::time::Duration,
+        cancel: CancellationToken,
+        on_sync: impl Fn(Result<usize, ReplicaError>) + Send + 'static,
+    ) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            let tick = interval.min(std::time::Duration::from_millis(50));
+            while !cancel.is_cancelled() {
+                on_sync(self.sync_once(Utc::now()));
+
+                let mut waited = std::time::Duration::ZERO;
+                while waited < interval && !cancel.is_cancelled() {
+                    std::thread::sleep(tick);
+                    waited += tick;
+                }
+            }
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full_node(blocks: usize) -> Arc<RwLock<Blockchain>> {
+        let mut chain = Blockchain::with_genesis("Genesis Block: replica".to_string(), 1);
+        for i in 0..blocks {
+            chain.add_block(format!("block {}", i));
+        }
+        Arc::new(RwLock::new(chain))
+    }
+
+    fn peer(name: &str, chain: &Arc<RwLock<Blockchain>>) -> Box<dyn PeerSource> {
+        Box::new(LocalPeer { name: name.to_string(), chain: Arc::clone(chain) })
+    }
+
+    #[test]
+    fn test_bootstrap_and_follow() {
+        let source = full_node(SYNC_PAGE_SIZE + 5);
+        let replica = Replica::bootstrap(vec![peer("full", &source)]).unwrap();
+        assert_eq!(replica.chain().read().unwrap().blocks.len(), 1);
+
+        let now = Utc::now();
+        assert_eq!(replica.sync_once(now), Ok(SYNC_PAGE_SIZE + 5));
+        source.write().unwrap().add_block("later".to_string());
+        assert_eq!(replica.sync_once(now), Ok(1));
+        assert_eq!(replica.sync_once(now), Ok(0));
+
+        let chain = replica.chain();
+        let chain = chain.read().unwrap();
+        assert!(chain.is_valid());
+        assert_eq!(chain.blocks.last().unwrap().hash, source.read().unwrap().blocks.last().unwrap().hash);
+        let status = replica.status().read().unwrap().clone();
+        assert_eq!((status.length, status.peer_length, status.blocks_synced), (SYNC_PAGE_SIZE + 7, (SYNC_PAGE_SIZE + 7) as u64, (SYNC_PAGE_SIZE + 6) as u64));
+    }
+
+    #[test]
+    fn test_forked_peer_is_rejected() {
+        let honest = full_node(2);
+        let forked = Arc::new(RwLock::new(Blockchain::with_genesis("Genesis Block: other".to_string(), 1)));
+        forked.write().unwrap().add_block("fork".to_string());
+
+        let replica = Replica::bootstrap(vec![peer("honest", &honest), peer("forked", &forked)]).unwrap();
+        assert_eq!(replica.sync_once(Utc::now()), Ok(2));
+        honest.write().unwrap().add_block("next".to_string());
+        forked.write().unwrap().add_block("fork 2".to_string());
+        forked.write().unwrap().add_block("fork 3".to_string());
+        forked.write().unwrap().add_block("fork 4".to_string());
+
+        assert_eq!(replica.sync_once(Utc::now()), Ok(1));
+        let status = replica.status().read().unwrap().clone();
+        assert!(status.last_error.unwrap().contains("forked sent an invalid block"));
+        assert_eq!(replica.chain().read().unwrap().blocks.len(), 4);
+        assert!(matches!(Replica::bootstrap(Vec::new()), Err(ReplicaError::NoPeers)));
+    }
+
+    #[test]
+    fn test_http_peer_unreachable() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        assert!(matches!(HttpPeer::new(&format!("http://{}/", addr)).fetch(0), Err(ReplicaError::Unreachable { .. })));
+    }
+}
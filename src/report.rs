@@ -0,0 +1,1267 @@
+// src/report.rs
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::delegation::{DelegationGraph, FallbackRegistry, FallbackTrigger, FallbackUse, VoteLineage};
+use crate::hashing;
+use crate::intake::{AdmissionLog, Channel, ChannelStats};
+use crate::rounds::RoundRecord;
+use crate::tally::{ApprovalIntegral, NormalizedTally, Tally};
+use crate::threshold::{DecisionRule, TallyMetric, ThresholdEscalator};
+use crate::trust::TrustSource;
+use crate::units::Seconds;
+use crate::vote::{ProposalType, SignedVote, VoteChoice};
+use crate::weight_engine::WeightEngine;
+
+/// Points sampled along the threshold curve between opening and finalization
+const CURVE_POINTS: i64 = 10;
+const TOP_CONTRIBUTORS: usize = 5;
+/// Intervals the approval curve is sampled at for time-weighted tallies
+const INTEGRAL_SAMPLES: i64 = 120;
+/// Share of total weight above which a single voter is flagged
+const CONCENTRATION_SHARE: f64 = 0.5;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LineageError {
+    #[error("Report for {0} has no valid certificate")]
+    InvalidCertificate(String),
+    #[error("{proposal_id} does not cite the certificate of {expected}")]
+    BrokenLink { proposal_id: String, expected: String },
+    #[error("Superseded report {0} is not in the archive")]
+    MissingPredecessor(String),
+    #[error("Lineage of {0} loops back on itself")]
+    Cycle(String),
+}
+
+/// One way a certificate's attestation differs from what an auditor expected
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AttestationMismatch {
+    #[error("Certificate carries no attestation")]
+    Missing,
+    #[error("Certified by version {certified}, expected {expected}")]
+    CrateVersion { certified: String, expected: String },
+    #[error("Parameter set hash {certified} does not match expected {expected}")]
+    Parameters { certified: String, expected: String },
+    #[error("Config digest {certified:?} does not match expected {expected:?}")]
+    Config { certified: Option<String>, expected: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposalMetadata {
+    pub proposal_id: String,
+    pub proposal_type: String,
+    pub opened_at: DateTime<Utc>,
+    pub finalized_at: DateTime<Utc>,
+    /// Earlier decision this proposal replaces
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<SupersededProposal>,
+    /// A practice run: the outcome binds no one
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub practice: bool,
+}
+
+/// Reference to a prior finalization, pinned by its certificate hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SupersededProposal {
+    pub proposal_id: String,
+    pub certificate_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportParameters {
+    pub base_threshold: f64,
+    pub ceiling: f64,
+    pub pattern: String,
+    pub profile: String,
+    pub emergency_override: bool,
+    pub min_vote_count: usize,
+    pub max_age_secs: i64,
+    /// Decimal places weight and threshold were rounded to before comparing
+    pub precision: Option<u32>,
+    pub tie_break: String,
+    /// Set when the decision used a tally other than the instantaneous one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric: Option<String>,
+    /// Block hash seeding `SeededRandom` tie-breaks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tie_break_seed: Option<String>,
+    /// Effective weight below which votes were left out as dust, when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_effective_weight: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contribution {
+    pub voter_id: String,
+    pub decay_model: String,
+    pub original_weight: f64,
+    pub trust_bonus: f64,
+    pub effective_weight: f64,
+    pub share: f64,
+    #[serde(default, skip_serializing_if = "VoteChoice::is_approve")]
+    pub choice: VoteChoice,
+    /// When the vote was cast; absent from reports built before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cast_at: Option<DateTime<Utc>>,
+    /// Set when this weight was routed to a fallback delegate rather than voted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_to: Option<String>,
+    /// Routes by which delegated or fallback weight reached this contribution
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lineage: Vec<VoteLineage>,
+}
+
+/// Valid votes left out of the tally for decaying below `min_effective_weight`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DustSummary {
+    pub votes: usize,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TallyBreakdown {
+    pub counted_votes: usize,
+    pub excluded_votes: usize,
+    pub total_weight: f64,
+    /// Approval minus opposing weight, set when any counted vote rejects or abstains;
+    /// the decision is made on this rather than `total_weight`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_weight: Option<f64>,
+    pub eligible_weight: f64,
+    pub normalized: NormalizedTally,
+    /// Effective weight per decay model
+    pub by_decay_model: BTreeMap<String, f64>,
+    /// Approval weight averaged over the window, when the decision used it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_weighted: Option<ApprovalIntegral>,
+    /// Set when any vote was too light to count; dust votes are also among `excluded_votes`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dust: Option<DustSummary>,
+    pub contributions: Vec<Contribution>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdPoint {
+    pub elapsed_secs: i64,
+    pub threshold: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Anomaly {
+    InvalidVote { voter_id: String, error: String },
+    DuplicateVote { voter_id: String },
+    OutsideWindow { voter_id: String, timestamp: DateTime<Utc> },
+    WeightConcentration { voter_id: String, share: f64 },
+}
+
+/// Which software and rules produced a report, signed into its certificate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attestation {
+    pub crate_version: String,
+    /// `hashing::parameters_hash` of the report's `ReportParameters`
+    pub parameters_hash: String,
+    /// Digest of the node configuration, when the issuer supplied one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_digest: Option<String>,
+}
+
+impl Attestation {
+    /// This build's version and `parameters`, with an optional `config_digest`
+    pub fn new(parameters: &ReportParameters, config_digest: Option<String>) -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            parameters_hash: parameters_hash(parameters),
+            config_digest,
+        }
+    }
+
+    /// Every field that differs from `expected`
+    pub fn compare(&self, expected: &Attestation) -> Vec<AttestationMismatch> {
+        let mut mismatches = Vec::new();
+        if self.crate_version != expected.crate_version {
+            mismatches.push(AttestationMismatch::CrateVersion {
+                certified: self.crate_version.clone(),
+                expected: expected.crate_version.clone(),
+            });
+        }
+        if self.parameters_hash != expected.parameters_hash {
+            mismatches.push(AttestationMismatch::Parameters {
+                certified: self.parameters_hash.clone(),
+                expected: expected.parameters_hash.clone(),
+            });
+        }
+        if self.config_digest != expected.config_digest {
+            mismatches.push(AttestationMismatch::Config {
+                certified: self.config_digest.clone(),
+                expected: expected.config_digest.clone(),
+            });
+        }
+        mismatches
+    }
+
+    fn signed_hash(&self, report_hash: &str) -> String {
+        hashing::attestation_hash(
+            report_hash,
+            &self.crate_version,
+            &self.parameters_hash,
+            self.config_digest.as_deref().unwrap_or(""),
+        )
+    }
+}
+
+/// Canonical hash of a parameter set, as attested in certificates
+pub fn parameters_hash(parameters: &ReportParameters) -> String {
+    hashing::parameters_hash(&serde_json::to_vec(parameters).expect("parameters serialize"))
+}
+
+/// Canonical digest of any serializable configuration (plugin config, signer policy, ...)
+pub fn config_digest(config: &impl Serialize) -> String {
+    hashing::config_digest(&serde_json::to_vec(config).expect("config serializes"))
+}
+
+/// Issuer signature over the canonical hash of the report body and, when present,
+/// the attestation of the software and rules that produced it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportCertificate {
+    pub report_hash: String,
+    pub issuer: String,
+    pub signature: String,
+    /// Absent from certificates issued before attestations were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<Attestation>,
+}
+
+impl ReportCertificate {
+    pub fn hash(&self) -> String {
+        hashing::certificate_hash(&self.report_hash, &self.issuer, &self.signature)
+    }
+}
+
+/// Self-contained record of how a proposal was finalized, for publishing to stakeholders
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinalizationReport {
+    pub proposal: ProposalMetadata,
+    pub parameters: ReportParameters,
+    pub passed: bool,
+    /// Weight equalled the threshold, so the tie-break rule decided `passed`
+    pub tied: bool,
+    pub final_threshold: f64,
+    pub tally: TallyBreakdown,
+    pub threshold_curve: Vec<ThresholdPoint>,
+    pub top_contributors: Vec<Contribution>,
+    pub anomalies: Vec<Anomaly>,
+    /// Intake statistics per ingestion channel, when attached
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub channels: BTreeMap<Channel, ChannelStats>,
+    /// Voting rounds, for proposals decided in multi-round mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rounds: Vec<RoundRecord>,
+    /// Weight routed to fallback delegates at close, also listed among the contributions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallbacks: Vec<FallbackUse>,
+    pub certificate: Option<ReportCertificate>,
+}
+
+/// Everything needed to build a report
+pub struct ReportInput<'a> {
+    pub proposal_id: &'a str,
+    pub proposal_type: ProposalType,
+    pub opened_at: DateTime<Utc>,
+    pub finalized_at: DateTime<Utc>,
+    pub votes: &'a [SignedVote],
+    pub trust: &'a dyn TrustSource,
+    pub max_age_secs: i64,
+    /// When this node received each vote; listed votes are checked against `max_age`
+    /// as of their receipt instead of the current clock
+    pub admissions: Option<&'a AdmissionLog>,
+    /// Total weight entitled to vote; `None` treats the counted weight as the whole electorate
+    pub eligible_weight: Option<f64>,
+    pub decision: DecisionRule,
+    /// Hash of a block every node agrees on, for seeded tie-breaks
+    pub tie_break_seed: Option<&'a str>,
+}
+
+impl FinalizationReport {
+    /// Tally the votes as of `finalized_at`. Invalid, duplicate, and out-of-window votes
+    /// are excluded from the tally and listed as anomalies.
+    pub fn build(input: ReportInput) -> Self {
+        Self::build_with_fallbacks(input, &FallbackRegistry::new())
+    }
+
+    /// Like `build`, routing the weight of voters who abstained or missed the window
+    /// to their registered fallback delegates. Fallback weight decays from `opened_at`
+    /// and is applied at close, so it does not enter a time-weighted average.
+    pub fn build_with_fallbacks(input: ReportInput, fallbacks: &FallbackRegistry) -> Self {
+        Self::build_with_routing(input, fallbacks, &DelegationGraph::new())
+    }
+
+    /// Like `build_with_fallbacks`, also counting power delegated to each voter by
+    /// voters who did not vote, with the chain it followed kept on the contribution.
+    /// A time-weighted average still uses direct weight only.
+    pub fn build_with_routing(input: ReportInput, fallbacks: &FallbackRegistry, delegations: &DelegationGraph) -> Self {
+        let mut escalator = ThresholdEscalator::for_proposal_type(input.proposal_type.clone());
+        escalator.decision = input.decision;
+        let mut weight_engine = WeightEngine::new();
+        let mut anomalies = Vec::new();
+        let mut seen = HashSet::new();
+        let mut contributions = Vec::new();
+        let mut counted = Vec::new();
+        let mut dust = DustSummary { votes: 0, weight: 0.0 };
+
+        let votes: Vec<&SignedVote> = input.votes.iter().filter(|v| v.proposal_id == input.proposal_id).collect();
+        let mut latest_version: HashMap<&str, u64> = HashMap::new();
+        for vote in &votes {
+            let latest = latest_version.entry(&vote.voter_id).or_insert(vote.version);
+            *latest = (*latest).max(vote.version);
+        }
+        for vote in &votes {
+            // Earlier versions were amended away, so they are neither counted nor anomalies
+            if vote.version < latest_version[vote.voter_id.as_str()] {
+                continue;
+            }
+            let verified = match input.admissions {
+                Some(log) => log.verify(vote, Seconds::new(input.max_age_secs), Utc::now()),
+                None => vote.verify(input.max_age_secs),
+            };
+            if let Err(e) = verified {
+                anomalies.push(Anomaly::InvalidVote {
+                    voter_id: vote.voter_id.to_string(),
+                    error: e.to_string(),
+                });
+                continue;
+            }
+            if vote.timestamp < input.opened_at || vote.timestamp > input.finalized_at {
+                anomalies.push(Anomaly::OutsideWindow {
+                    voter_id: vote.voter_id.to_string(),
+                    timestamp: vote.timestamp,
+                });
+                continue;
+            }
+            if !seen.insert(vote.voter_id.clone()) {
+                anomalies.push(Anomaly::DuplicateVote { voter_id: vote.voter_id.to_string() });
+                continue;
+            }
+
+            let (effective_weight, lineage) = if delegations.is_empty() {
+                (weight_engine.calculate_weight(vote, input.finalized_at, Some(input.trust)), Vec::new())
+            } else {
+                let explanation =
+                    weight_engine.explain_delegated(vote, input.finalized_at, Some(input.trust), None, delegations, input.votes);
+                (explanation.final_weight.get(), explanation.lineage)
+            };
+            if input.decision.is_dust(effective_weight) {
+                dust.votes += 1;
+                dust.weight += effective_weight;
+                continue;
+            }
+
+            counted.push(*vote);
+            contributions.push(Contribution {
+                voter_id: vote.voter_id.to_string(),
+                decay_model: format!("{:?}", vote.decay_model),
+                original_weight: vote.original_weight,
+                trust_bonus: input.trust.get_bonus(&vote.voter_id),
+                effective_weight,
+                share: 0.0,
+                choice: vote.choice,
+                cast_at: Some(vote.timestamp),
+                fallback_to: None,
+                lineage,
+            });
+        }
+        let direct_votes = contributions.len();
+
+        let fallbacks = match input.proposal_id.parse() {
+            Ok(proposal_id) => fallbacks.resolve(&proposal_id, input.opened_at, input.finalized_at, &counted),
+            Err(_) => Vec::new(),
+        };
+        for fallback in &fallbacks {
+            if fallback.trigger == FallbackTrigger::Abstained {
+                contributions.retain(|c| c.voter_id != fallback.voter_id.as_str());
+            }
+            let trust_bonus = input.trust.get_bonus(&fallback.voter_id);
+            contributions.push(Contribution {
+                voter_id: fallback.voter_id.to_string(),
+                decay_model: "Fallback".to_string(),
+                original_weight: fallback.original_weight,
+                trust_bonus,
+                effective_weight: fallback.decayed_weight * trust_bonus,
+                share: 0.0,
+                choice: fallback.choice,
+                cast_at: None,
+                fallback_to: Some(fallback.delegate.to_string()),
+                lineage: vec![fallback.lineage()],
+            });
+        }
+
+        let total_weight: f64 = contributions.iter().map(|c| c.effective_weight).sum();
+        let mut tally = Tally::new(input.eligible_weight.unwrap_or(total_weight));
+        for c in &contributions {
+            tally.record(c.choice, c.effective_weight);
+        }
+        let net_weight = contributions.iter().any(|c| !c.choice.is_approve()).then(|| tally.net_weight());
+        let mut by_decay_model = BTreeMap::new();
+        for c in &mut contributions {
+            if total_weight > 0.0 {
+                c.share = c.effective_weight / total_weight;
+            }
+            *by_decay_model.entry(c.decay_model.clone()).or_insert(0.0) += c.effective_weight;
+            if c.share > CONCENTRATION_SHARE {
+                anomalies.push(Anomaly::WeightConcentration {
+                    voter_id: c.voter_id.clone(),
+                    share: c.share,
+                });
+            }
+        }
+
+        let time_weighted = (input.decision.metric == TallyMetric::TimeWeighted).then(|| {
+            ApprovalIntegral::integrate(input.opened_at, input.finalized_at, INTEGRAL_SAMPLES, |at| {
+                let mut tally = Tally::new(0.0);
+                for v in counted.iter().filter(|v| v.timestamp <= at) {
+                    tally.record(v.choice, weight_engine.explain(v, at, Some(input.trust), None).final_weight.get());
+                }
+                tally.net_weight()
+            })
+        });
+
+        escalator.total_votes = direct_votes;
+        let final_threshold = escalator.threshold_with_profile(input.finalized_at, input.opened_at);
+        let decision = escalator.decide(
+            time_weighted.map_or(net_weight.unwrap_or(total_weight), |t| t.average),
+            final_threshold,
+            input.tie_break_seed.unwrap_or_default(),
+            input.proposal_id,
+        );
+
+        let span = (input.finalized_at - input.opened_at).num_seconds().max(0);
+        let mut threshold_curve: Vec<ThresholdPoint> = (0..=CURVE_POINTS)
+            .map(|i| {
+                let elapsed_secs = span * i / CURVE_POINTS;
+                ThresholdPoint {
+                    elapsed_secs,
+                    threshold: escalator
+                        .threshold_with_profile(input.opened_at + Duration::seconds(elapsed_secs), input.opened_at),
+                }
+            })
+            .collect();
+        // Short windows repeat sample points
+        threshold_curve.dedup_by_key(|p| p.elapsed_secs);
+
+        let mut top_contributors = contributions.clone();
+        top_contributors.sort_by(|a, b| b.effective_weight.total_cmp(&a.effective_weight));
+        top_contributors.truncate(TOP_CONTRIBUTORS);
+
+        FinalizationReport {
+            proposal: ProposalMetadata {
+                proposal_id: input.proposal_id.to_string(),
+                proposal_type: format!("{:?}", input.proposal_type),
+                opened_at: input.opened_at,
+                finalized_at: input.finalized_at,
+                supersedes: None,
+                practice: false,
+            },
+            parameters: ReportParameters {
+                base_threshold: escalator.base_threshold,
+                ceiling: escalator.ceiling,
+                pattern: format!("{:?}", escalator.pattern),
+                profile: format!("{:?}", escalator.profile),
+                emergency_override: escalator.emergency_override,
+                min_vote_count: escalator.min_vote_count,
+                max_age_secs: input.max_age_secs,
+                precision: input.decision.precision,
+                tie_break: format!("{:?}", input.decision.tie_break),
+                metric: (input.decision.metric != TallyMetric::Instantaneous)
+                    .then(|| format!("{:?}", input.decision.metric)),
+                tie_break_seed: input.tie_break_seed.map(str::to_string),
+                min_effective_weight: (input.decision.min_effective_weight > 0.0).then_some(input.decision.min_effective_weight),
+            },
+            passed: decision.passed,
+            tied: decision.tied,
+            final_threshold,
+            tally: TallyBreakdown {
+                counted_votes: direct_votes,
+                excluded_votes: votes.len() - direct_votes,
+                total_weight,
+                net_weight,
+                eligible_weight: tally.eligible_weight,
+                normalized: tally.normalized(),
+                by_decay_model,
+                time_weighted,
+                dust: (dust.votes > 0).then_some(dust),
+                contributions,
+            },
+            threshold_curve,
+            top_contributors,
+            anomalies,
+            channels: BTreeMap::new(),
+            rounds: Vec::new(),
+            fallbacks,
+            certificate: None,
+        }
+    }
+
+    /// The weight the decision compared against `final_threshold`
+    pub fn decided_weight(&self) -> f64 {
+        self.tally
+            .time_weighted
+            .map_or(self.tally.net_weight.unwrap_or(self.tally.total_weight), |t| t.average)
+    }
+
+    /// Canonical hash of the report with its certificate left out
+    pub fn body_hash(&self) -> String {
+        let mut body = self.clone();
+        body.certificate = None;
+        hashing::report_hash(&serde_json::to_vec(&body).expect("report serializes"))
+    }
+
+    /// Include per-channel intake statistics; like `supersede`, call before `certify`
+    pub fn attach_channels(&mut self, stats: &BTreeMap<Channel, ChannelStats>) {
+        self.channels = stats.clone();
+        self.certificate = None;
+    }
+
+    /// Include the rounds of a multi-round proposal; like `supersede`, call before `certify`
+    pub fn attach_rounds(&mut self, rounds: &[RoundRecord]) {
+        self.rounds = rounds.to_vec();
+        self.certificate = None;
+    }
+
+    /// Mark the outcome non-binding, for a practice proposal; like `supersede`, call
+    /// before `certify`
+    pub fn mark_practice(&mut self) {
+        self.proposal.practice = true;
+        self.certificate = None;
+    }
+
+    /// Record that this proposal replaces `prior`. Must be called before `certify`,
+    /// since the link is part of the signed body.
+    pub fn supersede(&mut self, prior: &FinalizationReport) -> Result<(), LineageError> {
+        if !prior.verify_certificate() {
+            return Err(LineageError::InvalidCertificate(prior.proposal.proposal_id.clone()));
+        }
+        let certificate = prior.certificate.as_ref().expect("verified certificate");
+        self.proposal.supersedes = Some(SupersededProposal {
+            proposal_id: prior.proposal.proposal_id.clone(),
+            certificate_hash: certificate.hash(),
+        });
+        self.certificate = None;
+        Ok(())
+    }
+
+    /// Follow `supersedes` links from `head` back through `archive`, checking every
+    /// certificate and link. Returns the lineage oldest first, ending with `head`.
+    pub fn lineage<'a>(
+        head: &'a FinalizationReport,
+        archive: &'a [FinalizationReport],
+    ) -> Result<Vec<&'a FinalizationReport>, LineageError> {
+        let by_certificate: BTreeMap<String, &FinalizationReport> = archive
+            .iter()
+            .filter_map(|r| r.certificate.as_ref().map(|c| (c.hash(), r)))
+            .collect();
+
+        let mut chain = vec![head];
+        let mut current = head;
+        loop {
+            if !current.verify_certificate() {
+                return Err(LineageError::InvalidCertificate(current.proposal.proposal_id.clone()));
+            }
+            let Some(link) = &current.proposal.supersedes else {
+                break;
+            };
+            let prior = *by_certificate
+                .get(&link.certificate_hash)
+                .ok_or_else(|| LineageError::MissingPredecessor(link.proposal_id.clone()))?;
+            if prior.proposal.proposal_id != link.proposal_id {
+                return Err(LineageError::BrokenLink {
+                    proposal_id: current.proposal.proposal_id.clone(),
+                    expected: link.proposal_id.clone(),
+                });
+            }
+            if chain.len() > archive.len() {
+                return Err(LineageError::Cycle(head.proposal.proposal_id.clone()));
+            }
+            chain.push(prior);
+            current = prior;
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Attach an issuer signature over `body_hash`, attesting this build's version and
+    /// the report's parameters
+    pub fn certify(&mut self, issuer: &SigningKey) {
+        self.certify_with_config(issuer, None);
+    }
+
+    /// Like `certify`, also attesting the digest of the configuration in force
+    /// (see `config_digest`)
+    pub fn certify_with_config(&mut self, issuer: &SigningKey, config_digest: Option<String>) {
+        let report_hash = self.body_hash();
+        let attestation = Attestation::new(&self.parameters, config_digest);
+        let signed = attestation.signed_hash(&report_hash);
+        self.certificate = Some(ReportCertificate {
+            signature: hex::encode(issuer.sign(signed.as_bytes()).to_bytes()),
+            issuer: hex::encode(issuer.verifying_key().as_bytes()),
+            report_hash,
+            attestation: Some(attestation),
+        });
+    }
+
+    /// Compare the certified attestation against what an auditor expected, e.g.
+    /// `Attestation::new(&agreed_parameters, Some(config_digest(&config)))`. Empty when
+    /// everything matches; an invalid certificate yields `Missing`.
+    pub fn audit_attestation(&self, expected: &Attestation) -> Vec<AttestationMismatch> {
+        if !self.verify_certificate() {
+            return vec![AttestationMismatch::Missing];
+        }
+        match self.certificate.as_ref().and_then(|c| c.attestation.as_ref()) {
+            Some(attestation) => attestation.compare(expected),
+            None => vec![AttestationMismatch::Missing],
+        }
+    }
+
+    /// True when a certificate is present and matches the current report body,
+    /// including an attested parameter hash that matches `parameters`
+    pub fn verify_certificate(&self) -> bool {
+        let Some(cert) = &self.certificate else {
+            return false;
+        };
+        let decoded = (|| {
+            let key: [u8; 32] = hex::decode(&cert.issuer).ok()?.try_into().ok()?;
+            let sig: [u8; 64] = hex::decode(&cert.signature).ok()?.try_into().ok()?;
+            Some((VerifyingKey::from_bytes(&key).ok()?, Signature::from_bytes(&sig)))
+        })();
+        let Some((key, signature)) = decoded else {
+            return false;
+        };
+        let signed = match &cert.attestation {
+            Some(attestation) => {
+                if attestation.parameters_hash != parameters_hash(&self.parameters) {
+                    return false;
+                }
+                attestation.signed_hash(&cert.report_hash)
+            }
+            None => cert.report_hash.clone(),
+        };
+        cert.report_hash == self.body_hash() && key.verify(signed.as_bytes(), &signature).is_ok()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report serializes")
+    }
+
+    /// Human-readable rendering for stakeholders
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let p = &self.proposal;
+        let _ = writeln!(out, "📜 Finalization report: {} ({})", p.proposal_id, p.proposal_type);
+        if p.practice {
+            let _ = writeln!(out, "  🧪 practice run: non-binding");
+        }
+        let _ = writeln!(out, "  opened:    {}", p.opened_at);
+        let _ = writeln!(out, "  finalized: {}", p.finalized_at);
+        if let Some(link) = &p.supersedes {
+            let _ = writeln!(out, "  supersedes: {} (certificate {})", link.proposal_id, link.certificate_hash);
+        }
+        let _ = writeln!(
+            out,
+            "  outcome:   {} (weight {:.4} vs threshold {:.4}, {} votes counted, {} excluded)",
+            if self.passed { "✅ passed" } else { "❌ rejected" },
+            self.tally.net_weight.unwrap_or(self.tally.total_weight),
+            self.final_threshold,
+            self.tally.counted_votes,
+            self.tally.excluded_votes
+        );
+        let _ = writeln!(
+            out,
+            "  turnout:   {:.1}% of eligible weight {:.4}, approval {:.1}%, margin {:+.3}",
+            self.tally.normalized.turnout_fraction * 100.0,
+            self.tally.eligible_weight,
+            self.tally.normalized.approval_fraction * 100.0,
+            self.tally.normalized.margin
+        );
+        let params = &self.parameters;
+        let _ = writeln!(
+            out,
+            "  parameters: base={:.2} ceiling={:.2} pattern={} profile={} min_votes={}",
+            params.base_threshold, params.ceiling, params.pattern, params.profile, params.min_vote_count
+        );
+        let _ = writeln!(
+            out,
+            "  tie-break: {} at {} (tied: {})",
+            params.tie_break,
+            params.precision.map_or("full precision".to_string(), |d| format!("{} decimals", d)),
+            self.tied
+        );
+        if let (Some(floor), Some(dust)) = (params.min_effective_weight, &self.tally.dust) {
+            let _ = writeln!(
+                out,
+                "  dust: {} vote(s) below {:.4} left out, {:.4} weight in all",
+                dust.votes, floor, dust.weight
+            );
+        }
+        if let (Some(metric), Some(integral)) = (&params.metric, &self.tally.time_weighted) {
+            let _ = writeln!(
+                out,
+                "  metric: {} (average {:.4} over the window, area {:.2} weight-seconds)",
+                metric, integral.average, integral.area
+            );
+        }
+
+        let _ = writeln!(out, "  weight by decay model:");
+        for (model, weight) in &self.tally.by_decay_model {
+            let _ = writeln!(out, "  - {}: {:.4}", model, weight);
+        }
+        let _ = writeln!(out, "  threshold curve:");
+        for point in &self.threshold_curve {
+            let _ = writeln!(out, "  - +{}s: {:.4}", point.elapsed_secs, point.threshold);
+        }
+        let _ = writeln!(out, "  top contributors:");
+        for c in &self.top_contributors {
+            let _ = writeln!(
+                out,
+                "  - {} weight={:.4} share={:.1}% decay={} trust={:.2}{}",
+                c.voter_id,
+                c.effective_weight,
+                c.share * 100.0,
+                c.decay_model,
+                c.trust_bonus,
+                if c.choice.is_approve() { String::new() } else { format!(" ({:?})", c.choice) }
+            );
+        }
+        if !self.channels.is_empty() {
+            let _ = writeln!(out, "  channels:");
+            for (channel, stats) in &self.channels {
+                let _ = writeln!(
+                    out,
+                    "  - {}: {} received, {:.1}% accepted, mean latency {:.1}s",
+                    channel,
+                    stats.received,
+                    stats.acceptance_rate() * 100.0,
+                    stats.mean_latency_secs()
+                );
+            }
+        }
+        if !self.rounds.is_empty() {
+            let _ = writeln!(out, "  rounds:");
+            for round in &self.rounds {
+                let _ = writeln!(
+                    out,
+                    "  - round {}: {:?}, weight {:.4} vs threshold {:.4} (base {:.2}), {} votes",
+                    round.number, round.outcome, round.weight, round.threshold, round.base_threshold, round.votes
+                );
+            }
+        }
+        if !self.fallbacks.is_empty() {
+            let _ = writeln!(out, "  fallbacks:");
+            for fallback in &self.fallbacks {
+                let _ = writeln!(
+                    out,
+                    "  - {} ({:?}) -> {}: {:.4} toward {:?}",
+                    fallback.voter_id, fallback.trigger, fallback.delegate, fallback.decayed_weight, fallback.choice
+                );
+            }
+        }
+        let lineage: Vec<&VoteLineage> = self.tally.contributions.iter().flat_map(|c| &c.lineage).collect();
+        if !lineage.is_empty() {
+            let _ = writeln!(out, "  lineage:");
+            for l in lineage {
+                let _ = writeln!(out, "  - {}", l);
+            }
+        }
+        if self.anomalies.is_empty() {
+            let _ = writeln!(out, "  anomalies: none");
+        } else {
+            let _ = writeln!(out, "  anomalies:");
+            for anomaly in &self.anomalies {
+                let _ = writeln!(out, "  - {}", describe(anomaly));
+            }
+        }
+        match &self.certificate {
+            Some(cert) => {
+                let _ = writeln!(
+                    out,
+                    "  certificate: {} signed by {} (valid: {})",
+                    cert.report_hash,
+                    cert.issuer,
+                    self.verify_certificate()
+                );
+                if let Some(attestation) = &cert.attestation {
+                    let _ = writeln!(
+                        out,
+                        "  attested: version {}, parameters {}, config {}",
+                        attestation.crate_version,
+                        attestation.parameters_hash,
+                        attestation.config_digest.as_deref().unwrap_or("none")
+                    );
+                }
+            }
+            None => {
+                let _ = writeln!(out, "  certificate: none");
+            }
+        }
+        out
+    }
+}
+
+fn describe(anomaly: &Anomaly) -> String {
+    match anomaly {
+        Anomaly::InvalidVote { voter_id, error } => format!("invalid vote from {}: {}", voter_id, error),
+        Anomaly::DuplicateVote { voter_id } => format!("duplicate vote from {}", voter_id),
+        Anomaly::OutsideWindow { voter_id, timestamp } => {
+            format!("vote from {} at {} is outside the voting window", voter_id, timestamp)
+        }
+        Anomaly::WeightConcentration { voter_id, share } => {
+            format!("{} holds {:.1}% of the counted weight", voter_id, share * 100.0)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trust::TrustEngine;
+    use crate::vote::DecayType;
+
+    fn vote(voter: &str, weight: f64, at: DateTime<Utc>) -> SignedVote {
+        let key = SignedVote::generate_keypair();
+        SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), weight, at, DecayType::Linear, &key)
+    }
+
+    fn report(votes: &[SignedVote], opened_at: DateTime<Utc>, finalized_at: DateTime<Utc>) -> FinalizationReport {
+        FinalizationReport::build(ReportInput {
+            proposal_id: "p1",
+            proposal_type: ProposalType::Normal,
+            opened_at,
+            finalized_at,
+            votes,
+            trust: &TrustEngine::new(),
+            max_age_secs: 3600,
+            admissions: None,
+            eligible_weight: Some(6.0),
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        })
+    }
+
+    #[test]
+    fn test_fallback_weight_is_reported() {
+        use crate::delegation::{DelegationScope, FallbackInstruction};
+
+        let now = Utc::now();
+        let opened = now - Duration::seconds(100);
+        let key = SignedVote::generate_keypair();
+        let abstain = SignedVote::new_with_choice(
+            "bob".parse().unwrap(),
+            "p1".parse().unwrap(),
+            1.0,
+            now - Duration::seconds(20),
+            DecayType::Linear,
+            VoteChoice::Abstain,
+            &key,
+        );
+        let votes = vec![vote("carol", 1.0, now - Duration::seconds(50)), abstain];
+        let mut fallbacks = FallbackRegistry::new();
+        for voter in ["alice", "bob"] {
+            let scope = DelegationScope::Proposal("p1".parse().unwrap());
+            let (voter, delegate) = (voter.parse().unwrap(), "carol".parse().unwrap());
+            fallbacks.register(FallbackInstruction::new(voter, delegate, scope, 2.0, DecayType::Linear, opened, &key)).unwrap();
+        }
+        let trust = TrustEngine::new();
+        let input = || ReportInput {
+            proposal_id: "p1",
+            proposal_type: ProposalType::Normal,
+            opened_at: opened,
+            finalized_at: now,
+            votes: &votes,
+            trust: &trust,
+            max_age_secs: 3600,
+            admissions: None,
+            eligible_weight: Some(6.0),
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        };
+        let plain = FinalizationReport::build(input());
+        let report = FinalizationReport::build_with_fallbacks(input(), &fallbacks);
+
+        assert_eq!(report.fallbacks.len(), 2);
+        assert_eq!(report.tally.counted_votes, 2);
+        let routed: Vec<&Contribution> = report.tally.contributions.iter().filter(|c| c.fallback_to.is_some()).collect();
+        assert_eq!(routed.len(), 2);
+        // Bob's abstention is replaced by his routed weight rather than counted twice
+        assert_eq!(report.tally.contributions.iter().filter(|c| c.voter_id == "bob").count(), 1);
+        let expected = 2.0 * (2.0 - 0.001 * 100.0);
+        assert!((routed.iter().map(|c| c.effective_weight).sum::<f64>() - expected).abs() < 1e-9);
+        let carol = plain.tally.contributions.iter().find(|c| c.voter_id == "carol").unwrap().effective_weight;
+        assert!((report.tally.total_weight - carol - expected).abs() < 1e-9);
+        assert!(report.render_text().contains("alice (Absent) -> carol"));
+        assert!(report.render_text().contains("  - alice -> carol (fallback 2.0000)"));
+        assert!(plain.fallbacks.is_empty() && !plain.to_json().contains("fallback"));
+    }
+
+    #[test]
+    fn test_delegation_lineage_is_reported() {
+        use crate::delegation::{Delegation, DelegationScope};
+
+        let now = Utc::now();
+        let opened = now - Duration::seconds(100);
+        let key = SignedVote::generate_keypair();
+        let votes = vec![vote("carol", 1.0, now - Duration::seconds(50)), vote("dave", 1.0, now - Duration::seconds(50))];
+        let mut delegations = DelegationGraph::new();
+        for (from, to) in [("alice", "bob"), ("bob", "carol")] {
+            let scope = DelegationScope::Proposal("p1".parse().unwrap());
+            delegations.add(Delegation::new(from.parse().unwrap(), to.parse().unwrap(), scope, 1.0, opened, &key)).unwrap();
+        }
+        let plain = report(&votes, opened, now);
+        let input = ReportInput {
+            proposal_id: "p1",
+            proposal_type: ProposalType::Normal,
+            opened_at: opened,
+            finalized_at: now,
+            votes: &votes,
+            trust: &TrustEngine::new(),
+            max_age_secs: 3600,
+            admissions: None,
+            eligible_weight: None,
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        };
+        let report = FinalizationReport::build_with_routing(input, &FallbackRegistry::new(), &delegations);
+
+        let carol = report.tally.contributions.iter().find(|c| c.voter_id == "carol").unwrap();
+        let paths: Vec<String> = carol.lineage.iter().map(|l| l.to_string()).collect();
+        assert_eq!(paths, ["alice -> bob -> carol (delegated 1.0000)", "bob -> carol (delegated 1.0000)"]);
+        assert!(carol.effective_weight > plain.tally.contributions[0].effective_weight);
+        assert!(report.tally.contributions.iter().find(|c| c.voter_id == "dave").unwrap().lineage.is_empty());
+        assert!(report.render_text().contains("  - alice -> bob -> carol (delegated 1.0000)"));
+        assert!(report.to_json().contains("\"lineage\"") && !plain.to_json().contains("lineage"));
+    }
+
+    #[test]
+    fn test_tally_and_curve() {
+        let now = Utc::now();
+        let opened = now - Duration::seconds(100);
+        let votes = vec![
+            vote("validator_001", 1.0, now - Duration::seconds(50)),
+            vote("bob", 1.0, now - Duration::seconds(40)),
+            vote("carol", 1.0, now - Duration::seconds(30)),
+        ];
+        let report = report(&votes, opened, now);
+
+        assert_eq!(report.tally.counted_votes, 3);
+        assert!(report.passed);
+        let turnout = report.tally.total_weight / 6.0;
+        assert!((report.tally.normalized.turnout_fraction - turnout).abs() < 1e-9);
+        assert_eq!(report.tally.normalized.approval_fraction, 1.0);
+        assert_eq!(report.top_contributors[0].voter_id, "validator_001");
+        assert_eq!(report.threshold_curve.len(), 11);
+        assert_eq!(report.threshold_curve[10].elapsed_secs, 100);
+        assert!(report.threshold_curve[0].threshold < report.threshold_curve[10].threshold);
+        assert!(report.anomalies.is_empty());
+
+        let text = report.render_text();
+        assert!(text.contains("validator_001"));
+        assert!(text.contains("anomalies: none"));
+    }
+
+    #[test]
+    fn test_opposing_weight_can_sink_a_proposal() {
+        let now = Utc::now();
+        let opened = now - Duration::seconds(100);
+        let choice = |voter: &str, choice: VoteChoice| {
+            let key = SignedVote::generate_keypair();
+            let at = now - Duration::seconds(30);
+            SignedVote::new_with_choice(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, at, DecayType::Linear, choice, &key)
+        };
+        let approving = vec![
+            vote("alice", 1.0, now - Duration::seconds(30)),
+            vote("bob", 1.0, now - Duration::seconds(30)),
+            choice("carol", VoteChoice::Abstain),
+        ];
+        let passed = report(&approving, opened, now);
+        assert!(passed.passed);
+        assert!((passed.tally.net_weight.unwrap() - 1.94).abs() < 1e-9);
+
+        let mut opposed = approving.clone();
+        opposed.push(choice("dave", VoteChoice::Reject));
+        opposed.push(choice("erin", VoteChoice::Reject));
+        let rejected = report(&opposed, opened, now);
+        assert!(!rejected.passed);
+        assert_eq!(rejected.tally.net_weight, Some(0.0));
+        assert!((rejected.tally.normalized.rejection_fraction - 0.5).abs() < 1e-9);
+        assert!(rejected.render_text().contains("(Reject)"));
+    }
+
+    #[test]
+    fn test_latest_version_counts_despite_clock_skew() {
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let first = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now - Duration::seconds(10), DecayType::Linear, &key);
+        let amended = first.amend(1.0, now - Duration::seconds(20), VoteChoice::Reject, &key);
+
+        let report = report(&[amended, first], now - Duration::seconds(100), now);
+        assert_eq!(report.tally.counted_votes, 1);
+        assert_eq!(report.tally.contributions[0].choice, VoteChoice::Reject);
+        assert!(!report.anomalies.iter().any(|a| matches!(a, Anomaly::DuplicateVote { .. })));
+    }
+
+    #[test]
+    fn test_anomalies_are_excluded_from_tally() {
+        let now = Utc::now();
+        let opened = now - Duration::seconds(100);
+        let mut forged = vote("eve", 50.0, now - Duration::seconds(10));
+        forged.voter_id = "mallory".parse().unwrap();
+        let votes = vec![
+            vote("alice", 5.0, now - Duration::seconds(50)),
+            vote("alice", 1.0, now - Duration::seconds(40)),
+            vote("bob", 1.0, now - Duration::seconds(200)),
+            forged,
+        ];
+        let report = report(&votes, opened, now);
+
+        assert_eq!(report.tally.counted_votes, 1);
+        assert_eq!(report.tally.excluded_votes, 3);
+        assert!(!report.passed);
+        let kinds: Vec<&str> = report
+            .anomalies
+            .iter()
+            .map(|a| match a {
+                Anomaly::InvalidVote { .. } => "invalid",
+                Anomaly::DuplicateVote { .. } => "duplicate",
+                Anomaly::OutsideWindow { .. } => "window",
+                Anomaly::WeightConcentration { .. } => "concentration",
+            })
+            .collect();
+        assert_eq!(kinds, vec!["duplicate", "window", "invalid", "concentration"]);
+    }
+
+    #[test]
+    fn test_time_weighted_metric_discounts_late_spikes() {
+        let now = Utc::now();
+        let opened = now - Duration::seconds(300);
+        let votes = vec![
+            vote("alice", 1.0, now - Duration::seconds(5)),
+            vote("bob", 1.0, now - Duration::seconds(4)),
+            vote("carol", 1.0, now - Duration::seconds(3)),
+        ];
+        let build = |metric| {
+            FinalizationReport::build(ReportInput {
+                proposal_id: "p1",
+                proposal_type: ProposalType::Normal,
+                opened_at: opened,
+                finalized_at: now,
+                votes: &votes,
+                trust: &TrustEngine::new(),
+                max_age_secs: 3600,
+                admissions: None,
+                eligible_weight: None,
+                decision: DecisionRule { metric, ..DecisionRule::default() },
+                tie_break_seed: None,
+            })
+        };
+
+        let instant = build(TallyMetric::Instantaneous);
+        assert!(instant.passed);
+        assert!(instant.tally.time_weighted.is_none());
+        assert!(!instant.to_json().contains("time_weighted"));
+
+        let sustained = build(TallyMetric::TimeWeighted);
+        let integral = sustained.tally.time_weighted.unwrap();
+        assert!(!sustained.passed);
+        assert!(integral.average < 0.1 && integral.area > 0.0);
+        assert_eq!(sustained.parameters.metric.as_deref(), Some("TimeWeighted"));
+        assert!(sustained.render_text().contains("metric: TimeWeighted"));
+    }
+
+    #[test]
+    fn test_dust_votes_are_left_out_of_the_tally() {
+        let now = Utc::now();
+        let opened = now - Duration::seconds(300);
+        let votes = vec![
+            vote("alice", 1.0, now - Duration::seconds(5)),
+            vote("bob", 1.0, now - Duration::seconds(10)),
+            vote("carol", 1.0, now - Duration::seconds(15)),
+            vote("dave", 0.01, now - Duration::seconds(20)),
+            vote("erin", 0.05, now - Duration::seconds(20)),
+        ];
+        let build = |min_effective_weight| {
+            FinalizationReport::build(ReportInput {
+                proposal_id: "p1",
+                proposal_type: ProposalType::Normal,
+                opened_at: opened,
+                finalized_at: now,
+                votes: &votes,
+                trust: &TrustEngine::new(),
+                max_age_secs: 3600,
+                admissions: None,
+                eligible_weight: None,
+                decision: DecisionRule { min_effective_weight, ..DecisionRule::default() },
+                tie_break_seed: None,
+            })
+        };
+
+        let all = build(0.0);
+        assert_eq!((all.tally.counted_votes, all.tally.dust), (5, None));
+        assert!(all.parameters.min_effective_weight.is_none());
+
+        let floored = build(0.1);
+        assert_eq!((floored.tally.counted_votes, floored.tally.excluded_votes), (3, 2));
+        let dust = floored.tally.dust.unwrap();
+        assert_eq!(dust.votes, 2);
+        assert!(dust.weight > 0.0 && dust.weight < 0.06);
+        assert!((all.tally.total_weight - floored.tally.total_weight - dust.weight).abs() < 1e-9);
+        assert!(floored.anomalies.iter().all(|a| !matches!(a, Anomaly::InvalidVote { .. })));
+        assert!(floored.render_text().contains("dust: 2 vote(s) below 0.1000"));
+    }
+
+    #[test]
+    fn test_certificate_round_trip() {
+        let now = Utc::now();
+        let votes = vec![vote("alice", 1.0, now - Duration::seconds(5))];
+        let mut report = report(&votes, now - Duration::seconds(10), now);
+        assert!(!report.verify_certificate());
+
+        report.certify(&SignedVote::generate_keypair());
+        assert!(report.verify_certificate());
+
+        let parsed: FinalizationReport = serde_json::from_str(&report.to_json()).unwrap();
+        assert!(parsed.verify_certificate());
+
+        report.passed = !report.passed;
+        assert!(!report.verify_certificate());
+    }
+
+    #[test]
+    fn test_certificate_attests_version_parameters_and_config() {
+        let now = Utc::now();
+        let votes = vec![vote("alice", 1.0, now - Duration::seconds(5))];
+        let mut report = report(&votes, now - Duration::seconds(10), now);
+        let config = BTreeMap::from([("decay", "linear")]);
+        let key = SignedVote::generate_keypair();
+        report.certify_with_config(&key, Some(config_digest(&config)));
+        assert!(report.verify_certificate());
+        assert!(report.render_text().contains("attested: version"));
+
+        let expected = Attestation::new(&report.parameters, Some(config_digest(&config)));
+        assert!(report.audit_attestation(&expected).is_empty());
+
+        let mut agreed = report.parameters.clone();
+        agreed.base_threshold += 0.1;
+        let other_config = BTreeMap::from([("decay", "exponential")]);
+        let mut stale = Attestation::new(&agreed, Some(config_digest(&other_config)));
+        stale.crate_version = "0.0.1".into();
+        let mismatches = report.audit_attestation(&stale);
+        assert_eq!(mismatches.len(), 3);
+        assert!(matches!(mismatches[0], AttestationMismatch::CrateVersion { .. }));
+        assert!(matches!(mismatches[1], AttestationMismatch::Parameters { .. }));
+        assert!(matches!(mismatches[2], AttestationMismatch::Config { .. }));
+
+        // A swapped attestation breaks the signature
+        let mut forged = report.clone();
+        forged.certificate.as_mut().unwrap().attestation.as_mut().unwrap().config_digest = None;
+        assert!(!forged.verify_certificate());
+        assert_eq!(forged.audit_attestation(&expected), vec![AttestationMismatch::Missing]);
+
+        // Certificates issued before attestations still verify, but audit as missing
+        let report_hash = report.body_hash();
+        report.certificate = Some(ReportCertificate {
+            signature: hex::encode(key.sign(report_hash.as_bytes()).to_bytes()),
+            issuer: hex::encode(key.verifying_key().as_bytes()),
+            report_hash,
+            attestation: None,
+        });
+        assert!(report.verify_certificate());
+        assert_eq!(report.audit_attestation(&expected), vec![AttestationMismatch::Missing]);
+    }
+
+    #[test]
+    fn test_superseding_reports_form_a_verifiable_lineage() {
+        let authority = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let opened = now - Duration::seconds(100);
+        let votes = vec![vote("alice", 1.0, now - Duration::seconds(50))];
+
+        let mut first = report(&votes, opened, now);
+        assert!(first.supersede(&first.clone()).is_err());
+        first.certify(&authority);
+
+        let mut second = report(&votes, opened, now);
+        second.proposal.proposal_id = "p2".to_string();
+        second.supersede(&first).unwrap();
+        second.certify(&authority);
+        assert!(second.render_text().contains("supersedes: p1"));
+
+        let mut third = report(&votes, opened, now);
+        third.proposal.proposal_id = "p3".to_string();
+        third.supersede(&second).unwrap();
+        third.certify(&authority);
+
+        let archive = vec![first.clone(), second.clone()];
+        let ids: Vec<&str> = FinalizationReport::lineage(&third, &archive)
+            .unwrap()
+            .iter()
+            .map(|r| r.proposal.proposal_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["p1", "p2", "p3"]);
+
+        assert_eq!(
+            FinalizationReport::lineage(&third, &archive[1..]),
+            Err(LineageError::MissingPredecessor("p1".to_string()))
+        );
+        let mut tampered = archive.clone();
+        tampered[0].passed = !tampered[0].passed;
+        assert_eq!(
+            FinalizationReport::lineage(&third, &tampered),
+            Err(LineageError::InvalidCertificate("p1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_channel_statistics_in_report() {
+        use crate::history::HistoryAnalyzer;
+        use crate::intake::{VoteIntake, VoteSource};
+        use crate::units::Seconds;
+
+        let now = Utc::now();
+        let mut intake = VoteIntake::new(Seconds::new(3600));
+        let mut history = HistoryAnalyzer::default();
+        for (voter, channel) in [("alice", Channel::Http), ("bob", Channel::Gossip), ("carol", Channel::Gossip)] {
+            let v = vote(voter, 1.0, now - Duration::seconds(20));
+            intake.submit(v, VoteSource::new(channel), now, None, &mut history).unwrap();
+        }
+
+        let mut report = report(&intake.votes(), now - Duration::seconds(100), now);
+        report.attach_channels(intake.stats());
+        assert_eq!(report.channels[&Channel::Gossip].accepted, 2);
+        let text = report.render_text();
+        assert!(text.contains("- gossip: 2 received, 100.0% accepted, mean latency 20.0s"));
+
+        let published: FinalizationReport = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(published.channels, report.channels);
+    }
+
+    #[test]
+    fn test_rounds_in_report() {
+        use crate::rounds::{MultiRoundProposal, RoundConfig, RoundOutcome};
+
+        let now = Utc::now();
+        let opened = now - Duration::seconds(100);
+        let votes = vec![vote("alice", 0.4, opened), vote("bob", 0.4, opened)];
+        let mut proposal = MultiRoundProposal::new("p1", ProposalType::Normal, opened, RoundConfig::default());
+        assert_eq!(proposal.close_round(&votes, opened + Duration::seconds(5), None), Some(RoundOutcome::Advanced));
+
+        let mut report = report(&votes, opened, now);
+        report.attach_rounds(proposal.rounds());
+        assert!(report.render_text().contains("- round 1: Advanced"));
+        let published: FinalizationReport = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(published.rounds, report.rounds);
+    }
+}
@@ -0,0 +1,336 @@
+// src/reputation.rs
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::hashing;
+use crate::ids::VoterId;
+use crate::trust::TrustSource;
+
+/// Interchange format version written by `ReputationBook::export`
+pub const FORMAT_VERSION: u32 = 1;
+/// Largest bonus multiplier an import will accept
+pub const MAX_BONUS: f64 = 2.0;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ReputationError {
+    #[error("Unsupported reputation format version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Reputation export has an invalid signature")]
+    InvalidSignature,
+    #[error("Reputation export issuer {0} is not trusted")]
+    UntrustedIssuer(String),
+    #[error("Invalid bonus {bonus} for {validator_id}; expected 0..={max}", max = MAX_BONUS)]
+    InvalidScore { validator_id: String, bonus: f64 },
+    #[error("{0} appears more than once in the export")]
+    DuplicateValidator(String),
+    #[error("Malformed reputation export: {0}")]
+    Malformed(String),
+}
+
+/// One hop in a score's history: set locally (no `issuer`) or imported from a signed export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Provenance {
+    pub deployment: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ReputationScore {
+    pub validator_id: String,
+    pub bonus: f64,
+    pub updated_at: DateTime<Utc>,
+    /// Oldest first
+    #[serde(default)]
+    pub provenance: Vec<Provenance>,
+}
+
+/// Signed interchange document for sharing or migrating validator reputations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ReputationExport {
+    pub version: u32,
+    pub deployment: String,
+    pub exported_at: DateTime<Utc>,
+    pub scores: Vec<ReputationScore>,
+    /// Hex ed25519 public key of the exporting deployment
+    pub issuer: String,
+    /// Hex signature over `body_hash`
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl ReputationExport {
+    /// Canonical hash of the export with its signature left out
+    pub fn body_hash(&self) -> String {
+        let mut body = self.clone();
+        body.signature = String::new();
+        hashing::reputation_hash(&serde_json::to_vec(&body).expect("reputation export serializes"))
+    }
+
+    fn issuer_key(&self) -> Result<VerifyingKey, ReputationError> {
+        let bytes: [u8; 32] = hex::decode(&self.issuer)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| ReputationError::Malformed("issuer is not a hex ed25519 key".to_string()))?;
+        VerifyingKey::from_bytes(&bytes).map_err(|_| ReputationError::Malformed("issuer is not a valid key".to_string()))
+    }
+
+    /// Check the version, signature, and every score; nothing is imported unless this passes
+    pub fn validate(&self) -> Result<(), ReputationError> {
+        if self.version != FORMAT_VERSION {
+            return Err(ReputationError::UnsupportedVersion(self.version));
+        }
+        let key = self.issuer_key()?;
+        let signature: [u8; 64] = hex::decode(&self.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or(ReputationError::InvalidSignature)?;
+        key.verify(self.body_hash().as_bytes(), &Signature::from_bytes(&signature))
+            .map_err(|_| ReputationError::InvalidSignature)?;
+
+        let mut seen = HashSet::new();
+        for score in &self.scores {
+            check_bonus(&score.validator_id, score.bonus)?;
+            if !seen.insert(score.validator_id.as_str()) {
+                return Err(ReputationError::DuplicateValidator(score.validator_id.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reputation export serializes")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ReputationError> {
+        crate::schema::parse(json).map_err(|e| ReputationError::Malformed(e.to_string()))
+    }
+}
+
+fn check_bonus(validator_id: &str, bonus: f64) -> Result<(), ReputationError> {
+    if (0.0..=MAX_BONUS).contains(&bonus) {
+        Ok(())
+    } else {
+        Err(ReputationError::InvalidScore { validator_id: validator_id.to_string(), bonus })
+    }
+}
+
+/// Which score wins when an import covers a validator already scored locally
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictRule {
+    KeepLocal,
+    PreferImported,
+    /// Whichever was updated more recently; local wins ties
+    #[default]
+    Newest,
+    Max,
+    Min,
+}
+
+impl ConflictRule {
+    fn prefers_import(&self, local: &ReputationScore, imported: &ReputationScore) -> bool {
+        match self {
+            ConflictRule::KeepLocal => false,
+            ConflictRule::PreferImported => true,
+            ConflictRule::Newest => imported.updated_at > local.updated_at,
+            ConflictRule::Max => imported.bonus > local.bonus,
+            ConflictRule::Min => imported.bonus < local.bonus,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub replaced: usize,
+    pub kept: usize,
+}
+
+/// Validator reputations for one deployment, each with the trail of where it came from
+#[derive(Debug, Clone, Default)]
+pub struct ReputationBook {
+    pub deployment: String,
+    scores: BTreeMap<String, ReputationScore>,
+}
+
+impl ReputationBook {
+    pub fn new(deployment: &str) -> Self {
+        Self {
+            deployment: deployment.to_string(),
+            scores: BTreeMap::new(),
+        }
+    }
+
+    /// Set a score locally, recording this deployment as its latest source
+    pub fn set(&mut self, validator_id: &str, bonus: f64, at: DateTime<Utc>) -> Result<(), ReputationError> {
+        check_bonus(validator_id, bonus)?;
+        let provenance = Provenance {
+            deployment: self.deployment.clone(),
+            issuer: None,
+            recorded_at: at,
+        };
+        let score = self.scores.entry(validator_id.to_string()).or_insert_with(|| ReputationScore {
+            validator_id: validator_id.to_string(),
+            bonus,
+            updated_at: at,
+            provenance: Vec::new(),
+        });
+        score.bonus = bonus;
+        score.updated_at = at;
+        score.provenance.push(provenance);
+        Ok(())
+    }
+
+    pub fn score(&self, validator_id: &str) -> Option<&ReputationScore> {
+        self.scores.get(validator_id)
+    }
+
+    pub fn scores(&self) -> impl Iterator<Item = &ReputationScore> {
+        self.scores.values()
+    }
+
+    /// Every score, signed by `key`
+    pub fn export(&self, at: DateTime<Utc>, key: &SigningKey) -> ReputationExport {
+        let mut export = ReputationExport {
+            version: FORMAT_VERSION,
+            deployment: self.deployment.clone(),
+            exported_at: at,
+            scores: self.scores.values().cloned().collect(),
+            issuer: hex::encode(key.verifying_key().as_bytes()),
+            signature: String::new(),
+        };
+        export.signature = hex::encode(key.sign(export.body_hash().as_bytes()).to_bytes());
+        export
+    }
+
+    /// Merge a validated export from one of `trusted` issuers, resolving overlaps with
+    /// `rule`. Imported scores keep their trail plus a hop for this import.
+    pub fn import(
+        &mut self,
+        export: &ReputationExport,
+        trusted: &[VerifyingKey],
+        rule: ConflictRule,
+        at: DateTime<Utc>,
+    ) -> Result<ImportSummary, ReputationError> {
+        export.validate()?;
+        let issuer = export.issuer_key()?;
+        if !trusted.contains(&issuer) {
+            return Err(ReputationError::UntrustedIssuer(export.issuer.clone()));
+        }
+
+        let mut summary = ImportSummary::default();
+        for imported in &export.scores {
+            let mut incoming = imported.clone();
+            incoming.provenance.push(Provenance {
+                deployment: export.deployment.clone(),
+                issuer: Some(export.issuer.clone()),
+                recorded_at: at,
+            });
+            match self.scores.get_mut(&imported.validator_id) {
+                None => {
+                    self.scores.insert(imported.validator_id.clone(), incoming);
+                    summary.added += 1;
+                }
+                Some(local) if rule.prefers_import(local, imported) => {
+                    *local = incoming;
+                    summary.replaced += 1;
+                }
+                Some(_) => summary.kept += 1,
+            }
+        }
+        Ok(summary)
+    }
+}
+
+/// Scores exactly as exported, e.g. to audit them before importing
+impl TrustSource for ReputationExport {
+    fn get_bonus(&self, validator_id: &VoterId) -> f64 {
+        self.scores.iter().find(|s| s.validator_id == validator_id.as_str()).map_or(1.0, |s| s.bonus)
+    }
+}
+
+impl TrustSource for ReputationBook {
+    fn get_bonus(&self, validator_id: &VoterId) -> f64 {
+        self.scores.get(validator_id.as_str()).map_or(1.0, |s| s.bonus)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::SignedVote;
+    use chrono::Duration;
+
+    fn origin(now: DateTime<Utc>) -> (ReputationBook, SigningKey) {
+        let mut book = ReputationBook::new("mainnet");
+        book.set("validator_001", 1.2, now).unwrap();
+        book.set("validator_002", 1.1, now).unwrap();
+        (book, SignedVote::generate_keypair())
+    }
+
+    #[test]
+    fn test_export_round_trips_and_rejects_tampering() {
+        let now = Utc::now();
+        let (book, key) = origin(now);
+        let export = ReputationExport::from_json(&book.export(now, &key).to_json()).unwrap();
+        assert_eq!(export.validate(), Ok(()));
+
+        let mut inflated = export.clone();
+        inflated.scores[0].bonus = 1.9;
+        assert_eq!(inflated.validate(), Err(ReputationError::InvalidSignature));
+
+        let mut future = export.clone();
+        future.version = 2;
+        assert_eq!(future.validate(), Err(ReputationError::UnsupportedVersion(2)));
+
+        let mut bad = ReputationBook::new("mainnet");
+        assert!(matches!(bad.set("validator_003", f64::NAN, now), Err(ReputationError::InvalidScore { .. })));
+        assert!(ReputationExport::from_json("{}").is_err());
+
+        let mut target = ReputationBook::new("testnet");
+        let stranger = SignedVote::generate_keypair().verifying_key();
+        assert!(matches!(
+            target.import(&export, &[stranger], ConflictRule::default(), now),
+            Err(ReputationError::UntrustedIssuer(_))
+        ));
+        assert_eq!(target.scores().count(), 0);
+    }
+
+    #[test]
+    fn test_import_resolves_conflicts_and_keeps_provenance() {
+        let now = Utc::now();
+        let (book, key) = origin(now);
+        let export = book.export(now, &key);
+        let trusted = [key.verifying_key()];
+
+        for (rule, expected, summary) in [
+            (ConflictRule::KeepLocal, 1.5, (1, 0, 1)),
+            (ConflictRule::PreferImported, 1.2, (1, 1, 0)),
+            (ConflictRule::Newest, 1.5, (1, 0, 1)),
+            (ConflictRule::Max, 1.5, (1, 0, 1)),
+            (ConflictRule::Min, 1.2, (1, 1, 0)),
+        ] {
+            let mut target = ReputationBook::new("testnet");
+            target.set("validator_001", 1.5, now + Duration::seconds(10)).unwrap();
+            let result = target.import(&export, &trusted, rule, now + Duration::seconds(20)).unwrap();
+            assert_eq!((result.added, result.replaced, result.kept), summary, "{:?}", rule);
+            assert_eq!(target.get_bonus(&"validator_001".parse().unwrap()), expected, "{:?}", rule);
+            assert_eq!(target.get_bonus(&"validator_002".parse().unwrap()), 1.1);
+        }
+
+        let mut target = ReputationBook::new("testnet");
+        target.import(&export, &trusted, ConflictRule::default(), now).unwrap();
+        let trail = &target.score("validator_002").unwrap().provenance;
+        assert_eq!(trail.len(), 2);
+        assert_eq!((trail[0].deployment.as_str(), trail[0].issuer.as_ref()), ("mainnet", None));
+        assert_eq!(trail[1].issuer.as_deref(), Some(export.issuer.as_str()));
+    }
+}
@@ -0,0 +1,327 @@
+// src/reverify.rs
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::VerifyingKey;
+use serde::Serialize;
+
+use crate::hashing;
+use crate::history::HistoryAnalyzer;
+use crate::hooks::AdmissionPolicy;
+use crate::intake::VoteIntake;
+use crate::registry::ValidatorRegistry;
+use crate::tally::Tally;
+use crate::vote::{SignedVote, VoteChoice};
+
+/// Something that may invalidate votes already admitted
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryChange {
+    /// Votes signed with this key at or after `at` no longer count
+    KeyRevoked { public_key: VerifyingKey, at: DateTime<Utc> },
+    ValidatorExited { voter_id: String },
+    /// An admission policy changed; every admitted vote is re-checked
+    PolicyChanged { reason: String },
+}
+
+impl RegistryChange {
+    /// Lower runs first: revoked keys are the most urgent, policy sweeps the least
+    pub fn priority(&self) -> u8 {
+        match self {
+            RegistryChange::KeyRevoked { .. } => 0,
+            RegistryChange::ValidatorExited { .. } => 1,
+            RegistryChange::PolicyChanged { .. } => 2,
+        }
+    }
+
+    pub fn affects(&self, vote: &SignedVote) -> bool {
+        match self {
+            RegistryChange::KeyRevoked { public_key, at } => vote.public_key == *public_key && vote.timestamp >= *at,
+            RegistryChange::ValidatorExited { voter_id } => vote.voter_id == *voter_id,
+            RegistryChange::PolicyChanged { .. } => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Task {
+    priority: u8,
+    sequence: u64,
+    vote_hash: String,
+}
+
+impl PartialEq for Task {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Task {}
+
+impl PartialOrd for Task {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// `BinaryHeap` pops the greatest, so the most urgent, oldest task compares greatest
+impl Ord for Task {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// Tally adjustment for a vote that failed re-verification
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Compensation {
+    pub vote_hash: String,
+    pub voter_id: String,
+    pub proposal_id: String,
+    pub reason: String,
+    /// The side the vote was counted toward
+    pub choice: VoteChoice,
+    /// Weight the vote contributed and must now be taken back
+    pub weight: f64,
+}
+
+impl Compensation {
+    pub fn apply(&self, tally: &mut Tally) {
+        tally.retract(self.choice, self.weight);
+    }
+}
+
+/// Admitted votes waiting to be re-checked after a registry or policy change.
+/// Each vote is queued once, at the priority of the most urgent change touching it.
+#[derive(Default)]
+pub struct ReverificationQueue {
+    heap: BinaryHeap<Task>,
+    queued: HashSet<String>,
+    sequence: u64,
+}
+
+impl ReverificationQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue every admitted vote `change` affects; returns how many were added
+    pub fn enqueue(&mut self, change: &RegistryChange, intake: &VoteIntake) -> usize {
+        let mut added = 0;
+        for admitted in intake.admitted().iter().filter(|a| change.affects(&a.vote)) {
+            let vote_hash = hashing::vote_hash(&admitted.vote);
+            if self.queued.contains(&vote_hash) {
+                self.heap = self
+                    .heap
+                    .drain()
+                    .map(|mut t| {
+                        if t.vote_hash == vote_hash {
+                            t.priority = t.priority.min(change.priority());
+                        }
+                        t
+                    })
+                    .collect();
+                continue;
+            }
+            self.sequence += 1;
+            self.queued.insert(vote_hash.clone());
+            self.heap.push(Task {
+                priority: change.priority(),
+                sequence: self.sequence,
+                vote_hash,
+            });
+            added += 1;
+        }
+        added
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Re-run admission for every queued vote, most urgent first. Votes that now fail
+    /// are retracted from `intake`, logged to `history`, and returned as compensations
+    /// carrying the weight `weigh` says they contributed.
+    pub fn process(
+        &mut self,
+        intake: &mut VoteIntake,
+        registry: &ValidatorRegistry,
+        policy: Option<&dyn AdmissionPolicy>,
+        now: DateTime<Utc>,
+        history: &mut HistoryAnalyzer,
+        weigh: impl Fn(&SignedVote) -> f64,
+    ) -> Vec<Compensation> {
+        let mut compensations = Vec::new();
+        while let Some(task) = self.heap.pop() {
+            self.queued.remove(&task.vote_hash);
+            let Some(admitted) = intake.find(&task.vote_hash) else {
+                continue;
+            };
+            let reason = match admitted
+                .vote
+                .verify_with_policy(&intake.policy, admitted.received_at)
+                .and_then(|_| registry.check_vote(&admitted.vote))
+            {
+                Err(e) => Some(e.to_string()),
+                Ok(()) => match policy.map(|p| p.admit(&admitted.vote, now)) {
+                    Some(Ok(Some(refusal))) => Some(refusal),
+                    Some(Err(e)) => Some(e.to_string()),
+                    _ => None,
+                },
+            };
+            let Some(reason) = reason else {
+                continue;
+            };
+            let weight = weigh(&admitted.vote);
+            if let Some(retracted) = intake.retract(&task.vote_hash, &reason, now, history) {
+                compensations.push(Compensation {
+                    vote_hash: task.vote_hash,
+                    voter_id: retracted.vote.voter_id.to_string(),
+                    proposal_id: retracted.vote.proposal_id.to_string(),
+                    reason,
+                    choice: retracted.vote.choice,
+                    weight,
+                });
+            }
+        }
+        compensations
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::HookError;
+    use crate::intake::{Channel, VoteSource};
+    use crate::registry::Revocation;
+    use crate::units::Seconds;
+    use crate::vote::DecayType;
+    use chrono::Duration;
+
+    struct DenyVoter(&'static str);
+
+    impl AdmissionPolicy for DenyVoter {
+        fn admit(&self, vote: &SignedVote, _now: DateTime<Utc>) -> Result<Option<String>, HookError> {
+            Ok((vote.voter_id == self.0).then(|| format!("{} is suspended", self.0)))
+        }
+    }
+
+    #[test]
+    fn test_revocation_retracts_votes_and_compensates_tally() {
+        let authority = SignedVote::generate_keypair();
+        let alice = SignedVote::generate_keypair();
+        let bob = SignedVote::generate_keypair();
+        let mut registry = ValidatorRegistry::new();
+        registry.add_authority(authority.verifying_key());
+        registry.register("alice".to_string(), alice.verifying_key());
+        registry.register("bob".to_string(), bob.verifying_key());
+
+        let now = Utc::now();
+        let mut intake = VoteIntake::new(Seconds::new(600));
+        let mut history = HistoryAnalyzer::default();
+        let mut tally = Tally::new(3.0);
+        for (voter, key) in [("alice", &alice), ("bob", &bob)] {
+            let vote = SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, key);
+            intake
+                .submit(vote, VoteSource::new(Channel::Http), now, Some(&registry), &mut history)
+                .unwrap();
+            tally.approve(1.0);
+        }
+
+        let revoked_at = now - Duration::seconds(5);
+        let revocation = Revocation::new(alice.verifying_key(), revoked_at, "leaked".to_string(), &authority);
+        registry.revoke(revocation).unwrap();
+
+        let mut queue = ReverificationQueue::new();
+        let change = RegistryChange::KeyRevoked { public_key: alice.verifying_key(), at: revoked_at };
+        assert_eq!(queue.enqueue(&change, &intake), 1);
+        assert_eq!(queue.enqueue(&change, &intake), 0);
+
+        let compensations = queue.process(&mut intake, &registry, None, now, &mut history, |v| v.original_weight);
+        assert!(queue.is_empty());
+        assert_eq!(compensations.len(), 1);
+        assert_eq!(compensations[0].voter_id, "alice");
+        for c in &compensations {
+            c.apply(&mut tally);
+        }
+        assert_eq!((tally.approve_weight, tally.votes), (1.0, 1));
+        assert_eq!(intake.votes().len(), 1);
+        assert_eq!(intake.stats()[&Channel::Http].retracted, 1);
+        assert_eq!(history.rejected[0].voter_id, "alice");
+    }
+
+    #[test]
+    fn test_revoked_reject_vote_is_taken_off_the_reject_side() {
+        let authority = SignedVote::generate_keypair();
+        let alice = SignedVote::generate_keypair();
+        let mut registry = ValidatorRegistry::new();
+        registry.add_authority(authority.verifying_key());
+        registry.register("alice".to_string(), alice.verifying_key());
+
+        let now = Utc::now();
+        let mut intake = VoteIntake::new(Seconds::new(600));
+        let mut history = HistoryAnalyzer::default();
+        let vote = SignedVote::new_with_choice(
+            "alice".parse().unwrap(),
+            "p1".parse().unwrap(),
+            1.0,
+            now,
+            DecayType::Linear,
+            VoteChoice::Reject,
+            &alice,
+        );
+        intake.submit(vote, VoteSource::new(Channel::Http), now, Some(&registry), &mut history).unwrap();
+        let mut tally = Tally::new(3.0);
+        tally.approve(2.0);
+        tally.reject(1.0);
+
+        let revoked_at = now - Duration::seconds(5);
+        registry.revoke(Revocation::new(alice.verifying_key(), revoked_at, "leaked".to_string(), &authority)).unwrap();
+        let mut queue = ReverificationQueue::new();
+        queue.enqueue(&RegistryChange::KeyRevoked { public_key: alice.verifying_key(), at: revoked_at }, &intake);
+        let compensations = queue.process(&mut intake, &registry, None, now, &mut history, |v| v.original_weight);
+
+        assert_eq!(compensations[0].choice, VoteChoice::Reject);
+        compensations[0].apply(&mut tally);
+        assert_eq!((tally.approve_weight, tally.reject_weight, tally.votes), (2.0, 0.0, 1));
+    }
+
+    #[test]
+    fn test_policy_change_rechecks_all_votes_in_priority_order() {
+        let key = SignedVote::generate_keypair();
+        let mut registry = ValidatorRegistry::new();
+        for voter in ["alice", "bob", "carol"] {
+            registry.register(voter.to_string(), key.verifying_key());
+        }
+        let now = Utc::now();
+        let mut intake = VoteIntake::new(Seconds::new(600));
+        let mut history = HistoryAnalyzer::default();
+        for voter in ["alice", "bob", "carol"] {
+            let vote = SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 0.5, now, DecayType::Linear, &key);
+            intake
+                .submit(vote, VoteSource::new(Channel::Cli), now, None, &mut history)
+                .unwrap();
+        }
+
+        let mut queue = ReverificationQueue::new();
+        let policy = RegistryChange::PolicyChanged { reason: "suspensions".to_string() };
+        assert_eq!(queue.enqueue(&policy, &intake), 3);
+        queue.enqueue(&RegistryChange::ValidatorExited { voter_id: "carol".to_string() }, &intake);
+        assert_eq!(queue.heap.peek().unwrap().vote_hash, hashing::vote_hash(&intake.admitted()[2].vote));
+
+        let compensations =
+            queue.process(&mut intake, &registry, Some(&DenyVoter("bob")), now, &mut history, |v| v.original_weight);
+        assert_eq!(compensations.len(), 1);
+        assert_eq!(compensations[0].reason, "bob is suspended");
+        assert_eq!(compensations[0].weight, 0.5);
+        assert_eq!(intake.votes().len(), 2);
+    }
+}
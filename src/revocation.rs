@@ -0,0 +1,180 @@
+// src/revocation.rs
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::VerifyingKey;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::registry::{RegistryError, Revocation};
+use crate::schema::SchemaError;
+use crate::verify::VerificationError;
+use crate::vote::SignedVote;
+
+#[derive(Error, Debug)]
+pub enum RevocationError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Schema(#[from] SchemaError),
+    #[error("Revocation {index} rejected: {error}")]
+    Rejected { index: usize, error: RegistryError },
+}
+
+/// Compromised validator keys, as signed revocations from registry authorities. It is
+/// the revocation state of a `ValidatorRegistry` and what `VerificationPolicy::with_revocations`
+/// consults. Entries are only ever appended, and each is checked against the authorities
+/// when applied, including when the list is read back from a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct RevocationList {
+    /// Never persisted, so editing a saved list cannot add an issuer
+    #[serde(skip)]
+    #[schemars(skip)]
+    authorities: BTreeSet<[u8; 32]>,
+    /// In the order applied
+    revocations: Vec<Revocation>,
+}
+
+/// The persisted form of a `RevocationList`
+#[derive(Deserialize, JsonSchema)]
+#[schemars(rename = "RevocationList")]
+struct StoredRevocations {
+    revocations: Vec<Revocation>,
+}
+
+impl RevocationList {
+    /// An empty list accepting revocations signed by `authorities`
+    pub fn new(authorities: impl IntoIterator<Item = VerifyingKey>) -> Self {
+        Self { authorities: authorities.into_iter().map(|k| k.to_bytes()).collect(), revocations: Vec::new() }
+    }
+
+    pub fn add_authority(&mut self, authority: VerifyingKey) {
+        self.authorities.insert(authority.to_bytes());
+    }
+
+    /// Authority keys, sorted by their bytes
+    pub fn authorities(&self) -> Vec<[u8; 32]> {
+        self.authorities.iter().copied().collect()
+    }
+
+    /// Append a signed revocation after checking its issuer and signature
+    pub fn revoke(&mut self, revocation: Revocation) -> Result<(), RegistryError> {
+        if !self.authorities.contains(&revocation.issuer.to_bytes()) {
+            return Err(RegistryError::UnauthorizedIssuer);
+        }
+        revocation.verify()?;
+        if self.revoked_at(&revocation.public_key).is_some() {
+            return Err(RegistryError::AlreadyRevoked);
+        }
+        self.revocations.push(revocation);
+        Ok(())
+    }
+
+    pub fn revoked_at(&self, public_key: &VerifyingKey) -> Option<DateTime<Utc>> {
+        self.revocations.iter().find(|r| r.public_key == *public_key).map(|r| r.revoked_at)
+    }
+
+    /// Reject `vote` if its key was revoked at or before the time it was signed
+    pub fn check(&self, vote: &SignedVote) -> Result<(), VerificationError> {
+        match self.revoked_at(&vote.public_key) {
+            Some(at) if vote.timestamp >= at => Err(VerificationError::KeyRevoked),
+            _ => Ok(()),
+        }
+    }
+
+    /// Every accepted revocation, in the order it was applied
+    pub fn log(&self) -> &[Revocation] {
+        &self.revocations
+    }
+
+    pub fn len(&self) -> usize {
+        self.revocations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.revocations.is_empty()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("revocation lists serialize")
+    }
+
+    /// Parse a list, checked against the published `revocation_list` schema, and replay
+    /// its entries against `authorities`; an entry that fails rejects the whole list
+    pub fn from_json(json: &str, authorities: impl IntoIterator<Item = VerifyingKey>) -> Result<Self, RevocationError> {
+        let stored: StoredRevocations = crate::schema::parse(json)?;
+        let mut list = Self::new(authorities);
+        for (index, revocation) in stored.revocations.into_iter().enumerate() {
+            list.revoke(revocation).map_err(|error| RevocationError::Rejected { index, error })?;
+        }
+        Ok(list)
+    }
+
+    pub fn load(path: impl AsRef<Path>, authorities: impl IntoIterator<Item = VerifyingKey>) -> Result<Self, RevocationError> {
+        Self::from_json(&std::fs::read_to_string(path)?, authorities)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RevocationError> {
+        Ok(std::fs::write(path, self.to_json() + "\n")?)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::registry::ValidatorRegistry;
+    use crate::units::Seconds;
+    use crate::verify::VerificationPolicy;
+    use crate::vote::DecayType;
+
+    #[test]
+    fn test_votes_after_revocation_are_rejected() {
+        let authority = SignedVote::generate_keypair();
+        let key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let cast = |secs_ago: i64| {
+            SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now - Duration::seconds(secs_ago), DecayType::Linear, &key)
+        };
+        let (before, after) = (cast(60), cast(10));
+
+        let mut list = RevocationList::new([authority.verifying_key()]);
+        let revocation = Revocation::new(key.verifying_key(), now - Duration::seconds(30), "leaked".to_string(), &authority);
+        list.revoke(revocation.clone()).unwrap();
+        assert_eq!(list.revoke(revocation), Err(RegistryError::AlreadyRevoked));
+        let rogue = SignedVote::generate_keypair();
+        let forged = Revocation::new(SignedVote::generate_keypair().verifying_key(), now, String::new(), &rogue);
+        assert_eq!(list.revoke(forged.clone()), Err(RegistryError::UnauthorizedIssuer));
+
+        let restored = RevocationList::from_json(&list.to_json(), [authority.verifying_key()]).unwrap();
+        assert_eq!(restored, list);
+        let policy = VerificationPolicy::new(Seconds::new(300)).with_revocations(restored);
+        assert_eq!(before.verify_with_policy(&policy, now), Ok(()));
+        assert_eq!(after.verify_with_policy(&policy, now), Err(VerificationError::KeyRevoked));
+
+        // The registry and the policy read the same list
+        let mut registry = ValidatorRegistry::new();
+        registry.add_authority(authority.verifying_key());
+        assert_eq!(registry.merge_revocations(&list), Ok(1));
+        assert_eq!(registry.merge_revocations(&list), Ok(0));
+        assert_eq!(registry.revocations(), &list);
+
+        // A saved list cannot be edited into revoking a key or loaded under other authorities
+        let mut tampered: serde_json::Value = serde_json::from_str(&list.to_json()).unwrap();
+        tampered["revocations"][0]["revoked_at"] = serde_json::json!("2000-01-01T00:00:00Z");
+        let tampered = RevocationList::from_json(&tampered.to_string(), [authority.verifying_key()]);
+        assert!(matches!(tampered, Err(RevocationError::Rejected { index: 0, error: RegistryError::InvalidRevocationSignature })));
+        let injected = serde_json::json!({ "revocations": [forged] }).to_string();
+        assert!(matches!(
+            RevocationList::from_json(&injected, [authority.verifying_key()]),
+            Err(RevocationError::Rejected { error: RegistryError::UnauthorizedIssuer, .. })
+        ));
+        assert!(RevocationList::from_json(&list.to_json(), []).is_err());
+        assert!(RevocationList::from_json(r#"{"revocations": [{"public_key": "zz"}]}"#, []).is_err());
+    }
+}
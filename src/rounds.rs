@@ -0,0 +1,231 @@
+// src/rounds.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::threshold::ThresholdEscalator;
+use crate::trust::TrustSource;
+use crate::units::{Seconds, Weight};
+use crate::vote::{ProposalType, SignedVote};
+use crate::weight_engine;
+
+/// When a failed round earns another one, and how the next round differs
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundConfig {
+    /// Total rounds allowed, including the first
+    pub max_rounds: u32,
+    /// A failed round with at least this share of the threshold weight advances
+    pub strong_support: f64,
+    /// Added to the base threshold for each round after the first (negative lowers it)
+    pub threshold_adjustment: f64,
+}
+
+impl RoundConfig {
+    pub fn new() -> Self {
+        Self {
+            max_rounds: 2,
+            strong_support: 0.75,
+            threshold_adjustment: -0.05,
+        }
+    }
+}
+
+impl Default for RoundConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundOutcome {
+    Passed,
+    Failed,
+    /// Failed with strong support; another round opened
+    Advanced,
+}
+
+/// A closed round, as tracked on the proposal and published in reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundRecord {
+    pub number: u32,
+    pub opened_at: DateTime<Utc>,
+    pub closed_at: DateTime<Utc>,
+    pub base_threshold: f64,
+    pub threshold: f64,
+    pub weight: f64,
+    pub votes: usize,
+    pub outcome: RoundOutcome,
+}
+
+/// A proposal voted on in rounds. Each round restarts the decay clock: votes from
+/// earlier rounds carry over but age from the round's opening, and a voter who
+/// votes again replaces their earlier vote.
+#[derive(Debug, Clone)]
+pub struct MultiRoundProposal {
+    pub proposal_id: String,
+    pub proposal_type: ProposalType,
+    pub config: RoundConfig,
+    round_opened_at: DateTime<Utc>,
+    rounds: Vec<RoundRecord>,
+}
+
+impl MultiRoundProposal {
+    pub fn new(proposal_id: &str, proposal_type: ProposalType, opened_at: DateTime<Utc>, config: RoundConfig) -> Self {
+        Self {
+            proposal_id: proposal_id.to_string(),
+            proposal_type,
+            config,
+            round_opened_at: opened_at,
+            rounds: Vec::new(),
+        }
+    }
+
+    /// Number of the open round, or of the last one once decided
+    pub fn round(&self) -> u32 {
+        match self.rounds.last() {
+            Some(last) if last.outcome != RoundOutcome::Advanced => last.number,
+            Some(last) => last.number + 1,
+            None => 1,
+        }
+    }
+
+    pub fn round_opened_at(&self) -> DateTime<Utc> {
+        self.round_opened_at
+    }
+
+    pub fn rounds(&self) -> &[RoundRecord] {
+        &self.rounds
+    }
+
+    /// Final outcome, once a round passed or the proposal ran out of rounds
+    pub fn outcome(&self) -> Option<RoundOutcome> {
+        self.rounds.last().map(|r| r.outcome).filter(|o| *o != RoundOutcome::Advanced)
+    }
+
+    /// Escalator for the open round, with the per-round threshold adjustment applied
+    pub fn escalator(&self) -> ThresholdEscalator {
+        let mut escalator = ThresholdEscalator::for_proposal_type(self.proposal_type.clone());
+        let adjustment = self.config.threshold_adjustment * (self.round() - 1) as f64;
+        escalator.base_threshold = (escalator.base_threshold + adjustment).clamp(0.0, escalator.ceiling);
+        escalator
+    }
+
+    /// Weight of the open round at `now`, and how many voters it counts
+    pub fn weight_at(&self, votes: &[SignedVote], now: DateTime<Utc>, trust: Option<&dyn TrustSource>) -> (f64, usize) {
+        let mut latest: BTreeMap<&str, &SignedVote> = BTreeMap::new();
+        for vote in votes.iter().filter(|v| v.proposal_id == self.proposal_id && v.timestamp <= now) {
+            let entry = latest.entry(&vote.voter_id).or_insert(vote);
+            if vote.timestamp > entry.timestamp {
+                *entry = vote;
+            }
+        }
+        let weight = latest
+            .values()
+            .map(|v| {
+                let age = Seconds::between(v.timestamp.max(self.round_opened_at), now);
+                let decayed = weight_engine::decay_weight(v.decay_model, Weight::saturating(v.original_weight), age);
+                decayed.scale(trust.map_or(1.0, |t| t.get_bonus(&v.voter_id))).get()
+            })
+            .sum();
+        (weight, latest.len())
+    }
+
+    /// Close the open round at `now`. A failed round with strong support opens the
+    /// next one at `now` while rounds remain. Returns `None` once already decided.
+    pub fn close_round(
+        &mut self,
+        votes: &[SignedVote],
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+    ) -> Option<RoundOutcome> {
+        if self.outcome().is_some() {
+            return None;
+        }
+        let mut escalator = self.escalator();
+        let (weight, count) = self.weight_at(votes, now, trust);
+        escalator.total_votes = count;
+        let threshold = escalator.threshold_with_profile(now, self.round_opened_at);
+
+        let number = self.round();
+        let outcome = if escalator.is_threshold_met(weight, threshold) {
+            RoundOutcome::Passed
+        } else if number < self.config.max_rounds && weight >= threshold * self.config.strong_support {
+            RoundOutcome::Advanced
+        } else {
+            RoundOutcome::Failed
+        };
+        self.rounds.push(RoundRecord {
+            number,
+            opened_at: self.round_opened_at,
+            closed_at: now,
+            base_threshold: escalator.base_threshold,
+            threshold,
+            weight,
+            votes: count,
+            outcome,
+        });
+        if outcome == RoundOutcome::Advanced {
+            self.round_opened_at = now;
+        }
+        Some(outcome)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::DecayType;
+    use chrono::Duration;
+
+    fn vote(voter: &str, weight: f64, at: DateTime<Utc>) -> SignedVote {
+        let key = SignedVote::generate_keypair();
+        SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), weight, at, DecayType::Stepped, &key)
+    }
+
+    #[test]
+    fn test_strong_support_opens_second_round() {
+        let opened = Utc::now();
+        let mut proposal = MultiRoundProposal::new("p1", ProposalType::Normal, opened, RoundConfig::default());
+        let mut votes = vec![
+            vote("alice", 0.3, opened + Duration::seconds(1)),
+            vote("bob", 0.3, opened + Duration::seconds(2)),
+        ];
+
+        // Two votes miss the three-vote quorum but carry plenty of weight
+        let first_close = opened + Duration::seconds(10);
+        assert_eq!(proposal.close_round(&votes, first_close, None), Some(RoundOutcome::Advanced));
+        assert_eq!(proposal.round(), 2);
+        assert_eq!(proposal.round_opened_at(), first_close);
+        assert!((proposal.escalator().base_threshold - 0.46).abs() < 1e-9);
+
+        // Round-one votes age from the new opening, so they are not stepped down yet
+        votes.push(vote("carol", 0.3, first_close + Duration::seconds(5)));
+        let second_close = first_close + Duration::seconds(30);
+        let (weight, count) = proposal.weight_at(&votes, second_close, None);
+        assert_eq!(count, 3);
+        assert!((weight - 0.9).abs() < 1e-9);
+
+        assert_eq!(proposal.close_round(&votes, second_close, None), Some(RoundOutcome::Passed));
+        assert_eq!(proposal.outcome(), Some(RoundOutcome::Passed));
+        assert_eq!(proposal.rounds().len(), 2);
+        assert_eq!(proposal.close_round(&votes, second_close, None), None);
+    }
+
+    #[test]
+    fn test_weak_support_or_last_round_fails() {
+        let opened = Utc::now();
+        let mut weak = MultiRoundProposal::new("p1", ProposalType::Normal, opened, RoundConfig::default());
+        let votes = vec![vote("alice", 0.1, opened + Duration::seconds(1))];
+        assert_eq!(weak.close_round(&votes, opened + Duration::seconds(10), None), Some(RoundOutcome::Failed));
+        assert_eq!(weak.round(), 1);
+
+        let config = RoundConfig { max_rounds: 1, ..RoundConfig::default() };
+        let mut single = MultiRoundProposal::new("p1", ProposalType::Normal, opened, config);
+        let strong = vec![vote("alice", 0.5, opened), vote("bob", 0.5, opened)];
+        assert_eq!(single.close_round(&strong, opened + Duration::seconds(10), None), Some(RoundOutcome::Failed));
+    }
+}
@@ -0,0 +1,180 @@
+// src/schema.rs
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema, schema_for};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use thiserror::Error;
+
+/// Directory the published schemas are committed to
+pub const SCHEMA_DIR: &str = "schemas";
+
+/// One place an input breaks its schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value; empty for the document itself
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "(root)" } else { &self.path };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    #[error("Invalid JSON at line {line}, column {column}: {message}")]
+    Syntax { line: usize, column: usize, message: String },
+    #[error("Input does not match the {schema} schema: {}", format_violations(.violations))]
+    Invalid { schema: String, violations: Vec<SchemaViolation> },
+    /// Passed the schema but failed a check it cannot express
+    #[error("Invalid {schema}: {message}")]
+    Rejected { schema: String, message: String },
+}
+
+fn format_violations(violations: &[SchemaViolation]) -> String {
+    violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+impl SchemaError {
+    /// Every violation, in path order; empty for syntax errors and rejections
+    pub fn violations(&self) -> &[SchemaViolation] {
+        match self {
+            SchemaError::Invalid { violations, .. } => violations,
+            _ => &[],
+        }
+    }
+}
+
+fn validators() -> &'static Mutex<HashMap<TypeId, Arc<jsonschema::Validator>>> {
+    static VALIDATORS: OnceLock<Mutex<HashMap<TypeId, Arc<jsonschema::Validator>>>> = OnceLock::new();
+    VALIDATORS.get_or_init(Mutex::default)
+}
+
+/// Compiled validator for `T`'s schema, built once per type
+fn validator<T: JsonSchema + 'static>() -> Arc<jsonschema::Validator> {
+    let mut validators = validators().lock().expect("schema cache lock poisoned");
+    let validator = validators.entry(TypeId::of::<T>()).or_insert_with(|| {
+        let schema = schema_for!(T);
+        Arc::new(jsonschema::validator_for(schema.as_value()).expect("generated schemas are valid"))
+    });
+    Arc::clone(validator)
+}
+
+/// Check `instance` against `T`'s schema, collecting every violation
+pub fn validate<T: JsonSchema + 'static>(instance: &Value) -> Result<(), SchemaError> {
+    let mut violations: Vec<SchemaViolation> = validator::<T>()
+        .iter_errors(instance)
+        .map(|e| SchemaViolation {
+            path: e.instance_path.to_string(),
+            message: e.to_string(),
+        })
+        .collect();
+    if violations.is_empty() {
+        return Ok(());
+    }
+    violations.sort_by(|a, b| a.path.cmp(&b.path));
+    Err(SchemaError::Invalid { schema: T::schema_name().into_owned(), violations })
+}
+
+/// Parse external JSON as `T`, validating it against `T`'s published schema first so
+/// callers get every offending path rather than serde's first error
+pub fn parse<T: JsonSchema + DeserializeOwned + 'static>(json: &str) -> Result<T, SchemaError> {
+    let value: Value = serde_json::from_str(json).map_err(|e| SchemaError::Syntax {
+        line: e.line(),
+        column: e.column(),
+        message: e.to_string(),
+    })?;
+    validate::<T>(&value)?;
+    serde_json::from_value(value).map_err(|e| SchemaError::Rejected {
+        schema: T::schema_name().into_owned(),
+        message: e.to_string(),
+    })
+}
+
+/// Schema for a string of `N` hex-encoded bytes, as keys and signatures travel in JSON
+pub fn hex_bytes<const N: usize>(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": "string",
+        "pattern": format!("^[0-9a-fA-F]{{{}}}$", N * 2),
+    })
+}
+
+/// Every externally supplied document, by the name its schema is published under
+pub fn published() -> Vec<(&'static str, Schema)> {
+    vec![
+        ("vote", schema_for!(crate::vote::SignedVote)),
+        ("vote_batch", schema_for!(crate::vote::SignedVoteBatch)),
+        ("vote_receipt", schema_for!(crate::receipt::VoteReceipt)),
+        ("signed_outcome", schema_for!(crate::webhook::SignedOutcome)),
+        ("import_mapping", schema_for!(crate::import::ImportMapping)),
+        ("plugin_config", schema_for!(crate::plugins::PluginConfig)),
+        ("signer_policy", schema_for!(crate::remote_signer::SignerPolicy)),
+        ("message_catalog", schema_for!(crate::messages::CatalogFile)),
+        ("reputation_export", schema_for!(crate::reputation::ReputationExport)),
+        ("revocation_list", schema_for!(crate::revocation::RevocationList)),
+        ("test_vectors", schema_for!(crate::vectors::TestVectors)),
+        ("sync_page", schema_for!(crate::replica::SyncPage)),
+    ]
+}
+
+/// Pretty JSON of a published schema, as committed under `schemas/`
+pub fn to_json(schema: &Schema) -> String {
+    serde_json::to_string_pretty(schema).expect("schemas serialize") + "\n"
+}
+
+/// Write every published schema to `dir` as `<name>.schema.json`
+pub fn write_all(dir: impl AsRef<Path>) -> std::io::Result<Vec<String>> {
+    std::fs::create_dir_all(&dir)?;
+    let mut written = Vec::new();
+    for (name, schema) in published() {
+        let path = dir.as_ref().join(format!("{}.schema.json", name));
+        std::fs::write(&path, to_json(&schema))?;
+        written.push(path.display().to_string());
+    }
+    Ok(written)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::{DecayType, SignedVote};
+
+    #[test]
+    fn test_published_schemas_are_committed() {
+        for (name, schema) in published() {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(SCHEMA_DIR).join(format!("{}.schema.json", name));
+            let committed = std::fs::read_to_string(&path).unwrap_or_default();
+            assert_eq!(committed, to_json(&schema), "{} is stale; run `cargo run -- schemas`", path.display());
+        }
+    }
+
+    #[test]
+    fn test_violations_carry_paths() {
+        let key = SignedVote::generate_keypair();
+        let vote = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, chrono::Utc::now(), DecayType::Linear, &key);
+        let mut json: Value = serde_json::to_value(&vote).unwrap();
+        assert_eq!(parse::<SignedVote>(&json.to_string()).unwrap().voter_id, vote.voter_id);
+
+        json["voter_id"] = "bad id".into();
+        json["decay_model"] = "Quadratic".into();
+        json["signature"] = "zz".into();
+        let err = parse::<SignedVote>(&json.to_string()).unwrap_err();
+        let paths: Vec<&str> = err.violations().iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, vec!["/decay_model", "/signature", "/voter_id"]);
+        assert!(err.to_string().contains("/voter_id:"));
+
+        let missing = parse::<SignedVote>("{}").unwrap_err();
+        assert_eq!(missing.violations()[0].path, "");
+        assert!(matches!(parse::<SignedVote>("{\"voter_id\":"), Err(SchemaError::Syntax { line: 1, .. })));
+    }
+}
@@ -0,0 +1,378 @@
+// src/scheme.rs
+
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SchemeError {
+    #[error("Malformed {scheme} public key")]
+    InvalidPublicKey { scheme: &'static str },
+    #[error("Malformed {scheme} signature")]
+    MalformedSignature { scheme: &'static str },
+    #[error("{scheme} signature does not verify")]
+    VerificationFailed { scheme: &'static str },
+    #[error("Nothing to aggregate")]
+    EmptyAggregate,
+    #[error("Aggregated messages must be distinct")]
+    DuplicateMessage,
+}
+
+/// A signature algorithm votes can be signed with. Keys and signatures travel as bytes,
+/// so callers never depend on a particular library's types.
+pub trait SignatureScheme {
+    const NAME: &'static str;
+    type SecretKey;
+    type PublicKey: Clone;
+    type Signature: Clone;
+
+    fn public_key(secret: &Self::SecretKey) -> Self::PublicKey;
+    fn sign(secret: &Self::SecretKey, message: &[u8]) -> Self::Signature;
+    fn verify(public: &Self::PublicKey, message: &[u8], signature: &Self::Signature) -> Result<(), SchemeError>;
+
+    fn public_key_bytes(public: &Self::PublicKey) -> Vec<u8>;
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey, SchemeError>;
+    fn signature_bytes(signature: &Self::Signature) -> Vec<u8>;
+    fn signature_from_bytes(bytes: &[u8]) -> Result<Self::Signature, SchemeError>;
+}
+
+/// A scheme whose signatures over distinct messages combine into one
+pub trait AggregateScheme: SignatureScheme {
+    fn aggregate(signatures: &[Self::Signature]) -> Result<Self::Signature, SchemeError>;
+
+    /// Check one aggregate against every `(public key, message)` pair it was built from
+    fn verify_aggregate(signed: &[(Self::PublicKey, &[u8])], aggregate: &Self::Signature) -> Result<(), SchemeError>;
+}
+
+/// Scheme `SignedVote` uses
+pub type DefaultScheme = Ed25519;
+
+pub struct Ed25519;
+
+impl SignatureScheme for Ed25519 {
+    const NAME: &'static str = "ed25519";
+    type SecretKey = ed25519_dalek::SigningKey;
+    type PublicKey = ed25519_dalek::VerifyingKey;
+    type Signature = ed25519_dalek::Signature;
+
+    fn public_key(secret: &Self::SecretKey) -> Self::PublicKey {
+        secret.verifying_key()
+    }
+
+    fn sign(secret: &Self::SecretKey, message: &[u8]) -> Self::Signature {
+        ed25519_dalek::Signer::sign(secret, message)
+    }
+
+    fn verify(public: &Self::PublicKey, message: &[u8], signature: &Self::Signature) -> Result<(), SchemeError> {
+        ed25519_dalek::Verifier::verify(public, message, signature)
+            .map_err(|_| SchemeError::VerificationFailed { scheme: Self::NAME })
+    }
+
+    fn public_key_bytes(public: &Self::PublicKey) -> Vec<u8> {
+        public.as_bytes().to_vec()
+    }
+
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey, SchemeError> {
+        let invalid = SchemeError::InvalidPublicKey { scheme: Self::NAME };
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| invalid.clone())?;
+        ed25519_dalek::VerifyingKey::from_bytes(&bytes).map_err(|_| invalid)
+    }
+
+    fn signature_bytes(signature: &Self::Signature) -> Vec<u8> {
+        signature.to_bytes().to_vec()
+    }
+
+    fn signature_from_bytes(bytes: &[u8]) -> Result<Self::Signature, SchemeError> {
+        ed25519_dalek::Signature::from_slice(bytes).map_err(|_| SchemeError::MalformedSignature { scheme: Self::NAME })
+    }
+}
+
+/// BLS12-381 with public keys in G1 and signatures in G2 (`--features bls`)
+#[cfg(feature = "bls")]
+pub struct Bls12381;
+
+#[cfg(feature = "bls")]
+impl Bls12381 {
+    /// Ciphersuite tag of the basic scheme; aggregation therefore requires distinct messages,
+    /// which votes satisfy because every vote message names its voter
+    pub const DST: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+    /// Derive a secret key from at least 32 bytes of key material
+    pub fn key_from_seed(seed: &[u8]) -> Option<blst::min_pk::SecretKey> {
+        blst::min_pk::SecretKey::key_gen(seed, &[]).ok()
+    }
+
+    pub fn generate_key() -> blst::min_pk::SecretKey {
+        let mut seed = [0u8; 32];
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut seed);
+        Self::key_from_seed(&seed).expect("32 bytes of key material")
+    }
+}
+
+#[cfg(feature = "bls")]
+impl SignatureScheme for Bls12381 {
+    const NAME: &'static str = "bls12-381";
+    type SecretKey = blst::min_pk::SecretKey;
+    type PublicKey = blst::min_pk::PublicKey;
+    type Signature = blst::min_pk::Signature;
+
+    fn public_key(secret: &Self::SecretKey) -> Self::PublicKey {
+        secret.sk_to_pk()
+    }
+
+    fn sign(secret: &Self::SecretKey, message: &[u8]) -> Self::Signature {
+        secret.sign(message, Self::DST, &[])
+    }
+
+    fn verify(public: &Self::PublicKey, message: &[u8], signature: &Self::Signature) -> Result<(), SchemeError> {
+        match signature.verify(true, message, Self::DST, &[], public, true) {
+            blst::BLST_ERROR::BLST_SUCCESS => Ok(()),
+            _ => Err(SchemeError::VerificationFailed { scheme: Self::NAME }),
+        }
+    }
+
+    fn public_key_bytes(public: &Self::PublicKey) -> Vec<u8> {
+        public.compress().to_vec()
+    }
+
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey, SchemeError> {
+        blst::min_pk::PublicKey::key_validate(bytes).map_err(|_| SchemeError::InvalidPublicKey { scheme: Self::NAME })
+    }
+
+    fn signature_bytes(signature: &Self::Signature) -> Vec<u8> {
+        signature.compress().to_vec()
+    }
+
+    fn signature_from_bytes(bytes: &[u8]) -> Result<Self::Signature, SchemeError> {
+        blst::min_pk::Signature::sig_validate(bytes, true)
+            .map_err(|_| SchemeError::MalformedSignature { scheme: Self::NAME })
+    }
+}
+
+#[cfg(feature = "bls")]
+impl AggregateScheme for Bls12381 {
+    fn aggregate(signatures: &[Self::Signature]) -> Result<Self::Signature, SchemeError> {
+        if signatures.is_empty() {
+            return Err(SchemeError::EmptyAggregate);
+        }
+        let refs: Vec<&Self::Signature> = signatures.iter().collect();
+        blst::min_pk::AggregateSignature::aggregate(&refs, true)
+            .map(|aggregate| aggregate.to_signature())
+            .map_err(|_| SchemeError::MalformedSignature { scheme: Self::NAME })
+    }
+
+    fn verify_aggregate(signed: &[(Self::PublicKey, &[u8])], aggregate: &Self::Signature) -> Result<(), SchemeError> {
+        if signed.is_empty() {
+            return Err(SchemeError::EmptyAggregate);
+        }
+        let messages: Vec<&[u8]> = signed.iter().map(|(_, m)| *m).collect();
+        if messages.iter().collect::<std::collections::HashSet<_>>().len() != messages.len() {
+            return Err(SchemeError::DuplicateMessage);
+        }
+        let keys: Vec<&Self::PublicKey> = signed.iter().map(|(k, _)| k).collect();
+        match aggregate.aggregate_verify(true, &messages, Self::DST, &keys, true) {
+            blst::BLST_ERROR::BLST_SUCCESS => Ok(()),
+            _ => Err(SchemeError::VerificationFailed { scheme: Self::NAME }),
+        }
+    }
+}
+
+/// secp256k1 ECDSA as Ethereum wallets sign (`--features secp256k1`): messages are
+/// prefixed per EIP-191 and hashed with Keccak-256, and signatures carry a recovery id
+/// so the signer's key, and so its address, can be recovered from the signature alone.
+#[cfg(feature = "secp256k1")]
+pub struct Secp256k1;
+
+/// An ECDSA signature with the recovery id that selects the signing key
+#[cfg(feature = "secp256k1")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoverableSignature {
+    pub signature: k256::ecdsa::Signature,
+    pub recovery_id: k256::ecdsa::RecoveryId,
+}
+
+#[cfg(feature = "secp256k1")]
+impl Secp256k1 {
+    pub fn generate_key() -> k256::ecdsa::SigningKey {
+        k256::ecdsa::SigningKey::random(&mut rand::rngs::OsRng)
+    }
+
+    /// Keccak-256 of the EIP-191 `personal_sign` envelope around `message`
+    pub fn prehash(message: &[u8]) -> [u8; 32] {
+        use sha3::Digest;
+        let mut hasher = sha3::Keccak256::new();
+        hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()).as_bytes());
+        hasher.update(message);
+        hasher.finalize().into()
+    }
+
+    /// The key that produced `signature` over `message`
+    pub fn recover(message: &[u8], signature: &RecoverableSignature) -> Result<k256::ecdsa::VerifyingKey, SchemeError> {
+        k256::ecdsa::VerifyingKey::recover_from_prehash(&Self::prehash(message), &signature.signature, signature.recovery_id)
+            .map_err(|_| SchemeError::VerificationFailed { scheme: Self::NAME })
+    }
+
+    /// Ethereum-style `0x` address: the last 20 bytes of the Keccak-256 of the uncompressed key
+    pub fn address(public: &k256::ecdsa::VerifyingKey) -> String {
+        use sha3::Digest;
+        let point = public.to_encoded_point(false);
+        let hash = sha3::Keccak256::digest(&point.as_bytes()[1..]);
+        format!("0x{}", hex::encode(&hash[12..]))
+    }
+
+    /// The address as a voter id, so wallet users vote under their existing identity
+    pub fn voter_id(public: &k256::ecdsa::VerifyingKey) -> crate::ids::VoterId {
+        crate::ids::VoterId::new(Self::address(public)).expect("hex addresses are valid ids")
+    }
+
+    /// Verify that `signature` over `message` was made by the key behind `voter_id`'s address
+    pub fn verify_address(
+        voter_id: &crate::ids::VoterId,
+        message: &[u8],
+        signature: &RecoverableSignature,
+    ) -> Result<(), SchemeError> {
+        let public = Self::recover(message, signature)?;
+        if !Self::address(&public).eq_ignore_ascii_case(voter_id.as_str()) {
+            return Err(SchemeError::VerificationFailed { scheme: Self::NAME });
+        }
+        Self::verify(&public, message, signature)
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+impl SignatureScheme for Secp256k1 {
+    const NAME: &'static str = "secp256k1";
+    type SecretKey = k256::ecdsa::SigningKey;
+    type PublicKey = k256::ecdsa::VerifyingKey;
+    type Signature = RecoverableSignature;
+
+    fn public_key(secret: &Self::SecretKey) -> Self::PublicKey {
+        *secret.verifying_key()
+    }
+
+    fn sign(secret: &Self::SecretKey, message: &[u8]) -> Self::Signature {
+        let (signature, recovery_id) = secret
+            .sign_prehash_recoverable(&Self::prehash(message))
+            .expect("32-byte digests always sign");
+        RecoverableSignature { signature, recovery_id }
+    }
+
+    /// High-S signatures are rejected, as Ethereum does, so each signature has one encoding
+    fn verify(public: &Self::PublicKey, message: &[u8], signature: &Self::Signature) -> Result<(), SchemeError> {
+        use k256::ecdsa::signature::hazmat::PrehashVerifier;
+        let failed = SchemeError::VerificationFailed { scheme: Self::NAME };
+        if signature.signature.normalize_s().is_some() {
+            return Err(failed);
+        }
+        public.verify_prehash(&Self::prehash(message), &signature.signature).map_err(|_| failed)
+    }
+
+    /// Compressed SEC1 encoding, 33 bytes
+    fn public_key_bytes(public: &Self::PublicKey) -> Vec<u8> {
+        public.to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    /// Accepts compressed or uncompressed SEC1
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey, SchemeError> {
+        k256::ecdsa::VerifyingKey::from_sec1_bytes(bytes).map_err(|_| SchemeError::InvalidPublicKey { scheme: Self::NAME })
+    }
+
+    /// `r || s || v` with Ethereum's `v` of 27 or 28
+    fn signature_bytes(signature: &Self::Signature) -> Vec<u8> {
+        let mut bytes = signature.signature.to_bytes().to_vec();
+        bytes.push(27 + signature.recovery_id.to_byte());
+        bytes
+    }
+
+    /// Takes `v` as 0/1 or 27/28
+    fn signature_from_bytes(bytes: &[u8]) -> Result<Self::Signature, SchemeError> {
+        let malformed = SchemeError::MalformedSignature { scheme: Self::NAME };
+        let (rs, v) = match bytes {
+            [rs @ .., v] if rs.len() == 64 => (rs, *v),
+            _ => return Err(malformed),
+        };
+        let recovery_id = k256::ecdsa::RecoveryId::from_byte(if v >= 27 { v - 27 } else { v }).ok_or(malformed.clone())?;
+        let signature = k256::ecdsa::Signature::from_slice(rs).map_err(|_| malformed)?;
+        Ok(RecoverableSignature { signature, recovery_id })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::{VoteChoice, vote_message};
+    use chrono::Utc;
+
+    fn round_trip<S: SignatureScheme>(secret: &S::SecretKey) {
+        let message = vote_message("alice", "p1", Utc::now(), VoteChoice::Approve, 0, 0);
+        let signature = S::sign(secret, message.as_bytes());
+        let public = S::public_key_from_bytes(&S::public_key_bytes(&S::public_key(secret))).unwrap();
+        let decoded = S::signature_from_bytes(&S::signature_bytes(&signature)).unwrap();
+
+        assert_eq!(S::verify(&public, message.as_bytes(), &decoded), Ok(()));
+        assert_eq!(
+            S::verify(&public, b"tampered", &decoded),
+            Err(SchemeError::VerificationFailed { scheme: S::NAME })
+        );
+        assert!(S::public_key_from_bytes(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_ed25519_round_trip() {
+        round_trip::<Ed25519>(&crate::vote::SignedVote::generate_keypair());
+    }
+
+    #[cfg(feature = "bls")]
+    #[test]
+    fn test_bls_round_trip_and_aggregation() {
+        round_trip::<Bls12381>(&Bls12381::generate_key());
+
+        let now = Utc::now();
+        let keys: Vec<_> = (0..3).map(|_| Bls12381::generate_key()).collect();
+        let messages: Vec<String> = ["alice", "bob", "carol"]
+            .iter()
+            .map(|voter| vote_message(voter, "p1", now, VoteChoice::Approve, 0, 0))
+            .collect();
+        let signatures: Vec<_> = keys.iter().zip(&messages).map(|(k, m)| Bls12381::sign(k, m.as_bytes())).collect();
+        let aggregate = Bls12381::aggregate(&signatures).unwrap();
+
+        let mut signed: Vec<_> = keys.iter().zip(&messages).map(|(k, m)| (Bls12381::public_key(k), m.as_bytes())).collect();
+        assert_eq!(Bls12381::verify_aggregate(&signed, &aggregate), Ok(()));
+        assert_eq!(Bls12381::signature_bytes(&aggregate).len(), 96);
+
+        signed[2].1 = messages[1].as_bytes();
+        assert_eq!(Bls12381::verify_aggregate(&signed, &aggregate), Err(SchemeError::DuplicateMessage));
+        signed.pop();
+        assert!(Bls12381::verify_aggregate(&signed, &aggregate).is_err());
+        assert_eq!(Bls12381::aggregate(&[]), Err(SchemeError::EmptyAggregate));
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_secp256k1_round_trip_and_recovery() {
+        round_trip::<Secp256k1>(&Secp256k1::generate_key());
+
+        // Hardhat's first development account
+        let key = k256::ecdsa::SigningKey::from_slice(
+            &hex::decode("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80").unwrap(),
+        )
+        .unwrap();
+        let public = Secp256k1::public_key(&key);
+        let voter = Secp256k1::voter_id(&public);
+        assert_eq!(voter.as_str(), "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+
+        let message = vote_message(&voter, "p1", Utc::now(), VoteChoice::Approve, 0, 0);
+        let signature = Secp256k1::sign(&key, message.as_bytes());
+        let bytes = Secp256k1::signature_bytes(&signature);
+        assert_eq!(bytes.len(), 65);
+        assert!(matches!(bytes[64], 27 | 28));
+        assert_eq!(Secp256k1::recover(message.as_bytes(), &signature), Ok(public));
+        assert_eq!(Secp256k1::verify_address(&voter, message.as_bytes(), &signature), Ok(()));
+
+        let mut raw_v = bytes.clone();
+        raw_v[64] -= 27;
+        assert_eq!(Secp256k1::signature_from_bytes(&raw_v), Ok(signature));
+        let stranger = Secp256k1::voter_id(&Secp256k1::public_key(&Secp256k1::generate_key()));
+        assert!(Secp256k1::verify_address(&stranger, message.as_bytes(), &signature).is_err());
+        assert!(Secp256k1::verify_address(&voter, b"tampered", &signature).is_err());
+    }
+}
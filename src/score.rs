@@ -0,0 +1,156 @@
+// src/score.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::decay::DecayModel;
+use crate::threshold::ThresholdEscalator;
+use crate::units::{Seconds, Weight};
+use crate::vote::DecayType;
+
+/// One amount credited to `key` at `at`. `scale` multiplies the amount after it
+/// has decayed, so a floor or step schedule applies to the amount itself; past
+/// `expires_at` the entry is worth nothing.
+#[derive(Debug, Clone)]
+pub struct ScoreEntry<T> {
+    pub key: T,
+    pub amount: Weight,
+    pub at: DateTime<Utc>,
+    pub decay: DecayType,
+    pub scale: f64,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl<T> ScoreEntry<T> {
+    /// The entry's decayed, scaled value as of `at`
+    pub fn value_at(&self, at: DateTime<Utc>) -> Weight {
+        if self.expires_at.is_some_and(|e| at >= e) {
+            return Weight::ZERO;
+        }
+        self.decay.decay(self.amount, Seconds::between(self.at, at)).scale(self.scale)
+    }
+}
+
+/// Time-decaying contributions keyed by `T`, independent of votes: add amounts as
+/// they arrive, read any key's or the whole score's value at a later time, and
+/// prune entries that have aged out of the window.
+#[derive(Debug, Clone)]
+pub struct DecayingScore<T> {
+    decay: DecayType,
+    window: Option<Seconds>,
+    entries: Vec<ScoreEntry<T>>,
+}
+
+impl<T: Ord + Clone> DecayingScore<T> {
+    /// An empty score whose plain `add`s decay under `decay`
+    pub fn new(decay: DecayType) -> Self {
+        Self { decay, window: None, entries: Vec::new() }
+    }
+
+    /// Drop entries older than `window` from every query, and from storage on `prune`
+    pub fn with_window(mut self, window: Seconds) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    pub fn add(&mut self, key: T, amount: Weight, at: DateTime<Utc>) {
+        self.push(ScoreEntry { key, amount, at, decay: self.decay, scale: 1.0, expires_at: None });
+    }
+
+    /// Add an entry with its own decay model and post-decay scale
+    pub fn push(&mut self, entry: ScoreEntry<T>) {
+        self.entries.push(entry);
+    }
+
+    pub fn entries(&self) -> &[ScoreEntry<T>] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn live(&self, at: DateTime<Utc>) -> impl Iterator<Item = &ScoreEntry<T>> {
+        let window = self.window;
+        self.entries.iter().filter(move |e| e.at <= at && !expired(window, e, at))
+    }
+
+    /// `key`'s decayed value as of `at`; entries added after `at` don't count yet
+    pub fn value(&self, key: &T, at: DateTime<Utc>) -> Weight {
+        self.live(at).filter(|e| e.key == *key).map(|e| e.value_at(at)).sum()
+    }
+
+    pub fn total(&self, at: DateTime<Utc>) -> Weight {
+        self.live(at).map(|e| e.value_at(at)).sum()
+    }
+
+    /// Every key's decayed value as of `at`
+    pub fn values(&self, at: DateTime<Utc>) -> BTreeMap<T, Weight> {
+        let mut values = BTreeMap::new();
+        for entry in self.live(at) {
+            let value = values.entry(entry.key.clone()).or_insert(Weight::ZERO);
+            *value = *value + entry.value_at(at);
+        }
+        values
+    }
+
+    /// Whether the total as of `at` meets `escalator`'s threshold for a round opened at `opened_at`
+    pub fn reaches(&self, escalator: &ThresholdEscalator, opened_at: DateTime<Utc>, at: DateTime<Utc>) -> bool {
+        self.total(at).get() >= escalator.threshold_with_profile(at, opened_at)
+    }
+
+    /// Remove entries that have aged out of the window or expired as of `now`, returning how many
+    pub fn prune(&mut self, now: DateTime<Utc>) -> usize {
+        let before = self.entries.len();
+        let window = self.window;
+        self.entries.retain(|e| !expired(window, e, now));
+        before - self.entries.len()
+    }
+}
+
+fn expired<T>(window: Option<Seconds>, entry: &ScoreEntry<T>, at: DateTime<Utc>) -> bool {
+    window.is_some_and(|w| Seconds::between(entry.at, at) > w) || entry.expires_at.is_some_and(|e| at >= e)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_score_decays_and_prunes() {
+        let start = Utc::now();
+        let mut score = DecayingScore::new(DecayType::Linear).with_window(Seconds::new(600));
+        score.add("alice", Weight::saturating(1.0), start);
+        score.add("bob", Weight::saturating(2.0), start + Duration::seconds(300));
+        score.push(ScoreEntry {
+            key: "alice",
+            amount: Weight::saturating(1.0),
+            at: start + Duration::seconds(500),
+            decay: DecayType::Stepped,
+            scale: 2.0,
+            expires_at: None,
+        });
+
+        // Entries after the query time don't count yet
+        assert!((score.total(start + Duration::seconds(100)).get() - 0.9).abs() < 1e-9);
+
+        let at = start + Duration::seconds(700);
+        // alice's first entry has left the window; her second is 200s into the steps
+        assert!((score.value(&"alice", at).get() - 2.0 * 0.5).abs() < 1e-9);
+        assert!((score.value(&"bob", at).get() - (2.0 - 0.4)).abs() < 1e-9);
+        let values = score.values(at);
+        assert_eq!(values.keys().copied().collect::<Vec<_>>(), vec!["alice", "bob"]);
+        assert!((score.total(at).get() - 2.6).abs() < 1e-9);
+
+        assert_eq!(score.prune(at), 1);
+        assert_eq!(score.len(), 2);
+        assert!((score.total(at).get() - 2.6).abs() < 1e-9);
+    }
+}
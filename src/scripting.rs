@@ -0,0 +1,211 @@
+// src/scripting.rs
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use thiserror::Error;
+
+use crate::hooks::{AdmissionPolicy, ExtensionPolicy, HookError, WeightModifier};
+use crate::vote::SignedVote;
+
+#[derive(Error, Debug)]
+pub enum ScriptError {
+    #[error("Failed to read script: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Script does not compile: {0}")]
+    Compile(String),
+}
+
+/// Resource limits applied to every hook call
+#[derive(Debug, Clone)]
+pub struct ScriptLimits {
+    pub max_operations: u64,
+    pub max_call_levels: usize,
+    pub max_expr_depth: usize,
+    pub max_string_size: usize,
+    pub max_array_size: usize,
+    pub max_map_size: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 10_000,
+            max_call_levels: 8,
+            max_expr_depth: 32,
+            max_string_size: 1024,
+            max_array_size: 256,
+            max_map_size: 64,
+        }
+    }
+}
+
+/// Rhai script implementing any of the policy hooks. A script defines some of:
+///
+/// - `fn modify_weight(vote, weight)` returning the new weight
+/// - `fn admit(vote, now)` returning `true`, `false`, or a rejection reason string
+/// - `fn extension_secs(time_left, weight, threshold)` returning seconds to add
+///
+/// Hooks the script leaves out keep the default behaviour. `vote` is a map with
+/// `voter_id`, `proposal_id`, `timestamp` (unix seconds), `original_weight` and
+/// `decay_model`; `now` is unix seconds. Scripts cannot import modules, print, or eval.
+pub struct ScriptPolicy {
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptPolicy {
+    pub fn compile(source: &str, limits: &ScriptLimits) -> Result<Self, ScriptError> {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(limits.max_operations)
+            .set_max_call_levels(limits.max_call_levels)
+            .set_max_expr_depths(limits.max_expr_depth, limits.max_expr_depth)
+            .set_max_string_size(limits.max_string_size)
+            .set_max_array_size(limits.max_array_size)
+            .set_max_map_size(limits.max_map_size)
+            .set_max_modules(0)
+            .on_print(|_| {})
+            .on_debug(|_, _, _| {});
+        engine.disable_symbol("eval").disable_symbol("import");
+
+        let ast = engine
+            .compile(source)
+            .map_err(|e| ScriptError::Compile(e.to_string()))?;
+        Ok(Self { engine, ast })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>, limits: &ScriptLimits) -> Result<Self, ScriptError> {
+        Self::compile(&std::fs::read_to_string(path)?, limits)
+    }
+
+    fn defines(&self, name: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name)
+    }
+
+    fn call(&self, name: &str, args: impl rhai::FuncArgs) -> Result<Dynamic, HookError> {
+        self.engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, name, args)
+            .map_err(|e| HookError(format!("{}: {}", name, e)))
+    }
+}
+
+fn vote_map(vote: &SignedVote) -> Map {
+    let mut map = Map::new();
+    map.insert("voter_id".into(), vote.voter_id.to_string().into());
+    map.insert("proposal_id".into(), vote.proposal_id.to_string().into());
+    map.insert("timestamp".into(), vote.timestamp.timestamp().into());
+    map.insert("original_weight".into(), vote.original_weight.into());
+    map.insert("decay_model".into(), format!("{:?}", vote.decay_model).into());
+    map
+}
+
+fn as_f64(name: &str, value: Dynamic) -> Result<f64, HookError> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|i| i as f64))
+        .map_err(|t| HookError(format!("{} returned {}, expected a number", name, t)))
+}
+
+impl WeightModifier for ScriptPolicy {
+    fn modify_weight(&self, vote: &SignedVote, weight: f64) -> Result<f64, HookError> {
+        if !self.defines("modify_weight") {
+            return Ok(weight);
+        }
+        let modified = as_f64("modify_weight", self.call("modify_weight", (vote_map(vote), weight))?)?;
+        if !modified.is_finite() || modified < 0.0 {
+            return Err(HookError(format!("modify_weight returned invalid weight {}", modified)));
+        }
+        Ok(modified)
+    }
+}
+
+impl AdmissionPolicy for ScriptPolicy {
+    fn admit(&self, vote: &SignedVote, now: DateTime<Utc>) -> Result<Option<String>, HookError> {
+        if !self.defines("admit") {
+            return Ok(None);
+        }
+        let verdict = self.call("admit", (vote_map(vote), now.timestamp()))?;
+        if let Ok(admitted) = verdict.as_bool() {
+            return Ok((!admitted).then(|| "rejected by admission script".to_string()));
+        }
+        if verdict.is_string() {
+            return Ok(Some(verdict.into_string().expect("checked is_string")));
+        }
+        Err(HookError(format!("admit returned {}, expected bool or string", verdict.type_name())))
+    }
+}
+
+impl ExtensionPolicy for ScriptPolicy {
+    fn extension_secs(&self, time_left: i64, weight: f64, threshold: f64) -> Result<u64, HookError> {
+        if !self.defines("extension_secs") {
+            return Ok(0);
+        }
+        let secs = self
+            .call("extension_secs", (time_left, weight, threshold))?
+            .as_int()
+            .map_err(|t| HookError(format!("extension_secs returned {}, expected an integer", t)))?;
+        u64::try_from(secs).map_err(|_| HookError(format!("extension_secs returned negative {}", secs)))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::DecayType;
+
+    fn vote(voter: &str) -> SignedVote {
+        let key = SignedVote::generate_keypair();
+        SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 2.0, Utc::now(), DecayType::Linear, &key)
+    }
+
+    fn policy(source: &str) -> ScriptPolicy {
+        ScriptPolicy::compile(source, &ScriptLimits::default()).unwrap()
+    }
+
+    #[test]
+    fn test_script_hooks() {
+        let script = policy(
+            r#"
+            fn modify_weight(vote, weight) { if weight > 1.0 { 1.0 } else { weight } }
+            fn admit(vote, now) {
+                if vote.voter_id.starts_with("banned") { "voter is banned" } else { true }
+            }
+            fn extension_secs(time_left, weight, threshold) {
+                if time_left < 30 && weight >= threshold * 0.8 { 60 } else { 0 }
+            }
+            "#,
+        );
+
+        assert_eq!(script.modify_weight(&vote("alice"), 1.7), Ok(1.0));
+        assert_eq!(script.admit(&vote("alice"), Utc::now()), Ok(None));
+        assert_eq!(
+            script.admit(&vote("banned_bob"), Utc::now()),
+            Ok(Some("voter is banned".to_string()))
+        );
+        assert_eq!(script.extension_secs(10, 0.5, 0.6), Ok(60));
+        assert_eq!(script.extension_secs(100, 0.5, 0.6), Ok(0));
+    }
+
+    #[test]
+    fn test_missing_hooks_use_defaults() {
+        let script = policy("fn unrelated() { 1 }");
+        assert_eq!(script.modify_weight(&vote("alice"), 0.4), Ok(0.4));
+        assert_eq!(script.admit(&vote("alice"), Utc::now()), Ok(None));
+        assert_eq!(script.extension_secs(0, 1.0, 0.5), Ok(0));
+    }
+
+    #[test]
+    fn test_limits_and_sandbox() {
+        let runaway = policy("fn modify_weight(vote, weight) { loop { weight += 1.0; } }");
+        assert!(runaway.modify_weight(&vote("alice"), 1.0).is_err());
+
+        let negative = policy("fn modify_weight(vote, weight) { -1.0 }");
+        assert!(negative.modify_weight(&vote("alice"), 1.0).is_err());
+
+        assert!(ScriptPolicy::compile(r#"import "fs" as fs;"#, &ScriptLimits::default()).is_err());
+        assert!(ScriptPolicy::compile("fn admit(v, n) { eval(\"true\") }", &ScriptLimits::default()).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+// src/signer.rs
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use thiserror::Error;
+
+use crate::scheme::{DefaultScheme, SignatureScheme};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SignerError {
+    #[error("Signer is unavailable")]
+    Unavailable,
+    #[error("Signer refused to sign: {0}")]
+    Refused(String),
+}
+
+/// Holds a voter's key and signs vote messages with it, so the key itself need not be
+/// in this process. In-memory keys never fail; an HSM or hardware wallet may.
+pub trait VoteSigner {
+    type Error;
+
+    fn public_key(&self) -> VerifyingKey;
+    fn sign_message(&self, message: &[u8]) -> Result<Signature, Self::Error>;
+}
+
+/// A signer that cannot fail, such as a key held in memory
+pub trait InfallibleSigner: VoteSigner<Error = Infallible> {}
+
+impl<T: VoteSigner<Error = Infallible> + ?Sized> InfallibleSigner for T {}
+
+impl VoteSigner for SigningKey {
+    type Error = Infallible;
+
+    fn public_key(&self) -> VerifyingKey {
+        DefaultScheme::public_key(self)
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Result<Signature, Infallible> {
+        Ok(DefaultScheme::sign(self, message))
+    }
+}
+
+/// A signer that answers asynchronously, e.g. over the network. Every `Sync`
+/// `VoteSigner` is one, answering at once.
+pub trait AsyncVoteSigner {
+    type Error;
+
+    fn public_key(&self) -> VerifyingKey;
+    fn sign_message(&self, message: &[u8]) -> impl Future<Output = Result<Signature, Self::Error>> + Send;
+}
+
+impl<T: VoteSigner + Sync + ?Sized> AsyncVoteSigner for T
+where
+    T::Error: Send,
+{
+    type Error = T::Error;
+
+    fn public_key(&self) -> VerifyingKey {
+        VoteSigner::public_key(self)
+    }
+
+    fn sign_message(&self, message: &[u8]) -> impl Future<Output = Result<Signature, T::Error>> + Send {
+        std::future::ready(VoteSigner::sign_message(self, message))
+    }
+}
+
+/// Stand-in for a remote signer such as an HSM: the key stays inside it, it can be
+/// taken offline, and it counts the signatures it has made
+#[derive(Debug)]
+pub struct MockRemoteSigner {
+    key: SigningKey,
+    online: AtomicBool,
+    signed: AtomicUsize,
+}
+
+impl MockRemoteSigner {
+    pub fn new(key: SigningKey) -> Self {
+        Self { key, online: AtomicBool::new(true), signed: AtomicUsize::new(0) }
+    }
+
+    pub fn set_online(&self, online: bool) {
+        self.online.store(online, Ordering::SeqCst);
+    }
+
+    pub fn signed(&self) -> usize {
+        self.signed.load(Ordering::SeqCst)
+    }
+}
+
+impl VoteSigner for MockRemoteSigner {
+    type Error = SignerError;
+
+    fn public_key(&self) -> VerifyingKey {
+        DefaultScheme::public_key(&self.key)
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        if !self.online.load(Ordering::SeqCst) {
+            return Err(SignerError::Unavailable);
+        }
+        self.signed.fetch_add(1, Ordering::SeqCst);
+        Ok(DefaultScheme::sign(&self.key, message))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::{Context, Poll, Waker};
+
+    use chrono::Utc;
+
+    use crate::vote::{DecayType, SignedVote};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        match std::pin::pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("test signers answer at once"),
+        }
+    }
+
+    #[test]
+    fn test_remote_signer_signs_what_a_local_key_signs() {
+        let key = SignedVote::generate_keypair();
+        let remote = MockRemoteSigner::new(key.clone());
+        let now = Utc::now();
+        let vote = |signer: &MockRemoteSigner| {
+            SignedVote::try_new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, signer)
+        };
+
+        let local = SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key);
+        let signed = vote(&remote).unwrap();
+        assert_eq!(signed.signature, local.signature);
+        assert_eq!(signed.verify(10), Ok(()));
+        assert_eq!(remote.signed(), 1);
+
+        remote.set_online(false);
+        assert_eq!(vote(&remote).unwrap_err(), SignerError::Unavailable);
+        assert_eq!(remote.signed(), 1);
+    }
+
+    #[test]
+    fn test_async_signing() {
+        let remote = MockRemoteSigner::new(SignedVote::generate_keypair());
+        let now = Utc::now();
+        let vote = block_on(SignedVote::try_new_async(
+            "alice".parse().unwrap(),
+            "p1".parse().unwrap(),
+            1.0,
+            now,
+            DecayType::Exponential,
+            &remote,
+        ))
+        .unwrap();
+        assert_eq!(vote.verify(10), Ok(()));
+        assert_eq!(vote.public_key, VoteSigner::public_key(&remote));
+
+        let amended = block_on(SignedVote { nonce: 7, ..vote }.sign_with_async(&remote)).unwrap();
+        assert_eq!(amended.verify(10), Ok(()));
+        assert_eq!(remote.signed(), 2);
+    }
+}
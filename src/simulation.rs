@@ -1,67 +1,623 @@
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Duration, Utc};
 use crate::vote::{SignedVote, DecayType, ProposalType};
-use crate::verify::{VerificationError};
-use crate::threshold::{ThresholdEscalator, EscalationPattern, ProgressionProfile};
+use crate::threshold::ThresholdEscalator;
 use crate::trust::TrustEngine;
+use crate::units::Seconds;
 use crate::weight_engine::WeightEngine;
 use crate::history::{VoteRecord, HistoryAnalyzer};
-use ed25519_dalek::{Signer};
+use crate::hooks::ExtensionPolicy;
+use crate::messages::{self, MessageKey};
+use crate::progress::{CancellationToken, Progress};
+use crate::tally::Tally;
+use ed25519_dalek::SigningKey;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+pub const SIMULATION_PROPOSAL: &str = "proposal_sim";
+
+/// Inputs that fully determine a simulation run
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Seeds voter keys and timestamp jitter
+    pub seed: u64,
+    /// Clock the run is evaluated against
+    pub now: DateTime<Utc>,
+}
+
+/// One voter's outcome; `error` is set when the vote failed verification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedVote {
+    pub voter_id: String,
+    pub public_key: String,
+    pub decay_model: String,
+    pub timestamp: DateTime<Utc>,
+    pub weight: Option<f64>,
+    pub threshold: Option<f64>,
+    pub passed: Option<bool>,
+    pub error: Option<String>,
+}
+
+/// Structured result of a simulation run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub seed: u64,
+    pub now: DateTime<Utc>,
+    pub proposal_id: String,
+    pub proposal_type: String,
+    pub votes: Vec<SimulatedVote>,
+    pub cancelled: bool,
+}
+
+impl SimulationReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("simulation report serializes")
+    }
+
+    /// Accepted votes as history records
+    pub fn history(&self) -> HistoryAnalyzer {
+        let mut history = HistoryAnalyzer::default();
+        for v in &self.votes {
+            if let (Some(weight), Some(threshold), Some(passed)) = (v.weight, v.threshold, v.passed) {
+                history.record_vote(VoteRecord {
+                    vote_id: v.voter_id.clone(),
+                    weight,
+                    threshold,
+                    passed,
+                    timestamp: self.now,
+                });
+            }
+        }
+        history
+    }
+}
 
 pub fn run_simulation() {
-    let now = Utc::now();
+    run_simulation_with(&CancellationToken::new());
+}
+
+/// Run the simulation, stopping between voters if `cancel` fires.
+/// Votes processed before cancellation stay recorded in the printed history.
+pub fn run_simulation_with(cancel: &CancellationToken) {
+    let config = SimulationConfig {
+        seed: rand::random(),
+        now: Utc::now(),
+    };
+    let report = simulate(&config, cancel);
+
+    for v in &report.votes {
+        match (&v.error, v.weight, v.threshold, v.passed) {
+            (None, Some(weight), Some(threshold), Some(passed)) => println!(
+                "{}",
+                messages::text(
+                    MessageKey::SimulationVoteAccepted,
+                    &[
+                        ("voter", v.voter_id.clone()),
+                        ("weight", format!("{:.4}", weight)),
+                        ("threshold", format!("{:.2}", threshold * 100.0)),
+                        ("passed", passed.to_string()),
+                    ],
+                )
+            ),
+            (error, ..) => println!(
+                "{}",
+                messages::text(
+                    MessageKey::SimulationVoteFailed,
+                    &[("voter", v.voter_id.clone()), ("error", error.clone().unwrap_or_default())],
+                )
+            ),
+        }
+    }
+
+    println!("{}", messages::text(MessageKey::SimulationHeader, &[]));
+    report.history().print_history();
+}
+
+/// Run the simulation without printing. The same config always yields the same report.
+pub fn simulate(config: &SimulationConfig, cancel: &CancellationToken) -> SimulationReport {
+    let now = config.now;
+    let mut rng = StdRng::seed_from_u64(config.seed);
     let trust_engine = TrustEngine::new();
     let mut weight_engine = WeightEngine::new();
-    let mut history = HistoryAnalyzer::default();
 
-    let voters = vec!["alice", "bob", "carol", "dave", "eve"];
-    let decay_models = vec![DecayType::Linear, DecayType::Exponential, DecayType::Stepped];
+    let voters = ["alice", "bob", "carol", "dave", "eve"];
+    let decay_models = [DecayType::Linear, DecayType::Exponential, DecayType::Stepped];
     let proposal_type = ProposalType::Critical;
 
     // Initialize threshold engine
     let mut threshold_engine = ThresholdEscalator::for_proposal_type(proposal_type.clone());
     threshold_engine.total_votes = voters.len();
 
+    let mut report = SimulationReport {
+        seed: config.seed,
+        now,
+        proposal_id: SIMULATION_PROPOSAL.to_string(),
+        proposal_type: format!("{:?}", proposal_type),
+        votes: Vec::new(),
+        cancelled: false,
+    };
+
+    let progress = Progress::new(voters.len() as u64, "Simulating votes");
     for (i, voter) in voters.iter().enumerate() {
-        let keypair = SignedVote::generate_keypair();
-        let decay = &decay_models[i % decay_models.len()];
+        if cancel.is_cancelled() {
+            progress.abandon(format!("Simulation cancelled after {} voters", i));
+            report.cancelled = true;
+            break;
+        }
+        let keypair = SigningKey::generate(&mut rng);
+        let decay = decay_models[i % decay_models.len()];
 
-        // Stagger timestamps: simulate votes at different times
-        let timestamp = now - Duration::seconds((i * 30) as i64);
+        // Stagger timestamps: simulate votes at different times, with seeded jitter
+        let timestamp = now - Duration::seconds((i * 30) as i64 + rng.gen_range(0..10));
 
-        let vote = SignedVote {
-            voter_id: voter.to_string(),
-            proposal_id: "proposal_sim".to_string(),
+        let vote = SignedVote::new(
+            voter.parse().expect("simulation voter ids are valid"),
+            SIMULATION_PROPOSAL.parse().expect("valid id"),
+            1.0,
             timestamp,
-            original_weight: 1.0,
-            decay_model: decay.clone(),
-            signature: keypair.sign(format!("{}:{}:{}", voter, "proposal_sim", timestamp).as_bytes()),
-            public_key: keypair.verifying_key(),
-        };
+            decay,
+            &keypair,
+        );
 
-        match vote.verify(300) {
+        let mut outcome = SimulatedVote {
+            voter_id: vote.voter_id.to_string(),
+            public_key: hex::encode(vote.public_key.as_bytes()),
+            decay_model: format!("{:?}", decay),
+            timestamp,
+            weight: None,
+            threshold: None,
+            passed: None,
+            error: None,
+        };
+        match vote.verify_at(now, Seconds::new(300)) {
             Ok(_) => {
                 let weight = weight_engine.calculate_weight(&vote, now, Some(&trust_engine));
                 let current_threshold = threshold_engine.threshold_with_profile(now, vote.timestamp);
-                let passed = threshold_engine.is_threshold_met(weight, current_threshold);
+                outcome.weight = Some(weight);
+                outcome.threshold = Some(current_threshold);
+                outcome.passed = Some(threshold_engine.is_threshold_met(weight, current_threshold));
+            }
+            Err(e) => outcome.error = Some(e.to_string()),
+        }
+        report.votes.push(outcome);
+        progress.inc(1);
+    }
 
-                let record = VoteRecord {
-                    vote_id: vote.voter_id.clone(),
-                    weight,
-                    threshold: current_threshold,
-                    passed,
-                    timestamp: now,
-                };
-                history.record_vote(record);
-
-                println!(
-                    "✅ {}: weight={:.4}, threshold={:.2}, passed={}",
-                    vote.voter_id, weight, current_threshold * 100.0, passed
-                );
+    progress.finish();
+    report
+}
+
+/// When votes arrive relative to a window of `duration_secs`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrivalProcess {
+    /// Spread evenly over the window
+    Uniform,
+    /// `share` of voters arrive within `within_secs` either side of the deadline,
+    /// the rest evenly over the window
+    DeadlineRush { share: f64, within_secs: i64 },
+}
+
+/// Inputs for comparing extension policies on the same seeded trials
+#[derive(Debug, Clone)]
+pub struct ExtensionSimConfig {
+    pub seed: u64,
+    pub trials: usize,
+    pub voters: usize,
+    pub duration_secs: i64,
+    /// Expected total weight of all voters, on the same scale as thresholds
+    pub mean_total_weight: f64,
+    pub arrival: ArrivalProcess,
+    pub proposal_type: ProposalType,
+    /// Stop asking a policy after this many extensions in one trial
+    pub max_extensions: usize,
+}
+
+impl Default for ExtensionSimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            trials: 1000,
+            voters: 8,
+            duration_secs: 300,
+            mean_total_weight: 0.9,
+            arrival: ArrivalProcess::DeadlineRush { share: 0.5, within_secs: 30 },
+            proposal_type: ProposalType::Normal,
+            max_extensions: 5,
+        }
+    }
+}
+
+/// How one policy fared across every trial
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtensionPolicyStats {
+    pub policy: String,
+    pub trials: usize,
+    /// Trials in which the policy extended at least once
+    pub extended_trials: usize,
+    pub extensions: usize,
+    /// Trials whose pass/fail outcome differs from the no-extension baseline
+    pub outcome_changes: usize,
+    pub pass_rate: f64,
+    pub baseline_pass_rate: f64,
+    /// Mean seconds the close moved past the original deadline, over all trials
+    pub mean_added_latency_secs: f64,
+    /// The same, over only the trials that were extended
+    pub mean_added_latency_when_extended_secs: f64,
+}
+
+impl ExtensionPolicyStats {
+    pub fn trigger_rate(&self) -> f64 {
+        if self.trials == 0 { 0.0 } else { self.extended_trials as f64 / self.trials as f64 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtensionSimReport {
+    pub seed: u64,
+    pub arrival: String,
+    pub policies: Vec<ExtensionPolicyStats>,
+}
+
+struct TrialOutcome {
+    passed: bool,
+    extensions: usize,
+    added_secs: i64,
+}
+
+/// Replay one trial's arrivals (sorted by offset) against `policy`; `None` never extends
+fn run_trial(
+    arrivals: &[(i64, f64)],
+    config: &ExtensionSimConfig,
+    escalator: &mut ThresholdEscalator,
+    policy: Option<&dyn ExtensionPolicy>,
+) -> TrialOutcome {
+    let mut deadline = config.duration_secs;
+    let mut weight = 0.0;
+    let mut counted = 0;
+    let mut extensions = 0;
+    for &(offset, vote_weight) in arrivals {
+        if offset > deadline {
+            break;
+        }
+        weight += vote_weight;
+        counted += 1;
+
+        let Some(policy) = policy else { continue };
+        if extensions >= config.max_extensions {
+            continue;
+        }
+        let threshold = escalator.threshold_at(Seconds::new(offset));
+        // A failing policy is treated as declining to extend
+        let extra = policy.extension_secs(deadline - offset, weight, threshold).unwrap_or(0);
+        if extra > 0 {
+            deadline = deadline.saturating_add(i64::try_from(extra).unwrap_or(i64::MAX));
+            extensions += 1;
+        }
+    }
+
+    escalator.total_votes = counted;
+    let threshold = escalator.threshold_at(Seconds::new(deadline));
+    TrialOutcome {
+        passed: escalator.is_threshold_met(weight, threshold),
+        extensions,
+        added_secs: deadline - config.duration_secs,
+    }
+}
+
+/// Run the same seeded arrival streams through each policy and against a baseline
+/// that never extends
+pub fn simulate_extensions(
+    config: &ExtensionSimConfig,
+    policies: &[(&str, &dyn ExtensionPolicy)],
+) -> ExtensionSimReport {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let duration = config.duration_secs.max(1);
+    let max_vote = 2.0 * config.mean_total_weight.max(0.0) / config.voters.max(1) as f64;
+
+    let trials: Vec<Vec<(i64, f64)>> = (0..config.trials)
+        .map(|_| {
+            let mut arrivals: Vec<(i64, f64)> = (0..config.voters)
+                .map(|_| {
+                    let offset = match config.arrival {
+                        ArrivalProcess::DeadlineRush { share, within_secs } if rng.gen_bool(share.clamp(0.0, 1.0)) => {
+                            let within = within_secs.max(0);
+                            (duration + rng.gen_range(-within..=within)).max(0)
+                        }
+                        _ => rng.gen_range(0..duration),
+                    };
+                    (offset, rng.gen_range(0.0..=max_vote))
+                })
+                .collect();
+            arrivals.sort_by_key(|a| a.0);
+            arrivals
+        })
+        .collect();
+
+    let mut escalator = ThresholdEscalator::for_proposal_type(config.proposal_type.clone());
+    let baseline: Vec<bool> = trials
+        .iter()
+        .map(|arrivals| run_trial(arrivals, config, &mut escalator, None).passed)
+        .collect();
+    let baseline_passes = baseline.iter().filter(|p| **p).count();
+    let rate = |n: usize| if trials.is_empty() { 0.0 } else { n as f64 / trials.len() as f64 };
+
+    let stats = policies
+        .iter()
+        .map(|(name, policy)| {
+            let outcomes: Vec<TrialOutcome> = trials
+                .iter()
+                .map(|arrivals| run_trial(arrivals, config, &mut escalator, Some(*policy)))
+                .collect();
+            let extended: Vec<&TrialOutcome> = outcomes.iter().filter(|o| o.extensions > 0).collect();
+            let added: i64 = outcomes.iter().map(|o| o.added_secs).sum();
+            ExtensionPolicyStats {
+                policy: name.to_string(),
+                trials: outcomes.len(),
+                extended_trials: extended.len(),
+                extensions: outcomes.iter().map(|o| o.extensions).sum(),
+                outcome_changes: outcomes.iter().zip(&baseline).filter(|(o, b)| o.passed != **b).count(),
+                pass_rate: rate(outcomes.iter().filter(|o| o.passed).count()),
+                baseline_pass_rate: rate(baseline_passes),
+                mean_added_latency_secs: if outcomes.is_empty() { 0.0 } else { added as f64 / outcomes.len() as f64 },
+                mean_added_latency_when_extended_secs: if extended.is_empty() {
+                    0.0
+                } else {
+                    added as f64 / extended.len() as f64
+                },
+            }
+        })
+        .collect();
+
+    ExtensionSimReport {
+        seed: config.seed,
+        arrival: format!("{:?}", config.arrival),
+        policies: stats,
+    }
+}
+
+/// How validators earn trust from voting: each vote adds `gain_per_vote` to a bonus
+/// above 1.0 that halves every `half_life_days` and never passes `cap`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrustAccrual {
+    pub name: String,
+    pub gain_per_vote: f64,
+    /// Scale each vote's gain by its original weight, so cheap votes earn little
+    pub weight_scaled: bool,
+    pub half_life_days: Option<f64>,
+    pub cap: f64,
+}
+
+impl TrustAccrual {
+    pub fn new(name: &str, gain_per_vote: f64, cap: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            gain_per_vote,
+            weight_scaled: false,
+            half_life_days: None,
+            cap,
+        }
+    }
+
+    /// Configurations worth comparing: none, uncapped, capped, decaying, weight-scaled
+    pub fn presets() -> Vec<Self> {
+        vec![
+            Self::new("no_trust", 0.0, 1.0),
+            Self::new("uncapped", 0.01, f64::INFINITY),
+            Self::new("capped_1.3", 0.01, 1.3),
+            Self { half_life_days: Some(3.0), ..Self::new("decay_3d", 0.01, 2.0) },
+            Self { weight_scaled: true, ..Self::new("weight_scaled", 0.01, 2.0) },
+        ]
+    }
+
+    /// Bonus after a day that started at `bonus` and saw `votes` votes of `vote_weight`
+    fn step_day(&self, bonus: f64, votes: usize, vote_weight: f64) -> f64 {
+        let retained = self.half_life_days.map_or(1.0, |h| 0.5f64.powf(1.0 / h.max(f64::MIN_POSITIVE)));
+        let gain = self.gain_per_vote * votes as f64 * if self.weight_scaled { vote_weight } else { 1.0 };
+        (1.0 + (bonus - 1.0) * retained + gain).min(self.cap.max(1.0))
+    }
+}
+
+/// Attackers farm trust with many cheap votes, then all reject a critical proposal the
+/// honest validators support
+#[derive(Debug, Clone)]
+pub struct ChaosSimConfig {
+    pub seed: u64,
+    pub trials: usize,
+    pub honest: usize,
+    pub attackers: usize,
+    pub farming_days: usize,
+    /// Chance an honest validator votes on a given day, with full weight
+    pub honest_participation: f64,
+    pub farm_votes_per_day: usize,
+    pub farm_vote_weight: f64,
+    /// Chance an honest validator turns out for the critical proposal
+    pub critical_turnout: f64,
+}
+
+impl Default for ChaosSimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            trials: 200,
+            honest: 30,
+            attackers: 5,
+            farming_days: 30,
+            honest_participation: 0.8,
+            farm_votes_per_day: 20,
+            farm_vote_weight: 0.01,
+            critical_turnout: 0.8,
+        }
+    }
+}
+
+/// Damage one trust configuration allowed, averaged over every trial
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaosOutcome {
+    pub accrual: String,
+    pub attacker_mean_bonus: f64,
+    pub honest_mean_bonus: f64,
+    /// Attackers' share of the weight cast on the critical proposal
+    pub attacker_weight_share: f64,
+    /// Attackers' share of the votes cast, for comparison
+    pub attacker_vote_share: f64,
+    /// Trials in which the attackers blocked the critical proposal
+    pub block_rate: f64,
+}
+
+impl ChaosOutcome {
+    /// How much farmed trust multiplied the attackers' voice
+    pub fn amplification(&self) -> f64 {
+        if self.attacker_vote_share > 0.0 { self.attacker_weight_share / self.attacker_vote_share } else { 0.0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaosSimReport {
+    pub seed: u64,
+    pub outcomes: Vec<ChaosOutcome>,
+}
+
+/// Run the same seeded participation through each trust configuration. The critical
+/// proposal passes when its approval share of cast weight meets the critical base
+/// threshold.
+pub fn simulate_trust_attack(config: &ChaosSimConfig, accruals: &[TrustAccrual]) -> ChaosSimReport {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    // Per trial: honest votes per validator per day, then who turns out for the critical vote
+    let trials: Vec<(Vec<Vec<usize>>, Vec<bool>)> = (0..config.trials)
+        .map(|_| {
+            let participation = config.honest_participation.clamp(0.0, 1.0);
+            let days = (0..config.honest)
+                .map(|_| (0..config.farming_days).map(|_| usize::from(rng.gen_bool(participation))).collect())
+                .collect();
+            let turnout = (0..config.honest).map(|_| rng.gen_bool(config.critical_turnout.clamp(0.0, 1.0))).collect();
+            (days, turnout)
+        })
+        .collect();
+
+    let mut escalator = ThresholdEscalator::for_proposal_type(ProposalType::Critical);
+    let threshold = escalator.threshold_at(Seconds::ZERO);
+    let mean = |values: &[f64]| if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 };
+
+    let outcomes = accruals
+        .iter()
+        .map(|accrual| {
+            let attacker_bonus = (0..config.farming_days)
+                .fold(1.0, |bonus, _| accrual.step_day(bonus, config.farm_votes_per_day, config.farm_vote_weight));
+            let (mut honest_bonuses, mut weight_shares, mut vote_shares) = (Vec::new(), Vec::new(), Vec::new());
+            let mut blocked = 0;
+            for (days, turnout) in &trials {
+                let mut tally = Tally::default();
+                for (votes, turned_out) in days.iter().zip(turnout) {
+                    let bonus = votes.iter().fold(1.0, |bonus, n| accrual.step_day(bonus, *n, 1.0));
+                    honest_bonuses.push(bonus);
+                    if *turned_out {
+                        tally.approve(bonus);
+                    }
+                }
+                for _ in 0..config.attackers {
+                    tally.reject(attacker_bonus);
+                }
+                escalator.total_votes = tally.votes;
+                if !escalator.is_threshold_met(tally.approval_fraction(), threshold) {
+                    blocked += 1;
+                }
+                weight_shares.push(tally.rejection_fraction());
+                vote_shares.push(if tally.votes > 0 { config.attackers as f64 / tally.votes as f64 } else { 0.0 });
             }
-            Err(e) => println!("❌ {}: verification failed ({})", voter, e),
+            ChaosOutcome {
+                accrual: accrual.name.clone(),
+                attacker_mean_bonus: attacker_bonus,
+                honest_mean_bonus: mean(&honest_bonuses),
+                attacker_weight_share: mean(&weight_shares),
+                attacker_vote_share: mean(&vote_shares),
+                block_rate: if trials.is_empty() { 0.0 } else { blocked as f64 / trials.len() as f64 },
+            }
+        })
+        .collect();
+
+    ChaosSimReport { seed: config.seed, outcomes }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(seed: u64) -> SimulationConfig {
+        SimulationConfig {
+            seed,
+            now: crate::vectors::vector_epoch(),
         }
     }
 
-    println!("\n📊 Simulation Results (History Log):");
-    history.print_history();
+    #[test]
+    fn test_same_seed_same_report() {
+        let cancel = CancellationToken::new();
+        let a = simulate(&config(7), &cancel);
+        assert_eq!(a, simulate(&config(7), &cancel));
+        assert_ne!(a.votes[0].public_key, simulate(&config(8), &cancel).votes[0].public_key);
+        assert_eq!(a.history().records.len(), 5);
+    }
+
+    #[test]
+    fn test_cancelled_run_is_marked() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let report = simulate(&config(7), &cancel);
+        assert!(report.cancelled);
+        assert!(report.votes.is_empty());
+    }
+
+    #[test]
+    fn test_extension_policies_compared_on_same_trials() {
+        use crate::hooks::CloseCallExtension;
+
+        let close_call = CloseCallExtension { extra_secs: 60 };
+        let generous = CloseCallExtension { extra_secs: 600 };
+        let config = ExtensionSimConfig { trials: 200, ..ExtensionSimConfig::default() };
+        let report = simulate_extensions(&config, &[("close_call_60", &close_call), ("close_call_600", &generous)]);
+
+        assert_eq!(report.policies.len(), 2);
+        let short = &report.policies[0];
+        assert_eq!(short.trials, 200);
+        assert!(short.extended_trials > 0);
+        assert!(short.trigger_rate() <= 1.0);
+        assert!(short.mean_added_latency_when_extended_secs >= 60.0);
+        assert_eq!(short.baseline_pass_rate, report.policies[1].baseline_pass_rate);
+        assert!(report.policies[1].mean_added_latency_secs >= short.mean_added_latency_secs);
+
+        let again = simulate_extensions(&config, &[("close_call_60", &close_call), ("close_call_600", &generous)]);
+        assert_eq!(report, again);
+
+        let uniform = ExtensionSimConfig { arrival: ArrivalProcess::Uniform, ..config };
+        let report = simulate_extensions(&uniform, &[("close_call_60", &close_call)]);
+        // Extensions only change outcomes when votes keep arriving past the deadline
+        assert!(short.outcome_changes > 0);
+        assert_eq!(report.policies[0].outcome_changes, 0);
+    }
+
+    #[test]
+    fn test_trust_farming_bounded_by_caps_and_decay() {
+        let config = ChaosSimConfig { trials: 50, ..ChaosSimConfig::default() };
+        let report = simulate_trust_attack(&config, &TrustAccrual::presets());
+        assert_eq!(report, simulate_trust_attack(&config, &TrustAccrual::presets()));
+        let outcome = |name: &str| report.outcomes.iter().find(|o| o.accrual == name).unwrap();
+
+        let baseline = outcome("no_trust");
+        assert_eq!(baseline.attacker_mean_bonus, 1.0);
+        assert!((baseline.amplification() - 1.0).abs() < 1e-9);
+
+        let uncapped = outcome("uncapped");
+        assert!((uncapped.attacker_mean_bonus - 7.0).abs() < 1e-9);
+        assert!(uncapped.amplification() > 3.0);
+        assert!(uncapped.block_rate > baseline.block_rate);
+        assert_eq!(uncapped.block_rate, 1.0);
+
+        for bounded in ["capped_1.3", "weight_scaled"] {
+            assert!(outcome(bounded).block_rate < uncapped.block_rate, "{}", bounded);
+        }
+        // Decay alone only bounds the bonus; sustained farming keeps it near the cap
+        assert!(outcome("decay_3d").attacker_mean_bonus < 2.0);
+        assert!(outcome("weight_scaled").attacker_mean_bonus < outcome("weight_scaled").honest_mean_bonus);
+    }
 }
@@ -0,0 +1,511 @@
+// src/snapshot.rs
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ids::{ProposalId, VoterId};
+use crate::tally::Tally;
+use crate::threshold::ThresholdEscalator;
+use crate::trust::TrustSource;
+use crate::units::Seconds;
+use crate::vote::{SignedVote, VoteChoice};
+use crate::weight_engine::WeightEngine;
+
+/// Spacing of persisted tally snapshots when none is configured
+pub const DEFAULT_SNAPSHOT_CADENCE: Seconds = Seconds::new(30);
+
+/// One voter's counted vote as of a snapshot
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VoterState {
+    pub voter_id: VoterId,
+    pub choice: VoteChoice,
+    pub version: u64,
+    pub cast_at: DateTime<Utc>,
+    pub original_weight: f64,
+    pub age_secs: u64,
+    pub trust_bonus: f64,
+    pub final_weight: f64,
+}
+
+/// A proposal's tally and threshold frozen at `at`, for comparing against another
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TallySnapshot {
+    pub proposal_id: ProposalId,
+    pub at: DateTime<Utc>,
+    pub voters: BTreeMap<VoterId, VoterState>,
+    pub tally: Tally,
+    /// The weight compared against `threshold`: net of opposition when anyone opposed
+    pub decided_weight: f64,
+    pub threshold: f64,
+    pub passing: bool,
+}
+
+impl TallySnapshot {
+    /// Tally the admitted `votes` on `proposal_id` cast by `at`, keeping each voter's
+    /// latest version as of then
+    pub fn capture(
+        proposal_id: &ProposalId,
+        votes: &[SignedVote],
+        escalator: &ThresholdEscalator,
+        opened_at: DateTime<Utc>,
+        at: DateTime<Utc>,
+        trust: &dyn TrustSource,
+    ) -> Self {
+        let mut latest: BTreeMap<&VoterId, &SignedVote> = BTreeMap::new();
+        for vote in votes.iter().filter(|v| v.proposal_id == *proposal_id && v.timestamp <= at) {
+            let current = latest.entry(&vote.voter_id).or_insert(vote);
+            if vote.version > current.version {
+                *current = vote;
+            }
+        }
+
+        let engine = WeightEngine::new();
+        let mut tally = Tally::new(0.0);
+        let mut voters = BTreeMap::new();
+        for (voter_id, vote) in latest {
+            let explanation = engine.explain(vote, at, Some(trust), None);
+            let state = VoterState {
+                voter_id: voter_id.clone(),
+                choice: vote.choice,
+                version: vote.version,
+                cast_at: vote.timestamp,
+                original_weight: vote.original_weight,
+                age_secs: explanation.age.as_u64_saturating(),
+                trust_bonus: explanation.trust_bonus,
+                final_weight: explanation.final_weight.get(),
+            };
+            if escalator.decision.is_dust(state.final_weight) {
+                continue;
+            }
+            tally.record(state.choice, state.final_weight);
+            voters.insert(voter_id.clone(), state);
+        }
+
+        let decided_weight = if voters.values().any(|v| !v.choice.is_approve()) {
+            tally.net_weight()
+        } else {
+            tally.approve_weight
+        };
+        let threshold = escalator.threshold_with_profile(at, opened_at);
+        let passing = escalator.decision.decide(decided_weight, threshold, "", proposal_id).passed
+            && voters.len() >= escalator.min_vote_count;
+        Self {
+            proposal_id: proposal_id.clone(),
+            at,
+            voters,
+            tally,
+            decided_weight,
+            threshold,
+            passing,
+        }
+    }
+}
+
+/// A snapshot reduced to what a chart needs, one per cadence slot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesPoint {
+    pub proposal_id: ProposalId,
+    pub at: DateTime<Utc>,
+    /// Seconds since the proposal opened
+    pub elapsed_secs: i64,
+    pub tally: Tally,
+    pub decided_weight: f64,
+    pub threshold: f64,
+    pub passing: bool,
+}
+
+impl SeriesPoint {
+    pub fn from_snapshot(snapshot: &TallySnapshot, opened_at: DateTime<Utc>) -> Self {
+        Self {
+            proposal_id: snapshot.proposal_id.clone(),
+            at: snapshot.at,
+            elapsed_secs: Seconds::between(opened_at, snapshot.at).as_i64(),
+            tally: snapshot.tally,
+            decided_weight: snapshot.decided_weight,
+            threshold: snapshot.threshold,
+            passing: snapshot.passing,
+        }
+    }
+}
+
+/// Snapshot slots every `cadence` from a proposal's opening, so series from different
+/// proposals and runs line up
+#[derive(Debug, Clone)]
+pub struct SnapshotSchedule {
+    pub proposal_id: ProposalId,
+    pub opened_at: DateTime<Utc>,
+    pub cadence: Seconds,
+    next_slot: i64,
+}
+
+impl SnapshotSchedule {
+    /// A cadence below one second is raised to one
+    pub fn new(proposal_id: ProposalId, opened_at: DateTime<Utc>, cadence: Seconds) -> Self {
+        Self { proposal_id, opened_at, cadence: cadence.max(Seconds::new(1)), next_slot: 0 }
+    }
+
+    /// The next slot not yet captured
+    pub fn next_at(&self) -> DateTime<Utc> {
+        self.opened_at + self.cadence.saturating_mul(self.next_slot).to_duration()
+    }
+
+    /// Capture every slot due by `now`, each as of its own slot time, so slots missed
+    /// between calls are filled in rather than skipped
+    pub fn capture_due(
+        &mut self,
+        now: DateTime<Utc>,
+        votes: &[SignedVote],
+        escalator: &ThresholdEscalator,
+        trust: &dyn TrustSource,
+    ) -> Vec<SeriesPoint> {
+        let mut points = Vec::new();
+        while self.next_at() <= now {
+            let snapshot = TallySnapshot::capture(&self.proposal_id, votes, escalator, self.opened_at, self.next_at(), trust);
+            points.push(SeriesPoint::from_snapshot(&snapshot, self.opened_at));
+            self.next_slot += 1;
+        }
+        points
+    }
+}
+
+/// Rebuild the series of `proposal_id` from its raw votes, one point per slot up to `until`
+pub fn sample_series(
+    proposal_id: &ProposalId,
+    votes: &[SignedVote],
+    escalator: &ThresholdEscalator,
+    opened_at: DateTime<Utc>,
+    until: DateTime<Utc>,
+    cadence: Seconds,
+    trust: &dyn TrustSource,
+) -> Vec<SeriesPoint> {
+    SnapshotSchedule::new(proposal_id.clone(), opened_at, cadence).capture_due(until, votes, escalator, trust)
+}
+
+#[derive(Error, Debug)]
+pub enum SeriesError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Malformed series point on line {line}: {message}")]
+    Malformed { line: usize, message: String },
+}
+
+/// Series points appended one JSON object per line, at most one per proposal and slot
+#[derive(Debug, Clone)]
+pub struct SeriesStore {
+    pub path: PathBuf,
+}
+
+impl SeriesStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Append the points not already stored for their proposal and time, returning how
+    /// many were written; appending the same slots again is a no-op
+    pub fn append(&self, points: &[SeriesPoint]) -> Result<usize, SeriesError> {
+        let stored: HashSet<(ProposalId, DateTime<Utc>)> = match self.load() {
+            Ok(stored) => stored.into_iter().map(|p| (p.proposal_id, p.at)).collect(),
+            Err(SeriesError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(e),
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let mut written = 0;
+        for point in points.iter().filter(|p| !stored.contains(&(p.proposal_id.clone(), p.at))) {
+            writeln!(file, "{}", serde_json::to_string(point).expect("series points serialize"))?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Every stored point, oldest first; the first unreadable line is reported
+    pub fn load(&self) -> Result<Vec<SeriesPoint>, SeriesError> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut points = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let point = serde_json::from_str(&line)
+                .map_err(|e| SeriesError::Malformed { line: i + 1, message: e.to_string() })?;
+            points.push(point);
+        }
+        Ok(points)
+    }
+
+    /// The stored series of one proposal
+    pub fn load_proposal(&self, proposal_id: &ProposalId) -> Result<Vec<SeriesPoint>, SeriesError> {
+        Ok(self.load()?.into_iter().filter(|p| p.proposal_id == *proposal_id).collect())
+    }
+}
+
+/// Persists every observed proposal's series to a `SeriesStore` as slots come due,
+/// under `escalator`'s thresholds and `trust`. `VoteIntake::with_snapshots` drives it
+/// from admissions, so charts read the store instead of replaying raw votes.
+pub struct SnapshotRecorder {
+    pub cadence: Seconds,
+    pub store: SeriesStore,
+    pub escalator: ThresholdEscalator,
+    trust: Box<dyn TrustSource>,
+    schedules: BTreeMap<ProposalId, SnapshotSchedule>,
+}
+
+impl SnapshotRecorder {
+    pub fn new(store: SeriesStore, cadence: Seconds, escalator: ThresholdEscalator, trust: Box<dyn TrustSource>) -> Self {
+        Self { cadence, store, escalator, trust, schedules: BTreeMap::new() }
+    }
+
+    /// Start `proposal_id`'s series at `opened_at`; a proposal already open keeps its schedule
+    pub fn open(&mut self, proposal_id: &ProposalId, opened_at: DateTime<Utc>) {
+        if !self.schedules.contains_key(proposal_id) {
+            let schedule = SnapshotSchedule::new(proposal_id.clone(), opened_at, self.cadence);
+            self.schedules.insert(proposal_id.clone(), schedule);
+        }
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.schedules.values().any(|s| s.next_at() <= now)
+    }
+
+    /// Capture and append every slot due by `now`, returning how many points were
+    /// written. A schedule only advances once its points are stored, so slots that
+    /// failed to persist are retried on the next call.
+    pub fn record(&mut self, now: DateTime<Utc>, votes: &[SignedVote]) -> Result<usize, SeriesError> {
+        let mut written = 0;
+        for schedule in self.schedules.values_mut() {
+            let mut advanced = schedule.clone();
+            let points = advanced.capture_due(now, votes, &self.escalator, self.trust.as_ref());
+            written += self.store.append(&points)?;
+            *schedule = advanced;
+        }
+        Ok(written)
+    }
+}
+
+/// Why a voter's weight differs between two snapshots
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChangeReason {
+    Decayed { from_age_secs: u64, to_age_secs: u64 },
+    TrustChanged { from: f64, to: f64 },
+    Amended { from_version: u64, to_version: u64 },
+    ChoiceChanged { from: VoteChoice, to: VoteChoice },
+    OriginalWeightChanged { from: f64, to: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeightChange {
+    pub voter_id: VoterId,
+    pub from: f64,
+    pub to: f64,
+    pub reasons: Vec<ChangeReason>,
+}
+
+/// What changed between two snapshots of the same proposal
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnapshotDiff {
+    pub proposal_id: ProposalId,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub added: Vec<VoterState>,
+    pub removed: Vec<VoterState>,
+    pub changed: Vec<WeightChange>,
+    pub decided_weight: (f64, f64),
+    pub threshold: (f64, f64),
+    pub passing: (bool, bool),
+}
+
+impl SnapshotDiff {
+    pub fn between(before: &TallySnapshot, after: &TallySnapshot) -> Self {
+        let added = after
+            .voters
+            .values()
+            .filter(|v| !before.voters.contains_key(&v.voter_id))
+            .cloned()
+            .collect();
+        let removed = before
+            .voters
+            .values()
+            .filter(|v| !after.voters.contains_key(&v.voter_id))
+            .cloned()
+            .collect();
+        let changed = before
+            .voters
+            .values()
+            .filter_map(|old| {
+                let new = after.voters.get(&old.voter_id)?;
+                let mut reasons = Vec::new();
+                if new.version != old.version {
+                    reasons.push(ChangeReason::Amended { from_version: old.version, to_version: new.version });
+                }
+                if new.choice != old.choice {
+                    reasons.push(ChangeReason::ChoiceChanged { from: old.choice, to: new.choice });
+                }
+                if new.original_weight != old.original_weight {
+                    reasons.push(ChangeReason::OriginalWeightChanged { from: old.original_weight, to: new.original_weight });
+                }
+                if new.trust_bonus != old.trust_bonus {
+                    reasons.push(ChangeReason::TrustChanged { from: old.trust_bonus, to: new.trust_bonus });
+                }
+                if new.final_weight != old.final_weight && new.age_secs != old.age_secs {
+                    reasons.push(ChangeReason::Decayed { from_age_secs: old.age_secs, to_age_secs: new.age_secs });
+                }
+                (new.final_weight != old.final_weight || !reasons.is_empty()).then(|| WeightChange {
+                    voter_id: old.voter_id.clone(),
+                    from: old.final_weight,
+                    to: new.final_weight,
+                    reasons,
+                })
+            })
+            .collect();
+        Self {
+            proposal_id: after.proposal_id.clone(),
+            from: before.at,
+            to: after.at,
+            added,
+            removed,
+            changed,
+            decided_weight: (before.decided_weight, after.decided_weight),
+            threshold: (before.threshold, after.threshold),
+            passing: (before.passing, after.passing),
+        }
+    }
+
+    pub fn outcome_changed(&self) -> bool {
+        self.passing.0 != self.passing.1
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("snapshot diffs serialize")
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "🔬 {} from {} to {}", self.proposal_id, self.from.to_rfc3339(), self.to.to_rfc3339());
+        let verdict = |passing: bool| if passing { "passing" } else { "failing" };
+        let _ = writeln!(
+            out,
+            "  outcome:   {} -> {}{}",
+            verdict(self.passing.0),
+            verdict(self.passing.1),
+            if self.outcome_changed() { " (changed)" } else { "" }
+        );
+        let _ = writeln!(out, "  weight:    {:.4} -> {:.4}", self.decided_weight.0, self.decided_weight.1);
+        let _ = writeln!(out, "  threshold: {:.4} -> {:.4}", self.threshold.0, self.threshold.1);
+        for vote in &self.added {
+            let _ = writeln!(out, "  + {} {:?} {:.4} (cast {})", vote.voter_id, vote.choice, vote.final_weight, vote.cast_at.to_rfc3339());
+        }
+        for vote in &self.removed {
+            let _ = writeln!(out, "  - {} {:?} {:.4}", vote.voter_id, vote.choice, vote.final_weight);
+        }
+        for change in &self.changed {
+            let reasons: Vec<String> = change.reasons.iter().map(describe).collect();
+            let _ = writeln!(out, "  ~ {} {:.4} -> {:.4}: {}", change.voter_id, change.from, change.to, reasons.join(", "));
+        }
+        out
+    }
+}
+
+fn describe(reason: &ChangeReason) -> String {
+    match reason {
+        ChangeReason::Decayed { from_age_secs, to_age_secs } => format!("decayed from age {}s to {}s", from_age_secs, to_age_secs),
+        ChangeReason::TrustChanged { from, to } => format!("trust x{:.2} -> x{:.2}", from, to),
+        ChangeReason::Amended { from_version, to_version } => format!("amended v{} -> v{}", from_version, to_version),
+        ChangeReason::ChoiceChanged { from, to } => format!("choice {:?} -> {:?}", from, to),
+        ChangeReason::OriginalWeightChanged { from, to } => format!("original weight {} -> {}", from, to),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::trust::TrustEngine;
+    use crate::vote::{DecayType, ProposalType};
+
+    #[test]
+    fn test_diff_explains_weight_changes() {
+        let opened = Utc::now() - Duration::seconds(600);
+        let p1: ProposalId = "p1".parse().unwrap();
+        let key = SignedVote::generate_keypair();
+        let cast = |voter: &str, secs: i64| {
+            SignedVote::new(voter.parse().unwrap(), p1.clone(), 1.0, opened + Duration::seconds(secs), DecayType::Linear, &key)
+        };
+        let alice = cast("alice", 10);
+        let amended = alice.amend(1.0, opened + Duration::seconds(200), VoteChoice::Reject, &key);
+        let votes = vec![alice, cast("bob", 20), cast("carol", 30), amended, cast("dave", 250)];
+
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+        let trust = TrustEngine::new();
+        let at = |secs: i64| TallySnapshot::capture(&p1, &votes, &escalator, opened, opened + Duration::seconds(secs), &trust);
+        let (before, after) = (at(100), at(300));
+        assert!(before.passing);
+
+        let diff = SnapshotDiff::between(&before, &after);
+        assert_eq!(diff.added.iter().map(|v| v.voter_id.as_str()).collect::<Vec<_>>(), vec!["dave"]);
+        assert!(diff.removed.is_empty());
+        let alice = diff.changed.iter().find(|c| c.voter_id == "alice").unwrap();
+        assert_eq!(
+            alice.reasons[..2],
+            [
+                ChangeReason::Amended { from_version: 0, to_version: 1 },
+                ChangeReason::ChoiceChanged { from: VoteChoice::Approve, to: VoteChoice::Reject },
+            ]
+        );
+        let bob = diff.changed.iter().find(|c| c.voter_id == "bob").unwrap();
+        assert_eq!(bob.reasons, vec![ChangeReason::Decayed { from_age_secs: 80, to_age_secs: 280 }]);
+        assert!(diff.threshold.1 >= diff.threshold.0);
+        assert!(diff.decided_weight.1 < diff.decided_weight.0);
+
+        let text = diff.render_text();
+        assert!(text.contains("+ dave"));
+        assert!(text.contains("~ alice"));
+        assert!(diff.to_json().contains("\"kind\": \"choice_changed\""));
+        assert!(SnapshotDiff::between(&after, &after).changed.is_empty());
+    }
+
+    #[test]
+    fn test_series_is_uniform_and_persisted() {
+        let opened = Utc::now() - Duration::seconds(600);
+        let p1: ProposalId = "p1".parse().unwrap();
+        let key = SignedVote::generate_keypair();
+        let cast = |voter: &str, secs: i64| {
+            SignedVote::new(voter.parse().unwrap(), p1.clone(), 1.0, opened + Duration::seconds(secs), DecayType::Linear, &key)
+        };
+        let votes = vec![cast("alice", 10), cast("bob", 70)];
+        let escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+
+        let path = std::env::temp_dir().join(format!("tdc-series-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = SeriesStore::new(&path);
+        let mut recorder =
+            SnapshotRecorder::new(store.clone(), DEFAULT_SNAPSHOT_CADENCE, escalator, Box::new(TrustEngine::new()));
+        recorder.open(&p1, opened);
+        assert_eq!(recorder.record(opened + Duration::seconds(45), &votes).unwrap(), 2);
+        assert!(!recorder.is_due(opened + Duration::seconds(50)));
+        // A late call fills in the slots it missed, each at its own time
+        assert_eq!(recorder.record(opened + Duration::seconds(125), &votes).unwrap(), 3);
+
+        let stored = store.load_proposal(&p1).unwrap();
+        assert_eq!(stored.iter().map(|p| p.elapsed_secs).collect::<Vec<_>>(), vec![0, 30, 60, 90, 120]);
+        assert_eq!(stored.iter().map(|p| p.tally.votes).collect::<Vec<_>>(), vec![0, 1, 1, 2, 2]);
+        let (trust, escalator) = (TrustEngine::new(), ThresholdEscalator::for_proposal_type(ProposalType::Normal));
+        let rebuilt = sample_series(&p1, &votes, &escalator, opened, opened + Duration::seconds(125), DEFAULT_SNAPSHOT_CADENCE, &trust);
+        assert_eq!(rebuilt, stored);
+        // Backfilling over a recorded series adds nothing
+        assert_eq!(store.append(&rebuilt).unwrap(), 0);
+
+        std::fs::write(&path, format!("{}\nnot json\n", serde_json::to_string(&stored[0]).unwrap())).unwrap();
+        assert!(matches!(store.load(), Err(SeriesError::Malformed { line: 2, .. })));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,148 @@
+// src/stake.rs
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ids::VoterId;
+use crate::scheme::{DefaultScheme, SignatureScheme};
+use crate::vote::{deserialize_public_key, deserialize_signature, serialize_public_key, serialize_signature};
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum StakeError {
+    #[error("Stake attestor is not a recognised authority")]
+    UnknownAttestor,
+    #[error("Invalid stake attestation signature")]
+    InvalidSignature,
+    #[error("Stake attested for {found}, not {expected}")]
+    WrongVoter { expected: VoterId, found: VoterId },
+    #[error("Attested stake {0} is not a positive amount")]
+    InvalidAmount(f64),
+    #[error("Vote carries no stake attestation")]
+    Missing,
+}
+
+pub fn stake_message(voter_id: &str, amount: f64, issued_at: DateTime<Utc>) -> String {
+    format!("stake:{}:{}:{}", voter_id, amount, issued_at.to_rfc3339())
+}
+
+/// An authority's signed statement of how much a voter has staked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StakeAttestation {
+    pub voter_id: VoterId,
+    pub amount: f64,
+    pub issued_at: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<32>")]
+    pub attestor: VerifyingKey,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<64>")]
+    pub signature: Signature,
+}
+
+impl StakeAttestation {
+    pub fn new(voter_id: VoterId, amount: f64, issued_at: DateTime<Utc>, attestor: &SigningKey) -> Self {
+        let signature = DefaultScheme::sign(attestor, stake_message(&voter_id, amount, issued_at).as_bytes());
+        Self {
+            voter_id,
+            amount,
+            issued_at,
+            attestor: DefaultScheme::public_key(attestor),
+            signature,
+        }
+    }
+
+    /// The attested amount, if `voter_id` holds it and a member of `authorities` signed it
+    pub fn verify(&self, voter_id: &VoterId, authorities: &StakeAuthorities) -> Result<f64, StakeError> {
+        if self.voter_id != *voter_id {
+            return Err(StakeError::WrongVoter { expected: voter_id.clone(), found: self.voter_id.clone() });
+        }
+        if !authorities.contains(&self.attestor) {
+            return Err(StakeError::UnknownAttestor);
+        }
+        DefaultScheme::verify(
+            &self.attestor,
+            stake_message(&self.voter_id, self.amount, self.issued_at).as_bytes(),
+            &self.signature,
+        )
+        .map_err(|_| StakeError::InvalidSignature)?;
+        if !(self.amount.is_finite() && self.amount > 0.0) {
+            return Err(StakeError::InvalidAmount(self.amount));
+        }
+        Ok(self.amount)
+    }
+}
+
+/// Keys whose stake attestations are accepted
+#[derive(Debug, Clone, Default)]
+pub struct StakeAuthorities {
+    keys: Vec<VerifyingKey>,
+}
+
+impl StakeAuthorities {
+    pub fn new(keys: impl IntoIterator<Item = VerifyingKey>) -> Self {
+        let mut authorities = Self::default();
+        for key in keys {
+            authorities.add(key);
+        }
+        authorities
+    }
+
+    pub fn add(&mut self, key: VerifyingKey) {
+        if !self.contains(&key) {
+            self.keys.push(key);
+        }
+    }
+
+    pub fn remove(&mut self, key: &VerifyingKey) {
+        self.keys.retain(|k| k != key);
+    }
+
+    pub fn contains(&self, key: &VerifyingKey) -> bool {
+        self.keys.contains(key)
+    }
+}
+
+/// Where a vote's original weight came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum StakeBasis {
+    /// The vote's own `original_weight`; no authorities are configured
+    Declared,
+    Attested { amount: f64 },
+    /// Authorities are configured but the vote's proof failed, so it carries no weight
+    Unproven(StakeError),
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attestations_checked_against_authorities() {
+        let authority = SigningKey::from_bytes(&[7; 32]);
+        let stranger = SigningKey::from_bytes(&[9; 32]);
+        let authorities = StakeAuthorities::new([authority.verifying_key()]);
+        let alice: VoterId = "alice".parse().unwrap();
+        let now = Utc::now();
+
+        let proof = StakeAttestation::new(alice.clone(), 250.0, now, &authority);
+        assert_eq!(proof.verify(&alice, &authorities), Ok(250.0));
+        assert!(matches!(proof.verify(&"bob".parse().unwrap(), &authorities), Err(StakeError::WrongVoter { .. })));
+
+        let mut inflated = proof.clone();
+        inflated.amount = 10_000.0;
+        assert_eq!(inflated.verify(&alice, &authorities), Err(StakeError::InvalidSignature));
+        let self_signed = StakeAttestation::new(alice.clone(), 250.0, now, &stranger);
+        assert_eq!(self_signed.verify(&alice, &authorities), Err(StakeError::UnknownAttestor));
+        assert_eq!(
+            StakeAttestation::new(alice.clone(), -1.0, now, &authority).verify(&alice, &authorities),
+            Err(StakeError::InvalidAmount(-1.0))
+        );
+
+        let decoded: StakeAttestation = serde_json::from_str(&serde_json::to_string(&proof).unwrap()).unwrap();
+        assert_eq!(decoded, proof);
+    }
+}
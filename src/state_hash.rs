@@ -0,0 +1,277 @@
+// src/state_hash.rs
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::blockchain::Blockchain;
+use crate::explorer::open_proposals;
+use crate::hashing::{self, CanonicalHasher, Domain};
+use crate::ids::VoterId;
+use crate::progress::CancellationToken;
+use crate::registry::{ExitPolicy, RampCurve, ValidatorRegistry};
+use crate::trust::TrustSource;
+use crate::units::Seconds;
+use crate::weight_engine::WeightEngine;
+
+/// Tallies are evaluated at multiples of this, so nodes a few seconds apart agree
+pub const DEFAULT_QUANTUM: Seconds = Seconds::new(60);
+
+/// Deterministic digest of a node's consensus-relevant state. Each component is
+/// hashed separately so operators can see which part diverged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateHash {
+    /// Quantized time the tallies were evaluated at
+    pub at: DateTime<Utc>,
+    pub open_proposals: usize,
+    pub proposals: String,
+    pub registry: String,
+    pub trust: String,
+    pub root: String,
+}
+
+impl StateHash {
+    /// Hash the state visible from `chain`, `registry`, and `trust` as of `now`,
+    /// rounded down to a multiple of `quantum`
+    pub fn compute(
+        chain: &Blockchain,
+        registry: &ValidatorRegistry,
+        trust: &dyn TrustSource,
+        now: DateTime<Utc>,
+        quantum: Seconds,
+    ) -> Self {
+        let at = quantize(now, quantum);
+        let open = open_proposals(chain);
+
+        let engine = WeightEngine::new();
+        let mut proposals = component("proposals");
+        proposals.u64(open.len() as u64);
+        for (proposal_id, votes) in &open {
+            let tally: f64 = votes
+                .iter()
+                .map(|v| engine.explain(v, at, Some(trust), Some(registry)).final_weight.get())
+                .sum();
+            proposals.str(proposal_id).u64(votes.len() as u64).f64(tally);
+        }
+
+        let mut voters: BTreeSet<&str> = registry.validator_ids().into_iter().collect();
+        voters.extend(open.values().flatten().map(|v| v.voter_id.as_str()));
+        let mut trust_hasher = component("trust");
+        for voter in &voters {
+            trust_hasher.str(voter).f64(voter.parse().map_or(1.0, |voter: VoterId| trust.get_bonus(&voter)));
+        }
+
+        let (proposals, registry, trust) = (
+            proposals.finish_hex(),
+            registry_digest(registry),
+            trust_hasher.finish_hex(),
+        );
+        StateHash {
+            at,
+            open_proposals: open.len(),
+            root: hashing::state_root(at.timestamp(), &proposals, &registry, &trust),
+            proposals,
+            registry,
+            trust,
+        }
+    }
+
+    /// Components that differ from `other`; empty when both nodes agree
+    pub fn diverged(&self, other: &StateHash) -> Vec<&'static str> {
+        let mut diverged = Vec::new();
+        if self.at != other.at {
+            diverged.push("at");
+        }
+        if self.proposals != other.proposals {
+            diverged.push("proposals");
+        }
+        if self.registry != other.registry {
+            diverged.push("registry");
+        }
+        if self.trust != other.trust {
+            diverged.push("trust");
+        }
+        diverged
+    }
+}
+
+/// Latest state hash, shared between the recompute loop and the API
+pub type SharedStateHash = Arc<RwLock<Option<StateHash>>>;
+
+/// Recompute the state hash every `interval` on a background thread until `cancel` fires
+pub fn spawn_recompute<F>(
+    interval: std::time::Duration,
+    cancel: CancellationToken,
+    compute: F,
+) -> (SharedStateHash, JoinHandle<()>)
+where
+    F: Fn() -> StateHash + Send + 'static,
+{
+    let shared: SharedStateHash = Arc::new(RwLock::new(None));
+    let latest = Arc::clone(&shared);
+    let handle = std::thread::spawn(move || {
+        let tick = interval.min(std::time::Duration::from_millis(50));
+        while !cancel.is_cancelled() {
+            let hash = compute();
+            *latest.write().expect("state hash lock poisoned") = Some(hash);
+
+            let mut waited = std::time::Duration::ZERO;
+            while waited < interval && !cancel.is_cancelled() {
+                std::thread::sleep(tick);
+                waited += tick;
+            }
+        }
+    });
+    (shared, handle)
+}
+
+/// Round `now` down to a multiple of `quantum`; non-positive quanta leave it to the second
+pub fn quantize(now: DateTime<Utc>, quantum: Seconds) -> DateTime<Utc> {
+    let step = quantum.as_i64().max(1);
+    let secs = now.timestamp().div_euclid(step) * step;
+    DateTime::from_timestamp(secs, 0).unwrap_or(now)
+}
+
+fn component(name: &str) -> CanonicalHasher {
+    let mut hasher = CanonicalHasher::new(Domain::State);
+    hasher.str(name);
+    hasher
+}
+
+fn time(hasher: &mut CanonicalHasher, at: Option<DateTime<Utc>>) {
+    match at {
+        Some(at) => hasher.u64(1).i64(at.timestamp_millis()),
+        None => hasher.u64(0),
+    };
+}
+
+fn registry_digest(registry: &ValidatorRegistry) -> String {
+    let mut hasher = component("registry");
+    for authority in registry.authorities() {
+        hasher.bytes(&authority);
+    }
+    for id in registry.validator_ids() {
+        let key = registry.public_key(id).expect("listed validator has a key");
+        hasher.str(id).bytes(key.as_bytes());
+        time(&mut hasher, registry.revoked_at(key));
+
+        match registry.onboarding(id) {
+            Some(o) => {
+                let curve = match o.policy.curve {
+                    RampCurve::Linear => "linear".to_string(),
+                    RampCurve::EaseIn => "ease_in".to_string(),
+                    RampCurve::Steps(n) => format!("steps:{}", n),
+                };
+                hasher
+                    .str("onboarding")
+                    .i64(o.onboarded_at.timestamp_millis())
+                    .i64(o.policy.period.as_i64())
+                    .f64(o.policy.initial_multiplier)
+                    .str(&curve);
+            }
+            None => {
+                hasher.str("");
+            }
+        }
+        match registry.exit(id) {
+            Some(e) => {
+                let policy = match e.policy {
+                    ExitPolicy::KeepDecaying => "keep_decaying",
+                    ExitPolicy::Freeze => "freeze",
+                    ExitPolicy::Remove => "remove",
+                };
+                hasher
+                    .str("exit")
+                    .i64(e.requested_at.timestamp_millis())
+                    .str(policy)
+                    .i64(e.cooldown.as_i64());
+                time(&mut hasher, e.released_at);
+            }
+            None => {
+                hasher.str("");
+            }
+        }
+        // Only validators with roles add to the digest, so older states hash as before
+        if let Some(roles) = registry.role_weights(id) {
+            hasher.str("roles");
+            for (category, weight) in roles {
+                hasher.str(category).f64(*weight);
+            }
+        }
+    }
+    hasher.finish_hex()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::{Transaction, VoteEntry};
+    use crate::registry::ExitPolicy;
+    use crate::trust::TrustEngine;
+    use crate::vote::{DecayType, SignedVote};
+    use chrono::{Duration, TimeZone};
+
+    fn node(votes: &[SignedVote]) -> (Blockchain, ValidatorRegistry) {
+        let mut chain = Blockchain::with_genesis("Genesis Block".to_string(), 1);
+        let txs: Vec<Transaction> = votes.iter().map(|v| Transaction::Vote(VoteEntry::from(v))).collect();
+        chain.add_transactions(&txs);
+        let mut registry = ValidatorRegistry::new();
+        for v in votes {
+            registry.register(v.voter_id.to_string(), v.public_key);
+        }
+        (chain, registry)
+    }
+
+    #[test]
+    fn test_identical_nodes_agree_and_divergence_is_located() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 10, 0).unwrap();
+        let votes: Vec<SignedVote> = ["alice", "bob"]
+            .iter()
+            .map(|voter| {
+                let key = SignedVote::generate_keypair();
+                let at = now - Duration::seconds(90);
+                SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, at, DecayType::Linear, &key)
+            })
+            .collect();
+        let (chain, registry) = node(&votes);
+        let trust = TrustEngine::new();
+
+        let a = StateHash::compute(&chain, &registry, &trust, now, DEFAULT_QUANTUM);
+        let b = StateHash::compute(&chain, &registry, &trust, now + Duration::seconds(30), DEFAULT_QUANTUM);
+        assert_eq!(a, b);
+        assert_eq!(a.open_proposals, 1);
+        assert!(a.diverged(&b).is_empty());
+
+        let (chain, mut other) = node(&votes);
+        other
+            .request_exit("bob", now - Duration::seconds(30), ExitPolicy::Remove, Seconds::new(60))
+            .unwrap();
+        let c = StateHash::compute(&chain, &other, &trust, now, DEFAULT_QUANTUM);
+        assert_ne!(a.root, c.root);
+        assert_eq!(a.diverged(&c), vec!["proposals", "registry"]);
+
+        let (chain, mut roles) = node(&votes);
+        roles.set_category_weight("alice", "parameter_change", 2.0).unwrap();
+        let d = StateHash::compute(&chain, &roles, &trust, now, DEFAULT_QUANTUM);
+        assert_eq!(a.diverged(&d), vec!["registry"]);
+    }
+
+    #[test]
+    fn test_recompute_publishes_latest_hash() {
+        let (chain, registry) = node(&[]);
+        let cancel = CancellationToken::new();
+        let (shared, handle) = spawn_recompute(std::time::Duration::from_millis(10), cancel.clone(), move || {
+            StateHash::compute(&chain, &registry, &TrustEngine::new(), Utc::now(), DEFAULT_QUANTUM)
+        });
+        while shared.read().unwrap().is_none() {
+            std::thread::yield_now();
+        }
+        cancel.cancel();
+        handle.join().unwrap();
+        assert_eq!(shared.read().unwrap().as_ref().unwrap().open_proposals, 0);
+    }
+}
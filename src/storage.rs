@@ -0,0 +1,244 @@
+// src/storage.rs
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::blockchain::{Blockchain, Transaction};
+
+/// Where a node's chain is durably written
+pub trait StorageBackend {
+    fn persist(&mut self, chain: &Blockchain) -> std::io::Result<()>;
+}
+
+/// Writes the whole chain as JSON to one file
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    pub path: PathBuf,
+}
+
+impl StorageBackend for FileStorage {
+    fn persist(&mut self, chain: &Blockchain) -> std::io::Result<()> {
+        chain.save(&self.path)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Storage is unavailable and the {capacity}-transaction buffer is full")]
+    BufferFull { capacity: usize },
+    #[error("Finalization refused while storage is unavailable")]
+    FinalizationRefused,
+    #[error("Storage error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// How a node behaves while its storage backend is failing
+#[derive(Debug, Clone)]
+pub struct DegradationPolicy {
+    /// Transactions held in memory before new ones are rejected
+    pub buffer_capacity: usize,
+    /// Reject finalizations until storage returns, so no outcome exists only in memory
+    pub refuse_finalization: bool,
+}
+
+impl Default for DegradationPolicy {
+    fn default() -> Self {
+        Self {
+            buffer_capacity: 10_000,
+            refuse_finalization: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageMode {
+    Healthy,
+    Degraded { since: DateTime<Utc>, error: String },
+}
+
+/// Sent to operators when storage fails, fills the buffer, or comes back
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageAlert {
+    Degraded { at: DateTime<Utc>, error: String },
+    BufferFull { capacity: usize },
+    Recovered { at: DateTime<Utc>, reconciled: usize },
+}
+
+/// A chain whose writes survive a failing backend: while storage is down, transactions
+/// wait in a bounded buffer and operators are alerted, and `reconcile` writes
+/// everything out once storage returns.
+pub struct ResilientStore<B: StorageBackend> {
+    chain: Blockchain,
+    backend: B,
+    pub policy: DegradationPolicy,
+    mode: StorageMode,
+    buffer: VecDeque<Transaction>,
+    alerts: Option<Sender<StorageAlert>>,
+}
+
+impl<B: StorageBackend> ResilientStore<B> {
+    pub fn new(chain: Blockchain, backend: B, policy: DegradationPolicy) -> Self {
+        Self {
+            chain,
+            backend,
+            policy,
+            mode: StorageMode::Healthy,
+            buffer: VecDeque::new(),
+            alerts: None,
+        }
+    }
+
+    /// Deliver alerts into `sender`
+    pub fn with_alerts(mut self, sender: Sender<StorageAlert>) -> Self {
+        self.alerts = Some(sender);
+        self
+    }
+
+    pub fn chain(&self) -> &Blockchain {
+        &self.chain
+    }
+
+    pub fn mode(&self) -> &StorageMode {
+        &self.mode
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.mode != StorageMode::Healthy
+    }
+
+    /// Transactions waiting for storage to return
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Add `transactions` as one block and persist the chain. While storage is down
+    /// they are buffered instead; a write failing here keeps the block in memory and
+    /// switches to buffering.
+    pub fn record(&mut self, transactions: &[Transaction], now: DateTime<Utc>) -> Result<(), StorageError> {
+        if !self.is_degraded() {
+            self.chain.add_transactions(transactions);
+            if let Err(e) = self.backend.persist(&self.chain) {
+                self.degrade(now, &e);
+            }
+            return Ok(());
+        }
+        if self.policy.refuse_finalization && transactions.iter().any(|tx| matches!(tx, Transaction::Finalization(_))) {
+            return Err(StorageError::FinalizationRefused);
+        }
+        if self.buffer.len() + transactions.len() > self.policy.buffer_capacity {
+            let capacity = self.policy.buffer_capacity;
+            self.alert(StorageAlert::BufferFull { capacity });
+            return Err(StorageError::BufferFull { capacity });
+        }
+        self.buffer.extend(transactions.iter().cloned());
+        Ok(())
+    }
+
+    /// Retry storage: write out blocks kept only in memory, then seal the buffer into
+    /// a block and write that. Returns how many buffered transactions were written;
+    /// on failure the store stays degraded.
+    pub fn reconcile(&mut self, now: DateTime<Utc>) -> Result<usize, StorageError> {
+        if !self.is_degraded() {
+            return Ok(0);
+        }
+        self.backend.persist(&self.chain)?;
+        let reconciled = self.buffer.len();
+        if reconciled > 0 {
+            let pending: Vec<Transaction> = self.buffer.drain(..).collect();
+            self.chain.add_transactions(&pending);
+            if let Err(e) = self.backend.persist(&self.chain) {
+                self.mode = StorageMode::Degraded { since: now, error: e.to_string() };
+                return Err(e.into());
+            }
+        }
+        self.mode = StorageMode::Healthy;
+        self.alert(StorageAlert::Recovered { at: now, reconciled });
+        Ok(reconciled)
+    }
+
+    fn degrade(&mut self, now: DateTime<Utc>, error: &std::io::Error) {
+        self.mode = StorageMode::Degraded { since: now, error: error.to_string() };
+        self.alert(StorageAlert::Degraded { at: now, error: error.to_string() });
+    }
+
+    fn alert(&self, alert: StorageAlert) {
+        if let Some(sender) = &self.alerts {
+            // Operators who stopped listening should not stall the node
+            let _ = sender.send(alert);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::mpsc;
+
+    use crate::blockchain::FinalizationEntry;
+
+    struct FlakyStorage {
+        down: Rc<Cell<bool>>,
+        writes: Rc<Cell<usize>>,
+    }
+
+    impl StorageBackend for FlakyStorage {
+        fn persist(&mut self, _chain: &Blockchain) -> std::io::Result<()> {
+            if self.down.get() {
+                return Err(std::io::Error::other("disk unavailable"));
+            }
+            self.writes.set(self.writes.get() + 1);
+            Ok(())
+        }
+    }
+
+    fn finalization(proposal_id: &str) -> Transaction {
+        Transaction::Finalization(FinalizationEntry {
+            proposal_id: proposal_id.to_string(),
+            passed: true,
+            weight: 1.0,
+            threshold: 0.5,
+            finalized_at: Utc::now(),
+        })
+    }
+
+    #[test]
+    fn test_degrades_buffers_and_reconciles() {
+        let (down, writes) = (Rc::new(Cell::new(false)), Rc::new(Cell::new(0)));
+        let backend = FlakyStorage { down: down.clone(), writes: writes.clone() };
+        let (sender, alerts) = mpsc::channel();
+        let policy = DegradationPolicy { buffer_capacity: 2, refuse_finalization: true };
+        let mut store = ResilientStore::new(Blockchain::with_genesis("Genesis".into(), 1), backend, policy).with_alerts(sender);
+        let now = Utc::now();
+
+        store.record(&[finalization("p1")], now).unwrap();
+        assert_eq!(writes.get(), 1);
+
+        down.set(true);
+        store.record(&[finalization("p2")], now).unwrap();
+        assert!(store.is_degraded());
+        assert!(matches!(alerts.try_recv(), Ok(StorageAlert::Degraded { .. })));
+        assert_eq!(store.chain().blocks.len(), 3);
+
+        assert!(matches!(store.record(&[finalization("p3")], now), Err(StorageError::FinalizationRefused)));
+        store.policy.refuse_finalization = false;
+        store.record(&[finalization("p3"), finalization("p4")], now).unwrap();
+        assert!(matches!(store.record(&[finalization("p5")], now), Err(StorageError::BufferFull { capacity: 2 })));
+        assert_eq!(alerts.try_recv(), Ok(StorageAlert::BufferFull { capacity: 2 }));
+        assert!(matches!(store.reconcile(now), Err(StorageError::Io(_))));
+        assert_eq!(store.buffered(), 2);
+
+        down.set(false);
+        assert_eq!(store.reconcile(now).unwrap(), 2);
+        assert_eq!(alerts.try_recv(), Ok(StorageAlert::Recovered { at: now, reconciled: 2 }));
+        assert_eq!((store.buffered(), store.chain().blocks.len(), writes.get()), (0, 4, 3));
+        assert_eq!(store.mode(), &StorageMode::Healthy);
+        assert_eq!(store.reconcile(now).unwrap(), 0);
+    }
+}
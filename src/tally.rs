@@ -0,0 +1,366 @@
+// src/tally.rs
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ids::VoterId;
+use crate::trust::TrustSource;
+use crate::vote::{RankedVote, VoteChoice};
+use crate::weight_engine::WeightEngine;
+
+/// Raw weight sums for one proposal, measured against the total weight eligible to vote
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Tally {
+    pub approve_weight: f64,
+    pub reject_weight: f64,
+    /// Counted toward turnout but neither side
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub abstain_weight: f64,
+    pub eligible_weight: f64,
+    pub votes: usize,
+}
+
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+
+/// The same tally as fractions in [0, 1] (margin in [-1, 1])
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NormalizedTally {
+    pub approval_fraction: f64,
+    pub rejection_fraction: f64,
+    pub turnout_fraction: f64,
+    pub support_fraction: f64,
+    pub margin: f64,
+}
+
+/// Area under the approval-weight curve over a window, in weight-seconds, and that
+/// area divided by the window length
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalIntegral {
+    pub area: f64,
+    pub average: f64,
+}
+
+impl ApprovalIntegral {
+    /// Trapezoidal integral of `approval_at` over `[start, end]` sampled at `samples`
+    /// evenly spaced intervals. An empty window averages to the value at `start`.
+    pub fn integrate(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        samples: i64,
+        mut approval_at: impl FnMut(DateTime<Utc>) -> f64,
+    ) -> Self {
+        let span = (end - start).num_milliseconds();
+        if span <= 0 {
+            return Self {
+                area: 0.0,
+                average: approval_at(start),
+            };
+        }
+        let samples = samples.clamp(1, span);
+        let mut area = 0.0;
+        let mut previous = approval_at(start);
+        for i in 1..=samples {
+            let current = approval_at(start + Duration::milliseconds(span * i / samples));
+            let width = (span * i / samples - span * (i - 1) / samples) as f64 / 1000.0;
+            area += (previous + current) / 2.0 * width;
+            previous = current;
+        }
+        Self {
+            area,
+            average: area / (span as f64 / 1000.0),
+        }
+    }
+}
+
+fn ratio(part: f64, whole: f64) -> f64 {
+    if whole > 0.0 { (part / whole).clamp(0.0, 1.0) } else { 0.0 }
+}
+
+impl Tally {
+    pub fn new(eligible_weight: f64) -> Self {
+        Self {
+            eligible_weight: eligible_weight.max(0.0),
+            ..Self::default()
+        }
+    }
+
+    pub fn approve(&mut self, weight: f64) {
+        self.approve_weight += weight.max(0.0);
+        self.votes += 1;
+    }
+
+    pub fn reject(&mut self, weight: f64) {
+        self.reject_weight += weight.max(0.0);
+        self.votes += 1;
+    }
+
+    pub fn abstain(&mut self, weight: f64) {
+        self.abstain_weight += weight.max(0.0);
+        self.votes += 1;
+    }
+
+    /// Count `weight` toward whichever side `choice` takes
+    pub fn record(&mut self, choice: VoteChoice, weight: f64) {
+        match choice {
+            VoteChoice::Approve => self.approve(weight),
+            VoteChoice::Reject => self.reject(weight),
+            VoteChoice::Abstain => self.abstain(weight),
+        }
+    }
+
+    /// Undo an earlier `approve`, e.g. when re-verification drops the vote
+    pub fn retract_approval(&mut self, weight: f64) {
+        self.retract(VoteChoice::Approve, weight);
+    }
+
+    /// Undo an earlier `record` of `weight` toward `choice`
+    pub fn retract(&mut self, choice: VoteChoice, weight: f64) {
+        let side = match choice {
+            VoteChoice::Approve => &mut self.approve_weight,
+            VoteChoice::Reject => &mut self.reject_weight,
+            VoteChoice::Abstain => &mut self.abstain_weight,
+        };
+        *side = (*side - weight.max(0.0)).max(0.0);
+        self.votes = self.votes.saturating_sub(1);
+    }
+
+    pub fn cast_weight(&self) -> f64 {
+        self.approve_weight + self.reject_weight
+    }
+
+    /// Share of the cast weight that approves
+    pub fn approval_fraction(&self) -> f64 {
+        ratio(self.approve_weight, self.cast_weight())
+    }
+
+    pub fn rejection_fraction(&self) -> f64 {
+        ratio(self.reject_weight, self.cast_weight())
+    }
+
+    /// Approval weight left after opposing weight is subtracted; what thresholds are met by
+    pub fn net_weight(&self) -> f64 {
+        (self.approve_weight - self.reject_weight).max(0.0)
+    }
+
+    /// Share of the eligible weight that voted at all, abstentions included; capped at 1
+    /// when trust bonuses push cast weight past the eligible total
+    pub fn turnout_fraction(&self) -> f64 {
+        ratio(self.cast_weight() + self.abstain_weight, self.eligible_weight)
+    }
+
+    /// Share of the eligible weight that approves
+    pub fn support_fraction(&self) -> f64 {
+        ratio(self.approve_weight, self.eligible_weight)
+    }
+
+    /// Approval minus rejection, as a share of the cast weight
+    pub fn margin(&self) -> f64 {
+        self.approval_fraction() - self.rejection_fraction()
+    }
+
+    pub fn normalized(&self) -> NormalizedTally {
+        NormalizedTally {
+            approval_fraction: self.approval_fraction(),
+            rejection_fraction: self.rejection_fraction(),
+            turnout_fraction: self.turnout_fraction(),
+            support_fraction: self.support_fraction(),
+            margin: self.margin(),
+        }
+    }
+}
+
+/// One instant-runoff round: each continuing option's weight from the ballots ranking
+/// it highest, and the option dropped before the next round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunoffRound {
+    pub counts: BTreeMap<String, f64>,
+    /// Weight of ballots ranking no continuing option
+    pub exhausted_weight: f64,
+    pub eliminated: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunoffResult {
+    pub rounds: Vec<RunoffRound>,
+    /// `None` when no ballot carried any weight
+    pub winner: Option<String>,
+}
+
+/// Instant-runoff over weighted rankings. An option wins with more than half the weight
+/// still in play; otherwise the weakest is eliminated (ties drop the option sorting
+/// last) and its ballots pass to their next continuing choice.
+pub fn instant_runoff(ballots: &[(&[String], f64)]) -> RunoffResult {
+    let mut continuing: BTreeSet<&String> = ballots.iter().flat_map(|(ranking, _)| ranking.iter()).collect();
+    let mut result = RunoffResult::default();
+    while !continuing.is_empty() {
+        let mut counts: BTreeMap<String, f64> = continuing.iter().map(|o| ((*o).clone(), 0.0)).collect();
+        let mut exhausted_weight = 0.0;
+        for (ranking, weight) in ballots {
+            let weight = weight.max(0.0);
+            match ranking.iter().find(|o| continuing.contains(o)) {
+                Some(option) => *counts.get_mut(option.as_str()).expect("continuing options are counted") += weight,
+                None => exhausted_weight += weight,
+            }
+        }
+        let in_play: f64 = counts.values().sum();
+        if in_play <= 0.0 {
+            result.rounds.push(RunoffRound { counts, exhausted_weight, eliminated: None });
+            break;
+        }
+        let (leader, most) = counts
+            .iter()
+            .fold(None, |best: Option<(&String, f64)>, (o, w)| match best {
+                Some((_, top)) if top >= *w => best,
+                _ => Some((o, *w)),
+            })
+            .expect("at least one option continues");
+        if most > in_play / 2.0 || continuing.len() == 1 {
+            result.winner = Some(leader.clone());
+            result.rounds.push(RunoffRound { counts, exhausted_weight, eliminated: None });
+            break;
+        }
+        let weakest = counts
+            .iter()
+            .fold(None, |worst: Option<(&String, f64)>, (o, w)| match worst {
+                Some((_, low)) if low < *w => worst,
+                _ => Some((o, *w)),
+            })
+            .map(|(o, _)| o.clone())
+            .expect("at least one option continues");
+        continuing.remove(&weakest);
+        result.rounds.push(RunoffRound { counts, exhausted_weight, eliminated: Some(weakest) });
+    }
+    result
+}
+
+/// Instant-runoff over ranked ballots weighted as of `at`: each voter's latest version
+/// counts, with its decay and trust bonus applied as for any other vote
+pub fn tally_ranked(ballots: &[RankedVote], at: DateTime<Utc>, trust: Option<&dyn TrustSource>) -> RunoffResult {
+    let mut latest: BTreeMap<&VoterId, &RankedVote> = BTreeMap::new();
+    for ballot in ballots {
+        let current = latest.entry(&ballot.vote.voter_id).or_insert(ballot);
+        if ballot.vote.version > current.vote.version {
+            *current = ballot;
+        }
+    }
+    let engine = WeightEngine::new();
+    let weighted: Vec<(&[String], f64)> = latest
+        .values()
+        .map(|b| (b.ranking.as_slice(), engine.explain(&b.vote, at, trust, None).final_weight.get()))
+        .collect();
+    instant_runoff(&weighted)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fractions() {
+        let mut tally = Tally::new(10.0);
+        tally.approve(3.0);
+        tally.approve(3.0);
+        tally.reject(2.0);
+
+        assert_eq!(tally.votes, 3);
+        assert_eq!(tally.cast_weight(), 8.0);
+        assert_eq!(tally.approval_fraction(), 0.75);
+        assert_eq!(tally.turnout_fraction(), 0.8);
+        assert_eq!(tally.support_fraction(), 0.6);
+        assert_eq!(tally.margin(), 0.5);
+        assert_eq!(tally.net_weight(), 4.0);
+    }
+
+    #[test]
+    fn test_choices_and_abstentions() {
+        let mut tally = Tally::new(10.0);
+        tally.record(VoteChoice::Approve, 2.0);
+        tally.record(VoteChoice::Reject, 3.0);
+        tally.record(VoteChoice::Abstain, 5.0);
+
+        assert_eq!(tally.votes, 3);
+        assert_eq!(tally.cast_weight(), 5.0);
+        assert_eq!(tally.approval_fraction(), 0.4);
+        assert_eq!(tally.turnout_fraction(), 1.0);
+        assert_eq!(tally.net_weight(), 0.0);
+        assert!(!serde_json::to_string(&Tally::new(1.0)).unwrap().contains("abstain"));
+    }
+
+    #[test]
+    fn test_integral_rewards_sustained_support() {
+        let start = Utc::now();
+        let end = start + Duration::seconds(100);
+        let sustained = ApprovalIntegral::integrate(start, end, 100, |_| 0.6);
+        assert!((sustained.area - 60.0).abs() < 1e-9);
+        assert!((sustained.average - 0.6).abs() < 1e-9);
+
+        // The same final weight arriving in the last ten seconds
+        let spike = ApprovalIntegral::integrate(start, end, 100, |t| if t >= start + Duration::seconds(90) { 0.6 } else { 0.0 });
+        assert!(spike.average < 0.1);
+        assert_eq!(ApprovalIntegral::integrate(end, start, 10, |_| 0.4).average, 0.4);
+    }
+
+    #[test]
+    fn test_empty_and_overfull_tallies() {
+        let empty = Tally::new(0.0);
+        assert_eq!(empty.normalized().approval_fraction, 0.0);
+        assert_eq!(empty.turnout_fraction(), 0.0);
+        assert_eq!(empty.margin(), 0.0);
+
+        let mut boosted = Tally::new(1.0);
+        boosted.approve(1.2);
+        assert_eq!(boosted.turnout_fraction(), 1.0);
+        assert_eq!(boosted.support_fraction(), 1.0);
+    }
+
+    fn ranked(voter: &str, options: &[&str], secs_ago: i64, now: DateTime<Utc>) -> RankedVote {
+        let key = crate::vote::SignedVote::generate_keypair();
+        let ranking = options.iter().map(|o| o.to_string()).collect();
+        RankedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, now - Duration::seconds(secs_ago), crate::vote::DecayType::Linear, ranking, &key)
+    }
+
+    #[test]
+    fn test_instant_runoff_transfers_eliminated_ballots() {
+        let r = |options: &[&str]| options.iter().map(|o| o.to_string()).collect::<Vec<_>>();
+        let (ab, bc, ca, c) = (r(&["a", "b"]), r(&["b", "c"]), r(&["c", "a"]), r(&["c"]));
+        let ballots: Vec<(&[String], f64)> = vec![(&ab, 4.0), (&bc, 3.0), (&ca, 2.0), (&c, 1.5)];
+        let result = instant_runoff(&ballots);
+
+        assert_eq!(result.rounds.len(), 2);
+        assert_eq!(result.rounds[0].counts["c"], 3.5);
+        assert_eq!(result.rounds[0].eliminated.as_deref(), Some("b"));
+        // b's ballots move to c; a's single-option supporters never exhaust
+        assert_eq!(result.rounds[1].counts["c"], 6.5);
+        assert_eq!(result.winner.as_deref(), Some("c"));
+
+        let tie: Vec<(&[String], f64)> = vec![(&ab, 1.0), (&bc, 1.0)];
+        let result = instant_runoff(&tie);
+        assert_eq!(result.rounds[0].eliminated.as_deref(), Some("c"));
+        assert_eq!(result.rounds[1].eliminated.as_deref(), Some("b"));
+        assert_eq!(result.winner.as_deref(), Some("a"));
+        assert_eq!(instant_runoff(&[]).winner, None);
+    }
+
+    #[test]
+    fn test_ranked_ballots_are_weighted_and_signed() {
+        let now = Utc::now();
+        let early = ranked("alice", &["x", "y"], 3000, now);
+        let ballots = vec![early.clone(), ranked("bob", &["y", "x"], 10, now), ranked("carol", &["y"], 20, now)];
+        assert_eq!(early.verify(3600), Ok(()));
+        let mut tampered = early.clone();
+        tampered.ranking.reverse();
+        assert_eq!(tampered.verify(3600), Err(crate::verify::VerificationError::InvalidRanking));
+
+        let result = tally_ranked(&ballots, now, None);
+        assert_eq!(result.winner.as_deref(), Some("y"));
+        // An abstaining carrier vote only adds turnout to a binary tally
+        let mut binary = Tally::new(3.0);
+        binary.record(early.vote.choice, 1.0);
+        assert_eq!(binary.approve_weight, 0.0);
+    }
+}
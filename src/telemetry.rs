@@ -0,0 +1,321 @@
+// src/telemetry.rs
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Serialize, Serializer};
+
+use crate::blockchain::Blockchain;
+use crate::discovery::{DiscoveryError, WebhookSink};
+use crate::explorer;
+use crate::hashing;
+use crate::vote::SignedVote;
+
+pub const SERVICE_NAME: &str = "time-decay-consensus";
+
+/// OTLP span kind for work done inside the service
+const KIND_INTERNAL: u8 = 1;
+const STATUS_OK: u8 = 1;
+const STATUS_ERROR: u8 = 2;
+
+/// An OTLP attribute value, serialized in the OTLP/JSON layout (`{"stringValue": ...}`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum AttributeValue {
+    #[serde(rename = "stringValue")]
+    Str(String),
+    #[serde(rename = "boolValue")]
+    Bool(bool),
+    #[serde(rename = "doubleValue")]
+    Double(f64),
+    /// OTLP/JSON carries 64-bit integers as strings
+    #[serde(rename = "intValue", serialize_with = "as_string")]
+    Int(i64),
+}
+
+fn as_string<S: Serializer>(value: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: AttributeValue,
+}
+
+fn attr(key: &str, value: AttributeValue) -> KeyValue {
+    KeyValue { key: key.to_string(), value }
+}
+
+fn nanos(at: DateTime<Utc>) -> String {
+    at.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanEvent {
+    pub time_unix_nano: String,
+    pub name: String,
+    pub attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpanStatus {
+    pub code: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    pub trace_id: String,
+    pub span_id: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub parent_span_id: String,
+    pub name: String,
+    pub kind: u8,
+    pub start_time_unix_nano: String,
+    pub end_time_unix_nano: String,
+    pub attributes: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<SpanEvent>,
+    pub status: SpanStatus,
+}
+
+/// A proposal's lifecycle as spans: a root `proposal` span from open to finalization,
+/// with children for each admission, window extension, and the finalization itself,
+/// and threshold escalations as events on the root
+#[derive(Debug, Clone)]
+pub struct ProposalTimeline {
+    pub proposal_id: String,
+    pub opened_at: DateTime<Utc>,
+    children: Vec<Span>,
+    escalations: Vec<SpanEvent>,
+    last_activity: DateTime<Utc>,
+    finalized: Option<(DateTime<Utc>, bool)>,
+}
+
+impl ProposalTimeline {
+    pub fn new(proposal_id: &str, opened_at: DateTime<Utc>) -> Self {
+        Self {
+            proposal_id: proposal_id.to_string(),
+            opened_at,
+            children: Vec::new(),
+            escalations: Vec::new(),
+            last_activity: opened_at,
+            finalized: None,
+        }
+    }
+
+    pub fn trace_id(&self) -> String {
+        hex::encode(&hashing::telemetry_id(&self.proposal_id, self.opened_at.timestamp(), 0)[..16])
+    }
+
+    fn span_id(&self, index: u64) -> String {
+        hex::encode(&hashing::telemetry_id(&self.proposal_id, self.opened_at.timestamp(), index)[..8])
+    }
+
+    fn child(&mut self, name: &str, start: DateTime<Utc>, end: DateTime<Utc>, attributes: Vec<KeyValue>, status: u8) {
+        let span = Span {
+            trace_id: self.trace_id(),
+            span_id: self.span_id(self.children.len() as u64 + 2),
+            parent_span_id: self.span_id(1),
+            name: name.to_string(),
+            kind: KIND_INTERNAL,
+            start_time_unix_nano: nanos(start),
+            end_time_unix_nano: nanos(end.max(start)),
+            attributes,
+            events: Vec::new(),
+            status: SpanStatus { code: status },
+        };
+        self.children.push(span);
+        self.last_activity = self.last_activity.max(end);
+    }
+
+    /// `vote` admitted at `admitted_at`; the span runs from the vote's own timestamp
+    pub fn admitted(&mut self, vote: &SignedVote, admitted_at: DateTime<Utc>) {
+        let attributes = vec![
+            attr("voter_id", AttributeValue::Str(vote.voter_id.to_string())),
+            attr("choice", AttributeValue::Str(format!("{:?}", vote.choice))),
+            attr("original_weight", AttributeValue::Double(vote.original_weight)),
+            attr("decay_model", AttributeValue::Str(format!("{:?}", vote.decay_model))),
+        ];
+        self.child("vote.admit", vote.timestamp, admitted_at, attributes, STATUS_OK);
+    }
+
+    /// `vote` turned away at `rejected_at`, recorded as an errored admission span
+    pub fn rejected(&mut self, vote: &SignedVote, reason: &str, rejected_at: DateTime<Utc>) {
+        let attributes = vec![
+            attr("voter_id", AttributeValue::Str(vote.voter_id.to_string())),
+            attr("reason", AttributeValue::Str(reason.to_string())),
+        ];
+        self.child("vote.admit", vote.timestamp, rejected_at, attributes, STATUS_ERROR);
+    }
+
+    pub fn escalated(&mut self, at: DateTime<Utc>, threshold: f64) {
+        self.escalations.push(SpanEvent {
+            time_unix_nano: nanos(at),
+            name: "threshold.escalated".to_string(),
+            attributes: vec![attr("threshold", AttributeValue::Double(threshold))],
+        });
+        self.last_activity = self.last_activity.max(at);
+    }
+
+    /// The window was pushed from `previous_close` to `new_close`; the span covers the added time
+    pub fn extended(&mut self, previous_close: DateTime<Utc>, new_close: DateTime<Utc>, reason: &str) {
+        let attributes = vec![
+            attr("reason", AttributeValue::Str(reason.to_string())),
+            attr("added_secs", AttributeValue::Int((new_close - previous_close).num_seconds())),
+        ];
+        self.child("window.extend", previous_close, new_close, attributes, STATUS_OK);
+    }
+
+    /// Close the trace; the finalization span runs from the last recorded activity
+    pub fn finalized(&mut self, at: DateTime<Utc>, passed: bool, weight: f64, threshold: f64) {
+        let attributes = vec![
+            attr("passed", AttributeValue::Bool(passed)),
+            attr("weight", AttributeValue::Double(weight)),
+            attr("threshold", AttributeValue::Double(threshold)),
+        ];
+        let start = self.last_activity.min(at);
+        self.child("proposal.finalize", start, at, attributes, STATUS_OK);
+        self.finalized = Some((at, passed));
+    }
+
+    /// Root span first, then children in the order they were recorded
+    pub fn spans(&self) -> Vec<Span> {
+        let mut attributes = vec![attr("proposal_id", AttributeValue::Str(self.proposal_id.clone()))];
+        if let Some((_, passed)) = self.finalized {
+            attributes.push(attr("passed", AttributeValue::Bool(passed)));
+        }
+        let root = Span {
+            trace_id: self.trace_id(),
+            span_id: self.span_id(1),
+            parent_span_id: String::new(),
+            name: "proposal".to_string(),
+            kind: KIND_INTERNAL,
+            start_time_unix_nano: nanos(self.opened_at),
+            end_time_unix_nano: nanos(self.finalized.map_or(self.last_activity, |(at, _)| at)),
+            attributes,
+            events: self.escalations.clone(),
+            status: SpanStatus { code: STATUS_OK },
+        };
+        std::iter::once(root).chain(self.children.iter().cloned()).collect()
+    }
+
+    /// The trace as an OTLP/JSON `ExportTraceServiceRequest`
+    pub fn to_otlp_json(&self) -> String {
+        let request = serde_json::json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [attr("service.name", AttributeValue::Str(SERVICE_NAME.to_string()))]
+                },
+                "scopeSpans": [{
+                    "scope": { "name": SERVICE_NAME },
+                    "spans": self.spans(),
+                }],
+            }]
+        });
+        serde_json::to_string_pretty(&request).expect("spans serialize")
+    }
+
+    /// Rebuild a timeline from the chain: admissions end at their block's timestamp
+    pub fn from_chain(chain: &Blockchain, proposal_id: &str) -> Option<Self> {
+        let activity = explorer::find_proposal(chain, proposal_id);
+        let block_time = |block_id: u64| {
+            chain
+                .blocks
+                .get(block_id as usize)
+                .and_then(|b| Utc.timestamp_opt(b.timestamp, 0).single())
+        };
+        let mut votes: Vec<(SignedVote, DateTime<Utc>)> = activity
+            .votes
+            .iter()
+            .filter_map(|v| Some((v.vote.to_signed_vote()?, block_time(v.block_id)?)))
+            .collect();
+        votes.sort_by_key(|(v, _)| v.timestamp);
+
+        let mut timeline = Self::new(proposal_id, votes.first()?.0.timestamp);
+        for (vote, admitted_at) in &votes {
+            timeline.admitted(vote, *admitted_at);
+        }
+        if let Some(f) = activity.finalizations.iter().map(|f| &f.finalization).max_by_key(|f| f.finalized_at) {
+            timeline.finalized(f.finalized_at, f.passed, f.weight, f.threshold);
+        }
+        Some(timeline)
+    }
+}
+
+/// Sends timelines to an OTLP/HTTP collector such as Jaeger or Tempo
+pub struct OtlpExporter {
+    sink: WebhookSink,
+}
+
+impl OtlpExporter {
+    /// `endpoint` is the collector's base URL, e.g. `http://localhost:4318`
+    pub fn new(endpoint: &str) -> Result<Self, DiscoveryError> {
+        Ok(Self {
+            sink: WebhookSink::new(&format!("{}/v1/traces", endpoint.trim_end_matches('/')))?,
+        })
+    }
+
+    pub fn export(&self, timeline: &ProposalTimeline) -> Result<(), DiscoveryError> {
+        self.sink.post(&timeline.to_otlp_json())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vote::DecayType;
+    use chrono::Duration;
+
+    fn vote(voter: &str, at: DateTime<Utc>) -> SignedVote {
+        let key = SignedVote::generate_keypair();
+        SignedVote::new(voter.parse().unwrap(), "p1".parse().unwrap(), 1.0, at, DecayType::Linear, &key)
+    }
+
+    #[test]
+    fn test_timeline_spans() {
+        let opened = Utc::now();
+        let mut timeline = ProposalTimeline::new("p1", opened);
+        timeline.admitted(&vote("alice", opened + Duration::seconds(5)), opened + Duration::seconds(6));
+        timeline.rejected(&vote("bob", opened + Duration::seconds(8)), "expired", opened + Duration::seconds(9));
+        timeline.escalated(opened + Duration::seconds(30), 0.6);
+        timeline.extended(opened + Duration::seconds(60), opened + Duration::seconds(90), "late surge");
+        timeline.finalized(opened + Duration::seconds(95), true, 0.8, 0.6);
+
+        let spans = timeline.spans();
+        let names: Vec<&str> = spans.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["proposal", "vote.admit", "vote.admit", "window.extend", "proposal.finalize"]);
+        assert!(spans[1..].iter().all(|s| s.parent_span_id == spans[0].span_id && s.trace_id == spans[0].trace_id));
+        assert_eq!(spans[0].events.len(), 1);
+        assert_eq!(spans[0].end_time_unix_nano, nanos(opened + Duration::seconds(95)));
+        assert_eq!(spans[2].status.code, STATUS_ERROR);
+        assert_eq!(spans[4].start_time_unix_nano, nanos(opened + Duration::seconds(90)));
+        assert_eq!(spans[0].trace_id.len(), 32);
+        assert_eq!(spans[0].span_id.len(), 16);
+
+        let json = timeline.to_otlp_json();
+        assert!(json.contains("\"resourceSpans\""));
+        assert!(json.contains("\"intValue\": \"30\""));
+        assert_eq!(ProposalTimeline::new("p1", opened).trace_id(), timeline.trace_id());
+    }
+
+    #[test]
+    fn test_exporter_posts_to_collector() {
+        use std::io::Read;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let exporter = OtlpExporter::new(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = String::new();
+            stream.read_to_string(&mut request).unwrap();
+            request
+        });
+
+        exporter.export(&ProposalTimeline::new("p1", Utc::now())).unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /v1/traces HTTP/1.1"));
+        assert!(request.contains(SERVICE_NAME));
+    }
+}
@@ -1,12 +1,48 @@
 // src/threshold.rs
 
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::clock::Clock;
+use crate::hashing;
+use crate::liveness::{PausedInterval, paused_between};
+use crate::tally::Tally;
+use crate::units::Seconds;
 use crate::vote::ProposalType;
 
+/// Escalation curve supplied from outside the crate, e.g. through the plugin registry
+pub trait EscalationCurve: std::fmt::Debug + Send + Sync {
+    fn threshold(&self, base_threshold: f64, ceiling: f64, elapsed_secs: u64) -> f64;
+}
+
 #[derive(Debug, Clone)]
 pub enum EscalationPattern {
     Linear(f64),       // rate: e.g., 0.01 means +1% per second
     Exponential(f64),  // factor: e.g., 0.001 for gradual curve
     Sigmoid(f64, f64), // (k, midpoint): smooth S-curve
+    Custom(Arc<dyn EscalationCurve>),
+}
+
+impl EscalationPattern {
+    pub fn threshold(&self, base_threshold: f64, ceiling: f64, elapsed_secs: u64) -> f64 {
+        match self {
+            EscalationPattern::Linear(rate) => {
+                let increase = rate * elapsed_secs as f64;
+                (base_threshold + increase).min(ceiling)
+            }
+            EscalationPattern::Exponential(factor) => {
+                let increase = 1.0 - (-factor * elapsed_secs as f64).exp();
+                (base_threshold + increase).min(ceiling)
+            }
+            EscalationPattern::Sigmoid(k, midpoint) => {
+                let x = elapsed_secs as f64;
+                let sigmoid = 1.0 / (1.0 + (-k * (x - midpoint)).exp());
+                let threshold_range = ceiling - base_threshold;
+                base_threshold + sigmoid * threshold_range
+            }
+            EscalationPattern::Custom(curve) => curve.threshold(base_threshold, ceiling, elapsed_secs),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -16,6 +52,87 @@ pub enum ProgressionProfile {
     Adaptive,
 }
 
+/// How a result that lands exactly on the threshold is decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    /// A tie rejects
+    FailClosed,
+    /// A tie passes; matches the plain `weight >= threshold` check
+    #[default]
+    FailOpen,
+    /// A coin drawn from the seed block hash and proposal id decides
+    SeededRandom,
+    /// The status quo wins: for a proposal measured against a threshold that means
+    /// nothing changes, so the proposal is rejected
+    StatusQuo,
+}
+
+/// Which tally is compared against the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TallyMetric {
+    /// Approval weight at the moment of finalization
+    #[default]
+    Instantaneous,
+    /// Approval weight averaged over the whole window, so support that was there
+    /// all along counts for more than a last-minute spike
+    TimeWeighted,
+}
+
+/// Rounding applied before weight and threshold are compared, what a tie means, and
+/// which votes are too light to count
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DecisionRule {
+    /// Decimal places both sides are rounded to; `None` compares exactly
+    pub precision: Option<u32>,
+    pub tie_break: TieBreak,
+    pub metric: TallyMetric,
+    /// Votes whose effective weight has decayed below this are dust: recorded, but left
+    /// out of the tally. Zero counts every vote.
+    pub min_effective_weight: f64,
+}
+
+/// Outcome of comparing a tally against its threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decision {
+    pub passed: bool,
+    /// Weight and threshold were equal after rounding, so `tie_break` decided
+    pub tied: bool,
+}
+
+impl DecisionRule {
+    /// Too light to count under `min_effective_weight`
+    pub fn is_dust(&self, effective_weight: f64) -> bool {
+        effective_weight < self.min_effective_weight
+    }
+
+    pub fn round(&self, value: f64) -> f64 {
+        match self.precision {
+            Some(decimals) => {
+                let scale = 10f64.powi(decimals.min(15) as i32);
+                (value * scale).round() / scale
+            }
+            None => value,
+        }
+    }
+
+    /// Compare `weight` against `threshold`. `seed_block_hash` and `proposal_id`
+    /// feed `TieBreak::SeededRandom` and are ignored otherwise.
+    pub fn decide(&self, weight: f64, threshold: f64, seed_block_hash: &str, proposal_id: &str) -> Decision {
+        match self.round(weight).total_cmp(&self.round(threshold)) {
+            Ordering::Greater => Decision { passed: true, tied: false },
+            Ordering::Less => Decision { passed: false, tied: false },
+            Ordering::Equal => Decision {
+                passed: match self.tie_break {
+                    TieBreak::FailOpen => true,
+                    TieBreak::FailClosed | TieBreak::StatusQuo => false,
+                    TieBreak::SeededRandom => hashing::tie_break_seed(seed_block_hash, proposal_id)[0] & 1 == 1,
+                },
+                tied: true,
+            },
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ThresholdEscalator {
     pub base_threshold: f64, // Starting threshold (e.g., 0.51)
@@ -25,6 +142,7 @@ pub struct ThresholdEscalator {
     pub profile: ProgressionProfile,
     pub total_votes: usize,
     pub min_vote_count: usize,
+    pub decision: DecisionRule,
 }
 
 impl ThresholdEscalator {
@@ -34,45 +152,12 @@ impl ThresholdEscalator {
             return self.ceiling; // Max threshold for critical situations
         }
 
-        match self.pattern {
-            EscalationPattern::Linear(rate) => {
-                let increase = rate * elapsed_secs as f64;
-                (self.base_threshold + increase).min(self.ceiling)
-            }
-            EscalationPattern::Exponential(factor) => {
-                let increase = 1.0 - (-factor * elapsed_secs as f64).exp();
-                (self.base_threshold + increase).min(self.ceiling)
-            }
-            EscalationPattern::Sigmoid(k, midpoint) => {
-                let x = elapsed_secs as f64;
-                let sigmoid = 1.0 / (1.0 + (-k * (x - midpoint)).exp());
-                let threshold_range = self.ceiling - self.base_threshold;
-                self.base_threshold + sigmoid * threshold_range
-            }
-        }
+        self.pattern.threshold(self.base_threshold, self.ceiling, elapsed_secs)
     }
 
+    /// Defaults for `proposal_type` from its built-in `ProposalPolicy`
     pub fn for_proposal_type(proposal_type: ProposalType) -> Self {
-        match proposal_type {
-            ProposalType::Normal => ThresholdEscalator {
-                base_threshold: 0.51,
-                ceiling: 0.9,
-                pattern: EscalationPattern::Linear(0.01),
-                emergency_override: false,
-                profile: ProgressionProfile::Conservative,
-                total_votes: 0,
-                min_vote_count: 3, // Minimum 3 votes required
-            },
-            ProposalType::Critical => ThresholdEscalator {
-                base_threshold: 0.75,
-                ceiling: 0.95,
-                pattern: EscalationPattern::Linear(0.02),
-                emergency_override: false,
-                profile: ProgressionProfile::Aggressive,
-                total_votes: 0,
-                min_vote_count: 5, // Stricter requirement for critical proposals
-            },
-        }
+        crate::policy::ProposalPolicy::for_type(&proposal_type).escalator()
     }
 
     /// Wrapper that adjusts time based on progression profile
@@ -85,25 +170,61 @@ impl ThresholdEscalator {
             return self.ceiling;
         }
 
-        let elapsed_secs = (now - start).num_seconds().max(0) as u64;
-        let adjusted_secs = match self.profile {
-            ProgressionProfile::Conservative => elapsed_secs,
-            ProgressionProfile::Aggressive => elapsed_secs * 2,
+        let elapsed = Seconds::between(start, now).max(Seconds::ZERO);
+        let adjusted = match self.profile {
+            ProgressionProfile::Conservative => elapsed,
+            ProgressionProfile::Aggressive => elapsed.saturating_mul(2),
             ProgressionProfile::Adaptive => {
                 if self.total_votes < 3 {
-                    elapsed_secs * 3
+                    elapsed.saturating_mul(3)
                 } else {
-                    elapsed_secs
+                    elapsed
                 }
             }
         };
 
-        self.current_threshold(adjusted_secs)
+        self.threshold_at(adjusted)
+    }
+
+    /// `threshold_with_profile` for a proposal opened at `start`, as of `clock`'s current time
+    pub fn threshold_now(&self, start: chrono::DateTime<chrono::Utc>, clock: &dyn Clock) -> f64 {
+        self.threshold_with_profile(clock.now(), start)
+    }
+
+    /// `threshold_with_profile` with the time spent in `paused` intervals (network-wide
+    /// vote stalls, see `liveness::LivenessMonitor`) taken off the elapsed time
+    pub fn threshold_with_pauses(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        start: chrono::DateTime<chrono::Utc>,
+        paused: &[PausedInterval],
+    ) -> f64 {
+        let paused = paused_between(paused, start, now);
+        self.threshold_with_profile(now - paused.to_duration(), start)
+    }
+
+    /// Typed form of `current_threshold`; negative spans count as zero
+    pub fn threshold_at(&self, elapsed: Seconds) -> f64 {
+        self.current_threshold(elapsed.as_u64_saturating())
     }
 
     /// Multi-dimensional threshold check: weight + vote count
     pub fn is_threshold_met(&self, vote_weight: f64, current_threshold: f64) -> bool {
-        vote_weight >= current_threshold && self.total_votes >= self.min_vote_count
+        self.decide(vote_weight, current_threshold, "", "").passed
+    }
+
+    /// `is_threshold_met` on the tally's approval weight net of opposing weight
+    pub fn is_tally_met(&self, tally: &Tally, current_threshold: f64) -> bool {
+        self.is_threshold_met(tally.net_weight(), current_threshold)
+    }
+
+    /// Like `is_threshold_met`, reporting whether the decision rule broke a tie
+    pub fn decide(&self, vote_weight: f64, current_threshold: f64, seed_block_hash: &str, proposal_id: &str) -> Decision {
+        let decision = self.decision.decide(vote_weight, current_threshold, seed_block_hash, proposal_id);
+        Decision {
+            passed: decision.passed && self.total_votes >= self.min_vote_count,
+            tied: decision.tied,
+        }
     }
 }
 
@@ -126,6 +247,7 @@ mod tests {
             profile,
             total_votes: votes,
             min_vote_count: min_votes,
+            decision: DecisionRule::default(),
         }
     }
 
@@ -207,6 +329,21 @@ mod tests {
         assert!(!esc.is_threshold_met(0.65, 0.7)); // weight < threshold
     }
 
+    #[test]
+    fn test_threshold_met_logic_fails_opposition() {
+        let esc = mock_escalator(
+            EscalationPattern::Linear(0.01),
+            ProgressionProfile::Adaptive,
+            5,
+            3,
+        );
+        let mut tally = Tally::new(2.0);
+        tally.approve(0.9);
+        assert!(esc.is_tally_met(&tally, 0.7));
+        tally.reject(0.3);
+        assert!(!esc.is_tally_met(&tally, 0.7)); // 0.9 approving - 0.3 opposing < threshold
+    }
+
     #[test]
     fn test_threshold_met_logic_fails_votes() {
         let esc = mock_escalator(
@@ -217,4 +354,26 @@ mod tests {
         );
         assert!(!esc.is_threshold_met(0.75, 0.7)); // total_votes < min_vote_count
     }
+
+    #[test]
+    fn test_tie_breaking_rules() {
+        let rule = |tie_break| DecisionRule { precision: Some(6), tie_break, ..DecisionRule::default() };
+        let (weight, threshold) = (0.7 + 1e-9, 0.7);
+
+        assert_eq!(rule(TieBreak::FailOpen).decide(weight, threshold, "", "p1"), Decision { passed: true, tied: true });
+        assert!(!rule(TieBreak::FailClosed).decide(weight, threshold, "", "p1").passed);
+        assert!(!rule(TieBreak::StatusQuo).decide(weight, threshold, "", "p1").passed);
+        assert!(!DecisionRule::default().decide(0.7 - 1e-9, threshold, "", "p1").tied);
+
+        let random = rule(TieBreak::SeededRandom);
+        let outcomes: Vec<bool> = (0..16)
+            .map(|i| random.decide(weight, threshold, &format!("{:064x}", i), "p1").passed)
+            .collect();
+        assert!(outcomes.contains(&true) && outcomes.contains(&false));
+        assert_eq!(random.decide(weight, threshold, "00ab", "p1"), random.decide(weight, threshold, "00ab", "p1"));
+
+        let mut esc = mock_escalator(EscalationPattern::Linear(0.01), ProgressionProfile::Conservative, 1, 3);
+        esc.decision = rule(TieBreak::FailOpen);
+        assert_eq!(esc.decide(weight, threshold, "", "p1"), Decision { passed: false, tied: true });
+    }
 }
@@ -0,0 +1,298 @@
+// src/tiers.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::history::{HistoryAnalyzer, RejectionQuery};
+use crate::ids::VoterId;
+use crate::trust::TrustSource;
+
+/// Named trust levels, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustTier {
+    Probation,
+    Member,
+    Trusted,
+    Guardian,
+}
+
+impl TrustTier {
+    pub fn promoted(self) -> Option<Self> {
+        match self {
+            TrustTier::Probation => Some(TrustTier::Member),
+            TrustTier::Member => Some(TrustTier::Trusted),
+            TrustTier::Trusted => Some(TrustTier::Guardian),
+            TrustTier::Guardian => None,
+        }
+    }
+
+    pub fn demoted(self) -> Option<Self> {
+        match self {
+            TrustTier::Probation => None,
+            TrustTier::Member => Some(TrustTier::Probation),
+            TrustTier::Trusted => Some(TrustTier::Member),
+            TrustTier::Guardian => Some(TrustTier::Trusted),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Privileges {
+    pub can_fast_track: bool,
+    pub can_cosign_overrides: bool,
+}
+
+/// A tier's multiplier range and what it may do
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TierSpec {
+    pub min_multiplier: f64,
+    pub max_multiplier: f64,
+    pub privileges: Privileges,
+}
+
+impl TierSpec {
+    /// Multiplier within the range, placed by `participation`
+    pub fn multiplier(&self, participation: f64) -> f64 {
+        self.min_multiplier + (self.max_multiplier - self.min_multiplier) * participation.clamp(0.0, 1.0)
+    }
+}
+
+/// A validator's behavior over one evaluation period
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Performance {
+    /// Share of open proposals the validator voted on
+    pub participation: f64,
+    pub rejected_votes: usize,
+}
+
+impl Performance {
+    /// Participation from `voted` of `open` proposals, rejections from `history` since `since`.
+    /// Rejections on practice proposals are left out; callers count only binding proposals
+    /// in `voted` and `open`.
+    pub fn observe(voter_id: &VoterId, voted: usize, open: usize, history: &HistoryAnalyzer, since: DateTime<Utc>) -> Self {
+        let query = RejectionQuery {
+            voter_id: Some(voter_id.clone()),
+            since: Some(since),
+            ..RejectionQuery::default()
+        };
+        Self {
+            participation: if open > 0 { (voted as f64 / open as f64).min(1.0) } else { 0.0 },
+            rejected_votes: history.rejections(&query).iter().filter(|r| !r.practice).count(),
+        }
+    }
+}
+
+/// Tier specs plus the promotion and demotion rules; one step per evaluation
+#[derive(Debug, Clone, PartialEq)]
+pub struct TierPolicy {
+    pub specs: BTreeMap<TrustTier, TierSpec>,
+    pub promote_participation: f64,
+    pub demote_participation: f64,
+    /// More rejected votes than this in a period demotes
+    pub max_rejected_votes: usize,
+}
+
+impl TierPolicy {
+    pub fn new() -> Self {
+        let spec = |min_multiplier, max_multiplier, can_fast_track, can_cosign_overrides| TierSpec {
+            min_multiplier,
+            max_multiplier,
+            privileges: Privileges { can_fast_track, can_cosign_overrides },
+        };
+        Self {
+            specs: BTreeMap::from([
+                (TrustTier::Probation, spec(0.5, 0.9, false, false)),
+                (TrustTier::Member, spec(1.0, 1.0, false, false)),
+                (TrustTier::Trusted, spec(1.05, 1.2, true, false)),
+                (TrustTier::Guardian, spec(1.2, 1.3, true, true)),
+            ]),
+            promote_participation: 0.8,
+            demote_participation: 0.3,
+            max_rejected_votes: 2,
+        }
+    }
+
+    pub fn spec(&self, tier: TrustTier) -> TierSpec {
+        self.specs.get(&tier).copied().unwrap_or(TierSpec {
+            min_multiplier: 1.0,
+            max_multiplier: 1.0,
+            privileges: Privileges::default(),
+        })
+    }
+
+    /// Tier earned by `performance` from `current`, with the reason when it changes
+    pub fn next_tier(&self, current: TrustTier, performance: &Performance) -> Option<(TrustTier, String)> {
+        if performance.rejected_votes > self.max_rejected_votes {
+            let reason = format!("{} rejected votes", performance.rejected_votes);
+            return current.demoted().map(|t| (t, reason));
+        }
+        if performance.participation < self.demote_participation {
+            let reason = format!("participation {:.0}%", performance.participation * 100.0);
+            return current.demoted().map(|t| (t, reason));
+        }
+        if performance.participation >= self.promote_participation && performance.rejected_votes == 0 {
+            let reason = format!("participation {:.0}% with no rejections", performance.participation * 100.0);
+            return current.promoted().map(|t| (t, reason));
+        }
+        None
+    }
+}
+
+impl Default for TierPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TierTransition {
+    pub validator_id: String,
+    pub from: Option<TrustTier>,
+    pub to: TrustTier,
+    pub reason: String,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TierState {
+    tier: TrustTier,
+    multiplier: f64,
+}
+
+/// Each validator's tier and multiplier; every change is kept as a transition
+#[derive(Debug, Clone, Default)]
+pub struct TierRegistry {
+    pub policy: TierPolicy,
+    states: BTreeMap<String, TierState>,
+    transitions: Vec<TierTransition>,
+}
+
+impl TierRegistry {
+    pub fn new(policy: TierPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Start `validator_id` on probation at the bottom of its range
+    pub fn admit(&mut self, validator_id: &str, at: DateTime<Utc>) {
+        self.assign(validator_id, TrustTier::Probation, 0.0, "admitted", at);
+    }
+
+    /// Place a validator in `tier` directly, e.g. for genesis guardians
+    pub fn set_tier(&mut self, validator_id: &str, tier: TrustTier, reason: &str, at: DateTime<Utc>) {
+        self.assign(validator_id, tier, 0.0, reason, at);
+    }
+
+    fn assign(&mut self, validator_id: &str, tier: TrustTier, participation: f64, reason: &str, at: DateTime<Utc>) {
+        let from = self.tier(validator_id);
+        let multiplier = self.policy.spec(tier).multiplier(participation);
+        self.states.insert(validator_id.to_string(), TierState { tier, multiplier });
+        if from != Some(tier) {
+            self.transitions.push(TierTransition {
+                validator_id: validator_id.to_string(),
+                from,
+                to: tier,
+                reason: reason.to_string(),
+                at,
+            });
+        }
+    }
+
+    /// Apply one period's performance: promote or demote a step, then place the
+    /// multiplier within the tier's range. Unknown validators are admitted first.
+    pub fn evaluate(&mut self, validator_id: &str, performance: &Performance, at: DateTime<Utc>) -> Option<&TierTransition> {
+        if self.tier(validator_id).is_none() {
+            self.admit(validator_id, at);
+        }
+        let current = self.tier(validator_id).expect("admitted above");
+        let logged = self.transitions.len();
+        match self.policy.next_tier(current, performance) {
+            Some((tier, reason)) => self.assign(validator_id, tier, performance.participation, &reason, at),
+            None => self.assign(validator_id, current, performance.participation, "", at),
+        }
+        self.transitions[logged..].last()
+    }
+
+    pub fn tier(&self, validator_id: &str) -> Option<TrustTier> {
+        self.states.get(validator_id).map(|s| s.tier)
+    }
+
+    pub fn privileges(&self, validator_id: &str) -> Privileges {
+        self.tier(validator_id).map(|t| self.policy.spec(t).privileges).unwrap_or_default()
+    }
+
+    pub fn transitions(&self) -> &[TierTransition] {
+        &self.transitions
+    }
+}
+
+impl TrustSource for TierRegistry {
+    fn get_bonus(&self, validator_id: &VoterId) -> f64 {
+        self.states.get(validator_id.as_str()).map_or(1.0, |s| s.multiplier)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::RejectedVote;
+    use crate::vote::{DecayType, SignedVote};
+    use chrono::Duration;
+
+    fn performance(participation: f64, rejected_votes: usize) -> Performance {
+        Performance { participation, rejected_votes }
+    }
+
+    #[test]
+    fn test_promotion_and_demotion_are_logged() {
+        let now = Utc::now();
+        let mut tiers = TierRegistry::new(TierPolicy::default());
+        tiers.admit("alice", now);
+        assert_eq!(tiers.get_bonus(&"alice".parse().unwrap()), 0.5);
+        assert_eq!(tiers.privileges("alice"), Privileges::default());
+
+        for day in 1..=3 {
+            tiers.evaluate("alice", &performance(1.0, 0), now + Duration::days(day));
+        }
+        assert_eq!(tiers.tier("alice"), Some(TrustTier::Guardian));
+        assert_eq!(tiers.get_bonus(&"alice".parse().unwrap()), 1.3);
+        assert!(tiers.privileges("alice").can_cosign_overrides);
+
+        // Steady middling participation keeps the tier but moves within its range
+        assert!(tiers.evaluate("alice", &performance(0.5, 0), now + Duration::days(4)).is_none());
+        assert!((tiers.get_bonus(&"alice".parse().unwrap()) - 1.25).abs() < 1e-9);
+
+        let demotion = tiers.evaluate("alice", &performance(0.9, 3), now + Duration::days(5)).unwrap().clone();
+        assert_eq!((demotion.from, demotion.to), (Some(TrustTier::Guardian), TrustTier::Trusted));
+        assert_eq!(demotion.reason, "3 rejected votes");
+        assert!(tiers.privileges("alice").can_fast_track);
+        assert!(!tiers.privileges("alice").can_cosign_overrides);
+        assert_eq!(tiers.transitions().len(), 5);
+        assert_eq!(tiers.get_bonus(&"unknown".parse().unwrap()), 1.0);
+    }
+
+    #[test]
+    fn test_performance_from_history() {
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let vote = SignedVote::new("bob".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key);
+        let mut history = HistoryAnalyzer::default();
+        history.record_rejection(RejectedVote::new(&vote, "expired", "gossip", now));
+        history.record_rejection(RejectedVote::new(&vote, "expired", "gossip", now - Duration::days(10)));
+
+        let observed = Performance::observe(&"bob".parse().unwrap(), 3, 4, &history, now - Duration::days(1));
+        assert_eq!(observed, performance(0.75, 1));
+
+        let mut tiers = TierRegistry::new(TierPolicy::default());
+        tiers.set_tier("bob", TrustTier::Member, "genesis", now);
+        assert!(tiers.evaluate("bob", &performance(0.1, 0), now).is_some());
+        assert_eq!(tiers.tier("bob"), Some(TrustTier::Probation));
+        assert!(tiers.evaluate("bob", &performance(0.1, 0), now).is_none());
+    }
+}
@@ -1,24 +1,43 @@
 use std::collections::HashMap;
 
+use crate::ids::VoterId;
+
+/// Anything that can supply a per-validator weight multiplier
+pub trait TrustSource: Send + Sync {
+    fn get_bonus(&self, validator_id: &VoterId) -> f64;
+}
+
 pub struct TrustEngine {
-    trusted_validators: HashMap<String, f64>, // validator_id -> bonus multiplier
+    trusted_validators: HashMap<VoterId, f64>, // validator_id -> bonus multiplier
+}
+
+impl Default for TrustEngine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TrustEngine {
     pub fn new() -> Self {
         let mut trusted = HashMap::new();
-        trusted.insert("validator_001".to_string(), 1.2); // +20%
-        trusted.insert("validator_002".to_string(), 1.1); // +10%
+        trusted.insert(VoterId::new("validator_001").expect("valid id"), 1.2); // +20%
+        trusted.insert(VoterId::new("validator_002").expect("valid id"), 1.1); // +10%
         Self {
             trusted_validators: trusted,
         }
     }
 
-    pub fn get_bonus(&self, validator_id: &str) -> f64 {
+    pub fn get_bonus(&self, validator_id: &VoterId) -> f64 {
         self.trusted_validators.get(validator_id).cloned().unwrap_or(1.0)
     }
 }
 
+impl TrustSource for TrustEngine {
+    fn get_bonus(&self, validator_id: &VoterId) -> f64 {
+        TrustEngine::get_bonus(self, validator_id)
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -28,16 +47,16 @@ mod tests {
     fn test_trusted_validator_bonus() {
         let engine = TrustEngine::new();
 
-        assert_eq!(engine.get_bonus("validator_001"), 1.2);
-        assert_eq!(engine.get_bonus("validator_002"), 1.1);
+        assert_eq!(engine.get_bonus(&"validator_001".parse().unwrap()), 1.2);
+        assert_eq!(engine.get_bonus(&"validator_002".parse().unwrap()), 1.1);
     }
 
     #[test]
     fn test_untrusted_validator_bonus() {
         let engine = TrustEngine::new();
 
-        assert_eq!(engine.get_bonus("unknown_validator"), 1.0);
-        assert_eq!(engine.get_bonus(""), 1.0);
+        assert_eq!(engine.get_bonus(&"unknown_validator".parse().unwrap()), 1.0);
+        assert!(VoterId::new("").is_err());
     }
 
     #[test]
@@ -45,7 +64,7 @@ mod tests {
         let engine = TrustEngine::new();
 
         // Should be case-sensitive by default
-        assert_eq!(engine.get_bonus("VALIDATOR_001"), 1.0);
-        assert_eq!(engine.get_bonus("Validator_001"), 1.0);
+        assert_eq!(engine.get_bonus(&"VALIDATOR_001".parse().unwrap()), 1.0);
+        assert_eq!(engine.get_bonus(&"Validator_001".parse().unwrap()), 1.0);
     }
 }
\ No newline at end of file
@@ -0,0 +1,208 @@
+// src/trust_audit.rs
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::ids::ProposalId;
+use crate::proposal::ProposalRegistry;
+use crate::snapshot::{SnapshotDiff, TallySnapshot};
+use crate::threshold::ThresholdEscalator;
+use crate::trust::TrustSource;
+use crate::vote::SignedVote;
+
+/// Margin changes smaller than this are treated as no change
+const MARGIN_EPSILON: f64 = 1e-9;
+
+/// What switching trust scores would do to an open proposal, worst first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutcomeRisk {
+    /// The proposal would change from passing to failing or back
+    Flips,
+    /// Same outcome, but closer to the threshold
+    Narrows,
+    Holds,
+}
+
+/// One open proposal tallied under the current and the proposed trust scores at the same moment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProposalAudit {
+    pub risk: OutcomeRisk,
+    /// From the current tally to the proposed one; weight changes carry `TrustChanged`
+    pub diff: SnapshotDiff,
+}
+
+impl ProposalAudit {
+    pub fn compare(
+        proposal_id: &ProposalId,
+        votes: &[SignedVote],
+        escalator: &ThresholdEscalator,
+        opened_at: DateTime<Utc>,
+        at: DateTime<Utc>,
+        current: &dyn TrustSource,
+        proposed: &dyn TrustSource,
+    ) -> Self {
+        let before = TallySnapshot::capture(proposal_id, votes, escalator, opened_at, at, current);
+        let after = TallySnapshot::capture(proposal_id, votes, escalator, opened_at, at, proposed);
+        let diff = SnapshotDiff::between(&before, &after);
+        let (from, to) = margins(&diff);
+        let risk = if diff.outcome_changed() {
+            OutcomeRisk::Flips
+        } else if to.abs() < from.abs() - MARGIN_EPSILON {
+            OutcomeRisk::Narrows
+        } else {
+            OutcomeRisk::Holds
+        };
+        Self { risk, diff }
+    }
+
+    /// Decided weight minus threshold, under the current and the proposed scores
+    pub fn margins(&self) -> (f64, f64) {
+        margins(&self.diff)
+    }
+}
+
+fn margins(diff: &SnapshotDiff) -> (f64, f64) {
+    (diff.decided_weight.0 - diff.threshold.0, diff.decided_weight.1 - diff.threshold.1)
+}
+
+/// Outcome risk of replacing the current trust scores, e.g. with a recalculation or an
+/// import, across every open proposal; run it before committing the new scores
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrustAudit {
+    pub at: DateTime<Utc>,
+    /// Riskiest first, then by how far the margin moved
+    pub proposals: Vec<ProposalAudit>,
+}
+
+impl TrustAudit {
+    pub fn new(at: DateTime<Utc>, mut proposals: Vec<ProposalAudit>) -> Self {
+        let shift = |p: &ProposalAudit| {
+            let (from, to) = p.margins();
+            (to - from).abs()
+        };
+        proposals.sort_by(|a, b| a.risk.cmp(&b.risk).then(shift(b).total_cmp(&shift(a))));
+        Self { at, proposals }
+    }
+
+    /// Audit every binding proposal `registry` has open at `at`; practice proposals bind
+    /// no one, so they are left out
+    pub fn of_registry(
+        registry: &ProposalRegistry,
+        votes: &[SignedVote],
+        current: &dyn TrustSource,
+        proposed: &dyn TrustSource,
+        at: DateTime<Utc>,
+    ) -> Self {
+        let proposals = registry
+            .open_at(at)
+            .into_iter()
+            .filter(|p| !p.practice)
+            .map(|p| ProposalAudit::compare(&p.id, votes, &p.escalator(), p.opens_at, at, current, proposed))
+            .collect();
+        Self::new(at, proposals)
+    }
+
+    pub fn flips(&self) -> impl Iterator<Item = &ProposalAudit> {
+        self.proposals.iter().filter(|p| p.risk == OutcomeRisk::Flips)
+    }
+
+    /// No open proposal would change outcome
+    pub fn is_safe(&self) -> bool {
+        self.flips().next().is_none()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("trust audits serialize")
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let flips = self.flips().count();
+        let _ = writeln!(
+            out,
+            "⚖️ Trust audit at {}: {} open proposal(s), {} would flip",
+            self.at.to_rfc3339(),
+            self.proposals.len(),
+            flips
+        );
+        let verdict = |passing: bool| if passing { "passing" } else { "failing" };
+        for audit in &self.proposals {
+            let diff = &audit.diff;
+            let (from, to) = audit.margins();
+            let _ = writeln!(
+                out,
+                "  {:<8} {} {} -> {}, weight {:.4} -> {:.4} vs {:.4}, margin {:+.4} -> {:+.4}",
+                format!("{:?}", audit.risk),
+                diff.proposal_id,
+                verdict(diff.passing.0),
+                verdict(diff.passing.1),
+                diff.decided_weight.0,
+                diff.decided_weight.1,
+                diff.threshold.1,
+                from,
+                to
+            );
+            if audit.risk != OutcomeRisk::Holds {
+                for change in &diff.changed {
+                    let _ = writeln!(out, "    ~ {} {:.4} -> {:.4}", change.voter_id, change.from, change.to);
+                }
+            }
+        }
+        out
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::policy::ProposalPolicy;
+    use crate::proposal::Proposal;
+    use crate::reputation::ReputationBook;
+    use crate::trust::TrustEngine;
+    use crate::vote::{DecayType, ProposalType, VoteChoice};
+
+    #[test]
+    fn test_audit_flags_flips_before_scores_change() {
+        let key = SignedVote::generate_keypair();
+        let opened = Utc::now() - Duration::seconds(60);
+        let at = opened + Duration::seconds(30);
+        let mut registry = ProposalRegistry::new();
+        for id in ["close", "safe"] {
+            let window = ProposalPolicy::for_type(&ProposalType::Normal).voting_window(opened, 10);
+            let proposal = Proposal::new(id.parse().unwrap(), "Adjust fees", ProposalType::Normal, &window, "carol".parse().unwrap(), &key);
+            registry.register(proposal).unwrap();
+        }
+        let cast = |voter: &str, proposal: &str, weight: f64, choice| {
+            SignedVote::new_with_choice(voter.parse().unwrap(), proposal.parse().unwrap(), weight, opened, DecayType::Linear, choice, &key)
+        };
+        let votes = vec![
+            cast("validator_001", "close", 1.0, VoteChoice::Approve),
+            cast("dave", "close", 0.3, VoteChoice::Approve),
+            cast("bob", "close", 0.4, VoteChoice::Reject),
+            cast("carol", "safe", 3.0, VoteChoice::Approve),
+            cast("dave", "safe", 1.0, VoteChoice::Approve),
+            cast("erin", "safe", 1.0, VoteChoice::Approve),
+        ];
+
+        // Dropping validator_001's bonus from x1.2 to x0.5 sinks "close"
+        let current = TrustEngine::new();
+        let mut proposed = ReputationBook::new("recalculated");
+        proposed.set("validator_001", 0.5, at).unwrap();
+        let audit = TrustAudit::of_registry(&registry, &votes, &current, &proposed, at);
+
+        assert!(!audit.is_safe());
+        let ids: Vec<(&str, OutcomeRisk)> = audit.proposals.iter().map(|p| (p.diff.proposal_id.as_str(), p.risk)).collect();
+        assert_eq!(ids, vec![("close", OutcomeRisk::Flips), ("safe", OutcomeRisk::Holds)]);
+        assert_eq!(audit.proposals[0].diff.passing, (true, false));
+        assert!(audit.render_text().contains("~ validator_001"));
+
+        let unchanged = TrustAudit::of_registry(&registry, &votes, &current, &current, at);
+        assert!(unchanged.is_safe() && unchanged.proposals.iter().all(|p| p.risk == OutcomeRisk::Holds));
+    }
+}
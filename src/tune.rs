@@ -0,0 +1,308 @@
+// src/tune.rs
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::decay::{DecayModel, ExponentialDecay, LinearDecay};
+use crate::guardrails::Guardrails;
+use crate::plugins::{PluginConfig, PluginRef};
+use crate::tally::Tally;
+use crate::threshold::{EscalationPattern, ThresholdEscalator};
+use crate::units::Seconds;
+use crate::vote::{ProposalType, VoteChoice};
+
+/// Window lengths considered, shortest first
+const WINDOWS: [u64; 10] = [60, 300, 900, 1_800, 3_600, 4 * 3_600, 12 * 3_600, 86_400, 3 * 86_400, 7 * 86_400];
+/// Thresholds the escalation may reach by the close
+const CEILINGS: [f64; 2] = [0.67, 0.8];
+/// Quorums, as shares of the voters expected to turn out
+const QUORUM_SHARES: [f64; 2] = [0.25, 0.5];
+/// Evenly spaced points in the window at which a trial checks whether it has passed
+const CHECKPOINTS: u64 = 12;
+/// Honest approval of a proposal that should pass, and of one only an attacker can pass
+const SUPPORTED_SHARE: f64 = 0.75;
+const CONTESTED_SHARE: f64 = 0.4;
+
+/// What an operator wants from a configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TuneGoals {
+    /// Expected size of the electorate
+    pub voters: usize,
+    /// Share of the electorate expected to vote at all
+    pub turnout: f64,
+    /// How much of its weight a vote cast at the opening may lose by the close, relative
+    /// to a last-minute vote: 0 keeps every vote equal, 0.5 lets late votes count double
+    pub late_vote_tolerance: f64,
+    /// Voters respond within this many seconds, and no recommended window is longer
+    pub target_latency_secs: u64,
+    /// Weight one attacker controls, as a share of the whole electorate's
+    pub attack_budget: f64,
+    pub trials: usize,
+    pub seed: u64,
+}
+
+impl Default for TuneGoals {
+    fn default() -> Self {
+        Self {
+            voters: 100,
+            turnout: 0.7,
+            late_vote_tolerance: 0.5,
+            target_latency_secs: 86_400,
+            attack_budget: 0.1,
+            trials: 100,
+            seed: 0,
+        }
+    }
+}
+
+/// The best configuration found for a set of goals, and how it fared in simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuneReport {
+    pub goals: TuneGoals,
+    pub config: PluginConfig,
+    /// Share of trials in which a proposal with honest support passed
+    pub pass_rate: f64,
+    /// Share of trials in which a proposal honest voters oppose passed, whether by the
+    /// attacker's weight or by chance in a small early quorum
+    pub attack_rate: f64,
+    /// Mean time for a supported proposal to be decided; failures count the whole window
+    pub mean_decision_secs: f64,
+    pub candidates: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DecayCandidate {
+    Linear(f64),
+    Exponential(f64),
+}
+
+impl DecayCandidate {
+    /// The candidates at which a vote cast at the opening keeps `retained` of its
+    /// weight by the close of `window` seconds
+    fn retaining(retained: f64, window: u64, guardrails: &Guardrails) -> [Self; 2] {
+        let bound = |rate: f64| rate.clamp(guardrails.min_decay_rate, guardrails.max_decay_rate);
+        let window = window as f64;
+        [
+            DecayCandidate::Exponential(bound(-retained.ln() / window)),
+            DecayCandidate::Linear(bound((1.0 - retained) / window)),
+        ]
+    }
+
+    fn weight(&self, original: f64, elapsed: f64) -> f64 {
+        match *self {
+            DecayCandidate::Linear(rate) => LinearDecay { rate }.compute_weight(original, elapsed),
+            DecayCandidate::Exponential(rate) => ExponentialDecay { rate }.compute_weight(original, elapsed),
+        }
+    }
+
+    fn plugin(&self) -> PluginRef {
+        let (name, rate) = match *self {
+            DecayCandidate::Linear(rate) => ("linear", rate),
+            DecayCandidate::Exponential(rate) => ("exponential", rate),
+        };
+        PluginRef { name: name.to_string(), params: json!({ "rate": rate }) }
+    }
+}
+
+/// An escalation curve that reaches `ceiling` by the close of `window` seconds
+fn escalations(base: f64, ceiling: f64, window: u64) -> [(EscalationPattern, PluginRef); 2] {
+    let window = window as f64;
+    let rate = (ceiling - base) / window;
+    let (k, midpoint) = (10.0 / window, window / 2.0);
+    [
+        (EscalationPattern::Linear(rate), PluginRef { name: "linear".to_string(), params: json!({ "rate": rate }) }),
+        (
+            EscalationPattern::Sigmoid(k, midpoint),
+            PluginRef { name: "sigmoid".to_string(), params: json!({ "k": k, "midpoint": midpoint }) },
+        ),
+    ]
+}
+
+/// One voter's arrival and how they vote on the supported and contested proposals
+struct Arrival {
+    offset: f64,
+    supports: bool,
+    contests: bool,
+}
+
+/// Tallies at every checkpoint for the supported proposal and for the contested one
+/// with the attacker voting at the opening or at the close
+type CheckpointTallies = Vec<[Tally; 3]>;
+
+fn checkpoint_tallies(arrivals: &[Arrival], window: u64, decay: DecayCandidate, attacker_weight: f64) -> CheckpointTallies {
+    (1..=CHECKPOINTS)
+        .map(|k| {
+            let at = (window * k / CHECKPOINTS) as f64;
+            let mut tallies = [Tally::default(); 3];
+            for arrival in arrivals.iter().take_while(|a| a.offset <= at) {
+                let weight = decay.weight(1.0, at - arrival.offset);
+                tallies[0].record(vote_choice(arrival.supports), weight);
+                tallies[1].record(vote_choice(arrival.contests), weight);
+                tallies[2].record(vote_choice(arrival.contests), weight);
+            }
+            if attacker_weight > 0.0 {
+                tallies[1].approve(decay.weight(attacker_weight, at));
+                if k == CHECKPOINTS {
+                    tallies[2].approve(attacker_weight);
+                }
+            }
+            tallies
+        })
+        .collect()
+}
+
+fn vote_choice(approves: bool) -> VoteChoice {
+    if approves { VoteChoice::Approve } else { VoteChoice::Reject }
+}
+
+/// When, if ever, `tallies` first meet `escalator`'s threshold on the approving share
+fn decided_at(escalator: &mut ThresholdEscalator, window: u64, tallies: &[Tally]) -> Option<u64> {
+    tallies.iter().zip(1..=CHECKPOINTS).find_map(|(tally, k)| {
+        let at = window * k / CHECKPOINTS;
+        escalator.total_votes = tally.votes;
+        let threshold = escalator.threshold_at(Seconds::new(at as i64));
+        escalator.is_threshold_met(tally.approval_fraction(), threshold).then_some(at)
+    })
+}
+
+/// Recommend decay, escalation, window, and quorum for `goals` by running every
+/// candidate against the same seeded trials. Voters arrive evenly over the target
+/// latency; each trial asks whether a proposal with honest support passes, and whether
+/// an attacker spending the whole budget at the opening or at the close can pass one
+/// honest voters oppose. Thresholds are met by the approving share of decayed weight.
+/// Candidates score their pass rate less twice their attack rate, with a small
+/// penalty for slow decisions; ties go to the shorter window and stronger decay.
+pub fn tune(goals: &TuneGoals) -> TuneReport {
+    let guardrails = Guardrails::default();
+    let mut rng = StdRng::seed_from_u64(goals.seed);
+    let latency = goals.target_latency_secs.max(1);
+    let turnout = goals.turnout.clamp(0.0, 1.0);
+    let trials: Vec<Vec<Arrival>> = (0..goals.trials)
+        .map(|_| {
+            let mut arrivals = Vec::new();
+            for _ in 0..goals.voters {
+                if rng.gen_bool(turnout) {
+                    arrivals.push(Arrival {
+                        offset: rng.gen_range(0.0..latency as f64),
+                        supports: rng.gen_bool(SUPPORTED_SHARE),
+                        contests: rng.gen_bool(CONTESTED_SHARE),
+                    });
+                }
+            }
+            arrivals.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+            arrivals
+        })
+        .collect();
+
+    let mut windows: Vec<u64> = WINDOWS
+        .into_iter()
+        .filter(|w| *w <= latency && (guardrails.min_window_secs..=guardrails.max_window_secs).contains(w))
+        .collect();
+    if windows.is_empty() {
+        windows.push(guardrails.min_window_secs);
+    }
+    let tolerance = goals.late_vote_tolerance.clamp(0.0, 0.9);
+    let retained = [1.0 - tolerance, 1.0 - tolerance / 2.0];
+    let attacker_weight = goals.attack_budget.max(0.0) * goals.voters as f64;
+    let expected = goals.voters as f64 * turnout;
+    let mut escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+    let base = escalator.base_threshold;
+    let rate = |n: usize| if trials.is_empty() { 0.0 } else { n as f64 / trials.len() as f64 };
+
+    let mut best: Option<(f64, TuneReport)> = None;
+    let mut candidates = 0;
+    for &window in &windows {
+        for decay in retained.iter().flat_map(|r| DecayCandidate::retaining(*r, window, &guardrails)) {
+            let tallies: Vec<CheckpointTallies> =
+                trials.iter().map(|t| checkpoint_tallies(t, window, decay, attacker_weight)).collect();
+            let scenario = |trial: &CheckpointTallies, i: usize| trial.iter().map(|t| t[i]).collect::<Vec<_>>();
+
+            for ceiling in CEILINGS {
+                for (pattern, escalation) in escalations(base, ceiling, window) {
+                    for share in QUORUM_SHARES {
+                        candidates += 1;
+                        let quorum = ((expected * share).ceil() as usize).max(1);
+                        escalator.pattern = pattern.clone();
+                        escalator.ceiling = ceiling;
+                        escalator.min_vote_count = quorum;
+
+                        let (mut passes, mut attacks, mut decision_secs) = (0, 0, 0);
+                        for trial in &tallies {
+                            match decided_at(&mut escalator, window, &scenario(trial, 0)) {
+                                Some(at) => {
+                                    passes += 1;
+                                    decision_secs += at;
+                                }
+                                None => decision_secs += window,
+                            }
+                            let attacked = (1..3).any(|i| decided_at(&mut escalator, window, &scenario(trial, i)).is_some());
+                            attacks += usize::from(attacked);
+                        }
+
+                        let mean_decision_secs = if tallies.is_empty() { 0.0 } else { decision_secs as f64 / tallies.len() as f64 };
+                        let score = rate(passes) - 2.0 * rate(attacks) - 0.1 * mean_decision_secs / latency as f64;
+                        if best.as_ref().is_some_and(|(s, _)| *s >= score) {
+                            continue;
+                        }
+                        let config = PluginConfig {
+                            decay: decay.plugin(),
+                            escalation: escalation.clone(),
+                            trust: PluginRef { name: "default".to_string(), params: json!({}) },
+                            window_secs: Some(window),
+                            base_threshold: Some(base),
+                            ceiling: Some(ceiling),
+                            min_vote_count: Some(quorum),
+                        };
+                        let report = TuneReport {
+                            goals: goals.clone(),
+                            config,
+                            pass_rate: rate(passes),
+                            attack_rate: rate(attacks),
+                            mean_decision_secs,
+                            candidates: 0,
+                        };
+                        best = Some((score, report));
+                    }
+                }
+            }
+        }
+    }
+
+    let (_, mut report) = best.expect("there is always at least one window and decay candidate");
+    report.candidates = candidates;
+    report
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tune_recommends_a_config_within_guardrails() {
+        let goals = TuneGoals { voters: 40, target_latency_secs: 3_600, trials: 30, ..TuneGoals::default() };
+        let report = tune(&goals);
+        assert_eq!(report.candidates, 5 * 4 * 2 * 2 * 2);
+        assert_eq!(Guardrails::default().check(&report.config), Ok(()));
+        assert!(report.config.window_secs.unwrap() <= 3_600);
+        assert!(report.config.min_vote_count.unwrap() >= 1);
+        assert!(report.pass_rate > report.attack_rate);
+
+        // Seeded: the same goals recommend the same config
+        assert_eq!(tune(&goals).config, report.config);
+        let json = serde_json::to_string(&report.config).unwrap();
+        assert_eq!(serde_json::from_str::<PluginConfig>(&json).unwrap(), report.config);
+    }
+
+    #[test]
+    fn test_larger_attack_budget_tightens_the_config() {
+        let goals = TuneGoals { voters: 40, target_latency_secs: 3_600, trials: 30, ..TuneGoals::default() };
+        let calm = tune(&TuneGoals { attack_budget: 0.0, ..goals.clone() });
+        let hostile = tune(&TuneGoals { attack_budget: 0.3, ..goals });
+        assert!(calm.pass_rate >= hostile.pass_rate);
+        let threshold = |r: &TuneReport| (r.config.ceiling.unwrap(), r.config.min_vote_count.unwrap());
+        assert!(threshold(&hostile) >= threshold(&calm));
+    }
+}
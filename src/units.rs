@@ -0,0 +1,196 @@
+// src/units.rs
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Sub};
+
+use chrono::{DateTime, Duration, Utc};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum UnitError {
+    #[error("Weight must be finite and non-negative, got {0}")]
+    InvalidWeight(f64),
+    #[error("Seconds out of range: {0}")]
+    SecondsOutOfRange(String),
+}
+
+/// A signed span of whole seconds; negative when an instant lies in the future
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Seconds(i64);
+
+impl Seconds {
+    pub const ZERO: Seconds = Seconds(0);
+
+    pub const fn new(secs: i64) -> Self {
+        Seconds(secs)
+    }
+
+    /// Whole seconds from `earlier` to `later`
+    pub fn between(earlier: DateTime<Utc>, later: DateTime<Utc>) -> Self {
+        Seconds((later - earlier).num_seconds())
+    }
+
+    pub fn from_duration(duration: Duration) -> Self {
+        Seconds(duration.num_seconds())
+    }
+
+    pub fn to_duration(self) -> Duration {
+        Duration::try_seconds(self.0).unwrap_or(if self.0 < 0 { Duration::MIN } else { Duration::MAX })
+    }
+
+    pub const fn as_i64(self) -> i64 {
+        self.0
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+
+    /// Negative spans clamp to zero
+    pub fn as_u64_saturating(self) -> u64 {
+        self.0.max(0) as u64
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn checked_add(self, other: Seconds) -> Option<Seconds> {
+        self.0.checked_add(other.0).map(Seconds)
+    }
+
+    pub fn checked_sub(self, other: Seconds) -> Option<Seconds> {
+        self.0.checked_sub(other.0).map(Seconds)
+    }
+
+    pub fn saturating_mul(self, factor: i64) -> Seconds {
+        Seconds(self.0.saturating_mul(factor))
+    }
+}
+
+impl TryFrom<u64> for Seconds {
+    type Error = UnitError;
+
+    fn try_from(secs: u64) -> Result<Self, Self::Error> {
+        i64::try_from(secs)
+            .map(Seconds)
+            .map_err(|_| UnitError::SecondsOutOfRange(secs.to_string()))
+    }
+}
+
+/// Saturates instead of overflowing
+impl Add for Seconds {
+    type Output = Seconds;
+
+    fn add(self, other: Seconds) -> Seconds {
+        Seconds(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for Seconds {
+    type Output = Seconds;
+
+    fn sub(self, other: Seconds) -> Seconds {
+        Seconds(self.0.saturating_sub(other.0))
+    }
+}
+
+impl fmt::Display for Seconds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.0)
+    }
+}
+
+/// A vote weight: always finite and non-negative
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Weight(f64);
+
+impl Weight {
+    pub const ZERO: Weight = Weight(0.0);
+
+    pub fn new(value: f64) -> Result<Self, UnitError> {
+        if value.is_finite() && value >= 0.0 {
+            Ok(Weight(value))
+        } else {
+            Err(UnitError::InvalidWeight(value))
+        }
+    }
+
+    /// Clamp arbitrary input into a valid weight; NaN becomes zero
+    pub fn saturating(value: f64) -> Self {
+        if value.is_nan() {
+            Weight::ZERO
+        } else {
+            Weight(value.clamp(0.0, f64::MAX))
+        }
+    }
+
+    pub const fn get(self) -> f64 {
+        self.0
+    }
+
+    /// Multiply by a factor such as a trust bonus or decay multiplier
+    pub fn scale(self, factor: f64) -> Weight {
+        Weight::saturating(self.0 * factor)
+    }
+}
+
+impl TryFrom<f64> for Weight {
+    type Error = UnitError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Weight::new(value)
+    }
+}
+
+impl Add for Weight {
+    type Output = Weight;
+
+    fn add(self, other: Weight) -> Weight {
+        Weight::saturating(self.0 + other.0)
+    }
+}
+
+impl Sum for Weight {
+    fn sum<I: Iterator<Item = Weight>>(iter: I) -> Weight {
+        iter.fold(Weight::ZERO, Add::add)
+    }
+}
+
+impl fmt::Display for Weight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.4}", self.0)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seconds_conversions() {
+        let start = Utc::now();
+        assert_eq!(Seconds::between(start, start + Duration::seconds(90)), Seconds::new(90));
+        assert!(Seconds::between(start + Duration::seconds(5), start).is_negative());
+        assert_eq!(Seconds::new(-3).as_u64_saturating(), 0);
+        assert_eq!(Seconds::try_from(u64::MAX), Err(UnitError::SecondsOutOfRange(u64::MAX.to_string())));
+        assert_eq!(Seconds::new(i64::MAX).checked_add(Seconds::new(1)), None);
+        assert_eq!(Seconds::new(i64::MAX) + Seconds::new(1), Seconds::new(i64::MAX));
+        assert_eq!(Seconds::new(i64::MAX).to_duration(), Duration::MAX);
+        assert_eq!(Seconds::new(30).to_duration(), Duration::seconds(30));
+    }
+
+    #[test]
+    fn test_weight_invariants() {
+        assert!(Weight::new(-0.1).is_err());
+        assert!(Weight::new(f64::NAN).is_err());
+        assert!(Weight::new(f64::INFINITY).is_err());
+        assert_eq!(Weight::saturating(-2.0), Weight::ZERO);
+        assert_eq!(Weight::new(2.0).unwrap().scale(0.5).get(), 1.0);
+
+        let total: Weight = [0.5, 0.25].into_iter().map(|w| Weight::new(w).unwrap()).sum();
+        assert_eq!(total.get(), 0.75);
+    }
+}
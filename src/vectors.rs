@@ -0,0 +1,364 @@
+// src/vectors.rs
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use ed25519_dalek::{Signer, SigningKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::decay::DecayParams;
+use crate::payload::{PayloadTime, VotePayload};
+use crate::threshold::ThresholdEscalator;
+use crate::vote::{DecayType, ProposalType, SignedVote, VoteChoice, vote_message};
+use crate::weight_engine::WeightEngine;
+
+/// Bumped whenever the layout or the expected values change
+pub const VECTORS_VERSION: u32 = 3;
+
+const DECAY_ELAPSED_SECS: [i64; 6] = [0, 30, 60, 180, 300, 1200];
+const THRESHOLD_ELAPSED_SECS: [i64; 5] = [0, 10, 30, 60, 600];
+
+/// Signed vote with every intermediate value an implementation needs to reproduce it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VoteVector {
+    pub secret_key: String,
+    pub public_key: String,
+    pub voter_id: String,
+    pub proposal_id: String,
+    pub timestamp: String,
+    pub payload: String,
+    pub signature: String,
+}
+
+/// Every payload field with its canonical encoding (hex) and signature, so other
+/// languages can check their encoder byte for byte
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PayloadVector {
+    pub secret_key: String,
+    pub voter_id: String,
+    pub proposal_id: String,
+    pub timestamp: PayloadTime,
+    pub choice: VoteChoice,
+    pub version: u64,
+    pub nonce: u64,
+    pub metadata: BTreeMap<String, String>,
+    pub stake_signature: Option<String>,
+    pub expires_at: Option<PayloadTime>,
+    #[serde(default)]
+    pub decay_params: Option<DecayParams>,
+    #[serde(default = "crate::payload::legacy_payload_version")]
+    pub payload_version: u32,
+    /// Set from payload version 2 on, along with `decay_model`
+    #[serde(default)]
+    pub original_weight: Option<f64>,
+    #[serde(default)]
+    pub decay_model: Option<String>,
+    pub payload: String,
+    pub signature: String,
+}
+
+impl PayloadVector {
+    /// The payload these fields describe, or `None` if the stake signature isn't 64 hex
+    /// bytes or a version 2 payload lacks a weight or known decay model
+    pub fn to_payload(&self) -> Option<VotePayload> {
+        let stake_signature = match &self.stake_signature {
+            Some(hex) => Some(hex::decode(hex).ok()?.try_into().ok()?),
+            None => None,
+        };
+        let payload = VotePayload {
+            choice: self.choice,
+            version: self.version,
+            nonce: self.nonce,
+            metadata: self.metadata.clone(),
+            stake_signature,
+            expires_at: self.expires_at,
+            decay_params: self.decay_params.clone(),
+            ..VotePayload::new(&self.voter_id, &self.proposal_id, self.timestamp)
+        };
+        if self.payload_version == crate::payload::LEGACY_PAYLOAD_VERSION {
+            return Some(payload);
+        }
+        let decay_model = decay_from_name(self.decay_model.as_deref()?)?;
+        Some(VotePayload { payload_version: self.payload_version, ..payload.covering(self.original_weight?, decay_model) })
+    }
+}
+
+/// Expected decayed weight of a vote `elapsed_secs` after it was cast
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DecayVector {
+    pub decay_model: String,
+    pub original_weight: f64,
+    pub elapsed_secs: i64,
+    pub expected_weight: f64,
+}
+
+/// Expected escalated threshold `elapsed_secs` after voting opened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ThresholdVector {
+    pub proposal_type: String,
+    pub elapsed_secs: i64,
+    pub expected_threshold: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TestVectors {
+    pub version: u32,
+    pub votes: Vec<VoteVector>,
+    #[serde(default)]
+    pub payloads: Vec<PayloadVector>,
+    pub decay: Vec<DecayVector>,
+    pub thresholds: Vec<ThresholdVector>,
+}
+
+/// Reference epoch every vector timestamp is derived from
+pub fn vector_epoch() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()
+}
+
+/// Deterministic signing key for vector number `index`
+pub fn vector_key(index: u32) -> SigningKey {
+    let seed: [u8; 32] = Sha256::digest(format!("tdc-vector-key-{}", index)).into();
+    SigningKey::from_bytes(&seed)
+}
+
+pub fn decay_name(decay: DecayType) -> &'static str {
+    match decay {
+        DecayType::Linear => "linear",
+        DecayType::Exponential => "exponential",
+        DecayType::Stepped => "stepped",
+    }
+}
+
+pub fn proposal_type_name(proposal_type: &ProposalType) -> &'static str {
+    match proposal_type {
+        ProposalType::Normal => "normal",
+        ProposalType::Critical => "critical",
+        ProposalType::Constitutional => "constitutional",
+        ProposalType::Budget => "budget",
+        ProposalType::Emergency => "emergency",
+    }
+}
+
+pub fn decay_from_name(name: &str) -> Option<DecayType> {
+    match name {
+        "linear" => Some(DecayType::Linear),
+        "exponential" => Some(DecayType::Exponential),
+        "stepped" => Some(DecayType::Stepped),
+        _ => None,
+    }
+}
+
+pub fn proposal_type_from_name(name: &str) -> Option<ProposalType> {
+    match name {
+        "normal" => Some(ProposalType::Normal),
+        "critical" => Some(ProposalType::Critical),
+        "constitutional" => Some(ProposalType::Constitutional),
+        "budget" => Some(ProposalType::Budget),
+        "emergency" => Some(ProposalType::Emergency),
+        _ => None,
+    }
+}
+
+fn vote_vectors() -> Vec<VoteVector> {
+    let cases = [
+        ("alice", "proposal_001", 0),
+        ("bob", "proposal_001", 45),
+        ("validator_001", "proposal_002", 3600),
+    ];
+
+    cases
+        .iter()
+        .enumerate()
+        .map(|(i, (voter, proposal, offset))| {
+            let key = vector_key(i as u32);
+            let timestamp = vector_epoch() + Duration::seconds(*offset);
+            let payload = vote_message(voter, proposal, timestamp, VoteChoice::Approve, 0, 0);
+            VoteVector {
+                secret_key: hex::encode(key.to_bytes()),
+                public_key: hex::encode(key.verifying_key().as_bytes()),
+                voter_id: voter.to_string(),
+                proposal_id: proposal.to_string(),
+                timestamp: timestamp.to_rfc3339(),
+                payload: hex::encode(payload.as_bytes()),
+                signature: hex::encode(key.sign(payload.as_bytes()).to_bytes()),
+            }
+        })
+        .collect()
+}
+
+fn payload_vectors() -> Vec<PayloadVector> {
+    let epoch = vector_epoch();
+    let stake = vector_key(9).sign(b"stake").to_bytes();
+    let cases = [
+        VotePayload::new("alice", "proposal_001", epoch.into()),
+        VotePayload {
+            choice: VoteChoice::Reject,
+            version: 2,
+            ..VotePayload::new("bob", "proposal_001", (epoch + Duration::milliseconds(250)).into())
+        },
+        VotePayload {
+            choice: VoteChoice::Abstain,
+            nonce: 4_294_967_297,
+            metadata: BTreeMap::from([
+                ("chain_id".to_string(), "tdc-main".to_string()),
+                ("note".to_string(), "\"quoted\": café\n".to_string()),
+            ]),
+            ..VotePayload::new("carol", "proposal_002", (epoch + Duration::microseconds(1_500)).into())
+        },
+        VotePayload {
+            stake_signature: Some(stake),
+            expires_at: Some((epoch + Duration::seconds(3600) + Duration::nanoseconds(7)).into()),
+            ..VotePayload::new("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266", "proposal_002", (epoch + Duration::nanoseconds(123_456_789)).into())
+        },
+        VotePayload::new("dave", "proposal_003", PayloadTime { secs: -1, nanos: 500_000_000 }),
+        VotePayload {
+            decay_params: Some(DecayParams { floor: Some(0.25), rate: Some(0.00002), steps: None }),
+            ..VotePayload::new("erin", "proposal_003", epoch.into())
+        },
+        VotePayload {
+            decay_params: Some(DecayParams { floor: None, rate: None, steps: Some(vec![(60.0, 0.9), (3600.0, 0.4)]) }),
+            ..VotePayload::new("frank", "proposal_003", epoch.into())
+        },
+        VotePayload::new("grace", "proposal_004", epoch.into()).covering(1.0, DecayType::Linear),
+        VotePayload {
+            choice: VoteChoice::Reject,
+            version: 1,
+            nonce: 9,
+            decay_params: Some(DecayParams { floor: Some(0.1), rate: None, steps: None }),
+            ..VotePayload::new("heidi", "proposal_004", (epoch + Duration::milliseconds(5)).into())
+        }
+        .covering(2.75, DecayType::Exponential),
+        VotePayload::new("ivan", "proposal_004", epoch.into()).covering(1e-7, DecayType::Stepped),
+    ];
+
+    cases
+        .into_iter()
+        .enumerate()
+        .map(|(i, payload)| {
+            let key = vector_key(i as u32);
+            let bytes = payload.to_bytes();
+            let covered = payload.payload_version > crate::payload::LEGACY_PAYLOAD_VERSION;
+            PayloadVector {
+                secret_key: hex::encode(key.to_bytes()),
+                voter_id: payload.voter_id,
+                proposal_id: payload.proposal_id,
+                timestamp: payload.timestamp,
+                choice: payload.choice,
+                version: payload.version,
+                nonce: payload.nonce,
+                metadata: payload.metadata,
+                stake_signature: payload.stake_signature.map(hex::encode),
+                expires_at: payload.expires_at,
+                decay_params: payload.decay_params,
+                payload_version: payload.payload_version,
+                original_weight: covered.then_some(payload.original_weight),
+                decay_model: covered.then(|| decay_name(payload.decay_model).to_string()),
+                payload: hex::encode(&bytes),
+                signature: hex::encode(key.sign(&bytes).to_bytes()),
+            }
+        })
+        .collect()
+}
+
+fn decay_vectors() -> Vec<DecayVector> {
+    let key = vector_key(0);
+    let cast_at = vector_epoch();
+    let mut vectors = Vec::new();
+
+    for decay in [DecayType::Linear, DecayType::Exponential, DecayType::Stepped] {
+        for elapsed in DECAY_ELAPSED_SECS {
+            let vote = SignedVote::new(
+                "vector_voter".parse().expect("valid id"),
+                "vector_proposal".parse().expect("valid id"),
+                1.0,
+                cast_at,
+                decay,
+                &key,
+            );
+            let mut engine = WeightEngine::new();
+            let weight = engine.calculate_weight(&vote, cast_at + Duration::seconds(elapsed), None);
+            vectors.push(DecayVector {
+                decay_model: decay_name(decay).to_string(),
+                original_weight: vote.original_weight,
+                elapsed_secs: elapsed,
+                expected_weight: weight,
+            });
+        }
+    }
+    vectors
+}
+
+fn threshold_vectors() -> Vec<ThresholdVector> {
+    let start = vector_epoch();
+    let mut vectors = Vec::new();
+
+    for proposal_type in [ProposalType::Normal, ProposalType::Critical] {
+        let escalator = ThresholdEscalator::for_proposal_type(proposal_type.clone());
+        for elapsed in THRESHOLD_ELAPSED_SECS {
+            vectors.push(ThresholdVector {
+                proposal_type: proposal_type_name(&proposal_type).to_string(),
+                elapsed_secs: elapsed,
+                expected_threshold: escalator.threshold_with_profile(start + Duration::seconds(elapsed), start),
+            });
+        }
+    }
+    vectors
+}
+
+/// Build the full, deterministic vector set
+pub fn generate() -> TestVectors {
+    TestVectors {
+        version: VECTORS_VERSION,
+        votes: vote_vectors(),
+        payloads: payload_vectors(),
+        decay: decay_vectors(),
+        thresholds: threshold_vectors(),
+    }
+}
+
+pub fn to_json(vectors: &TestVectors) -> String {
+    serde_json::to_string_pretty(vectors).expect("test vectors serialize to JSON")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        assert_eq!(to_json(&generate()), to_json(&generate()));
+    }
+
+    #[test]
+    fn test_vote_vectors_verify() {
+        for v in generate().votes {
+            let public: [u8; 32] = hex::decode(&v.public_key).unwrap().try_into().unwrap();
+            let signature: [u8; 64] = hex::decode(&v.signature).unwrap().try_into().unwrap();
+            let key = VerifyingKey::from_bytes(&public).unwrap();
+            let payload = hex::decode(&v.payload).unwrap();
+            assert!(key.verify(&payload, &Signature::from_bytes(&signature)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_expected_values() {
+        let vectors = generate();
+        assert!(
+            vectors
+                .decay
+                .iter()
+                .filter(|d| d.elapsed_secs == 0)
+                .all(|d| d.expected_weight == d.original_weight)
+        );
+        let normal_start = vectors
+            .thresholds
+            .iter()
+            .find(|t| t.proposal_type == "normal" && t.elapsed_secs == 0)
+            .unwrap();
+        assert_eq!(normal_start.expected_threshold, 0.51);
+    }
+}
@@ -1,12 +1,76 @@
 // src/verify.rs
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
 use chrono::{DateTime, Utc};
-use ed25519_dalek::{SECRET_KEY_LENGTH, Signer, SigningKey, Verifier};
+use ed25519_dalek::{SECRET_KEY_LENGTH, Signature, SigningKey, VerifyingKey};
 use rand::RngCore;
 use rand::rngs::OsRng;
 use thiserror::Error;
 
-use crate::vote::SignedVote;
+use crate::clock::{Clock, SystemClock};
+use crate::decay::{DecayBounds, DecayParams, DecayParamsError};
+use crate::payload::{LEGACY_PAYLOAD_VERSION, PAYLOAD_VERSION};
+use crate::progress::{CancellationToken, Progress};
+use crate::ids::{ProposalId, VoterId};
+use crate::proposal::ProposalRegistry;
+use crate::registry::ValidatorRegistry;
+use crate::revocation::RevocationList;
+use crate::scheme::{DefaultScheme, SignatureScheme};
+use crate::signer::{AsyncVoteSigner, InfallibleSigner, VoteSigner};
+use crate::stake::StakeAttestation;
+use crate::units::Seconds;
+use crate::vote::{DecayType, MAX_METADATA_BYTES, MAX_METADATA_ENTRIES, RANKING_KEY, RankedVote, SignedVote, SignedVoteBatch, VoteChoice};
+
+/// How far ahead of the local clock a vote timestamp may be, unless a
+/// `VerificationPolicy` says otherwise
+pub const MAX_FUTURE_SKEW: Seconds = Seconds::new(5);
+
+/// Oldest a vote may be under `VerificationPolicy::default()`
+pub const DEFAULT_MAX_AGE: Seconds = Seconds::new(300);
+
+/// Timestamp rules a vote is verified under. Operators on high-latency networks, or
+/// with loosely synced clocks, can widen `max_future_skew`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationPolicy {
+    /// Oldest a vote may be when verified
+    pub max_age: Seconds,
+    /// How far ahead of the verifier's clock a vote may be stamped
+    pub max_future_skew: Seconds,
+    /// Compromised keys; votes they signed after revocation are rejected
+    pub revocations: Option<Arc<RevocationList>>,
+    /// Oldest payload version accepted; version 1 signatures do not cover the weight or
+    /// decay model, so anyone relaying such a vote can change them
+    pub min_payload_version: u32,
+}
+
+impl VerificationPolicy {
+    pub fn new(max_age: Seconds) -> Self {
+        Self { max_age, max_future_skew: MAX_FUTURE_SKEW, revocations: None, min_payload_version: LEGACY_PAYLOAD_VERSION }
+    }
+
+    pub fn with_min_payload_version(mut self, min_payload_version: u32) -> Self {
+        self.min_payload_version = min_payload_version;
+        self
+    }
+
+    pub fn with_max_future_skew(mut self, max_future_skew: Seconds) -> Self {
+        self.max_future_skew = max_future_skew;
+        self
+    }
+
+    pub fn with_revocations(mut self, revocations: RevocationList) -> Self {
+        self.revocations = Some(Arc::new(revocations));
+        self
+    }
+}
+
+impl Default for VerificationPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_AGE)
+    }
+}
 
 #[derive(Error, Debug, PartialEq)]
 pub enum VerificationError {
@@ -16,50 +80,402 @@ pub enum VerificationError {
     TimestampExpired,
     #[error("Timestamp is in the future")]
     TimestampInFuture,
+    #[error("Signing key was revoked before the vote timestamp")]
+    KeyRevoked,
+    #[error("Validator exited before the vote timestamp")]
+    ValidatorExited,
+    #[error("Voter {0} is not a registered validator")]
+    UnregisteredVoter(VoterId),
+    #[error("Vote is not signed with the key registered for {0}")]
+    UnregisteredKey(VoterId),
+    #[error("Vote version {got} does not follow version {latest}")]
+    OutOfOrderVersion { latest: u64, got: u64 },
+    #[error("Vote was already submitted")]
+    Replayed,
+    #[error("Vote metadata has {entries} entries and {bytes} bytes, over the limit")]
+    MetadataTooLarge { entries: usize, bytes: usize },
+    #[error("Unknown proposal: {0}")]
+    UnknownProposal(ProposalId),
+    #[error("Proposal {0} was not open yet when the vote was cast")]
+    ProposalNotOpen(ProposalId),
+    #[error("Proposal {0} was closed when the vote was cast")]
+    ProposalClosed(ProposalId),
+    #[error("Proposal {proposal_id} does not accept {decay_model:?} votes")]
+    DecayModelForbidden { proposal_id: ProposalId, decay_model: DecayType },
+    #[error("Ranking is empty, repeats an option, or differs from the signed one")]
+    InvalidRanking,
+    #[error("Decay parameters rejected: {0}")]
+    DecayOutOfBounds(DecayParamsError),
+    #[error("Payload version {version} is older than the minimum of {min}")]
+    PayloadVersionTooOld { version: u32, min: u32 },
+}
+
+/// Latest accepted version per voter and proposal; anything not newer is out of order,
+/// however its timestamp compares
+#[derive(Debug, Clone, Default)]
+pub struct VersionTracker {
+    latest: HashMap<(VoterId, ProposalId), u64>,
+}
+
+impl VersionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn latest(&self, voter_id: &VoterId, proposal_id: &ProposalId) -> Option<u64> {
+        self.latest.get(&(voter_id.clone(), proposal_id.clone())).copied()
+    }
+
+    /// Reject `vote` unless its version is newer than any accepted before it
+    pub fn check(&self, vote: &SignedVote) -> Result<(), VerificationError> {
+        match self.latest(&vote.voter_id, &vote.proposal_id) {
+            Some(latest) if vote.version <= latest => {
+                Err(VerificationError::OutOfOrderVersion { latest, got: vote.version })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `check`, then record `vote` as the latest version
+    pub fn accept(&mut self, vote: &SignedVote) -> Result<(), VerificationError> {
+        self.check(vote)?;
+        self.latest.insert((vote.voter_id.clone(), vote.proposal_id.clone()), vote.version);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ReplayKey {
+    Nonce(u64),
+    /// Votes signed without a nonce are told apart by their signature
+    Signature([u8; 64]),
+}
+
+impl ReplayKey {
+    fn of(vote: &SignedVote) -> Self {
+        if vote.nonce == 0 { ReplayKey::Signature(vote.signature.to_bytes()) } else { ReplayKey::Nonce(vote.nonce) }
+    }
+}
+
+/// Nonces already seen per proposal and voter; a vote whose nonce was used before is a
+/// replay, however fresh its timestamp still is
+#[derive(Debug, Clone, Default)]
+pub struct ReplayGuard {
+    seen: HashMap<ProposalId, HashSet<(VoterId, ReplayKey)>>,
+}
+
+impl ReplayGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn check(&self, vote: &SignedVote) -> Result<(), VerificationError> {
+        let key = (vote.voter_id.clone(), ReplayKey::of(vote));
+        match self.seen.get(&vote.proposal_id) {
+            Some(seen) if seen.contains(&key) => Err(VerificationError::Replayed),
+            _ => Ok(()),
+        }
+    }
+
+    /// `check`, then remember `vote`'s nonce
+    pub fn accept(&mut self, vote: &SignedVote) -> Result<(), VerificationError> {
+        self.check(vote)?;
+        self.seen
+            .entry(vote.proposal_id.clone())
+            .or_default()
+            .insert((vote.voter_id.clone(), ReplayKey::of(vote)));
+        Ok(())
+    }
+
+    /// Drop what was seen for a proposal once it can no longer take votes
+    pub fn forget(&mut self, proposal_id: &ProposalId) {
+        self.seen.remove(proposal_id);
+    }
+
+    pub fn seen(&self, proposal_id: &ProposalId) -> usize {
+        self.seen.get(proposal_id).map_or(0, HashSet::len)
+    }
 }
 
 impl SignedVote {
     /// Generate a new signed vote
-pub fn new(
-    voter_id: String,
-    proposal_id: String,
+pub fn new<S: InfallibleSigner + ?Sized>(
+    voter_id: VoterId,
+    proposal_id: ProposalId,
     original_weight: f64,
     timestamp: DateTime<Utc>, // ✅ take from caller
     decay_model: crate::vote::DecayType,
-    signing_key: &SigningKey,
+    signer: &S,
 ) -> Self {
-    let message = format!("{}:{}:{}", voter_id, proposal_id, timestamp);
-    let signature = signing_key.sign(message.as_bytes());
-    let public_key = signing_key.verifying_key();
+    Self::new_with_choice(voter_id, proposal_id, original_weight, timestamp, decay_model, VoteChoice::Approve, signer)
+}
 
-    Self {
-        voter_id,
-        proposal_id,
-        timestamp,
-        original_weight,
-        decay_model,
-        signature,
-        public_key,
+    /// Generate a signed vote for `choice`; `new` always approves
+    pub fn new_with_choice<S: InfallibleSigner + ?Sized>(
+        voter_id: VoterId,
+        proposal_id: ProposalId,
+        original_weight: f64,
+        timestamp: DateTime<Utc>,
+        decay_model: crate::vote::DecayType,
+        choice: VoteChoice,
+        signer: &S,
+    ) -> Self {
+        let Ok(vote) = Self::try_new_with_choice(voter_id, proposal_id, original_weight, timestamp, decay_model, choice, signer);
+        vote
+    }
+
+    /// `new` with a signer that can fail, such as an HSM or hardware wallet
+    pub fn try_new<S: VoteSigner + ?Sized>(
+        voter_id: VoterId,
+        proposal_id: ProposalId,
+        original_weight: f64,
+        timestamp: DateTime<Utc>,
+        decay_model: crate::vote::DecayType,
+        signer: &S,
+    ) -> Result<Self, S::Error> {
+        Self::try_new_with_choice(voter_id, proposal_id, original_weight, timestamp, decay_model, VoteChoice::Approve, signer)
+    }
+
+    pub fn try_new_with_choice<S: VoteSigner + ?Sized>(
+        voter_id: VoterId,
+        proposal_id: ProposalId,
+        original_weight: f64,
+        timestamp: DateTime<Utc>,
+        decay_model: crate::vote::DecayType,
+        choice: VoteChoice,
+        signer: &S,
+    ) -> Result<Self, S::Error> {
+        Self::unsigned(voter_id, proposal_id, original_weight, timestamp, decay_model, choice, signer.public_key())
+            .sign_with(signer)
+    }
+
+    /// `try_new` with a signer that answers asynchronously
+    pub async fn try_new_async<S: AsyncVoteSigner + ?Sized>(
+        voter_id: VoterId,
+        proposal_id: ProposalId,
+        original_weight: f64,
+        timestamp: DateTime<Utc>,
+        decay_model: crate::vote::DecayType,
+        signer: &S,
+    ) -> Result<Self, S::Error> {
+        Self::try_new_with_choice_async(voter_id, proposal_id, original_weight, timestamp, decay_model, VoteChoice::Approve, signer)
+            .await
+    }
+
+    pub async fn try_new_with_choice_async<S: AsyncVoteSigner + ?Sized>(
+        voter_id: VoterId,
+        proposal_id: ProposalId,
+        original_weight: f64,
+        timestamp: DateTime<Utc>,
+        decay_model: crate::vote::DecayType,
+        choice: VoteChoice,
+        signer: &S,
+    ) -> Result<Self, S::Error> {
+        let public_key = AsyncVoteSigner::public_key(signer);
+        Self::unsigned(voter_id, proposal_id, original_weight, timestamp, decay_model, choice, public_key)
+            .sign_with_async(signer)
+            .await
+    }
+
+    fn unsigned(
+        voter_id: VoterId,
+        proposal_id: ProposalId,
+        original_weight: f64,
+        timestamp: DateTime<Utc>,
+        decay_model: crate::vote::DecayType,
+        choice: VoteChoice,
+        public_key: VerifyingKey,
+    ) -> Self {
+        Self {
+            voter_id,
+            proposal_id,
+            timestamp,
+            original_weight,
+            decay_model,
+            decay_params: None,
+            choice,
+            version: 0,
+            nonce: 0,
+            metadata: BTreeMap::new(),
+            stake: None,
+            expires_at: None,
+            payload_version: PAYLOAD_VERSION,
+            signature: Signature::from_bytes(&[0; 64]),
+            public_key,
+        }
+    }
+
+    /// Sign this vote as it stands with `signer`, replacing its signature and key; the
+    /// new signature always uses the current payload version
+    pub fn sign_with<S: VoteSigner + ?Sized>(self, signer: &S) -> Result<Self, S::Error> {
+        let vote = Self { payload_version: PAYLOAD_VERSION, ..self };
+        let signature = signer.sign_message(vote.message().as_bytes())?;
+        Ok(Self { signature, public_key: signer.public_key(), ..vote })
+    }
+
+    /// `sign_with` for a signer that answers asynchronously
+    pub async fn sign_with_async<S: AsyncVoteSigner + ?Sized>(self, signer: &S) -> Result<Self, S::Error> {
+        let vote = Self { payload_version: PAYLOAD_VERSION, ..self };
+        let signature = AsyncVoteSigner::sign_message(signer, vote.message().as_bytes()).await?;
+        Ok(Self { signature, public_key: AsyncVoteSigner::public_key(signer), ..vote })
+    }
+
+    /// Canonical message this vote's signature covers, metadata included
+    pub fn message(&self) -> String {
+        // Binding the stake proof's signature stops a relayer from stripping or swapping it
+        crate::payload::VotePayload::from(self).to_string()
+    }
+
+    /// Re-sign this vote to stop counting at `expires_at`
+    pub fn with_expiry(self, expires_at: DateTime<Utc>, signing_key: &SigningKey) -> Self {
+        Self { expires_at: Some(expires_at), ..self }.resign(signing_key)
+    }
+
+    /// Re-sign this vote to decay under `params` instead of the standard ones
+    pub fn with_decay_params(self, params: DecayParams, signing_key: &SigningKey) -> Self {
+        Self { decay_params: Some(params), ..self }.resign(signing_key)
+    }
+
+    /// Reject decay parameters outside a server's `bounds`
+    pub fn check_decay(&self, bounds: &DecayBounds) -> Result<(), VerificationError> {
+        match &self.decay_params {
+            Some(params) => bounds.check(self.decay_model, params).map_err(VerificationError::DecayOutOfBounds),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the voter's own expiry has passed as of `at`
+    pub fn is_expired(&self, at: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|e| at >= e)
+    }
+
+    /// Re-sign this vote carrying `stake` as proof of its weight
+    pub fn with_stake(self, stake: StakeAttestation, signing_key: &SigningKey) -> Self {
+        Self { stake: Some(stake), ..self }.resign(signing_key)
+    }
+
+    /// Re-sign this vote carrying `metadata`, replacing any it had
+    pub fn with_metadata(self, metadata: BTreeMap<String, String>, signing_key: &SigningKey) -> Self {
+        Self { metadata, ..self }.resign(signing_key)
+    }
+
+    fn resign(self, signing_key: &SigningKey) -> Self {
+        let Ok(vote) = self.sign_with(signing_key);
+        vote
+    }
+
+    /// Re-sign this vote with `nonce`, so a replay of it can be told from a new vote
+    pub fn with_nonce(self, nonce: u64, signing_key: &SigningKey) -> Self {
+        Self { nonce, ..self }.resign(signing_key)
+    }
+
+    /// A random non-zero nonce
+    pub fn generate_nonce() -> u64 {
+        OsRng.next_u64().max(1)
+    }
+
+    /// Sign the next version of this vote, replacing its weight, timestamp, and choice.
+    /// Votes that carry a nonce get a fresh one; metadata carries over.
+    pub fn amend(
+        &self,
+        original_weight: f64,
+        timestamp: DateTime<Utc>,
+        choice: VoteChoice,
+        signing_key: &SigningKey,
+    ) -> Self {
+        let version = self.version + 1;
+        let nonce = if self.nonce == 0 { 0 } else { Self::generate_nonce() };
+        Self {
+            timestamp,
+            original_weight,
+            choice,
+            version,
+            nonce,
+            ..self.clone()
+        }
+        .resign(signing_key)
     }
-}
 
     /// Verify the vote signature and timestamp
     pub fn verify(&self, max_age_secs: i64) -> Result<(), VerificationError> {
-        let message = format!("{}:{}:{}", self.voter_id, self.proposal_id, self.timestamp);
-        let now = Utc::now();
-        let age_secs = (now - self.timestamp).num_seconds();
+        self.verify_within(Seconds::new(max_age_secs))
+    }
+
+    /// Typed form of `verify`
+    pub fn verify_within(&self, max_age: Seconds) -> Result<(), VerificationError> {
+        self.verify_with_clock(max_age, &SystemClock)
+    }
+
+    /// Verify as of `clock`'s current time
+    pub fn verify_with_clock(&self, max_age: Seconds, clock: &dyn Clock) -> Result<(), VerificationError> {
+        self.verify_at(clock.now(), max_age)
+    }
+
+    /// Verify against a caller-supplied clock, for replays and deterministic simulations
+    pub fn verify_at(&self, now: DateTime<Utc>, max_age: Seconds) -> Result<(), VerificationError> {
+        self.verify_with_policy(&VerificationPolicy::new(max_age), now)
+    }
 
-        // Reject if timestamp is too old or in the future (±5 seconds allowed)
-        if age_secs < -5 {
+    /// `verify_at` under `policy`'s age limit, future skew, and revocation list
+    pub fn verify_with_policy(&self, policy: &VerificationPolicy, now: DateTime<Utc>) -> Result<(), VerificationError> {
+        let entries = self.metadata.len();
+        let bytes: usize = self.metadata.iter().map(|(k, v)| k.len() + v.len()).sum();
+        if entries > MAX_METADATA_ENTRIES || bytes > MAX_METADATA_BYTES {
+            return Err(VerificationError::MetadataTooLarge { entries, bytes });
+        }
+        let message = self.message();
+        let age = Seconds::between(self.timestamp, now);
+
+        // Reject if timestamp is too old or in the future
+        if age < Seconds::ZERO - policy.max_future_skew {
             return Err(VerificationError::TimestampInFuture);
         }
-        if age_secs > max_age_secs {
+        if age > policy.max_age {
             return Err(VerificationError::TimestampExpired);
         }
+        if self.payload_version < policy.min_payload_version {
+            return Err(VerificationError::PayloadVersionTooOld {
+                version: self.payload_version,
+                min: policy.min_payload_version,
+            });
+        }
 
-        self.public_key
-            .verify(message.as_bytes(), &self.signature)
-            .map_err(|_| VerificationError::InvalidSignature)
+        DefaultScheme::verify(&self.public_key, message.as_bytes(), &self.signature)
+            .map_err(|_| VerificationError::InvalidSignature)?;
+        policy.revocations.as_ref().map_or(Ok(()), |list| list.check(self))
+    }
+
+    /// Verify the vote as of `now` and reject it if its key was revoked before it was cast
+    pub fn verify_with_registry(
+        &self,
+        now: DateTime<Utc>,
+        max_age_secs: i64,
+        registry: &ValidatorRegistry,
+    ) -> Result<(), VerificationError> {
+        self.verify_at(now, Seconds::new(max_age_secs))?;
+        registry.check_vote(self)
+    }
+
+    /// Verify the vote as of `now` and reject it unless its proposal is registered, was
+    /// open when it was cast, and accepts its decay model
+    pub fn verify_with_proposals(
+        &self,
+        now: DateTime<Utc>,
+        max_age_secs: i64,
+        proposals: &ProposalRegistry,
+    ) -> Result<(), VerificationError> {
+        self.verify_against(&VerificationPolicy::new(Seconds::new(max_age_secs)), now, Some(proposals))
+    }
+
+    /// `verify_with_policy`, then `ProposalRegistry::check_vote` when `proposals` is given
+    pub fn verify_against(
+        &self,
+        policy: &VerificationPolicy,
+        now: DateTime<Utc>,
+        proposals: Option<&ProposalRegistry>,
+    ) -> Result<(), VerificationError> {
+        self.verify_with_policy(policy, now)?;
+        proposals.map_or(Ok(()), |p| p.check_vote(self))
     }
 
     /// Utility function to generate a validator keypair
@@ -72,10 +488,91 @@ pub fn new(
 
 }
 
+impl RankedVote {
+    pub fn new(
+        voter_id: VoterId,
+        proposal_id: ProposalId,
+        original_weight: f64,
+        timestamp: DateTime<Utc>,
+        decay_model: crate::vote::DecayType,
+        ranking: Vec<String>,
+        signing_key: &SigningKey,
+    ) -> Self {
+        let signed = serde_json::to_string(&ranking).expect("rankings serialize");
+        let vote = SignedVote::new_with_choice(voter_id, proposal_id, original_weight, timestamp, decay_model, VoteChoice::Abstain, signing_key)
+            .with_metadata(BTreeMap::from([(RANKING_KEY.to_string(), signed)]), signing_key);
+        Self { vote, ranking }
+    }
+
+    /// Verify the carrying vote and that `ranking` is the non-empty, repeat-free order it signed
+    pub fn verify(&self, max_age_secs: i64) -> Result<(), VerificationError> {
+        self.vote.verify(max_age_secs)?;
+        let signed: Option<Vec<String>> =
+            self.vote.metadata.get(RANKING_KEY).and_then(|json| serde_json::from_str(json).ok());
+        let distinct: HashSet<&String> = self.ranking.iter().collect();
+        if self.ranking.is_empty() || distinct.len() != self.ranking.len() || signed.as_ref() != Some(&self.ranking) {
+            return Err(VerificationError::InvalidRanking);
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of verifying many votes; `cancelled` means the tail was never checked
+#[derive(Debug, Default, PartialEq)]
+pub struct BatchStatus {
+    pub verified: usize,
+    pub failed: Vec<(usize, VerificationError)>,
+    pub cancelled: bool,
+}
+
+impl BatchStatus {
+    pub fn processed(&self) -> usize {
+        self.verified + self.failed.len()
+    }
+}
+
+/// Verify votes in order with a progress bar, stopping between votes on cancellation
+pub fn verify_batch(votes: &[SignedVote], max_age_secs: i64, cancel: &CancellationToken) -> BatchStatus {
+    let mut status = BatchStatus::default();
+    let progress = Progress::new(votes.len() as u64, "Verifying votes");
+
+    for (i, vote) in votes.iter().enumerate() {
+        if cancel.is_cancelled() {
+            status.cancelled = true;
+            progress.abandon(format!("Verification cancelled after {} votes", i));
+            return status;
+        }
+        match vote.verify(max_age_secs) {
+            Ok(()) => status.verified += 1,
+            Err(e) => status.failed.push((i, e)),
+        }
+        progress.inc(1);
+    }
+
+    progress.finish();
+    status
+}
+
+impl SignedVoteBatch {
+    /// Verify every vote in the batch as of `now`; failures are reported by index
+    pub fn verify_at(&self, now: DateTime<Utc>, max_age: Seconds) -> BatchStatus {
+        let mut status = BatchStatus::default();
+        for (i, vote) in self.votes().iter().enumerate() {
+            match vote.verify_at(now, max_age) {
+                Ok(()) => status.verified += 1,
+                Err(e) => status.failed.push((i, e)),
+            }
+        }
+        status
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::history::HistoryAnalyzer;
+    use crate::intake::{Channel, VoteIntake, VoteSource};
     use crate::vote::{SignedVote, DecayType};
     use chrono::{Utc, Duration};
 
@@ -83,8 +580,8 @@ mod tests {
         let signing_key = SignedVote::generate_keypair();
         let timestamp = Utc::now() + Duration::seconds(offset_secs);
         SignedVote::new(
-            "voter1".to_string(),
-            "proposal1".to_string(),
+            "voter1".parse().unwrap(),
+            "proposal1".parse().unwrap(),
             1.0,
             timestamp,
             DecayType::Linear, // use tuple variant syntax if Linear is defined as Linear(f64)
@@ -105,6 +602,68 @@ mod tests {
         assert_eq!(result, Err(VerificationError::TimestampExpired));
     }
 
+    #[test]
+    fn test_default_policy_accepts_votes_up_to_the_default_age() {
+        let vote = mock_signed_vote(0);
+        let policy = VerificationPolicy::default();
+        assert_eq!(vote.verify_with_policy(&policy, vote.timestamp + Duration::seconds(60)), Ok(()));
+        assert_eq!(
+            vote.verify_with_policy(&policy, vote.timestamp + Duration::seconds(301)),
+            Err(VerificationError::TimestampExpired)
+        );
+        // Only the clock passed in counts, not the system clock
+        assert_eq!(vote.verify_at(vote.timestamp + Duration::seconds(301), Seconds::new(600)), Ok(()));
+    }
+
+    #[test]
+    fn test_amendments_are_ordered_by_version() {
+        let signing_key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let first = SignedVote::new("voter1".parse().unwrap(), "proposal1".parse().unwrap(), 1.0, now, DecayType::Linear, &signing_key);
+        // The amendment's clock runs behind, but its version still orders it after the first vote
+        let second = first.amend(0.5, now - Duration::seconds(3), VoteChoice::Reject, &signing_key);
+        assert_eq!(second.version, 1);
+        assert_eq!(second.verify(10), Ok(()));
+
+        let mut tampered = second.clone();
+        tampered.version = 7;
+        assert_eq!(tampered.verify(10), Err(VerificationError::InvalidSignature));
+
+        let mut tracker = VersionTracker::new();
+        tracker.accept(&first).unwrap();
+        tracker.accept(&second).unwrap();
+        assert_eq!(tracker.latest(&"voter1".parse().unwrap(), &"proposal1".parse().unwrap()), Some(1));
+        assert_eq!(tracker.check(&first), Err(VerificationError::OutOfOrderVersion { latest: 1, got: 0 }));
+        assert_eq!(tracker.check(&second.amend(1.0, now, VoteChoice::Approve, &signing_key)), Ok(()));
+    }
+
+    #[test]
+    fn test_replayed_nonces_rejected() {
+        let signing_key = SignedVote::generate_keypair();
+        let now = Utc::now();
+        let vote = SignedVote::new("voter1".parse().unwrap(), "proposal1".parse().unwrap(), 1.0, now, DecayType::Linear, &signing_key)
+            .with_nonce(SignedVote::generate_nonce(), &signing_key);
+        assert_eq!(vote.verify(10), Ok(()));
+        let mut tampered = vote.clone();
+        tampered.nonce += 1;
+        assert_eq!(tampered.verify(10), Err(VerificationError::InvalidSignature));
+
+        let mut guard = ReplayGuard::new();
+        guard.accept(&vote).unwrap();
+        assert_eq!(guard.accept(&vote.clone()), Err(VerificationError::Replayed));
+        let amended = vote.amend(1.0, now, VoteChoice::Reject, &signing_key);
+        assert_ne!(amended.nonce, vote.nonce);
+        guard.accept(&amended).unwrap();
+
+        // Without a nonce, a resubmitted vote is caught by its signature
+        let legacy = mock_signed_vote(0);
+        guard.accept(&legacy).unwrap();
+        assert_eq!(guard.check(&legacy), Err(VerificationError::Replayed));
+        assert_eq!(guard.seen(&vote.proposal_id), 3);
+        guard.forget(&vote.proposal_id);
+        assert_eq!(guard.check(&vote), Ok(()));
+    }
+
     #[test]
     fn test_vote_in_future() {
         let vote = mock_signed_vote(10);
@@ -112,13 +671,99 @@ mod tests {
         assert_eq!(result, Err(VerificationError::TimestampInFuture));
     }
 
+    #[test]
+    fn test_future_skew_is_configurable() {
+        let vote = mock_signed_vote(10);
+        let now = Utc::now();
+        let lenient = VerificationPolicy::new(Seconds::new(60)).with_max_future_skew(Seconds::new(30));
+        assert_eq!(vote.verify_with_policy(&lenient, now), Ok(()));
+        assert_eq!(vote.verify_with_policy(&VerificationPolicy::new(Seconds::new(60)), now), Err(VerificationError::TimestampInFuture));
+
+        let mut intake = VoteIntake::with_policy(lenient);
+        let mut history = HistoryAnalyzer::default();
+        assert_eq!(intake.submit(vote, VoteSource::new(Channel::Http), now, None, &mut history), Ok(()));
+    }
+
+    #[test]
+    fn test_revoked_key_rejected() {
+        let signing_key = SignedVote::generate_keypair();
+        let authority = SignedVote::generate_keypair();
+        let mut registry = ValidatorRegistry::new();
+        registry.add_authority(authority.verifying_key());
+        registry.register("voter1".to_string(), signing_key.verifying_key());
+
+        let vote = SignedVote::new(
+            "voter1".parse().unwrap(),
+            "proposal1".parse().unwrap(),
+            1.0,
+            Utc::now(),
+            DecayType::Linear,
+            &signing_key,
+        );
+        assert_eq!(vote.verify_with_registry(Utc::now(), 10, &registry), Ok(()));
+
+        let revocation = crate::registry::Revocation::new(
+            signing_key.verifying_key(),
+            Utc::now() - Duration::seconds(5),
+            "compromised".to_string(),
+            &authority,
+        );
+        registry.revoke(revocation).unwrap();
+        assert_eq!(vote.verify_with_registry(Utc::now(), 10, &registry), Err(VerificationError::KeyRevoked));
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let votes = vec![mock_signed_vote(0), mock_signed_vote(-20), mock_signed_vote(0)];
+        let status = verify_batch(&votes, 10, &CancellationToken::new());
+        assert_eq!(status.verified, 2);
+        assert_eq!(status.failed, vec![(1, VerificationError::TimestampExpired)]);
+        assert!(!status.cancelled);
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let status = verify_batch(&votes, 10, &cancel);
+        assert!(status.cancelled);
+        assert_eq!(status.processed(), 0);
+    }
+
     #[test]
     fn test_invalid_signature() {
         let mut vote = mock_signed_vote(0);
         // Corrupt the signature bytes
-        vote.signature = ed25519_dalek::Signature::try_from([0u8; 64])
-    .expect("Failed to create dummy signature");
+        vote.signature = ed25519_dalek::Signature::from_bytes(&[0u8; 64]);
         let result = vote.verify(10);
         assert_eq!(result, Err(VerificationError::InvalidSignature));
     }
+
+    #[test]
+    fn test_metadata_is_signed() {
+        let key = SignedVote::generate_keypair();
+        let plain = SignedVote::new("voter1".parse().unwrap(), "proposal1".parse().unwrap(), 1.0, Utc::now(), DecayType::Linear, &key);
+        assert!(!plain.to_json().unwrap().contains("metadata"));
+
+        let metadata = BTreeMap::from([("chain_id".to_string(), "tdc-main".to_string()), ("client".to_string(), "cli/0.1".to_string())]);
+        let vote = plain.clone().with_metadata(metadata.clone(), &key);
+        assert_eq!(vote.verify(10), Ok(()));
+        assert_ne!(vote.message(), plain.message());
+        let decoded = SignedVote::from_json(&vote.to_json().unwrap()).unwrap();
+        assert_eq!(decoded.metadata, metadata);
+        assert_eq!(decoded.verify(10), Ok(()));
+        let batch = SignedVoteBatch::from_votes(vote.proposal_id.clone(), vec![vote.clone()]).unwrap();
+        assert_eq!(SignedVoteBatch::from_cbor(&batch.to_cbor().unwrap()).unwrap().votes()[0].verify(10), Ok(()));
+
+        let amended = vote.amend(2.0, Utc::now(), VoteChoice::Reject, &key);
+        assert_eq!(amended.metadata, metadata);
+        assert_eq!(amended.verify(10), Ok(()));
+
+        let mut tampered = vote.clone();
+        tampered.metadata.insert("client".into(), "forged".into());
+        assert_eq!(tampered.verify(10), Err(VerificationError::InvalidSignature));
+        tampered.metadata.clear();
+        assert_eq!(tampered.verify(10), Err(VerificationError::InvalidSignature));
+
+        let bulky = (0..=MAX_METADATA_ENTRIES).map(|i| (format!("k{}", i), String::new())).collect();
+        let oversized = plain.with_metadata(bulky, &key);
+        assert!(matches!(oversized.verify(10), Err(VerificationError::MetadataTooLarge { entries: 17, .. })));
+    }
 } 
\ No newline at end of file
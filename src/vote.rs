@@ -1,34 +1,434 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
 use chrono::{DateTime, Utc};
-use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use serde::de::{self, Visitor};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::decay::DecayParams;
+use crate::ids::{ProposalId, VoterId};
+use crate::payload::VotePayload;
+use crate::scheme::{DefaultScheme, SignatureScheme};
+use crate::stake::StakeAttestation;
 
+/// Most metadata entries a vote may carry
+pub const MAX_METADATA_ENTRIES: usize = 16;
+/// Most bytes of metadata keys and values together a vote may carry
+pub const MAX_METADATA_BYTES: usize = 1024;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum DecayType {
     Linear,
     Exponential,
     Stepped,
 }
 
-#[derive(Clone)]
+/// What a vote says about the proposal; its weight counts toward that side
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteChoice {
+    #[default]
+    Approve,
+    Reject,
+    /// Counts toward turnout only
+    Abstain,
+}
+
+impl VoteChoice {
+    pub fn is_approve(&self) -> bool {
+        *self == VoteChoice::Approve
+    }
+}
+
+/// What a proposal decides; each type has its own thresholds, window, and minimum
+/// vote count (see `policy::ProposalPolicy`)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ProposalType {
     Normal,
     Critical,
+    /// Changes to the governance rules themselves
+    Constitutional,
+    /// Spending from a shared treasury
+    Budget,
+    /// Time-critical responses decided within minutes
+    Emergency,
 }
 
-#[derive(Debug, Clone)]
+impl ProposalType {
+    pub const ALL: [ProposalType; 5] = [
+        ProposalType::Normal,
+        ProposalType::Critical,
+        ProposalType::Constitutional,
+        ProposalType::Budget,
+        ProposalType::Emergency,
+    ];
+}
+
+/// Wire form: the signature and public key are hex strings in JSON and byte strings in CBOR
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SignedVote {
-    pub voter_id: String,
-    pub proposal_id: String,
+    pub voter_id: VoterId,
+    pub proposal_id: ProposalId,
     pub timestamp: DateTime<Utc>,
     pub original_weight: f64,
     pub decay_model: DecayType,
+    /// Voter-chosen rate, steps or floor for `decay_model`, covered by the signature
+    /// and held to the server's `DecayBounds`; standard decay when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decay_params: Option<DecayParams>,
+    /// Older votes carry no choice and approve
+    #[serde(default)]
+    pub choice: VoteChoice,
+    /// Amendment sequence for this voter and proposal; 0 for the first vote
+    #[serde(default)]
+    pub version: u64,
+    /// Random value that makes each signed vote unique; 0 on votes signed without one
+    #[serde(default)]
+    pub nonce: u64,
+    /// Integrator-defined entries (chain id, client version, justification, ...),
+    /// covered by the signature; empty on votes signed without any
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Authority-signed proof of stake, used for the weight in place of
+    /// `original_weight` when stake authorities are configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stake: Option<StakeAttestation>,
+    /// Set by the voter; the vote stops counting at this time whatever its decay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Layout of the signed payload (see `VotePayload`); votes signed before version 2
+    /// leave `original_weight` and `decay_model` uncovered
+    #[serde(default = "crate::payload::legacy_payload_version", skip_serializing_if = "crate::payload::is_legacy_payload")]
+    pub payload_version: u32,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<64>")]
     pub signature: Signature,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<32>")]
     pub public_key: VerifyingKey,    
 }
 
-pub fn sign_vote(voter_id: String, signing_key: &SigningKey, timestamp: DateTime<Utc>) -> Signature {
+#[derive(Error, Debug)]
+pub enum WireError {
+    #[error("JSON encoding failed: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Schema(#[from] crate::schema::SchemaError),
+    #[error("CBOR encoding failed: {0}")]
+    Cbor(String),
+}
+
+impl SignedVote {
+    pub fn to_json(&self) -> Result<String, WireError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Parse a vote, checked against the published `vote` schema
+    pub fn from_json(json: &str) -> Result<Self, WireError> {
+        Ok(crate::schema::parse(json)?)
+    }
+
+    pub fn to_cbor(&self) -> Result<Vec<u8>, WireError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes).map_err(|e| WireError::Cbor(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, WireError> {
+        ciborium::from_reader(bytes).map_err(|e| WireError::Cbor(e.to_string()))
+    }
+}
+
+/// Metadata key under which a ranked ballot's preference order is signed
+pub const RANKING_KEY: &str = "ranking";
+
+/// A ballot listing options in order of preference, most preferred first. The ranking
+/// is signed as metadata on an abstaining vote, so binary tallies count it toward
+/// turnout only while decay and trust weight it like any other vote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedVote {
+    pub vote: SignedVote,
+    pub ranking: Vec<String>,
+}
+
+/// What a voter cast: a choice on the proposal, or options in order of preference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "ballot", rename_all = "snake_case")]
+pub enum Ballot {
+    Choice(SignedVote),
+    Ranked(RankedVote),
+}
+
+impl Ballot {
+    /// The signed vote that carries this ballot's weight
+    pub fn vote(&self) -> &SignedVote {
+        match self {
+            Ballot::Choice(vote) => vote,
+            Ballot::Ranked(ranked) => &ranked.vote,
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+#[error("Vote on {found} cannot join a batch for {expected}")]
+pub struct BatchMismatch {
+    pub expected: ProposalId,
+    pub found: ProposalId,
+}
+
+/// Many votes on one proposal, e.g. gathered by a relayer off-node. On the wire the
+/// proposal id is written once and default choices and versions are left out.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(from = "BatchWire", into = "BatchWire")]
+pub struct SignedVoteBatch {
+    proposal_id: ProposalId,
+    votes: Vec<SignedVote>,
+}
+
+impl SignedVoteBatch {
+    pub fn new(proposal_id: ProposalId) -> Self {
+        Self {
+            proposal_id,
+            votes: Vec::new(),
+        }
+    }
+
+    /// Batch `votes`, which must all be on `proposal_id`
+    pub fn from_votes(proposal_id: ProposalId, votes: Vec<SignedVote>) -> Result<Self, BatchMismatch> {
+        let mut batch = Self::new(proposal_id);
+        for vote in votes {
+            batch.push(vote)?;
+        }
+        Ok(batch)
+    }
+
+    pub fn push(&mut self, vote: SignedVote) -> Result<(), BatchMismatch> {
+        if vote.proposal_id != self.proposal_id {
+            return Err(BatchMismatch {
+                expected: self.proposal_id.clone(),
+                found: vote.proposal_id,
+            });
+        }
+        self.votes.push(vote);
+        Ok(())
+    }
+
+    pub fn proposal_id(&self) -> &ProposalId {
+        &self.proposal_id
+    }
+
+    pub fn votes(&self) -> &[SignedVote] {
+        &self.votes
+    }
+
+    pub fn len(&self) -> usize {
+        self.votes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.votes.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String, WireError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Parse a bundle, checked against the published `vote_batch` schema
+    pub fn from_json(json: &str) -> Result<Self, WireError> {
+        Ok(crate::schema::parse(json)?)
+    }
+
+    pub fn to_cbor(&self) -> Result<Vec<u8>, WireError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes).map_err(|e| WireError::Cbor(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, WireError> {
+        ciborium::from_reader(bytes).map_err(|e| WireError::Cbor(e.to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct BatchWire {
+    proposal_id: ProposalId,
+    votes: Vec<BatchEntry>,
+}
+
+/// A `SignedVote` without its proposal id
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct BatchEntry {
+    voter_id: VoterId,
+    timestamp: DateTime<Utc>,
+    original_weight: f64,
+    decay_model: DecayType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    decay_params: Option<DecayParams>,
+    #[serde(default, skip_serializing_if = "VoteChoice::is_approve")]
+    choice: VoteChoice,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    version: u64,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    nonce: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stake: Option<StakeAttestation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<DateTime<Utc>>,
+    #[serde(default = "crate::payload::legacy_payload_version", skip_serializing_if = "crate::payload::is_legacy_payload")]
+    payload_version: u32,
+    #[serde(serialize_with = "serialize_signature", deserialize_with = "deserialize_signature")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<64>")]
+    signature: Signature,
+    #[serde(serialize_with = "serialize_public_key", deserialize_with = "deserialize_public_key")]
+    #[schemars(schema_with = "crate::schema::hex_bytes::<32>")]
+    public_key: VerifyingKey,
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+impl From<SignedVoteBatch> for BatchWire {
+    fn from(batch: SignedVoteBatch) -> Self {
+        let votes = batch
+            .votes
+            .into_iter()
+            .map(|v| BatchEntry {
+                voter_id: v.voter_id,
+                timestamp: v.timestamp,
+                original_weight: v.original_weight,
+                decay_model: v.decay_model,
+                decay_params: v.decay_params,
+                choice: v.choice,
+                version: v.version,
+                nonce: v.nonce,
+                metadata: v.metadata,
+                stake: v.stake,
+                expires_at: v.expires_at,
+                payload_version: v.payload_version,
+                signature: v.signature,
+                public_key: v.public_key,
+            })
+            .collect();
+        Self { proposal_id: batch.proposal_id, votes }
+    }
+}
+
+impl From<BatchWire> for SignedVoteBatch {
+    fn from(wire: BatchWire) -> Self {
+        let votes = wire
+            .votes
+            .into_iter()
+            .map(|e| SignedVote {
+                voter_id: e.voter_id,
+                proposal_id: wire.proposal_id.clone(),
+                timestamp: e.timestamp,
+                original_weight: e.original_weight,
+                decay_model: e.decay_model,
+                decay_params: e.decay_params,
+                choice: e.choice,
+                version: e.version,
+                nonce: e.nonce,
+                metadata: e.metadata,
+                stake: e.stake,
+                expires_at: e.expires_at,
+                payload_version: e.payload_version,
+                signature: e.signature,
+                public_key: e.public_key,
+            })
+            .collect();
+        Self { proposal_id: wire.proposal_id, votes }
+    }
+}
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+struct BytesVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for BytesVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes", N)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        let bytes = hex::decode(value).map_err(E::custom)?;
+        self.visit_bytes(&bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        value.try_into().map_err(|_| E::invalid_length(value.len(), &self))
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(BytesVisitor::<N>)
+    } else {
+        deserializer.deserialize_bytes(BytesVisitor::<N>)
+    }
+}
+
+pub(crate) fn serialize_signature<S: Serializer>(signature: &Signature, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_bytes(&signature.to_bytes(), serializer)
+}
+
+pub(crate) fn deserialize_signature<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Signature, D::Error> {
+    Ok(Signature::from_bytes(&deserialize_bytes(deserializer)?))
+}
+
+pub(crate) fn serialize_public_key<S: Serializer>(key: &VerifyingKey, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_bytes(key.as_bytes(), serializer)
+}
+
+pub(crate) fn deserialize_public_key<'de, D: Deserializer<'de>>(deserializer: D) -> Result<VerifyingKey, D::Error> {
+    VerifyingKey::from_bytes(&deserialize_bytes(deserializer)?).map_err(de::Error::custom)
+}
+
+/// Canonical message covered by a `SignedVote` signature (see `VotePayload`). First-version approvals
+/// without a nonce keep the original three-part layout so signatures made before
+/// choices, versions, and nonces existed still verify.
+pub fn vote_message(
+    voter_id: &str,
+    proposal_id: &str,
+    timestamp: DateTime<Utc>,
+    choice: VoteChoice,
+    version: u64,
+    nonce: u64,
+) -> String {
+    VotePayload {
+        choice,
+        version,
+        nonce,
+        ..VotePayload::new(voter_id, proposal_id, timestamp.into())
+    }
+    .to_string()
+}
+
+/// `vote_message` extended with `metadata` as sorted JSON; empty metadata leaves the
+/// message unchanged, so votes signed before metadata existed still verify
+pub fn vote_message_with_metadata(base: String, metadata: &BTreeMap<String, String>) -> String {
+    if metadata.is_empty() {
+        return base;
+    }
+    format!("{}:m{}", base, serde_json::to_string(metadata).expect("metadata serializes"))
+}
+
+pub fn sign_vote(voter_id: &VoterId, signing_key: &SigningKey, timestamp: DateTime<Utc>) -> Signature {
     let message = format!("{}{}", voter_id, timestamp.to_rfc3339());
-    signing_key.sign(message.as_bytes())
+    DefaultScheme::sign(signing_key, message.as_bytes())
 }
 
 
@@ -36,16 +436,16 @@ pub fn sign_vote(voter_id: String, signing_key: &SigningKey, timestamp: DateTime
 mod tests {
     use super::*;
     use chrono::Utc;
-    use ed25519_dalek::{Verifier};
+    use ed25519_dalek::{Signer, Verifier};
 
     #[test]
     fn test_sign_vote_validity() {
         let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
         let verifying_key = signing_key.verifying_key();
 
-        let voter_id = "voter1".to_string();
+        let voter_id: VoterId = "voter1".parse().unwrap();
         let timestamp = Utc::now();
-        let sig = sign_vote(voter_id.clone(), &signing_key, timestamp);
+        let sig = sign_vote(&voter_id, &signing_key, timestamp);
 
         let message = format!("{}{}", voter_id, timestamp.to_rfc3339());
         assert!(verifying_key.verify(message.as_bytes(), &sig).is_ok());
@@ -66,12 +466,20 @@ mod tests {
         let signature = signing_key.sign(message.as_bytes());
 
         let vote = SignedVote {
-            voter_id: voter_id.clone(),
-            proposal_id: proposal_id.clone(),
+            voter_id: voter_id.parse().unwrap(),
+            proposal_id: proposal_id.parse().unwrap(),
             timestamp,
             original_weight: weight,
             decay_model: decay,
-            signature: signature.clone(),
+            decay_params: None,
+            choice: VoteChoice::Approve,
+            version: 0,
+            nonce: 0,
+            metadata: BTreeMap::new(),
+            stake: None,
+            expires_at: None,
+            payload_version: crate::payload::LEGACY_PAYLOAD_VERSION,
+            signature,
             public_key: verifying_key,
         };
 
@@ -93,4 +501,36 @@ mod tests {
         let _normal = ProposalType::Normal;
         let _critical = ProposalType::Critical;
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_signed_vote_wire_round_trip() {
+        let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
+        let vote = SignedVote::new(
+            "voter1".parse().unwrap(),
+            "proposal1".parse().unwrap(),
+            0.8,
+            Utc::now(),
+            DecayType::Stepped,
+            &signing_key,
+        );
+
+        let json = vote.to_json().unwrap();
+        assert!(json.contains(&hex::encode(vote.signature.to_bytes())));
+        assert!(json.contains("\"decay_model\":\"Stepped\""));
+        let cbor = vote.to_cbor().unwrap();
+        assert!(cbor.len() < json.len());
+
+        for decoded in [SignedVote::from_json(&json).unwrap(), SignedVote::from_cbor(&cbor).unwrap()] {
+            assert_eq!(decoded.signature, vote.signature);
+            assert_eq!(decoded.public_key, vote.public_key);
+            assert_eq!(decoded.timestamp, vote.timestamp);
+            assert!(decoded.verify(60).is_ok());
+        }
+
+        let truncated = json.replace(&hex::encode(vote.public_key.as_bytes()), "abcd");
+        assert!(SignedVote::from_json(&truncated).is_err());
+        assert!(SignedVote::from_cbor(&cbor[..cbor.len() - 1]).is_err());
+        let critical: ProposalType = serde_json::from_str("\"Critical\"").unwrap();
+        assert!(matches!(critical, ProposalType::Critical));
+    }
+}
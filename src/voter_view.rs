@@ -0,0 +1,231 @@
+// src/voter_view.rs
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::ids::{ProposalId, VoterId};
+use crate::proposal::ProposalRegistry;
+use crate::trust::TrustSource;
+use crate::units::{Seconds, Weight};
+use crate::vote::{DecayType, SignedVote};
+use crate::weight_engine::WeightEngine;
+
+/// Suggest re-affirming a vote once it keeps less than this share of its weight
+pub const REAFFIRM_BELOW: f64 = 0.5;
+
+/// Why casting a fresh version of a vote would help
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reaffirm {
+    /// The vote has decayed to `retained` of its weight; a fresh one counts in full
+    Faded { retained: f64 },
+    /// The voter's own expiry falls before the proposal closes
+    ExpiresBeforeClose { expires_at: DateTime<Utc> },
+    /// The voter's expiry has passed, so the vote counts as zero
+    Expired,
+}
+
+impl fmt::Display for Reaffirm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reaffirm::Faded { retained } => write!(f, "decayed to {:.0}% of its weight", retained * 100.0),
+            Reaffirm::ExpiresBeforeClose { expires_at } => write!(f, "expires at {}, before the proposal closes", expires_at),
+            Reaffirm::Expired => write!(f, "expired and no longer counts"),
+        }
+    }
+}
+
+/// One of a voter's votes on a proposal that is still open
+#[derive(Debug, Clone)]
+pub struct ActiveVote {
+    pub proposal_id: ProposalId,
+    pub cast_at: DateTime<Utc>,
+    pub decay_model: DecayType,
+    /// Weight before decay
+    pub effective_weight: Weight,
+    pub current_weight: Weight,
+    /// Until a stepped vote drops to its next step
+    pub next_step_in: Option<Seconds>,
+    pub expires_in: Option<Seconds>,
+    pub closes_in: Seconds,
+    pub reaffirm: Option<Reaffirm>,
+}
+
+impl ActiveVote {
+    /// Share of the pre-decay weight the vote still carries
+    pub fn retained(&self) -> f64 {
+        if self.effective_weight.get() > 0.0 { self.current_weight.get() / self.effective_weight.get() } else { 0.0 }
+    }
+}
+
+/// Where a voter's weight stands across every open proposal they voted on, most
+/// urgent first
+#[derive(Debug, Clone)]
+pub struct VoterDecayView {
+    pub voter_id: VoterId,
+    pub at: DateTime<Utc>,
+    pub votes: Vec<ActiveVote>,
+}
+
+impl VoterDecayView {
+    /// `voter_id`'s latest vote on each proposal `proposals` has open at `at`, weighed by `engine`
+    pub fn build(
+        voter_id: &VoterId,
+        votes: &[SignedVote],
+        engine: &WeightEngine,
+        trust: Option<&dyn TrustSource>,
+        proposals: &ProposalRegistry,
+        at: DateTime<Utc>,
+    ) -> Self {
+        let mut latest: BTreeMap<&ProposalId, &SignedVote> = BTreeMap::new();
+        for vote in votes.iter().filter(|v| v.voter_id == *voter_id && proposals.is_open(&v.proposal_id, at)) {
+            let entry = latest.entry(&vote.proposal_id).or_insert(vote);
+            if vote.version > entry.version {
+                *entry = vote;
+            }
+        }
+
+        let mut active: Vec<ActiveVote> = latest
+            .into_iter()
+            .filter_map(|(proposal_id, vote)| {
+                let closes_at = proposals.closes_at(proposal_id)?;
+                let explanation = engine.explain(vote, at, trust, None);
+                let mut view = ActiveVote {
+                    proposal_id: proposal_id.clone(),
+                    cast_at: vote.timestamp,
+                    decay_model: vote.decay_model,
+                    effective_weight: explanation.effective_weight,
+                    current_weight: explanation.final_weight,
+                    next_step_in: engine.decay_of(vote).next_step(explanation.age),
+                    expires_in: vote.expires_at.map(|e| Seconds::between(at, e).max(Seconds::ZERO)),
+                    closes_in: Seconds::between(at, closes_at),
+                    reaffirm: None,
+                };
+                view.reaffirm = match vote.expires_at {
+                    _ if explanation.expired => Some(Reaffirm::Expired),
+                    Some(expires_at) if expires_at < closes_at => Some(Reaffirm::ExpiresBeforeClose { expires_at }),
+                    _ if view.retained() < REAFFIRM_BELOW => Some(Reaffirm::Faded { retained: view.retained() }),
+                    _ => None,
+                };
+                Some(view)
+            })
+            .collect();
+        active.sort_by_key(|v| v.closes_in);
+        Self { voter_id: voter_id.clone(), at, votes: active }
+    }
+
+    pub fn total_weight(&self) -> Weight {
+        self.votes.iter().map(|v| v.current_weight).sum()
+    }
+
+    /// Votes worth casting again
+    pub fn suggestions(&self) -> impl Iterator<Item = (&ProposalId, &Reaffirm)> {
+        self.votes.iter().filter_map(|v| v.reaffirm.as_ref().map(|r| (&v.proposal_id, r)))
+    }
+}
+
+impl fmt::Display for VoterDecayView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "🗳️ {}: {} active vote(s), {} weight in all", self.voter_id, self.votes.len(), self.total_weight())?;
+        for vote in &self.votes {
+            write!(
+                f,
+                "  {:<16} {:<11} {} of {} ({:>3.0}%)",
+                vote.proposal_id.as_str(),
+                format!("{:?}", vote.decay_model),
+                vote.current_weight,
+                vote.effective_weight,
+                vote.retained() * 100.0
+            )?;
+            if let Some(next) = vote.next_step_in {
+                write!(f, ", next step in {}", next)?;
+            }
+            if let Some(expires_in) = vote.expires_in {
+                write!(f, ", expires in {}", expires_in)?;
+            }
+            writeln!(f, ", closes in {}", vote.closes_in)?;
+            if let Some(reaffirm) = &vote.reaffirm {
+                writeln!(f, "    ↻ re-affirm: {}", reaffirm)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::policy::ProposalPolicy;
+    use crate::proposal::Proposal;
+    use crate::vote::{ProposalType, VoteChoice};
+
+    fn open(registry: &mut ProposalRegistry, id: &str, opens_at: DateTime<Utc>) {
+        let key = SignedVote::generate_keypair();
+        let window = ProposalPolicy::for_type(&ProposalType::Budget).voting_window(opens_at, 10);
+        let proposal = Proposal::new(id.parse().unwrap(), "Fund the audit", ProposalType::Budget, &window, "carol".parse().unwrap(), &key);
+        registry.register(proposal).unwrap();
+    }
+
+    #[test]
+    fn test_view_lists_latest_votes_on_open_proposals() {
+        let key = SignedVote::generate_keypair();
+        let start = Utc::now() - Duration::seconds(3_600);
+        let at = start + Duration::seconds(400);
+        let mut proposals = ProposalRegistry::new();
+        for id in ["p1", "p2", "p3"] {
+            open(&mut proposals, id, start);
+        }
+        proposals.close(&"p3".parse().unwrap(), start + Duration::seconds(100)).unwrap();
+
+        let alice: VoterId = "alice".parse().unwrap();
+        let vote = |proposal: &str, age: i64, decay| {
+            SignedVote::new(alice.clone(), proposal.parse().unwrap(), 1.0, at - Duration::seconds(age), decay, &key)
+        };
+        let stepped = vote("p1", 100, DecayType::Stepped);
+        let faded = vote("p2", 390, DecayType::Linear);
+        let refreshed = faded.amend(1.0, at - Duration::seconds(10), VoteChoice::Approve, &key);
+        let votes = vec![
+            stepped,
+            faded,
+            refreshed,
+            vote("p3", 350, DecayType::Linear),
+            SignedVote::new("bob".parse().unwrap(), "p1".parse().unwrap(), 1.0, at, DecayType::Linear, &key),
+        ];
+
+        let view = VoterDecayView::build(&alice, &votes, &WeightEngine::new(), None, &proposals, at);
+        // p3 is closed, bob's vote is not alice's, and p2 shows only its latest version
+        let ids: Vec<&str> = view.votes.iter().map(|v| v.proposal_id.as_str()).collect();
+        assert_eq!(ids, vec!["p1", "p2"]);
+        let p1 = &view.votes[0];
+        assert_eq!(p1.next_step_in, Some(Seconds::new(80)));
+        assert!((p1.retained() - 0.8).abs() < 1e-9);
+        assert!(p1.reaffirm.is_none());
+        assert!((view.votes[1].current_weight.get() - 0.99).abs() < 1e-9);
+        assert!(view.to_string().contains("next step in"));
+    }
+
+    #[test]
+    fn test_faded_and_expiring_votes_are_flagged() {
+        let key = SignedVote::generate_keypair();
+        let start = Utc::now() - Duration::seconds(3_600);
+        let at = start + Duration::seconds(1_000);
+        let mut proposals = ProposalRegistry::new();
+        open(&mut proposals, "p1", start);
+        open(&mut proposals, "p2", start);
+        let alice: VoterId = "alice".parse().unwrap();
+
+        let faded = SignedVote::new(alice.clone(), "p1".parse().unwrap(), 1.0, start, DecayType::Linear, &key);
+        let expiring = SignedVote::new(alice.clone(), "p2".parse().unwrap(), 1.0, at, DecayType::Linear, &key)
+            .with_expiry(at + Duration::seconds(60), &key);
+        let view = VoterDecayView::build(&alice, &[faded, expiring], &WeightEngine::new(), None, &proposals, at);
+
+        let suggestions: BTreeMap<&str, &Reaffirm> = view.suggestions().map(|(id, r)| (id.as_str(), r)).collect();
+        assert!(matches!(suggestions["p1"], Reaffirm::Faded { retained } if (retained - 0.1).abs() < 1e-9));
+        assert!(matches!(suggestions["p2"], Reaffirm::ExpiresBeforeClose { .. }));
+        assert_eq!(view.votes.iter().find(|v| v.proposal_id.as_str() == "p2").unwrap().expires_in, Some(Seconds::new(60)));
+    }
+}
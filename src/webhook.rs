@@ -0,0 +1,413 @@
+// src/webhook.rs
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::discovery::{DiscoveryError, WebhookSink};
+use crate::report::FinalizationReport;
+use crate::units::Seconds;
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>`
+pub const SIGNATURE_HEADER: &str = "X-TDC-Signature";
+/// How far a delivery's `sent_at` may be from the receiver's clock
+pub const DEFAULT_REPLAY_WINDOW: Seconds = Seconds::new(300);
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum WebhookError {
+    #[error("Signature header is not of the form sha256=<hex>")]
+    MalformedSignature,
+    #[error("Signature does not match the body under the shared secret")]
+    InvalidSignature,
+    #[error("Body is not a signed outcome: {0}")]
+    MalformedBody(String),
+    #[error("Outcome sent at {sent_at} is outside the {window} replay window")]
+    Stale { sent_at: DateTime<Utc>, window: Seconds },
+    #[error("Outcome delivery {0} was already received")]
+    Replayed(String),
+    #[error("Delivery state could not be persisted: {0}")]
+    State(String),
+}
+
+/// What an outcome webhook tells its subscriber about a finalized proposal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct OutcomeNotice {
+    pub proposal_id: String,
+    pub proposal_type: String,
+    pub passed: bool,
+    pub tied: bool,
+    pub final_threshold: f64,
+    /// Weight the decision was made on: net weight when any vote opposed, else total
+    pub decided_weight: f64,
+    pub eligible_weight: f64,
+    pub finalized_at: DateTime<Utc>,
+    /// Hash of the certified report, for fetching and checking the full report
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_hash: Option<String>,
+}
+
+impl From<&FinalizationReport> for OutcomeNotice {
+    fn from(report: &FinalizationReport) -> Self {
+        Self {
+            proposal_id: report.proposal.proposal_id.clone(),
+            proposal_type: report.proposal.proposal_type.clone(),
+            passed: report.passed,
+            tied: report.tied,
+            final_threshold: report.final_threshold,
+            decided_weight: report.decided_weight(),
+            eligible_weight: report.tally.eligible_weight,
+            finalized_at: report.proposal.finalized_at,
+            report_hash: report.certificate.as_ref().map(|c| c.report_hash.clone()),
+        }
+    }
+}
+
+/// Body of one delivery attempt. The nonce is fixed per outcome and endpoint, so a
+/// receiver can drop redeliveries; `sent_at` is refreshed on every attempt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SignedOutcome {
+    pub nonce: String,
+    pub sent_at: DateTime<Utc>,
+    pub outcome: OutcomeNotice,
+}
+
+/// `SIGNATURE_HEADER` value for `body` under `secret`
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC takes keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Check `header` signs `body` under `secret`, in constant time
+pub fn verify_signature(secret: &[u8], header: &str, body: &[u8]) -> Result<(), WebhookError> {
+    let tag = header
+        .trim()
+        .strip_prefix("sha256=")
+        .and_then(|h| hex::decode(h).ok())
+        .ok_or(WebhookError::MalformedSignature)?;
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC takes keys of any length");
+    mac.update(body);
+    mac.verify_slice(&tag).map_err(|_| WebhookError::InvalidSignature)
+}
+
+/// Receiver-side checks for outcome webhooks: the signature, the replay window, and
+/// nonces already seen inside it. A `Replayed` error is a redelivery of an outcome
+/// already handled; acknowledge it with a 2xx so the sender stops retrying.
+#[derive(Debug, Clone)]
+pub struct OutcomeVerifier {
+    secret: Vec<u8>,
+    window: Seconds,
+    seen: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl OutcomeVerifier {
+    pub fn new(secret: impl Into<Vec<u8>>, window: Seconds) -> Self {
+        Self { secret: secret.into(), window, seen: BTreeMap::new() }
+    }
+
+    /// The outcome in `body` if `header` signs it, it was sent within the window of
+    /// `now`, and its nonce is new
+    pub fn verify(&mut self, header: &str, body: &[u8], now: DateTime<Utc>) -> Result<SignedOutcome, WebhookError> {
+        verify_signature(&self.secret, header, body)?;
+        let signed: SignedOutcome = serde_json::from_slice(body).map_err(|e| WebhookError::MalformedBody(e.to_string()))?;
+        let skew = Seconds::between(signed.sent_at, now);
+        if skew > self.window || skew < Seconds::ZERO - self.window {
+            return Err(WebhookError::Stale { sent_at: signed.sent_at, window: self.window });
+        }
+        // Anything older than the window is turned away as stale, so its nonce can go
+        let window = self.window;
+        self.seen.retain(|_, seen_at| Seconds::between(*seen_at, now) <= window);
+        if self.seen.contains_key(&signed.nonce) {
+            return Err(WebhookError::Replayed(signed.nonce));
+        }
+        self.seen.insert(signed.nonce.clone(), signed.sent_at);
+        Ok(signed)
+    }
+}
+
+/// Carries one delivery attempt; `Ok` only once the receiver has acknowledged it
+pub trait OutcomeTransport: Send + Sync {
+    fn post(&self, url: &str, headers: &[(&str, String)], body: &str) -> Result<(), DiscoveryError>;
+}
+
+/// Plain-HTTP delivery through `WebhookSink`, requiring a 2xx response
+pub struct HttpTransport;
+
+impl OutcomeTransport for HttpTransport {
+    fn post(&self, url: &str, headers: &[(&str, String)], body: &str) -> Result<(), DiscoveryError> {
+        WebhookSink::new(url)?.post_acknowledged(body, headers)
+    }
+}
+
+/// An outcome not yet acknowledged by one endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingDelivery {
+    pub nonce: String,
+    pub url: String,
+    pub outcome: OutcomeNotice,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Doubling delay between attempts, from `initial_delay` up to `max_delay`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub initial_delay: Seconds,
+    pub max_delay: Seconds,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { initial_delay: Seconds::new(10), max_delay: Seconds::new(3600) }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait after the `attempts`th failed attempt
+    pub fn delay(&self, attempts: u32) -> Seconds {
+        let factor = 1_i64.checked_shl(attempts.saturating_sub(1)).unwrap_or(i64::MAX);
+        self.initial_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Result of one `OutcomeDispatcher::deliver_due` pass
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeliveryRound {
+    pub delivered: usize,
+    pub failed: usize,
+    /// Still waiting for an acknowledgement, failed ones included
+    pub pending: usize,
+}
+
+/// Delivers signed finalization outcomes to webhook endpoints at least once. Every
+/// outcome is queued, and with a state file persisted, before its first attempt, and
+/// leaves the queue only when its endpoint acknowledges it, so a restart resumes
+/// the retries where they stopped.
+pub struct OutcomeDispatcher {
+    transport: Box<dyn OutcomeTransport>,
+    retry: RetryPolicy,
+    endpoints: BTreeMap<String, Vec<u8>>,
+    pending: Vec<PendingDelivery>,
+    state_path: Option<PathBuf>,
+}
+
+impl OutcomeDispatcher {
+    pub fn new(transport: Box<dyn OutcomeTransport>, retry: RetryPolicy) -> Self {
+        Self { transport, retry, endpoints: BTreeMap::new(), pending: Vec::new(), state_path: None }
+    }
+
+    /// Persist the queue to `path` after every change, resuming any queue already there.
+    /// Secrets are never written; register the endpoints again after a restart.
+    pub fn with_state(mut self, path: impl Into<PathBuf>) -> Result<Self, WebhookError> {
+        let path = path.into();
+        if path.exists() {
+            let json = std::fs::read_to_string(&path).map_err(|e| WebhookError::State(e.to_string()))?;
+            self.pending = serde_json::from_str(&json).map_err(|e| WebhookError::State(e.to_string()))?;
+        }
+        self.state_path = Some(path);
+        Ok(self)
+    }
+
+    /// Send future outcomes to `url`, signed with `secret`
+    pub fn add_endpoint(&mut self, url: &str, secret: impl Into<Vec<u8>>) {
+        self.endpoints.insert(url.to_string(), secret.into());
+    }
+
+    pub fn pending(&self) -> &[PendingDelivery] {
+        &self.pending
+    }
+
+    /// Queue `report`'s outcome for every endpoint, due at once; returns how many
+    pub fn enqueue(&mut self, report: &FinalizationReport, now: DateTime<Utc>) -> Result<usize, WebhookError> {
+        let outcome = OutcomeNotice::from(report);
+        for url in self.endpoints.keys() {
+            let mut nonce = [0u8; 16];
+            OsRng.fill_bytes(&mut nonce);
+            self.pending.push(PendingDelivery {
+                nonce: hex::encode(nonce),
+                url: url.clone(),
+                outcome: outcome.clone(),
+                attempts: 0,
+                next_attempt_at: now,
+                last_error: None,
+            });
+        }
+        self.persist()?;
+        Ok(self.endpoints.len())
+    }
+
+    /// Attempt every delivery due at `now`, dropping the acknowledged ones and
+    /// backing off the rest
+    pub fn deliver_due(&mut self, now: DateTime<Utc>) -> Result<DeliveryRound, WebhookError> {
+        let mut round = DeliveryRound::default();
+        let mut remaining = Vec::with_capacity(self.pending.len());
+        for mut delivery in std::mem::take(&mut self.pending) {
+            if delivery.next_attempt_at > now {
+                remaining.push(delivery);
+                continue;
+            }
+            let result = match self.endpoints.get(&delivery.url) {
+                Some(secret) => {
+                    let signed = SignedOutcome { nonce: delivery.nonce.clone(), sent_at: now, outcome: delivery.outcome.clone() };
+                    let body = serde_json::to_string(&signed).expect("signed outcomes serialize");
+                    let headers = [(SIGNATURE_HEADER, sign(secret, body.as_bytes()))];
+                    self.transport.post(&delivery.url, &headers, &body).map_err(|e| e.to_string())
+                }
+                None => Err("no secret registered for this endpoint".to_string()),
+            };
+            match result {
+                Ok(()) => round.delivered += 1,
+                Err(e) => {
+                    round.failed += 1;
+                    delivery.attempts += 1;
+                    delivery.next_attempt_at = now + self.retry.delay(delivery.attempts).to_duration();
+                    delivery.last_error = Some(e);
+                    remaining.push(delivery);
+                }
+            }
+        }
+        self.pending = remaining;
+        round.pending = self.pending.len();
+        self.persist()?;
+        Ok(round)
+    }
+
+    fn persist(&self) -> Result<(), WebhookError> {
+        let Some(path) = &self.state_path else {
+            return Ok(());
+        };
+        // Write beside the state file and rename, so a crash never leaves half a queue
+        let json = serde_json::to_string_pretty(&self.pending).expect("pending deliveries serialize");
+        let staged = path.with_extension("tmp");
+        std::fs::write(&staged, json)
+            .and_then(|_| std::fs::rename(&staged, path))
+            .map_err(|e| WebhookError::State(e.to_string()))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use chrono::Duration;
+
+    use crate::report::ReportInput;
+    use crate::threshold::DecisionRule;
+    use crate::trust::TrustEngine;
+    use crate::vote::{DecayType, ProposalType, SignedVote};
+
+    fn report(now: DateTime<Utc>) -> FinalizationReport {
+        let key = SignedVote::generate_keypair();
+        let votes = vec![SignedVote::new("alice".parse().unwrap(), "p1".parse().unwrap(), 1.0, now, DecayType::Linear, &key)];
+        let mut report = FinalizationReport::build(ReportInput {
+            proposal_id: "p1",
+            proposal_type: ProposalType::Normal,
+            opened_at: now - Duration::seconds(60),
+            finalized_at: now,
+            votes: &votes,
+            trust: &TrustEngine::new(),
+            max_age_secs: 3600,
+            admissions: None,
+            eligible_weight: None,
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        });
+        report.certify(&key);
+        report
+    }
+
+    /// Records every request, failing while `down` is set
+    #[derive(Clone, Default)]
+    struct Recorder {
+        down: Arc<Mutex<bool>>,
+        requests: Arc<Mutex<Vec<(String, String, String)>>>,
+    }
+
+    impl OutcomeTransport for Recorder {
+        fn post(&self, url: &str, headers: &[(&str, String)], body: &str) -> Result<(), DiscoveryError> {
+            if *self.down.lock().unwrap() {
+                return Err(DiscoveryError::Delivery("connection refused".to_string()));
+            }
+            self.requests.lock().unwrap().push((url.to_string(), headers[0].1.clone(), body.to_string()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_receiver_checks_signature_window_and_nonce() {
+        let now = Utc::now();
+        let signed = SignedOutcome { nonce: "n1".to_string(), sent_at: now, outcome: OutcomeNotice::from(&report(now)) };
+        let body = serde_json::to_vec(&signed).unwrap();
+        let header = sign(b"shh", &body);
+        let mut verifier = OutcomeVerifier::new("shh", DEFAULT_REPLAY_WINDOW);
+
+        assert_eq!(verifier.verify(&header, &body, now + Duration::seconds(5)), Ok(signed.clone()));
+        assert!(signed.outcome.report_hash.is_some());
+        assert_eq!(verifier.verify(&header, &body, now + Duration::seconds(6)), Err(WebhookError::Replayed("n1".to_string())));
+        assert_eq!(verifier.verify("sha256=zz", &body, now), Err(WebhookError::MalformedSignature));
+        assert_eq!(verify_signature(b"other", &header, &body), Err(WebhookError::InvalidSignature));
+
+        let mut tampered = body.clone();
+        let at = tampered.iter().position(|b| *b == b'1').unwrap();
+        tampered[at] = b'2';
+        assert_eq!(verifier.verify(&header, &tampered, now), Err(WebhookError::InvalidSignature));
+
+        let late = SignedOutcome { nonce: "n2".to_string(), ..signed };
+        let late = serde_json::to_vec(&late).unwrap();
+        assert!(matches!(
+            verifier.verify(&sign(b"shh", &late), &late, now + Duration::seconds(301)),
+            Err(WebhookError::Stale { .. })
+        ));
+    }
+
+    #[test]
+    fn test_failed_deliveries_are_retried_across_restarts() {
+        let path = std::env::temp_dir().join(format!("tdc-webhook-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let now = Utc::now();
+        let transport = Recorder::default();
+        let retry = RetryPolicy { initial_delay: Seconds::new(10), max_delay: Seconds::new(15) };
+        let dispatcher = |transport: &Recorder| {
+            let mut d = OutcomeDispatcher::new(Box::new(transport.clone()), retry).with_state(&path).unwrap();
+            d.add_endpoint("http://hooks.example/outcomes", "shh");
+            d
+        };
+
+        *transport.down.lock().unwrap() = true;
+        let mut first = dispatcher(&transport);
+        assert_eq!(first.enqueue(&report(now), now), Ok(1));
+        assert_eq!(first.deliver_due(now), Ok(DeliveryRound { delivered: 0, failed: 1, pending: 1 }));
+        assert_eq!(first.deliver_due(now + Duration::seconds(5)), Ok(DeliveryRound { delivered: 0, failed: 0, pending: 1 }));
+        assert_eq!(first.deliver_due(now + Duration::seconds(10)).unwrap().failed, 1);
+        assert_eq!(first.pending()[0].next_attempt_at, now + Duration::seconds(25));
+        drop(first);
+
+        // A restarted node picks the queue up from its state file
+        *transport.down.lock().unwrap() = false;
+        let mut restarted = dispatcher(&transport);
+        assert_eq!(restarted.pending()[0].attempts, 2);
+        let at = now + Duration::seconds(25);
+        assert_eq!(restarted.deliver_due(at), Ok(DeliveryRound { delivered: 1, failed: 0, pending: 0 }));
+        assert!(dispatcher(&transport).pending().is_empty());
+
+        let (url, header, body) = transport.requests.lock().unwrap()[0].clone();
+        assert_eq!(url, "http://hooks.example/outcomes");
+        let received = OutcomeVerifier::new("shh", DEFAULT_REPLAY_WINDOW).verify(&header, body.as_bytes(), at).unwrap();
+        assert_eq!((received.outcome.proposal_id.as_str(), received.sent_at), ("p1", at));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,18 +1,157 @@
-use crate::decay::{DecayModel, ExponentialDecay, LinearDecay, SteppedDecay};
-use crate::trust::TrustEngine;
-use crate::vote::{DecayType, SignedVote};
+use crate::carry_over::{CarryOver, ProposalSeries};
+use crate::committee::{Committee, CommitteeError, CommitteeVote};
+use crate::decay::{DecayBounds, DecayModel, VoteDecay};
+use crate::delegation::{DelegatedPower, DelegationGraph, VoteLineage};
+use crate::ids::{ProposalId, VoterId};
+use crate::registry::{ExitPolicy, ValidatorRegistry};
+use crate::score::{DecayingScore, ScoreEntry};
+use crate::stake::{StakeAuthorities, StakeBasis, StakeError};
+use crate::tally::Tally;
+use crate::trust::TrustSource;
+use crate::units::{Seconds, Weight};
+use crate::verify::BatchStatus;
+use crate::vote::{DecayType, SignedVote, SignedVoteBatch};
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 pub struct VoteRecord {
-    pub vote_id: String,
+    pub vote_id: VoterId,
     pub weight: f64,
     pub timestamp: DateTime<Utc>,
+    /// Weight the voter committed before any weighting transform or decay
+    pub committed_weight: f64,
+    pub weighting: WeightingMode,
+    /// The voter's own expiry had passed, so the vote counted as zero
+    pub expired: bool,
+}
+
+/// How committed weight becomes voting power, chosen per proposal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightingMode {
+    #[default]
+    Linear,
+    /// Power is the square root of the committed weight or stake, so influence costs
+    /// quadratically more
+    Quadratic,
+}
+
+impl WeightingMode {
+    pub fn apply(self, committed: Weight) -> Weight {
+        match self {
+            WeightingMode::Linear => committed,
+            WeightingMode::Quadratic => Weight::saturating(committed.get().sqrt()),
+        }
+    }
+}
+
+/// Every factor that went into a vote's effective weight
+#[derive(Debug, Clone)]
+pub struct WeightExplanation {
+    pub voter_id: VoterId,
+    pub original_weight: Weight,
+    pub stake: StakeBasis,
+    /// Power handed to this voter through delegation; it decays along with the vote
+    pub delegated_weight: Weight,
+    pub delegators: Vec<VoterId>,
+    /// The chain each delegator's power followed to this voter
+    pub lineage: Vec<VoteLineage>,
+    pub weighting: WeightingMode,
+    /// Original plus delegated weight after the weighting transform, before decay
+    pub effective_weight: Weight,
+    pub age: Seconds,
+    pub decay_model: DecayType,
+    pub decayed_weight: Weight,
+    pub trust_bonus: f64,
+    /// Below 1.0 while the voter was on probation when the vote was cast
+    pub probation_multiplier: f64,
+    /// The voter's role weight for the proposal's category; 1.0 when none applies
+    pub category_multiplier: f64,
+    /// Set when the voter has since exited and this vote predates the exit
+    pub exit_policy: Option<ExitPolicy>,
+    /// Set when the proposal is a later phase of a series with a carry-over policy
+    pub carry_over: Option<CarryOver>,
+    /// Members who signed, when this is a committee's multi-signature vote
+    pub committee_signers: Vec<VoterId>,
+    /// The voter's own expiry, after which the vote counts as zero
+    pub expires_at: Option<DateTime<Utc>>,
+    pub expired: bool,
+    pub final_weight: Weight,
+}
+
+impl fmt::Display for WeightExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "🔍 Weight for {}:", self.voter_id)?;
+        writeln!(f, "  original:  {}", self.original_weight)?;
+        match &self.stake {
+            StakeBasis::Declared => {}
+            StakeBasis::Attested { amount } => writeln!(f, "  stake:     attested {:.4}", amount)?,
+            StakeBasis::Unproven(e) => writeln!(f, "  stake:     unproven ({})", e)?,
+        }
+        if !self.committee_signers.is_empty() {
+            let signers: Vec<&str> = self.committee_signers.iter().map(VoterId::as_str).collect();
+            writeln!(f, "  signed by: {}", signers.join(", "))?;
+        }
+        if !self.delegators.is_empty() {
+            let delegators: Vec<&str> = self.delegators.iter().map(VoterId::as_str).collect();
+            writeln!(f, "  delegated: +{} from {}", self.delegated_weight, delegators.join(", "))?;
+            for lineage in &self.lineage {
+                writeln!(f, "    via {}", lineage)?;
+            }
+        }
+        if self.weighting == WeightingMode::Quadratic {
+            writeln!(f, "  quadratic: sqrt -> {}", self.effective_weight)?;
+        }
+        writeln!(f, "  decay:     {:?} over {} -> {}", self.decay_model, self.age, self.decayed_weight)?;
+        writeln!(f, "  trust:     x{:.2}", self.trust_bonus)?;
+        writeln!(f, "  probation: x{:.2}", self.probation_multiplier)?;
+        if self.category_multiplier != 1.0 {
+            writeln!(f, "  role:      x{:.2}", self.category_multiplier)?;
+        }
+        if let Some(policy) = self.exit_policy {
+            writeln!(f, "  exit:      {:?}", policy)?;
+        }
+        if let Some(carry) = &self.carry_over {
+            let prior = carry.prior_weight.map_or("absent".to_string(), |w| format!("prior {:.4}", w));
+            let clamped = if carry.clamped() { format!(", clamped from x{:.2}", carry.raw_multiplier) } else { String::new() };
+            writeln!(f, "  carry:     x{:.2} from {} ({}{})", carry.multiplier, carry.from_proposal, prior, clamped)?;
+        }
+        if let Some(expires_at) = self.expires_at {
+            let state = if self.expired { "expired" } else { "expires" };
+            writeln!(f, "  ttl:       {} {}", state, expires_at.to_rfc3339())?;
+        }
+        write!(f, "  final:     {}", self.final_weight)
+    }
+}
+
+/// `original` after `age` under the engine's standard rates for `decay`
+pub fn decay_weight(decay: DecayType, original: Weight, age: Seconds) -> Weight {
+    decay.decay(original, age)
+}
+
+/// Result of `WeightEngine::batch_calculate`; `tally` has no eligible weight set
+#[derive(Debug)]
+pub struct BatchTally {
+    pub weights: Vec<f64>,
+    pub tally: Tally,
+    pub status: BatchStatus,
 }
 
 pub struct WeightEngine {
-    cache: HashMap<String, f64>,
+    /// Weights already calculated, per voter on each proposal
+    cache: HashMap<(VoterId, ProposalId), f64>,
     history: Vec<VoteRecord>,
+    weighting: HashMap<ProposalId, WeightingMode>,
+    stake_authorities: Option<StakeAuthorities>,
+    decay_bounds: DecayBounds,
+}
+
+impl Default for WeightEngine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WeightEngine {
@@ -20,64 +159,335 @@ impl WeightEngine {
         Self {
             cache: HashMap::new(),
             history: Vec::new(),
+            weighting: HashMap::new(),
+            stake_authorities: None,
+            decay_bounds: DecayBounds::default(),
+        }
+    }
+
+    /// Hold the decay parameters votes are signed with to `bounds`; out-of-bounds
+    /// values are clamped rather than honored
+    pub fn set_decay_bounds(&mut self, bounds: DecayBounds) {
+        self.decay_bounds = bounds;
+    }
+
+    /// The decay `vote` is weighed under: its signed parameters, within bounds
+    pub fn decay_of(&self, vote: &SignedVote) -> VoteDecay {
+        self.decay_bounds.resolve(vote.decay_model, vote.decay_params.as_ref())
+    }
+
+    /// Weigh votes by their attested stake instead of their declared weight; votes
+    /// without a proof from one of `authorities` then carry no weight
+    pub fn set_stake_authorities(&mut self, authorities: StakeAuthorities) {
+        self.stake_authorities = Some(authorities);
+    }
+
+    /// The original weight `vote` counts with, and where it came from
+    pub fn stake_of(&self, vote: &SignedVote) -> (Weight, StakeBasis) {
+        let Some(authorities) = &self.stake_authorities else {
+            return (Weight::saturating(vote.original_weight), StakeBasis::Declared);
+        };
+        match vote.stake.as_ref().ok_or(StakeError::Missing).and_then(|s| s.verify(&vote.voter_id, authorities)) {
+            Ok(amount) => (Weight::saturating(amount), StakeBasis::Attested { amount }),
+            Err(e) => (Weight::ZERO, StakeBasis::Unproven(e)),
         }
     }
 
+    /// Weigh votes on `proposal_id` under `mode`; proposals not set use linear weighting
+    pub fn set_weighting(&mut self, proposal_id: ProposalId, mode: WeightingMode) {
+        self.weighting.insert(proposal_id, mode);
+    }
+
+    pub fn weighting(&self, proposal_id: &ProposalId) -> WeightingMode {
+        self.weighting.get(proposal_id).copied().unwrap_or_default()
+    }
+
     pub fn calculate_weight(
         &mut self,
         vote: &SignedVote,
         now: DateTime<Utc>,
-        trust: Option<&TrustEngine>,
+        trust: Option<&dyn TrustSource>,
     ) -> f64 {
-        if let Some(w) = self.cache.get(&vote.voter_id) {
-            return *w;
+        self.calculate(vote, now, trust).get()
+    }
+
+    /// Decayed, trust-adjusted weight of `vote` as of `now`
+    pub fn calculate(
+        &mut self,
+        vote: &SignedVote,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+    ) -> Weight {
+        self.calculate_with_registry(vote, now, trust, None)
+    }
+
+    /// Like `calculate`, also applying probation ramps from `registry`. Votes with an
+    /// expiry bypass the cache, since their weight drops to zero once `now` passes it.
+    pub fn calculate_with_registry(
+        &mut self,
+        vote: &SignedVote,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+        registry: Option<&ValidatorRegistry>,
+    ) -> Weight {
+        let key = (vote.voter_id.clone(), vote.proposal_id.clone());
+        let cacheable = vote.expires_at.is_none();
+        if cacheable && let Some(w) = self.cache.get(&key) {
+            return Weight::saturating(*w);
         }
 
-        let age = (now - vote.timestamp).num_seconds() as f64;
+        let explanation = self.explain(vote, now, trust, registry);
+        let weight = explanation.final_weight;
 
-        let mut weight = match vote.decay_model {
-            DecayType::Exponential => {
-                ExponentialDecay { rate: 0.005 }.compute_weight(vote.original_weight, age)
-            }
-            DecayType::Linear => {
-                LinearDecay { rate: 0.001 }.compute_weight(vote.original_weight, age)
-            }
-            DecayType::Stepped => SteppedDecay {
-                decay_steps: vec![(60.0, 0.8), (180.0, 0.5), (300.0, 0.2)],
-            }
-            .compute_weight(vote.original_weight, age),
-        };
+        if cacheable {
+            self.cache.insert(key, weight.get());
+        }
+        self.record(&explanation, now);
 
-        if let Some(trust_engine) = trust {
-            let bonus = trust_engine.get_bonus(&vote.voter_id);
-            weight *= bonus;
+        weight
+    }
+
+    /// Break down how `vote`'s weight is derived, bypassing the cache. Probation is
+    /// judged at the vote's own timestamp, so a vote cast on probation stays reduced.
+    /// Votes from validators that exited afterwards follow their `ExitPolicy`.
+    pub fn explain(
+        &self,
+        vote: &SignedVote,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+        registry: Option<&ValidatorRegistry>,
+    ) -> WeightExplanation {
+        self.explain_for_category(vote, now, trust, registry, None)
+    }
+
+    /// Like `explain`, also applying the voter's registry role weight for `category`
+    pub fn explain_for_category(
+        &self,
+        vote: &SignedVote,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+        registry: Option<&ValidatorRegistry>,
+        category: Option<&str>,
+    ) -> WeightExplanation {
+        self.explain_with_power(vote, now, trust, registry, category, DelegatedPower::default())
+    }
+
+    /// Like `explain`, adding the power of voters who delegated to `vote`'s voter and did
+    /// not vote on the proposal themselves. `cast` holds every vote on the proposal;
+    /// delegations are resolved as of `vote.timestamp`.
+    pub fn explain_delegated(
+        &self,
+        vote: &SignedVote,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+        registry: Option<&ValidatorRegistry>,
+        delegations: &DelegationGraph,
+        cast: &[SignedVote],
+    ) -> WeightExplanation {
+        let voted: HashSet<VoterId> = cast
+            .iter()
+            .filter(|v| v.proposal_id == vote.proposal_id)
+            .map(|v| v.voter_id.clone())
+            .chain([vote.voter_id.clone()])
+            .collect();
+        let power = delegations.delegated_to(&vote.voter_id, &vote.proposal_id, vote.timestamp, &voted);
+        self.explain_with_power(vote, now, trust, registry, None, power)
+    }
+
+    /// Delegated counterpart of `calculate_weight`; bypasses the cache, since the same
+    /// voter's weight depends on who else voted, but is still recorded in the history
+    pub fn calculate_delegated(
+        &mut self,
+        vote: &SignedVote,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+        delegations: &DelegationGraph,
+        cast: &[SignedVote],
+    ) -> Weight {
+        let explanation = self.explain_delegated(vote, now, trust, None, delegations, cast);
+        self.record(&explanation, now);
+        explanation.final_weight
+    }
+
+    /// Like `explain`, scaling by the carry-over from the previous phase of `series`
+    pub fn explain_in_series(
+        &self,
+        vote: &SignedVote,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+        registry: Option<&ValidatorRegistry>,
+        series: &ProposalSeries,
+    ) -> WeightExplanation {
+        let mut explanation = self.explain(vote, now, trust, registry);
+        if let Some(carry) = series.carry_over(&vote.voter_id, &vote.proposal_id) {
+            explanation.final_weight = explanation.final_weight.scale(carry.multiplier);
+            explanation.carry_over = Some(carry);
         }
+        explanation
+    }
 
-        self.cache.insert(vote.voter_id.clone(), weight);
-        self.history.push(VoteRecord {
-            vote_id: vote.voter_id.clone(),
-            weight,
-            timestamp: now,
+    /// Break down a committee's multi-signature vote after checking it against the
+    /// committee's key set. It counts once, with the committee's combined weight decaying
+    /// under the committee's model; trust is the mean bonus of the signing members.
+    pub fn explain_committee(
+        &self,
+        vote: &CommitteeVote,
+        committee: &Committee,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+    ) -> Result<WeightExplanation, CommitteeError> {
+        committee.verify(vote)?;
+        let signers = vote
+            .signatures
+            .iter()
+            .map(|s| s.member.parse())
+            .collect::<Result<Vec<VoterId>, _>>()?;
+        let age = Seconds::between(vote.timestamp, now);
+        let decayed = decay_weight(committee.decay, committee.weight, age);
+        let trust_bonus = trust.map_or(1.0, |t| {
+            signers.iter().map(|s| t.get_bonus(s)).sum::<f64>() / signers.len() as f64
         });
 
-        weight
+        Ok(WeightExplanation {
+            voter_id: committee.id.parse()?,
+            original_weight: committee.weight,
+            stake: StakeBasis::Declared,
+            delegated_weight: Weight::ZERO,
+            delegators: Vec::new(),
+            lineage: Vec::new(),
+            weighting: WeightingMode::Linear,
+            effective_weight: committee.weight,
+            age,
+            decay_model: committee.decay,
+            decayed_weight: decayed,
+            trust_bonus,
+            probation_multiplier: 1.0,
+            category_multiplier: 1.0,
+            exit_policy: None,
+            carry_over: None,
+            committee_signers: signers,
+            expires_at: None,
+            expired: false,
+            final_weight: decayed.scale(trust_bonus),
+        })
     }
 
-    #[allow(dead_code)]
+    fn explain_with_power(
+        &self,
+        vote: &SignedVote,
+        now: DateTime<Utc>,
+        trust: Option<&dyn TrustSource>,
+        registry: Option<&ValidatorRegistry>,
+        category: Option<&str>,
+        delegated: DelegatedPower,
+    ) -> WeightExplanation {
+        let exit = registry
+            .and_then(|r| r.exit(&vote.voter_id))
+            .filter(|e| e.covers(vote));
+        let decay_until = exit.map_or(now, |e| e.decay_until(now));
+        let age = Seconds::between(vote.timestamp, decay_until);
+        let (original, stake) = self.stake_of(vote);
+        let delegated_weight = Weight::saturating(delegated.weight);
+
+        let weighting = self.weighting(&vote.proposal_id);
+        let effective_weight = weighting.apply(original + delegated_weight);
+        let decayed = self.decay_of(vote).decay(effective_weight, age);
+
+        let trust_bonus = trust.map_or(1.0, |t| t.get_bonus(&vote.voter_id));
+        let probation_multiplier =
+            registry.map_or(1.0, |r| r.probation_multiplier(&vote.voter_id, vote.timestamp));
+        let exit_multiplier = exit.map_or(1.0, |e| e.weight_multiplier());
+        let expired = vote.is_expired(now);
+        let category_multiplier = match (registry, category) {
+            (Some(r), Some(c)) => r.category_weight(&vote.voter_id, c),
+            _ => 1.0,
+        };
+
+        WeightExplanation {
+            voter_id: vote.voter_id.clone(),
+            original_weight: original,
+            stake,
+            delegated_weight,
+            delegators: delegated.delegators,
+            lineage: delegated.lineage,
+            weighting,
+            effective_weight,
+            age,
+            decay_model: vote.decay_model,
+            decayed_weight: decayed,
+            trust_bonus,
+            probation_multiplier,
+            category_multiplier,
+            exit_policy: exit.map(|e| e.policy),
+            carry_over: None,
+            committee_signers: Vec::new(),
+            expires_at: vote.expires_at,
+            expired,
+            final_weight: if expired {
+                Weight::ZERO
+            } else {
+                decayed
+                    .scale(trust_bonus)
+                    .scale(probation_multiplier)
+                    .scale(category_multiplier)
+                    .scale(exit_multiplier)
+            },
+        }
+    }
+
+    /// `votes` as a decaying score keyed by voter, each vote credited with its
+    /// stake-resolved, weighted amount under its own decay model and trust bonus
+    pub fn score(&self, votes: &[SignedVote], trust: Option<&dyn TrustSource>) -> DecayingScore<VoterId> {
+        let mut score = DecayingScore::new(DecayType::Exponential);
+        for vote in votes {
+            let (original, _) = self.stake_of(vote);
+            score.push(ScoreEntry {
+                key: vote.voter_id.clone(),
+                amount: self.weighting(&vote.proposal_id).apply(original),
+                at: vote.timestamp,
+                decay: vote.decay_model,
+                scale: trust.map_or(1.0, |t| t.get_bonus(&vote.voter_id)),
+                expires_at: vote.expires_at,
+            });
+        }
+        score
+    }
+
+    /// Verify a batch and tally the votes that pass, each on the side its choice takes.
+    /// `weights` lines up with the batch; votes that fail verification weigh nothing.
     pub fn batch_calculate(
         &mut self,
-        votes: &[SignedVote],
+        batch: &SignedVoteBatch,
+        max_age: Seconds,
         now: DateTime<Utc>,
-        trust: Option<&TrustEngine>,
-    ) -> Vec<f64> {
-        votes
-            .iter()
-            .map(|v| self.calculate_weight(v, now, trust))
-            .collect()
+        trust: Option<&dyn TrustSource>,
+    ) -> BatchTally {
+        let status = batch.verify_at(now, max_age);
+        let mut weights = vec![0.0; batch.len()];
+        let mut tally = Tally::default();
+        for (i, vote) in batch.votes().iter().enumerate() {
+            if status.failed.iter().any(|(failed, _)| *failed == i) {
+                continue;
+            }
+            weights[i] = self.calculate_weight(vote, now, trust);
+            tally.record(vote.choice, weights[i]);
+        }
+        BatchTally { weights, tally, status }
+    }
+
+    fn record(&mut self, explanation: &WeightExplanation, now: DateTime<Utc>) {
+        self.history.push(VoteRecord {
+            vote_id: explanation.voter_id.clone(),
+            weight: explanation.final_weight.get(),
+            timestamp: now,
+            committed_weight: (explanation.original_weight + explanation.delegated_weight).get(),
+            weighting: explanation.weighting,
+            expired: explanation.expired,
+        });
     }
 
     #[allow(dead_code)]
-    pub fn get_weight_history(&self) -> &HashMap<String, f64> {
+    pub fn get_weight_history(&self) -> &HashMap<(VoterId, ProposalId), f64> {
         &self.cache
     }
 
@@ -98,43 +508,11 @@ impl WeightEngine {
 mod tests {
     use super::*;
     use crate::trust::TrustEngine;
-    use crate::vote::{DecayType, SignedVote};
+    use crate::vote::{DecayType, SignedVote, VoteChoice};
     use chrono::Utc;
     use ed25519_dalek::SigningKey;
     use signature::Signer;
 
-    // Mocked decay models
-    pub struct LinearDecay {
-        pub rate: f64,
-    }
-    pub struct ExponentialDecay {
-        pub rate: f64,
-    }
-    pub struct SteppedDecay {
-        pub decay_steps: Vec<(f64, f64)>,
-    }
-
-    impl LinearDecay {
-        pub fn compute_weight(&self, original: f64, age: f64) -> f64 {
-            (original - self.rate * age).max(0.0)
-        }
-    }
-    impl ExponentialDecay {
-        pub fn compute_weight(&self, original: f64, age: f64) -> f64 {
-            original * (-self.rate * age).exp()
-        }
-    }
-    impl SteppedDecay {
-        pub fn compute_weight(&self, original: f64, age: f64) -> f64 {
-            for (threshold, factor) in &self.decay_steps {
-                if age <= *threshold {
-                    return original * *factor;
-                }
-            }
-            0.0
-        }
-    }
-
     fn mock_signed_vote(decay: DecayType) -> SignedVote {
         let signing_key = SigningKey::generate(&mut rand::rngs::OsRng);
         let voter_id = "validator_001".to_string();
@@ -146,11 +524,19 @@ mod tests {
         let signature = signing_key.sign(msg.as_bytes());
 
         SignedVote {
-            voter_id,
-            proposal_id,
+            voter_id: voter_id.parse().unwrap(),
+            proposal_id: proposal_id.parse().unwrap(),
             timestamp,
             original_weight,
             decay_model: decay,
+            decay_params: None,
+            choice: VoteChoice::Approve,
+            version: 0,
+            nonce: 0,
+            metadata: Default::default(),
+            stake: None,
+            expires_at: None,
+            payload_version: crate::payload::LEGACY_PAYLOAD_VERSION,
             signature,
             public_key: signing_key.verifying_key(),
         }
@@ -164,7 +550,7 @@ mod tests {
 
         let weight = engine.calculate_weight(&vote, now, None);
         assert!(weight >= 0.0, "Weight should be non-negative");
-        assert!(engine.cache.contains_key(&vote.voter_id));
+        assert!(engine.cache.contains_key(&(vote.voter_id.clone(), vote.proposal_id.clone())));
         assert_eq!(engine.history.len(), 1);
     }
 
@@ -183,21 +569,89 @@ mod tests {
         );
     }
 
-    // #[test]
-    // fn test_batch_calculate() {
-    //     let mut engine = WeightEngine::new();
-    //     let now = Utc::now();
+    #[test]
+    fn test_expired_votes_count_as_zero() {
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let vote = SignedVote::new("validator_003".parse().unwrap(), "proposal_001".parse().unwrap(), 1.0, now, DecayType::Linear, &key)
+            .with_expiry(now + chrono::Duration::seconds(60), &key);
+        assert_eq!(vote.verify(10), Ok(()));
+        let mut extended = vote.clone();
+        extended.expires_at = None;
+        assert_eq!(extended.verify(10), Err(crate::verify::VerificationError::InvalidSignature));
+
+        let mut engine = WeightEngine::new();
+        assert!(engine.calculate_weight(&vote, now + chrono::Duration::seconds(30), None) > 0.0);
+        assert!(!engine.get_history()[0].expired);
+        engine.clear_cache();
+        assert_eq!(engine.calculate_weight(&vote, now + chrono::Duration::seconds(60), None), 0.0);
+        assert!(engine.get_history()[0].expired);
+        assert_eq!(engine.score(&[vote], None).total(now + chrono::Duration::seconds(90)), Weight::ZERO);
+    }
+
+    #[test]
+    fn test_expiring_votes_are_not_served_from_the_cache() {
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let vote = SignedVote::new("validator_003".parse().unwrap(), "proposal_001".parse().unwrap(), 1.0, now, DecayType::Linear, &key)
+            .with_expiry(now + chrono::Duration::seconds(60), &key);
+
+        let mut engine = WeightEngine::new();
+        assert!(engine.calculate_weight(&vote, now + chrono::Duration::seconds(30), None) > 0.0);
+        assert_eq!(engine.calculate_weight(&vote, now + chrono::Duration::seconds(90), None), 0.0);
+        assert!(engine.cache.is_empty());
+        assert!(engine.get_history()[1].expired);
+    }
+
+    #[test]
+    fn test_score_matches_calculated_weights() {
+        let engine = WeightEngine::new();
+        let trust = TrustEngine::new();
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let votes: Vec<SignedVote> = [("validator_001", DecayType::Linear, 400), ("carol", DecayType::Stepped, 200)]
+            .iter()
+            .map(|(voter, decay, age)| {
+                SignedVote::new(voter.parse().unwrap(), "proposal_001".parse().unwrap(), 2.0, now - chrono::Duration::seconds(*age), *decay, &key)
+            })
+            .collect();
+
+        let score = engine.score(&votes, Some(&trust));
+        for vote in &votes {
+            let expected = engine.explain(vote, now, Some(&trust), None).final_weight.get();
+            assert!((score.value(&vote.voter_id, now).get() - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_batch_calculate() {
+        let mut engine = WeightEngine::new();
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let vote = |voter: &str, choice| {
+            SignedVote::new_with_choice(voter.parse().unwrap(), "proposal_001".parse().unwrap(), 1.0, now, DecayType::Linear, choice, &key)
+        };
+        let mut forged = vote("validator_004", VoteChoice::Approve);
+        forged.voter_id = "validator_003".parse().unwrap();
+        let batch = SignedVoteBatch::from_votes(
+            "proposal_001".parse().unwrap(),
+            vec![vote("validator_001", VoteChoice::Approve), vote("validator_002", VoteChoice::Reject), forged],
+        )
+        .unwrap();
+        assert!(SignedVoteBatch::new("proposal_002".parse().unwrap()).push(vote("validator_001", VoteChoice::Approve)).is_err());
 
-    //     let votes = vec![
-    //         mock_signed_vote(DecayType::Linear),
-    //         mock_signed_vote(DecayType::Exponential),
-    //         mock_signed_vote(DecayType::Stepped),
-    //     ];
+        let decoded = SignedVoteBatch::from_cbor(&batch.to_cbor().unwrap()).unwrap();
+        let json = batch.to_json().unwrap();
+        assert_eq!(json.matches("proposal_001").count(), 1);
+        assert_eq!(SignedVoteBatch::from_json(&json).unwrap().votes()[1].choice, VoteChoice::Reject);
 
-    //     let weights = engine.batch_calculate(&votes, now, None);
-    //     assert_eq!(weights.len(), votes.len());
-    //     assert_eq!(engine.history.len(), weights.len());
-    // }
+        let result = engine.batch_calculate(&decoded, Seconds::new(300), now, None);
+        assert_eq!(result.weights, vec![1.0, 1.0, 0.0]);
+        assert_eq!(result.status.verified, 2);
+        assert_eq!(result.status.failed[0].0, 2);
+        assert_eq!((result.tally.approve_weight, result.tally.reject_weight, result.tally.votes), (1.0, 1.0, 2));
+        assert_eq!(engine.history.len(), 2);
+    }
 
     #[test]
     fn test_clear_cache() {
@@ -214,4 +668,260 @@ mod tests {
         assert!(engine.cache.is_empty());
         assert!(engine.history.is_empty());
     }
+
+    #[test]
+    fn test_probation_applied_and_explained() {
+        use crate::registry::{ProbationPolicy, RampCurve, ValidatorRegistry};
+
+        let vote = mock_signed_vote(DecayType::Linear);
+        let now = Utc::now();
+        let mut registry = ValidatorRegistry::new();
+        registry.onboard(
+            vote.voter_id.to_string(),
+            vote.public_key,
+            vote.timestamp,
+            ProbationPolicy {
+                period: Seconds::new(3600),
+                initial_multiplier: 0.5,
+                curve: RampCurve::Linear,
+            },
+        );
+
+        let trust = TrustEngine::new();
+        let full = WeightEngine::new().calculate(&vote, now, Some(&trust));
+        let mut engine = WeightEngine::new();
+        let reduced = engine.calculate_with_registry(&vote, now, Some(&trust), Some(&registry));
+        assert!((reduced.get() - full.get() * 0.5).abs() < 1e-9);
+
+        let explanation = engine.explain(&vote, now, Some(&trust), Some(&registry));
+        assert_eq!(explanation.probation_multiplier, 0.5);
+        assert_eq!(explanation.trust_bonus, 1.2);
+        assert_eq!(explanation.final_weight, reduced);
+        assert!(explanation.to_string().contains("probation: x0.50"));
+    }
+
+    #[test]
+    fn test_exit_policies_for_outstanding_votes() {
+        use crate::registry::ValidatorRegistry;
+        use chrono::Duration;
+
+        let vote = mock_signed_vote(DecayType::Linear);
+        let exited_at = vote.timestamp + Duration::seconds(10);
+        let now = vote.timestamp + Duration::seconds(200);
+        let weight_under = |policy: ExitPolicy| {
+            let mut registry = ValidatorRegistry::new();
+            registry.register(vote.voter_id.to_string(), vote.public_key);
+            registry
+                .request_exit(&vote.voter_id, exited_at, policy, Seconds::new(60))
+                .unwrap();
+            WeightEngine::new().explain(&vote, now, None, Some(&registry))
+        };
+
+        let plain = WeightEngine::new().explain(&vote, now, None, None);
+        let keep = weight_under(ExitPolicy::KeepDecaying);
+        assert_eq!(keep.final_weight, plain.final_weight);
+        assert_eq!(keep.exit_policy, Some(ExitPolicy::KeepDecaying));
+
+        let frozen = weight_under(ExitPolicy::Freeze);
+        assert_eq!(frozen.age, Seconds::new(10));
+        assert!(frozen.final_weight > plain.final_weight);
+        assert_eq!(frozen.final_weight, weight_under(ExitPolicy::Freeze).final_weight);
+
+        assert_eq!(weight_under(ExitPolicy::Remove).final_weight, Weight::ZERO);
+    }
+
+    #[test]
+    fn test_category_role_weights() {
+        use crate::registry::{RegistryError, ValidatorRegistry};
+
+        let vote = mock_signed_vote(DecayType::Linear);
+        let now = Utc::now();
+        let mut registry = ValidatorRegistry::new();
+        registry.register(vote.voter_id.to_string(), vote.public_key);
+        registry.set_category_weight(&vote.voter_id, "parameter_change", 2.0).unwrap();
+        assert_eq!(
+            registry.set_category_weight("nobody", "parameter_change", 2.0),
+            Err(RegistryError::UnknownValidator("nobody".to_string()))
+        );
+        assert!(registry.set_category_weight(&vote.voter_id, "treasury", -1.0).is_err());
+
+        let engine = WeightEngine::new();
+        let plain = engine.explain(&vote, now, None, Some(&registry));
+        let technical = engine.explain_for_category(&vote, now, None, Some(&registry), Some("parameter_change"));
+        let other = engine.explain_for_category(&vote, now, None, Some(&registry), Some("treasury"));
+        assert_eq!(technical.category_multiplier, 2.0);
+        assert!((technical.final_weight.get() - plain.final_weight.get() * 2.0).abs() < 1e-9);
+        assert_eq!(other.final_weight, plain.final_weight);
+        assert!(technical.to_string().contains("role:      x2.00"));
+    }
+
+    #[test]
+    fn test_delegated_weight_decays_with_vote() {
+        use crate::delegation::{Delegation, DelegationScope};
+
+        let vote = mock_signed_vote(DecayType::Linear);
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let mut graph = DelegationGraph::new();
+        for delegator in ["delegator_a", "delegator_b"] {
+            graph
+                .add(Delegation::new(
+                    delegator.parse().unwrap(),
+                    vote.voter_id.clone(),
+                    DelegationScope::Proposal(vote.proposal_id.clone()),
+                    0.5,
+                    vote.timestamp,
+                    &key,
+                ))
+                .unwrap();
+        }
+        let mut direct = vote.clone();
+        direct.voter_id = "delegator_b".parse().unwrap();
+
+        let engine = WeightEngine::new();
+        let plain = engine.explain(&vote, now, None, None);
+        let delegated = engine.explain_delegated(&vote, now, None, None, &graph, std::slice::from_ref(&direct));
+        assert_eq!(delegated.delegators, vec!["delegator_a".parse::<VoterId>().unwrap()]);
+        assert_eq!(delegated.delegated_weight, Weight::saturating(0.5));
+        assert_eq!(delegated.decayed_weight, decay_weight(DecayType::Linear, Weight::saturating(1.5), plain.age));
+        assert!(delegated.final_weight > plain.final_weight);
+        assert!(delegated.to_string().contains("delegated: +0.5000 from delegator_a"));
+        assert!(delegated.to_string().contains(&format!("via delegator_a -> {} (delegated 0.5000)", vote.voter_id)));
+
+        let mut engine = WeightEngine::new();
+        engine.calculate_delegated(&vote, now, None, &graph, &[]);
+        assert!(engine.cache.is_empty());
+        assert_eq!(engine.history.len(), 1);
+    }
+
+    #[test]
+    fn test_carry_over_in_series_is_explained() {
+        use crate::carry_over::CarryOverPolicy;
+
+        let vote = mock_signed_vote(DecayType::Linear);
+        let now = Utc::now();
+        let mut series = ProposalSeries::new("budget", Some(CarryOverPolicy::new(0.5, 1.0, 0.5, 1.2).unwrap()));
+        series.add_phase("proposal_000".parse().unwrap()).unwrap();
+        series.add_phase(vote.proposal_id.clone()).unwrap();
+        series
+            .close_phase(&"proposal_000".parse().unwrap(), [(vote.voter_id.clone(), 0.8)])
+            .unwrap();
+
+        let engine = WeightEngine::new();
+        let plain = engine.explain(&vote, now, None, None);
+        let carried = engine.explain_in_series(&vote, now, None, None, &series);
+        assert_eq!(carried.carry_over.as_ref().unwrap().multiplier, 1.2);
+        assert!((carried.final_weight.get() - plain.final_weight.get() * 1.2).abs() < 1e-9);
+        assert!(carried.to_string().contains("carry:     x1.20 from proposal_000 (prior 0.8000, clamped from x1.40)"));
+        assert!(plain.carry_over.is_none());
+    }
+
+    #[test]
+    fn test_committee_vote_counts_combined_weight() {
+        use crate::registry::ValidatorRegistry;
+        use crate::vote::VoteChoice;
+
+        let keys: Vec<_> = (0..2).map(|_| SignedVote::generate_keypair()).collect();
+        let mut registry = ValidatorRegistry::new();
+        for (id, key) in ["validator_001", "validator_003"].iter().zip(&keys) {
+            registry.register(id.to_string(), key.verifying_key());
+        }
+        let committee = Committee::new(
+            "council",
+            &["validator_001", "validator_003"],
+            Weight::saturating(4.0),
+            DecayType::Linear,
+            1.0,
+            &registry,
+        )
+        .unwrap();
+        let now = Utc::now();
+        let signers = [("validator_001", &keys[0]), ("validator_003", &keys[1])];
+        let vote = CommitteeVote::sign(&committee, "proposal_001", VoteChoice::Approve, now, &signers);
+
+        let engine = WeightEngine::new();
+        let trust = TrustEngine::new();
+        let explanation = engine.explain_committee(&vote, &committee, now, Some(&trust)).unwrap();
+        assert_eq!(explanation.voter_id.as_str(), "council");
+        assert_eq!(explanation.committee_signers.len(), 2);
+        assert!((explanation.trust_bonus - 1.1).abs() < 1e-9);
+        assert!((explanation.final_weight.get() - 4.4).abs() < 1e-9);
+        assert!(explanation.to_string().contains("signed by: validator_001, validator_003"));
+
+        let partial = CommitteeVote::sign(&committee, "proposal_001", VoteChoice::Approve, now, &signers[..1]);
+        assert!(matches!(
+            engine.explain_committee(&partial, &committee, now, None),
+            Err(CommitteeError::BelowThreshold { signed: 1, required: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_quadratic_weighting_per_proposal() {
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let vote = |proposal: &str| {
+            SignedVote::new("whale".parse().unwrap(), proposal.parse().unwrap(), 16.0, now, DecayType::Linear, &key)
+        };
+        let mut engine = WeightEngine::new();
+        engine.set_weighting("proposal_q".parse().unwrap(), WeightingMode::Quadratic);
+
+        let quadratic = engine.explain(&vote("proposal_q"), now, None, None);
+        assert_eq!(quadratic.effective_weight, Weight::saturating(4.0));
+        assert_eq!(quadratic.final_weight, Weight::saturating(4.0));
+        assert!(quadratic.to_string().contains("quadratic: sqrt -> 4.0000"));
+        assert_eq!(engine.explain(&vote("proposal_l"), now, None, None).final_weight, Weight::saturating(16.0));
+
+        engine.calculate_weight(&vote("proposal_q"), now, None);
+        let record = &engine.get_history()[0];
+        assert_eq!((record.committed_weight, record.weight, record.weighting), (16.0, 4.0, WeightingMode::Quadratic));
+        assert_eq!(engine.calculate_weight(&vote("proposal_l"), now, None), 16.0);
+        assert_eq!(engine.calculate_weight(&vote("proposal_q"), now, None), 4.0);
+    }
+
+    #[test]
+    fn test_attested_stake_replaces_declared_weight() {
+        use crate::stake::StakeAttestation;
+
+        let now = Utc::now();
+        let authority = SignedVote::generate_keypair();
+        let key = SignedVote::generate_keypair();
+        let declared = SignedVote::new("alice".parse().unwrap(), "proposal_001".parse().unwrap(), 1000.0, now, DecayType::Linear, &key);
+        let proven = declared.clone().with_stake(StakeAttestation::new("alice".parse().unwrap(), 40.0, now, &authority), &key);
+        assert_eq!(proven.verify(60), Ok(()));
+        let mut stripped = proven.clone();
+        stripped.stake = None;
+        assert!(stripped.verify(60).is_err());
+
+        let mut engine = WeightEngine::new();
+        assert_eq!(engine.explain(&declared, now, None, None).final_weight, Weight::saturating(1000.0));
+        engine.set_stake_authorities(StakeAuthorities::new([authority.verifying_key()]));
+        let attested = engine.explain(&proven, now, None, None);
+        assert_eq!((attested.stake.clone(), attested.final_weight), (StakeBasis::Attested { amount: 40.0 }, Weight::saturating(40.0)));
+        assert!(attested.to_string().contains("stake:     attested 40.0000"));
+        let unproven = engine.explain(&declared, now, None, None);
+        assert_eq!((unproven.stake, unproven.final_weight), (StakeBasis::Unproven(StakeError::Missing), Weight::ZERO));
+    }
+
+    #[test]
+    fn test_signed_decay_params_are_weighed_within_bounds() {
+        use crate::decay::{DecayBounds, DecayParams};
+
+        let now = Utc::now();
+        let key = SignedVote::generate_keypair();
+        let cast = now - chrono::Duration::seconds(100);
+        let plain = SignedVote::new("alice".parse().unwrap(), "proposal_001".parse().unwrap(), 1.0, cast, DecayType::Linear, &key);
+        let fast = plain.clone().with_decay_params(DecayParams { rate: Some(0.004), ..DecayParams::default() }, &key);
+        assert_eq!(fast.verify(300), Ok(()));
+        let mut tampered = fast.clone();
+        tampered.decay_params = Some(DecayParams { rate: Some(0.0001), ..DecayParams::default() });
+        assert_eq!(tampered.verify(300), Err(crate::verify::VerificationError::InvalidSignature));
+
+        let mut engine = WeightEngine::new();
+        assert!((engine.explain(&plain, now, None, None).final_weight.get() - 0.9).abs() < 1e-9);
+        assert!((engine.explain(&fast, now, None, None).final_weight.get() - 0.6).abs() < 1e-9);
+
+        // A server allowing slower decay only clamps the rate the voter signed
+        engine.set_decay_bounds(DecayBounds { max_rate: 0.002, ..DecayBounds::default() });
+        assert!((engine.explain(&fast, now, None, None).final_weight.get() - 0.8).abs() < 1e-9);
+    }
 }
@@ -1,4 +1,8 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Utc};
+
+use crate::clock::Clock;
+use crate::hooks::{ExtensionPolicy, HookError};
+use crate::units::Seconds;
 
 #[derive(Debug, Clone, Copy)]
 pub enum WindowType {
@@ -29,15 +33,37 @@ impl VotingWindow {
         }
     }
 
+    pub fn duration(&self) -> Seconds {
+        Seconds::try_from(self.duration_secs).unwrap_or(Seconds::new(i64::MAX))
+    }
+
+    pub fn grace(&self) -> Seconds {
+        Seconds::try_from(self.grace_secs).unwrap_or(Seconds::new(i64::MAX))
+    }
+
+    /// Last instant the window accepts votes, grace included
+    pub fn closes_at(&self) -> DateTime<Utc> {
+        self.start_time
+            .checked_add_signed((self.duration() + self.grace()).to_duration())
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
     pub fn is_open(&self, now: DateTime<Utc>) -> bool {
-        let deadline =
-            self.start_time + Duration::seconds((self.duration_secs + self.grace_secs) as i64);
-        now <= deadline
+        Seconds::between(self.start_time, now) <= self.duration() + self.grace()
+    }
+
+    /// `is_open` as of `clock`'s current time
+    pub fn is_open_now(&self, clock: &dyn Clock) -> bool {
+        self.is_open(clock.now())
     }
 
     pub fn time_left(&self, now: DateTime<Utc>) -> i64 {
-        let deadline = self.start_time + Duration::seconds(self.duration_secs as i64);
-        (deadline - now).num_seconds()
+        self.remaining(now).as_i64()
+    }
+
+    /// Time until the window closes, excluding grace; negative once it has closed
+    pub fn remaining(&self, now: DateTime<Utc>) -> Seconds {
+        self.duration() - Seconds::between(self.start_time, now)
     }
 
     pub fn should_extend(
@@ -55,6 +81,26 @@ impl VotingWindow {
         self.duration_secs += extra_secs;
         println!("⏳ Voting window extended by {} seconds!", extra_secs);
     }
+
+    /// Typed form of `extend`; negative spans are ignored
+    pub fn extend_by(&mut self, extra: Seconds) {
+        self.extend(extra.as_u64_saturating());
+    }
+
+    /// Ask a pluggable policy whether to extend, and apply its answer
+    pub fn apply_extension(
+        &mut self,
+        now: DateTime<Utc>,
+        current_weight: f64,
+        current_threshold: f64,
+        policy: &dyn ExtensionPolicy,
+    ) -> Result<u64, HookError> {
+        let extra_secs = policy.extension_secs(self.time_left(now), current_weight, current_threshold)?;
+        if extra_secs > 0 {
+            self.extend(extra_secs);
+        }
+        Ok(extra_secs)
+    }
 }
 
 
@@ -113,7 +159,7 @@ mod tests {
     #[test]
     fn test_should_extend() {
         let now = Utc::now();
-        let mut vw = VotingWindow::new(now, WindowType::Short, 10);
+        let vw = VotingWindow::new(now, WindowType::Short, 10);
 
         // Move close to end
         let near_end =
@@ -136,4 +182,18 @@ mod tests {
         vw.extend(60);
         assert_eq!(vw.duration_secs, original_duration + 60);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_apply_extension_policy() {
+        use crate::hooks::CloseCallExtension;
+
+        let now = Utc::now();
+        let mut vw = VotingWindow::new(now, WindowType::Short, 10);
+        let policy = CloseCallExtension { extra_secs: 30 };
+
+        assert_eq!(vw.apply_extension(now, 95.0, 100.0, &policy), Ok(0));
+        let near_end = now + Duration::seconds((vw.duration_secs - 15) as i64);
+        assert_eq!(vw.apply_extension(near_end, 95.0, 100.0, &policy), Ok(30));
+        assert_eq!(vw.duration_secs, 330);
+    }
+}
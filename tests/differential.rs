@@ -0,0 +1,41 @@
+// tests/differential.rs
+//
+// Differential fuzzing of the float and fixed-point tally paths over many seeded vote
+// streams. Sensitive parameter regions are printed; run with `--nocapture` to see them.
+
+use time_decay_consensus::differential::{run, DifferentialConfig};
+use time_decay_consensus::trust::TrustEngine;
+
+#[test]
+fn float_and_fixed_paths_agree_across_seeds() {
+    let trust = TrustEngine::new();
+    for seed in 0..8 {
+        let report = run(&DifferentialConfig { seed, ..DifferentialConfig::default() }, &trust);
+        assert!(
+            report.agrees(),
+            "seed {} disagreed (max difference {:e}):\n{}",
+            seed,
+            report.max_difference,
+            serde_json::to_string_pretty(&report.disagreements).unwrap()
+        );
+        for (region, count) in &report.regions {
+            println!("seed {}: {} near-threshold case(s) in {}", seed, count, region);
+        }
+    }
+}
+
+#[test]
+fn heavy_and_old_streams_stay_within_tolerance() {
+    let report = run(
+        &DifferentialConfig {
+            seed: 99,
+            max_votes: 40,
+            max_age_secs: 86_400,
+            max_weight: 1_000.0,
+            tolerance: 1e-4,
+            ..DifferentialConfig::default()
+        },
+        &TrustEngine::new(),
+    );
+    assert!(report.agrees(), "max difference {:e}", report.max_difference);
+}
@@ -0,0 +1,93 @@
+// tests/golden_simulation.rs
+//
+// Seeded simulation reports compared against committed golden files, so changes to
+// decay or threshold behaviour show up as diffs. Regenerate after an intended change
+// with `UPDATE_GOLDEN=1 cargo test --test golden_simulation`.
+
+use std::path::PathBuf;
+
+use chrono::{TimeZone, Utc};
+use serde_json::Value;
+
+use time_decay_consensus::progress::CancellationToken;
+use time_decay_consensus::simulation::{simulate, SimulationConfig};
+
+const TOLERANCE: f64 = 1e-9;
+
+fn golden_path(seed: u64) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("simulation_seed_{}.json", seed))
+}
+
+/// Paths where `actual` differs from `expected`; numbers compare within `TOLERANCE`
+fn differences(path: &str, expected: &Value, actual: &Value, out: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Number(e), Value::Number(a)) => {
+            let (e, a) = (e.as_f64().unwrap(), a.as_f64().unwrap());
+            if (e - a).abs() > TOLERANCE {
+                out.push(format!("{}: expected {}, got {}", path, e, a));
+            }
+        }
+        (Value::Array(e), Value::Array(a)) if e.len() == a.len() => {
+            for (i, (e, a)) in e.iter().zip(a).enumerate() {
+                differences(&format!("{}[{}]", path, i), e, a, out);
+            }
+        }
+        (Value::Object(e), Value::Object(a)) if e.len() == a.len() => {
+            for (key, e) in e {
+                match a.get(key) {
+                    Some(a) => differences(&format!("{}.{}", path, key), e, a, out),
+                    None => out.push(format!("{}.{}: missing", path, key)),
+                }
+            }
+        }
+        (e, a) if e != a => out.push(format!("{}: expected {}, got {}", path, e, a)),
+        _ => {}
+    }
+}
+
+fn check_golden(seed: u64) {
+    let report = simulate(
+        &SimulationConfig {
+            seed,
+            now: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+        },
+        &CancellationToken::new(),
+    );
+    let actual = report.to_json();
+    let path = golden_path(seed);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual + "\n").unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("missing golden file {}: {} (set UPDATE_GOLDEN=1)", path.display(), e));
+    let mut diffs = Vec::new();
+    differences(
+        "$",
+        &serde_json::from_str(&expected).unwrap(),
+        &serde_json::from_str(&actual).unwrap(),
+        &mut diffs,
+    );
+    assert!(
+        diffs.is_empty(),
+        "simulation for seed {} drifted from {}:\n{}",
+        seed,
+        path.display(),
+        diffs.join("\n")
+    );
+}
+
+#[test]
+fn golden_seed_1() {
+    check_golden(1);
+}
+
+#[test]
+fn golden_seed_42() {
+    check_golden(42);
+}
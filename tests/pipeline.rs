@@ -0,0 +1,251 @@
+// tests/pipeline.rs
+//
+// End-to-end runs of the voting pipeline through the public API: keys, proposal,
+// signed votes, decay, escalation, extension, finalization, certificate, history.
+
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::SigningKey;
+
+use time_decay_consensus::blockchain::{FinalizationEntry, Transaction, VoteEntry};
+use time_decay_consensus::explorer;
+use time_decay_consensus::history::{HistoryAnalyzer, VoteRecord};
+use time_decay_consensus::hooks::CloseCallExtension;
+use time_decay_consensus::namespace::{NamespaceConfig, NamespaceManager};
+use time_decay_consensus::registry::{Revocation, ValidatorRegistry};
+use time_decay_consensus::report::{FinalizationReport, ReportInput};
+use time_decay_consensus::threshold::{DecisionRule, ThresholdEscalator};
+use time_decay_consensus::trust::TrustEngine;
+use time_decay_consensus::vote::{DecayType, ProposalType, SignedVote};
+use time_decay_consensus::weight_engine::WeightEngine;
+use time_decay_consensus::window::{VotingWindow, WindowType};
+
+const PROPOSAL: &str = "proposal_upgrade";
+const VOTERS: [&str; 5] = ["validator_001", "validator_002", "carol", "dave", "erin"];
+
+struct Electorate {
+    opened_at: DateTime<Utc>,
+    keys: Vec<(String, SigningKey)>,
+    votes: Vec<SignedVote>,
+}
+
+/// Five voters cast signed votes 10 seconds apart after the proposal opens two minutes ago
+fn cast_votes() -> Electorate {
+    let opened_at = Utc::now() - Duration::seconds(120);
+    let decays = [DecayType::Linear, DecayType::Exponential, DecayType::Stepped];
+    let keys: Vec<(String, SigningKey)> = VOTERS
+        .iter()
+        .map(|v| (v.to_string(), SignedVote::generate_keypair()))
+        .collect();
+    let votes = keys
+        .iter()
+        .enumerate()
+        .map(|(i, (voter, key))| {
+            SignedVote::new(
+                voter.parse().unwrap(),
+                PROPOSAL.parse().unwrap(),
+                1.0,
+                opened_at + Duration::seconds(10 * (i as i64 + 1)),
+                decays[i % decays.len()],
+                key,
+            )
+        })
+        .collect();
+    Electorate { opened_at, keys, votes }
+}
+
+fn total_weight(votes: &[SignedVote], at: DateTime<Utc>) -> f64 {
+    let trust = TrustEngine::new();
+    let mut engine = WeightEngine::new();
+    votes.iter().map(|v| engine.calculate_weight(v, at, Some(&trust))).sum()
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("tdc-pipeline-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn full_pipeline_from_keygen_to_history_export() {
+    let Electorate { opened_at, votes, .. } = cast_votes();
+    let dir = temp_dir("full");
+
+    // Open the proposal in its namespace
+    let mut namespaces = NamespaceManager::new();
+    namespaces.add_namespace(NamespaceConfig::new("dao", 1, &dir)).unwrap();
+    namespaces.open_proposal("dao", PROPOSAL).unwrap();
+
+    // Every vote verifies
+    for vote in &votes {
+        vote.verify(300).unwrap();
+    }
+
+    // Decay over mocked time: the same votes weigh less the later they are counted
+    let early = total_weight(&votes, opened_at + Duration::seconds(100));
+    let late = total_weight(&votes, opened_at + Duration::seconds(400));
+    assert!(late < early, "weight should decay: {} -> {}", early, late);
+
+    // Escalation: the bar rises while the proposal stays open
+    let mut escalator = ThresholdEscalator::for_proposal_type(ProposalType::Normal);
+    escalator.total_votes = votes.len();
+    let opening_threshold = escalator.threshold_with_profile(opened_at, opened_at);
+    let closing_threshold = escalator.threshold_with_profile(opened_at + Duration::seconds(400), opened_at);
+    assert!(closing_threshold > opening_threshold);
+
+    // Extension: a close call 15 seconds before the deadline buys more time
+    let mut window = VotingWindow::new(opened_at, WindowType::Custom(130), 0);
+    let near_close = opened_at + Duration::seconds(115);
+    let extended = window
+        .apply_extension(near_close, 0.95, 1.0, &CloseCallExtension { extra_secs: 300 })
+        .unwrap();
+    assert_eq!(extended, 300);
+    let finalized_at = opened_at + Duration::seconds(400);
+    assert!(window.is_open(finalized_at));
+
+    // Finalization report with a certificate from the registry authority
+    let authority = SignedVote::generate_keypair();
+    let mut report = FinalizationReport::build(ReportInput {
+        proposal_id: PROPOSAL,
+        proposal_type: ProposalType::Normal,
+        opened_at,
+        finalized_at,
+        votes: &votes,
+        trust: &TrustEngine::new(),
+        max_age_secs: 300,
+        admissions: None,
+        eligible_weight: Some(6.0),
+        decision: DecisionRule::default(),
+        tie_break_seed: None,
+    });
+    assert_eq!(report.tally.counted_votes, 5);
+    assert!((report.tally.total_weight - late).abs() < 1e-9);
+    assert!(report.passed);
+    assert!(report.tally.normalized.turnout_fraction < 1.0);
+
+    report.certify(&authority);
+    let published: FinalizationReport = serde_json::from_str(&report.to_json()).unwrap();
+    assert!(published.verify_certificate());
+    let mut tampered = published.clone();
+    tampered.tally.counted_votes += 1;
+    assert!(!tampered.verify_certificate());
+
+    // Anchor votes and the outcome on the namespace chain
+    let mut transactions: Vec<Transaction> = votes.iter().map(|v| Transaction::Vote(VoteEntry::from(v))).collect();
+    transactions.push(Transaction::Finalization(FinalizationEntry {
+        proposal_id: PROPOSAL.to_string(),
+        passed: report.passed,
+        weight: report.tally.total_weight,
+        threshold: report.final_threshold,
+        finalized_at,
+    }));
+    namespaces.record("dao", &transactions).unwrap();
+    let chain = namespaces.chain("dao").unwrap();
+    assert!(chain.is_valid());
+    let activity = explorer::find_proposal(chain, PROPOSAL);
+    assert_eq!(activity.votes.len(), 5);
+    assert!(activity.votes.iter().all(|v| v.signature_valid));
+    assert_eq!(activity.finalizations.len(), 1);
+
+    // History export round trip
+    let mut history = HistoryAnalyzer::default();
+    for contribution in &report.tally.contributions {
+        history.record_vote(VoteRecord {
+            vote_id: contribution.voter_id.clone(),
+            weight: contribution.effective_weight,
+            threshold: report.final_threshold,
+            passed: report.passed,
+            timestamp: finalized_at,
+        });
+    }
+    let imported = HistoryAnalyzer::import_json(&history.export_json()).unwrap();
+    assert_eq!(imported.records, history.records);
+    assert_eq!(imported.records.len(), 5);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn revoked_key_is_excluded_before_tally() {
+    let Electorate { opened_at, keys, votes } = cast_votes();
+    let authority = SignedVote::generate_keypair();
+    let mut registry = ValidatorRegistry::new();
+    registry.add_authority(authority.verifying_key());
+    for (voter, key) in &keys {
+        registry.register(voter.clone(), key.verifying_key());
+    }
+
+    // carol's key is revoked before her vote at +30s
+    let revocation = Revocation::new(
+        keys[2].1.verifying_key(),
+        opened_at + Duration::seconds(25),
+        "key compromised".to_string(),
+        &authority,
+    );
+    registry.revoke(revocation).unwrap();
+
+    let accepted: Vec<SignedVote> = votes
+        .into_iter()
+        .filter(|v| v.verify_with_registry(opened_at + Duration::seconds(120), 300, &registry).is_ok())
+        .collect();
+    assert_eq!(accepted.len(), 4);
+    assert!(accepted.iter().all(|v| v.voter_id != "carol"));
+
+    let report = FinalizationReport::build(ReportInput {
+        proposal_id: PROPOSAL,
+        proposal_type: ProposalType::Critical,
+        opened_at,
+        finalized_at: opened_at + Duration::seconds(60),
+        votes: &accepted,
+        trust: &TrustEngine::new(),
+        max_age_secs: 300,
+        admissions: None,
+        eligible_weight: Some(5.0),
+        decision: DecisionRule::default(),
+        tie_break_seed: None,
+    });
+    // Critical proposals need five votes
+    assert_eq!(report.tally.counted_votes, 4);
+    assert!(!report.passed);
+}
+
+#[test]
+fn chain_survives_restart_and_rebuilds_the_same_tally() {
+    let Electorate { opened_at, votes, .. } = cast_votes();
+    let dir = temp_dir("restart");
+    let finalized_at = opened_at + Duration::seconds(90);
+
+    let mut namespaces = NamespaceManager::new();
+    namespaces.add_namespace(NamespaceConfig::new("dao", 1, &dir)).unwrap();
+    namespaces.open_proposal("dao", PROPOSAL).unwrap();
+    let transactions: Vec<Transaction> = votes.iter().map(|v| Transaction::Vote(VoteEntry::from(v))).collect();
+    namespaces.record("dao", &transactions).unwrap();
+    namespaces.save_all().unwrap();
+
+    let mut restarted = NamespaceManager::new();
+    restarted.add_namespace(NamespaceConfig::new("dao", 1, &dir)).unwrap();
+    assert_eq!(restarted.namespace_of(PROPOSAL), Some("dao"));
+
+    let replayed: Vec<SignedVote> = explorer::find_proposal(restarted.chain("dao").unwrap(), PROPOSAL)
+        .votes
+        .iter()
+        .filter_map(|v| v.vote.to_signed_vote())
+        .collect();
+    let build = |votes: &[SignedVote]| {
+        FinalizationReport::build(ReportInput {
+            proposal_id: PROPOSAL,
+            proposal_type: ProposalType::Normal,
+            opened_at,
+            finalized_at,
+            votes,
+            trust: &TrustEngine::new(),
+            max_age_secs: 300,
+            admissions: None,
+            eligible_weight: None,
+            decision: DecisionRule::default(),
+            tie_break_seed: None,
+        })
+    };
+    assert_eq!(build(&replayed).tally, build(&votes).tally);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}